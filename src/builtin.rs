@@ -1,16 +1,84 @@
+use crate::builtins::json::json_parse;
 use crate::vm::{
-    jsvalue::value::*,
+    error::RuntimeError,
+    jsvalue::{number, value::*},
     vm::{CallMode, VMValueResult, VM},
 };
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
 
 pub type BuiltinFuncTy = fn(&mut VM, &[Value], Value) -> VMValueResult;
 
+/// Builtin functions can not see new.target, so a constructor call is told from a normal call
+/// by the receiver: ``new`` passes a fresh ordinary object that inherits from ``prototype``.
+pub fn is_constructor_call(this: Value, prototype: Value) -> bool {
+    if !this.is_object() {
+        return false;
+    }
+    if let ObjectKind::Ordinary = this.get_object_info().kind {
+        let mut proto = this.get_object_info().prototype;
+        while proto.is_object() {
+            if proto == prototype {
+                return true;
+            }
+            proto = proto.get_object_info().prototype;
+        }
+    }
+    false
+}
+
+/// The argument at ``idx``, or undefined if it is not passed.
+pub fn arg(args: &[Value], idx: usize) -> Value {
+    args.get(idx).copied().unwrap_or_else(Value::undefined)
+}
+
+/// The kind of ``val`` if it is an object.
+pub fn object_kind<'a>(val: Value) -> Option<&'a mut ObjectKind> {
+    match val {
+        Value::Object(info) => {
+            let info: &'a mut ObjectInfo = unsafe { &mut *info };
+            Some(&mut info.kind)
+        }
+        _ => None,
+    }
+}
+
 pub fn parse_float(_vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
-    let string = args.get(0).unwrap_or(&Value::undefined()).to_string();
-    let val = Value::Number(string.parse::<f64>().unwrap_or(::std::f64::NAN));
+    let string = args.first().unwrap_or(&Value::undefined()).to_string();
+    let val = Value::Number(number::parse_float(string.as_str()));
     Ok(val)
 }
 
+/// https://tc39.github.io/ecma262/#sec-parseint-string-radix
+pub fn parse_int(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    let string = args.first().unwrap_or(&Value::undefined()).to_string();
+    let radix = args
+        .get(1)
+        .unwrap_or(&Value::undefined())
+        .to_int32(&mut vm.factory.memory_allocator);
+    let val = Value::Number(number::parse_int(string.as_str(), radix));
+    Ok(val)
+}
+
+/// https://tc39.github.io/ecma262/#sec-isnan-number
+pub fn is_nan(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    let number = args
+        .first()
+        .unwrap_or(&Value::undefined())
+        .to_number(&mut vm.factory.memory_allocator);
+    Ok(Value::bool(number.is_nan()))
+}
+
+/// https://tc39.github.io/ecma262/#sec-isfinite-number
+pub fn is_finite(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    let number = args
+        .first()
+        .unwrap_or(&Value::undefined())
+        .to_number(&mut vm.factory.memory_allocator);
+    Ok(Value::bool(number.is_finite()))
+}
+
 pub fn deep_seq(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
     if args.len() != 2 {
         return Err(vm
@@ -28,6 +96,8 @@ pub fn deep_seq(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
 /// Accesor property is not suppoeed. (alway return false)
 fn deep_seq_bool(lval: &Value, rval: &Value) -> bool {
     match (*lval, *rval) {
+        // Like Object.is(), NaN equals NaN.
+        (Value::Number(l), Value::Number(r)) if l.is_nan() && r.is_nan() => true,
         (Value::Object(l_info), Value::Object(r_info)) => {
             let lobj_info = ObjectRef(l_info);
             let robj_info = ObjectRef(r_info);
@@ -60,6 +130,9 @@ fn deep_seq_bool(lval: &Value, rval: &Value) -> bool {
             }
             match (&lobj_info.kind, &robj_info.kind) {
                 (ObjectKind::Ordinary, ObjectKind::Ordinary) => true,
+                (ObjectKind::Date(l_time), ObjectKind::Date(r_time)) => {
+                    l_time == r_time || l_time.is_nan() && r_time.is_nan()
+                }
                 (ObjectKind::Array(l_info), ObjectKind::Array(r_info)) => {
                     let l_elems = &*l_info.elems;
                     let r_elems = &*r_info.elems;
@@ -87,30 +160,45 @@ fn deep_seq_bool(lval: &Value, rval: &Value) -> bool {
 }
 
 pub fn require(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
-    let file_name = {
-        let val = args.get(0).ok_or(
-            vm.current_context
-                .error_general("require(): One argument is needed."),
-        )?;
-        match val {
-            Value::String(_) => val.to_string(),
-            _ => {
-                return Err(vm
-                    .current_context
-                    .error_type("require(): An argument should be string."));
-            }
-        }
-    };
+    let request = require_request(vm, args)?;
+    let file_name = resolve_filename(vm, &request)?;
 
-    use crate::parser::Parser;
-    let mut parser = Parser::load_module(file_name.clone())
-        .map_err(|e| return vm.current_context.error_general(format!("{:?}", e)))?;
-    let absolute_path = parser.file_name.clone();
+    let cache = vm.module_map.require_cache;
+    let cached_module = cache.get_property(file_name.as_str());
+    if cached_module.is_object() {
+        return Ok(cached_module.get_property("exports"));
+    }
+
+    let code = fs::read_to_string(&file_name).map_err(|ioerr| {
+        vm.current_context
+            .error_general(format!("Cannot load module '{}': {}", file_name, ioerr))
+    })?;
+
+    let empty_object = make_normal_object!(vm.factory);
+    let id_object = vm.factory.string(file_name.clone());
+    let module = make_normal_object!(
+        vm.factory,
+        id       => false, false, false: id_object,
+        filename => false, false, false: id_object,
+        loaded   => true,  false, false: Value::bool(false),
+        exports  => true,  false, false: empty_object
+    );
+
+    if Path::new(&file_name).extension() == Some(OsStr::new("json")) {
+        let text = vm.factory.string(code);
+        let exports = json_parse(vm, &[text], Value::undefined())?;
+        module.set_property("exports", exports);
+        module.set_property("loaded", Value::bool(true));
+        cache.set_property(file_name, module);
+        return Ok(exports);
+    }
 
+    use crate::parser::Parser;
+    let mut parser = Parser::new(file_name.clone(), code);
     let node = parser.parse_all().map_err(|parse_err| {
         parser.handle_error(&parse_err);
         vm.current_context
-            .error_general(format!("Error in parsing module \"{}\"", file_name))
+            .error_syntax(format!("Error in parsing module \"{}\"", file_name))
     })?;
 
     use crate::vm::codegen::Error;
@@ -118,31 +206,34 @@ pub fn require(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
         let Error { msg, token_pos, .. } = codegen_err;
         parser.show_error_at(token_pos, msg);
         vm.current_context
-            .error_general(format!("Error in parsing module \"{}\"", file_name))
+            .error_syntax(format!("Error in parsing module \"{}\"", file_name))
     })?;
     let id = module_info.module_func_id;
     let script_info = parser.into_script_info();
     vm.script_info.push((id, script_info));
 
+    // Cached before running, so a cyclic require gets the unfinished exports.
+    cache.set_property(file_name.clone(), module);
+
     vm.prepare_context_for_function_invokation(
         module_info,
         Some(vm.global_environment),
-        args: &[Value],
-        Value::undefined(),
+        args,
+        empty_object,
         CallMode::ModuleCall,
         false,
     )?;
 
-    let empty_object = make_normal_object!(vm.factory);
-    let id_object = vm.factory.string(absolute_path);
-    let module = make_normal_object!(
-        vm.factory,
-        id       => false, false, false: id_object,
-        exports  => true,  false, false: empty_object
-    );
-    vm.current_context
-        .lex_env_mut()
-        .set_own_value("module", module)?;
+    let dir_name = Path::new(&file_name)
+        .parent()
+        .map(|dir| dir.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let dir_name = vm.factory.string(dir_name);
+    let lex_env = vm.current_context.lex_env_mut();
+    lex_env.set_own_value("module", module)?;
+    lex_env.set_own_value("exports", empty_object)?;
+    lex_env.set_own_value("__filename", id_object)?;
+    lex_env.set_own_value("__dirname", dir_name)?;
 
     if vm.is_trace {
         println!("--> call module");
@@ -154,3 +245,122 @@ pub fn require(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
 
     Ok(Value::empty())
 }
+
+/// require.resolve(request)
+pub fn require_resolve(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    let request = require_request(vm, args)?;
+    let file_name = resolve_filename(vm, &request)?;
+    Ok(vm.factory.string(file_name))
+}
+
+fn require_request(vm: &mut VM, args: &[Value]) -> Result<String, RuntimeError> {
+    let val = args.first().ok_or_else(|| {
+        vm.current_context
+            .error_general("require(): One argument is needed.")
+    })?;
+    match val {
+        Value::String(_) => Ok(val.to_string()),
+        _ => Err(vm
+            .current_context
+            .error_type("require(): An argument should be string.")),
+    }
+}
+
+/// Resolves ``request`` from the directory of the running script, and returns the absolute
+/// path of the module file.
+/// https://nodejs.org/api/modules.html#modules_all_together
+fn resolve_filename(vm: &mut VM, request: &str) -> Result<String, RuntimeError> {
+    let referrer = vm.current_file_name();
+    let dir = match Path::new(&referrer).parent() {
+        Some(dir) if dir != Path::new("") => dir.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+
+    let is_path = request.starts_with("./")
+        || request.starts_with("../")
+        || request.starts_with('/')
+        || request == "."
+        || request == "..";
+    let found = if is_path {
+        let path = dir.join(request);
+        match load_as_file(&path) {
+            Some(file) => Some(file),
+            None => load_as_directory(vm, &path)?,
+        }
+    } else {
+        match load_node_modules(vm, request, &dir)? {
+            Some(file) => Some(file),
+            // The modules bundled with rapidus (e.g. assert) are in the working directory.
+            None => load_as_file(Path::new(request)),
+        }
+    };
+
+    match found.and_then(|file| file.canonicalize().ok()) {
+        Some(file) => Ok(file.to_string_lossy().to_string()),
+        None => Err(vm
+            .current_context
+            .error_general(format!("Cannot find module '{}'", request))),
+    }
+}
+
+fn load_as_file(path: &Path) -> Option<PathBuf> {
+    if path.is_file() {
+        return Some(path.to_path_buf());
+    }
+    for extension in &[".js", ".json"] {
+        let mut file = path.as_os_str().to_os_string();
+        file.push(extension);
+        let file = PathBuf::from(file);
+        if file.is_file() {
+            return Some(file);
+        }
+    }
+    None
+}
+
+fn load_index(path: &Path) -> Option<PathBuf> {
+    ["index.js", "index.json"]
+        .iter()
+        .map(|index| path.join(index))
+        .find(|file| file.is_file())
+}
+
+/// Loads the file named by "main" in package.json, or index.js.
+fn load_as_directory(vm: &mut VM, path: &Path) -> Result<Option<PathBuf>, RuntimeError> {
+    if let Ok(json) = fs::read_to_string(path.join("package.json")) {
+        let text = vm.factory.string(json);
+        let package = json_parse(vm, &[text], Value::undefined())?;
+        if let main @ Value::String(_) = package.get_property("main") {
+            let main = path.join(main.to_string());
+            if let Some(file) = load_as_file(&main).or_else(|| load_index(&main)) {
+                return Ok(Some(file));
+            }
+        }
+    }
+    Ok(load_index(path))
+}
+
+/// Looks for the module in node_modules of the directory and all its ancestors.
+fn load_node_modules(
+    vm: &mut VM,
+    request: &str,
+    dir: &Path,
+) -> Result<Option<PathBuf>, RuntimeError> {
+    let dir = match dir.canonicalize() {
+        Ok(dir) => dir,
+        Err(_) => return Ok(None),
+    };
+    for dir in dir.ancestors() {
+        if dir.file_name() == Some(OsStr::new("node_modules")) {
+            continue;
+        }
+        let path = dir.join("node_modules").join(request);
+        if let Some(file) = load_as_file(&path) {
+            return Ok(Some(file));
+        }
+        if let Some(file) = load_as_directory(vm, &path)? {
+            return Ok(Some(file));
+        }
+    }
+    Ok(None)
+}
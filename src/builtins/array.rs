@@ -1,14 +1,35 @@
+use crate::builtin::arg;
+use crate::builtins::iterator::create_iter_result_object;
+use crate::builtins::number::to_integer_or_infinity;
+use crate::builtins::object;
 use crate::vm::{
-    jsvalue::{object::Property, value::Value},
+    error::RuntimeError,
+    jsvalue::{
+        iterator::IterationKind,
+        object::{DataProperty, ObjectKind, Property},
+        value::Value,
+    },
     vm::{Factory, VMValueResult, VM},
 };
+use std::cmp::Ordering;
 
 pub fn array(factory: &mut Factory) -> Value {
-    factory.generate_builtin_constructor(
+    let obj = factory.generate_builtin_constructor(
         "Array",
         array_constructor,
+        1,
         factory.object_prototypes.array,
-    )
+    );
+    // Array.from
+    obj.set_property("from", factory.builtin_function("from", array_from, 1));
+    // Array.isArray
+    obj.set_property(
+        "isArray",
+        factory.builtin_function("isArray", array_is_array, 1),
+    );
+    // Array.of
+    obj.set_property("of", factory.builtin_function("of", array_of, 0));
+    obj
 }
 
 pub fn array_constructor(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
@@ -17,10 +38,12 @@ pub fn array_constructor(vm: &mut VM, args: &[Value], _this: Value) -> VMValueRe
         match arg_length {
             0 => vec![],
             1 => {
-                let len = args[0];
-                if len.is_number() {
-                    let len = len.to_uint32(&mut vm.factory.memory_allocator) as usize;
-                    vec![Property::new_data_simple(Value::empty()); len]
+                if let Value::Number(number) = args[0] {
+                    let len = args[0].to_uint32(&mut vm.factory.memory_allocator);
+                    if len as f64 != number {
+                        return Err(vm.current_context.error_range("Invalid array length"));
+                    }
+                    vec![Property::new_data_simple(Value::empty()); len as usize]
                 } else {
                     vec![Property::new_data_simple(args[0])]
                 }
@@ -38,65 +61,942 @@ pub fn array_constructor(vm: &mut VM, args: &[Value], _this: Value) -> VMValueRe
     Ok(val)
 }
 
-pub fn array_prototype_join(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
-    if !this.is_array_object() {
-        return Err(vm.current_context.error_unknown());
+/// Array.from
+/// https://tc39.github.io/ecma262/#sec-array.from
+pub fn array_from(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    let items = arg(args, 0);
+    let map_fn = arg(args, 1);
+    let this_arg = arg(args, 2);
+    if !map_fn.is_undefined() && !map_fn.is_function_object() {
+        return Err(vm
+            .current_context
+            .error_type(format!("{} is not a function", map_fn.debug_string(true))));
+    }
+    if items.is_undefined() || items.is_null() {
+        return Err(vm
+            .current_context
+            .error_type(format!("{} is not iterable", items.debug_string(true))));
+    }
+
+    let using_iterator = vm.get_property_by_value(items, vm.factory.symbols.iterator)?;
+    let mut values = vec![];
+    if !using_iterator.is_undefined() && !using_iterator.is_null() {
+        let iter = vm.get_iterator(items)?;
+        while let Some(val) = vm.iterator_step(iter)? {
+            values.push(val);
+        }
+    } else {
+        // An array-like object.
+        let length = vm.factory.string("length");
+        let length = vm.get_property_by_value(items, length)?;
+        let len = to_integer_or_infinity(vm, length).max(0.0) as usize;
+        for k in 0..len {
+            values.push(vm.get_property_by_value(items, Value::Number(k as f64))?);
+        }
+    }
+
+    let mut elems = vec![];
+    for (k, val) in values.into_iter().enumerate() {
+        let val = if map_fn.is_undefined() {
+            val
+        } else {
+            vm.call_function(map_fn, &[val, Value::Number(k as f64)], this_arg)?
+        };
+        elems.push(Property::new_data_simple(val));
+    }
+    Ok(vm.factory.array(elems))
+}
+
+/// Array.isArray
+pub fn array_is_array(_vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    Ok(Value::bool(arg(args, 0).is_array_object()))
+}
+
+/// Array.of
+pub fn array_of(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    let elems = args
+        .iter()
+        .map(|arg| Property::new_data_simple(*arg))
+        .collect();
+    Ok(vm.factory.array(elems))
+}
+
+/// Array.prototype.concat
+/// https://tc39.github.io/ecma262/#sec-array.prototype.concat
+pub fn array_prototype_concat(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let this = this_object(vm, this, "Array.prototype.concat")?;
+    let mut elems = vec![];
+    for item in std::iter::once(&this).chain(args.iter()) {
+        if item.is_array_object() {
+            let len = length_of(vm, *item)?;
+            elems.extend(copy_elements(vm, *item, 0, len)?);
+        } else {
+            elems.push(Property::new_data_simple(*item));
+        }
     }
+    Ok(vm.factory.array(elems))
+}
 
-    let ary_info = this.as_array_mut();
-    let seperator = match args.len() {
-        0 => None,
-        1 if args[0].is_undefined() => None,
-        _ => Some(args[0].to_string()),
+/// Array.prototype.copyWithin
+/// https://tc39.github.io/ecma262/#sec-array.prototype.copywithin
+pub fn array_prototype_copy_within(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let this = this_object(vm, this, "Array.prototype.copyWithin")?;
+    let len = length_of(vm, this)?;
+    let to = relative_index(vm, arg(args, 0), len, 0);
+    let from = relative_index(vm, arg(args, 1), len, 0);
+    let end = relative_index(vm, arg(args, 2), len, len);
+    let count = end.saturating_sub(from).min(len - to);
+    if this.is_array_object() {
+        if count > 0 {
+            let elems = &mut this.as_array_mut().elems;
+            let copied = elems[from..from + count].to_vec();
+            elems.splice(to..to + count, copied);
+        }
+        return Ok(this);
+    }
+    // Copies from the end if the ranges overlap and ``to`` comes after ``from``.
+    let offsets: Vec<usize> = if from < to && to < from + count {
+        (0..count).rev().collect()
+    } else {
+        (0..count).collect()
     };
-    let result = ary_info.join(seperator);
-    let val = vm.factory.string(result);
-    Ok(val)
+    for i in offsets {
+        if has_element(vm, this, from + i) {
+            let val = get_element(vm, this, from + i)?;
+            set_element(vm, this, to + i, val)?;
+        } else {
+            delete_element(vm, this, to + i)?;
+        }
+    }
+    Ok(this)
+}
+
+/// Array.prototype.entries
+pub fn array_prototype_entries(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    if !this.is_object() {
+        return Err(vm.current_context.error_type("this is not an object"));
+    }
+
+    let iter = vm.factory.array_iterator(this, IterationKind::KeyAndValue);
+    Ok(iter)
+}
+
+/// Array.prototype.every
+pub fn array_prototype_every(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let this = this_object(vm, this, "Array.prototype.every")?;
+    let (callback, this_arg) = callback_arg(vm, args)?;
+    let len = length_of(vm, this)?;
+    for k in 0..len {
+        if !has_element(vm, this, k) {
+            continue;
+        }
+        let val = get_element(vm, this, k)?;
+        if !call_callback(vm, callback, this_arg, val, k, this)?.to_boolean() {
+            return Ok(Value::bool(false));
+        }
+    }
+    Ok(Value::bool(true))
+}
+
+/// Array.prototype.fill
+pub fn array_prototype_fill(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let this = this_object(vm, this, "Array.prototype.fill")?;
+    let val = arg(args, 0);
+    let len = length_of(vm, this)?;
+    let start = relative_index(vm, arg(args, 1), len, 0);
+    let end = relative_index(vm, arg(args, 2), len, len);
+    for k in start..end {
+        set_element(vm, this, k, val)?;
+    }
+    Ok(this)
+}
+
+/// Array.prototype.filter
+pub fn array_prototype_filter(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let this = this_object(vm, this, "Array.prototype.filter")?;
+    let (callback, this_arg) = callback_arg(vm, args)?;
+    let len = length_of(vm, this)?;
+    let mut elems = vec![];
+    for k in 0..len {
+        if !has_element(vm, this, k) {
+            continue;
+        }
+        let val = get_element(vm, this, k)?;
+        if call_callback(vm, callback, this_arg, val, k, this)?.to_boolean() {
+            elems.push(Property::new_data_simple(val));
+        }
+    }
+    Ok(vm.factory.array(elems))
+}
+
+/// Array.prototype.find
+pub fn array_prototype_find(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let this = this_object(vm, this, "Array.prototype.find")?;
+    match find_element(vm, args, this)? {
+        Some((_, val)) => Ok(val),
+        None => Ok(Value::undefined()),
+    }
+}
+
+/// Array.prototype.findIndex
+pub fn array_prototype_find_index(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let this = this_object(vm, this, "Array.prototype.findIndex")?;
+    match find_element(vm, args, this)? {
+        Some((k, _)) => Ok(Value::Number(k as f64)),
+        None => Ok(Value::Number(-1.0)),
+    }
+}
+
+/// Array.prototype.flat
+/// https://tc39.github.io/ecma262/#sec-array.prototype.flat
+pub fn array_prototype_flat(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let this = this_object(vm, this, "Array.prototype.flat")?;
+    let depth = match arg(args, 0) {
+        depth if depth.is_undefined() => 1.0,
+        depth => to_integer_or_infinity(vm, depth),
+    };
+    let mut elems = vec![];
+    flatten_into_array(vm, &mut elems, this, depth)?;
+    Ok(vm.factory.array(elems))
+}
+
+/// Array.prototype.flatMap
+pub fn array_prototype_flat_map(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let this = this_object(vm, this, "Array.prototype.flatMap")?;
+    let (callback, this_arg) = callback_arg(vm, args)?;
+    let len = length_of(vm, this)?;
+    let mut elems = vec![];
+    for k in 0..len {
+        if !has_element(vm, this, k) {
+            continue;
+        }
+        let val = get_element(vm, this, k)?;
+        let mapped = call_callback(vm, callback, this_arg, val, k, this)?;
+        if mapped.is_array_object() {
+            flatten_into_array(vm, &mut elems, mapped, 0.0)?;
+        } else {
+            elems.push(Property::new_data_simple(mapped));
+        }
+    }
+    Ok(vm.factory.array(elems))
+}
+
+/// Array.prototype.forEach
+pub fn array_prototype_for_each(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let this = this_object(vm, this, "Array.prototype.forEach")?;
+    let (callback, this_arg) = callback_arg(vm, args)?;
+    let len = length_of(vm, this)?;
+    for k in 0..len {
+        if !has_element(vm, this, k) {
+            continue;
+        }
+        let val = get_element(vm, this, k)?;
+        call_callback(vm, callback, this_arg, val, k, this)?;
+    }
+    Ok(Value::undefined())
+}
+
+/// Array.prototype.includes
+pub fn array_prototype_includes(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let this = this_object(vm, this, "Array.prototype.includes")?;
+    let search_element = arg(args, 0);
+    let len = length_of(vm, this)?;
+    let start = relative_index(vm, arg(args, 1), len, 0);
+    for k in start..len {
+        // A hole is read as undefined.
+        if get_element(vm, this, k)?.same_value_zero(search_element) {
+            return Ok(Value::bool(true));
+        }
+    }
+    Ok(Value::bool(false))
+}
+
+/// Array.prototype.indexOf
+pub fn array_prototype_index_of(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let this = this_object(vm, this, "Array.prototype.indexOf")?;
+    let search_element = arg(args, 0);
+    let len = length_of(vm, this)?;
+    let start = relative_index(vm, arg(args, 1), len, 0);
+    for k in start..len {
+        if has_element(vm, this, k) && get_element(vm, this, k)?.strict_eq_bool(search_element) {
+            return Ok(Value::Number(k as f64));
+        }
+    }
+    Ok(Value::Number(-1.0))
+}
+
+/// Array.prototype.join
+/// https://tc39.github.io/ecma262/#sec-array.prototype.join
+pub fn array_prototype_join(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let this = this_object(vm, this, "Array.prototype.join")?;
+    let separator = match arg(args, 0) {
+        separator if separator.is_undefined() => vec![',' as u16],
+        separator => separator.to_utf16(),
+    };
+    if this.is_array_object() {
+        let result = this.as_array_mut().join(Some(&separator));
+        return Ok(vm.factory.utf16_string(result));
+    }
+    let len = length_of(vm, this)?;
+    let mut result = vec![];
+    for k in 0..len {
+        if k > 0 {
+            result.extend_from_slice(&separator);
+        }
+        let elem = get_element(vm, this, k)?;
+        if !elem.is_undefined() && !elem.is_null() {
+            result.extend(elem.to_utf16());
+        }
+    }
+    Ok(vm.factory.utf16_string(result))
+}
+
+/// https://tc39.github.io/ecma262/#sec-array.prototype.tostring
+pub fn array_prototype_to_string(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    if this.is_object() {
+        let join = vm.factory.string("join");
+        let func = vm.get_property_by_value(this, join)?;
+        if func.is_function_object() {
+            return vm.call_function(func, &[], this);
+        }
+    }
+    object::object_prototype_to_string(vm, &[], this)
 }
 
+/// Array.prototype.push
 pub fn array_prototype_push(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
-    if !this.is_array_object() {
-        return Err(vm.current_context.error_unknown());
+    let this = this_object(vm, this, "Array.prototype.push")?;
+    if this.is_array_object() {
+        check_extensible(vm, this, args.len())?;
+        let ary_info = this.as_array_mut();
+        for arg in args {
+            ary_info.elems.push(Property::new_data_simple(*arg));
+        }
+        return Ok(Value::Number(ary_info.get_length() as f64));
+    }
+
+    let len = length_of(vm, this)?;
+    for (i, arg) in args.iter().enumerate() {
+        set_element(vm, this, len + i, *arg)?;
+    }
+    set_length(vm, this, len + args.len())?;
+    Ok(Value::Number((len + args.len()) as f64))
+}
+
+pub fn array_prototype_values(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    if !this.is_object() {
+        return Err(vm.current_context.error_type("this is not an object"));
     }
 
-    let ary_info = this.as_array_mut();
+    let iter = vm.factory.array_iterator(this, IterationKind::Value);
+    Ok(iter)
+}
 
-    for arg in args {
-        ary_info.elems.push(Property::new_data_simple(*arg));
+/// Array.prototype.keys
+pub fn array_prototype_keys(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    if !this.is_object() {
+        return Err(vm.current_context.error_type("this is not an object"));
     }
 
-    let val = Value::Number(ary_info.get_length() as f64);
+    let iter = vm.factory.array_iterator(this, IterationKind::Key);
+    Ok(iter)
+}
 
-    Ok(val)
+/// Array.prototype.lastIndexOf
+pub fn array_prototype_last_index_of(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let this = this_object(vm, this, "Array.prototype.lastIndexOf")?;
+    let search_element = arg(args, 0);
+    let len = length_of(vm, this)?;
+    let from_index = if args.len() > 1 {
+        to_integer_or_infinity(vm, args[1])
+    } else {
+        len as f64 - 1.0
+    };
+    let start = if from_index < 0.0 {
+        len as f64 + from_index
+    } else {
+        from_index.min(len as f64 - 1.0)
+    };
+    if start < 0.0 {
+        return Ok(Value::Number(-1.0));
+    }
+    for k in (0..=start as usize).rev() {
+        if has_element(vm, this, k) && get_element(vm, this, k)?.strict_eq_bool(search_element) {
+            return Ok(Value::Number(k as f64));
+        }
+    }
+    Ok(Value::Number(-1.0))
 }
 
+/// Array.prototype.map
 pub fn array_prototype_map(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let this = this_object(vm, this, "Array.prototype.map")?;
+    let (callback, this_arg) = callback_arg(vm, args)?;
+    let len = length_of(vm, this)?;
+    // Holes are kept.
+    let mut new_ary = vec![Property::new_data_simple(Value::empty()); len];
+    for (k, elem) in new_ary.iter_mut().enumerate() {
+        if !has_element(vm, this, k) {
+            continue;
+        }
+        let val = get_element(vm, this, k)?; // 'k'th element may be getter
+        *elem = Property::new_data_simple(call_callback(vm, callback, this_arg, val, k, this)?);
+    }
+    Ok(vm.factory.array(new_ary))
+}
+
+/// Array.prototype.pop
+pub fn array_prototype_pop(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    let this = this_object(vm, this, "Array.prototype.pop")?;
+    let len = length_of(vm, this)?;
+    if len == 0 {
+        set_length(vm, this, 0)?;
+        return Ok(Value::undefined());
+    }
+    let val = get_element(vm, this, len - 1)?;
     if !this.is_array_object() {
-        return Err(vm.current_context.error_unknown());
+        delete_element(vm, this, len - 1)?;
+    }
+    set_length(vm, this, len - 1)?;
+    Ok(val)
+}
+
+/// Array.prototype.reduce
+pub fn array_prototype_reduce(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let this = this_object(vm, this, "Array.prototype.reduce")?;
+    let len = length_of(vm, this)?;
+    reduce(vm, args, this, (0..len).collect())
+}
+
+/// Array.prototype.reduceRight
+pub fn array_prototype_reduce_right(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let this = this_object(vm, this, "Array.prototype.reduceRight")?;
+    let len = length_of(vm, this)?;
+    reduce(vm, args, this, (0..len).rev().collect())
+}
+
+/// Array.prototype.reverse
+pub fn array_prototype_reverse(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    let this = this_object(vm, this, "Array.prototype.reverse")?;
+    if this.is_array_object() {
+        this.as_array_mut().elems.reverse();
+        return Ok(this);
+    }
+    let len = length_of(vm, this)?;
+    for lower in 0..len / 2 {
+        let upper = len - 1 - lower;
+        let lower_value = if has_element(vm, this, lower) {
+            Some(get_element(vm, this, lower)?)
+        } else {
+            None
+        };
+        let upper_value = if has_element(vm, this, upper) {
+            Some(get_element(vm, this, upper)?)
+        } else {
+            None
+        };
+        for (k, val) in [(lower, upper_value), (upper, lower_value)] {
+            match val {
+                Some(val) => set_element(vm, this, k, val)?,
+                None => delete_element(vm, this, k)?,
+            }
+        }
     }
+    Ok(this)
+}
+
+/// Array.prototype.shift
+pub fn array_prototype_shift(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    let this = this_object(vm, this, "Array.prototype.shift")?;
+    let len = length_of(vm, this)?;
+    if len == 0 {
+        set_length(vm, this, 0)?;
+        return Ok(Value::undefined());
+    }
+    let first = get_element(vm, this, 0)?;
+    if this.is_array_object() {
+        this.as_array_mut().elems.remove(0);
+        return Ok(first);
+    }
+    move_elements(vm, this, 1, 0, len - 1)?;
+    delete_element(vm, this, len - 1)?;
+    set_length(vm, this, len - 1)?;
+    Ok(first)
+}
+
+/// Array.prototype.slice
+pub fn array_prototype_slice(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let this = this_object(vm, this, "Array.prototype.slice")?;
+    let len = length_of(vm, this)?;
+    let start = relative_index(vm, arg(args, 0), len, 0);
+    let end = relative_index(vm, arg(args, 1), len, len);
+    let elems = copy_elements(vm, this, start, end.max(start))?;
+    Ok(vm.factory.array(elems))
+}
+
+/// Array.prototype.some
+pub fn array_prototype_some(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let this = this_object(vm, this, "Array.prototype.some")?;
+    let (callback, this_arg) = callback_arg(vm, args)?;
+    let len = length_of(vm, this)?;
+    for k in 0..len {
+        if !has_element(vm, this, k) {
+            continue;
+        }
+        let val = get_element(vm, this, k)?;
+        if call_callback(vm, callback, this_arg, val, k, this)?.to_boolean() {
+            return Ok(Value::bool(true));
+        }
+    }
+    Ok(Value::bool(false))
+}
+
+/// Array.prototype.sort
+/// https://tc39.github.io/ecma262/#sec-array.prototype.sort
+pub fn array_prototype_sort(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let comparefn = arg(args, 0);
+    if !comparefn.is_undefined() && !comparefn.is_function_object() {
+        return Err(vm
+            .current_context
+            .error_type("The comparison function must be either a function or undefined"));
+    }
+    let this = this_object(vm, this, "Array.prototype.sort")?;
+
+    // Holes are moved to the end, and undefined values right before them.
+    let len = length_of(vm, this)?;
+    let mut items = vec![];
+    let mut undefined_count = 0;
+    for k in 0..len {
+        if !has_element(vm, this, k) {
+            continue;
+        }
+        let val = get_element(vm, this, k)?;
+        if val.is_undefined() {
+            undefined_count += 1;
+        } else {
+            items.push(val);
+        }
+    }
+
+    let sorted = merge_sort(vm, items, comparefn)?;
+    let sorted_len = sorted.len();
+    for (k, val) in sorted.into_iter().enumerate() {
+        set_element(vm, this, k, val)?;
+    }
+    for k in sorted_len..sorted_len + undefined_count {
+        set_element(vm, this, k, Value::undefined())?;
+    }
+    for k in sorted_len + undefined_count..len {
+        delete_element(vm, this, k)?;
+    }
+    Ok(this)
+}
+
+/// Array.prototype.splice
+/// https://tc39.github.io/ecma262/#sec-array.prototype.splice
+pub fn array_prototype_splice(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let this = this_object(vm, this, "Array.prototype.splice")?;
+    let len = length_of(vm, this)?;
+    let start = relative_index(vm, arg(args, 0), len, 0);
+    let delete_count = match args.len() {
+        0 => 0,
+        1 => len - start,
+        _ => (to_integer_or_infinity(vm, args[1]).max(0.0) as usize).min(len - start),
+    };
+    let items = args.get(2..).unwrap_or(&[]);
+    let removed = copy_elements(vm, this, start, start + delete_count)?;
+    if this.is_array_object() {
+        check_extensible(vm, this, items.len().saturating_sub(delete_count))?;
+        this.as_array_mut().elems.splice(
+            start..start + delete_count,
+            items.iter().map(|item| Property::new_data_simple(*item)),
+        );
+        return Ok(vm.factory.array(removed));
+    }
+
+    let new_len = len - delete_count + items.len();
+    move_elements(
+        vm,
+        this,
+        start + delete_count,
+        start + items.len(),
+        len - start - delete_count,
+    )?;
+    for k in new_len..len {
+        delete_element(vm, this, k)?;
+    }
+    for (i, item) in items.iter().enumerate() {
+        set_element(vm, this, start + i, *item)?;
+    }
+    set_length(vm, this, new_len)?;
+    Ok(vm.factory.array(removed))
+}
+
+/// Array.prototype.unshift
+pub fn array_prototype_unshift(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let this = this_object(vm, this, "Array.prototype.unshift")?;
+    if this.is_array_object() {
+        check_extensible(vm, this, args.len())?;
+        let ary = this.as_array_mut();
+        ary.elems
+            .splice(0..0, args.iter().map(|arg| Property::new_data_simple(*arg)));
+        return Ok(Value::Number(ary.get_length() as f64));
+    }
+
+    let len = length_of(vm, this)?;
+    move_elements(vm, this, 0, args.len(), len)?;
+    for (i, arg) in args.iter().enumerate() {
+        set_element(vm, this, i, *arg)?;
+    }
+    set_length(vm, this, len + args.len())?;
+    Ok(Value::Number((len + args.len()) as f64))
+}
+
+/// %ArrayIteratorPrototype%.next
+pub fn array_iterator_prototype_next(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    let mut obj_info = match this {
+        Value::Object(_) => this.get_object_info(),
+        _ => {
+            return Err(vm
+                .current_context
+                .error_type("this is not an Array Iterator"))
+        }
+    };
+    let (iterated, index, kind) = match obj_info.kind {
+        ObjectKind::ArrayIterator(ref info) => (info.iterated, info.next_index, info.kind),
+        _ => {
+            return Err(vm
+                .current_context
+                .error_type("this is not an Array Iterator"))
+        }
+    };
+
+    if iterated.is_undefined() {
+        return Ok(create_iter_result_object(
+            &mut vm.factory,
+            Value::undefined(),
+            true,
+        ));
+    }
+
+    let len = if iterated.is_array_object() {
+        iterated.as_array_mut().get_length()
+    } else {
+        let length = vm.factory.string("length");
+        let length = vm.get_property_by_value(iterated, length)?;
+        length.to_uint32(&mut vm.factory.memory_allocator) as usize
+    };
+
+    if let ObjectKind::ArrayIterator(ref mut info) = obj_info.kind {
+        if index >= len {
+            info.iterated = Value::undefined();
+            return Ok(create_iter_result_object(
+                &mut vm.factory,
+                Value::undefined(),
+                true,
+            ));
+        }
+        info.next_index = index + 1;
+    }
+
+    let result = match kind {
+        IterationKind::Key => Value::Number(index as f64),
+        IterationKind::Value => vm.get_property_by_value(iterated, Value::Number(index as f64))?,
+        IterationKind::KeyAndValue => {
+            let val = vm.get_property_by_value(iterated, Value::Number(index as f64))?;
+            vm.factory.array(vec![
+                Property::new_data_simple(Value::Number(index as f64)),
+                Property::new_data_simple(val),
+            ])
+        }
+    };
+
+    Ok(create_iter_result_object(&mut vm.factory, result, false))
+}
+
+/// ToObject(this). The methods work on any array-like object, and take a faster path for an
+/// array.
+fn this_object(vm: &mut VM, this: Value, method: &str) -> VMValueResult {
+    if this.is_undefined() || this.is_null() {
+        return Err(vm
+            .current_context
+            .error_type(format!("{} called on null or undefined", method)));
+    }
+    object::to_object(vm, this)
+}
+
+/// LengthOfArrayLike(obj)
+/// https://tc39.github.io/ecma262/#sec-lengthofarraylike
+fn length_of(vm: &mut VM, obj: Value) -> Result<usize, RuntimeError> {
+    if obj.is_array_object() {
+        return Ok(obj.as_array_mut().get_length());
+    }
+    let length = vm.factory.string("length");
+    let length = vm.get_property_by_value(obj, length)?;
+    Ok(to_integer_or_infinity(vm, length).clamp(0.0, 9_007_199_254_740_991.0) as usize)
+}
+
+/// Set(obj, "length", len, true)
+fn set_length(vm: &mut VM, obj: Value, len: usize) -> Result<(), RuntimeError> {
+    if obj.is_array_object() {
+        obj.as_array_mut().set_length(len);
+        return Ok(());
+    }
+    let length = vm.factory.string("length");
+    vm.set_property_by_value(obj, length, Value::Number(len as f64))
+}
+
+/// Adding ``count`` elements to a non-extensible array is a TypeError.
+fn check_extensible(vm: &VM, this: Value, count: usize) -> Result<(), RuntimeError> {
+    if count > 0 && !this.get_object_info().extensible {
+        return Err(vm.current_context.error_type(format!(
+            "Cannot add property {}, object is not extensible",
+            this.as_array_mut().get_length()
+        )));
+    }
+    Ok(())
+}
+
+/// The callback function and thisArg of the iteration methods.
+fn callback_arg(vm: &VM, args: &[Value]) -> Result<(Value, Value), RuntimeError> {
+    let callback = arg(args, 0);
+    if !callback.is_function_object() {
+        return Err(vm
+            .current_context
+            .error_type(format!("{} is not a function", callback.debug_string(true))));
+    }
+    Ok((callback, arg(args, 1)))
+}
+
+/// Calls ``callback(val, k, ary)``.
+fn call_callback(
+    vm: &mut VM,
+    callback: Value,
+    this_arg: Value,
+    val: Value,
+    k: usize,
+    ary: Value,
+) -> VMValueResult {
+    vm.call_function(callback, &[val, Value::Number(k as f64), ary], this_arg)
+}
+
+/// HasProperty(ary, k), which is false for a hole.
+fn has_element(vm: &mut VM, ary: Value, k: usize) -> bool {
+    if !ary.is_array_object() {
+        return ary
+            .get_object_info()
+            .has_property_by_value(&mut vm.factory.memory_allocator, Value::Number(k as f64));
+    }
+    match ary.as_array_mut().elems.get(k) {
+        Some(Property::Data(DataProperty { val, .. })) => !val.is_empty(),
+        Some(Property::Accessor(_)) => true,
+        None => false,
+    }
+}
 
-    let ary_info = this.as_array_mut();
+/// Get(ary, k), which calls the getter of an accessor element.
+fn get_element(vm: &mut VM, ary: Value, k: usize) -> VMValueResult {
+    vm.get_property_by_value(ary, Value::Number(k as f64))
+}
 
-    let callback = args[0];
-    let mut args_for_callback = [
-        /* value = */ Value::undefined(),
-        /* nth element = */ Value::Number(0.0),
-        /* array itself = */ this,
-    ];
+/// Set(ary, k, val, true)
+fn set_element(vm: &mut VM, ary: Value, k: usize, val: Value) -> Result<(), RuntimeError> {
+    if ary.is_array_object() {
+        ary.as_array_mut().set_element(k, val);
+        return Ok(());
+    }
+    vm.set_property_by_value(ary, Value::Number(k as f64), val)
+}
 
-    let mut new_ary = vec![];
+/// DeletePropertyOrThrow(ary, k)
+fn delete_element(vm: &mut VM, ary: Value, k: usize) -> Result<(), RuntimeError> {
+    if ary
+        .get_object_info()
+        .delete_property_by_value(Value::Number(k as f64))
+    {
+        return Ok(());
+    }
+    Err(vm
+        .current_context
+        .error_type(format!("Cannot delete property '{}'", k)))
+}
 
-    for i in 0..ary_info.get_length() {
-        args_for_callback[0] = vm.get_property_by_value(this, Value::Number(i as f64))?; // 'i'th element may be getter
-        args_for_callback[1] = Value::Number(i as f64);
+/// Moves ``count`` elements from ``from`` to ``to`` in an array-like object, keeping holes.
+fn move_elements(
+    vm: &mut VM,
+    ary: Value,
+    from: usize,
+    to: usize,
+    count: usize,
+) -> Result<(), RuntimeError> {
+    // Moves from the end if the elements go to higher indices, not to overwrite them.
+    let offsets: Vec<usize> = if from < to {
+        (0..count).rev().collect()
+    } else {
+        (0..count).collect()
+    };
+    for i in offsets {
+        if has_element(vm, ary, from + i) {
+            let val = get_element(vm, ary, from + i)?;
+            set_element(vm, ary, to + i, val)?;
+        } else {
+            delete_element(vm, ary, to + i)?;
+        }
+    }
+    Ok(())
+}
 
-        let val = vm.call_function(callback, &args_for_callback, Value::undefined())?;
-        if val.is_empty() {
-            unreachable!("EMPTY")
+/// The elements of ``ary[start..end]`` as data properties, keeping holes.
+fn copy_elements(
+    vm: &mut VM,
+    ary: Value,
+    start: usize,
+    end: usize,
+) -> Result<Vec<Property>, RuntimeError> {
+    let mut elems = vec![];
+    for k in start..end {
+        let val = if has_element(vm, ary, k) {
+            get_element(vm, ary, k)?
+        } else {
+            Value::empty()
         };
+        elems.push(Property::new_data_simple(val));
+    }
+    Ok(elems)
+}
 
-        new_ary.push(Property::new_data_simple(val));
+/// A relative index argument clamped into ``0..=len``, where undefined is ``default``.
+fn relative_index(vm: &mut VM, val: Value, len: usize, default: usize) -> usize {
+    if val.is_undefined() {
+        return default;
     }
-    let val = vm.factory.array(new_ary);
-    Ok(val)
+    let relative = to_integer_or_infinity(vm, val);
+    let index = if relative < 0.0 {
+        len as f64 + relative
+    } else {
+        relative
+    };
+    index.max(0.0).min(len as f64) as usize
+}
+
+/// The first element for which the callback returns true, visiting holes as undefined.
+fn find_element(
+    vm: &mut VM,
+    args: &[Value],
+    this: Value,
+) -> Result<Option<(usize, Value)>, RuntimeError> {
+    let (predicate, this_arg) = callback_arg(vm, args)?;
+    let len = length_of(vm, this)?;
+    for k in 0..len {
+        let val = get_element(vm, this, k)?;
+        if call_callback(vm, predicate, this_arg, val, k, this)?.to_boolean() {
+            return Ok(Some((k, val)));
+        }
+    }
+    Ok(None)
+}
+
+/// https://tc39.github.io/ecma262/#sec-flattenintoarray
+fn flatten_into_array(
+    vm: &mut VM,
+    target: &mut Vec<Property>,
+    source: Value,
+    depth: f64,
+) -> Result<(), RuntimeError> {
+    let len = length_of(vm, source)?;
+    for k in 0..len {
+        if !has_element(vm, source, k) {
+            continue;
+        }
+        let val = get_element(vm, source, k)?;
+        if depth > 0.0 && val.is_array_object() {
+            flatten_into_array(vm, target, val, depth - 1.0)?;
+        } else {
+            target.push(Property::new_data_simple(val));
+        }
+    }
+    Ok(())
+}
+
+/// Reduces the elements at ``indices`` in order, skipping holes.
+fn reduce(vm: &mut VM, args: &[Value], this: Value, indices: Vec<usize>) -> VMValueResult {
+    let callback = arg(args, 0);
+    if !callback.is_function_object() {
+        return Err(vm
+            .current_context
+            .error_type(format!("{} is not a function", callback.debug_string(true))));
+    }
+    let mut indices = indices.into_iter();
+    let mut accumulator = if args.len() > 1 {
+        args[1]
+    } else {
+        match indices.find(|k| has_element(vm, this, *k)) {
+            Some(k) => get_element(vm, this, k)?,
+            None => {
+                return Err(vm
+                    .current_context
+                    .error_type("Reduce of empty array with no initial value"))
+            }
+        }
+    };
+    for k in indices {
+        if !has_element(vm, this, k) {
+            continue;
+        }
+        let val = get_element(vm, this, k)?;
+        accumulator = vm.call_function(
+            callback,
+            &[accumulator, val, Value::Number(k as f64), this],
+            Value::undefined(),
+        )?;
+    }
+    Ok(accumulator)
+}
+
+/// https://tc39.github.io/ecma262/#sec-sortcompare
+fn sort_compare(
+    vm: &mut VM,
+    comparefn: Value,
+    x: Value,
+    y: Value,
+) -> Result<Ordering, RuntimeError> {
+    if comparefn.is_undefined() {
+        let x: Vec<u16> = x.to_string().encode_utf16().collect();
+        let y: Vec<u16> = y.to_string().encode_utf16().collect();
+        return Ok(x.cmp(&y));
+    }
+    let v = vm
+        .call_function(comparefn, &[x, y], Value::undefined())?
+        .to_number(&mut vm.factory.memory_allocator);
+    Ok(v.partial_cmp(&0.0).unwrap_or(Ordering::Equal))
+}
+
+/// A stable merge sort, since the comparison may throw.
+fn merge_sort(
+    vm: &mut VM,
+    items: Vec<Value>,
+    comparefn: Value,
+) -> Result<Vec<Value>, RuntimeError> {
+    if items.len() <= 1 {
+        return Ok(items);
+    }
+    let mut right = items;
+    let left = right.drain(..right.len() / 2).collect();
+    let left = merge_sort(vm, left, comparefn)?;
+    let right = merge_sort(vm, right, comparefn)?;
+
+    let mut merged = Vec::with_capacity(left.len() + right.len());
+    let mut left = left.into_iter().peekable();
+    let mut right = right.into_iter().peekable();
+    while let (Some(x), Some(y)) = (left.peek(), right.peek()) {
+        // Takes from the left unless the right is strictly smaller.
+        if sort_compare(vm, comparefn, *y, *x)? == Ordering::Less {
+            merged.push(right.next().unwrap());
+        } else {
+            merged.push(left.next().unwrap());
+        }
+    }
+    merged.extend(left);
+    merged.extend(right);
+    Ok(merged)
 }
@@ -1,6 +1,6 @@
 use crate::vm::{
     jsvalue::value::{
-        cstrp_to_str, AccessorProperty, DataProperty, ObjectKind, ObjectRef, Property, Value,
+        AccessorProperty, DataProperty, ObjectKind, ObjectRef, PromiseState, Property, Value,
         EMPTY, NULL, UNDEFINED, UNINITIALIZED,
     },
     vm::VMValueResult,
@@ -66,11 +66,9 @@ pub fn debug_print(val: &Value, nest: bool) {
         Value::Bool(1) => print!("true"),
         Value::Bool(0) => print!("false"),
         Value::Bool(_) => unreachable!(),
-        Value::Number(n) if n.is_nan() => print!("NaN"),
-        Value::Number(n) if n.is_infinite() => print!("Infinity"),
-        Value::Number(n) => print!("{}", *n),
-        Value::String(ref s) => {
-            let s = cstrp_to_str(*s);
+        Value::Number(_) => print!("{}", val.debug_string(nest)),
+        Value::String(_) => {
+            let s = val.into_str();
             if nest {
                 print!("'{}'", s)
             } else {
@@ -97,9 +95,41 @@ pub fn debug_print(val: &Value, nest: bool) {
                     "Symbol({})",
                     info.description.as_ref().unwrap_or(&"".to_string())
                 ),
-                ObjectKind::Error(ref _info) => {
-                    print!("Error({})", obj_info.get_property("message").to_string())
-                }
+                ObjectKind::Error(ref _info) => print!(
+                    "{}({})",
+                    obj_info.get_property("name").to_string(),
+                    obj_info.get_property("message").to_string()
+                ),
+                ObjectKind::ArrayIterator(_) => print!("[Array Iterator]"),
+                ObjectKind::StringIterator(_) => print!("[String Iterator]"),
+                ObjectKind::RegExpStringIterator(_) => print!("[RegExp String Iterator]"),
+                ObjectKind::MapIterator(_) => print!("[Map Iterator]"),
+                ObjectKind::SetIterator(_) => print!("[Set Iterator]"),
+                ObjectKind::ForInIterator(_) => print!("[For-In Iterator]"),
+                ObjectKind::RegExp(ref info) => print!("{}", info),
+                ObjectKind::Promise(ref info) => match info.state {
+                    PromiseState::Pending => print!("Promise {{ <pending> }}"),
+                    PromiseState::Fulfilled => {
+                        print!("Promise {{ ");
+                        debug_print(&info.result, true);
+                        print!(" }}");
+                    }
+                    PromiseState::Rejected => {
+                        print!("Promise {{ <rejected> ");
+                        debug_print(&info.result, true);
+                        print!(" }}");
+                    }
+                },
+                ObjectKind::AsyncFunction(_) => print!("[AsyncFunction]"),
+                ObjectKind::Generator(_) => print!("Object [Generator] {{}}"),
+                ObjectKind::ModuleNamespace(_)
+                | ObjectKind::Date(_)
+                | ObjectKind::Number(_)
+                | ObjectKind::String(_)
+                | ObjectKind::Map(_)
+                | ObjectKind::Set(_)
+                | ObjectKind::WeakMap(_)
+                | ObjectKind::WeakSet(_) => print!("{}", val.debug_string(true)),
                 ObjectKind::Function(ref func_info) => {
                     if let Some(ref name) = func_info.name {
                         print!("[Function: {}]", name);
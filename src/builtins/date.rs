@@ -1,51 +1,613 @@
-// use chrono::Utc;
-// use vm::value::*;
-// use vm::{error::RuntimeError, vm::VM};
-//
-// thread_local!(
-//     pub static DATE_PROTOTYPE: Value = {
-//         make_object!(
-//             // TODO: Add methods
-//         )
-//     };
-//
-//     pub static DATE_OBJ: Value = {
-//         let mut prototype = DATE_PROTOTYPE.with(|x| x.clone());
-//         let date = Value::builtin_function(
-//             date,
-//             None,
-//             &mut make_npp!(
-//                 // TODO: Add methods
-//                 now:    Value::default_builtin_function(date_now)
-//             ),
-//             Some(prototype.clone())
-//         );
-//
-//         prototype.set_constructor(date.clone());
-//
-//         date
-//     }
-// );
-//
-// pub fn date(vm: &mut VM, _args: &Vec<Value>, _: CallObjectRef) -> Result<(), RuntimeError> {
-//     let now = Utc::now();
-//
-//     vm.state.stack.push(Value::string(now.to_rfc3339()));
-//
-//     Ok(())
-// }
-//
-// pub fn date_new(vm: &mut VM, _args: &Vec<Value>, _: CallObjectRef) -> Result<(), RuntimeError> {
-//     let now = Utc::now();
-//
-//     vm.state.stack.push(Value::date(now));
-//
-//     Ok(())
-// }
-//
-// pub fn date_now(vm: &mut VM, _args: &Vec<Value>, _: CallObjectRef) -> Result<(), RuntimeError> {
-//     let now = Utc::now();
-//     let now_millis = now.timestamp_millis();
-//     vm.state.stack.push(Value::Number(now_millis as f64));
-//     Ok(())
-// }
+use crate::builtin::{arg, is_constructor_call};
+use crate::vm::{
+    error::RuntimeError,
+    jsvalue::{date, object::ObjectKind, value::*},
+    vm::{Factory, VMValueResult, VM},
+};
+
+const YEAR: usize = 0;
+const MONTH: usize = 1;
+const DATE: usize = 2;
+const HOURS: usize = 3;
+const MINUTES: usize = 4;
+const SECONDS: usize = 5;
+const MILLISECONDS: usize = 6;
+
+pub fn date(factory: &mut Factory) -> Value {
+    let obj = factory.generate_builtin_constructor(
+        "Date",
+        date_constructor,
+        7,
+        factory.object_prototypes.date,
+    );
+
+    // Date.now
+    obj.set_property("now", factory.builtin_function("now", date_now, 0));
+    // Date.parse
+    obj.set_property("parse", factory.builtin_function("parse", date_parse, 1));
+    // Date.UTC
+    obj.set_property("UTC", factory.builtin_function("UTC", date_utc, 7));
+    obj
+}
+
+/// https://tc39.github.io/ecma262/#sec-date-constructor
+pub fn date_constructor(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    if !is_constructor_call(this, vm.factory.object_prototypes.date) {
+        let string = date::to_date_string(date::current_time());
+        return Ok(vm.factory.string(string));
+    }
+
+    let time = match args.len() {
+        0 => date::current_time(),
+        1 => match date_value(args[0]) {
+            Some(time) => time,
+            None => {
+                let value = vm.to_primitive(args[0], PreferredType::Default)?;
+                if value.is_string() {
+                    date::parse(value.into_str())
+                } else {
+                    value.to_number(&mut vm.factory.memory_allocator)
+                }
+            }
+        },
+        _ => date::utc(time_from_components(vm, args)),
+    };
+    let prototype = this.get_object_info().prototype;
+    Ok(vm.factory.date(prototype, date::time_clip(time)))
+}
+
+/// Date.now
+pub fn date_now(_vm: &mut VM, _args: &[Value], _this: Value) -> VMValueResult {
+    Ok(Value::Number(date::current_time()))
+}
+
+/// Date.parse
+pub fn date_parse(_vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    let string = arg(args, 0).to_string();
+    Ok(Value::Number(date::parse(string.as_str())))
+}
+
+/// Date.UTC
+pub fn date_utc(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    let time = if args.is_empty() {
+        f64::NAN
+    } else {
+        time_from_components(vm, args)
+    };
+    Ok(Value::Number(date::time_clip(time)))
+}
+
+/// The time value of ``val`` if it is a Date object.
+fn date_value(val: Value) -> Option<f64> {
+    if !val.is_object() {
+        return None;
+    }
+    match val.get_object_info().kind {
+        ObjectKind::Date(time) => Some(time),
+        _ => None,
+    }
+}
+
+/// The time value given by (year, month[, date[, hours[, minutes[, seconds[, ms]]]]]),
+/// where a year between 0 and 99 means 1900 to 1999.
+fn time_from_components(vm: &mut VM, args: &[Value]) -> f64 {
+    let mut components = [f64::NAN, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0];
+    for (component, arg) in components.iter_mut().zip(args.iter()) {
+        *component = arg.to_number(&mut vm.factory.memory_allocator);
+    }
+    let year = components[YEAR];
+    if !year.is_nan() && (0.0..=99.0).contains(&year.trunc()) {
+        components[YEAR] = 1900.0 + year.trunc();
+    }
+    make_date_from_components(&components)
+}
+
+fn make_date_from_components(components: &[f64; 7]) -> f64 {
+    date::make_date(
+        date::make_day(components[YEAR], components[MONTH], components[DATE]),
+        date::make_time(
+            components[HOURS],
+            components[MINUTES],
+            components[SECONDS],
+            components[MILLISECONDS],
+        ),
+    )
+}
+
+/// https://tc39.github.io/ecma262/#sec-thistimevalue
+fn this_time_value(vm: &VM, this: Value, method: &str) -> Result<f64, RuntimeError> {
+    date_value(this).ok_or_else(|| {
+        vm.current_context
+            .error_type(format!("{} called on incompatible receiver", method))
+    })
+}
+
+/// Returns ``f`` of the local time of ``this``, or NaN for an invalid date.
+fn get_local(vm: &VM, this: Value, method: &str, f: fn(f64) -> f64) -> VMValueResult {
+    let time = this_time_value(vm, this, method)?;
+    if time.is_nan() {
+        return Ok(Value::Number(time));
+    }
+    Ok(Value::Number(f(date::local_time(time))))
+}
+
+/// Returns ``f`` of the time value of ``this``, or NaN for an invalid date.
+fn get_utc(vm: &VM, this: Value, method: &str, f: fn(f64) -> f64) -> VMValueResult {
+    let time = this_time_value(vm, this, method)?;
+    if time.is_nan() {
+        return Ok(Value::Number(time));
+    }
+    Ok(Value::Number(f(time)))
+}
+
+/// Replaces the components of the (local or UTC) time of ``this`` from ``first`` with at most
+/// ``count`` arguments, and returns the new time value.
+fn set_components(
+    vm: &mut VM,
+    args: &[Value],
+    this: Value,
+    method: &str,
+    is_local: bool,
+    first: usize,
+    count: usize,
+) -> VMValueResult {
+    let time = this_time_value(vm, this, method)?;
+    let values = if args.is_empty() {
+        vec![f64::NAN]
+    } else {
+        args.iter()
+            .take(count)
+            .map(|arg| arg.to_number(&mut vm.factory.memory_allocator))
+            .collect()
+    };
+
+    let time = match (time.is_nan(), first) {
+        // setFullYear and setUTCFullYear start from +0 if the date is invalid.
+        (true, YEAR) => 0.0,
+        (true, _) => return Ok(Value::Number(time)),
+        (false, _) if is_local => date::local_time(time),
+        (false, _) => time,
+    };
+    let mut components = [
+        date::year_from_time(time),
+        date::month_from_time(time),
+        date::date_from_time(time),
+        date::hour_from_time(time),
+        date::min_from_time(time),
+        date::sec_from_time(time),
+        date::ms_from_time(time),
+    ];
+    components[first..first + values.len()].copy_from_slice(&values);
+
+    let new_time = make_date_from_components(&components);
+    let new_time = date::time_clip(if is_local {
+        date::utc(new_time)
+    } else {
+        new_time
+    });
+    this.get_object_info().kind = ObjectKind::Date(new_time);
+    Ok(Value::Number(new_time))
+}
+
+/// Returns ``f`` of the time value of ``this`` as a string.
+fn to_string_with(vm: &mut VM, this: Value, method: &str, f: fn(f64) -> String) -> VMValueResult {
+    let time = this_time_value(vm, this, method)?;
+    Ok(vm.factory.string(f(time)))
+}
+
+/// Date.prototype.getDate
+pub fn date_prototype_get_date(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    get_local(vm, this, "Date.prototype.getDate", date::date_from_time)
+}
+
+/// Date.prototype.getDay
+pub fn date_prototype_get_day(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    get_local(vm, this, "Date.prototype.getDay", date::week_day)
+}
+
+/// Date.prototype.getFullYear
+pub fn date_prototype_get_full_year(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    get_local(vm, this, "Date.prototype.getFullYear", date::year_from_time)
+}
+
+/// Date.prototype.getHours
+pub fn date_prototype_get_hours(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    get_local(vm, this, "Date.prototype.getHours", date::hour_from_time)
+}
+
+/// Date.prototype.getMilliseconds
+pub fn date_prototype_get_milliseconds(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    get_local(
+        vm,
+        this,
+        "Date.prototype.getMilliseconds",
+        date::ms_from_time,
+    )
+}
+
+/// Date.prototype.getMinutes
+pub fn date_prototype_get_minutes(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    get_local(vm, this, "Date.prototype.getMinutes", date::min_from_time)
+}
+
+/// Date.prototype.getMonth
+pub fn date_prototype_get_month(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    get_local(vm, this, "Date.prototype.getMonth", date::month_from_time)
+}
+
+/// Date.prototype.getSeconds
+pub fn date_prototype_get_seconds(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    get_local(vm, this, "Date.prototype.getSeconds", date::sec_from_time)
+}
+
+/// Date.prototype.getTime
+pub fn date_prototype_get_time(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    let time = this_time_value(vm, this, "Date.prototype.getTime")?;
+    Ok(Value::Number(time))
+}
+
+/// Date.prototype.getTimezoneOffset
+pub fn date_prototype_get_timezone_offset(
+    vm: &mut VM,
+    _args: &[Value],
+    this: Value,
+) -> VMValueResult {
+    get_utc(vm, this, "Date.prototype.getTimezoneOffset", |time| {
+        (time - date::local_time(time)) / date::MS_PER_MINUTE + 0.0
+    })
+}
+
+/// Date.prototype.getUTCDate
+pub fn date_prototype_get_utc_date(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    get_utc(vm, this, "Date.prototype.getUTCDate", date::date_from_time)
+}
+
+/// Date.prototype.getUTCDay
+pub fn date_prototype_get_utc_day(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    get_utc(vm, this, "Date.prototype.getUTCDay", date::week_day)
+}
+
+/// Date.prototype.getUTCFullYear
+pub fn date_prototype_get_utc_full_year(
+    vm: &mut VM,
+    _args: &[Value],
+    this: Value,
+) -> VMValueResult {
+    get_utc(
+        vm,
+        this,
+        "Date.prototype.getUTCFullYear",
+        date::year_from_time,
+    )
+}
+
+/// Date.prototype.getUTCHours
+pub fn date_prototype_get_utc_hours(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    get_utc(vm, this, "Date.prototype.getUTCHours", date::hour_from_time)
+}
+
+/// Date.prototype.getUTCMilliseconds
+pub fn date_prototype_get_utc_milliseconds(
+    vm: &mut VM,
+    _args: &[Value],
+    this: Value,
+) -> VMValueResult {
+    get_utc(
+        vm,
+        this,
+        "Date.prototype.getUTCMilliseconds",
+        date::ms_from_time,
+    )
+}
+
+/// Date.prototype.getUTCMinutes
+pub fn date_prototype_get_utc_minutes(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    get_utc(
+        vm,
+        this,
+        "Date.prototype.getUTCMinutes",
+        date::min_from_time,
+    )
+}
+
+/// Date.prototype.getUTCMonth
+pub fn date_prototype_get_utc_month(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    get_utc(
+        vm,
+        this,
+        "Date.prototype.getUTCMonth",
+        date::month_from_time,
+    )
+}
+
+/// Date.prototype.getUTCSeconds
+pub fn date_prototype_get_utc_seconds(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    get_utc(
+        vm,
+        this,
+        "Date.prototype.getUTCSeconds",
+        date::sec_from_time,
+    )
+}
+
+/// Date.prototype.setDate
+pub fn date_prototype_set_date(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    set_components(vm, args, this, "Date.prototype.setDate", true, DATE, 1)
+}
+
+/// Date.prototype.setFullYear
+pub fn date_prototype_set_full_year(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    set_components(vm, args, this, "Date.prototype.setFullYear", true, YEAR, 3)
+}
+
+/// Date.prototype.setHours
+pub fn date_prototype_set_hours(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    set_components(vm, args, this, "Date.prototype.setHours", true, HOURS, 4)
+}
+
+/// Date.prototype.setMilliseconds
+pub fn date_prototype_set_milliseconds(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    set_components(
+        vm,
+        args,
+        this,
+        "Date.prototype.setMilliseconds",
+        true,
+        MILLISECONDS,
+        1,
+    )
+}
+
+/// Date.prototype.setMinutes
+pub fn date_prototype_set_minutes(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    set_components(
+        vm,
+        args,
+        this,
+        "Date.prototype.setMinutes",
+        true,
+        MINUTES,
+        3,
+    )
+}
+
+/// Date.prototype.setMonth
+pub fn date_prototype_set_month(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    set_components(vm, args, this, "Date.prototype.setMonth", true, MONTH, 2)
+}
+
+/// Date.prototype.setSeconds
+pub fn date_prototype_set_seconds(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    set_components(
+        vm,
+        args,
+        this,
+        "Date.prototype.setSeconds",
+        true,
+        SECONDS,
+        2,
+    )
+}
+
+/// Date.prototype.setTime
+pub fn date_prototype_set_time(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    this_time_value(vm, this, "Date.prototype.setTime")?;
+    let time = arg(args, 0).to_number(&mut vm.factory.memory_allocator);
+    let time = date::time_clip(time);
+    this.get_object_info().kind = ObjectKind::Date(time);
+    Ok(Value::Number(time))
+}
+
+/// Date.prototype.setUTCDate
+pub fn date_prototype_set_utc_date(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    set_components(vm, args, this, "Date.prototype.setUTCDate", false, DATE, 1)
+}
+
+/// Date.prototype.setUTCFullYear
+pub fn date_prototype_set_utc_full_year(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    set_components(
+        vm,
+        args,
+        this,
+        "Date.prototype.setUTCFullYear",
+        false,
+        YEAR,
+        3,
+    )
+}
+
+/// Date.prototype.setUTCHours
+pub fn date_prototype_set_utc_hours(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    set_components(
+        vm,
+        args,
+        this,
+        "Date.prototype.setUTCHours",
+        false,
+        HOURS,
+        4,
+    )
+}
+
+/// Date.prototype.setUTCMilliseconds
+pub fn date_prototype_set_utc_milliseconds(
+    vm: &mut VM,
+    args: &[Value],
+    this: Value,
+) -> VMValueResult {
+    set_components(
+        vm,
+        args,
+        this,
+        "Date.prototype.setUTCMilliseconds",
+        false,
+        MILLISECONDS,
+        1,
+    )
+}
+
+/// Date.prototype.setUTCMinutes
+pub fn date_prototype_set_utc_minutes(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    set_components(
+        vm,
+        args,
+        this,
+        "Date.prototype.setUTCMinutes",
+        false,
+        MINUTES,
+        3,
+    )
+}
+
+/// Date.prototype.setUTCMonth
+pub fn date_prototype_set_utc_month(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    set_components(
+        vm,
+        args,
+        this,
+        "Date.prototype.setUTCMonth",
+        false,
+        MONTH,
+        2,
+    )
+}
+
+/// Date.prototype.setUTCSeconds
+pub fn date_prototype_set_utc_seconds(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    set_components(
+        vm,
+        args,
+        this,
+        "Date.prototype.setUTCSeconds",
+        false,
+        SECONDS,
+        2,
+    )
+}
+
+/// Date.prototype.toDateString
+pub fn date_prototype_to_date_string(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    to_string_with(
+        vm,
+        this,
+        "Date.prototype.toDateString",
+        date::to_local_date_string,
+    )
+}
+
+/// Date.prototype.toISOString
+pub fn date_prototype_to_iso_string(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    let time = this_time_value(vm, this, "Date.prototype.toISOString")?;
+    match date::to_iso_string(time) {
+        Some(string) => Ok(vm.factory.string(string)),
+        None => Err(vm.current_context.error_range("Invalid time value")),
+    }
+}
+
+/// Date.prototype.toJSON
+/// https://tc39.github.io/ecma262/#sec-date.prototype.tojson
+pub fn date_prototype_to_json(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    let time_value = vm.to_primitive(this, PreferredType::Number)?;
+    if let Value::Number(time) = time_value {
+        if !time.is_finite() {
+            return Ok(Value::null());
+        }
+    }
+    let key = vm.factory.string("toISOString");
+    let to_iso_string = vm.get_property_by_value(this, key)?;
+    if !to_iso_string.is_function_object() {
+        return Err(vm
+            .current_context
+            .error_type("toISOString is not a function"));
+    }
+    vm.call_function(to_iso_string, &[], this)
+}
+
+/// Date.prototype.toLocaleDateString
+pub fn date_prototype_to_locale_date_string(
+    vm: &mut VM,
+    _args: &[Value],
+    this: Value,
+) -> VMValueResult {
+    to_string_with(
+        vm,
+        this,
+        "Date.prototype.toLocaleDateString",
+        date::to_locale_date_string,
+    )
+}
+
+/// Date.prototype.toLocaleString
+pub fn date_prototype_to_locale_string(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    to_string_with(
+        vm,
+        this,
+        "Date.prototype.toLocaleString",
+        date::to_locale_string,
+    )
+}
+
+/// Date.prototype.toLocaleTimeString
+pub fn date_prototype_to_locale_time_string(
+    vm: &mut VM,
+    _args: &[Value],
+    this: Value,
+) -> VMValueResult {
+    to_string_with(
+        vm,
+        this,
+        "Date.prototype.toLocaleTimeString",
+        date::to_locale_time_string,
+    )
+}
+
+/// Date.prototype.toString
+pub fn date_prototype_to_string(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    to_string_with(vm, this, "Date.prototype.toString", date::to_date_string)
+}
+
+/// Date.prototype.toTimeString
+pub fn date_prototype_to_time_string(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    to_string_with(
+        vm,
+        this,
+        "Date.prototype.toTimeString",
+        date::to_local_time_string,
+    )
+}
+
+/// Date.prototype.toUTCString
+pub fn date_prototype_to_utc_string(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    to_string_with(vm, this, "Date.prototype.toUTCString", date::to_utc_string)
+}
+
+/// Date.prototype.valueOf
+pub fn date_prototype_value_of(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    let time = this_time_value(vm, this, "Date.prototype.valueOf")?;
+    Ok(Value::Number(time))
+}
+
+/// Date.prototype[@@toPrimitive]
+/// https://tc39.github.io/ecma262/#sec-date.prototype-@@toprimitive
+pub fn date_prototype_to_primitive(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    if !this.is_object() {
+        return Err(vm
+            .current_context
+            .error_type("Date.prototype[Symbol.toPrimitive] called on non-object"));
+    }
+    let hint = arg(args, 0);
+    let hint = match if hint.is_string() {
+        hint.into_str()
+    } else {
+        ""
+    } {
+        "string" | "default" => PreferredType::String,
+        "number" => PreferredType::Number,
+        _ => {
+            return Err(vm
+                .current_context
+                .error_type(format!("Invalid hint: {}", hint.debug_string(true))))
+        }
+    };
+    vm.ordinary_to_primitive(this, hint)
+}
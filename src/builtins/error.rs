@@ -1,5 +1,9 @@
+use crate::builtin::{arg, is_constructor_call, object_kind, BuiltinFuncTy};
 use crate::vm::{
-    jsvalue::value::Value,
+    jsvalue::{
+        object::{DataProperty, ObjectKind, Property},
+        value::Value,
+    },
     vm::{Factory, VMValueResult, VM},
 };
 
@@ -7,40 +11,206 @@ pub fn error(factory: &mut Factory) -> Value {
     factory.generate_builtin_constructor(
         "Error",
         error_constructor,
+        1,
         factory.object_prototypes.error,
     )
 }
 
-pub fn error_constructor(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
-    let message = if args.len() == 0 {
+/// https://tc39.github.io/ecma262/#sec-native-error-types-used-in-this-standard
+/// The index of a name is the ``KIND`` of its constructor.
+const NATIVE_ERRORS: [&str; 6] = [
+    "EvalError",
+    "RangeError",
+    "ReferenceError",
+    "SyntaxError",
+    "TypeError",
+    "URIError",
+];
+
+/// The NativeError constructors in the order of NATIVE_ERRORS.
+pub fn native_errors(factory: &mut Factory, error_constructor: Value) -> [Value; 6] {
+    let funcs: [BuiltinFuncTy; 6] = [
+        native_error_constructor::<0>,
+        native_error_constructor::<1>,
+        native_error_constructor::<2>,
+        native_error_constructor::<3>,
+        native_error_constructor::<4>,
+        native_error_constructor::<5>,
+    ];
+    let mut constructors = [Value::undefined(); 6];
+    for (kind, name) in NATIVE_ERRORS.iter().enumerate() {
+        let prototype = native_error_prototype(factory, kind);
+        constructors[kind] =
+            native_error(factory, error_constructor, name, funcs[kind], 1, prototype);
+    }
+    constructors
+}
+
+pub fn aggregate_error(factory: &mut Factory, error_constructor: Value) -> Value {
+    let prototype = factory.object_prototypes.aggregate_error;
+    native_error(
+        factory,
+        error_constructor,
+        "AggregateError",
+        aggregate_error_constructor,
+        2,
+        prototype,
+    )
+}
+
+/// The NativeError constructors and AggregateError inherit from the Error constructor.
+fn native_error(
+    factory: &mut Factory,
+    error_constructor: Value,
+    name: &str,
+    func: BuiltinFuncTy,
+    length: usize,
+    prototype: Value,
+) -> Value {
+    let constructor = factory.generate_builtin_constructor(name, func, length, prototype);
+    constructor.get_object_info().prototype = error_constructor;
+    constructor
+}
+
+/// https://tc39.github.io/ecma262/#sec-error-message
+pub fn error_constructor(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let prototype = vm.factory.object_prototypes.error;
+    create_error(vm, this, prototype, arg(args, 0), arg(args, 1))
+}
+
+/// https://tc39.github.io/ecma262/#sec-nativeerror
+/// The NativeError constructors differ only in the prototype of the errors they create.
+fn native_error_constructor<const KIND: usize>(
+    vm: &mut VM,
+    args: &[Value],
+    this: Value,
+) -> VMValueResult {
+    let prototype = native_error_prototype(&vm.factory, KIND);
+    create_error(vm, this, prototype, arg(args, 0), arg(args, 1))
+}
+
+fn native_error_prototype(factory: &Factory, kind: usize) -> Value {
+    let prototypes = &factory.object_prototypes;
+    [
+        prototypes.eval_error,
+        prototypes.range_error,
+        prototypes.reference_error,
+        prototypes.syntax_error,
+        prototypes.type_error,
+        prototypes.uri_error,
+    ][kind]
+}
+
+/// https://tc39.github.io/ecma262/#sec-aggregate-error
+pub fn aggregate_error_constructor(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let prototype = vm.factory.object_prototypes.aggregate_error;
+    let error = create_error(vm, this, prototype, arg(args, 1), arg(args, 2))?;
+    let mut errors = vec![];
+    let iter = vm.get_iterator(arg(args, 0))?;
+    while let Some(val) = vm.iterator_step(iter)? {
+        errors.push(Property::new_data_simple(val));
+    }
+    let errors = vm.factory.array(errors);
+    define_non_enumerable(error, "errors", errors);
+    Ok(error)
+}
+
+/// https://tc39.github.io/ecma262/#sec-error.prototype.tostring
+pub fn error_prototype_to_string(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    if !this.is_object() {
+        return Err(vm.current_context.error_type(format!(
+            "Error.prototype.toString requires that 'this' be an Object: {}",
+            this.debug_string(true)
+        )));
+    }
+    let name = vm.factory.string("name");
+    let name = vm.get_property_by_value(this, name)?;
+    let name = if name.is_undefined() {
+        "Error".to_string()
+    } else {
+        name.to_string()
+    };
+    let message = vm.factory.string("message");
+    let message = vm.get_property_by_value(this, message)?;
+    let message = if message.is_undefined() {
         "".to_string()
     } else {
-        args[0].to_string()
+        message.to_string()
+    };
+    let string = if name.is_empty() {
+        message
+    } else if message.is_empty() {
+        name
+    } else {
+        format!("{}: {}", name, message)
+    };
+    Ok(vm.factory.string(string))
+}
+
+/// get Error.prototype.stack
+/// The string of the error followed by the stack trace captured when it was created.
+pub fn error_prototype_stack(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    let stack_trace = match object_kind(this) {
+        Some(ObjectKind::Error(info)) => info.stack_trace.clone(),
+        _ => return Ok(Value::undefined()),
+    };
+    let string = error_prototype_to_string(vm, &[], this)?.to_string();
+    Ok(vm.factory.string(string + stack_trace.as_str()))
+}
+
+/// set Error.prototype.stack
+/// The stack is replaced with an own property.
+pub fn error_prototype_set_stack(_vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    if this.is_object() {
+        define_non_enumerable(this, "stack", arg(args, 0));
+    }
+    Ok(Value::undefined())
+}
+
+/// Creates an error object with ``message`` and the cause given in ``options``. A constructor
+/// call creates it with the prototype of the constructor, which may be a subclass.
+fn create_error(
+    vm: &mut VM,
+    this: Value,
+    prototype: Value,
+    message: Value,
+    options: Value,
+) -> VMValueResult {
+    let prototype = if is_constructor_call(this, prototype) {
+        this.get_object_info().prototype
+    } else {
+        prototype
+    };
+    let message = if message.is_undefined() {
+        None
+    } else {
+        Some(message.to_string())
     };
-    let obj = vm.factory.error(message);
-    Ok(obj)
-}
-
-// pub fn init() -> Value {
-//     let mut prototype = ERROR_PROTOTYPE.with(|x| x.clone());
-//     let obj = Value::builtin_function(error_new, None, &mut vec![], Some(prototype.clone()));
-//     prototype.set_constructor(obj.clone());
-//
-//     obj
-// }
-
-// fn error_new(vm: &mut VM, args: &Vec<Value>, _: CallObjectRef) -> Result<(), RuntimeError> {
-//     let message = match args.len() {
-//         0 => "".to_string(),
-//         _ => args[0].to_string(),
-//     };
-//     let prototype = ERROR_PROTOTYPE.with(|x| x.clone());
-//     let obj = make_object!(
-//         message:    Value::string(message),
-//         name:       Value::string("Error".to_string()),
-//         __proto__:  prototype
-//     );
-//     vm.set_return_value(obj);
-//
-//     Ok(())
-// }
+    let error = vm.factory.error_object(prototype, message);
+    vm.capture_stack_trace(error);
+
+    // https://tc39.github.io/ecma262/#sec-installerrorcause
+    if options.is_object() {
+        let key = vm.factory.string("cause");
+        if options
+            .get_object_info()
+            .has_property_by_value(&mut vm.factory.memory_allocator, key)
+        {
+            let cause = vm.get_property_by_value(options, key)?;
+            define_non_enumerable(error, "cause", cause);
+        }
+    }
+    Ok(error)
+}
+
+pub fn define_non_enumerable(obj: Value, key: &str, val: Value) {
+    obj.get_object_info().property.insert(
+        key.to_string(),
+        Property::Data(DataProperty {
+            val,
+            writable: true,
+            enumerable: false,
+            configurable: true,
+        }),
+    );
+}
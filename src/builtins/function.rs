@@ -1,5 +1,13 @@
+use crate::builtin::{arg, object_kind};
+use crate::builtins::number::to_integer_or_infinity;
+use crate::parser::Parser;
 use crate::vm::{
-    jsvalue::{function::UserFunctionInfo, value::Value},
+    error::RuntimeError,
+    jsvalue::{
+        function::{FuncInfoRef, FunctionObjectKind},
+        object::ObjectKind,
+        value::Value,
+    },
     vm::{Factory, VMValueResult, VM},
 };
 
@@ -7,18 +15,69 @@ pub fn function(factory: &mut Factory) -> Value {
     factory.generate_builtin_constructor(
         "Function",
         function_constructor,
+        1,
         factory.object_prototypes.function,
     )
 }
 
-// TODO: https://www.ecma-international.org/ecma-262/9.0/index.html#sec-function-constructor
-pub fn function_constructor(vm: &mut VM, _args: &[Value], _this: Value) -> VMValueResult {
-    let user_func_info =
-        UserFunctionInfo::new(&mut vm.factory, vm.current_context.func_ref.module_func_id);
-    let func_id = vm.factory.new_func_id();
-    let func_info = vm.factory.alloc_user_func_info(func_id, user_func_info);
-    let func = vm.factory.function(func_info, None);
-    Ok(func)
+/// The last argument is the body, and the others are the parameters. The function is created in
+/// the global scope.
+/// https://tc39.github.io/ecma262/#sec-createdynamicfunction
+pub fn function_constructor(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    let (params, body) = match args.split_last() {
+        Some((body, params)) => (params, body.to_string()),
+        None => (args, "".to_string()),
+    };
+    let params = params
+        .iter()
+        .map(|param| param.to_string())
+        .collect::<Vec<String>>()
+        .join(",");
+    let source = format!("(function anonymous({}\n) {{\n{}\n}})", params, body);
+
+    let mut parser = Parser::new("Function", source);
+    let func_info = match parser.parse_all() {
+        Ok(node) => vm.compile_function(&node).ok(),
+        Err(_) => None,
+    };
+    let func_info = match func_info {
+        Some(func_info) => func_info,
+        None => {
+            return Err(vm
+                .current_context
+                .error_syntax("Invalid source text for the Function constructor"))
+        }
+    };
+    vm.script_info
+        .push((func_info.module_func_id, parser.into_script_info()));
+    Ok(vm.factory.function(func_info, vm.global_environment))
+}
+
+/// https://tc39.github.io/ecma262/#sec-function.prototype.apply
+pub fn function_prototype_apply(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    if !this.is_function_object() {
+        return Err(vm
+            .current_context
+            .error_type("Function.prototype.apply was called on a non-function"));
+    }
+    let arg_array = arg(args, 1);
+    let arg_list = if arg_array.is_undefined() || arg_array.is_null() {
+        vec![]
+    } else {
+        create_list_from_array_like(vm, arg_array)?
+    };
+    vm.call_function(this, &arg_list, arg(args, 0))
+}
+
+/// https://tc39.github.io/ecma262/#sec-function.prototype.bind
+pub fn function_prototype_bind(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    if !this.is_function_object() {
+        return Err(vm
+            .current_context
+            .error_type("Bind must be called on a function"));
+    }
+    let bound_args = args.get(1..).unwrap_or(&[]).to_vec();
+    Ok(vm.factory.bound_function(this, arg(args, 0), bound_args))
 }
 
 pub fn function_prototype_call(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
@@ -26,3 +85,64 @@ pub fn function_prototype_call(vm: &mut VM, args: &[Value], this: Value) -> VMVa
     let func = this;
     vm.call_function(func, args.get(1..).unwrap_or(&[]), this_arg)
 }
+
+/// User functions return their source text. The others, and functions whose source text is not
+/// kept, return a NativeFunction.
+/// https://tc39.github.io/ecma262/#sec-function.prototype.tostring
+pub fn function_prototype_to_string(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    let func = match object_kind(this) {
+        Some(ObjectKind::Function(func)) => func,
+        _ => {
+            return Err(vm
+                .current_context
+                .error_type("Function.prototype.toString requires that 'this' be a Function"))
+        }
+    };
+    let source = match func.kind {
+        FunctionObjectKind::User { info, .. } => source_text(vm, info),
+        _ => None,
+    };
+    let source = source.unwrap_or_else(|| {
+        let name = func.name.clone().unwrap_or_default();
+        format!("function {}() {{ [native code] }}", name)
+    });
+    Ok(vm.factory.string(source))
+}
+
+/// https://tc39.github.io/ecma262/#sec-function.prototype-@@hasinstance
+pub fn function_prototype_has_instance(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let val = *args.first().unwrap_or(&Value::undefined());
+    let result = vm.ordinary_has_instance(this, val)?;
+    Ok(Value::bool(result))
+}
+
+/// https://tc39.github.io/ecma262/#sec-createlistfromarraylike
+fn create_list_from_array_like(vm: &mut VM, obj: Value) -> Result<Vec<Value>, RuntimeError> {
+    if !obj.is_object() {
+        return Err(vm.current_context.error_type(format!(
+            "CreateListFromArrayLike called on non-object {}",
+            obj.debug_string(true)
+        )));
+    }
+    let length = vm.factory.string("length");
+    let length = vm.get_property_by_value(obj, length)?;
+    let len = to_integer_or_infinity(vm, length).max(0.0) as usize;
+    let mut list = vec![];
+    for k in 0..len {
+        list.push(vm.get_property_by_value(obj, Value::Number(k as f64))?);
+    }
+    Ok(list)
+}
+
+/// The source text of the function in the script it was parsed from.
+fn source_text(vm: &VM, func_info: FuncInfoRef) -> Option<String> {
+    let (_, script_info) = vm
+        .script_info
+        .iter()
+        .find(|(id, _)| *id == func_info.module_func_id)?;
+    let (start, end) = *script_info.function_sources.get(&func_info.source_pos)?;
+    script_info
+        .code
+        .get(start..end)
+        .map(|text| text.to_string())
+}
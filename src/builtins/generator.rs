@@ -0,0 +1,119 @@
+use crate::builtin::arg;
+use crate::builtins::iterator::create_iter_result_object;
+use crate::vm::{
+    jsvalue::value::*,
+    vm::{VMValueResult, VM},
+};
+
+/// How a generator is resumed.
+/// https://tc39.github.io/ecma262/#sec-generatorresumeabrupt
+#[derive(Clone, Debug, PartialEq, Copy)]
+enum ResumeKind {
+    Next,
+    Return,
+    Throw,
+}
+
+/// https://tc39.github.io/ecma262/#sec-generator.prototype.next
+pub fn generator_prototype_next(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    generator_resume(vm, this, arg(args, 0), ResumeKind::Next, "next")
+}
+
+/// https://tc39.github.io/ecma262/#sec-generator.prototype.return
+pub fn generator_prototype_return(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    generator_resume(vm, this, arg(args, 0), ResumeKind::Return, "return")
+}
+
+/// https://tc39.github.io/ecma262/#sec-generator.prototype.throw
+pub fn generator_prototype_throw(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    generator_resume(vm, this, arg(args, 0), ResumeKind::Throw, "throw")
+}
+
+/// https://tc39.github.io/ecma262/#sec-generatorresume
+fn generator_resume(
+    vm: &mut VM,
+    generator: Value,
+    value: Value,
+    kind: ResumeKind,
+    method: &str,
+) -> VMValueResult {
+    let info = match generator_info(generator) {
+        Some(info) => info,
+        None => {
+            return Err(vm.current_context.error_type(format!(
+                "Method Generator.prototype.{} called on incompatible receiver {}",
+                method,
+                generator.debug_string(true)
+            )))
+        }
+    };
+
+    match info.state {
+        GeneratorState::Executing => {
+            return Err(vm
+                .current_context
+                .error_type("Generator is already running"))
+        }
+        // A generator which has not started completes without running its body.
+        GeneratorState::SuspendedStart if kind != ResumeKind::Next => {
+            info.state = GeneratorState::Completed;
+            info.context = None;
+        }
+        _ => {}
+    }
+
+    if info.state == GeneratorState::Completed {
+        return match kind {
+            ResumeKind::Next => Ok(create_iter_result_object(
+                &mut vm.factory,
+                Value::undefined(),
+                true,
+            )),
+            ResumeKind::Return => Ok(create_iter_result_object(&mut vm.factory, value, true)),
+            ResumeKind::Throw => Err(vm.current_context.error_exception(value)),
+        };
+    }
+
+    let mut context = info.context.take().unwrap();
+    if info.state == GeneratorState::SuspendedYield {
+        context.stack.push(value.into());
+        context
+            .stack
+            .push(Value::bool(kind == ResumeKind::Throw).into());
+        context
+            .stack
+            .push(Value::bool(kind == ResumeKind::Return).into());
+    }
+    info.state = GeneratorState::Executing;
+
+    let result = vm.run_generator_context(context);
+    let info = generator_info(generator).unwrap();
+    match result {
+        Ok((val, Some(context))) => {
+            info.state = GeneratorState::SuspendedYield;
+            info.context = Some(context);
+            Ok(val)
+        }
+        Ok((val, None)) => {
+            info.state = GeneratorState::Completed;
+            Ok(create_iter_result_object(&mut vm.factory, val, true))
+        }
+        Err(err) => {
+            info.state = GeneratorState::Completed;
+            Err(err)
+        }
+    }
+}
+
+fn generator_info<'a>(val: Value) -> Option<&'a mut GeneratorInfo> {
+    match val {
+        Value::Object(info) => {
+            let info: &'a mut ObjectInfo = unsafe { &mut *info };
+            match info.kind {
+                ObjectKind::Generator(ref mut info) => Some(info),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
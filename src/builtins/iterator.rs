@@ -0,0 +1,17 @@
+use crate::vm::{
+    jsvalue::value::Value,
+    vm::{Factory, VMValueResult, VM},
+};
+
+/// %IteratorPrototype%\[@@iterator\]
+pub fn iterator_prototype_iterator(_vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    Ok(this)
+}
+
+/// https://tc39.github.io/ecma262/#sec-createiterresultobject
+pub fn create_iter_result_object(factory: &mut Factory, value: Value, done: bool) -> Value {
+    make_normal_object!(factory,
+        value => true, true, true: value,
+        done  => true, true, true: Value::bool(done)
+    )
+}
@@ -0,0 +1,511 @@
+use crate::builtin::arg;
+use crate::vm::{
+    error::RuntimeError,
+    jsvalue::object::ObjectRef,
+    jsvalue::value::{ObjectKind, Property, PropertyMap, Value, NULL},
+    vm::{Factory, VMValueResult, VM},
+};
+
+pub fn json(factory: &mut Factory) -> Value {
+    let parse = factory.builtin_function("parse", json_parse, 2);
+    let stringify = factory.builtin_function("stringify", json_stringify, 3);
+
+    make_normal_object!(factory,
+        parse     => true, false, true: parse,
+        stringify => true, false, true: stringify
+    )
+}
+
+/// https://tc39.github.io/ecma262/#sec-json.parse
+pub fn json_parse(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    let text = arg(args, 0).to_string();
+    let reviver = arg(args, 1);
+
+    let unfiltered = match JSONParser::new(text.as_str(), &mut vm.factory).parse() {
+        Ok(val) => val,
+        Err(msg) => return Err(vm.current_context.error_syntax(msg)),
+    };
+    if !reviver.is_function_object() {
+        return Ok(unfiltered);
+    }
+
+    let root = make_normal_object!(vm.factory);
+    root.set_property("", unfiltered);
+    internalize_json_property(vm, reviver, root, "".to_string())
+}
+
+/// https://tc39.github.io/ecma262/#sec-internalizejsonproperty
+fn internalize_json_property(
+    vm: &mut VM,
+    reviver: Value,
+    holder: Value,
+    name: String,
+) -> VMValueResult {
+    let key = vm.factory.string(name);
+    let val = vm.get_property_by_value(holder, key)?;
+
+    if val.is_object() {
+        let keys = match val.get_object_info().kind {
+            ObjectKind::Array(ref info) => (0..info.elems.len()).map(|i| i.to_string()).collect(),
+            _ => val
+                .get_object_info()
+                .own_string_keys()
+                .into_iter()
+                .filter(|(_, enumerable)| *enumerable)
+                .map(|(key, _)| key)
+                .collect::<Vec<String>>(),
+        };
+        for key in keys {
+            let new_element = internalize_json_property(vm, reviver, val, key.clone())?;
+            if new_element.is_undefined() {
+                val.get_object_info().delete_property(key.as_str());
+            } else {
+                let key = vm.factory.string(key);
+                vm.set_property_by_value(val, key, new_element)?;
+            }
+        }
+    }
+
+    vm.call_function(reviver, &[key, val], holder)
+}
+
+struct JSONParser<'a> {
+    chars: Vec<char>,
+    pos: usize,
+    factory: &'a mut Factory,
+}
+
+impl<'a> JSONParser<'a> {
+    fn new(text: &str, factory: &'a mut Factory) -> Self {
+        JSONParser {
+            chars: text.chars().collect(),
+            pos: 0,
+            factory,
+        }
+    }
+
+    fn parse(mut self) -> Result<Value, String> {
+        let val = self.read_value()?;
+        self.skip_whitespace();
+        if self.pos < self.chars.len() {
+            return Err(self.unexpected());
+        }
+        Ok(val)
+    }
+
+    fn read_value(&mut self) -> Result<Value, String> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('{') => self.read_object(),
+            Some('[') => self.read_array(),
+            Some('"') => {
+                let string = self.read_string()?;
+                Ok(self.factory.utf16_string(string))
+            }
+            Some('-') | Some('0'..='9') => self.read_number(),
+            Some('t') => self.read_literal("true", Value::bool(true)),
+            Some('f') => self.read_literal("false", Value::bool(false)),
+            Some('n') => self.read_literal("null", Value::null()),
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    fn read_object(&mut self) -> Result<Value, String> {
+        self.pos += 1; // {
+        let mut property = PropertyMap::default();
+
+        self.skip_whitespace();
+        if self.eat('}') {
+            return Ok(self.factory.object(property));
+        }
+
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some('"') {
+                return Err(self.unexpected());
+            }
+            let key = String::from_utf16_lossy(&self.read_string()?);
+            self.skip_whitespace();
+            if !self.eat(':') {
+                return Err(self.unexpected());
+            }
+            let val = self.read_value()?;
+            property.insert(key, Property::new_data_simple(val));
+
+            self.skip_whitespace();
+            if self.eat('}') {
+                return Ok(self.factory.object(property));
+            }
+            if !self.eat(',') {
+                return Err(self.unexpected());
+            }
+        }
+    }
+
+    fn read_array(&mut self) -> Result<Value, String> {
+        self.pos += 1; // [
+        let mut elems = vec![];
+
+        self.skip_whitespace();
+        if self.eat(']') {
+            return Ok(self.factory.array(elems));
+        }
+
+        loop {
+            let val = self.read_value()?;
+            elems.push(Property::new_data_simple(val));
+
+            self.skip_whitespace();
+            if self.eat(']') {
+                return Ok(self.factory.array(elems));
+            }
+            if !self.eat(',') {
+                return Err(self.unexpected());
+            }
+        }
+    }
+
+    fn read_string(&mut self) -> Result<Vec<u16>, String> {
+        self.pos += 1; // "
+        let mut units: Vec<u16> = vec![];
+        loop {
+            let c = match self.next() {
+                Some(c) => c,
+                None => return Err(self.unexpected()),
+            };
+            match c {
+                '"' => return Ok(units),
+                '\\' => {
+                    let escaped = match self.next() {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('/') => '/',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('u') => {
+                            units.push(self.read_hex4()?);
+                            continue;
+                        }
+                        _ => {
+                            self.pos -= 1;
+                            return Err(self.unexpected());
+                        }
+                    };
+                    units.push(escaped as u16)
+                }
+                c if (c as u32) < 0x20 => {
+                    self.pos -= 1;
+                    return Err(self.unexpected());
+                }
+                c => {
+                    let mut buf = [0; 2];
+                    units.extend_from_slice(c.encode_utf16(&mut buf))
+                }
+            }
+        }
+    }
+
+    fn read_hex4(&mut self) -> Result<u16, String> {
+        let mut n = 0;
+        for _ in 0..4 {
+            match self.peek().and_then(|c| c.to_digit(16)) {
+                Some(digit) => n = n * 16 + digit as u16,
+                None => return Err(self.unexpected()),
+            }
+            self.pos += 1;
+        }
+        Ok(n)
+    }
+
+    fn read_number(&mut self) -> Result<Value, String> {
+        let start = self.pos;
+        self.eat('-');
+        if !self.eat('0') && !self.skip_digits() {
+            return Err(self.unexpected());
+        }
+        if self.eat('.') && !self.skip_digits() {
+            return Err(self.unexpected());
+        }
+        if self.eat('e') || self.eat('E') {
+            if !self.eat('+') {
+                self.eat('-');
+            }
+            if !self.skip_digits() {
+                return Err(self.unexpected());
+            }
+        }
+        let number: String = self.chars[start..self.pos].iter().collect();
+        Ok(Value::Number(number.parse::<f64>().unwrap()))
+    }
+
+    fn read_literal(&mut self, literal: &str, val: Value) -> Result<Value, String> {
+        for expected in literal.chars() {
+            if !self.eat(expected) {
+                return Err(self.unexpected());
+            }
+        }
+        Ok(val)
+    }
+
+    /// Returns false if there is no digit.
+    fn skip_digits(&mut self) -> bool {
+        let start = self.pos;
+        while let Some('0'..='9') = self.peek() {
+            self.pos += 1;
+        }
+        start != self.pos
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some('\t') | Some('\n') | Some('\r') | Some(' ') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).cloned()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek();
+        self.pos += 1;
+        c
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        if self.peek() == Some(expected) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn unexpected(&self) -> String {
+        match self.peek() {
+            Some(c) => format!("Unexpected token {} in JSON at position {}", c, self.pos),
+            None => "Unexpected end of JSON input".to_string(),
+        }
+    }
+}
+
+/// https://tc39.github.io/ecma262/#sec-json.stringify
+pub fn json_stringify(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    let val = arg(args, 0);
+    let replacer = arg(args, 1);
+    let space = arg(args, 2);
+
+    let mut stringifier = JSONStringifier {
+        stack: vec![],
+        indent: "".to_string(),
+        gap: "".to_string(),
+        property_list: None,
+        replacer_function: None,
+    };
+
+    if replacer.is_function_object() {
+        stringifier.replacer_function = Some(replacer);
+    } else if is_array(replacer) {
+        let mut property_list: Vec<String> = vec![];
+        let elems: Vec<Value> = match replacer.get_object_info().kind {
+            ObjectKind::Array(ref info) => info.elems.iter().map(|e| e.as_data().val).collect(),
+            _ => unreachable!(),
+        };
+        for elem in elems {
+            let item = match elem {
+                Value::String(_) | Value::Number(_) => elem.to_string(),
+                _ => continue,
+            };
+            if !property_list.contains(&item) {
+                property_list.push(item)
+            }
+        }
+        stringifier.property_list = Some(property_list);
+    }
+
+    stringifier.gap = match space {
+        Value::Number(n) => {
+            let n = if n.is_nan() { 0.0 } else { n.trunc() };
+            " ".repeat(n.clamp(0.0, 10.0) as usize)
+        }
+        Value::String(_) => space.to_string().chars().take(10).collect(),
+        _ => "".to_string(),
+    };
+
+    let wrapper = make_normal_object!(vm.factory);
+    wrapper.set_property("", val);
+    match stringifier.serialize_property(vm, "".to_string(), wrapper)? {
+        Some(json) => Ok(vm.factory.string(json)),
+        None => Ok(Value::undefined()),
+    }
+}
+
+struct JSONStringifier {
+    /// Objects being serialized, to detect cyclic structures.
+    stack: Vec<Value>,
+    indent: String,
+    gap: String,
+    property_list: Option<Vec<String>>,
+    replacer_function: Option<Value>,
+}
+
+impl JSONStringifier {
+    /// https://tc39.github.io/ecma262/#sec-serializejsonproperty
+    /// Returns None if the value is not serializable (e.g. undefined or a function).
+    fn serialize_property(
+        &mut self,
+        vm: &mut VM,
+        key: String,
+        holder: Value,
+    ) -> Result<Option<String>, RuntimeError> {
+        let key = vm.factory.string(key);
+        let mut val = vm.get_property_by_value(holder, key)?;
+
+        if val.is_object() {
+            let to_json_key = vm.factory.string("toJSON");
+            let to_json = vm.get_property_by_value(val, to_json_key)?;
+            if to_json.is_function_object() {
+                val = vm.call_function(to_json, &[key], val)?;
+            }
+        }
+
+        if let Some(replacer) = self.replacer_function {
+            val = vm.call_function(replacer, &[key, val], holder)?;
+        }
+
+        // Unwrap Number and String objects.
+        if let Value::Object(info) = val {
+            match ObjectRef(info).kind {
+                ObjectKind::Number(_) => {
+                    val = Value::Number(val.to_number(&mut vm.factory.memory_allocator))
+                }
+                ObjectKind::String(_) => val = vm.factory.string(val.to_string()),
+                _ => {}
+            }
+        }
+
+        Ok(match val {
+            Value::Other(NULL) => Some("null".to_string()),
+            Value::Bool(_) => Some(val.to_string()),
+            Value::String(_) => Some(quote_json_string(val.to_string().as_str())),
+            // ToString(-0) is "0".
+            Value::Number(n) if n.is_finite() => Some(Value::Number(n + 0.0).to_string()),
+            Value::Number(_) => Some("null".to_string()),
+            Value::Object(_) if val.is_symbol() || val.is_function_object() => None,
+            Value::Object(_) if is_array(val) => Some(self.serialize_array(vm, val)?),
+            Value::Object(_) => Some(self.serialize_object(vm, val)?),
+            _ => None,
+        })
+    }
+
+    /// https://tc39.github.io/ecma262/#sec-serializejsonobject
+    fn serialize_object(&mut self, vm: &mut VM, val: Value) -> Result<String, RuntimeError> {
+        self.enter(vm, val)?;
+        let stepback = self.indent.clone();
+        self.indent += self.gap.as_str();
+
+        let keys = match self.property_list {
+            Some(ref property_list) => property_list.clone(),
+            None => val
+                .get_object_info()
+                .own_string_keys()
+                .into_iter()
+                .filter(|(_, enumerable)| *enumerable)
+                .map(|(key, _)| key)
+                .collect(),
+        };
+
+        let mut partial = vec![];
+        for key in keys {
+            if let Some(str_p) = self.serialize_property(vm, key.clone(), val)? {
+                let separator = if self.gap.is_empty() { ":" } else { ": " };
+                partial.push(format!("{}{}{}", quote_json_string(&key), separator, str_p));
+            }
+        }
+
+        let json = self.join(partial, "{", "}", stepback.as_str());
+        self.stack.pop();
+        self.indent = stepback;
+        Ok(json)
+    }
+
+    /// https://tc39.github.io/ecma262/#sec-serializejsonarray
+    fn serialize_array(&mut self, vm: &mut VM, val: Value) -> Result<String, RuntimeError> {
+        self.enter(vm, val)?;
+        let stepback = self.indent.clone();
+        self.indent += self.gap.as_str();
+
+        let len = match val.get_object_info().kind {
+            ObjectKind::Array(ref info) => info.elems.len(),
+            _ => unreachable!(),
+        };
+        let mut partial = vec![];
+        for i in 0..len {
+            let str_p = self.serialize_property(vm, i.to_string(), val)?;
+            partial.push(str_p.unwrap_or_else(|| "null".to_string()));
+        }
+
+        let json = self.join(partial, "[", "]", stepback.as_str());
+        self.stack.pop();
+        self.indent = stepback;
+        Ok(json)
+    }
+
+    fn enter(&mut self, vm: &mut VM, val: Value) -> Result<(), RuntimeError> {
+        if self.stack.contains(&val) {
+            return Err(vm
+                .current_context
+                .error_type("Converting circular structure to JSON"));
+        }
+        self.stack.push(val);
+        Ok(())
+    }
+
+    fn join(&self, partial: Vec<String>, open: &str, close: &str, stepback: &str) -> String {
+        if partial.is_empty() {
+            return format!("{}{}", open, close);
+        }
+        if self.gap.is_empty() {
+            return format!("{}{}{}", open, partial.join(","), close);
+        }
+        let separator = format!(",\n{}", self.indent);
+        format!(
+            "{}\n{}{}\n{}{}",
+            open,
+            self.indent,
+            partial.join(separator.as_str()),
+            stepback,
+            close
+        )
+    }
+}
+
+/// https://tc39.github.io/ecma262/#sec-quotejsonstring
+fn quote_json_string(s: &str) -> String {
+    let mut product = "\"".to_string();
+    for c in s.chars() {
+        match c {
+            '\u{8}' => product.push_str("\\b"),
+            '\t' => product.push_str("\\t"),
+            '\n' => product.push_str("\\n"),
+            '\u{c}' => product.push_str("\\f"),
+            '\r' => product.push_str("\\r"),
+            '"' => product.push_str("\\\""),
+            '\\' => product.push_str("\\\\"),
+            c if (c as u32) < 0x20 => product.push_str(format!("\\u{:04x}", c as u32).as_str()),
+            c => product.push(c),
+        }
+    }
+    product.push('"');
+    product
+}
+
+fn is_array(val: Value) -> bool {
+    match val {
+        Value::Object(_) => matches!(val.get_object_info().kind, ObjectKind::Array(_)),
+        _ => false,
+    }
+}
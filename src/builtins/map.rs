@@ -0,0 +1,244 @@
+use crate::builtin::{arg, is_constructor_call, object_kind};
+use crate::builtins::iterator::create_iter_result_object;
+use crate::vm::{
+    error::RuntimeError,
+    jsvalue::{
+        collection::MapData,
+        iterator::IterationKind,
+        object::{ObjectKind, Property},
+        value::Value,
+    },
+    vm::{Factory, VMResult, VMValueResult, VM},
+};
+
+pub fn map(factory: &mut Factory) -> Value {
+    factory.generate_builtin_constructor("Map", map_constructor, 0, factory.object_prototypes.map)
+}
+
+pub fn weak_map(factory: &mut Factory) -> Value {
+    factory.generate_builtin_constructor(
+        "WeakMap",
+        weak_map_constructor,
+        0,
+        factory.object_prototypes.weak_map,
+    )
+}
+
+/// https://tc39.github.io/ecma262/#sec-map-iterable
+pub fn map_constructor(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    if !is_constructor_call(this, vm.factory.object_prototypes.map) {
+        return Err(vm
+            .current_context
+            .error_type("Constructor Map requires 'new'"));
+    }
+    let prototype = this.get_object_info().prototype;
+    let map = vm
+        .factory
+        .keyed_collection(prototype, ObjectKind::Map(Box::default()));
+    add_entries_from_iterable(vm, map, arg(args, 0))?;
+    Ok(map)
+}
+
+/// Map.prototype.clear
+pub fn map_prototype_clear(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    this_map(vm, this, "Map.prototype.clear")?.clear();
+    Ok(Value::undefined())
+}
+
+/// Map.prototype.delete
+pub fn map_prototype_delete(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let data = this_map(vm, this, "Map.prototype.delete")?;
+    Ok(Value::bool(data.delete(arg(args, 0))))
+}
+
+/// Map.prototype.entries
+pub fn map_prototype_entries(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    let cursor = this_map(vm, this, "Map.prototype.entries")?.cursor();
+    Ok(vm
+        .factory
+        .map_iterator(this, cursor, IterationKind::KeyAndValue))
+}
+
+/// Map.prototype.forEach
+pub fn map_prototype_for_each(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let cursor = this_map(vm, this, "Map.prototype.forEach")?.cursor();
+    let callback = arg(args, 0);
+    if !callback.is_function_object() {
+        return Err(vm
+            .current_context
+            .error_type(format!("{} is not a function", callback.debug_string(true))));
+    }
+    // Entries added by the callback are visited as well.
+    while let Some((key, val)) = this_map(vm, this, "Map.prototype.forEach")?.next_entry(&cursor) {
+        vm.call_function(callback, &[val, key, this], arg(args, 1))?;
+    }
+    Ok(Value::undefined())
+}
+
+/// Map.prototype.get
+pub fn map_prototype_get(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let data = this_map(vm, this, "Map.prototype.get")?;
+    Ok(data.get(arg(args, 0)).unwrap_or_else(Value::undefined))
+}
+
+/// Map.prototype.has
+pub fn map_prototype_has(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let data = this_map(vm, this, "Map.prototype.has")?;
+    Ok(Value::bool(data.has(arg(args, 0))))
+}
+
+/// Map.prototype.keys
+pub fn map_prototype_keys(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    let cursor = this_map(vm, this, "Map.prototype.keys")?.cursor();
+    Ok(vm.factory.map_iterator(this, cursor, IterationKind::Key))
+}
+
+/// Map.prototype.set
+pub fn map_prototype_set(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    this_map(vm, this, "Map.prototype.set")?.set(arg(args, 0), arg(args, 1));
+    Ok(this)
+}
+
+/// get Map.prototype.size
+pub fn map_prototype_size(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    let data = this_map(vm, this, "get Map.prototype.size")?;
+    Ok(Value::Number(data.size() as f64))
+}
+
+/// Map.prototype.values
+pub fn map_prototype_values(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    let cursor = this_map(vm, this, "Map.prototype.values")?.cursor();
+    Ok(vm.factory.map_iterator(this, cursor, IterationKind::Value))
+}
+
+/// %MapIteratorPrototype%.next
+pub fn map_iterator_prototype_next(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    let info = match object_kind(this) {
+        Some(ObjectKind::MapIterator(info)) => info,
+        _ => return Err(vm.current_context.error_type("this is not a Map Iterator")),
+    };
+
+    if let Some(ObjectKind::Map(data)) = object_kind(info.iterated) {
+        if let Some((key, val)) = data.next_entry(&info.cursor) {
+            let result = match info.kind {
+                IterationKind::Key => key,
+                IterationKind::Value => val,
+                IterationKind::KeyAndValue => vm.factory.array(vec![
+                    Property::new_data_simple(key),
+                    Property::new_data_simple(val),
+                ]),
+            };
+            return Ok(create_iter_result_object(&mut vm.factory, result, false));
+        }
+    }
+
+    info.iterated = Value::undefined();
+    Ok(create_iter_result_object(
+        &mut vm.factory,
+        Value::undefined(),
+        true,
+    ))
+}
+
+/// https://tc39.github.io/ecma262/#sec-weakmap-iterable
+pub fn weak_map_constructor(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    if !is_constructor_call(this, vm.factory.object_prototypes.weak_map) {
+        return Err(vm
+            .current_context
+            .error_type("Constructor WeakMap requires 'new'"));
+    }
+    let prototype = this.get_object_info().prototype;
+    let weak_map = vm
+        .factory
+        .keyed_collection(prototype, ObjectKind::WeakMap(Box::default()));
+    add_entries_from_iterable(vm, weak_map, arg(args, 0))?;
+    Ok(weak_map)
+}
+
+/// WeakMap.prototype.delete
+pub fn weak_map_prototype_delete(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let data = this_weak_map(vm, this, "WeakMap.prototype.delete")?;
+    Ok(Value::bool(data.delete(arg(args, 0))))
+}
+
+/// WeakMap.prototype.get
+pub fn weak_map_prototype_get(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let data = this_weak_map(vm, this, "WeakMap.prototype.get")?;
+    Ok(data.get(arg(args, 0)).unwrap_or_else(Value::undefined))
+}
+
+/// WeakMap.prototype.has
+pub fn weak_map_prototype_has(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let data = this_weak_map(vm, this, "WeakMap.prototype.has")?;
+    Ok(Value::bool(data.has(arg(args, 0))))
+}
+
+/// WeakMap.prototype.set
+pub fn weak_map_prototype_set(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let data = this_weak_map(vm, this, "WeakMap.prototype.set")?;
+    let key = arg(args, 0);
+    if !can_be_held_weakly(key) {
+        return Err(vm.current_context.error_type(format!(
+            "Invalid value used as weak map key: {}",
+            key.debug_string(true)
+        )));
+    }
+    data.set(key, arg(args, 1));
+    Ok(this)
+}
+
+/// The error for ``method`` called on a ``this`` of another kind.
+pub fn incompatible_receiver(vm: &VM, this: Value, method: &str) -> RuntimeError {
+    vm.current_context.error_type(format!(
+        "Method {} called on incompatible receiver {}",
+        method,
+        this.debug_string(true)
+    ))
+}
+
+/// Only an object, which is not a symbol, can be the key of a WeakMap or a value of a WeakSet.
+pub fn can_be_held_weakly(val: Value) -> bool {
+    val.is_object() && !val.is_symbol()
+}
+
+fn this_map<'a>(vm: &VM, this: Value, method: &str) -> Result<&'a mut MapData, RuntimeError> {
+    match object_kind(this) {
+        Some(ObjectKind::Map(data)) => Ok(data),
+        _ => Err(incompatible_receiver(vm, this, method)),
+    }
+}
+
+fn this_weak_map<'a>(vm: &VM, this: Value, method: &str) -> Result<&'a mut MapData, RuntimeError> {
+    match object_kind(this) {
+        Some(ObjectKind::WeakMap(data)) => Ok(data),
+        _ => Err(incompatible_receiver(vm, this, method)),
+    }
+}
+
+/// Calls ``target.set(key, value)`` for each ``[key, value]`` entry of ``iterable``.
+/// https://tc39.github.io/ecma262/#sec-add-entries-from-iterable
+fn add_entries_from_iterable(vm: &mut VM, target: Value, iterable: Value) -> VMResult {
+    if iterable.is_undefined() || iterable.is_null() {
+        return Ok(());
+    }
+    let adder = vm.factory.string("set");
+    let adder = vm.get_property_by_value(target, adder)?;
+    if !adder.is_function_object() {
+        return Err(vm
+            .current_context
+            .error_type(format!("{} is not a function", adder.debug_string(true))));
+    }
+    let iter = vm.get_iterator(iterable)?;
+    while let Some(entry) = vm.iterator_step(iter)? {
+        if !entry.is_object() {
+            return Err(vm.current_context.error_type(format!(
+                "Iterator value {} is not an entry object",
+                entry.debug_string(true)
+            )));
+        }
+        let key = vm.get_property_by_value(entry, Value::Number(0.0))?;
+        let val = vm.get_property_by_value(entry, Value::Number(1.0))?;
+        vm.call_function(adder, &[key, val], target)?;
+    }
+    Ok(())
+}
@@ -1,16 +1,340 @@
+use crate::builtin::BuiltinFuncTy;
+use crate::vm::jsvalue::object::{DataProperty, Property};
 use crate::vm::jsvalue::value::Value;
 use crate::vm::vm::{Factory, VMValueResult, VM};
-use rand::random;
+use rand::Rng;
+use std::f64::consts;
 
 pub fn math(factory: &mut Factory) -> Value {
-    let math_random = factory.builtin_function("random", math_random);
+    let obj = make_normal_object!(factory);
 
-    make_normal_object!(factory,
-        random => true, false, true: math_random
-    )
+    // The value properties are neither writable, enumerable nor configurable.
+    let constants: [(&str, f64); 8] = [
+        ("E", consts::E),
+        ("LN10", consts::LN_10),
+        ("LN2", consts::LN_2),
+        ("LOG10E", consts::LOG10_E),
+        ("LOG2E", consts::LOG2_E),
+        ("PI", consts::PI),
+        ("SQRT1_2", consts::FRAC_1_SQRT_2),
+        ("SQRT2", consts::SQRT_2),
+    ];
+    for (name, val) in constants.iter() {
+        obj.get_object_info().property.insert(
+            name.to_string(),
+            Property::Data(DataProperty {
+                val: Value::Number(*val),
+                writable: false,
+                enumerable: false,
+                configurable: false,
+            }),
+        );
+    }
+
+    let functions: [(&str, BuiltinFuncTy, usize); 35] = [
+        ("abs", math_abs, 1),
+        ("acos", math_acos, 1),
+        ("acosh", math_acosh, 1),
+        ("asin", math_asin, 1),
+        ("asinh", math_asinh, 1),
+        ("atan", math_atan, 1),
+        ("atanh", math_atanh, 1),
+        ("atan2", math_atan2, 2),
+        ("cbrt", math_cbrt, 1),
+        ("ceil", math_ceil, 1),
+        ("clz32", math_clz32, 1),
+        ("cos", math_cos, 1),
+        ("cosh", math_cosh, 1),
+        ("exp", math_exp, 1),
+        ("expm1", math_expm1, 1),
+        ("floor", math_floor, 1),
+        ("fround", math_fround, 1),
+        ("hypot", math_hypot, 2),
+        ("imul", math_imul, 2),
+        ("log", math_log, 1),
+        ("log1p", math_log1p, 1),
+        ("log10", math_log10, 1),
+        ("log2", math_log2, 1),
+        ("max", math_max, 2),
+        ("min", math_min, 2),
+        ("pow", math_pow, 2),
+        ("random", math_random, 0),
+        ("round", math_round, 1),
+        ("sign", math_sign, 1),
+        ("sin", math_sin, 1),
+        ("sinh", math_sinh, 1),
+        ("sqrt", math_sqrt, 1),
+        ("tan", math_tan, 1),
+        ("tanh", math_tanh, 1),
+        ("trunc", math_trunc, 1),
+    ];
+    for (name, func, length) in functions.iter() {
+        let func = factory.builtin_function(*name, *func, *length);
+        obj.get_object_info().property.insert(
+            name.to_string(),
+            Property::Data(DataProperty {
+                val: func,
+                writable: true,
+                enumerable: false,
+                configurable: true,
+            }),
+        );
+    }
+
+    obj
+}
+
+/// ToNumber(args[idx]), where a missing argument is undefined.
+fn number_arg(vm: &mut VM, args: &[Value], idx: usize) -> f64 {
+    args.get(idx)
+        .copied()
+        .unwrap_or_else(Value::undefined)
+        .to_number(&mut vm.factory.memory_allocator)
+}
+
+/// ToNumber() of all the arguments.
+fn number_args(vm: &mut VM, args: &[Value]) -> Vec<f64> {
+    args.iter()
+        .map(|arg| arg.to_number(&mut vm.factory.memory_allocator))
+        .collect()
+}
+
+/// Applies ``f`` to ToNumber(args[0]).
+fn unary(vm: &mut VM, args: &[Value], f: fn(f64) -> f64) -> VMValueResult {
+    Ok(Value::number(f(number_arg(vm, args, 0))))
+}
+
+/// Applies ``f`` to ToNumber(args[0]) and ToNumber(args[1]).
+fn binary(vm: &mut VM, args: &[Value], f: fn(f64, f64) -> f64) -> VMValueResult {
+    let x = number_arg(vm, args, 0);
+    let y = number_arg(vm, args, 1);
+    Ok(Value::number(f(x, y)))
+}
+
+/// Number::exponentiate, which differs from f64::powf() for 1 and NaN.
+/// https://tc39.github.io/ecma262/#sec-applying-the-exp-operator
+pub fn pow(base: f64, exponent: f64) -> f64 {
+    if exponent.is_nan() || (base.abs() == 1.0 && exponent.is_infinite()) {
+        return f64::NAN;
+    }
+    base.powf(exponent)
+}
+
+pub fn math_abs(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    unary(vm, args, f64::abs)
+}
+
+pub fn math_acos(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    unary(vm, args, f64::acos)
+}
+
+pub fn math_acosh(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    unary(vm, args, f64::acosh)
+}
+
+pub fn math_asin(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    unary(vm, args, f64::asin)
+}
+
+pub fn math_asinh(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    // Keeps the sign of -0.
+    unary(vm, args, |x| if x == 0.0 { x } else { x.asinh() })
+}
+
+pub fn math_atan(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    unary(vm, args, f64::atan)
+}
+
+pub fn math_atanh(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    unary(vm, args, f64::atanh)
+}
+
+pub fn math_atan2(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    binary(vm, args, f64::atan2)
+}
+
+pub fn math_cbrt(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    unary(vm, args, f64::cbrt)
+}
+
+pub fn math_ceil(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    unary(vm, args, f64::ceil)
+}
+
+pub fn math_clz32(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    let n = args
+        .first()
+        .copied()
+        .unwrap_or_else(Value::undefined)
+        .to_uint32(&mut vm.factory.memory_allocator);
+    Ok(Value::Number(f64::from(n.leading_zeros())))
+}
+
+pub fn math_cos(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    unary(vm, args, f64::cos)
+}
+
+pub fn math_cosh(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    unary(vm, args, f64::cosh)
+}
+
+pub fn math_exp(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    unary(vm, args, f64::exp)
 }
 
-pub fn math_random(_vm: &mut VM, _args: &[Value], _this: Value) -> VMValueResult {
-    let val = Value::Number(random::<f64>());
+pub fn math_expm1(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    unary(vm, args, f64::exp_m1)
+}
+
+pub fn math_floor(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    unary(vm, args, f64::floor)
+}
+
+pub fn math_fround(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    unary(vm, args, |x| f64::from(x as f32))
+}
+
+/// https://tc39.github.io/ecma262/#sec-math.hypot
+pub fn math_hypot(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    let numbers = number_args(vm, args);
+    // An infinity wins over NaN.
+    if numbers.iter().any(|n| n.is_infinite()) {
+        return Ok(Value::Number(f64::INFINITY));
+    }
+    if numbers.iter().any(|n| n.is_nan()) {
+        return Ok(Value::Number(f64::NAN));
+    }
+    // Scales by the largest magnitude to avoid overflow and underflow.
+    let largest = numbers.iter().fold(0.0, |max: f64, n| max.max(n.abs()));
+    if largest == 0.0 {
+        return Ok(Value::Number(0.0));
+    }
+    let sum = numbers
+        .iter()
+        .map(|n| (n / largest) * (n / largest))
+        .sum::<f64>();
+    Ok(Value::number(sum.sqrt() * largest))
+}
+
+pub fn math_imul(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    let mut operand = |idx: usize| {
+        args.get(idx)
+            .copied()
+            .unwrap_or_else(Value::undefined)
+            .to_uint32(&mut vm.factory.memory_allocator)
+    };
+    let a = operand(0);
+    let b = operand(1);
+    Ok(Value::Number(f64::from(a.wrapping_mul(b) as i32)))
+}
+
+pub fn math_log(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    unary(vm, args, f64::ln)
+}
+
+pub fn math_log1p(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    unary(vm, args, f64::ln_1p)
+}
+
+pub fn math_log10(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    unary(vm, args, f64::log10)
+}
+
+pub fn math_log2(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    unary(vm, args, f64::log2)
+}
+
+/// https://tc39.github.io/ecma262/#sec-math.max
+pub fn math_max(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    let numbers = number_args(vm, args);
+    let max = numbers.iter().fold(f64::NEG_INFINITY, |max, &n| {
+        // +0 is larger than -0.
+        if max.is_nan() || n.is_nan() {
+            f64::NAN
+        } else if n > max || (n == 0.0 && max == 0.0 && max.is_sign_negative()) {
+            n
+        } else {
+            max
+        }
+    });
+    Ok(Value::Number(max))
+}
+
+/// https://tc39.github.io/ecma262/#sec-math.min
+pub fn math_min(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    let numbers = number_args(vm, args);
+    let min = numbers.iter().fold(f64::INFINITY, |min, &n| {
+        // -0 is smaller than +0.
+        if min.is_nan() || n.is_nan() {
+            f64::NAN
+        } else if n < min || (n == 0.0 && min == 0.0 && n.is_sign_negative()) {
+            n
+        } else {
+            min
+        }
+    });
+    Ok(Value::Number(min))
+}
+
+pub fn math_pow(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    binary(vm, args, pow)
+}
+
+pub fn math_random(vm: &mut VM, _args: &[Value], _this: Value) -> VMValueResult {
+    let val = Value::Number(vm.random.gen::<f64>());
     Ok(val)
 }
+
+/// https://tc39.github.io/ecma262/#sec-math.round
+pub fn math_round(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    unary(vm, args, |x| {
+        if !x.is_finite() || x.fract() == 0.0 {
+            x
+        } else if (-0.5..0.0).contains(&x) {
+            -0.0
+        } else {
+            // Rounds half up, unlike f64::round().
+            let floor = x.floor();
+            if x - floor >= 0.5 {
+                floor + 1.0
+            } else {
+                floor
+            }
+        }
+    })
+}
+
+pub fn math_sign(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    // NaN, +0 and -0 are returned as is.
+    unary(vm, args, |x| {
+        if x.is_nan() || x == 0.0 {
+            x
+        } else {
+            x.signum()
+        }
+    })
+}
+
+pub fn math_sin(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    unary(vm, args, f64::sin)
+}
+
+pub fn math_sinh(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    unary(vm, args, f64::sinh)
+}
+
+pub fn math_sqrt(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    unary(vm, args, f64::sqrt)
+}
+
+pub fn math_tan(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    unary(vm, args, f64::tan)
+}
+
+pub fn math_tanh(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    unary(vm, args, f64::tanh)
+}
+
+pub fn math_trunc(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    unary(vm, args, f64::trunc)
+}
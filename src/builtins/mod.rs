@@ -3,8 +3,15 @@ pub mod console;
 pub mod date;
 pub mod error;
 pub mod function;
+pub mod generator;
+pub mod iterator;
+pub mod json;
+pub mod map;
 pub mod math;
 pub mod number;
 pub mod object;
+pub mod promise;
+pub mod regexp;
+pub mod set;
 pub mod string;
 pub mod symbol;
@@ -1,76 +1,233 @@
-// use vm::{error::RuntimeError, value::*, vm::VM};
-//
-// thread_local!(
-//     pub static NUMBER_PROTOTYPE: Value =
-//         { make_object!(toString: Value::default_builtin_function(number_prototype_tostring)) };
-// );
-//
-// pub fn number_prototype_tostring(
-//     vm: &mut VM,
-//     args: &Vec<Value>,
-//     callobj: CallObjectRef,
-// ) -> Result<(), RuntimeError> {
-//     let number = if let Value::Number(num) = *callobj.this {
-//         num
-//     } else {
-//         vm.set_return_value(Value::Undefined);
-//         return Ok(());
-//     };
-//
-//     let base = match args.get(0) {
-//         Some(val) => {
-//             let num = val.to_number();
-//             if num - num.floor() == 0.0 && 2.0 <= num && num <= 36.0 {
-//                 num as usize
-//             } else {
-//                 10
-//             }
-//         }
-//         _ => 10,
-//     };
-//
-//     vm.set_return_value(Value::string(f64_to_string(number, base)));
-//
-//     Ok(())
-// }
-//
-// // TODO: Maybe, this function had better be somewhere else. (like ./src/util.rs)
-// fn f64_to_string(f: f64, radix: usize) -> String {
-//     if f.is_nan() {
-//         return "NaN".to_string();
-//     }
-//     if f.is_infinite() {
-//         return "Infinity".to_string();
-//     }
-//
-//     let chars = "0123456789abcdefghijklmnopqrstuvwxyz";
-//     let negative = f < 0.0;
-//     let mut integer = f.abs() as usize;
-//     let mut fraction = f.abs() - integer as f64;
-//     let mut s = "".to_string();
-//     let max_digits = 14;
-//
-//     while integer > 0 {
-//         s.push(chars[(integer % radix)..].chars().next().unwrap());
-//         integer /= radix;
-//     }
-//
-//     s = s.chars().rev().collect();
-//     if fraction != 0.0 {
-//         s.push('.');
-//     }
-//
-//     let mut count = 0;
-//     while fraction > 0.0 && count < max_digits {
-//         fraction *= radix as f64;
-//         s.push(chars[fraction as usize..].chars().next().unwrap());
-//         fraction -= fraction.floor();
-//         count += 1;
-//     }
-//
-//     if negative {
-//         s.insert(0, '-')
-//     }
-//
-//     s
-// }
+use crate::builtin::is_constructor_call;
+use crate::vm::{
+    error::RuntimeError,
+    jsvalue::{
+        number,
+        object::{DataProperty, ObjectKind, Property},
+        value::Value,
+    },
+    vm::{Factory, VMValueResult, VM},
+};
+
+pub fn number(factory: &mut Factory) -> Value {
+    let obj = factory.generate_builtin_constructor(
+        "Number",
+        number_constructor,
+        1,
+        factory.object_prototypes.number,
+    );
+
+    // The value properties are neither writable, enumerable nor configurable.
+    let constants: [(&str, f64); 8] = [
+        ("EPSILON", f64::EPSILON),
+        ("MAX_SAFE_INTEGER", 9_007_199_254_740_991.0),
+        ("MAX_VALUE", f64::MAX),
+        ("MIN_SAFE_INTEGER", -9_007_199_254_740_991.0),
+        ("MIN_VALUE", f64::from_bits(1)),
+        ("NaN", f64::NAN),
+        ("NEGATIVE_INFINITY", f64::NEG_INFINITY),
+        ("POSITIVE_INFINITY", f64::INFINITY),
+    ];
+    for (name, val) in constants.iter() {
+        obj.get_object_info().property.insert(
+            name.to_string(),
+            Property::Data(DataProperty {
+                val: Value::Number(*val),
+                writable: false,
+                enumerable: false,
+                configurable: false,
+            }),
+        );
+    }
+
+    // Number.isFinite
+    obj.set_property(
+        "isFinite",
+        factory.builtin_function("isFinite", number_is_finite, 1),
+    );
+    // Number.isInteger
+    obj.set_property(
+        "isInteger",
+        factory.builtin_function("isInteger", number_is_integer, 1),
+    );
+    // Number.isNaN
+    obj.set_property("isNaN", factory.builtin_function("isNaN", number_is_nan, 1));
+    // Number.isSafeInteger
+    obj.set_property(
+        "isSafeInteger",
+        factory.builtin_function("isSafeInteger", number_is_safe_integer, 1),
+    );
+    obj
+}
+
+/// https://tc39.github.io/ecma262/#sec-number-constructor-number-value
+pub fn number_constructor(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let n = match args.first() {
+        Some(value) => value.to_number(&mut vm.factory.memory_allocator),
+        None => 0.0,
+    };
+    if !is_constructor_call(this, vm.factory.object_prototypes.number) {
+        return Ok(Value::number(n));
+    }
+    let prototype = this.get_object_info().prototype;
+    Ok(vm.factory.number_object(prototype, n))
+}
+
+/// Number.isFinite
+pub fn number_is_finite(_vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    Ok(Value::bool(match args.first() {
+        Some(Value::Number(n)) => n.is_finite(),
+        _ => false,
+    }))
+}
+
+/// Number.isInteger
+pub fn number_is_integer(_vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    Ok(Value::bool(match args.first() {
+        Some(Value::Number(n)) => n.is_finite() && n.trunc() == *n,
+        _ => false,
+    }))
+}
+
+/// Number.isNaN
+pub fn number_is_nan(_vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    Ok(Value::bool(match args.first() {
+        Some(Value::Number(n)) => n.is_nan(),
+        _ => false,
+    }))
+}
+
+/// Number.isSafeInteger
+pub fn number_is_safe_integer(_vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    Ok(Value::bool(match args.first() {
+        Some(Value::Number(n)) => {
+            n.is_finite() && n.trunc() == *n && n.abs() <= 9_007_199_254_740_991.0
+        }
+        _ => false,
+    }))
+}
+
+/// https://tc39.github.io/ecma262/#sec-thisnumbervalue
+fn this_number_value(vm: &VM, this: Value, method: &str) -> Result<f64, RuntimeError> {
+    match this {
+        Value::Number(n) => return Ok(n),
+        Value::Object(_) => {
+            if let ObjectKind::Number(n) = this.get_object_info().kind {
+                return Ok(n);
+            }
+        }
+        _ => {}
+    }
+    Err(vm
+        .current_context
+        .error_type(format!("{} requires that 'this' be a Number", method)))
+}
+
+/// https://tc39.github.io/ecma262/#sec-tointegerorinfinity
+pub fn to_integer_or_infinity(vm: &mut VM, val: Value) -> f64 {
+    let n = val.to_number(&mut vm.factory.memory_allocator);
+    if n.is_nan() {
+        0.0
+    } else {
+        // Converts -0 to +0.
+        n.trunc() + 0.0
+    }
+}
+
+/// ToIntegerOrInfinity(args[0]) in ``min..=max``.
+fn digits_arg(
+    vm: &mut VM,
+    args: &[Value],
+    method: &str,
+    min: f64,
+    max: f64,
+) -> Result<usize, RuntimeError> {
+    let digits = to_integer_or_infinity(vm, args.first().copied().unwrap_or_else(Value::undefined));
+    if !(min..=max).contains(&digits) {
+        return Err(vm.current_context.error_range(format!(
+            "{}() argument must be between {} and {}",
+            method, min, max
+        )));
+    }
+    Ok(digits as usize)
+}
+
+/// Number.prototype.toExponential
+/// https://tc39.github.io/ecma262/#sec-number.prototype.toexponential
+pub fn number_prototype_to_exponential(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let x = this_number_value(vm, this, "Number.prototype.toExponential")?;
+    let fraction_digits = args.first().copied().unwrap_or_else(Value::undefined);
+    let f = to_integer_or_infinity(vm, fraction_digits);
+    if !x.is_finite() {
+        return Ok(vm.factory.string(number::to_string(x)));
+    }
+    if !(0.0..=100.0).contains(&f) {
+        return Err(vm
+            .current_context
+            .error_range("toExponential() argument must be between 0 and 100"));
+    }
+    let f = if fraction_digits.is_undefined() {
+        None
+    } else {
+        Some(f as usize)
+    };
+    Ok(vm.factory.string(number::to_exponential(x, f)))
+}
+
+/// Number.prototype.toFixed
+/// https://tc39.github.io/ecma262/#sec-number.prototype.tofixed
+pub fn number_prototype_to_fixed(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let x = this_number_value(vm, this, "Number.prototype.toFixed")?;
+    let f = digits_arg(vm, args, "toFixed", 0.0, 100.0)?;
+    if !x.is_finite() || x.abs() >= 1e21 {
+        return Ok(vm.factory.string(number::to_string(x)));
+    }
+    Ok(vm.factory.string(number::to_fixed(x, f)))
+}
+
+/// Number.prototype.toLocaleString
+pub fn number_prototype_to_locale_string(
+    vm: &mut VM,
+    _args: &[Value],
+    this: Value,
+) -> VMValueResult {
+    let x = this_number_value(vm, this, "Number.prototype.toLocaleString")?;
+    Ok(vm.factory.string(number::to_string(x)))
+}
+
+/// Number.prototype.toPrecision
+/// https://tc39.github.io/ecma262/#sec-number.prototype.toprecision
+pub fn number_prototype_to_precision(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let x = this_number_value(vm, this, "Number.prototype.toPrecision")?;
+    let precision = args.first().copied().unwrap_or_else(Value::undefined);
+    if precision.is_undefined() {
+        return Ok(vm.factory.string(number::to_string(x)));
+    }
+    let p = to_integer_or_infinity(vm, precision);
+    if !x.is_finite() {
+        return Ok(vm.factory.string(number::to_string(x)));
+    }
+    if !(1.0..=100.0).contains(&p) {
+        return Err(vm
+            .current_context
+            .error_range("toPrecision() argument must be between 1 and 100"));
+    }
+    Ok(vm.factory.string(number::to_precision(x, p as usize)))
+}
+
+/// Number.prototype.toString
+/// https://tc39.github.io/ecma262/#sec-number.prototype.tostring
+pub fn number_prototype_to_string(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let x = this_number_value(vm, this, "Number.prototype.toString")?;
+    let radix = match args.first() {
+        Some(radix) if !radix.is_undefined() => digits_arg(vm, args, "toString", 2.0, 36.0)?,
+        _ => 10,
+    };
+    Ok(vm.factory.string(number::to_radix_string(x, radix as u32)))
+}
+
+/// Number.prototype.valueOf
+pub fn number_prototype_value_of(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    let x = this_number_value(vm, this, "Number.prototype.valueOf")?;
+    Ok(Value::Number(x))
+}
@@ -1,33 +1,644 @@
+use crate::builtin::{arg, is_constructor_call, BuiltinFuncTy};
 use crate::vm::{
+    error::RuntimeError,
     jsvalue::value::*,
     vm::{Factory, VMValueResult, VM},
 };
-use rustc_hash::FxHashMap;
 
 pub fn object(factory: &mut Factory) -> Value {
-    factory.generate_builtin_constructor(
+    let obj = factory.generate_builtin_constructor(
         "Object",
         object_constructor,
+        1,
         factory.object_prototypes.object,
-    )
+    );
+
+    let functions: [(&str, BuiltinFuncTy, usize); 21] = [
+        ("assign", object_assign, 2),
+        ("create", object_create, 2),
+        ("defineProperties", object_define_properties, 2),
+        ("defineProperty", object_define_property, 3),
+        ("entries", object_entries, 1),
+        ("freeze", object_freeze, 1),
+        ("fromEntries", object_from_entries, 1),
+        (
+            "getOwnPropertyDescriptor",
+            object_get_own_property_descriptor,
+            2,
+        ),
+        (
+            "getOwnPropertyDescriptors",
+            object_get_own_property_descriptors,
+            1,
+        ),
+        ("getOwnPropertyNames", object_get_own_property_names, 1),
+        ("getOwnPropertySymbols", object_get_own_property_symbols, 1),
+        ("getPrototypeOf", object_get_prototype_of, 1),
+        ("is", object_is, 2),
+        ("isExtensible", object_is_extensible, 1),
+        ("isFrozen", object_is_frozen, 1),
+        ("isSealed", object_is_sealed, 1),
+        ("keys", object_keys, 1),
+        ("preventExtensions", object_prevent_extensions, 1),
+        ("seal", object_seal, 1),
+        ("setPrototypeOf", object_set_prototype_of, 2),
+        ("values", object_values, 1),
+    ];
+    for (name, func, length) in functions.iter() {
+        let func = factory.builtin_function(*name, *func, *length);
+        obj.get_object_info().property.insert(
+            name.to_string(),
+            Property::Data(DataProperty {
+                val: func,
+                writable: true,
+                enumerable: false,
+                configurable: true,
+            }),
+        );
+    }
+
+    obj
+}
+
+/// https://tc39.github.io/ecma262/#sec-object-value
+pub fn object_constructor(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let value = arg(args, 0);
+    if value.is_undefined() || value.is_null() {
+        // The prototype differs from Object.prototype for a subclass.
+        if is_constructor_call(this, vm.factory.object_prototypes.object) {
+            return Ok(this);
+        }
+        return Ok(vm.factory.object(PropertyMap::default()));
+    }
+    to_object(vm, value)
+}
+
+/// Object.assign
+/// https://tc39.github.io/ecma262/#sec-object.assign
+pub fn object_assign(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    let target = to_object(vm, arg(args, 0))?;
+    for source in args.iter().skip(1) {
+        if source.is_undefined() || source.is_null() {
+            continue;
+        }
+        let from = to_object(vm, *source)?;
+        let keys = own_property_keys(vm, from, true)
+            .into_iter()
+            .chain(own_property_symbols(vm, from))
+            .collect::<Vec<Value>>();
+        for key in keys {
+            let prop = from
+                .get_object_info()
+                .get_own_property(&mut vm.factory.memory_allocator, key);
+            if !prop.is_some_and(|prop| prop.is_enumerable()) {
+                continue;
+            }
+            let val = vm.get_property_by_value(from, key)?;
+            vm.set_property_by_value(target, key, val)?;
+        }
+    }
+    Ok(target)
+}
+
+/// Object.create
+pub fn object_create(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    let proto = arg(args, 0);
+    if !proto.is_object() && !proto.is_null() {
+        return Err(vm.current_context.error_type(format!(
+            "Object prototype may only be an Object or null: {}",
+            proto.debug_string(true)
+        )));
+    }
+    let obj = vm.factory.object(PropertyMap::default());
+    obj.get_object_info().prototype = proto;
+    let properties = arg(args, 1);
+    if !properties.is_undefined() {
+        define_properties(vm, obj, properties)?;
+    }
+    Ok(obj)
+}
+
+/// Object.defineProperties
+pub fn object_define_properties(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    let obj = arg(args, 0);
+    if !obj.is_object() {
+        return Err(vm
+            .current_context
+            .error_type("Object.defineProperties called on non-object"));
+    }
+    define_properties(vm, obj, arg(args, 1))?;
+    Ok(obj)
+}
+
+/// Object.defineProperty
+pub fn object_define_property(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    let obj = arg(args, 0);
+    if !obj.is_object() {
+        return Err(vm
+            .current_context
+            .error_type("Object.defineProperty called on non-object"));
+    }
+    let key = to_property_key(vm, arg(args, 1));
+    let desc = to_property_descriptor(vm, arg(args, 2))?;
+    define_property_or_throw(vm, obj, key, desc)?;
+    Ok(obj)
+}
+
+/// Object.entries
+pub fn object_entries(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    let obj = to_object(vm, arg(args, 0))?;
+    let mut entries = vec![];
+    for key in own_property_keys(vm, obj, false) {
+        let val = vm.get_property_by_value(obj, key)?;
+        let entry = vm.factory.array(vec![
+            Property::new_data_simple(key),
+            Property::new_data_simple(val),
+        ]);
+        entries.push(Property::new_data_simple(entry));
+    }
+    Ok(vm.factory.array(entries))
+}
+
+/// Object.freeze
+pub fn object_freeze(_vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    let obj = arg(args, 0);
+    if obj.is_object() {
+        obj.get_object_info().set_integrity_level(true);
+    }
+    Ok(obj)
+}
+
+/// Object.fromEntries
+pub fn object_from_entries(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    let iterable = arg(args, 0);
+    if iterable.is_undefined() || iterable.is_null() {
+        return Err(vm
+            .current_context
+            .error_type(format!("{} is not iterable", iterable.debug_string(true))));
+    }
+    let obj = vm.factory.object(PropertyMap::default());
+    let iter = vm.get_iterator(iterable)?;
+    while let Some(entry) = vm.iterator_step(iter)? {
+        if !entry.is_object() {
+            return Err(vm.current_context.error_type(format!(
+                "Iterator value {} is not an entry object",
+                entry.debug_string(true)
+            )));
+        }
+        let key = vm.get_property_by_value(entry, Value::Number(0.0))?;
+        let key = to_property_key(vm, key);
+        let val = vm.get_property_by_value(entry, Value::Number(1.0))?;
+        let desc = PropertyDescriptor {
+            value: Some(val),
+            writable: Some(true),
+            enumerable: Some(true),
+            configurable: Some(true),
+            ..PropertyDescriptor::default()
+        };
+        obj.get_object_info()
+            .define_own_property(&mut vm.factory.memory_allocator, key, desc);
+    }
+    Ok(obj)
+}
+
+/// Object.getOwnPropertyDescriptor
+pub fn object_get_own_property_descriptor(
+    vm: &mut VM,
+    args: &[Value],
+    _this: Value,
+) -> VMValueResult {
+    let obj = to_object(vm, arg(args, 0))?;
+    let key = to_property_key(vm, arg(args, 1));
+    let prop = obj
+        .get_object_info()
+        .get_own_property(&mut vm.factory.memory_allocator, key);
+    match prop {
+        Some(prop) => Ok(from_property_descriptor(vm, prop)),
+        None => Ok(Value::undefined()),
+    }
+}
+
+/// Object.getOwnPropertyDescriptors
+pub fn object_get_own_property_descriptors(
+    vm: &mut VM,
+    args: &[Value],
+    _this: Value,
+) -> VMValueResult {
+    let obj = to_object(vm, arg(args, 0))?;
+    let descriptors = vm.factory.object(PropertyMap::default());
+    let keys = own_property_keys(vm, obj, false)
+        .into_iter()
+        .chain(own_property_symbols(vm, obj));
+    for key in keys {
+        let prop = obj
+            .get_object_info()
+            .get_own_property(&mut vm.factory.memory_allocator, key);
+        if let Some(prop) = prop {
+            let desc = from_property_descriptor(vm, prop);
+            vm.set_property_by_value(descriptors, key, desc)?;
+        }
+    }
+    Ok(descriptors)
+}
+
+/// Object.getOwnPropertyNames
+pub fn object_get_own_property_names(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    let obj = to_object(vm, arg(args, 0))?;
+    let names = own_property_keys(vm, obj, false)
+        .into_iter()
+        .map(Property::new_data_simple)
+        .collect();
+    Ok(vm.factory.array(names))
+}
+
+/// Object.getOwnPropertySymbols
+pub fn object_get_own_property_symbols(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    let obj = to_object(vm, arg(args, 0))?;
+    let symbols = own_property_symbols(vm, obj)
+        .into_iter()
+        .map(Property::new_data_simple)
+        .collect();
+    Ok(vm.factory.array(symbols))
+}
+
+/// Object.getPrototypeOf
+pub fn object_get_prototype_of(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    let obj = to_object(vm, arg(args, 0))?;
+    Ok(obj.get_object_info().prototype)
+}
+
+/// Object.is
+pub fn object_is(_vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    Ok(Value::bool(arg(args, 0).same_value(arg(args, 1))))
+}
+
+/// Object.isExtensible
+pub fn object_is_extensible(_vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    let obj = arg(args, 0);
+    Ok(Value::bool(
+        obj.is_object() && obj.get_object_info().extensible,
+    ))
+}
+
+/// Object.isFrozen
+pub fn object_is_frozen(_vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    let obj = arg(args, 0);
+    Ok(Value::bool(
+        !obj.is_object() || obj.get_object_info().test_integrity_level(true),
+    ))
+}
+
+/// Object.isSealed
+pub fn object_is_sealed(_vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    let obj = arg(args, 0);
+    Ok(Value::bool(
+        !obj.is_object() || obj.get_object_info().test_integrity_level(false),
+    ))
+}
+
+/// Object.keys
+pub fn object_keys(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    let obj = to_object(vm, arg(args, 0))?;
+    let keys = own_property_keys(vm, obj, true)
+        .into_iter()
+        .map(Property::new_data_simple)
+        .collect();
+    Ok(vm.factory.array(keys))
+}
+
+/// Object.preventExtensions
+pub fn object_prevent_extensions(_vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    let obj = arg(args, 0);
+    if obj.is_object() {
+        obj.get_object_info().extensible = false;
+    }
+    Ok(obj)
+}
+
+/// Object.seal
+pub fn object_seal(_vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    let obj = arg(args, 0);
+    if obj.is_object() {
+        obj.get_object_info().set_integrity_level(false);
+    }
+    Ok(obj)
+}
+
+/// Object.setPrototypeOf
+/// https://tc39.github.io/ecma262/#sec-ordinarysetprototypeof
+pub fn object_set_prototype_of(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    let obj = arg(args, 0);
+    let proto = arg(args, 1);
+    if obj.is_undefined() || obj.is_null() {
+        return Err(vm
+            .current_context
+            .error_type("Object.setPrototypeOf called on null or undefined"));
+    }
+    if !proto.is_object() && !proto.is_null() {
+        return Err(vm.current_context.error_type(format!(
+            "Object prototype may only be an Object or null: {}",
+            proto.debug_string(true)
+        )));
+    }
+    if !obj.is_object() {
+        return Ok(obj);
+    }
+
+    let mut obj_info = obj.get_object_info();
+    if obj_info.prototype == proto {
+        return Ok(obj);
+    }
+    if !obj_info.extensible {
+        return Err(vm
+            .current_context
+            .error_type(format!("{} is not extensible", obj.debug_string(true))));
+    }
+    let mut p = proto;
+    while p.is_object() {
+        if p == obj {
+            return Err(vm.current_context.error_type("Cyclic __proto__ value"));
+        }
+        p = p.get_object_info().prototype;
+    }
+    obj_info.prototype = proto;
+    Ok(obj)
+}
+
+/// Object.values
+pub fn object_values(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    let obj = to_object(vm, arg(args, 0))?;
+    let mut values = vec![];
+    for key in own_property_keys(vm, obj, true) {
+        let val = vm.get_property_by_value(obj, key)?;
+        values.push(Property::new_data_simple(val));
+    }
+    Ok(vm.factory.array(values))
+}
+
+/// Object.prototype.hasOwnProperty
+pub fn object_prototype_has_own_property(
+    vm: &mut VM,
+    args: &[Value],
+    this: Value,
+) -> VMValueResult {
+    let key = to_property_key(vm, arg(args, 0));
+    let obj = to_object(vm, this)?;
+    let prop = obj
+        .get_object_info()
+        .get_own_property(&mut vm.factory.memory_allocator, key);
+    Ok(Value::bool(prop.is_some()))
+}
+
+/// Object.prototype.isPrototypeOf
+pub fn object_prototype_is_prototype_of(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let val = arg(args, 0);
+    if !val.is_object() {
+        return Ok(Value::bool(false));
+    }
+    let obj = to_object(vm, this)?;
+    let mut proto = val.get_object_info().prototype;
+    while proto.is_object() {
+        if proto == obj {
+            return Ok(Value::bool(true));
+        }
+        proto = proto.get_object_info().prototype;
+    }
+    Ok(Value::bool(false))
+}
+
+/// Object.prototype.propertyIsEnumerable
+pub fn object_prototype_property_is_enumerable(
+    vm: &mut VM,
+    args: &[Value],
+    this: Value,
+) -> VMValueResult {
+    let key = to_property_key(vm, arg(args, 0));
+    let obj = to_object(vm, this)?;
+    let prop = obj
+        .get_object_info()
+        .get_own_property(&mut vm.factory.memory_allocator, key);
+    Ok(Value::bool(prop.is_some_and(|prop| prop.is_enumerable())))
+}
+
+/// Object.prototype.toString
+/// https://tc39.github.io/ecma262/#sec-object.prototype.tostring
+pub fn object_prototype_to_string(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    if this.is_undefined() {
+        return Ok(vm.factory.string("[object Undefined]"));
+    }
+    if this.is_null() {
+        return Ok(vm.factory.string("[object Null]"));
+    }
+    // TODO: @@toStringTag
+    let tag = match this {
+        Value::Bool(_) => "Boolean",
+        Value::Number(_) => "Number",
+        Value::String(_) => "String",
+        Value::Object(_) => match this.get_object_info().kind {
+            ObjectKind::Array(_) => "Array",
+            ObjectKind::Function(_) => "Function",
+            ObjectKind::Error(_) => "Error",
+            ObjectKind::Date(_) => "Date",
+            ObjectKind::RegExp(_) => "RegExp",
+            ObjectKind::Number(_) => "Number",
+            ObjectKind::String(_) => "String",
+            // These come from @@toStringTag in the specification.
+            ObjectKind::Map(_) => "Map",
+            ObjectKind::Set(_) => "Set",
+            ObjectKind::WeakMap(_) => "WeakMap",
+            ObjectKind::WeakSet(_) => "WeakSet",
+            _ => "Object",
+        },
+        _ => "Object",
+    };
+    Ok(vm.factory.string(format!("[object {}]", tag)))
+}
+
+/// Object.prototype.valueOf
+pub fn object_prototype_value_of(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    to_object(vm, this)
 }
 
-pub fn object_constructor(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
-    if args.len() == 0 {
-        let empty_obj = vm.factory.object(FxHashMap::default());
-        vm.current_context.stack.push(empty_obj.into());
-        return Ok(empty_obj);
+/// https://tc39.github.io/ecma262/#sec-toobject
+pub fn to_object(vm: &mut VM, val: Value) -> VMValueResult {
+    match val {
+        Value::Object(_) => Ok(val),
+        Value::Number(n) => {
+            let prototype = vm.factory.object_prototypes.number;
+            Ok(vm.factory.number_object(prototype, n))
+        }
+        Value::String(_) => {
+            let prototype = vm.factory.object_prototypes.string;
+            Ok(vm.factory.string_object(prototype, val))
+        }
+        // TODO: Boolean objects
+        Value::Bool(_) => Ok(vm.factory.object(PropertyMap::default())),
+        _ => Err(vm
+            .current_context
+            .error_type("Cannot convert undefined or null to object")),
+    }
+}
+
+/// A symbol as is, or ToString() of any other value.
+/// https://tc39.github.io/ecma262/#sec-topropertykey
+fn to_property_key(vm: &mut VM, val: Value) -> Value {
+    if val.is_symbol() {
+        val
+    } else {
+        vm.factory.string(val.to_string())
+    }
+}
+
+/// The own string keys of ``obj``, which are only the enumerable ones if ``only_enumerable``.
+fn own_property_keys(vm: &mut VM, obj: Value, only_enumerable: bool) -> Vec<Value> {
+    let obj_info = obj.get_object_info();
+    let mut keys = obj_info.own_string_keys();
+    // The length of an array is not in its property map.
+    if let ObjectKind::Array(_) = obj_info.kind {
+        let elems_len = keys.iter().take_while(|(key, _)| is_index(key)).count();
+        keys.insert(elems_len, ("length".to_string(), false));
+    }
+    keys.into_iter()
+        .filter(|(_, enumerable)| *enumerable || !only_enumerable)
+        .map(|(key, _)| vm.factory.string(key))
+        .collect()
+}
+
+/// The own symbol keys of ``obj`` in the order the symbols were created.
+fn own_property_symbols(vm: &mut VM, obj: Value) -> Vec<Value> {
+    let mut ids = obj
+        .get_object_info()
+        .sym_property
+        .keys()
+        .copied()
+        .collect::<Vec<usize>>();
+    ids.sort_unstable();
+    ids.iter()
+        .filter_map(|id| vm.factory.symbol_registry.get(id).copied())
+        .collect()
+}
+
+fn is_index(key: &str) -> bool {
+    match key.parse::<u32>() {
+        Ok(idx) => idx != u32::MAX && idx.to_string() == key,
+        Err(_) => false,
+    }
+}
+
+/// https://tc39.github.io/ecma262/#sec-topropertydescriptor
+fn to_property_descriptor(vm: &mut VM, obj: Value) -> Result<PropertyDescriptor, RuntimeError> {
+    if !obj.is_object() {
+        return Err(vm.current_context.error_type(format!(
+            "Property description must be an object: {}",
+            obj.debug_string(true)
+        )));
     }
 
-    match &args[0] {
-        Value::Other(NULL) | Value::Other(UNDEFINED) => {
-            let empty_obj = vm.factory.object(FxHashMap::default());
-            Ok(empty_obj)
+    let field = |vm: &mut VM, name: &str| -> Result<Option<Value>, RuntimeError> {
+        let key = vm.factory.string(name);
+        if !obj
+            .get_object_info()
+            .has_property_by_value(&mut vm.factory.memory_allocator, key)
+        {
+            return Ok(None);
         }
-        Value::Other(EMPTY) => unreachable!(),
-        _ => {
-            // TODO: Follow the specification
-            Ok(args[0])
+        vm.get_property_by_value(obj, key).map(Some)
+    };
+
+    let desc = PropertyDescriptor {
+        enumerable: field(vm, "enumerable")?.map(|val| val.to_boolean()),
+        configurable: field(vm, "configurable")?.map(|val| val.to_boolean()),
+        value: field(vm, "value")?,
+        writable: field(vm, "writable")?.map(|val| val.to_boolean()),
+        get: field(vm, "get")?,
+        set: field(vm, "set")?,
+    };
+
+    for func in [desc.get, desc.set].iter().flatten() {
+        if !func.is_undefined() && !func.is_function_object() {
+            return Err(vm.current_context.error_type(format!(
+                "Getter or setter must be a function: {}",
+                func.debug_string(true)
+            )));
         }
     }
+    if (desc.get.is_some() || desc.set.is_some())
+        && (desc.value.is_some() || desc.writable.is_some())
+    {
+        return Err(vm.current_context.error_type(
+            "Invalid property descriptor. Cannot both specify accessors and a value or writable attribute",
+        ));
+    }
+    Ok(desc)
+}
+
+/// https://tc39.github.io/ecma262/#sec-frompropertydescriptor
+fn from_property_descriptor(vm: &mut VM, prop: Property) -> Value {
+    match prop {
+        Property::Data(DataProperty {
+            val,
+            writable,
+            enumerable,
+            configurable,
+        }) => make_normal_object!(vm.factory,
+            value        => true, true, true: val,
+            writable     => true, true, true: Value::bool(writable),
+            enumerable   => true, true, true: Value::bool(enumerable),
+            configurable => true, true, true: Value::bool(configurable)
+        ),
+        Property::Accessor(AccessorProperty {
+            get,
+            set,
+            enumerable,
+            configurable,
+        }) => make_normal_object!(vm.factory,
+            get          => true, true, true: get,
+            set          => true, true, true: set,
+            enumerable   => true, true, true: Value::bool(enumerable),
+            configurable => true, true, true: Value::bool(configurable)
+        ),
+    }
+}
+
+fn define_property_or_throw(
+    vm: &mut VM,
+    obj: Value,
+    key: Value,
+    desc: PropertyDescriptor,
+) -> Result<(), RuntimeError> {
+    let defined =
+        obj.get_object_info()
+            .define_own_property(&mut vm.factory.memory_allocator, key, desc);
+    if !defined {
+        return Err(vm.current_context.error_type(format!(
+            "Cannot redefine property: {}",
+            key.debug_string(false)
+        )));
+    }
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-objectdefineproperties
+fn define_properties(vm: &mut VM, obj: Value, properties: Value) -> Result<(), RuntimeError> {
+    let props = to_object(vm, properties)?;
+    let keys = own_property_keys(vm, props, true)
+        .into_iter()
+        .chain(own_property_symbols(vm, props))
+        .collect::<Vec<Value>>();
+    // All the descriptors are validated before any property is defined.
+    let mut descriptors = vec![];
+    for key in keys {
+        let prop = props
+            .get_object_info()
+            .get_own_property(&mut vm.factory.memory_allocator, key);
+        if !prop.is_some_and(|prop| prop.is_enumerable()) {
+            continue;
+        }
+        let desc_obj = vm.get_property_by_value(props, key)?;
+        descriptors.push((key, to_property_descriptor(vm, desc_obj)?));
+    }
+    for (key, desc) in descriptors {
+        define_property_or_throw(vm, obj, key, desc)?;
+    }
+    Ok(())
 }
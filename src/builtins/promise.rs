@@ -0,0 +1,654 @@
+use crate::builtin::{arg, BuiltinFuncTy};
+use crate::builtins::error::define_non_enumerable;
+use crate::vm::{
+    jsvalue::{
+        object::{ObjectInfo, ObjectKind},
+        value::*,
+    },
+    vm::{Factory, VMResult, VMValueResult, VM},
+};
+
+pub fn promise(factory: &mut Factory) -> Value {
+    let obj = factory.generate_builtin_constructor(
+        "Promise",
+        promise_constructor,
+        1,
+        factory.object_prototypes.promise,
+    );
+
+    // Promise.all
+    obj.set_property("all", factory.builtin_function("all", promise_all, 1));
+    // Promise.allSettled
+    obj.set_property(
+        "allSettled",
+        factory.builtin_function("allSettled", promise_all_settled, 1),
+    );
+    // Promise.any
+    obj.set_property("any", factory.builtin_function("any", promise_any, 1));
+    // Promise.race
+    obj.set_property("race", factory.builtin_function("race", promise_race, 1));
+    // Promise.reject
+    obj.set_property(
+        "reject",
+        factory.builtin_function("reject", promise_reject, 1),
+    );
+    // Promise.resolve
+    obj.set_property(
+        "resolve",
+        factory.builtin_function("resolve", promise_resolve_, 1),
+    );
+    obj
+}
+
+/// https://tc39.github.io/ecma262/#sec-promise-executor
+pub fn promise_constructor(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    let executor = arg(args, 0);
+    if !executor.is_function_object() {
+        return Err(vm.current_context.error_type(format!(
+            "Promise resolver {} is not a function",
+            executor.debug_string(true)
+        )));
+    }
+
+    let promise = vm.factory.promise(vm.factory.object_prototypes.promise);
+    let (resolve, reject) = create_resolving_functions(vm, promise);
+    if let Err(err) = vm.call_function(executor, &[resolve, reject], Value::undefined()) {
+        let reason = err.to_value(vm);
+        vm.call_function(reject, &[reason], Value::undefined())?;
+    }
+    Ok(promise)
+}
+
+/// Promise.prototype.then
+/// https://tc39.github.io/ecma262/#sec-promise.prototype.then
+pub fn promise_prototype_then(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    if promise_info(this).is_none() {
+        return Err(vm.current_context.error_type(format!(
+            "Method Promise.prototype.then called on incompatible receiver {}",
+            this.debug_string(true)
+        )));
+    }
+    let capability = vm.factory.promise(vm.factory.object_prototypes.promise);
+    perform_promise_then(vm, this, arg(args, 0), arg(args, 1), Some(capability));
+    Ok(capability)
+}
+
+/// Promise.prototype.catch
+/// https://tc39.github.io/ecma262/#sec-promise.prototype.catch
+pub fn promise_prototype_catch(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let then = get(vm, this, "then")?;
+    vm.call_function(then, &[Value::undefined(), arg(args, 0)], this)
+}
+
+/// Promise.prototype.finally
+/// https://tc39.github.io/ecma262/#sec-promise.prototype.finally
+pub fn promise_prototype_finally(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    if !this.is_object() {
+        return Err(vm.current_context.error_type(format!(
+            "Method Promise.prototype.finally called on incompatible receiver {}",
+            this.debug_string(true)
+        )));
+    }
+    let on_finally = arg(args, 0);
+    let (then_finally, catch_finally) = if on_finally.is_function_object() {
+        (
+            closure(vm, then_finally_function, 1, vec![on_finally]),
+            closure(vm, catch_finally_function, 1, vec![on_finally]),
+        )
+    } else {
+        (on_finally, on_finally)
+    };
+    let then = get(vm, this, "then")?;
+    vm.call_function(then, &[then_finally, catch_finally], this)
+}
+
+/// https://tc39.github.io/ecma262/#sec-thenfinallyfunctions
+fn then_finally_function(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    let value_thunk = closure(vm, return_value, 0, vec![arg(args, 1)]);
+    call_on_finally(vm, args[0], value_thunk)
+}
+
+/// https://tc39.github.io/ecma262/#sec-catchfinallyfunctions
+fn catch_finally_function(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    let thrower = closure(vm, throw_value, 0, vec![arg(args, 1)]);
+    call_on_finally(vm, args[0], thrower)
+}
+
+/// Calls ``on_finally``, then settles with the original result once its result settles.
+fn call_on_finally(vm: &mut VM, on_finally: Value, on_settled: Value) -> VMValueResult {
+    let result = vm.call_function(on_finally, &[], Value::undefined())?;
+    let constructor = promise_constructor_of(vm);
+    let promise = promise_resolve(vm, constructor, result)?;
+    let then = get(vm, promise, "then")?;
+    vm.call_function(then, &[on_settled], promise)
+}
+
+fn return_value(_vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    Ok(args[0])
+}
+
+fn throw_value(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    Err(vm.current_context.error_exception(args[0]))
+}
+
+/// Promise.resolve
+/// https://tc39.github.io/ecma262/#sec-promise.resolve
+pub fn promise_resolve_(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    if !this.is_object() {
+        return Err(vm
+            .current_context
+            .error_type("PromiseResolve called on non-object"));
+    }
+    promise_resolve(vm, this, arg(args, 0))
+}
+
+/// Promise.reject
+/// https://tc39.github.io/ecma262/#sec-promise.reject
+pub fn promise_reject(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let promise = new_promise(vm, this);
+    reject_promise(vm, promise, arg(args, 0));
+    Ok(promise)
+}
+
+/// Promise.all
+/// https://tc39.github.io/ecma262/#sec-promise.all
+pub fn promise_all(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    perform_combinator(vm, this, arg(args, 0), Combinator::All)
+}
+
+/// Promise.allSettled
+/// https://tc39.github.io/proposal-promise-allSettled/#sec-promise.allsettled
+pub fn promise_all_settled(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    perform_combinator(vm, this, arg(args, 0), Combinator::AllSettled)
+}
+
+/// Promise.any
+/// https://tc39.github.io/proposal-promise-any/#sec-promise.any
+pub fn promise_any(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    perform_combinator(vm, this, arg(args, 0), Combinator::Any)
+}
+
+/// Promise.race
+/// https://tc39.github.io/ecma262/#sec-promise.race
+pub fn promise_race(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    perform_combinator(vm, this, arg(args, 0), Combinator::Race)
+}
+
+#[derive(Clone, Debug, PartialEq, Copy)]
+enum Combinator {
+    All,
+    AllSettled,
+    Any,
+    Race,
+}
+
+/// Subscribes to each element of ``iterable``. An abrupt completion rejects the returned promise.
+fn perform_combinator(
+    vm: &mut VM,
+    constructor: Value,
+    iterable: Value,
+    kind: Combinator,
+) -> VMValueResult {
+    let promise = new_promise(vm, constructor);
+    let (resolve, reject) = create_resolving_functions(vm, promise);
+    if let Err(err) = subscribe_elements(vm, constructor, iterable, kind, resolve, reject) {
+        let reason = err.to_value(vm);
+        vm.call_function(reject, &[reason], Value::undefined())?;
+    }
+    Ok(promise)
+}
+
+fn subscribe_elements(
+    vm: &mut VM,
+    constructor: Value,
+    iterable: Value,
+    kind: Combinator,
+    resolve: Value,
+    reject: Value,
+) -> VMResult {
+    // Results of the elements, and the number of elements yet to settle. The count starts at
+    // one so that the promise does not settle before all the elements are subscribed.
+    let values = vm.factory.array(vec![]);
+    let state =
+        make_normal_object!(vm.factory, remaining => true, false, false: Value::Number(1.0));
+
+    let iter = vm.get_iterator(iterable)?;
+    let resolve_element = get(vm, constructor, "resolve")?;
+    let mut index = 0;
+    while let Some(next_value) = vm.iterator_step(iter)? {
+        let next_promise = vm.call_function(resolve_element, &[next_value], constructor)?;
+        let (on_fulfilled, on_rejected) = if kind == Combinator::Race {
+            (resolve, reject)
+        } else {
+            values.as_array_mut().set_element(index, Value::undefined());
+            let remaining = state.get_property("remaining").into_number();
+            state.set_property("remaining", Value::Number(remaining + 1.0));
+
+            let already_called =
+                make_normal_object!(vm.factory, value => true, false, false: Value::bool(false));
+            let index = Value::Number(index as f64);
+            match kind {
+                Combinator::All => (
+                    closure(
+                        vm,
+                        promise_all_resolve_element,
+                        1,
+                        vec![index, values, state, already_called, resolve],
+                    ),
+                    reject,
+                ),
+                Combinator::AllSettled => (
+                    closure(
+                        vm,
+                        promise_all_settled_resolve_element,
+                        1,
+                        vec![index, values, state, already_called, resolve],
+                    ),
+                    closure(
+                        vm,
+                        promise_all_settled_reject_element,
+                        1,
+                        vec![index, values, state, already_called, resolve],
+                    ),
+                ),
+                _ => (
+                    resolve,
+                    closure(
+                        vm,
+                        promise_any_reject_element,
+                        1,
+                        vec![index, values, state, already_called, reject],
+                    ),
+                ),
+            }
+        };
+        let then = get(vm, next_promise, "then")?;
+        vm.call_function(then, &[on_fulfilled, on_rejected], next_promise)?;
+        index += 1;
+    }
+
+    if kind != Combinator::Race {
+        settle_element(
+            vm,
+            kind,
+            values,
+            state,
+            resolve_or_reject(kind, resolve, reject),
+        )?;
+    }
+    Ok(())
+}
+
+fn resolve_or_reject(kind: Combinator, resolve: Value, reject: Value) -> Value {
+    if kind == Combinator::Any {
+        reject
+    } else {
+        resolve
+    }
+}
+
+/// https://tc39.github.io/ecma262/#sec-promise.all-resolve-element-functions
+fn promise_all_resolve_element(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    store_element(vm, args, arg(args, 5), Combinator::All)
+}
+
+fn promise_all_settled_resolve_element(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    let status = vm.factory.string("fulfilled");
+    let value = arg(args, 5);
+    let result = make_normal_object!(
+        vm.factory,
+        status => true, true, true: status,
+        value  => true, true, true: value
+    );
+    store_element(vm, args, result, Combinator::AllSettled)
+}
+
+fn promise_all_settled_reject_element(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    let status = vm.factory.string("rejected");
+    let reason = arg(args, 5);
+    let result = make_normal_object!(
+        vm.factory,
+        status => true, true, true: status,
+        reason => true, true, true: reason
+    );
+    store_element(vm, args, result, Combinator::AllSettled)
+}
+
+fn promise_any_reject_element(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    store_element(vm, args, arg(args, 5), Combinator::Any)
+}
+
+/// Stores the result of an element. ``args`` are the captures of the element function
+/// (index, values, state, already called record, and resolve or reject function) and its
+/// argument.
+fn store_element(vm: &mut VM, args: &[Value], result: Value, kind: Combinator) -> VMValueResult {
+    let (index, values, state, already_called, settle) =
+        (args[0], args[1], args[2], args[3], args[4]);
+    if already_resolved(already_called) {
+        return Ok(Value::undefined());
+    }
+    values
+        .as_array_mut()
+        .set_element(index.into_number() as usize, result);
+    settle_element(vm, kind, values, state, settle)?;
+    Ok(Value::undefined())
+}
+
+/// Counts down the elements yet to settle, and settles the promise when it reaches zero.
+fn settle_element(
+    vm: &mut VM,
+    kind: Combinator,
+    values: Value,
+    state: Value,
+    settle: Value,
+) -> VMResult {
+    let remaining = state.get_property("remaining").into_number() - 1.0;
+    state.set_property("remaining", Value::Number(remaining));
+    if remaining != 0.0 {
+        return Ok(());
+    }
+
+    let result = if kind == Combinator::Any {
+        let prototype = vm.factory.object_prototypes.aggregate_error;
+        let error = vm
+            .factory
+            .error_object(prototype, Some("All promises were rejected".to_string()));
+        vm.capture_stack_trace(error);
+        define_non_enumerable(error, "errors", values);
+        error
+    } else {
+        values
+    };
+    vm.call_function(settle, &[result], Value::undefined())?;
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-createresolvingfunctions
+pub fn create_resolving_functions(vm: &mut VM, promise: Value) -> (Value, Value) {
+    let already_resolved =
+        make_normal_object!(vm.factory, value => true, false, false: Value::bool(false));
+    let resolve = closure(
+        vm,
+        promise_resolve_function,
+        1,
+        vec![promise, already_resolved],
+    );
+    let reject = closure(
+        vm,
+        promise_reject_function,
+        1,
+        vec![promise, already_resolved],
+    );
+    (resolve, reject)
+}
+
+/// https://tc39.github.io/ecma262/#sec-promise-resolve-functions
+fn promise_resolve_function(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    if !already_resolved(args[1]) {
+        resolve_promise(vm, args[0], arg(args, 2));
+    }
+    Ok(Value::undefined())
+}
+
+/// https://tc39.github.io/ecma262/#sec-promise-reject-functions
+fn promise_reject_function(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    if !already_resolved(args[1]) {
+        reject_promise(vm, args[0], arg(args, 2));
+    }
+    Ok(Value::undefined())
+}
+
+/// Returns true if ``record`` has already been resolved, and marks it as resolved.
+fn already_resolved(record: Value) -> bool {
+    let resolved = record.get_property("value").to_boolean();
+    record.set_property("value", Value::bool(true));
+    resolved
+}
+
+/// Resolves ``promise`` with ``resolution``. A thenable is followed in a job.
+/// https://tc39.github.io/ecma262/#sec-promise-resolve-functions (steps 7-15)
+pub fn resolve_promise(vm: &mut VM, promise: Value, resolution: Value) {
+    if resolution == promise {
+        let reason = vm
+            .current_context
+            .error_type("Chaining cycle detected for promise")
+            .to_value(vm);
+        return reject_promise(vm, promise, reason);
+    }
+
+    if !resolution.is_object() {
+        return fulfill_promise(vm, promise, resolution);
+    }
+
+    let then = match get(vm, resolution, "then") {
+        Ok(then) => then,
+        Err(err) => {
+            let reason = err.to_value(vm);
+            return reject_promise(vm, promise, reason);
+        }
+    };
+
+    if !then.is_function_object() {
+        return fulfill_promise(vm, promise, resolution);
+    }
+
+    vm.job_queue.jobs.push_back(Job::PromiseResolveThenable {
+        promise,
+        thenable: resolution,
+        then,
+    });
+}
+
+/// https://tc39.github.io/ecma262/#sec-fulfillpromise
+pub fn fulfill_promise(vm: &mut VM, promise: Value, value: Value) {
+    settle_promise(vm, promise, PromiseState::Fulfilled, value)
+}
+
+/// https://tc39.github.io/ecma262/#sec-rejectpromise
+pub fn reject_promise(vm: &mut VM, promise: Value, reason: Value) {
+    settle_promise(vm, promise, PromiseState::Rejected, reason);
+    if !promise_info(promise).unwrap().is_handled {
+        // HostPromiseRejectionTracker(promise, "reject")
+        let error = vm.current_context.error_exception(reason);
+        vm.job_queue.rejected_promises.push((promise, error));
+    }
+}
+
+/// https://tc39.github.io/ecma262/#sec-triggerpromisereactions
+fn settle_promise(vm: &mut VM, promise: Value, state: PromiseState, result: Value) {
+    let info = promise_info(promise).unwrap();
+    info.state = state;
+    info.result = result;
+    let fulfill_reactions = std::mem::take(&mut info.fulfill_reactions);
+    let reject_reactions = std::mem::take(&mut info.reject_reactions);
+    let reactions = if state == PromiseState::Fulfilled {
+        fulfill_reactions
+    } else {
+        reject_reactions
+    };
+    for reaction in reactions {
+        vm.job_queue.jobs.push_back(Job::PromiseReaction {
+            reaction,
+            argument: result,
+        });
+    }
+}
+
+/// ``capability`` is None when the result of the handlers is not needed, e.g. for ``await``.
+/// https://tc39.github.io/ecma262/#sec-performpromisethen
+pub fn perform_promise_then(
+    vm: &mut VM,
+    promise: Value,
+    on_fulfilled: Value,
+    on_rejected: Value,
+    capability: Option<Value>,
+) {
+    let handler = |handler: Value| {
+        if handler.is_function_object() {
+            handler
+        } else {
+            Value::undefined()
+        }
+    };
+    let fulfill_reaction = PromiseReaction {
+        capability,
+        kind: ReactionKind::Fulfill,
+        handler: handler(on_fulfilled),
+    };
+    let reject_reaction = PromiseReaction {
+        capability,
+        kind: ReactionKind::Reject,
+        handler: handler(on_rejected),
+    };
+
+    let info = promise_info(promise).unwrap();
+    match info.state {
+        PromiseState::Pending => {
+            info.fulfill_reactions.push(fulfill_reaction);
+            info.reject_reactions.push(reject_reaction);
+        }
+        PromiseState::Fulfilled => vm.job_queue.jobs.push_back(Job::PromiseReaction {
+            reaction: fulfill_reaction,
+            argument: info.result,
+        }),
+        PromiseState::Rejected => {
+            if !info.is_handled {
+                // HostPromiseRejectionTracker(promise, "handle")
+                vm.job_queue
+                    .rejected_promises
+                    .retain(|(rejected, _)| *rejected != promise);
+            }
+            vm.job_queue.jobs.push_back(Job::PromiseReaction {
+                reaction: reject_reaction,
+                argument: info.result,
+            })
+        }
+    }
+    info.is_handled = true;
+}
+
+/// Runs a job taken from the job queue.
+pub fn run_job(vm: &mut VM, job: Job) -> VMResult {
+    match job {
+        Job::PromiseReaction { reaction, argument } => {
+            let result = if reaction.handler.is_undefined() {
+                match reaction.kind {
+                    ReactionKind::Fulfill => Ok(argument),
+                    ReactionKind::Reject => Err(argument),
+                }
+            } else {
+                vm.call_function(reaction.handler, &[argument], Value::undefined())
+                    .map_err(|err| err.to_value(vm))
+            };
+            if let Some(capability) = reaction.capability {
+                match result {
+                    Ok(value) => resolve_promise(vm, capability, value),
+                    Err(reason) => reject_promise(vm, capability, reason),
+                }
+            }
+        }
+        Job::PromiseResolveThenable {
+            promise,
+            thenable,
+            then,
+        } => {
+            let (resolve, reject) = create_resolving_functions(vm, promise);
+            if let Err(err) = vm.call_function(then, &[resolve, reject], thenable) {
+                let reason = err.to_value(vm);
+                vm.call_function(reject, &[reason], Value::undefined())?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Resumes the async function suspended in ``state`` when ``value`` settles.
+/// https://tc39.github.io/ecma262/#await
+pub fn await_value(vm: &mut VM, state: Value, value: Value) {
+    let constructor = promise_constructor_of(vm);
+    let promise = match promise_resolve(vm, constructor, value) {
+        Ok(promise) => promise,
+        Err(err) => {
+            let promise = vm.factory.promise(vm.factory.object_prototypes.promise);
+            let reason = err.to_value(vm);
+            reject_promise(vm, promise, reason);
+            promise
+        }
+    };
+    let on_fulfilled = closure(vm, await_fulfilled_function, 1, vec![state]);
+    let on_rejected = closure(vm, await_rejected_function, 1, vec![state]);
+    perform_promise_then(vm, promise, on_fulfilled, on_rejected, None);
+}
+
+fn await_fulfilled_function(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    vm.resume_async_function(args[0], arg(args, 1), false);
+    Ok(Value::undefined())
+}
+
+fn await_rejected_function(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    vm.resume_async_function(args[0], arg(args, 1), true);
+    Ok(Value::undefined())
+}
+
+/// https://tc39.github.io/ecma262/#sec-promise-resolve
+pub fn promise_resolve(vm: &mut VM, constructor: Value, x: Value) -> VMValueResult {
+    if promise_info(x).is_some() {
+        let x_constructor = get(vm, x, "constructor")?;
+        if x_constructor == constructor {
+            return Ok(x);
+        }
+    }
+    let promise = new_promise(vm, constructor);
+    resolve_promise(vm, promise, x);
+    Ok(promise)
+}
+
+/// A pending promise whose prototype is taken from ``constructor``, which may be a subclass.
+fn new_promise(vm: &mut VM, constructor: Value) -> Value {
+    let prototype = if constructor.is_object() {
+        constructor.get_property("prototype")
+    } else {
+        Value::undefined()
+    };
+    let prototype = if prototype.is_object() {
+        prototype
+    } else {
+        vm.factory.object_prototypes.promise
+    };
+    vm.factory.promise(prototype)
+}
+
+/// %Promise%
+fn promise_constructor_of(vm: &VM) -> Value {
+    vm.factory
+        .object_prototypes
+        .promise
+        .get_property("constructor")
+}
+
+/// Creates a built-in function that receives ``captures`` before its own ``length`` arguments.
+fn closure(vm: &mut VM, func: BuiltinFuncTy, length: usize, captures: Vec<Value>) -> Value {
+    let target = vm
+        .factory
+        .builtin_function("", func, length + captures.len());
+    vm.factory
+        .bound_function(target, Value::undefined(), captures)
+}
+
+fn promise_info<'a>(val: Value) -> Option<&'a mut PromiseInfo> {
+    match val {
+        Value::Object(info) => {
+            let info: &'a mut ObjectInfo = unsafe { &mut *info };
+            match info.kind {
+                ObjectKind::Promise(ref mut info) => Some(info),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+fn get(vm: &mut VM, obj: Value, key: &str) -> VMValueResult {
+    let key = vm.factory.string(key);
+    vm.get_property_by_value(obj, key)
+}
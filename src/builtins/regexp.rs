@@ -0,0 +1,643 @@
+use crate::builtins::iterator::create_iter_result_object;
+use crate::builtins::string::get_substitution;
+use crate::regexp::{Flags, Regex};
+use crate::vm::{
+    error::RuntimeError,
+    jsvalue::{
+        object::{ObjectInfo, ObjectKind, Property, PropertyMap},
+        regexp::RegExpInfo,
+        value::Value,
+    },
+    vm::{Factory, VMValueResult, VM},
+};
+use rustc_hash::FxHashMap;
+use std::rc::Rc;
+
+pub fn regexp(factory: &mut Factory) -> Value {
+    factory.generate_builtin_constructor(
+        "RegExp",
+        regexp_constructor,
+        2,
+        factory.object_prototypes.regexp,
+    )
+}
+
+/// https://tc39.github.io/ecma262/#sec-regexp-pattern-flags
+pub fn regexp_constructor(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    let pattern = args.first().copied().unwrap_or(Value::undefined());
+    let flags = args.get(1).copied().unwrap_or(Value::undefined());
+    let (pattern, flags) = match regexp_info(pattern) {
+        Some(info) if flags.is_undefined() => (info.source, info.flags),
+        Some(info) => (info.source, flags.to_string()),
+        None => (to_string_or_empty(pattern), to_string_or_empty(flags)),
+    };
+    regexp_create(vm, pattern, flags)
+}
+
+/// https://tc39.github.io/ecma262/#sec-regexpcreate
+pub fn regexp_create(vm: &mut VM, pattern: String, flags: String) -> VMValueResult {
+    let regex = match Regex::new(pattern.as_str(), flags.as_str()) {
+        Ok(regex) => regex,
+        Err(msg) => {
+            return Err(vm.current_context.error_syntax(format!(
+                "Invalid regular expression: /{}/: {}",
+                pattern, msg
+            )))
+        }
+    };
+    let regexp = vm.factory.regexp(RegExpInfo {
+        source: pattern,
+        flags,
+        regex: Rc::new(regex),
+    });
+    Ok(regexp)
+}
+
+/// RegExp.prototype.exec
+pub fn regexp_prototype_exec(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let info = match regexp_info(this) {
+        Some(info) => info,
+        None => {
+            return Err(vm
+                .current_context
+                .error_type("RegExp.prototype.exec called on incompatible receiver"))
+        }
+    };
+    let string = arg_to_string(vm, args, 0);
+    regexp_builtin_exec(vm, this, &info.regex, string)
+}
+
+/// RegExp.prototype.test
+pub fn regexp_prototype_test(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    require_object(vm, this, "RegExp.prototype.test")?;
+    let string = arg_to_string(vm, args, 0);
+    let result = regexp_exec(vm, this, string)?;
+    Ok(Value::bool(!result.is_null()))
+}
+
+/// RegExp.prototype.toString
+pub fn regexp_prototype_to_string(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    require_object(vm, this, "RegExp.prototype.toString")?;
+    let source = get(vm, this, "source")?.to_string();
+    let flags = get(vm, this, "flags")?.to_string();
+    let string = vm.factory.string(format!("/{}/{}", source, flags));
+    Ok(string)
+}
+
+/// get RegExp.prototype.source
+pub fn regexp_prototype_source(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    let source = match regexp_info(this) {
+        Some(info) => info.escaped_source(),
+        None if this == vm.factory.object_prototypes.regexp => "(?:)".to_string(),
+        None => {
+            return Err(vm
+                .current_context
+                .error_type("RegExp.prototype.source getter called on non-RegExp object"))
+        }
+    };
+    let source = vm.factory.string(source);
+    Ok(source)
+}
+
+/// get RegExp.prototype.flags
+pub fn regexp_prototype_flags(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    require_object(vm, this, "RegExp.prototype.flags getter")?;
+    let mut flags = "".to_string();
+    for (name, flag) in [
+        ("global", 'g'),
+        ("ignoreCase", 'i'),
+        ("multiline", 'm'),
+        ("dotAll", 's'),
+        ("unicode", 'u'),
+        ("sticky", 'y'),
+    ]
+    .iter()
+    {
+        if get(vm, this, name)?.to_boolean() {
+            flags.push(*flag)
+        }
+    }
+    let flags = vm.factory.string(flags);
+    Ok(flags)
+}
+
+fn regexp_flag(vm: &mut VM, this: Value, name: &str, flag: fn(&Flags) -> bool) -> VMValueResult {
+    match regexp_info(this) {
+        Some(info) => Ok(Value::bool(flag(&info.regex.flags))),
+        None if this == vm.factory.object_prototypes.regexp => Ok(Value::undefined()),
+        None => Err(vm.current_context.error_type(format!(
+            "RegExp.prototype.{} getter called on non-RegExp object",
+            name
+        ))),
+    }
+}
+
+/// get RegExp.prototype.global
+pub fn regexp_prototype_global(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    regexp_flag(vm, this, "global", |flags| flags.global)
+}
+
+/// get RegExp.prototype.ignoreCase
+pub fn regexp_prototype_ignore_case(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    regexp_flag(vm, this, "ignoreCase", |flags| flags.ignore_case)
+}
+
+/// get RegExp.prototype.multiline
+pub fn regexp_prototype_multiline(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    regexp_flag(vm, this, "multiline", |flags| flags.multiline)
+}
+
+/// get RegExp.prototype.dotAll
+pub fn regexp_prototype_dot_all(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    regexp_flag(vm, this, "dotAll", |flags| flags.dot_all)
+}
+
+/// get RegExp.prototype.unicode
+pub fn regexp_prototype_unicode(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    regexp_flag(vm, this, "unicode", |flags| flags.unicode)
+}
+
+/// get RegExp.prototype.sticky
+pub fn regexp_prototype_sticky(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    regexp_flag(vm, this, "sticky", |flags| flags.sticky)
+}
+
+/// RegExp.prototype\[@@match\]
+pub fn regexp_prototype_match(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    require_object(vm, this, "RegExp.prototype[Symbol.match]")?;
+    let string = arg_to_string(vm, args, 0);
+
+    if !get(vm, this, "global")?.to_boolean() {
+        return regexp_exec(vm, this, string);
+    }
+
+    let full_unicode = get(vm, this, "unicode")?.to_boolean();
+    set(vm, this, "lastIndex", Value::Number(0.0))?;
+    let mut matches = vec![];
+    loop {
+        let result = regexp_exec(vm, this, string)?;
+        if result.is_null() {
+            break;
+        }
+        let matched = get(vm, result, "0")?;
+        let matched = vm.factory.utf16_string(matched.to_utf16());
+        if matched.into_utf16().is_empty() {
+            advance_last_index(vm, this, string, full_unicode)?;
+        }
+        matches.push(Property::new_data_simple(matched));
+    }
+
+    if matches.is_empty() {
+        return Ok(Value::null());
+    }
+    let ary = vm.factory.array(matches);
+    Ok(ary)
+}
+
+/// RegExp.prototype\[@@matchAll\]
+pub fn regexp_prototype_match_all(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    require_object(vm, this, "RegExp.prototype[Symbol.matchAll]")?;
+    let string = arg_to_string(vm, args, 0);
+    let flags = get(vm, this, "flags")?;
+    let matcher = regexp_constructor(vm, &[this, flags], Value::undefined())?;
+    let last_index = get(vm, this, "lastIndex")?;
+    let last_index = Value::Number(to_length(vm, last_index) as f64);
+    set(vm, matcher, "lastIndex", last_index)?;
+
+    let flags = flags.to_string();
+    let iter = vm.factory.regexp_string_iterator(
+        matcher,
+        string,
+        flags.contains('g'),
+        flags.contains('u'),
+    );
+    Ok(iter)
+}
+
+/// %RegExpStringIteratorPrototype%.next
+pub fn regexp_string_iterator_prototype_next(
+    vm: &mut VM,
+    _args: &[Value],
+    this: Value,
+) -> VMValueResult {
+    let mut obj_info = match this {
+        Value::Object(_) => this.get_object_info(),
+        _ => {
+            return Err(vm
+                .current_context
+                .error_type("this is not a RegExp String Iterator"))
+        }
+    };
+    let info = match obj_info.kind {
+        ObjectKind::RegExpStringIterator(ref mut info) => info,
+        _ => {
+            return Err(vm
+                .current_context
+                .error_type("this is not a RegExp String Iterator"))
+        }
+    };
+
+    if info.done {
+        return Ok(create_iter_result_object(
+            &mut vm.factory,
+            Value::undefined(),
+            true,
+        ));
+    }
+
+    let (regexp, string) = (info.iterating_regexp, info.iterated);
+    let result = regexp_exec(vm, regexp, string)?;
+    if result.is_null() {
+        info.done = true;
+        return Ok(create_iter_result_object(
+            &mut vm.factory,
+            Value::undefined(),
+            true,
+        ));
+    }
+
+    if info.global {
+        let matched = get(vm, result, "0")?.to_string();
+        if matched.is_empty() {
+            advance_last_index(vm, regexp, string, info.unicode)?;
+        }
+    } else {
+        info.done = true;
+    }
+    Ok(create_iter_result_object(&mut vm.factory, result, false))
+}
+
+/// RegExp.prototype\[@@replace\]
+pub fn regexp_prototype_replace(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    require_object(vm, this, "RegExp.prototype[Symbol.replace]")?;
+    let string = arg_to_string(vm, args, 0);
+    let string_units = string.into_utf16();
+    let replace_value = args.get(1).copied().unwrap_or(Value::undefined());
+    let functional_replace = replace_value.is_function_object();
+    let replace_units: Vec<u16> = if functional_replace {
+        vec![]
+    } else {
+        replace_value.to_utf16()
+    };
+
+    let global = get(vm, this, "global")?.to_boolean();
+    let full_unicode = if global {
+        let full_unicode = get(vm, this, "unicode")?.to_boolean();
+        set(vm, this, "lastIndex", Value::Number(0.0))?;
+        full_unicode
+    } else {
+        false
+    };
+
+    let mut results = vec![];
+    loop {
+        let result = regexp_exec(vm, this, string)?;
+        if result.is_null() {
+            break;
+        }
+        results.push(result);
+        if !global {
+            break;
+        }
+        let matched = get(vm, result, "0")?.to_string();
+        if matched.is_empty() {
+            advance_last_index(vm, this, string, full_unicode)?;
+        }
+    }
+
+    let mut accumulated = vec![];
+    let mut next_source_position = 0;
+    for result in results {
+        let length = get(vm, result, "length")?;
+        let captures_len = to_length(vm, length).saturating_sub(1);
+        let matched = get(vm, result, "0")?.to_utf16();
+        let position = get(vm, result, "index")?.to_number(&mut vm.factory.memory_allocator);
+        let position = if position.is_nan() {
+            0
+        } else {
+            position.max(0.0).min(string_units.len() as f64) as usize
+        };
+
+        let mut captures = vec![];
+        for n in 1..=captures_len {
+            let capture = get(vm, result, n.to_string().as_str())?;
+            captures.push(if capture.is_undefined() {
+                capture
+            } else {
+                vm.factory.utf16_string(capture.to_utf16())
+            });
+        }
+        let named_captures = get(vm, result, "groups")?;
+
+        let replacement: Vec<u16> = if functional_replace {
+            let mut replacer_args = vec![vm.factory.utf16_string(matched.to_vec())];
+            replacer_args.extend(captures);
+            replacer_args.push(Value::Number(position as f64));
+            replacer_args.push(string);
+            if !named_captures.is_undefined() {
+                replacer_args.push(named_captures);
+            }
+            let replaced = vm.call_function(replace_value, &replacer_args, Value::undefined())?;
+            replaced.to_string().encode_utf16().collect()
+        } else {
+            get_substitution(
+                vm,
+                &matched,
+                string_units,
+                position,
+                &captures,
+                named_captures,
+                &replace_units,
+            )?
+        };
+
+        if position >= next_source_position {
+            accumulated.extend_from_slice(&string_units[next_source_position..position]);
+            accumulated.extend(replacement);
+            next_source_position = position + matched.len();
+        }
+    }
+
+    if next_source_position < string_units.len() {
+        accumulated.extend_from_slice(&string_units[next_source_position..]);
+    }
+    let result = vm.factory.utf16_string(accumulated);
+    Ok(result)
+}
+
+/// RegExp.prototype\[@@search\]
+pub fn regexp_prototype_search(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    require_object(vm, this, "RegExp.prototype[Symbol.search]")?;
+    let string = arg_to_string(vm, args, 0);
+
+    let previous_last_index = get(vm, this, "lastIndex")?;
+    if previous_last_index != Value::Number(0.0) {
+        set(vm, this, "lastIndex", Value::Number(0.0))?;
+    }
+    let result = regexp_exec(vm, this, string)?;
+    let current_last_index = get(vm, this, "lastIndex")?;
+    if current_last_index != previous_last_index {
+        set(vm, this, "lastIndex", previous_last_index)?;
+    }
+
+    if result.is_null() {
+        return Ok(Value::Number(-1.0));
+    }
+    get(vm, result, "index")
+}
+
+/// RegExp.prototype\[@@split\]
+pub fn regexp_prototype_split(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    require_object(vm, this, "RegExp.prototype[Symbol.split]")?;
+    let string = arg_to_string(vm, args, 0);
+    let string_units = string.into_utf16();
+    let size = string_units.len();
+
+    let flags = get(vm, this, "flags")?.to_string();
+    let unicode_matching = flags.contains('u');
+    let new_flags = if flags.contains('y') {
+        flags
+    } else {
+        format!("{}y", flags)
+    };
+    let new_flags = vm.factory.string(new_flags);
+    let splitter = regexp_constructor(vm, &[this, new_flags], Value::undefined())?;
+
+    let limit = args.get(1).copied().unwrap_or(Value::undefined());
+    let limit = if limit.is_undefined() {
+        u32::MAX as usize
+    } else {
+        limit.to_uint32(&mut vm.factory.memory_allocator) as usize
+    };
+
+    let mut substrings = vec![];
+    if limit == 0 {
+        return Ok(vm.factory.array(substrings));
+    }
+
+    let push = |substrings: &mut Vec<Property>, val: Value| {
+        substrings.push(Property::new_data_simple(val));
+        substrings.len() == limit
+    };
+
+    if size == 0 {
+        if regexp_exec(vm, splitter, string)?.is_null() {
+            push(&mut substrings, string);
+        }
+        return Ok(vm.factory.array(substrings));
+    }
+
+    let mut p = 0;
+    let mut q = p;
+    while q < size {
+        set(vm, splitter, "lastIndex", Value::Number(q as f64))?;
+        let z = regexp_exec(vm, splitter, string)?;
+        if z.is_null() {
+            q = advance_string_index(string_units, q, unicode_matching);
+            continue;
+        }
+        let e = get(vm, splitter, "lastIndex")?;
+        let e = to_length(vm, e).min(size);
+        if e == p {
+            q = advance_string_index(string_units, q, unicode_matching);
+            continue;
+        }
+
+        let t = vm.factory.utf16_string(string_units[p..q].to_vec());
+        if push(&mut substrings, t) {
+            return Ok(vm.factory.array(substrings));
+        }
+        p = e;
+
+        let length = get(vm, z, "length")?;
+        let captures_len = to_length(vm, length).saturating_sub(1);
+        for i in 1..=captures_len {
+            let capture = get(vm, z, i.to_string().as_str())?;
+            if push(&mut substrings, capture) {
+                return Ok(vm.factory.array(substrings));
+            }
+        }
+        q = p;
+    }
+
+    let t = vm.factory.utf16_string(string_units[p..size].to_vec());
+    push(&mut substrings, t);
+    Ok(vm.factory.array(substrings))
+}
+
+/// https://tc39.github.io/ecma262/#sec-regexpexec
+pub fn regexp_exec(vm: &mut VM, regexp: Value, string: Value) -> VMValueResult {
+    let exec = get(vm, regexp, "exec")?;
+    if exec.is_function_object() {
+        let result = vm.call_function(exec, &[string], regexp)?;
+        if !result.is_object() && !result.is_null() {
+            return Err(vm
+                .current_context
+                .error_type("exec result must be an object or null"));
+        }
+        return Ok(result);
+    }
+
+    match regexp_info(regexp) {
+        Some(info) => regexp_builtin_exec(vm, regexp, &info.regex, string),
+        None => Err(vm
+            .current_context
+            .error_type("RegExp exec method called on incompatible receiver")),
+    }
+}
+
+/// https://tc39.github.io/ecma262/#sec-regexpbuiltinexec
+/// ``string`` must be a string value, which is also the ``input`` of the result.
+fn regexp_builtin_exec(vm: &mut VM, regexp: Value, regex: &Regex, string: Value) -> VMValueResult {
+    let Flags {
+        global,
+        sticky,
+        unicode,
+        ..
+    } = regex.flags;
+    let mut last_index = if global || sticky {
+        let last_index = get(vm, regexp, "lastIndex")?;
+        to_length(vm, last_index)
+    } else {
+        0
+    };
+
+    let input = string.into_utf16();
+    let captures = loop {
+        if last_index > input.len() {
+            if global || sticky {
+                set(vm, regexp, "lastIndex", Value::Number(0.0))?;
+            }
+            return Ok(Value::null());
+        }
+        match regex.match_at(input, last_index) {
+            Some(captures) => break captures,
+            None if sticky => {
+                set(vm, regexp, "lastIndex", Value::Number(0.0))?;
+                return Ok(Value::null());
+            }
+            None => last_index = advance_string_index(input, last_index, unicode),
+        }
+    };
+
+    let (start, end) = captures[0].unwrap();
+    if global || sticky {
+        set(vm, regexp, "lastIndex", Value::Number(end as f64))?;
+    }
+
+    let elems: Vec<Value> = captures
+        .iter()
+        .map(|capture| match capture {
+            Some((start, end)) => vm.factory.utf16_string(input[*start..*end].to_vec()),
+            None => Value::undefined(),
+        })
+        .collect();
+
+    let groups = if regex.group_names.is_empty() {
+        Value::undefined()
+    } else {
+        let groups = Value::Object(vm.factory.alloc(ObjectInfo {
+            kind: ObjectKind::Ordinary,
+            prototype: Value::null(),
+            property: PropertyMap::default(),
+            sym_property: FxHashMap::default(),
+            extensible: true,
+        }));
+        for (name, index) in &regex.group_names {
+            groups.set_property(name.clone(), elems[*index]);
+        }
+        groups
+    };
+
+    let ary = vm
+        .factory
+        .array(elems.into_iter().map(Property::new_data_simple).collect());
+    ary.set_property("index", Value::Number(start as f64));
+    ary.set_property("input", string);
+    ary.set_property("groups", groups);
+    Ok(ary)
+}
+
+/// https://tc39.github.io/ecma262/#sec-advancestringindex
+pub fn advance_string_index(string: &[u16], index: usize, unicode: bool) -> usize {
+    if !unicode || index + 1 >= string.len() {
+        return index + 1;
+    }
+    let (lead, trail) = (string[index], string[index + 1]);
+    if (0xd800..0xdc00).contains(&lead) && (0xdc00..0xe000).contains(&trail) {
+        index + 2
+    } else {
+        index + 1
+    }
+}
+
+fn advance_last_index(
+    vm: &mut VM,
+    regexp: Value,
+    string: Value,
+    unicode: bool,
+) -> Result<(), RuntimeError> {
+    let this_index = get(vm, regexp, "lastIndex")?;
+    let this_index = to_length(vm, this_index);
+    let next_index = advance_string_index(string.into_utf16(), this_index, unicode);
+    set(vm, regexp, "lastIndex", Value::Number(next_index as f64))
+}
+
+fn regexp_info(val: Value) -> Option<RegExpInfo> {
+    match val {
+        Value::Object(_) => match val.get_object_info().kind {
+            ObjectKind::RegExp(ref info) => Some(info.clone()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn require_object(vm: &mut VM, this: Value, method: &str) -> Result<(), RuntimeError> {
+    if this.is_object() {
+        Ok(())
+    } else {
+        Err(vm
+            .current_context
+            .error_type(format!("{} called on non-object", method)))
+    }
+}
+
+fn to_string_or_empty(val: Value) -> String {
+    if val.is_undefined() {
+        "".to_string()
+    } else {
+        val.to_string()
+    }
+}
+
+/// ToString(args[idx]), which is the argument itself if it is a string.
+fn arg_to_string(vm: &mut VM, args: &[Value], idx: usize) -> Value {
+    match args.get(idx) {
+        Some(string) if string.is_string() => *string,
+        string => {
+            let string = string.unwrap_or(&Value::undefined()).to_utf16();
+            vm.factory.utf16_string(string)
+        }
+    }
+}
+
+/// https://tc39.github.io/ecma262/#sec-tolength
+fn to_length(vm: &mut VM, val: Value) -> usize {
+    let len = val.to_number(&mut vm.factory.memory_allocator);
+    if len.is_nan() || len <= 0.0 {
+        0
+    } else {
+        len.min(9007199254740991.0) as usize
+    }
+}
+
+fn get(vm: &mut VM, obj: Value, key: &str) -> VMValueResult {
+    let key = vm.factory.string(key);
+    vm.get_property_by_value(obj, key)
+}
+
+fn set(vm: &mut VM, obj: Value, key: &str, val: Value) -> Result<(), RuntimeError> {
+    let key = vm.factory.string(key);
+    vm.set_property_by_value(obj, key, val)
+}
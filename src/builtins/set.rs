@@ -0,0 +1,203 @@
+use crate::builtin::{arg, is_constructor_call, object_kind};
+use crate::builtins::iterator::create_iter_result_object;
+use crate::builtins::map::{can_be_held_weakly, incompatible_receiver};
+use crate::vm::{
+    error::RuntimeError,
+    jsvalue::{
+        collection::MapData,
+        iterator::IterationKind,
+        object::{ObjectKind, Property},
+        value::Value,
+    },
+    vm::{Factory, VMResult, VMValueResult, VM},
+};
+
+pub fn set(factory: &mut Factory) -> Value {
+    factory.generate_builtin_constructor("Set", set_constructor, 0, factory.object_prototypes.set)
+}
+
+pub fn weak_set(factory: &mut Factory) -> Value {
+    factory.generate_builtin_constructor(
+        "WeakSet",
+        weak_set_constructor,
+        0,
+        factory.object_prototypes.weak_set,
+    )
+}
+
+/// https://tc39.github.io/ecma262/#sec-set-iterable
+pub fn set_constructor(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    if !is_constructor_call(this, vm.factory.object_prototypes.set) {
+        return Err(vm
+            .current_context
+            .error_type("Constructor Set requires 'new'"));
+    }
+    let prototype = this.get_object_info().prototype;
+    let set = vm
+        .factory
+        .keyed_collection(prototype, ObjectKind::Set(Box::default()));
+    add_values_from_iterable(vm, set, arg(args, 0))?;
+    Ok(set)
+}
+
+/// Set.prototype.add
+pub fn set_prototype_add(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    this_set(vm, this, "Set.prototype.add")?.set(arg(args, 0), Value::undefined());
+    Ok(this)
+}
+
+/// Set.prototype.clear
+pub fn set_prototype_clear(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    this_set(vm, this, "Set.prototype.clear")?.clear();
+    Ok(Value::undefined())
+}
+
+/// Set.prototype.delete
+pub fn set_prototype_delete(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let data = this_set(vm, this, "Set.prototype.delete")?;
+    Ok(Value::bool(data.delete(arg(args, 0))))
+}
+
+/// Set.prototype.entries
+pub fn set_prototype_entries(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    let cursor = this_set(vm, this, "Set.prototype.entries")?.cursor();
+    Ok(vm
+        .factory
+        .set_iterator(this, cursor, IterationKind::KeyAndValue))
+}
+
+/// Set.prototype.forEach
+pub fn set_prototype_for_each(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let cursor = this_set(vm, this, "Set.prototype.forEach")?.cursor();
+    let callback = arg(args, 0);
+    if !callback.is_function_object() {
+        return Err(vm
+            .current_context
+            .error_type(format!("{} is not a function", callback.debug_string(true))));
+    }
+    // Values added by the callback are visited as well.
+    while let Some((val, _)) = this_set(vm, this, "Set.prototype.forEach")?.next_entry(&cursor) {
+        vm.call_function(callback, &[val, val, this], arg(args, 1))?;
+    }
+    Ok(Value::undefined())
+}
+
+/// Set.prototype.has
+pub fn set_prototype_has(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let data = this_set(vm, this, "Set.prototype.has")?;
+    Ok(Value::bool(data.has(arg(args, 0))))
+}
+
+/// get Set.prototype.size
+pub fn set_prototype_size(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    let data = this_set(vm, this, "get Set.prototype.size")?;
+    Ok(Value::Number(data.size() as f64))
+}
+
+/// Set.prototype.values
+pub fn set_prototype_values(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    let cursor = this_set(vm, this, "Set.prototype.values")?.cursor();
+    Ok(vm.factory.set_iterator(this, cursor, IterationKind::Value))
+}
+
+/// %SetIteratorPrototype%.next
+pub fn set_iterator_prototype_next(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    let info = match object_kind(this) {
+        Some(ObjectKind::SetIterator(info)) => info,
+        _ => return Err(vm.current_context.error_type("this is not a Set Iterator")),
+    };
+
+    if let Some(ObjectKind::Set(data)) = object_kind(info.iterated) {
+        if let Some((val, _)) = data.next_entry(&info.cursor) {
+            let result = match info.kind {
+                IterationKind::Key | IterationKind::Value => val,
+                IterationKind::KeyAndValue => vm.factory.array(vec![
+                    Property::new_data_simple(val),
+                    Property::new_data_simple(val),
+                ]),
+            };
+            return Ok(create_iter_result_object(&mut vm.factory, result, false));
+        }
+    }
+
+    info.iterated = Value::undefined();
+    Ok(create_iter_result_object(
+        &mut vm.factory,
+        Value::undefined(),
+        true,
+    ))
+}
+
+/// https://tc39.github.io/ecma262/#sec-weakset-iterable
+pub fn weak_set_constructor(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    if !is_constructor_call(this, vm.factory.object_prototypes.weak_set) {
+        return Err(vm
+            .current_context
+            .error_type("Constructor WeakSet requires 'new'"));
+    }
+    let prototype = this.get_object_info().prototype;
+    let weak_set = vm
+        .factory
+        .keyed_collection(prototype, ObjectKind::WeakSet(Box::default()));
+    add_values_from_iterable(vm, weak_set, arg(args, 0))?;
+    Ok(weak_set)
+}
+
+/// WeakSet.prototype.add
+pub fn weak_set_prototype_add(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let data = this_weak_set(vm, this, "WeakSet.prototype.add")?;
+    let val = arg(args, 0);
+    if !can_be_held_weakly(val) {
+        return Err(vm.current_context.error_type(format!(
+            "Invalid value used in weak set: {}",
+            val.debug_string(true)
+        )));
+    }
+    data.set(val, Value::undefined());
+    Ok(this)
+}
+
+/// WeakSet.prototype.delete
+pub fn weak_set_prototype_delete(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let data = this_weak_set(vm, this, "WeakSet.prototype.delete")?;
+    Ok(Value::bool(data.delete(arg(args, 0))))
+}
+
+/// WeakSet.prototype.has
+pub fn weak_set_prototype_has(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let data = this_weak_set(vm, this, "WeakSet.prototype.has")?;
+    Ok(Value::bool(data.has(arg(args, 0))))
+}
+
+fn this_set<'a>(vm: &VM, this: Value, method: &str) -> Result<&'a mut MapData, RuntimeError> {
+    match object_kind(this) {
+        Some(ObjectKind::Set(data)) => Ok(data),
+        _ => Err(incompatible_receiver(vm, this, method)),
+    }
+}
+
+fn this_weak_set<'a>(vm: &VM, this: Value, method: &str) -> Result<&'a mut MapData, RuntimeError> {
+    match object_kind(this) {
+        Some(ObjectKind::WeakSet(data)) => Ok(data),
+        _ => Err(incompatible_receiver(vm, this, method)),
+    }
+}
+
+/// Calls ``target.add(value)`` for each value of ``iterable``.
+fn add_values_from_iterable(vm: &mut VM, target: Value, iterable: Value) -> VMResult {
+    if iterable.is_undefined() || iterable.is_null() {
+        return Ok(());
+    }
+    let adder = vm.factory.string("add");
+    let adder = vm.get_property_by_value(target, adder)?;
+    if !adder.is_function_object() {
+        return Err(vm
+            .current_context
+            .error_type(format!("{} is not a function", adder.debug_string(true))));
+    }
+    let iter = vm.get_iterator(iterable)?;
+    while let Some(val) = vm.iterator_step(iter)? {
+        vm.call_function(adder, &[val], target)?;
+    }
+    Ok(())
+}
@@ -1,34 +1,996 @@
+use crate::builtin::{arg, is_constructor_call};
+use crate::builtins::iterator::create_iter_result_object;
+use crate::builtins::number::to_integer_or_infinity;
+use crate::builtins::regexp::regexp_create;
 use crate::vm::{
-    jsvalue::value::{Property, Value},
+    error::RuntimeError,
+    jsvalue::value::{ObjectKind, Property, Value},
     vm::VMValueResult,
-    vm::VM,
+    vm::{Factory, VM},
 };
+use std::cmp::Ordering;
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
 
+pub fn string(factory: &mut Factory) -> Value {
+    let obj = factory.generate_builtin_constructor(
+        "String",
+        string_constructor,
+        1,
+        factory.object_prototypes.string,
+    );
+    // String.fromCharCode
+    obj.set_property(
+        "fromCharCode",
+        factory.builtin_function("fromCharCode", string_from_char_code, 1),
+    );
+    // String.fromCodePoint
+    obj.set_property(
+        "fromCodePoint",
+        factory.builtin_function("fromCodePoint", string_from_code_point, 1),
+    );
+    // String.raw
+    obj.set_property("raw", factory.builtin_function("raw", string_raw, 1));
+    obj
+}
+
+/// https://tc39.github.io/ecma262/#sec-string-constructor-string-value
+pub fn string_constructor(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let constructing = is_constructor_call(this, vm.factory.object_prototypes.string);
+    let s = match args.first() {
+        None => vm.factory.string(""),
+        // SymbolDescriptiveString(value)
+        Some(value) if value.is_symbol() && !constructing => {
+            vm.factory.string(value.debug_string(false))
+        }
+        Some(value) if value.is_symbol() => {
+            return Err(vm
+                .current_context
+                .error_type("Cannot convert a Symbol value to a string"))
+        }
+        Some(value) => vm.factory.utf16_string(value.to_utf16()),
+    };
+    if !constructing {
+        return Ok(s);
+    }
+    let prototype = this.get_object_info().prototype;
+    Ok(vm.factory.string_object(prototype, s))
+}
+
+/// String.fromCharCode
+pub fn string_from_char_code(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    let units = args
+        .iter()
+        .map(|code| code.to_uint32(&mut vm.factory.memory_allocator) as u16)
+        .collect::<Vec<u16>>();
+    Ok(string_from_utf16(vm, &units))
+}
+
+/// String.fromCodePoint
+pub fn string_from_code_point(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    let mut units = vec![];
+    for code in args {
+        let n = code.to_number(&mut vm.factory.memory_allocator);
+        if n.trunc() != n || !(0.0..=f64::from(0x10_ffff)).contains(&n) {
+            return Err(vm
+                .current_context
+                .error_range(format!("Invalid code point {}", code.to_string())));
+        }
+        let mut buf = [0; 2];
+        match std::char::from_u32(n as u32) {
+            Some(c) => units.extend_from_slice(c.encode_utf16(&mut buf)),
+            // A lone surrogate.
+            None => units.push(n as u16),
+        }
+    }
+    Ok(string_from_utf16(vm, &units))
+}
+
+/// String.raw
+/// https://tc39.github.io/ecma262/#sec-string.raw
+pub fn string_raw(vm: &mut VM, args: &[Value], _this: Value) -> VMValueResult {
+    let cooked = arg(args, 0);
+    if cooked.is_undefined() || cooked.is_null() {
+        return Err(vm
+            .current_context
+            .error_type("Cannot convert undefined or null to object"));
+    }
+    let raw = vm.factory.string("raw");
+    let raw = vm.get_property_by_value(cooked, raw)?;
+    if raw.is_undefined() || raw.is_null() {
+        return Err(vm
+            .current_context
+            .error_type("Cannot convert undefined or null to object"));
+    }
+    let length = vm.factory.string("length");
+    let length = vm
+        .get_property_by_value(raw, length)?
+        .to_number(&mut vm.factory.memory_allocator);
+    let literal_segments = if length.is_nan() || length <= 0.0 {
+        0
+    } else {
+        length as usize
+    };
+
+    let mut result = vec![];
+    for i in 0..literal_segments {
+        let segment = vm.get_property_by_value(raw, Value::Number(i as f64))?;
+        result.extend(segment.to_utf16());
+        if i + 1 < literal_segments {
+            if let Some(substitution) = args.get(i + 1) {
+                result.extend(substitution.to_utf16());
+            }
+        }
+    }
+    Ok(string_from_utf16(vm, &result))
+}
+
+/// String.prototype.charAt
+pub fn string_prototype_char_at(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let string = this_string(vm, this, "String.prototype.charAt")?;
+    let position = integer_arg(vm, args, 0);
+    match code_unit_at(string, position) {
+        Some(unit) => Ok(string_from_utf16(vm, &[unit])),
+        None => Ok(vm.factory.string("")),
+    }
+}
+
+/// String.prototype.charCodeAt
+pub fn string_prototype_char_code_at(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let string = this_string(vm, this, "String.prototype.charCodeAt")?;
+    let position = integer_arg(vm, args, 0);
+    match code_unit_at(string, position) {
+        Some(unit) => Ok(Value::Number(f64::from(unit))),
+        None => Ok(Value::Number(f64::NAN)),
+    }
+}
+
+/// String.prototype.codePointAt
+/// https://tc39.github.io/ecma262/#sec-codepointat
+pub fn string_prototype_code_point_at(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let string = this_string(vm, this, "String.prototype.codePointAt")?;
+    let position = integer_arg(vm, args, 0);
+    let first = match code_unit_at(string, position) {
+        Some(unit) => unit,
+        None => return Ok(Value::undefined()),
+    };
+    let second = string.get(position as usize + 1).copied();
+    let code_point = match second {
+        Some(second) if is_lead_surrogate(first) && is_trail_surrogate(second) => {
+            0x10000 + ((u32::from(first) - 0xd800) << 10) + (u32::from(second) - 0xdc00)
+        }
+        _ => u32::from(first),
+    };
+    Ok(Value::Number(f64::from(code_point)))
+}
+
+/// String.prototype.concat
+pub fn string_prototype_concat(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    require_object_coercible(vm, this, "String.prototype.concat")?;
+    let mut result = this.to_utf16();
+    for arg in args {
+        result.extend(arg.to_utf16());
+    }
+    Ok(string_from_utf16(vm, &result))
+}
+
+/// String.prototype.endsWith
+pub fn string_prototype_ends_with(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let string = this_string(vm, this, "String.prototype.endsWith")?;
+    let search_string = search_string_arg(vm, args, "String.prototype.endsWith")?;
+    let end = if arg(args, 1).is_undefined() {
+        string.len()
+    } else {
+        clamp_index(integer_arg(vm, args, 1), string.len())
+    };
+    let found =
+        search_string.len() <= end && string[end - search_string.len()..end] == search_string[..];
+    Ok(Value::bool(found))
+}
+
+/// String.prototype.includes
+pub fn string_prototype_includes(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let string = this_string(vm, this, "String.prototype.includes")?;
+    let search_string = search_string_arg(vm, args, "String.prototype.includes")?;
+    let start = clamp_index(integer_arg(vm, args, 1), string.len());
+    Ok(Value::bool(find(string, &search_string, start).is_some()))
+}
+
+/// String.prototype.indexOf
+pub fn string_prototype_index_of(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let string = this_string(vm, this, "String.prototype.indexOf")?;
+    let search_string = arg(args, 0).to_utf16();
+    let start = clamp_index(integer_arg(vm, args, 1), string.len());
+    let found_pos = find(string, &search_string, start).map_or(-1.0, |p| p as f64);
+    Ok(Value::Number(found_pos))
+}
+
+/// String.prototype.lastIndexOf
+/// https://tc39.github.io/ecma262/#sec-string.prototype.lastindexof
+pub fn string_prototype_last_index_of(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let string = this_string(vm, this, "String.prototype.lastIndexOf")?;
+    let search_string = arg(args, 0).to_utf16();
+    let num_pos = arg(args, 1).to_number(&mut vm.factory.memory_allocator);
+    let position = if num_pos.is_nan() {
+        f64::INFINITY
+    } else {
+        num_pos.trunc()
+    };
+    let start = clamp_index(position, string.len());
+    if search_string.len() > string.len() {
+        return Ok(Value::Number(-1.0));
+    }
+    let found_pos = (0..=start.min(string.len() - search_string.len()))
+        .rev()
+        .find(|i| string[*i..*i + search_string.len()] == search_string[..])
+        .map_or(-1.0, |p| p as f64);
+    Ok(Value::Number(found_pos))
+}
+
+/// String.prototype.localeCompare
+///
+/// Approximates the root locale collation: base letters are compared first, then accents, and
+/// then case, where a lowercase letter comes before its uppercase one.
+pub fn string_prototype_locale_compare(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    require_object_coercible(vm, this, "String.prototype.localeCompare")?;
+    let string = this.to_string();
+    let that = arg(args, 0).to_string();
+
+    let base = |s: &str| -> Vec<char> {
+        s.nfd()
+            .filter(|c| !is_combining_mark(*c))
+            .flat_map(char::to_lowercase)
+            .collect()
+    };
+    let accents = |s: &str| -> Vec<char> { s.nfd().flat_map(char::to_lowercase).collect() };
+    // Swaps the case so that a lowercase letter sorts first.
+    let case = |s: &str| -> Vec<char> {
+        s.nfd()
+            .map(|c| {
+                if c.is_lowercase() {
+                    c.to_uppercase().next().unwrap_or(c)
+                } else {
+                    c.to_lowercase().next().unwrap_or(c)
+                }
+            })
+            .collect()
+    };
+
+    let ordering = base(&string)
+        .cmp(&base(&that))
+        .then_with(|| accents(&string).cmp(&accents(&that)))
+        .then_with(|| case(&string).cmp(&case(&that)));
+    Ok(Value::Number(match ordering {
+        Ordering::Less => -1.0,
+        Ordering::Equal => 0.0,
+        Ordering::Greater => 1.0,
+    }))
+}
+
+/// String.prototype.normalize
+pub fn string_prototype_normalize(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    require_object_coercible(vm, this, "String.prototype.normalize")?;
+    let string = this.to_string();
+    let form = match arg(args, 0) {
+        form if form.is_undefined() => "NFC".to_string(),
+        form => form.to_string(),
+    };
+    let normalized: String = match form.as_str() {
+        "NFC" => string.nfc().collect(),
+        "NFD" => string.nfd().collect(),
+        "NFKC" => string.nfkc().collect(),
+        "NFKD" => string.nfkd().collect(),
+        _ => {
+            return Err(vm
+                .current_context
+                .error_range("The normalization form should be one of NFC, NFD, NFKC, NFKD."))
+        }
+    };
+    Ok(vm.factory.string(normalized))
+}
+
+/// String.prototype.padEnd
+pub fn string_prototype_pad_end(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let string = this_string(vm, this, "String.prototype.padEnd")?;
+    let padding = string_padding(vm, args, string.len())?;
+    let result = [string, &padding].concat();
+    Ok(string_from_utf16(vm, &result))
+}
+
+/// String.prototype.padStart
+pub fn string_prototype_pad_start(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let string = this_string(vm, this, "String.prototype.padStart")?;
+    let mut result = string_padding(vm, args, string.len())?;
+    result.extend(string);
+    Ok(string_from_utf16(vm, &result))
+}
+
+/// String.prototype.repeat
+pub fn string_prototype_repeat(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let string = this_string(vm, this, "String.prototype.repeat")?;
+    let count = integer_arg(vm, args, 0);
+    if count < 0.0 || count.is_infinite() {
+        return Err(vm.current_context.error_range(format!(
+            "Invalid count value: {}",
+            Value::Number(count).to_string()
+        )));
+    }
+    if string.is_empty() || count == 0.0 {
+        return Ok(vm.factory.string(""));
+    }
+    if string.len() as f64 * count > MAX_STRING_LENGTH {
+        return Err(vm.current_context.error_range("Invalid string length"));
+    }
+    Ok(string_from_utf16(vm, &string.repeat(count as usize)))
+}
+
+/// String.prototype.replaceAll
+/// https://tc39.es/proposal-string-replaceall/
+pub fn string_prototype_replace_all(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    require_object_coercible(vm, this, "String.prototype.replaceAll")?;
+    let search_value = arg(args, 0);
+    let replace_value = arg(args, 1);
+    if is_regexp(vm, search_value)? {
+        let flags = vm.factory.string("flags");
+        let flags = vm.get_property_by_value(search_value, flags)?;
+        if flags.is_undefined() || flags.is_null() || !flags.to_string().contains('g') {
+            return Err(vm
+                .current_context
+                .error_type("replaceAll must be called with a global RegExp"));
+        }
+    }
+    let symbol = vm.factory.symbols.replace;
+    if let Some(result) = call_symbol_method(vm, search_value, symbol, &[this, replace_value])? {
+        return Ok(result);
+    }
+
+    let string = this.to_utf16();
+    let search_string = search_value.to_utf16();
+    let advance_by = search_string.len().max(1);
+    let mut match_positions = vec![];
+    let mut position = find(&string, &search_string, 0);
+    while let Some(p) = position {
+        match_positions.push(p);
+        position = if p + advance_by <= string.len() {
+            find(&string, &search_string, p + advance_by)
+        } else {
+            None
+        };
+    }
+
+    let mut end_of_last_match = 0;
+    let mut result = vec![];
+    for position in match_positions {
+        let replacement: Vec<u16> = if replace_value.is_function_object() {
+            let matched = string_from_utf16(vm, &search_string);
+            let string = string_from_utf16(vm, &string);
+            let replaced = vm.call_function(
+                replace_value,
+                &[matched, Value::Number(position as f64), string],
+                Value::undefined(),
+            )?;
+            replaced.to_utf16()
+        } else {
+            get_substitution(
+                vm,
+                &search_string,
+                &string,
+                position,
+                &[],
+                Value::undefined(),
+                &replace_value.to_utf16(),
+            )?
+        };
+        result.extend_from_slice(&string[end_of_last_match..position]);
+        result.extend(replacement);
+        end_of_last_match = position + search_string.len();
+    }
+    result.extend_from_slice(&string[end_of_last_match.min(string.len())..]);
+    Ok(string_from_utf16(vm, &result))
+}
+
+/// String.prototype.slice
+pub fn string_prototype_slice(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let string = this_string(vm, this, "String.prototype.slice")?;
+    let len = string.len();
+    let from = relative_index(integer_arg(vm, args, 0), len);
+    let to = if arg(args, 1).is_undefined() {
+        len
+    } else {
+        relative_index(integer_arg(vm, args, 1), len)
+    };
+    if from >= to {
+        return Ok(vm.factory.string(""));
+    }
+    Ok(string_from_utf16(vm, &string[from..to]))
+}
+
+/// String.prototype.startsWith
+pub fn string_prototype_starts_with(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let string = this_string(vm, this, "String.prototype.startsWith")?;
+    let search_string = search_string_arg(vm, args, "String.prototype.startsWith")?;
+    let start = clamp_index(integer_arg(vm, args, 1), string.len());
+    let found = start + search_string.len() <= string.len()
+        && string[start..start + search_string.len()] == search_string[..];
+    Ok(Value::bool(found))
+}
+
+/// String.prototype.substring
+pub fn string_prototype_substring(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    let string = this_string(vm, this, "String.prototype.substring")?;
+    let len = string.len();
+    let start = clamp_index(integer_arg(vm, args, 0), len);
+    let end = if arg(args, 1).is_undefined() {
+        len
+    } else {
+        clamp_index(integer_arg(vm, args, 1), len)
+    };
+    let (from, to) = (start.min(end), start.max(end));
+    Ok(string_from_utf16(vm, &string[from..to]))
+}
+
+/// String.prototype.toLowerCase
+pub fn string_prototype_to_lower_case(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    let string = this_string(vm, this, "String.prototype.toLowerCase")?;
+    let result = map_well_formed(string, str::to_lowercase);
+    Ok(string_from_utf16(vm, &result))
+}
+
+/// String.prototype.toString
+pub fn string_prototype_to_string(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    this_string_value(vm, this, "String.prototype.toString")
+}
+
+/// String.prototype.toUpperCase
+pub fn string_prototype_to_upper_case(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    let string = this_string(vm, this, "String.prototype.toUpperCase")?;
+    let result = map_well_formed(string, str::to_uppercase);
+    Ok(string_from_utf16(vm, &result))
+}
+
+/// String.prototype.trim
+pub fn string_prototype_trim(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    let string = this_string(vm, this, "String.prototype.trim")?;
+    let result = trim_white_space(string, true, true);
+    Ok(string_from_utf16(vm, result))
+}
+
+/// String.prototype.trimEnd
+pub fn string_prototype_trim_end(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    let string = this_string(vm, this, "String.prototype.trimEnd")?;
+    let result = trim_white_space(string, false, true);
+    Ok(string_from_utf16(vm, result))
+}
+
+/// String.prototype.trimStart
+pub fn string_prototype_trim_start(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    let string = this_string(vm, this, "String.prototype.trimStart")?;
+    let result = trim_white_space(string, true, false);
+    Ok(string_from_utf16(vm, result))
+}
+
+/// String.prototype.valueOf
+pub fn string_prototype_value_of(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    this_string_value(vm, this, "String.prototype.valueOf")
+}
+
+/// String.prototype.split
 pub fn string_prototype_split(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
-    let string = this.into_str();
-    let separator_ = args.get(0).map(|x| *x).unwrap_or(Value::undefined());
-    if separator_.is_undefined() {
-        let ary = vm.factory.array(vec![Property::new_data_simple(this)]);
-        return Ok(ary);
-    }
-    let separator = separator_.to_string();
-    let elems = string
-        .split(separator.as_str())
-        .collect::<Vec<&str>>()
+    require_object_coercible(vm, this, "String.prototype.split")?;
+    let separator_value = args.first().copied().unwrap_or(Value::undefined());
+    let limit = args.get(1).copied().unwrap_or(Value::undefined());
+    let symbol = vm.factory.symbols.split;
+    if let Some(result) = call_symbol_method(vm, separator_value, symbol, &[this, limit])? {
+        return Ok(result);
+    }
+
+    let string = this.to_utf16();
+    let limit = if limit.is_undefined() {
+        u32::MAX as usize
+    } else {
+        limit.to_uint32(&mut vm.factory.memory_allocator) as usize
+    };
+    let separator = separator_value.to_utf16();
+    if limit == 0 {
+        return Ok(vm.factory.array(vec![]));
+    }
+    if separator_value.is_undefined() {
+        let string = string_from_utf16(vm, &string);
+        return Ok(vm.factory.array(vec![Property::new_data_simple(string)]));
+    }
+
+    let substrings: Vec<Vec<u16>> = if separator.is_empty() {
+        string.iter().take(limit).map(|c| vec![*c]).collect()
+    } else {
+        let mut substrings = vec![];
+        let mut p = 0;
+        let mut q = 0;
+        while q + separator.len() <= string.len() && substrings.len() < limit {
+            if string[q..q + separator.len()] == separator[..] {
+                substrings.push(string[p..q].to_vec());
+                q += separator.len();
+                p = q;
+            } else {
+                q += 1;
+            }
+        }
+        if substrings.len() < limit {
+            substrings.push(string[p..].to_vec());
+        }
+        substrings
+    };
+    let elems = substrings
         .iter()
-        .map(|s| Property::new_data_simple(vm.factory.string(s.to_string())))
+        .map(|s| Property::new_data_simple(string_from_utf16(vm, s)))
         .collect::<Vec<Property>>();
     let ary = vm.factory.array(elems);
     Ok(ary)
 }
 
-pub fn string_prototype_index_of(_vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
-    let string = this.into_str();
-    let search_string = args.get(0).unwrap_or(&Value::undefined()).to_string();
-    let position = args.get(1).unwrap_or(&Value::Number(0.0)).into_number() as usize;
-    let found_pos = string[position..]
-        .find(search_string.as_str())
-        .map_or(-1.0, |p| p as f64);
-    let val = Value::Number(found_pos);
-    Ok(val)
+/// String.prototype.match
+pub fn string_prototype_match(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    require_object_coercible(vm, this, "String.prototype.match")?;
+    let regexp = args.first().copied().unwrap_or(Value::undefined());
+    let symbol = vm.factory.symbols.match_;
+    if let Some(result) = call_symbol_method(vm, regexp, symbol, &[this])? {
+        return Ok(result);
+    }
+
+    let string = vm.factory.utf16_string(this.to_utf16());
+    let rx = create_regexp(vm, regexp, "")?;
+    invoke(vm, rx, symbol, &[string])
+}
+
+/// String.prototype.matchAll
+pub fn string_prototype_match_all(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    require_object_coercible(vm, this, "String.prototype.matchAll")?;
+    let regexp = args.first().copied().unwrap_or(Value::undefined());
+    if is_regexp(vm, regexp)? {
+        let flags = vm.factory.string("flags");
+        let flags = vm.get_property_by_value(regexp, flags)?;
+        if flags.is_undefined() || flags.is_null() || !flags.to_string().contains('g') {
+            return Err(vm
+                .current_context
+                .error_type("String.prototype.matchAll called with a non-global RegExp argument"));
+        }
+    }
+    let symbol = vm.factory.symbols.match_all;
+    if let Some(result) = call_symbol_method(vm, regexp, symbol, &[this])? {
+        return Ok(result);
+    }
+
+    let string = vm.factory.utf16_string(this.to_utf16());
+    let rx = create_regexp(vm, regexp, "g")?;
+    invoke(vm, rx, symbol, &[string])
+}
+
+/// String.prototype.replace
+pub fn string_prototype_replace(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    require_object_coercible(vm, this, "String.prototype.replace")?;
+    let search_value = args.first().copied().unwrap_or(Value::undefined());
+    let replace_value = args.get(1).copied().unwrap_or(Value::undefined());
+    let symbol = vm.factory.symbols.replace;
+    if let Some(result) = call_symbol_method(vm, search_value, symbol, &[this, replace_value])? {
+        return Ok(result);
+    }
+
+    let string = this.to_utf16();
+    let search_string = search_value.to_utf16();
+    let position = match find(&string, &search_string, 0) {
+        Some(position) => position,
+        None => return Ok(string_from_utf16(vm, &string)),
+    };
+
+    let replacement: Vec<u16> = if replace_value.is_function_object() {
+        let matched = string_from_utf16(vm, &search_string);
+        let string = string_from_utf16(vm, &string);
+        let replaced = vm.call_function(
+            replace_value,
+            &[matched, Value::Number(position as f64), string],
+            Value::undefined(),
+        )?;
+        replaced.to_utf16()
+    } else {
+        let replace_value = replace_value.to_utf16();
+        get_substitution(
+            vm,
+            &search_string,
+            &string,
+            position,
+            &[],
+            Value::undefined(),
+            &replace_value,
+        )?
+    };
+
+    let mut result = string[..position].to_vec();
+    result.extend(replacement);
+    result.extend_from_slice(&string[position + search_string.len()..]);
+    Ok(string_from_utf16(vm, &result))
+}
+
+/// String.prototype.search
+pub fn string_prototype_search(vm: &mut VM, args: &[Value], this: Value) -> VMValueResult {
+    require_object_coercible(vm, this, "String.prototype.search")?;
+    let regexp = args.first().copied().unwrap_or(Value::undefined());
+    let symbol = vm.factory.symbols.search;
+    if let Some(result) = call_symbol_method(vm, regexp, symbol, &[this])? {
+        return Ok(result);
+    }
+
+    let string = vm.factory.utf16_string(this.to_utf16());
+    let rx = create_regexp(vm, regexp, "")?;
+    invoke(vm, rx, symbol, &[string])
+}
+
+/// https://tc39.github.io/ecma262/#sec-getsubstitution
+pub fn get_substitution(
+    vm: &mut VM,
+    matched: &[u16],
+    string: &[u16],
+    position: usize,
+    captures: &[Value],
+    named_captures: Value,
+    replacement: &[u16],
+) -> Result<Vec<u16>, RuntimeError> {
+    let tail_pos = (position + matched.len()).min(string.len());
+    let capture = |n: usize| -> Vec<u16> {
+        match captures.get(n - 1) {
+            Some(capture) if !capture.is_undefined() => capture.to_utf16(),
+            _ => vec![],
+        }
+    };
+    let digit = |i: usize| -> Option<usize> {
+        replacement
+            .get(i)
+            .and_then(|c| std::char::from_u32(*c as u32))
+            .and_then(|c| c.to_digit(10))
+            .map(|d| d as usize)
+    };
+
+    let mut result = vec![];
+    let mut i = 0;
+    while i < replacement.len() {
+        let c = replacement[i];
+        if c != '$' as u16 || i + 1 == replacement.len() {
+            result.push(c);
+            i += 1;
+            continue;
+        }
+        match std::char::from_u32(replacement[i + 1] as u32).unwrap_or('\0') {
+            '$' => {
+                result.push('$' as u16);
+                i += 2;
+            }
+            '&' => {
+                result.extend_from_slice(matched);
+                i += 2;
+            }
+            '`' => {
+                result.extend_from_slice(&string[..position]);
+                i += 2;
+            }
+            '\'' => {
+                result.extend_from_slice(&string[tail_pos..]);
+                i += 2;
+            }
+            '0'..='9' => {
+                let n = digit(i + 1).unwrap();
+                match digit(i + 2).map(|d| n * 10 + d) {
+                    Some(nn) if 1 <= nn && nn <= captures.len() => {
+                        result.extend(capture(nn));
+                        i += 3;
+                    }
+                    _ if 1 <= n && n <= captures.len() => {
+                        result.extend(capture(n));
+                        i += 2;
+                    }
+                    _ => {
+                        result.push('$' as u16);
+                        i += 1;
+                    }
+                }
+            }
+            '<' if !named_captures.is_undefined() => {
+                match replacement[i + 2..].iter().position(|c| *c == '>' as u16) {
+                    Some(len) => {
+                        let group_name = String::from_utf16_lossy(&replacement[i + 2..i + 2 + len]);
+                        let group_name = vm.factory.string(group_name);
+                        let capture = vm.get_property_by_value(named_captures, group_name)?;
+                        if !capture.is_undefined() {
+                            result.extend(capture.to_utf16());
+                        }
+                        i += len + 3;
+                    }
+                    None => {
+                        result.push('$' as u16);
+                        i += 1;
+                    }
+                }
+            }
+            _ => {
+                result.push('$' as u16);
+                i += 1;
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Calls ``obj[symbol](...args)`` if ``obj`` is neither undefined nor null and has the method.
+fn call_symbol_method(
+    vm: &mut VM,
+    obj: Value,
+    symbol: Value,
+    args: &[Value],
+) -> Result<Option<Value>, RuntimeError> {
+    if obj.is_undefined() || obj.is_null() {
+        return Ok(None);
+    }
+    let method = vm.get_property_by_value(obj, symbol)?;
+    if method.is_undefined() || method.is_null() {
+        return Ok(None);
+    }
+    if !method.is_function_object() {
+        return Err(vm
+            .current_context
+            .error_type(format!("{} is not a function", method.debug_string(false))));
+    }
+    vm.call_function(method, args, obj).map(Some)
+}
+
+fn invoke(vm: &mut VM, obj: Value, symbol: Value, args: &[Value]) -> VMValueResult {
+    let method = vm.get_property_by_value(obj, symbol)?;
+    vm.call_function(method, args, obj)
+}
+
+fn create_regexp(vm: &mut VM, pattern: Value, flags: &str) -> VMValueResult {
+    let pattern = if pattern.is_undefined() {
+        "".to_string()
+    } else {
+        pattern.to_string()
+    };
+    regexp_create(vm, pattern, flags.to_string())
+}
+
+/// https://tc39.github.io/ecma262/#sec-isregexp
+fn is_regexp(vm: &mut VM, val: Value) -> Result<bool, RuntimeError> {
+    if !val.is_object() {
+        return Ok(false);
+    }
+    let symbol = vm.factory.symbols.match_;
+    let matcher = vm.get_property_by_value(val, symbol)?;
+    if !matcher.is_undefined() {
+        return Ok(matcher.to_boolean());
+    }
+    match val.get_object_info().kind {
+        ObjectKind::RegExp(_) => Ok(true),
+        _ => Ok(false),
+    }
+}
+
+fn require_object_coercible(vm: &mut VM, this: Value, method: &str) -> Result<(), RuntimeError> {
+    if this.is_undefined() || this.is_null() {
+        return Err(vm
+            .current_context
+            .error_type(format!("{} called on null or undefined", method)));
+    }
+    Ok(())
+}
+
+fn find(string: &[u16], search: &[u16], from: usize) -> Option<usize> {
+    if search.len() > string.len() {
+        return None;
+    }
+    (from..=string.len() - search.len()).find(|i| string[*i..*i + search.len()] == search[..])
+}
+
+/// The maximum length of a string in UTF-16 code units, which is the same as V8's.
+const MAX_STRING_LENGTH: f64 = 1_073_741_799.0;
+
+/// ToIntegerOrInfinity(args[idx]).
+fn integer_arg(vm: &mut VM, args: &[Value], idx: usize) -> f64 {
+    to_integer_or_infinity(vm, arg(args, idx))
+}
+
+fn string_from_utf16(vm: &mut VM, units: &[u16]) -> Value {
+    vm.factory.utf16_string(units.to_vec())
+}
+
+/// RequireObjectCoercible(this) and the UTF-16 code units of ToString(this).
+fn this_string<'a>(vm: &mut VM, this: Value, method: &str) -> Result<&'a [u16], RuntimeError> {
+    require_object_coercible(vm, this, method)?;
+    let string = match this {
+        Value::String(_) => this,
+        _ => vm.factory.utf16_string(this.to_utf16()),
+    };
+    Ok(string.into_utf16())
+}
+
+/// https://tc39.github.io/ecma262/#sec-thisstringvalue
+fn this_string_value(vm: &VM, this: Value, method: &str) -> VMValueResult {
+    match this {
+        Value::String(_) => return Ok(this),
+        Value::Object(_) => {
+            if let ObjectKind::String(s) = this.get_object_info().kind {
+                return Ok(s);
+            }
+        }
+        _ => {}
+    }
+    Err(vm
+        .current_context
+        .error_type(format!("{} requires that 'this' be a String", method)))
+}
+
+/// The search string of startsWith, endsWith and includes, which must not be a RegExp.
+fn search_string_arg(vm: &mut VM, args: &[Value], method: &str) -> Result<Vec<u16>, RuntimeError> {
+    let search_string = arg(args, 0);
+    if is_regexp(vm, search_string)? {
+        return Err(vm.current_context.error_type(format!(
+            "First argument to {} must not be a regular expression",
+            method
+        )));
+    }
+    Ok(search_string.to_utf16())
+}
+
+/// The filler of padStart and padEnd that makes ``len`` code units reach args[0].
+fn string_padding(vm: &mut VM, args: &[Value], len: usize) -> Result<Vec<u16>, RuntimeError> {
+    let max_length = integer_arg(vm, args, 0);
+    if max_length <= len as f64 {
+        return Ok(vec![]);
+    }
+    let fill_string = match arg(args, 1) {
+        fill_string if fill_string.is_undefined() => vec![' ' as u16],
+        fill_string => fill_string.to_utf16(),
+    };
+    if fill_string.is_empty() {
+        return Ok(vec![]);
+    }
+    if max_length > MAX_STRING_LENGTH {
+        return Err(vm.current_context.error_range("Invalid string length"));
+    }
+    let fill_len = max_length as usize - len;
+    Ok(fill_string.iter().copied().cycle().take(fill_len).collect())
+}
+
+/// The code unit at ``position``, which may be out of range.
+fn code_unit_at(string: &[u16], position: f64) -> Option<u16> {
+    if position < 0.0 || position >= string.len() as f64 {
+        return None;
+    }
+    Some(string[position as usize])
+}
+
+/// Clamps ``position`` into ``0..=len``.
+fn clamp_index(position: f64, len: usize) -> usize {
+    position.max(0.0).min(len as f64) as usize
+}
+
+/// Clamps ``position`` into ``0..=len``, where a negative one is relative to the end.
+fn relative_index(position: f64, len: usize) -> usize {
+    if position < 0.0 {
+        clamp_index(len as f64 + position, len)
+    } else {
+        clamp_index(position, len)
+    }
+}
+
+fn is_lead_surrogate(unit: u16) -> bool {
+    (0xd800..=0xdbff).contains(&unit)
+}
+
+fn is_trail_surrogate(unit: u16) -> bool {
+    (0xdc00..=0xdfff).contains(&unit)
+}
+
+/// Maps each well-formed part of ``string`` with ``f``, and keeps lone surrogates as they are.
+fn map_well_formed(string: &[u16], f: impl Fn(&str) -> String) -> Vec<u16> {
+    let mut result = vec![];
+    let mut part = String::new();
+    for c in std::char::decode_utf16(string.iter().copied()) {
+        match c {
+            Ok(c) => part.push(c),
+            Err(err) => {
+                result.extend(f(&part).encode_utf16());
+                result.push(err.unpaired_surrogate());
+                part.clear();
+            }
+        }
+    }
+    result.extend(f(&part).encode_utf16());
+    result
+}
+
+/// Removes white space from the ``start`` and/or the ``end`` of ``string``.
+fn trim_white_space(string: &[u16], start: bool, end: bool) -> &[u16] {
+    let is_white_space_unit =
+        |unit: &u16| std::char::from_u32(u32::from(*unit)).is_some_and(is_white_space);
+    let from = if start {
+        string
+            .iter()
+            .position(|unit| !is_white_space_unit(unit))
+            .unwrap_or(string.len())
+    } else {
+        0
+    };
+    let to = if end {
+        string
+            .iter()
+            .rposition(|unit| !is_white_space_unit(unit))
+            .map_or(from, |p| p + 1)
+    } else {
+        string.len()
+    };
+    &string[from..to.max(from)]
+}
+
+/// WhiteSpace and LineTerminator, which differ from char::is_whitespace() in U+0085 and U+FEFF.
+/// https://tc39.github.io/ecma262/#sec-white-space
+fn is_white_space(c: char) -> bool {
+    c == '\u{feff}' || (c.is_whitespace() && c != '\u{85}')
+}
+
+/// String.prototype\[@@iterator\]
+pub fn string_prototype_iterator(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    if this.is_undefined() || this.is_null() {
+        return Err(vm
+            .current_context
+            .error_type("String.prototype[Symbol.iterator] called on null or undefined"));
+    }
+
+    let string = vm.factory.utf16_string(this.to_utf16());
+    let iter = vm.factory.string_iterator(string);
+    Ok(iter)
+}
+
+/// %StringIteratorPrototype%.next
+pub fn string_iterator_prototype_next(vm: &mut VM, _args: &[Value], this: Value) -> VMValueResult {
+    let mut obj_info = match this {
+        Value::Object(_) => this.get_object_info(),
+        _ => {
+            return Err(vm
+                .current_context
+                .error_type("this is not a String Iterator"))
+        }
+    };
+    let info = match obj_info.kind {
+        ObjectKind::StringIterator(ref mut info) => info,
+        _ => {
+            return Err(vm
+                .current_context
+                .error_type("this is not a String Iterator"))
+        }
+    };
+
+    if info.iterated.is_undefined() {
+        return Ok(create_iter_result_object(
+            &mut vm.factory,
+            Value::undefined(),
+            true,
+        ));
+    }
+
+    // Yields code points, where a lone surrogate is a code point by itself.
+    let string = info.iterated.into_utf16();
+    match string.get(info.next_index) {
+        Some(&first) => {
+            let len = match string.get(info.next_index + 1) {
+                Some(&second) if is_lead_surrogate(first) && is_trail_surrogate(second) => 2,
+                _ => 1,
+            };
+            let code_point = &string[info.next_index..info.next_index + len];
+            info.next_index += len;
+            let result = string_from_utf16(vm, code_point);
+            Ok(create_iter_result_object(&mut vm.factory, result, false))
+        }
+        None => {
+            info.iterated = Value::undefined();
+            Ok(create_iter_result_object(
+                &mut vm.factory,
+                Value::undefined(),
+                true,
+            ))
+        }
+    }
 }
@@ -7,13 +7,35 @@ pub fn symbol(factory: &mut Factory) -> Value {
     let obj = factory.generate_builtin_constructor(
         "Symbol",
         symbol_constructor,
+        0,
         factory.object_prototypes.symbol,
     );
 
     // Symbol.for
-    obj.set_property("for", factory.builtin_function("for", symbol_for));
+    obj.set_property("for", factory.builtin_function("for", symbol_for, 1));
     // Symbol.keyFor
-    obj.set_property("keyFor", factory.builtin_function("keyFor", symbol_key_for));
+    obj.set_property(
+        "keyFor",
+        factory.builtin_function("keyFor", symbol_key_for, 1),
+    );
+    // Symbol.asyncIterator
+    obj.set_property("asyncIterator", factory.symbols.async_iterator);
+    // Symbol.hasInstance
+    obj.set_property("hasInstance", factory.symbols.has_instance);
+    // Symbol.iterator
+    obj.set_property("iterator", factory.symbols.iterator);
+    // Symbol.match
+    obj.set_property("match", factory.symbols.match_);
+    // Symbol.matchAll
+    obj.set_property("matchAll", factory.symbols.match_all);
+    // Symbol.replace
+    obj.set_property("replace", factory.symbols.replace);
+    // Symbol.search
+    obj.set_property("search", factory.symbols.search);
+    // Symbol.split
+    obj.set_property("split", factory.symbols.split);
+    // Symbol.toPrimitive
+    obj.set_property("toPrimitive", factory.symbols.to_primitive);
     obj
 }
 
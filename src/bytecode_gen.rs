@@ -134,6 +134,18 @@ impl<'a> ByteCodeGenerator<'a> {
     pub fn append_ge(&self, iseq: &mut ByteCode) {
         iseq.push(VMInst::GE);
     }
+    pub fn append_in(&self, iseq: &mut ByteCode) {
+        iseq.push(VMInst::IN);
+    }
+    pub fn append_instance_of(&self, iseq: &mut ByteCode) {
+        iseq.push(VMInst::INSTANCE_OF);
+    }
+    pub fn append_delete(&self, iseq: &mut ByteCode) {
+        iseq.push(VMInst::DELETE);
+    }
+    pub fn append_import_call(&self, iseq: &mut ByteCode) {
+        iseq.push(VMInst::IMPORT_CALL);
+    }
     pub fn append_eq(&self, iseq: &mut ByteCode) {
         iseq.push(VMInst::EQ);
     }
@@ -197,6 +209,11 @@ impl<'a> ByteCodeGenerator<'a> {
         self.append_int32(argc as i32, iseq);
     }
 
+    pub fn append_call_with_this(&self, argc: u32, iseq: &mut ByteCode) {
+        iseq.push(VMInst::CALL_WITH_THIS);
+        self.append_int32(argc as i32, iseq);
+    }
+
     pub fn append_jmp(&self, dst: i32, iseq: &mut ByteCode) {
         iseq.push(VMInst::JMP);
         self.append_int32(dst, iseq);
@@ -211,6 +228,10 @@ impl<'a> ByteCodeGenerator<'a> {
         iseq.push(VMInst::RETURN_SUB);
     }
 
+    pub fn append_drop_sub(&self, iseq: &mut ByteCode) {
+        iseq.push(VMInst::DROP_SUB);
+    }
+
     pub fn append_jmp_sub(&self, dst: i32, iseq: &mut ByteCode) {
         iseq.push(VMInst::JMP_SUB);
         self.append_int32(dst, iseq);
@@ -231,6 +252,12 @@ impl<'a> ByteCodeGenerator<'a> {
         self.append_int32(id, iseq);
     }
 
+    pub fn append_delete_value(&mut self, name: &str, iseq: &mut ByteCode) {
+        let id = self.constant_table.add_string(name.to_string()) as i32;
+        iseq.push(VMInst::DELETE_VALUE);
+        self.append_int32(id, iseq);
+    }
+
     pub fn append_set_value(&mut self, name: &String, iseq: &mut ByteCode) {
         let id = self.constant_table.add_string(name.clone()) as i32;
         iseq.push(VMInst::SET_VALUE);
@@ -267,6 +294,87 @@ impl<'a> ByteCodeGenerator<'a> {
         iseq.push(VMInst::TYPEOF);
     }
 
+    pub fn append_to_string(&mut self, iseq: &mut ByteCode) {
+        iseq.push(VMInst::TO_STRING);
+    }
+
+    pub fn append_get_iterator(&mut self, iseq: &mut ByteCode) {
+        iseq.push(VMInst::GET_ITERATOR);
+    }
+
+    pub fn append_enumerate(&mut self, iseq: &mut ByteCode) {
+        iseq.push(VMInst::ENUMERATE);
+    }
+
+    pub fn append_iter_next(&mut self, dst: i32, iseq: &mut ByteCode) {
+        iseq.push(VMInst::ITER_NEXT);
+        self.append_int32(dst, iseq);
+    }
+
+    pub fn append_create_class(&self, id: usize, iseq: &mut ByteCode) {
+        iseq.push(VMInst::CREATE_CLASS);
+        self.append_int32(id as i32, iseq);
+    }
+
+    pub fn append_super_call(&self, iseq: &mut ByteCode) {
+        iseq.push(VMInst::SUPER_CALL);
+    }
+
+    pub fn append_bind_this(&self, iseq: &mut ByteCode) {
+        iseq.push(VMInst::BIND_THIS);
+    }
+
+    pub fn append_get_super_member(&self, iseq: &mut ByteCode) {
+        iseq.push(VMInst::GET_SUPER_MEMBER);
+    }
+
+    pub fn append_iter_value(&self, iseq: &mut ByteCode) {
+        iseq.push(VMInst::ITER_VALUE);
+    }
+
+    pub fn append_iter_rest(&self, iseq: &mut ByteCode) {
+        iseq.push(VMInst::ITER_REST);
+    }
+
+    pub fn append_enter_iter(&self, iseq: &mut ByteCode) {
+        iseq.push(VMInst::ENTER_ITER);
+    }
+
+    /// ``enter_iter_pos`` is the position of the ENTER_ITER instruction of the iterator.
+    pub fn append_iter_close(&self, enter_iter_pos: usize, iseq: &mut ByteCode) {
+        let offset = iseq.len() - enter_iter_pos;
+        iseq.push(VMInst::ITER_CLOSE);
+        self.append_int32(offset as i32, iseq);
+    }
+
+    /// ``enter_iter_pos`` is the position of the ENTER_ITER instruction of the iterator.
+    pub fn append_iter_close_throw(&self, enter_iter_pos: usize, iseq: &mut ByteCode) {
+        let offset = iseq.len() - enter_iter_pos;
+        iseq.push(VMInst::ITER_CLOSE_THROW);
+        self.append_int32(offset as i32, iseq);
+    }
+
+    pub fn append_copy_data_properties(&self, excluded_keys: usize, iseq: &mut ByteCode) {
+        iseq.push(VMInst::COPY_DATA_PROPERTIES);
+        self.append_int32(excluded_keys as i32, iseq);
+    }
+
+    pub fn append_create_regexp(&self, iseq: &mut ByteCode) {
+        iseq.push(VMInst::CREATE_REGEXP);
+    }
+
+    pub fn append_await(&self, iseq: &mut ByteCode) {
+        iseq.push(VMInst::AWAIT);
+    }
+
+    pub fn append_yield(&self, iseq: &mut ByteCode) {
+        iseq.push(VMInst::YIELD);
+    }
+
+    pub fn append_yield_delegate(&self, iseq: &mut ByteCode) {
+        iseq.push(VMInst::YIELD_DELEGATE);
+    }
+
     // Utils
 
     pub fn append_int8(&self, n: i8, iseq: &mut ByteCode) {
@@ -361,6 +469,10 @@ pub fn show_inst(code: &ByteCode, i: usize, const_table: &constant::ConstantTabl
                 let int32 = read_int32(code, i + 1);
                 format!("CallMethod {}", int32)
             }
+            VMInst::CALL_WITH_THIS => {
+                let int32 = read_int32(code, i + 1);
+                format!("CallWithThis {}", int32)
+            }
             VMInst::GET_VALUE => {
                 let int32 = read_int32(code, i + 1);
                 let name = const_table.get(int32 as usize).as_string();
@@ -371,6 +483,11 @@ pub fn show_inst(code: &ByteCode, i: usize, const_table: &constant::ConstantTabl
                 let name = const_table.get(int32 as usize).as_string();
                 format!("SetValue '{}'", name)
             }
+            VMInst::DELETE_VALUE => {
+                let int32 = read_int32(code, i + 1);
+                let name = const_table.get(int32 as usize).as_string();
+                format!("DeleteValue '{}'", name)
+            }
             VMInst::DECL_VAR => {
                 let int32 = read_int32(code, i + 1);
                 let name = const_table.get(int32 as usize).as_string();
@@ -395,6 +512,22 @@ pub fn show_inst(code: &ByteCode, i: usize, const_table: &constant::ConstantTabl
                 let int32 = read_int32(code, i + 1);
                 format!("JmpSub {:05}", i as i32 + int32 + 5)
             }
+            VMInst::ITER_NEXT => {
+                let int32 = read_int32(code, i + 1);
+                format!("IterNext {:05}", i as i32 + int32 + 5)
+            }
+            VMInst::COPY_DATA_PROPERTIES => {
+                let int32 = read_int32(code, i + 1);
+                format!("CopyDataProperties {}", int32)
+            }
+            VMInst::ITER_CLOSE => {
+                let int32 = read_int32(code, i + 1);
+                format!("IterClose {:05}", i as i32 - int32)
+            }
+            VMInst::ITER_CLOSE_THROW => {
+                let int32 = read_int32(code, i + 1);
+                format!("IterCloseThrow {:05}", i as i32 - int32)
+            }
             _ => inst_to_inst_name(code[i]).to_string(),
         }
     )
@@ -443,6 +576,7 @@ pub fn inst_to_inst_name(inst: u8) -> &'static str {
         VMInst::JMP => "Jmp",
         VMInst::CALL => "Call",
         VMInst::CALL_METHOD => "CallMethod",
+        VMInst::CALL_WITH_THIS => "CallWithThis",
         VMInst::RETURN => "Return",
         VMInst::DOUBLE => "Double",
         VMInst::POP => "Pop",
@@ -467,6 +601,30 @@ pub fn inst_to_inst_name(inst: u8) -> &'static str {
         VMInst::EXP => "Exp",
         VMInst::PUSH_SEPERATOR => "PushSeperator",
         VMInst::SPREAD_ARRAY => "SpreadArray",
+        VMInst::GET_ITERATOR => "GetIterator",
+        VMInst::ENUMERATE => "Enumerate",
+        VMInst::ITER_NEXT => "IterNext",
+        VMInst::CREATE_CLASS => "CreateClass",
+        VMInst::SUPER_CALL => "SuperCall",
+        VMInst::BIND_THIS => "BindThis",
+        VMInst::GET_SUPER_MEMBER => "GetSuperMember",
+        VMInst::ITER_VALUE => "IterValue",
+        VMInst::ITER_REST => "IterRest",
+        VMInst::COPY_DATA_PROPERTIES => "CopyDataProperties",
+        VMInst::CREATE_REGEXP => "CreateRegExp",
+        VMInst::AWAIT => "Await",
+        VMInst::YIELD => "Yield",
+        VMInst::YIELD_DELEGATE => "YieldDelegate",
+        VMInst::IN => "In",
+        VMInst::INSTANCE_OF => "InstanceOf",
+        VMInst::DELETE => "Delete",
+        VMInst::IMPORT_CALL => "ImportCall",
+        VMInst::DROP_SUB => "DropSub",
+        VMInst::ENTER_ITER => "EnterIter",
+        VMInst::ITER_CLOSE => "IterClose",
+        VMInst::ITER_CLOSE_THROW => "IterCloseThrow",
+        VMInst::TO_STRING => "ToString",
+        VMInst::DELETE_VALUE => "DeleteValue",
         _ => "???",
     }
 }
@@ -538,20 +696,48 @@ pub mod VMInst {
     pub const JMP_SUB: u8 = 0x43;
     pub const RETURN_SUB: u8 = 0x44;
     pub const TYPEOF: u8 = 0x45;
+    pub const GET_ITERATOR: u8 = 0x49;
+    pub const ENUMERATE: u8 = 0x4a;
+    pub const ITER_NEXT: u8 = 0x4b;
+    pub const CREATE_CLASS: u8 = 0x4c;
+    pub const SUPER_CALL: u8 = 0x4d;
+    pub const BIND_THIS: u8 = 0x4e;
+    pub const GET_SUPER_MEMBER: u8 = 0x4f;
+    pub const ITER_VALUE: u8 = 0x50;
+    pub const ITER_REST: u8 = 0x51;
+    pub const COPY_DATA_PROPERTIES: u8 = 0x52;
+    pub const CREATE_REGEXP: u8 = 0x53;
+    pub const AWAIT: u8 = 0x54;
+    pub const YIELD: u8 = 0x55;
+    pub const YIELD_DELEGATE: u8 = 0x56;
+    pub const IN: u8 = 0x57;
+    pub const INSTANCE_OF: u8 = 0x58;
+    pub const DELETE: u8 = 0x59;
+    pub const CALL_WITH_THIS: u8 = 0x5a;
+    pub const IMPORT_CALL: u8 = 0x5b;
+    pub const DROP_SUB: u8 = 0x5c;
+    pub const ENTER_ITER: u8 = 0x5d;
+    pub const ITER_CLOSE: u8 = 0x5e;
+    pub const ITER_CLOSE_THROW: u8 = 0x5f;
+    pub const TO_STRING: u8 = 0x60;
+    pub const DELETE_VALUE: u8 = 0x61;
 
     pub fn get_inst_size(inst: u8) -> Option<usize> {
         match inst {
             THROW | RETURN_SUB | SET_OUTER_ENV | POP_ENV | TYPEOF | PUSH_NULL => Some(1),
             CONSTRUCT | CREATE_OBJECT | PUSH_CONST | PUSH_INT32 | JMP_IF_FALSE | RETURN_TRY
             | DECL_VAR | LOOP_START | JMP | SET_VALUE | GET_VALUE | CALL | JMP_SUB
-            | CALL_METHOD | PUSH_ENV | DECL_LET | DECL_CONST => Some(5),
+            | CALL_METHOD | PUSH_ENV | DECL_LET | DECL_CONST | ITER_NEXT | CREATE_CLASS
+            | COPY_DATA_PROPERTIES | CALL_WITH_THIS | ITER_CLOSE | ITER_CLOSE_THROW
+            | DELETE_VALUE => Some(5),
             PUSH_INT8 => Some(2),
             PUSH_FALSE | END | PUSH_TRUE | PUSH_THIS | ADD | SUB | MUL | DIV | REM | LT | EXP
             | PUSH_ARGUMENTS | NEG | POSI | GT | LE | GE | EQ | NE | GET_MEMBER | RETURN | SNE
             | ZFSHR | POP | DOUBLE | AND | COND_OP | OR | SEQ | SET_MEMBER | LNOT
-            | PUSH_UNDEFINED | LAND | SHR | SHL | XOR | LOR | NOT | CREATE_ARRAY | SPREAD_ARRAY => {
-                Some(1)
-            }
+            | PUSH_UNDEFINED | LAND | SHR | SHL | XOR | LOR | NOT | CREATE_ARRAY | SPREAD_ARRAY
+            | GET_ITERATOR | ENUMERATE | SUPER_CALL | BIND_THIS | GET_SUPER_MEMBER | ITER_VALUE
+            | ITER_REST | CREATE_REGEXP | AWAIT | YIELD | YIELD_DELEGATE | IN | INSTANCE_OF
+            | DELETE | IMPORT_CALL | DROP_SUB | ENTER_ITER | TO_STRING => Some(1),
             _ => None,
         }
     }
@@ -1,6 +1,12 @@
 use crate::vm::{
     constant,
-    jsvalue::{function, object, prototype, value::Value},
+    jsvalue::{
+        collection, function, module, object,
+        promise::{Job, JobQueue, PromiseReaction},
+        prototype, string, symbol,
+        value::Value,
+    },
+    module::ModuleMap,
     vm::*,
 };
 use rustc_hash::{FxHashMap, FxHashSet};
@@ -40,6 +46,8 @@ pub struct MemoryAllocator {
     pub state: GCState,
     white: MarkState,
     counter: u32,
+    /// The entries of the WeakMaps and WeakSets reached in the current marking.
+    weak_collections: Vec<*mut collection::MapData>,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -79,6 +87,7 @@ impl MemoryAllocator {
             state: GCState::Initial,
             white: MarkState::White,
             counter: 0,
+            weak_collections: vec![],
         }
     }
 
@@ -92,13 +101,17 @@ impl MemoryAllocator {
 }
 
 impl MemoryAllocator {
+    #[allow(clippy::too_many_arguments)]
     pub fn mark(
         &mut self,
         global: LexicalEnvironmentRef,
         object_prototypes: &prototype::ObjectPrototypes,
+        symbols: &symbol::WellKnownSymbols,
         constant_table: &constant::ConstantTable,
         cur_context: &ExecContext,
         saved_context: &Vec<ExecContext>,
+        job_queue: &JobQueue,
+        module_map: &ModuleMap,
     ) {
         self.counter += 1;
         if self.counter < 100 {
@@ -119,7 +132,40 @@ impl MemoryAllocator {
                 object_prototypes.object.initial_trace(&mut markset);
                 object_prototypes.function.initial_trace(&mut markset);
                 object_prototypes.string.initial_trace(&mut markset);
+                object_prototypes.error.initial_trace(&mut markset);
+                object_prototypes.eval_error.initial_trace(&mut markset);
+                object_prototypes.range_error.initial_trace(&mut markset);
+                object_prototypes
+                    .reference_error
+                    .initial_trace(&mut markset);
+                object_prototypes.syntax_error.initial_trace(&mut markset);
+                object_prototypes.type_error.initial_trace(&mut markset);
+                object_prototypes.uri_error.initial_trace(&mut markset);
+                object_prototypes
+                    .aggregate_error
+                    .initial_trace(&mut markset);
                 object_prototypes.array.initial_trace(&mut markset);
+                object_prototypes.iterator.initial_trace(&mut markset);
+                object_prototypes.array_iterator.initial_trace(&mut markset);
+                object_prototypes
+                    .string_iterator
+                    .initial_trace(&mut markset);
+                object_prototypes.regexp.initial_trace(&mut markset);
+                object_prototypes
+                    .regexp_string_iterator
+                    .initial_trace(&mut markset);
+                object_prototypes.promise.initial_trace(&mut markset);
+                object_prototypes.date.initial_trace(&mut markset);
+                object_prototypes.number.initial_trace(&mut markset);
+                object_prototypes.map.initial_trace(&mut markset);
+                object_prototypes.map_iterator.initial_trace(&mut markset);
+                object_prototypes.set.initial_trace(&mut markset);
+                object_prototypes.set_iterator.initial_trace(&mut markset);
+                object_prototypes.weak_map.initial_trace(&mut markset);
+                object_prototypes.weak_set.initial_trace(&mut markset);
+                object_prototypes.generator.initial_trace(&mut markset);
+
+                symbols.initial_trace(&mut markset);
 
                 constant_table.initial_trace(&mut markset);
 
@@ -128,6 +174,10 @@ impl MemoryAllocator {
                     context.this.initial_trace(&mut markset);
                 }
 
+                job_queue.initial_trace(&mut markset);
+
+                module_map.initial_trace(&mut markset);
+
                 self.white = self.white.flip_white();
 
                 self.roots = &markset | &self.locked;
@@ -141,6 +191,12 @@ impl MemoryAllocator {
                     unsafe { &*root.0 }.trace(self, &mut markset);
                 }
 
+                // The value of a weak entry is reachable only if its key is (ephemeron), which is
+                // known once everything else has been marked.
+                if markset.is_empty() {
+                    self.trace_ephemerons(&mut markset);
+                }
+
                 self.roots = markset;
 
                 if self.roots.len() == 0 {
@@ -150,6 +206,10 @@ impl MemoryAllocator {
                 }
             }
             GCState::ReadyToSweep => {
+                for data in mem::take(&mut self.weak_collections) {
+                    unsafe { &mut *data }.retain(|key| !self.is_unreachable(key));
+                }
+
                 let white = self.white;
                 let mut size = 0;
                 self.allocated_memory.retain(|obj, mark| {
@@ -171,6 +231,29 @@ impl MemoryAllocator {
         }
     }
 
+    /// Whether ``val`` will be released by the coming sweep.
+    fn is_unreachable(&self, val: Value) -> bool {
+        let mark = match val {
+            Value::Object(obj) => self.allocated_memory.get(&GcTargetKey(obj)),
+            Value::String(s) => self.allocated_memory.get(&GcTargetKey(s)),
+            _ => None,
+        };
+        match mark {
+            Some(MarkState::Black) | Some(MarkState::NeverReleased) | None => false,
+            Some(mark) => *mark != self.white,
+        }
+    }
+
+    fn trace_ephemerons(&mut self, markset: &mut MarkSet) {
+        for data in self.weak_collections.clone() {
+            for (key, val) in unsafe { &*data }.iter() {
+                if !self.is_unreachable(*key) {
+                    val.trace(self, markset);
+                }
+            }
+        }
+    }
+
     pub fn gray(&mut self, object: GcTargetKey) {
         unsafe { &*object.0 }.initial_trace(&mut self.roots);
     }
@@ -184,6 +267,12 @@ impl MemoryAllocator {
         self.roots = &self.roots | &self.locked;
     }
 
+    /// Keeps the object or string ``val`` points to from being collected.
+    pub fn lock_value(&mut self, val: Value) {
+        val.initial_trace(&mut self.locked);
+        self.roots = &self.roots | &self.locked;
+    }
+
     pub fn unlock<T: GcTarget>(&mut self, val: T) {
         let mut map = MarkSet::default();
         val.initial_trace(&mut map);
@@ -239,28 +328,51 @@ impl ExecContext {
             val.initial_trace(markset);
         }
     }
-    /*
+
     fn trace(&self, allocator: &mut MemoryAllocator, markset: &mut MarkSet) {
         mark_if_white!(allocator, markset, self.lexical_environment.as_ptr());
         mark_if_white!(allocator, markset, self.variable_environment.as_ptr());
         for env in &self.saved_lexical_environment {
             mark_if_white!(allocator, markset, env.as_ptr());
         }
+        for val_boxed in &self.stack {
+            let val: Value = (*val_boxed).into();
+            val.trace(allocator, markset);
+        }
+        self.this.trace(allocator, markset);
     }
-    */
 }
 
 impl GcTarget for LexicalEnvironment {
     fn initial_trace(&self, markset: &mut MarkSet) {
         fn trace_record(record: &EnvironmentRecord, markset: &mut MarkSet) {
             match record {
-                EnvironmentRecord::Declarative(record)
-                | EnvironmentRecord::Function { record, .. }
-                | EnvironmentRecord::Module { record, .. } => {
-                    for (_, val) in record {
+                EnvironmentRecord::Declarative(record) => {
+                    for val in record.values() {
                         val.initial_trace(markset);
                     }
                 }
+                EnvironmentRecord::Function {
+                    this,
+                    new_target,
+                    record,
+                } => {
+                    this.initial_trace(markset);
+                    new_target.initial_trace(markset);
+                    for val in record.values() {
+                        val.initial_trace(markset);
+                    }
+                }
+                EnvironmentRecord::Module {
+                    record, imports, ..
+                } => {
+                    for val in record.values() {
+                        val.initial_trace(markset);
+                    }
+                    for (env, _) in imports.values() {
+                        mark!(markset, env.as_ptr());
+                    }
+                }
                 EnvironmentRecord::Object(obj) | EnvironmentRecord::Global(obj) => {
                     obj.initial_trace(markset)
                 }
@@ -281,13 +393,32 @@ impl GcTarget for LexicalEnvironment {
             markset: &mut MarkSet,
         ) {
             match record {
-                EnvironmentRecord::Declarative(record)
-                | EnvironmentRecord::Function { record, .. }
-                | EnvironmentRecord::Module { record, .. } => {
-                    for (_, val) in record {
+                EnvironmentRecord::Declarative(record) => {
+                    for val in record.values() {
+                        val.trace(allocator, markset);
+                    }
+                }
+                EnvironmentRecord::Function {
+                    this,
+                    new_target,
+                    record,
+                } => {
+                    this.trace(allocator, markset);
+                    new_target.trace(allocator, markset);
+                    for val in record.values() {
                         val.trace(allocator, markset);
                     }
                 }
+                EnvironmentRecord::Module {
+                    record, imports, ..
+                } => {
+                    for val in record.values() {
+                        val.trace(allocator, markset);
+                    }
+                    for (env, _) in imports.values() {
+                        mark_if_white!(allocator, markset, env.as_ptr());
+                    }
+                }
                 EnvironmentRecord::Object(obj) | EnvironmentRecord::Global(obj) => {
                     obj.trace(allocator, markset)
                 }
@@ -306,12 +437,12 @@ impl GcTarget for LexicalEnvironment {
     }
 }
 
-impl GcTarget for ::std::ffi::CString {
+impl GcTarget for string::StringInfo {
     fn initial_trace(&self, _markset: &mut MarkSet) {}
     fn trace(&self, _allocator: &mut MemoryAllocator, _markset: &mut MarkSet) {}
     fn free(&self) -> usize {
         // mem::drop(self);
-        mem::size_of::<::std::ffi::CString>()
+        mem::size_of::<string::StringInfo>()
     }
 }
 
@@ -340,16 +471,24 @@ impl Value {
 impl GcTarget for object::ObjectInfo {
     fn initial_trace(&self, markset: &mut MarkSet) {
         self.kind.initial_trace(markset);
+        self.prototype.initial_trace(markset);
         for (_, property) in &self.property {
             property.initial_trace(markset)
         }
+        for property in self.sym_property.values() {
+            property.initial_trace(markset)
+        }
     }
 
     fn trace(&self, allocator: &mut MemoryAllocator, markset: &mut MarkSet) {
         self.kind.trace(allocator, markset);
+        self.prototype.trace(allocator, markset);
         for (_, property) in &self.property {
             property.trace(allocator, markset)
         }
+        for property in self.sym_property.values() {
+            property.trace(allocator, markset)
+        }
     }
 
     fn free(&self) -> usize {
@@ -390,6 +529,17 @@ impl object::ObjectKind {
                         mark!(markset, env.as_ptr());
                     }
                 }
+                function::FunctionObjectKind::Bound {
+                    target,
+                    this,
+                    ref args,
+                } => {
+                    target.initial_trace(markset);
+                    this.initial_trace(markset);
+                    for arg in args {
+                        arg.initial_trace(markset)
+                    }
+                }
                 function::FunctionObjectKind::Builtin(_) => {}
             },
             object::ObjectKind::Array(ary_info) => {
@@ -397,8 +547,57 @@ impl object::ObjectKind {
                     elem.initial_trace(markset)
                 }
             }
+            object::ObjectKind::ArrayIterator(iter_info) => {
+                iter_info.iterated.initial_trace(markset)
+            }
+            object::ObjectKind::StringIterator(iter_info) => {
+                iter_info.iterated.initial_trace(markset)
+            }
+            object::ObjectKind::ForInIterator(iter_info) => iter_info.object.initial_trace(markset),
+            object::ObjectKind::RegExpStringIterator(iter_info) => {
+                iter_info.iterating_regexp.initial_trace(markset);
+                iter_info.iterated.initial_trace(markset)
+            }
+            object::ObjectKind::Promise(info) => {
+                info.result.initial_trace(markset);
+                for reaction in info.fulfill_reactions.iter().chain(&info.reject_reactions) {
+                    reaction.initial_trace(markset)
+                }
+            }
+            object::ObjectKind::AsyncFunction(info) => {
+                if let Some(ref context) = info.context {
+                    context.initial_trace(markset);
+                    context.this.initial_trace(markset);
+                }
+                info.promise.initial_trace(markset)
+            }
+            object::ObjectKind::Generator(info) => {
+                if let Some(ref context) = info.context {
+                    context.initial_trace(markset);
+                    context.this.initial_trace(markset);
+                }
+            }
+            object::ObjectKind::ModuleNamespace(info) => {
+                for (_, binding) in &info.exports {
+                    binding.initial_trace(markset)
+                }
+            }
+            object::ObjectKind::RegExp(_) => {}
             object::ObjectKind::Symbol(_) => {}
             object::ObjectKind::Error(_) => {}
+            object::ObjectKind::Date(_) => {}
+            object::ObjectKind::Number(_) => {}
+            object::ObjectKind::String(s) => s.initial_trace(markset),
+            object::ObjectKind::Map(data) | object::ObjectKind::Set(data) => {
+                for (key, val) in data.iter() {
+                    key.initial_trace(markset);
+                    val.initial_trace(markset)
+                }
+            }
+            // Entries of a weak collection are traced only through their keys during marking.
+            object::ObjectKind::WeakMap(_) | object::ObjectKind::WeakSet(_) => {}
+            object::ObjectKind::MapIterator(iter_info) => iter_info.iterated.initial_trace(markset),
+            object::ObjectKind::SetIterator(iter_info) => iter_info.iterated.initial_trace(markset),
             object::ObjectKind::Ordinary => {}
         }
     }
@@ -411,6 +610,17 @@ impl object::ObjectKind {
                         mark_if_white!(allocator, markset, env.as_ptr());
                     }
                 }
+                function::FunctionObjectKind::Bound {
+                    target,
+                    this,
+                    ref args,
+                } => {
+                    target.trace(allocator, markset);
+                    this.trace(allocator, markset);
+                    for arg in args {
+                        arg.trace(allocator, markset)
+                    }
+                }
                 function::FunctionObjectKind::Builtin(_) => {}
             },
             object::ObjectKind::Array(ary_info) => {
@@ -418,13 +628,153 @@ impl object::ObjectKind {
                     elem.trace(allocator, markset)
                 }
             }
+            object::ObjectKind::ArrayIterator(iter_info) => {
+                iter_info.iterated.trace(allocator, markset)
+            }
+            object::ObjectKind::StringIterator(iter_info) => {
+                iter_info.iterated.trace(allocator, markset)
+            }
+            object::ObjectKind::ForInIterator(iter_info) => {
+                iter_info.object.trace(allocator, markset)
+            }
+            object::ObjectKind::RegExpStringIterator(iter_info) => {
+                iter_info.iterating_regexp.trace(allocator, markset);
+                iter_info.iterated.trace(allocator, markset)
+            }
+            object::ObjectKind::Promise(info) => {
+                info.result.trace(allocator, markset);
+                for reaction in info.fulfill_reactions.iter().chain(&info.reject_reactions) {
+                    reaction.trace(allocator, markset)
+                }
+            }
+            object::ObjectKind::AsyncFunction(info) => {
+                if let Some(ref context) = info.context {
+                    context.trace(allocator, markset)
+                }
+                info.promise.trace(allocator, markset)
+            }
+            object::ObjectKind::Generator(info) => {
+                if let Some(ref context) = info.context {
+                    context.trace(allocator, markset)
+                }
+            }
+            object::ObjectKind::ModuleNamespace(info) => {
+                for (_, binding) in &info.exports {
+                    binding.trace(allocator, markset)
+                }
+            }
+            object::ObjectKind::RegExp(_) => {}
             object::ObjectKind::Symbol(_) => {}
             object::ObjectKind::Error(_) => {}
+            object::ObjectKind::Date(_) => {}
+            object::ObjectKind::Number(_) => {}
+            object::ObjectKind::String(s) => s.trace(allocator, markset),
+            object::ObjectKind::Map(data) | object::ObjectKind::Set(data) => {
+                for (key, val) in data.iter() {
+                    key.trace(allocator, markset);
+                    val.trace(allocator, markset)
+                }
+            }
+            object::ObjectKind::WeakMap(data) | object::ObjectKind::WeakSet(data) => {
+                allocator
+                    .weak_collections
+                    .push(&**data as *const collection::MapData as *mut collection::MapData);
+            }
+            object::ObjectKind::MapIterator(iter_info) => {
+                iter_info.iterated.trace(allocator, markset)
+            }
+            object::ObjectKind::SetIterator(iter_info) => {
+                iter_info.iterated.trace(allocator, markset)
+            }
             object::ObjectKind::Ordinary => {}
         }
     }
 }
 
+impl module::ResolvedBinding {
+    fn initial_trace(&self, markset: &mut MarkSet) {
+        match self {
+            module::ResolvedBinding::Binding(env, _) => mark!(markset, env.as_ptr()),
+            module::ResolvedBinding::Namespace(namespace) => namespace.initial_trace(markset),
+        }
+    }
+
+    fn trace(&self, allocator: &mut MemoryAllocator, markset: &mut MarkSet) {
+        match self {
+            module::ResolvedBinding::Binding(env, _) => {
+                mark_if_white!(allocator, markset, env.as_ptr())
+            }
+            module::ResolvedBinding::Namespace(namespace) => namespace.trace(allocator, markset),
+        }
+    }
+}
+
+impl PromiseReaction {
+    fn initial_trace(&self, markset: &mut MarkSet) {
+        if let Some(capability) = self.capability {
+            capability.initial_trace(markset)
+        }
+        self.handler.initial_trace(markset)
+    }
+
+    fn trace(&self, allocator: &mut MemoryAllocator, markset: &mut MarkSet) {
+        if let Some(capability) = self.capability {
+            capability.trace(allocator, markset)
+        }
+        self.handler.trace(allocator, markset)
+    }
+}
+
+impl JobQueue {
+    fn initial_trace(&self, markset: &mut MarkSet) {
+        for job in &self.jobs {
+            match job {
+                Job::PromiseReaction { reaction, argument } => {
+                    reaction.initial_trace(markset);
+                    argument.initial_trace(markset)
+                }
+                Job::PromiseResolveThenable {
+                    promise,
+                    thenable,
+                    then,
+                } => {
+                    promise.initial_trace(markset);
+                    thenable.initial_trace(markset);
+                    then.initial_trace(markset)
+                }
+            }
+        }
+        for (promise, _) in &self.rejected_promises {
+            promise.initial_trace(markset)
+        }
+    }
+}
+
+impl ModuleMap {
+    fn initial_trace(&self, markset: &mut MarkSet) {
+        for module in &self.modules {
+            if let Some(env) = module.env {
+                mark!(markset, env.as_ptr())
+            }
+            if let Some(namespace) = module.namespace {
+                namespace.initial_trace(markset)
+            }
+            if let Some(error) = module.evaluation_error {
+                error.initial_trace(markset)
+            }
+        }
+        self.require_cache.initial_trace(markset)
+    }
+}
+
+impl symbol::WellKnownSymbols {
+    fn initial_trace(&self, markset: &mut MarkSet) {
+        for symbol in self.all() {
+            symbol.initial_trace(markset)
+        }
+    }
+}
+
 impl constant::ConstantTable {
     fn initial_trace(&self, markset: &mut MarkSet) {
         for const_ in &self.table {
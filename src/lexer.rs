@@ -1,11 +1,8 @@
 use crate::parser::Error;
-use crate::token::{convert_reserved_keyword, Kind, Symbol, Token};
+use crate::token::{convert_reserved_keyword, Keyword, Kind, Symbol, TemplateKind, Token};
 
 use std::collections::VecDeque;
 
-use encoding::all::UTF_16BE;
-use encoding::{DecoderTrap, Encoding};
-
 #[derive(Clone, Debug)]
 pub struct Lexer {
     pub code: String,
@@ -32,6 +29,10 @@ pub struct Lexer {
 
     /// Saved states
     pub states: Vec<usize>,
+
+    /// Depth of braces in each template substitution being read.
+    /// ``}`` at depth 0 resumes the template literal.
+    pub template_brace_depth: Vec<usize>,
 }
 
 impl Lexer {
@@ -45,6 +46,7 @@ impl Lexer {
             token_pos: 0,
             prev_token_pos: 0,
             states: vec![],
+            template_brace_depth: vec![],
         }
     }
 
@@ -52,7 +54,10 @@ impl Lexer {
     pub fn tokenize_all(&mut self) -> Result<(), Error> {
         loop {
             match self.tokenize() {
-                Ok(tok) => self.buf.push_back(tok),
+                Ok(mut tok) => {
+                    tok.end_pos = self.pos;
+                    self.buf.push_back(tok)
+                }
                 Err(Error::NormalEOF) => break,
                 Err(err) => {
                     // When error occurs in tokenizer, pos_line_list is not completed.
@@ -168,6 +173,11 @@ impl Lexer {
         }
     }
 
+    /// Get char position in the script just after the previous token.
+    pub fn get_prev_end_pos(&mut self) -> usize {
+        self.buf[self.token_pos - 1].end_pos
+    }
+
     /// Peek the next token and if it is ``kind``, get the next token, return true.
     /// Otherwise, return false.
     pub fn next_if(&mut self, kind: Kind) -> bool {
@@ -235,6 +245,20 @@ impl Lexer {
             'a'..='z' | 'A'..='Z' | '_' | '$' => self.read_identifier(),
             '0'..='9' => self.read_number(),
             '\'' | '\"' => self.read_string_literal(),
+            '`' => self.read_template_literal(),
+            '/' if self.is_regexp_allowed() => self.read_regexp_literal(),
+            '}' if self.template_brace_depth.last() == Some(&0) => self.read_template_literal(),
+            '{' | '}' => {
+                let tok = self.read_symbol()?;
+                if let Some(depth) = self.template_brace_depth.last_mut() {
+                    if tok.kind == Kind::Symbol(Symbol::OpeningBrace) {
+                        *depth += 1
+                    } else {
+                        *depth -= 1
+                    }
+                }
+                Ok(tok)
+            }
             '\n' => self.read_line_terminator(),
             c if c.is_whitespace() => {
                 self.skip_whitespace()?;
@@ -399,69 +423,261 @@ impl Lexer {
     fn read_string_literal(&mut self) -> Result<Token, Error> {
         let pos = self.pos;
         let quote = self.take_char()?;
-        let mut s = "".to_string();
+        // Kept as UTF-16 so that surrogate pairs written as two \u escapes are combined.
+        let mut units = vec![];
         loop {
             match self.take_char()? {
                 q if q == quote => break,
+                '\\' => units.extend(self.read_escaped_char()?),
+                c => units.extend(c.encode_utf16(&mut [0; 2]).iter()),
+            }
+        }
+        Ok(Token::new_string(String::from_utf16_lossy(&units), pos))
+    }
+
+    /// Read a template literal from ``\``` or ``}`` to the next ``${`` or ``\```.
+    /// https://tc39.github.io/ecma262/#sec-template-literal-lexical-components
+    fn read_template_literal(&mut self) -> Result<Token, Error> {
+        let pos = self.pos;
+        let is_head = self.take_char()? == '`';
+        if !is_head {
+            self.template_brace_depth.pop();
+        }
+
+        let start = self.pos;
+        // The cooked string is kept as UTF-16 so that surrogate pairs written as two \u escapes
+        // are combined. It is None once an invalid escape sequence is found.
+        let mut cooked = Some(vec![]);
+        let (end, is_tail) = loop {
+            let c = self
+                .take_char()
+                .map_err(|_| Error::UnexpectedEOF("unterminated template literal".to_string()))?;
+            match c {
+                '`' => break (self.pos - 1, true),
+                '$' if self.take_char_if('{')? => break (self.pos - 2, false),
+                '\\' => {
+                    let escaped = self.read_template_escape_sequence().map_err(|_| {
+                        Error::UnexpectedEOF("unterminated template literal".to_string())
+                    })?;
+                    cooked = match (cooked, escaped) {
+                        (Some(mut cooked), Some(units)) => {
+                            cooked.extend(units);
+                            Some(cooked)
+                        }
+                        _ => None,
+                    };
+                }
+                // Line terminators are normalized to LF.
+                '\r' | '\n' => {
+                    if c == '\r' {
+                        self.take_char_if('\n')?;
+                    }
+                    self.line += 1;
+                    self.pos_line_list.push((self.pos, self.line));
+                    if let Some(ref mut cooked) = cooked {
+                        cooked.push('\n' as u16)
+                    }
+                }
+                c => {
+                    if let Some(ref mut cooked) = cooked {
+                        cooked.extend(c.encode_utf16(&mut [0; 2]).iter())
+                    }
+                }
+            }
+        };
+        let cooked = cooked.map(|cooked| String::from_utf16_lossy(&cooked));
+
+        if !is_tail {
+            self.template_brace_depth.push(0);
+        }
+
+        let raw = self.code[start..end]
+            .replace("\r\n", "\n")
+            .replace('\r', "\n");
+        let kind = match (is_head, is_tail) {
+            (true, true) => TemplateKind::NoSubstitution,
+            (true, false) => TemplateKind::Head,
+            (false, false) => TemplateKind::Middle,
+            (false, true) => TemplateKind::Tail,
+        };
+        Ok(Token::new_template(kind, cooked, raw, pos))
+    }
+
+    /// Whether ``/`` starts a regular expression literal rather than a division.
+    /// The lexer doesn't know the syntactic context, so it is decided by the previous token.
+    fn is_regexp_allowed(&self) -> bool {
+        let prev = self
+            .buf
+            .iter()
+            .rev()
+            .find(|tok| tok.kind != Kind::LineTerminator);
+        match prev.map(|tok| &tok.kind) {
+            None => true,
+            Some(Kind::Identifier(_))
+            | Some(Kind::Number(_))
+            | Some(Kind::String(_))
+            | Some(Kind::RegExp(_, _))
+            | Some(Kind::Template(TemplateKind::NoSubstitution, _, _))
+            | Some(Kind::Template(TemplateKind::Tail, _, _))
+            | Some(Kind::Keyword(Keyword::This))
+            | Some(Kind::Keyword(Keyword::Super))
+            | Some(Kind::Keyword(Keyword::Arguments))
+            | Some(Kind::Symbol(Symbol::ClosingParen))
+            | Some(Kind::Symbol(Symbol::ClosingBoxBracket))
+            | Some(Kind::Symbol(Symbol::ClosingBrace))
+            | Some(Kind::Symbol(Symbol::Inc))
+            | Some(Kind::Symbol(Symbol::Dec)) => false,
+            _ => true,
+        }
+    }
+
+    /// https://tc39.github.io/ecma262/#sec-literals-regular-expression-literals
+    fn read_regexp_literal(&mut self) -> Result<Token, Error> {
+        let pos = self.pos;
+        assert_eq!(self.take_char()?, '/');
+
+        let mut pattern = "".to_string();
+        let mut in_class = false;
+        loop {
+            let c = match self.take_char() {
+                Ok('\n') | Err(_) => {
+                    return Err(Error::General(
+                        pos,
+                        "Invalid regular expression: missing /".to_string(),
+                    ))
+                }
+                Ok(c) => c,
+            };
+            match c {
+                '/' if !in_class => break,
+                '[' => in_class = true,
+                ']' => in_class = false,
                 '\\' => {
-                    for c in self.read_escaped_char()? {
-                        s.push(c)
+                    pattern.push(c);
+                    match self.take_char() {
+                        Ok(c) if c != '\n' => pattern.push(c),
+                        _ => {
+                            return Err(Error::General(
+                                pos,
+                                "Invalid regular expression: missing /".to_string(),
+                            ))
+                        }
                     }
+                    continue;
                 }
-                c => s.push(c),
+                _ => {}
             }
+            pattern.push(c)
         }
-        Ok(Token::new_string(s, pos))
+
+        let flags = self.take_char_while(|c| c.is_alphanumeric() || c == '_' || c == '$')?;
+
+        Ok(Token::new_regexp(pattern, flags, pos))
     }
 
-    // TODO: Support more escape sequences
-    fn read_escaped_char(&mut self) -> Result<Vec<char>, Error> {
+    /// Read an escape sequence in a template literal after ``\\``, and return its UTF-16 code
+    /// units. Returns None if the escape sequence is invalid, in which case only the characters
+    /// that can not end the template are consumed.
+    /// https://tc39.github.io/ecma262/#prod-TemplateEscapeSequence
+    fn read_template_escape_sequence(&mut self) -> Result<Option<Vec<u16>>, Error> {
         let c = self.take_char()?;
+        let code_point = match c {
+            // LineContinuation
+            '\n' | '\r' | '\u{2028}' | '\u{2029}' => {
+                if c == '\r' {
+                    self.take_char_if('\n')?;
+                }
+                self.line += 1;
+                self.pos_line_list.push((self.pos, self.line));
+                return Ok(Some(vec![]));
+            }
+            'b' => 0x08,
+            'f' => 0x0c,
+            'n' => 0x0a,
+            'r' => 0x0d,
+            't' => 0x09,
+            'v' => 0x0b,
+            '0' if !matches!(self.peek_char(), Ok(c) if c.is_ascii_digit()) => 0,
+            '0'..='9' => return Ok(None),
+            'x' | 'u' => match self.read_hex_escape_sequence(c)? {
+                Some(n) => n,
+                None => return Ok(None),
+            },
+            c => c as u32,
+        };
+        Ok(Some(match ::std::char::from_u32(code_point) {
+            Some(c) => c.encode_utf16(&mut [0; 2]).to_vec(),
+            // A surrogate is kept as a code unit to be paired with the following one.
+            None => vec![code_point as u16],
+        }))
+    }
+
+    /// Read the rest of a ``\\x`` or ``\\u`` escape sequence after ``c``, and return the code
+    /// point (or the surrogate code unit) it denotes. Returns None if it is malformed.
+    /// https://tc39.github.io/ecma262/#prod-HexEscapeSequence
+    /// https://tc39.github.io/ecma262/#prod-UnicodeEscapeSequence
+    fn read_hex_escape_sequence(&mut self, c: char) -> Result<Option<u32>, Error> {
+        fn hex_value(digits: &str) -> Option<u32> {
+            u32::from_str_radix(digits, 16).ok()
+        }
+
         Ok(match c {
-            '\'' | '"' | '?' | '\\' => vec![c],
-            'a' => vec!['\x07'],
-            'b' => vec!['\x08'],
-            'f' => vec!['\x0c'],
-            'n' => vec!['\x0a'],
-            'r' => vec!['\x0d'],
-            't' => vec!['\x09'],
-            'v' => vec!['\x0b'],
             'x' => {
-                let hex = self.take_char_while(|c| c.is_alphanumeric())?;
-                vec![self.read_hex_num(hex.as_str()) as u8 as char]
+                let digits = self.take_hex_digits(2)?;
+                hex_value(digits.as_str()).filter(|_| digits.len() == 2)
             }
-            'u' => {
-                let mut u8s = vec![];
-                loop {
-                    let hex = self.take_char_while(|c| c.is_alphanumeric())?;
-                    let mut i = 0;
-                    while i < hex.len() {
-                        u8s.push(
-                            self.read_hex_num(&hex[i..i + if i + 2 > hex.len() { 1 } else { 2 }])
-                                as u8,
-                        );
-                        i += 2;
-                    }
-                    if u8s.len() % 2 != 0 {
-                        // TODO: Support \u{X..X}
-                        unimplemented!("unsupported escape sequence");
-                    }
-                    let save_pos = self.pos;
-                    // TODO: Error handling
-                    if self.take_char()? == '\\' && self.take_char()? == 'u' {
-                        continue;
-                    } else {
-                        self.pos = save_pos;
-                        break;
-                    }
+            'u' if self.take_char_if('{')? => {
+                let digits = self.take_char_while(|c| c.is_ascii_hexdigit())?;
+                match hex_value(digits.as_str()) {
+                    Some(n) if n <= 0x10ffff && self.take_char_if('}')? => Some(n),
+                    _ => None,
                 }
-                UTF_16BE
-                    .decode(u8s.as_slice(), DecoderTrap::Strict)
-                    .unwrap()
-                    .chars()
-                    .collect::<Vec<char>>()
             }
-            _ => vec![c],
+            'u' => {
+                let digits = self.take_hex_digits(4)?;
+                hex_value(digits.as_str()).filter(|_| digits.len() == 4)
+            }
+            _ => None,
+        })
+    }
+
+    /// Take at most ``max`` hexadecimal digits.
+    fn take_hex_digits(&mut self, max: usize) -> Result<String, Error> {
+        let mut digits = "".to_string();
+        while digits.len() < max && matches!(self.peek_char(), Ok(c) if c.is_ascii_hexdigit()) {
+            digits.push(self.take_char()?);
+        }
+        Ok(digits)
+    }
+
+    // TODO: Support more escape sequences
+    fn read_escaped_char(&mut self) -> Result<Vec<u16>, Error> {
+        let pos = self.pos;
+        let c = self.take_char()?;
+        let code_point = match c {
+            '\'' | '"' | '?' | '\\' => c as u32,
+            'a' => 0x07,
+            'b' => 0x08,
+            'f' => 0x0c,
+            'n' => 0x0a,
+            'r' => 0x0d,
+            't' => 0x09,
+            'v' => 0x0b,
+            '0' if !matches!(self.peek_char(), Ok(c) if c.is_ascii_digit()) => 0,
+            'x' | 'u' => match self.read_hex_escape_sequence(c)? {
+                Some(n) => n,
+                None => {
+                    return Err(Error::General(
+                        pos,
+                        "Invalid hexadecimal escape sequence".to_string(),
+                    ))
+                }
+            },
+            c => c as u32,
+        };
+        Ok(match ::std::char::from_u32(code_point) {
+            Some(c) => c.encode_utf16(&mut [0; 2]).to_vec(),
+            None => vec![code_point as u16],
         })
     }
 }
@@ -770,6 +986,79 @@ fn string() {
     assert_eq!(lexer.next().unwrap().kind, Kind::String("bbb".to_string()));
 }
 
+#[test]
+fn template() {
+    use crate::token::TemplateKind;
+    let mut lexer = Lexer::new("`a\\n` `b${ {} }c${`d`}e`".to_string());
+    lexer.tokenize_all().unwrap();
+    for kind in [
+        Kind::Template(
+            TemplateKind::NoSubstitution,
+            Some("a\n".to_string()),
+            "a\\n".to_string(),
+        ),
+        Kind::Template(TemplateKind::Head, Some("b".to_string()), "b".to_string()),
+        Kind::Symbol(Symbol::OpeningBrace),
+        Kind::Symbol(Symbol::ClosingBrace),
+        Kind::Template(TemplateKind::Middle, Some("c".to_string()), "c".to_string()),
+        Kind::Template(
+            TemplateKind::NoSubstitution,
+            Some("d".to_string()),
+            "d".to_string(),
+        ),
+        Kind::Template(TemplateKind::Tail, Some("e".to_string()), "e".to_string()),
+    ]
+    .iter()
+    {
+        assert_eq!(lexer.next().unwrap().kind, *kind);
+    }
+    assert!(lexer.is_empty());
+
+    let mut lexer = Lexer::new("`\\u{41}\\1` `\\xyz\\\n`".to_string());
+    lexer.tokenize_all().unwrap();
+    for kind in [
+        Kind::Template(TemplateKind::NoSubstitution, None, "\\u{41}\\1".to_string()),
+        Kind::Template(TemplateKind::NoSubstitution, None, "\\xyz\\\n".to_string()),
+    ]
+    .iter()
+    {
+        assert_eq!(lexer.next().unwrap().kind, *kind);
+    }
+
+    let mut lexer = Lexer::new("`a${b".to_string());
+    lexer.tokenize_all().unwrap();
+    let mut lexer = Lexer::new("`abc".to_string());
+    lexer.tokenize_all().expect_err("should be error");
+}
+
+#[test]
+fn regexp() {
+    let mut lexer = Lexer::new("/[/]\\//g.test(a / b) / /c/".to_string());
+    lexer.tokenize_all().unwrap();
+    for kind in [
+        Kind::RegExp("[/]\\/".to_string(), "g".to_string()),
+        Kind::Symbol(Symbol::Point),
+        Kind::Identifier("test".to_string()),
+        Kind::Symbol(Symbol::OpeningParen),
+        Kind::Identifier("a".to_string()),
+        Kind::Symbol(Symbol::Div),
+        Kind::Identifier("b".to_string()),
+        Kind::Symbol(Symbol::ClosingParen),
+        Kind::Symbol(Symbol::Div),
+        Kind::RegExp("c".to_string(), "".to_string()),
+    ]
+    .iter()
+    {
+        assert_eq!(lexer.next().unwrap().kind, *kind);
+    }
+    assert!(lexer.is_empty());
+
+    for input in ["/abc", "/a\\\n/", "/[/"].iter() {
+        let mut lexer = Lexer::new(input.to_string());
+        lexer.tokenize_all().expect_err(input);
+    }
+}
+
 #[test]
 fn keyword() {
     use crate::token::Keyword;
@@ -825,9 +1114,9 @@ fn keyword() {
 #[test]
 fn symbol() {
     let mut lexer = Lexer::new(
-        "() {} [] , ; : . -> ++ -- + - * / % **\
+        "() {} [] , ; : . -> ++ -- + - * a / % **\
          ! ~ << >> >>> < <= > >= == != === !== & | ^ && || \
-         ? = += -= *= /= %= <<= >>= &= |= ^= \
+         ? = += -= *= a /= %= <<= >>= &= |= ^= \
          &&= ||= #"
             .to_string(),
     );
@@ -867,6 +1156,11 @@ fn symbol() {
     assert_eq!(lexer.next().unwrap().kind, Kind::Symbol(Symbol::Add,));
     assert_eq!(lexer.next().unwrap().kind, Kind::Symbol(Symbol::Sub,));
     assert_eq!(lexer.next().unwrap().kind, Kind::Symbol(Symbol::Asterisk,));
+    // '/' after an operator starts a regular expression literal.
+    assert_eq!(
+        lexer.next().unwrap().kind,
+        Kind::Identifier("a".to_string())
+    );
     assert_eq!(lexer.next().unwrap().kind, Kind::Symbol(Symbol::Div,));
     assert_eq!(lexer.next().unwrap().kind, Kind::Symbol(Symbol::Mod,));
     assert_eq!(lexer.next().unwrap().kind, Kind::Symbol(Symbol::Exp,));
@@ -896,6 +1190,10 @@ fn symbol() {
     assert_eq!(lexer.next().unwrap().kind, Kind::Symbol(Symbol::AssignAdd,));
     assert_eq!(lexer.next().unwrap().kind, Kind::Symbol(Symbol::AssignSub,));
     assert_eq!(lexer.next().unwrap().kind, Kind::Symbol(Symbol::AssignMul,));
+    assert_eq!(
+        lexer.next().unwrap().kind,
+        Kind::Identifier("a".to_string())
+    );
     assert_eq!(lexer.next().unwrap().kind, Kind::Symbol(Symbol::AssignDiv,));
     assert_eq!(lexer.next().unwrap().kind, Kind::Symbol(Symbol::AssignMod,));
     assert_eq!(lexer.next().unwrap().kind, Kind::Symbol(Symbol::AssignShl,));
@@ -954,6 +1252,19 @@ fn escape_seq() {
     );
 }
 
+#[test]
+fn hex_escape_seq() {
+    let mut lexer = Lexer::new("'\\u00e9t\\u00e9' '\\x41g' '\\u{1F600}'".to_string());
+    lexer.tokenize_all().unwrap();
+    for s in &["été", "Ag", "😀"] {
+        assert_eq!(lexer.next().unwrap().kind, Kind::String(s.to_string()));
+    }
+    assert!(Lexer::new("'\\x4'".to_string()).tokenize_all().is_err());
+    assert!(Lexer::new("'\\u{110000}'".to_string())
+        .tokenize_all()
+        .is_err());
+}
+
 #[test]
 fn comment() {
     let mut lexer = Lexer::new(
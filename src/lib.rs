@@ -1,6 +1,5 @@
 #![feature(box_patterns)]
 #![feature(repeat_generic_slice)]
-#![feature(type_ascription)]
 #[macro_use]
 pub mod util;
 #[macro_use]
@@ -13,11 +12,13 @@ pub mod id;
 pub mod lexer;
 pub mod node;
 pub mod parser;
+pub mod regexp;
 pub mod token;
 
 extern crate ansi_term;
 extern crate chrono;
 extern crate encoding;
+extern crate indexmap;
 extern crate libc;
 extern crate libloading;
 //extern crate llvm_sys as llvm;
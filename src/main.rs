@@ -43,6 +43,20 @@ fn main() {
         }
     };
 
+    if file_name.ends_with(".mjs") {
+        let mut vm = VM::new();
+        if is_profile {
+            vm = vm.profile();
+        }
+        if is_trace {
+            vm = vm.trace();
+        }
+        if let Err(e) = vm.run_module(file_name) {
+            vm.show_error_message(e);
+        }
+        return;
+    }
+
     let mut parser = match parser::Parser::load_module(file_name.clone()) {
         Ok(ok) => ok,
         Err(_) => return,
@@ -141,7 +155,7 @@ fn repl(is_profile: bool, is_trace: bool) {
                     match vm.run() {
                         Ok(val) => println!("{}", val.debug_string(true)),
                         Err(e) => {
-                            let val = e.to_value(&mut vm.factory);
+                            let val = e.to_value(&mut vm);
                             if val.is_error_object() {
                                 println!("Error: {}", val.get_property("message"));
                             } else {
@@ -149,6 +163,9 @@ fn repl(is_profile: bool, is_trace: bool) {
                             };
                         }
                     }
+                    if let Err(e) = vm.run_jobs() {
+                        vm.show_error_message(e);
+                    }
                     break;
                 }
                 Err(parser::Error::UnexpectedEOF(_)) => match rl.readline("... ") {
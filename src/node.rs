@@ -1,8 +1,8 @@
-// TODO: Support all features: https://tc39.github.io/ecma262/#prod-FormalParameter
-//       Especially, destructing of rest parameter.
+/// https://tc39.github.io/ecma262/#prod-FormalParameter
+/// ``binding`` is an Identifier node or a binding pattern.
 #[derive(Clone, Debug, PartialEq)]
 pub struct FormalParameter {
-    pub name: String,
+    pub binding: Node,
     pub init: Option<Node>,
     pub is_rest_param: bool,
 }
@@ -10,31 +10,102 @@ pub struct FormalParameter {
 pub type FormalParameters = Vec<FormalParameter>;
 
 impl FormalParameter {
-    pub fn new(name: String, init: Option<Node>, is_rest_param: bool) -> FormalParameter {
+    pub fn new(binding: Node, init: Option<Node>, is_rest_param: bool) -> FormalParameter {
         FormalParameter {
-            name: name,
-            init: init,
-            is_rest_param: is_rest_param,
+            binding,
+            init,
+            is_rest_param,
         }
     }
 }
 
+/// https://tc39.github.io/ecma262/#prod-CaseClause
+/// ``test`` is None for the default clause.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CaseClause {
+    pub test: Option<Node>,
+    pub body: Vec<Node>,
+}
+
+impl CaseClause {
+    pub fn new(test: Option<Node>, body: Vec<Node>) -> CaseClause {
+        CaseClause { test, body }
+    }
+}
+
 // TODO: Support all features: https://tc39.github.io/ecma262/#prod-PropertyDefinition
 #[derive(Clone, Debug, PartialEq)]
 pub enum PropertyDefinition {
     IdentifierReference(String),
     Property(String, Node),
-    MethodDefinition(MethodDefinitionKind, String, Node),
+    /// ``key`` is a String node unless the name is computed. ``func`` is a FunctionExpr.
+    MethodDefinition(MethodDefinitionKind, Node, Node),
     SpreadObject(Node),
 }
 
+/// https://tc39.github.io/ecma262/#prod-BindingProperty
+/// ``key`` of a property is a String node unless the name is computed.
 #[derive(Clone, Debug, PartialEq)]
+pub enum PropertyPattern {
+    Property(Node, Node), // Key, Target
+    Rest(Node),
+}
+
+#[derive(Clone, Debug, PartialEq, Copy)]
 pub enum MethodDefinitionKind {
     Get,
     Set,
     Ordinary,
 }
 
+/// https://tc39.github.io/ecma262/#prod-ClassElement
+/// ``key`` is a String node unless the name is computed. ``func`` is a FunctionExpr.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClassElement {
+    pub is_static: bool,
+    pub kind: MethodDefinitionKind,
+    pub key: Node,
+    pub func: Node,
+}
+
+impl ClassElement {
+    pub fn new(is_static: bool, kind: MethodDefinitionKind, key: Node, func: Node) -> ClassElement {
+        ClassElement {
+            is_static,
+            kind,
+            key,
+            func,
+        }
+    }
+}
+
+/// https://tc39.github.io/ecma262/#prod-ImportSpecifier
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImportSpecifier {
+    /// The name exported by the imported module. None for ``* as local``.
+    pub imported: Option<String>,
+    pub local: String,
+}
+
+impl ImportSpecifier {
+    pub fn new(imported: Option<String>, local: String) -> ImportSpecifier {
+        ImportSpecifier { imported, local }
+    }
+}
+
+/// https://tc39.github.io/ecma262/#prod-ExportDeclaration
+#[derive(Clone, Debug, PartialEq)]
+pub enum ExportDeclaration {
+    /// export var/let/const/function/class ...
+    Declaration(Box<Node>),
+    /// export default ... (A declaration, or an expression bound to "*default*")
+    Default(Box<Node>),
+    /// export { local as exported, ... } [from "module"]
+    Named(Vec<(String, String)>, Option<String>),
+    /// export * [as exported] from "module"
+    All(Option<String>, String),
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct Node {
     pub base: NodeBase,
@@ -52,17 +123,28 @@ pub enum VarKind {
 pub enum NodeBase {
     StatementList(Vec<Node>),
     Block(Vec<Node>),
-    FunctionDecl(String, FormalParameters, Box<Node>), // name, params, body
-    FunctionExpr(Option<String>, FormalParameters, Box<Node>), // Name, params, body
-    ArrowFunction(FormalParameters, Box<Node>),
+    FunctionDecl(String, FormalParameters, Box<Node>, bool, bool), // name, params, body, is_async, is_generator
+    FunctionExpr(Option<String>, FormalParameters, Box<Node>, bool, bool), // Name, params, body, is_async, is_generator
+    ArrowFunction(FormalParameters, Box<Node>, bool),                      // Params, Body, is_async
+    Class(
+        Option<String>,
+        Option<Box<Node>>,
+        Option<Box<Node>>,
+        Vec<ClassElement>,
+    ), // Name, Heritage, Constructor, Elements
     VarDecl(String, Option<Box<Node>>, VarKind),
+    PatternDecl(Box<Node>, Option<Box<Node>>, VarKind), // Pattern, Init, Kind
     Member(Box<Node>, String),
     Index(Box<Node>, Box<Node>),
     New(Box<Node>),
     Call(Box<Node>, Vec<Node>),
     If(Box<Node>, Box<Node>, Box<Node>), // Cond, Then, Else
     While(Box<Node>, Box<Node>),         // Cond, Body
+    DoWhile(Box<Node>, Box<Node>),       // Body, Cond
     For(Box<Node>, Box<Node>, Box<Node>, Box<Node>), // Init, Cond, Step, Body
+    ForIn(Box<Node>, Box<Node>, Box<Node>), // Var, Object, Body
+    ForOf(Box<Node>, Box<Node>, Box<Node>), // Var, Iterable, Body
+    Switch(Box<Node>, Vec<CaseClause>),  // Discriminant, Clauses
     Assign(Box<Node>, Box<Node>),
     UnaryOp(Box<Node>, UnaryOp),
     BinaryOp(Box<Node>, Box<Node>, BinOp),
@@ -73,12 +155,24 @@ pub enum NodeBase {
     Continue(Option<String>),
     Try(Box<Node>, Box<Node>, Box<Node>, Box<Node>), // Try, Catch, Param, Finally
     Throw(Box<Node>),
+    Await(Box<Node>),
+    Yield(Option<Box<Node>>, bool), // Value, is_delegate (yield*)
     Array(Vec<Node>),
     Object(Vec<PropertyDefinition>),
     Identifier(String),
     Spread(Box<Node>),
+    ArrayPattern(Vec<Node>), // Elements: Nope for a hole, Spread for the rest element
+    ObjectPattern(Vec<PropertyPattern>),
+    AssignmentPattern(Box<Node>, Box<Node>), // Target, Default
+    TemplateLiteral(Vec<String>, Vec<Node>), // Cooked strings, Substitutions
+    TaggedTemplate(Box<Node>, Vec<Option<String>>, Vec<String>, Vec<Node>), // Tag, Cooked strings (None if invalid), Raw strings, Substitutions
+    RegExpLiteral(String, String),                                  // Pattern, Flags
+    Import(Vec<ImportSpecifier>, String),                           // Specifiers, Module specifier
+    Export(ExportDeclaration),
+    ImportCall(Box<Node>), // import(specifier)
     This,
-    // Arguments,
+    Super,
+    Arguments,
     // Undefined,
     Null,
     String(String),
@@ -94,6 +188,59 @@ impl Node {
             pos: pos,
         }
     }
+
+    /// Names bound by an identifier or a binding pattern.
+    /// https://tc39.github.io/ecma262/#sec-static-semantics-boundnames
+    pub fn bound_names(&self) -> Vec<String> {
+        match self.base {
+            NodeBase::Identifier(ref name) => vec![name.clone()],
+            NodeBase::ArrayPattern(ref elems) => {
+                elems.iter().flat_map(|elem| elem.bound_names()).collect()
+            }
+            NodeBase::ObjectPattern(ref properties) => properties
+                .iter()
+                .flat_map(|property| match property {
+                    PropertyPattern::Property(_, target) | PropertyPattern::Rest(target) => {
+                        target.bound_names()
+                    }
+                })
+                .collect(),
+            NodeBase::AssignmentPattern(ref target, _) | NodeBase::Spread(ref target) => {
+                target.bound_names()
+            }
+            _ => vec![],
+        }
+    }
+
+    /// True if the directive prologue of a script or function body contains "use strict".
+    /// https://tc39.github.io/ecma262/#sec-directive-prologues-and-the-use-strict-directive
+    pub fn has_use_strict_directive(&self) -> bool {
+        match self.base {
+            NodeBase::StatementList(ref items) => items
+                .iter()
+                .take_while(|item| matches!(item.base, NodeBase::String(_)))
+                .any(|item| matches!(item.base, NodeBase::String(ref s) if s == "use strict")),
+            _ => false,
+        }
+    }
+
+    /// https://tc39.github.io/ecma262/#prod-IterationStatement
+    /// Labels in front of the statement are skipped.
+    pub fn is_iteration_statement(&self) -> bool {
+        match self.base {
+            NodeBase::While(_, _)
+            | NodeBase::DoWhile(_, _)
+            | NodeBase::For(_, _, _, _)
+            | NodeBase::ForIn(_, _, _)
+            | NodeBase::ForOf(_, _, _) => true,
+            // The parser wraps a for statement in a block.
+            NodeBase::Block(ref body) if body.len() == 1 => {
+                matches!(body[0].base, NodeBase::For(_, _, _, _))
+            }
+            NodeBase::Label(_, ref body) => body.is_iteration_statement(),
+            _ => false,
+        }
+    }
     /*
     pub fn definitely_returns(&self) -> bool {
         match self.base {
@@ -255,6 +402,8 @@ pub enum BinOp {
     Gt,
     Le,
     Ge,
+    In,
+    InstanceOf,
     Shl,
     Shr,
     ZFShr,
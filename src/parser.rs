@@ -1,9 +1,12 @@
 pub use crate::lexer;
 use crate::node::{
-    BinOp, FormalParameter, FormalParameters, MethodDefinitionKind, Node, NodeBase,
-    PropertyDefinition, UnaryOp, VarKind,
+    BinOp, CaseClause, ClassElement, ExportDeclaration, FormalParameter, FormalParameters,
+    ImportSpecifier, MethodDefinitionKind, Node, NodeBase, PropertyDefinition, PropertyPattern,
+    UnaryOp, VarKind,
 };
-use crate::token::{get_string_for_symbol, Keyword, Kind, Symbol, Token};
+use crate::regexp::Regex;
+use crate::token::{get_string_for_symbol, Keyword, Kind, Symbol, TemplateKind, Token};
+use rustc_hash::FxHashMap;
 use std::fs::OpenOptions;
 use std::io::Read;
 use std::path::Path;
@@ -40,10 +43,22 @@ pub enum Error {
     General(usize, String),
 }
 
+/// Cooked strings, raw strings and substitutions of a template literal.
+type TemplateParts = (Vec<Option<String>>, Vec<String>, Vec<Node>);
+
 #[derive(Clone, Debug)]
 pub struct Parser {
     pub file_name: String,
     pub lexer: lexer::Lexer,
+    /// True while reading the body of an async function, where ``await`` is an operator.
+    in_async: bool,
+    /// True while reading the body of a generator function, where ``yield`` is an operator.
+    in_generator: bool,
+    /// True while reading strict mode code.
+    /// https://tc39.github.io/ecma262/#sec-strict-mode-code
+    in_strict: bool,
+    /// The source text range of each function and class, keyed by the position of its node.
+    function_sources: FxHashMap<usize, (usize, usize)>,
 }
 
 #[derive(Clone, Debug)]
@@ -55,6 +70,22 @@ pub struct ScriptInfo {
     pub code: String,
     /// Correspondence between char postions and line numbers.
     pub pos_line_list: Vec<(usize, usize)>,
+    /// The source text range of each function and class, keyed by the position of its node.
+    pub function_sources: FxHashMap<usize, (usize, usize)>,
+}
+
+impl ScriptInfo {
+    /// The line and column numbers, both starting from 1, of a char position in the script.
+    pub fn line_and_column(&self, pos: usize) -> (usize, usize) {
+        let (start_pos, line) = self
+            .pos_line_list
+            .iter()
+            .take_while(|(start_pos, _)| *start_pos <= pos)
+            .last()
+            .copied()
+            .unwrap_or((0, 1));
+        (line, pos - start_pos + 1)
+    }
 }
 
 impl Parser {
@@ -62,6 +93,10 @@ impl Parser {
         Parser {
             file_name: file_name.into(),
             lexer: lexer::Lexer::new(code.into()),
+            in_async: false,
+            in_generator: false,
+            in_strict: false,
+            function_sources: FxHashMap::default(),
         }
     }
 
@@ -104,6 +139,7 @@ impl Parser {
             file_name: self.file_name,
             code: self.lexer.code,
             pos_line_list: self.lexer.pos_line_list,
+            function_sources: self.function_sources,
         }
     }
 
@@ -117,7 +153,7 @@ impl Parser {
             "{}: line {}: {}\n{}",
             Colour::Red.bold().paint("SyntaxError"),
             line,
-            msg.into(): String,
+            Into::<String>::into(msg),
             source_at_err_point,
         );
     }
@@ -149,6 +185,13 @@ impl Parser {
         self.lexer.tokenize_all()?;
         self.read_script()
     }
+
+    /// Parse the whole source as a module, which may contain ``import`` and ``export``
+    /// declarations.
+    pub fn parse_module(&mut self) -> Result<Node, Error> {
+        self.lexer.tokenize_all()?;
+        self.read_module()
+    }
 }
 
 impl Parser {
@@ -157,23 +200,371 @@ impl Parser {
     }
 }
 
+impl Parser {
+    /// https://tc39.github.io/ecma262/#prod-Module
+    /// Module code is always strict mode code.
+    fn read_module(&mut self) -> Result<Node, Error> {
+        self.in_strict = true;
+        let pos = self.lexer.get_current_pos();
+        let mut items = vec![];
+
+        loop {
+            let tok = match self.lexer.peek_skip_lineterminator() {
+                Ok(tok) => tok,
+                Err(Error::NormalEOF) => break,
+                Err(e) => return Err(e),
+            };
+
+            let item = match tok.kind {
+                Kind::Keyword(Keyword::Import) if !self.is_import_call() => {
+                    self.read_import_declaration()
+                }
+                Kind::Keyword(Keyword::Export) => self.read_export_declaration(),
+                _ => self.read_statement_list_item(),
+            };
+            match item {
+                Ok(ok) => items.push(ok),
+                Err(Error::NormalEOF) => return Err(Error::UnexpectedEOF("".to_string())),
+                Err(e) => return Err(e),
+            }
+
+            while match self
+                .lexer
+                .next_if_skip_lineterminator(Kind::Symbol(Symbol::Semicolon))
+            {
+                Ok(succ) => succ,
+                Err(Error::NormalEOF) => false,
+                Err(e) => return Err(e),
+            } {}
+        }
+
+        Ok(Node::new(NodeBase::StatementList(items), pos))
+    }
+
+    /// Returns true if the next tokens are ``import (``, which starts an expression.
+    fn is_import_call(&mut self) -> bool {
+        if self.lexer.skip_lineterminator().is_err() {
+            return false;
+        }
+        let mut i = 1;
+        while let Ok(tok) = self.lexer.peek(i) {
+            match tok.kind {
+                Kind::LineTerminator => i += 1,
+                kind => return kind == Kind::Symbol(Symbol::OpeningParen),
+            }
+        }
+        false
+    }
+
+    /// https://tc39.github.io/ecma262/#prod-ImportDeclaration
+    fn read_import_declaration(&mut self) -> Result<Node, Error> {
+        let pos = self.lexer.get_current_pos();
+        expect!(self, Kind::Keyword(Keyword::Import), "expect 'import'");
+
+        let mut specifiers = vec![];
+
+        // import "module"
+        if let Kind::String(module) = self.lexer.peek_skip_lineterminator()?.kind {
+            self.lexer.next_skip_lineterminator()?;
+            self.expect_end_of_statement()?;
+            return Ok(Node::new(NodeBase::Import(specifiers, module), pos));
+        }
+
+        // import local, ...
+        if let Kind::Identifier(local) = self.lexer.peek_skip_lineterminator()?.kind {
+            self.lexer.next_skip_lineterminator()?;
+            specifiers.push(ImportSpecifier::new(Some("default".to_string()), local));
+            if !self
+                .lexer
+                .next_if_skip_lineterminator(Kind::Symbol(Symbol::Comma))?
+            {
+                let module = self.read_from_clause()?;
+                self.expect_end_of_statement()?;
+                return Ok(Node::new(NodeBase::Import(specifiers, module), pos));
+            }
+        }
+
+        let tok = self.lexer.next_skip_lineterminator()?;
+        match tok.kind {
+            // * as local
+            Kind::Symbol(Symbol::Asterisk) => {
+                self.expect_contextual_keyword("as")?;
+                let local = self.read_binding_identifier()?;
+                specifiers.push(ImportSpecifier::new(None, local));
+            }
+            // { imported as local, ... }
+            Kind::Symbol(Symbol::OpeningBrace) => loop {
+                if self
+                    .lexer
+                    .next_if_skip_lineterminator(Kind::Symbol(Symbol::ClosingBrace))?
+                {
+                    break;
+                }
+                let tok = self.lexer.next_skip_lineterminator()?;
+                let imported = match tok.kind {
+                    Kind::Identifier(ref name) => name.clone(),
+                    Kind::Keyword(kw) => kw.to_str().to_string(),
+                    _ => return Err(Error::Expect(tok.pos, "expect identifier".to_string())),
+                };
+                let local = if self.next_if_contextual_keyword("as")? {
+                    self.read_binding_identifier()?
+                } else if let Kind::Keyword(_) = tok.kind {
+                    return Err(Error::UnexpectedToken(
+                        tok.pos,
+                        "Unexpected reserved word".to_string(),
+                    ));
+                } else {
+                    imported.clone()
+                };
+                specifiers.push(ImportSpecifier::new(Some(imported), local));
+                if !self
+                    .lexer
+                    .next_if_skip_lineterminator(Kind::Symbol(Symbol::Comma))?
+                {
+                    expect!(self, Kind::Symbol(Symbol::ClosingBrace), "expect '}'");
+                    break;
+                }
+            },
+            _ => {
+                return Err(Error::UnexpectedToken(
+                    tok.pos,
+                    "unexpected token.".to_string(),
+                ))
+            }
+        }
+
+        let module = self.read_from_clause()?;
+        self.expect_end_of_statement()?;
+        Ok(Node::new(NodeBase::Import(specifiers, module), pos))
+    }
+
+    /// https://tc39.github.io/ecma262/#prod-ExportDeclaration
+    fn read_export_declaration(&mut self) -> Result<Node, Error> {
+        let pos = self.lexer.get_current_pos();
+        expect!(self, Kind::Keyword(Keyword::Export), "expect 'export'");
+
+        let tok = self.lexer.peek_skip_lineterminator()?;
+        let declaration = match tok.kind {
+            // export * [as exported] from "module"
+            Kind::Symbol(Symbol::Asterisk) => {
+                self.lexer.next_skip_lineterminator()?;
+                let exported = if self.next_if_contextual_keyword("as")? {
+                    Some(self.read_identifier_name()?)
+                } else {
+                    None
+                };
+                let module = self.read_from_clause()?;
+                self.expect_end_of_statement()?;
+                ExportDeclaration::All(exported, module)
+            }
+            // export { local as exported, ... } [from "module"]
+            Kind::Symbol(Symbol::OpeningBrace) => {
+                self.lexer.next_skip_lineterminator()?;
+                let mut names = vec![];
+                loop {
+                    if self
+                        .lexer
+                        .next_if_skip_lineterminator(Kind::Symbol(Symbol::ClosingBrace))?
+                    {
+                        break;
+                    }
+                    let local = self.read_identifier_name()?;
+                    let exported = if self.next_if_contextual_keyword("as")? {
+                        self.read_identifier_name()?
+                    } else {
+                        local.clone()
+                    };
+                    names.push((local, exported));
+                    if !self
+                        .lexer
+                        .next_if_skip_lineterminator(Kind::Symbol(Symbol::Comma))?
+                    {
+                        expect!(self, Kind::Symbol(Symbol::ClosingBrace), "expect '}'");
+                        break;
+                    }
+                }
+                let module = match self.lexer.peek_skip_lineterminator() {
+                    Ok(Token {
+                        kind: Kind::Identifier(ref name),
+                        ..
+                    }) if name == "from" => Some(self.read_from_clause()?),
+                    _ => None,
+                };
+                self.expect_end_of_statement()?;
+                ExportDeclaration::Named(names, module)
+            }
+            // export default ...
+            Kind::Keyword(Keyword::Default) => {
+                self.lexer.next_skip_lineterminator()?;
+                ExportDeclaration::Default(Box::new(self.read_export_default()?))
+            }
+            Kind::Keyword(Keyword::Var) => {
+                ExportDeclaration::Declaration(Box::new(self.read_statement()?))
+            }
+            Kind::Keyword(Keyword::Function)
+            | Kind::Keyword(Keyword::Class)
+            | Kind::Keyword(Keyword::Const)
+            | Kind::Keyword(Keyword::Let) => {
+                ExportDeclaration::Declaration(Box::new(self.read_declaration()?))
+            }
+            Kind::Identifier(ref name) if name == "async" && self.is_async_function() => {
+                ExportDeclaration::Declaration(Box::new(self.read_declaration()?))
+            }
+            _ => {
+                return Err(Error::UnexpectedToken(
+                    tok.pos,
+                    "unexpected token.".to_string(),
+                ))
+            }
+        };
+
+        Ok(Node::new(NodeBase::Export(declaration), pos))
+    }
+
+    /// Reads what follows ``export default``. A named function or class is a declaration.
+    /// Anything else is an expression, whose value is bound to "*default*".
+    fn read_export_default(&mut self) -> Result<Node, Error> {
+        let into_declaration = |node: Node| match node.base {
+            NodeBase::FunctionExpr(Some(name), params, body, is_async, is_generator) => Node::new(
+                NodeBase::FunctionDecl(name, params, body, is_async, is_generator),
+                node.pos,
+            ),
+            NodeBase::Class(Some(ref name), _, _, _) => {
+                let name = name.clone();
+                let pos = node.pos;
+                Node::new(
+                    NodeBase::VarDecl(name, Some(Box::new(node)), VarKind::Let),
+                    pos,
+                )
+            }
+            _ => node,
+        };
+
+        match self.lexer.peek_skip_lineterminator()?.kind {
+            Kind::Keyword(Keyword::Function) => {
+                self.lexer.next_skip_lineterminator()?;
+                Ok(into_declaration(self.read_function_expression(false)?))
+            }
+            Kind::Identifier(ref name) if name == "async" && self.is_async_function() => {
+                self.lexer.next_skip_lineterminator()?;
+                self.lexer.next_skip_lineterminator()?;
+                Ok(into_declaration(self.read_function_expression(true)?))
+            }
+            Kind::Keyword(Keyword::Class) => {
+                self.lexer.next_skip_lineterminator()?;
+                Ok(into_declaration(self.read_class_expression()?))
+            }
+            _ => {
+                let expr = self.read_assignment_expression()?;
+                self.expect_end_of_statement()?;
+                Ok(expr)
+            }
+        }
+    }
+
+    /// https://tc39.github.io/ecma262/#prod-FromClause
+    fn read_from_clause(&mut self) -> Result<String, Error> {
+        self.expect_contextual_keyword("from")?;
+        let tok = self.lexer.next_skip_lineterminator()?;
+        match tok.kind {
+            Kind::String(module) => Ok(module),
+            _ => Err(Error::Expect(
+                tok.pos,
+                "expect module specifier".to_string(),
+            )),
+        }
+    }
+
+    /// Reads an identifier, or a reserved word used as a name, e.g. ``default``.
+    /// https://tc39.github.io/ecma262/#prod-IdentifierName
+    fn read_identifier_name(&mut self) -> Result<String, Error> {
+        let tok = self.lexer.next_skip_lineterminator()?;
+        match tok.kind {
+            Kind::Identifier(name) => Ok(name),
+            Kind::Keyword(kw) => Ok(kw.to_str().to_string()),
+            _ => Err(Error::Expect(tok.pos, "expect identifier".to_string())),
+        }
+    }
+
+    /// https://tc39.github.io/ecma262/#prod-BindingIdentifier
+    fn read_binding_identifier(&mut self) -> Result<String, Error> {
+        let tok = self.lexer.next_skip_lineterminator()?;
+        match tok.kind {
+            Kind::Identifier(name) => Ok(name),
+            _ => Err(Error::Expect(tok.pos, "expect identifier".to_string())),
+        }
+    }
+
+    /// Consumes ``word``, which is an identifier only in some places, e.g. ``as`` and ``from``.
+    fn next_if_contextual_keyword(&mut self, word: &str) -> Result<bool, Error> {
+        match self
+            .lexer
+            .next_if_skip_lineterminator(Kind::Identifier(word.to_string()))
+        {
+            Err(Error::NormalEOF) => Ok(false),
+            otherwise => otherwise,
+        }
+    }
+
+    fn expect_contextual_keyword(&mut self, word: &str) -> Result<(), Error> {
+        if self.next_if_contextual_keyword(word)? {
+            return Ok(());
+        }
+        Err(Error::Expect(
+            self.lexer.get_current_pos(),
+            format!("expect '{}'", word),
+        ))
+    }
+
+    /// A statement must end with ``;``, a line terminator or the end of the code.
+    fn expect_end_of_statement(&mut self) -> Result<(), Error> {
+        match self.lexer.peek(0) {
+            Ok(Token {
+                kind: Kind::LineTerminator,
+                ..
+            })
+            | Ok(Token {
+                kind: Kind::Symbol(Symbol::Semicolon),
+                ..
+            })
+            | Err(Error::NormalEOF) => Ok(()),
+            Ok(tok) => Err(Error::Expect(
+                tok.pos,
+                "expect ';' or line terminator".to_string(),
+            )),
+            Err(e) => Err(e),
+        }
+    }
+}
+
 impl Parser {
     fn read_statement_list(&mut self) -> Result<Node, Error> {
-        self.read_statements(false, false)
+        self.read_statements(false, false, true)
     }
 
     fn read_block_statement(&mut self) -> Result<Node, Error> {
-        self.read_statements(true, true)
+        self.read_statements(true, true, false)
     }
 
     fn read_block(&mut self) -> Result<Node, Error> {
-        self.read_statements(true, false)
+        self.read_statements(true, false, false)
+    }
+
+    /// Reads ``{ ... }`` of a function body, which may start with a directive prologue.
+    fn read_function_body_block(&mut self) -> Result<Node, Error> {
+        let save_in_strict = self.in_strict;
+        let body = self.read_statements(true, false, true);
+        self.in_strict = save_in_strict;
+        body
     }
 
+    /// If ``has_directive_prologue`` is true, the statements following a "use strict" directive
+    /// are read as strict mode code.
     fn read_statements(
         &mut self,
         break_when_closingbrase: bool,
         is_block_statement: bool,
+        has_directive_prologue: bool,
     ) -> Result<Node, Error> {
         let pos = if break_when_closingbrase {
             self.lexer.get_prev_pos()
@@ -182,6 +573,7 @@ impl Parser {
         };
 
         let mut items = vec![];
+        let mut in_directive_prologue = has_directive_prologue;
 
         loop {
             match self
@@ -212,7 +604,17 @@ impl Parser {
             }
 
             match self.read_statement_list_item() {
-                Ok(ok) => items.push(ok),
+                Ok(ok) => {
+                    // https://tc39.github.io/ecma262/#sec-directive-prologues-and-the-use-strict-directive
+                    if in_directive_prologue {
+                        match ok.base {
+                            NodeBase::String(ref s) if s == "use strict" => self.in_strict = true,
+                            NodeBase::String(_) => {}
+                            _ => in_directive_prologue = false,
+                        }
+                    }
+                    items.push(ok)
+                }
                 Err(Error::NormalEOF) => {
                     return Err(Error::UnexpectedEOF("".to_string()));
                 }
@@ -241,8 +643,12 @@ impl Parser {
         if let Ok(tok) = self.lexer.peek_skip_lineterminator() {
             match tok.kind {
                 Kind::Keyword(Keyword::Function) => self.read_declaration(),
+                Kind::Keyword(Keyword::Class) => self.read_declaration(),
                 Kind::Keyword(Keyword::Const) => self.read_declaration(),
                 Kind::Keyword(Keyword::Let) => self.read_declaration(),
+                Kind::Identifier(ref name) if name == "async" && self.is_async_function() => {
+                    self.read_declaration()
+                }
                 _ => self.read_statement(),
             }
         } else {
@@ -279,12 +685,22 @@ impl Parser {
             Kind::Keyword(Keyword::If) => self.read_if_statement(),
             Kind::Keyword(Keyword::Var) => self.read_variable_statement(),
             Kind::Keyword(Keyword::While) => self.read_while_statement(),
+            Kind::Keyword(Keyword::Do) => self.read_do_while_statement(),
             Kind::Keyword(Keyword::For) => self.read_for_statement(),
+            Kind::Keyword(Keyword::Switch) => self.read_switch_statement(),
             Kind::Keyword(Keyword::Return) => self.read_return_statement(),
             Kind::Keyword(Keyword::Break) => self.read_break_statement(),
             Kind::Keyword(Keyword::Continue) => self.read_continue_statement(),
             Kind::Keyword(Keyword::Try) => self.read_try_statement(),
             Kind::Keyword(Keyword::Throw) => self.read_throw_statement(),
+            Kind::Keyword(Keyword::With) if self.in_strict => {
+                return Err(Error::General(
+                    tok.pos,
+                    "Strict mode code may not include a with statement".to_string(),
+                ));
+            }
+            // TODO: https://tc39.github.io/ecma262/#sec-with-statement
+            Kind::Keyword(Keyword::With) => return Err(Error::UnsupportedFeature(tok.pos)),
             Kind::Symbol(Symbol::OpeningBrace) => self.read_block_statement(),
             Kind::Symbol(Symbol::Semicolon) => return Ok(Node::new(NodeBase::Nope, tok.pos)),
             _ => {
@@ -369,6 +785,10 @@ impl Parser {
 
     /// https://tc39.github.io/ecma262/#prod-VariableDeclaration
     fn read_variable_declaration(&mut self) -> Result<Node, Error> {
+        if self.is_pattern_start() {
+            return self.read_pattern_declaration(VarKind::Var);
+        }
+
         let pos = self.lexer.get_current_pos();
         let name = match self.lexer.next_skip_lineterminator()?.kind {
             Kind::Identifier(name) => name,
@@ -393,6 +813,22 @@ impl Parser {
         }
     }
 
+    /// A destructuring declaration, which must have an initializer.
+    fn read_pattern_declaration(&mut self, kind: VarKind) -> Result<Node, Error> {
+        let pos = self.lexer.get_current_pos();
+        let pattern = self.read_pattern(false)?;
+        expect!(
+            self,
+            Kind::Symbol(Symbol::Assign),
+            "Missing initializer in destructuring declaration"
+        );
+        let init = self.read_initializer()?;
+        Ok(Node::new(
+            NodeBase::PatternDecl(Box::new(pattern), Some(Box::new(init)), kind),
+            pos,
+        ))
+    }
+
     /// https://tc39.github.io/ecma262/#prod-Initializer
     fn read_initializer(&mut self) -> Result<Node, Error> {
         self.read_assignment_expression()
@@ -456,11 +892,83 @@ impl Parser {
         ))
     }
 
+    /// https://tc39.github.io/ecma262/#prod-IterationStatement
+    fn read_do_while_statement(&mut self) -> Result<Node, Error> {
+        let pos = self.lexer.get_prev_pos();
+
+        let body = self.read_statement()?;
+
+        expect!(self, Kind::Keyword(Keyword::While), "expect 'while'");
+        expect!(self, Kind::Symbol(Symbol::OpeningParen), "expect '('");
+
+        let cond = self.read_expression()?;
+
+        expect!(self, Kind::Symbol(Symbol::ClosingParen), "expect ')'");
+
+        Ok(Node::new(
+            NodeBase::DoWhile(Box::new(body), Box::new(cond)),
+            pos,
+        ))
+    }
+
     fn read_for_statement(&mut self) -> Result<Node, Error> {
         let pos = self.lexer.get_prev_pos();
 
         expect!(self, Kind::Symbol(Symbol::OpeningParen), "expect '('");
 
+        // for (var/let/const x in/of ...)
+        if self.is_for_in_of_declaration() {
+            let decl_pos = self.lexer.get_current_pos();
+            let kind = match self.lexer.next()?.kind {
+                Kind::Keyword(Keyword::Var) => VarKind::Var,
+                Kind::Keyword(Keyword::Let) => VarKind::Let,
+                Kind::Keyword(Keyword::Const) => VarKind::Const,
+                _ => unreachable!(),
+            };
+            let decl = if self.is_pattern_start() {
+                let pattern = self.read_pattern(false)?;
+                Node::new(
+                    NodeBase::PatternDecl(Box::new(pattern), None, kind),
+                    decl_pos,
+                )
+            } else {
+                let name = match self.lexer.next()?.kind {
+                    Kind::Identifier(name) => name,
+                    _ => unreachable!(),
+                };
+                Node::new(NodeBase::VarDecl(name, None, kind), decl_pos)
+            };
+            return self.read_for_in_of_statement(pos, decl);
+        }
+
+        // for ([a, b] in/of ...)
+        if self.is_pattern_start() {
+            if let Some(end) = self.peek_after_brackets(0) {
+                if self.is_for_in_of_keyword(end) {
+                    let pattern = self.read_pattern(true)?;
+                    return self.read_for_in_of_statement(pos, pattern);
+                }
+            }
+        }
+
+        // for (lhs in/of ...)
+        let save_pos = self.lexer.token_pos;
+        if let Ok(lhs) = self.read_left_hand_side_expression() {
+            if self.is_for_in_of_keyword(0) {
+                match lhs.base {
+                    NodeBase::Identifier(_) | NodeBase::Member(_, _) | NodeBase::Index(_, _) => {}
+                    _ => {
+                        return Err(Error::General(
+                            lhs.pos,
+                            "invalid left-hand side in for-in/of loop".to_string(),
+                        ))
+                    }
+                }
+                return self.read_for_in_of_statement(pos, lhs);
+            }
+        }
+        self.lexer.token_pos = save_pos;
+
         let init = match self.lexer.peek(0)?.kind {
             Kind::Keyword(Keyword::Var) => {
                 assert_eq!(self.lexer.next()?.kind, Kind::Keyword(Keyword::Var));
@@ -504,30 +1012,185 @@ impl Parser {
 
         Ok(Node::new(NodeBase::Block(vec![for_node]), pos))
     }
-}
 
-impl Parser {
-    fn read_break_statement(&mut self) -> Result<Node, Error> {
-        let pos = self.lexer.get_prev_pos();
-        let tok = self.lexer.next()?;
-        match tok.kind {
-            Kind::LineTerminator
-            | Kind::Symbol(Symbol::Semicolon)
-            | Kind::Symbol(Symbol::ClosingBrace) => {
-                self.lexer.unget();
-                Ok(Node::new(NodeBase::Break(None), pos))
-            }
-            Kind::Identifier(name) => Ok(Node::new(NodeBase::Break(Some(name)), pos)),
-            _ => Err(Error::UnexpectedToken(
-                tok.pos,
-                "expected ';', identifier or line terminator".to_string(),
-            )),
+    /// Read the rest of `for (<var> in <expr>) <body>` or `for (<var> of <expr>) <body>`.
+    fn read_for_in_of_statement(&mut self, pos: usize, var: Node) -> Result<Node, Error> {
+        let is_for_of = match self.lexer.next_skip_lineterminator()?.kind {
+            Kind::Keyword(Keyword::In) => false,
+            Kind::Identifier(ref of) if of == "of" => true,
+            _ => unreachable!(),
+        };
+
+        let expr = if is_for_of {
+            self.read_assignment_expression()?
+        } else {
+            self.read_expression()?
+        };
+
+        expect!(self, Kind::Symbol(Symbol::ClosingParen), "expect ')'");
+
+        let body = self.read_statement()?;
+
+        let base = if is_for_of {
+            NodeBase::ForOf(Box::new(var), Box::new(expr), Box::new(body))
+        } else {
+            NodeBase::ForIn(Box::new(var), Box::new(expr), Box::new(body))
+        };
+
+        Ok(Node::new(base, pos))
+    }
+
+    fn is_for_in_of_declaration(&mut self) -> bool {
+        match self.lexer.peek(0) {
+            Ok(Token {
+                kind: Kind::Keyword(Keyword::Var),
+                ..
+            })
+            | Ok(Token {
+                kind: Kind::Keyword(Keyword::Let),
+                ..
+            })
+            | Ok(Token {
+                kind: Kind::Keyword(Keyword::Const),
+                ..
+            }) => {}
+            _ => return false,
+        }
+        match self.lexer.peek(1) {
+            Ok(Token {
+                kind: Kind::Identifier(_),
+                ..
+            }) => self.is_for_in_of_keyword(2),
+            Ok(Token {
+                kind: Kind::Symbol(Symbol::OpeningBoxBracket),
+                ..
+            })
+            | Ok(Token {
+                kind: Kind::Symbol(Symbol::OpeningBrace),
+                ..
+            }) => match self.peek_after_brackets(1) {
+                Some(end) => self.is_for_in_of_keyword(end),
+                None => false,
+            },
+            _ => false,
         }
     }
 
-    fn read_continue_statement(&mut self) -> Result<Node, Error> {
-        let pos = self.lexer.get_prev_pos();
-        let tok = self.lexer.next()?;
+    /// Return true if the token specified by ``index`` is 'in' or 'of'.
+    fn is_for_in_of_keyword(&mut self, index: usize) -> bool {
+        match self.lexer.peek(index) {
+            Ok(Token {
+                kind: Kind::Keyword(Keyword::In),
+                ..
+            }) => true,
+            Ok(Token {
+                kind: Kind::Identifier(ref of),
+                ..
+            }) => of == "of",
+            _ => false,
+        }
+    }
+}
+
+impl Parser {
+    /// https://tc39.github.io/ecma262/#prod-SwitchStatement
+    fn read_switch_statement(&mut self) -> Result<Node, Error> {
+        let pos = self.lexer.get_prev_pos();
+
+        expect!(self, Kind::Symbol(Symbol::OpeningParen), "expect '('");
+        let discriminant = self.read_expression()?;
+        expect!(self, Kind::Symbol(Symbol::ClosingParen), "expect ')'");
+        expect!(self, Kind::Symbol(Symbol::OpeningBrace), "expect '{'");
+
+        let mut clauses = vec![];
+        let mut has_default = false;
+
+        loop {
+            let tok = self.lexer.next_skip_lineterminator()?;
+            let test = match tok.kind {
+                Kind::Keyword(Keyword::Case) => Some(self.read_expression()?),
+                Kind::Keyword(Keyword::Default) => {
+                    if has_default {
+                        return Err(Error::General(
+                            tok.pos,
+                            "more than one default clause in switch statement".to_string(),
+                        ));
+                    }
+                    has_default = true;
+                    None
+                }
+                Kind::Symbol(Symbol::ClosingBrace) => break,
+                _ => {
+                    return Err(Error::UnexpectedToken(
+                        tok.pos,
+                        "expected 'case', 'default' or '}'.".to_string(),
+                    ));
+                }
+            };
+            expect!(self, Kind::Symbol(Symbol::Colon), "expect ':'");
+            clauses.push(CaseClause::new(test, self.read_case_clause_body()?));
+        }
+
+        Ok(Node::new(
+            NodeBase::Switch(Box::new(discriminant), clauses),
+            pos,
+        ))
+    }
+
+    /// Read statements until the next 'case', 'default' or '}'.
+    fn read_case_clause_body(&mut self) -> Result<Vec<Node>, Error> {
+        let mut items = vec![];
+
+        loop {
+            match self.lexer.peek_skip_lineterminator() {
+                Ok(Token {
+                    kind: Kind::Keyword(Keyword::Case),
+                    ..
+                })
+                | Ok(Token {
+                    kind: Kind::Keyword(Keyword::Default),
+                    ..
+                })
+                | Ok(Token {
+                    kind: Kind::Symbol(Symbol::ClosingBrace),
+                    ..
+                }) => break,
+                Ok(_) => {}
+                Err(Error::NormalEOF) => {
+                    return Err(Error::UnexpectedEOF("expected '}'.".to_string()));
+                }
+                Err(e) => return Err(e),
+            }
+
+            items.push(self.read_statement_list_item()?);
+        }
+
+        Ok(items)
+    }
+}
+
+impl Parser {
+    fn read_break_statement(&mut self) -> Result<Node, Error> {
+        let pos = self.lexer.get_prev_pos();
+        let tok = self.lexer.next()?;
+        match tok.kind {
+            Kind::LineTerminator
+            | Kind::Symbol(Symbol::Semicolon)
+            | Kind::Symbol(Symbol::ClosingBrace) => {
+                self.lexer.unget();
+                Ok(Node::new(NodeBase::Break(None), pos))
+            }
+            Kind::Identifier(name) => Ok(Node::new(NodeBase::Break(Some(name)), pos)),
+            _ => Err(Error::UnexpectedToken(
+                tok.pos,
+                "expected ';', identifier or line terminator".to_string(),
+            )),
+        }
+    }
+
+    fn read_continue_statement(&mut self) -> Result<Node, Error> {
+        let pos = self.lexer.get_prev_pos();
+        let tok = self.lexer.next()?;
         match tok.kind {
             Kind::LineTerminator
             | Kind::Symbol(Symbol::Semicolon)
@@ -579,13 +1242,17 @@ impl Parser {
         self.lexer.skip_lineterminator()?;
         let pos = self.lexer.get_current_pos();
 
+        if self.in_generator && self.lexer.peek(0)?.kind == Kind::Identifier("yield".to_string()) {
+            return self.read_yield_expression(pos);
+        }
+
         // Arrow function
         let next_token = self.lexer.peek(0)?;
         match next_token.kind {
             // (a,b)=>{}
             Kind::Symbol(Symbol::OpeningParen) => {
                 let save_pos = self.lexer.token_pos;
-                let f = self.read_arrow_function(true);
+                let f = self.read_arrow_function(true, false);
                 if f.is_err() {
                     self.lexer.token_pos = save_pos;
                 } else {
@@ -593,17 +1260,34 @@ impl Parser {
                 }
             }
             // a=>{}
-            Kind::Identifier(_) => match self.lexer.peek(1) {
-                Ok(tok) => {
+            Kind::Identifier(ref name) => {
+                if let Ok(tok) = self.lexer.peek(1) {
                     if tok.kind == Kind::Symbol(Symbol::FatArrow) {
-                        return self.read_arrow_function(false);
+                        return self.read_arrow_function(false, false);
+                    }
+                    if name == "async" {
+                        if let Some(f) = self.read_async_arrow_function(tok.kind) {
+                            return f;
+                        }
                     }
                 }
-                _ => {}
-            },
+            }
             _ => {}
         }
 
+        // [a, b] = [b, a]
+        if self.is_assignment_pattern() {
+            let pattern = self.read_pattern(true)?;
+            expect!(self, Kind::Symbol(Symbol::Assign), "expect '='");
+            return Ok(Node::new(
+                NodeBase::Assign(
+                    Box::new(pattern),
+                    Box::new(self.read_assignment_expression()?),
+                ),
+                pos,
+            ));
+        }
+
         let mut lhs = self.read_conditional_expression()?;
 
         if let Ok(tok) = self.lexer.next() {
@@ -715,11 +1399,27 @@ impl Parser {
     );
 
     /// https://tc39.github.io/ecma262/#prod-RelationalExpression
-    expression!(
-        read_relational_expression,
-        read_shift_expression,
-        [Symbol::Lt, Symbol::Gt, Symbol::Le, Symbol::Ge]
-    );
+    fn read_relational_expression(&mut self) -> Result<Node, Error> {
+        let mut lhs = self.read_shift_expression()?;
+        while let Ok(tok) = self.lexer.peek_skip_lineterminator() {
+            let op = match tok.kind {
+                Kind::Symbol(Symbol::Lt) => BinOp::Lt,
+                Kind::Symbol(Symbol::Gt) => BinOp::Gt,
+                Kind::Symbol(Symbol::Le) => BinOp::Le,
+                Kind::Symbol(Symbol::Ge) => BinOp::Ge,
+                Kind::Keyword(Keyword::Instanceof) => BinOp::InstanceOf,
+                Kind::Keyword(Keyword::In) => BinOp::In,
+                _ => break,
+            };
+            self.lexer.next_skip_lineterminator().unwrap();
+            let pos = self.lexer.get_current_pos();
+            lhs = Node::new(
+                NodeBase::BinaryOp(Box::new(lhs), Box::new(self.read_shift_expression()?), op),
+                pos,
+            );
+        }
+        Ok(lhs)
+    }
 
     /// https://tc39.github.io/ecma262/#prod-ShiftExpression
     expression!(
@@ -776,6 +1476,7 @@ impl Parser {
                 | Kind::Symbol(Symbol::Sub)
                 | Kind::Symbol(Symbol::BitwiseNot)
                 | Kind::Symbol(Symbol::Not) => true,
+                Kind::Identifier(ref name) => name == "await" && self.in_async,
                 _ => false,
             },
             Err(_) => false,
@@ -787,10 +1488,21 @@ impl Parser {
         let pos = self.lexer.get_current_pos();
         let tok = self.lexer.next()?;
         match tok.kind {
-            Kind::Keyword(Keyword::Delete) => Ok(Node::new(
-                NodeBase::UnaryOp(Box::new(self.read_unary_expression()?), UnaryOp::Delete),
-                pos,
-            )),
+            Kind::Keyword(Keyword::Delete) => {
+                let expr = self.read_unary_expression()?;
+                if self.in_strict {
+                    if let NodeBase::Identifier(_) = expr.base {
+                        return Err(Error::General(
+                            expr.pos,
+                            "Delete of an unqualified identifier in strict mode".to_string(),
+                        ));
+                    }
+                }
+                Ok(Node::new(
+                    NodeBase::UnaryOp(Box::new(expr), UnaryOp::Delete),
+                    pos,
+                ))
+            }
             Kind::Keyword(Keyword::Void) => Ok(Node::new(
                 NodeBase::UnaryOp(Box::new(self.read_unary_expression()?), UnaryOp::Void),
                 pos,
@@ -815,6 +1527,11 @@ impl Parser {
                 NodeBase::UnaryOp(Box::new(self.read_unary_expression()?), UnaryOp::Not),
                 pos,
             )),
+            // https://tc39.github.io/ecma262/#prod-AwaitExpression
+            Kind::Identifier(ref name) if name == "await" && self.in_async => Ok(Node::new(
+                NodeBase::Await(Box::new(self.read_unary_expression()?)),
+                pos,
+            )),
             _ => {
                 self.lexer.unget();
                 self.read_update_expression()
@@ -942,6 +1659,15 @@ impl Parser {
                     }
                     lhs = Node::new(NodeBase::Index(Box::new(lhs), Box::new(idx)), pos);
                 }
+                Kind::Template(kind, cooked, raw)
+                    if kind == TemplateKind::NoSubstitution || kind == TemplateKind::Head =>
+                {
+                    let (cooked, raw, substitutions) = self.read_template(kind, cooked, raw)?;
+                    lhs = Node::new(
+                        NodeBase::TaggedTemplate(Box::new(lhs), cooked, raw, substitutions),
+                        pos,
+                    );
+                }
                 _ => {
                     self.lexer.unget();
                     break;
@@ -994,6 +1720,15 @@ impl Parser {
                     }
                     lhs = Node::new(NodeBase::Index(Box::new(lhs), Box::new(idx)), pos);
                 }
+                Kind::Template(kind, cooked, raw)
+                    if kind == TemplateKind::NoSubstitution || kind == TemplateKind::Head =>
+                {
+                    let (cooked, raw, substitutions) = self.read_template(kind, cooked, raw)?;
+                    lhs = Node::new(
+                        NodeBase::TaggedTemplate(Box::new(lhs), cooked, raw, substitutions),
+                        pos,
+                    );
+                }
                 _ => {
                     self.lexer.unget();
                     break;
@@ -1049,8 +1784,45 @@ impl Parser {
 
         match tok.kind {
             Kind::Keyword(Keyword::This) => Ok(Node::new(NodeBase::This, tok.pos)),
-            // Kind::Keyword(Keyword::Arguments) => Ok(Node::new(NodeBase::Arguments, tok.pos)),
-            Kind::Keyword(Keyword::Function) => self.read_function_expression(),
+            Kind::Keyword(Keyword::Arguments) => Ok(Node::new(NodeBase::Arguments, tok.pos)),
+            Kind::Keyword(Keyword::Function) => self.read_function_expression(false),
+            // No line terminator is allowed between ``async`` and ``function``.
+            Kind::Identifier(ref name)
+                if name == "async" && self.lexer.next_if(Kind::Keyword(Keyword::Function)) =>
+            {
+                self.read_function_expression(true)
+            }
+            Kind::Keyword(Keyword::Class) => self.read_class_expression(),
+            // https://tc39.github.io/ecma262/#prod-ImportCall
+            Kind::Keyword(Keyword::Import) => {
+                if !self
+                    .lexer
+                    .next_if_skip_lineterminator(Kind::Symbol(Symbol::OpeningParen))?
+                {
+                    return Err(Error::UnexpectedToken(
+                        tok.pos,
+                        "Cannot use import statement outside a module".to_string(),
+                    ));
+                }
+                let specifier = self.read_assignment_expression()?;
+                expect!(self, Kind::Symbol(Symbol::ClosingParen), "expect ')'");
+                Ok(Node::new(
+                    NodeBase::ImportCall(Box::new(specifier)),
+                    tok.pos,
+                ))
+            }
+            // ``super`` is only valid as ``super(...)``, ``super.name`` or ``super[expr]``.
+            Kind::Keyword(Keyword::Super) => match self.lexer.peek_skip_lineterminator()?.kind {
+                Kind::Symbol(Symbol::OpeningParen)
+                | Kind::Symbol(Symbol::Point)
+                | Kind::Symbol(Symbol::OpeningBoxBracket) => {
+                    Ok(Node::new(NodeBase::Super, tok.pos))
+                }
+                _ => Err(Error::UnexpectedToken(
+                    tok.pos,
+                    "'super' keyword unexpected here".to_string(),
+                )),
+            },
             Kind::Symbol(Symbol::OpeningParen) => {
                 let expr = self.read_expression();
                 expect!(self, Kind::Symbol(Symbol::ClosingParen), "expect ')'");
@@ -1070,7 +1842,40 @@ impl Parser {
             Kind::Identifier(ref i) if i == "null" => Ok(Node::new(NodeBase::Null, tok.pos)),
             Kind::Identifier(ident) => Ok(Node::new(NodeBase::Identifier(ident), tok.pos)),
             Kind::String(s) => Ok(Node::new(NodeBase::String(s), tok.pos)),
+            Kind::Template(kind, cooked, raw)
+                if kind == TemplateKind::NoSubstitution || kind == TemplateKind::Head =>
+            {
+                let (cooked, _, substitutions) = self.read_template(kind, cooked, raw)?;
+                // Only tagged templates can have invalid escape sequences.
+                let pos = tok.pos;
+                let cooked = cooked
+                    .into_iter()
+                    .collect::<Option<Vec<String>>>()
+                    .ok_or_else(|| {
+                        Error::General(pos, "Invalid escape sequence in template".to_string())
+                    })?;
+                Ok(Node::new(
+                    NodeBase::TemplateLiteral(cooked, substitutions),
+                    tok.pos,
+                ))
+            }
+            Kind::Number(_) if self.in_strict && self.is_legacy_octal_like_literal(tok.pos) => {
+                Err(Error::General(
+                    tok.pos,
+                    "Octal literals are not allowed in strict mode".to_string(),
+                ))
+            }
             Kind::Number(num) => Ok(Node::new(NodeBase::Number(num), tok.pos)),
+            Kind::RegExp(pattern, flags) => {
+                // https://tc39.github.io/ecma262/#sec-primary-expression-regular-expression-literals-static-semantics-early-errors
+                if let Err(msg) = Regex::new(pattern.as_str(), flags.as_str()) {
+                    return Err(Error::General(
+                        tok.pos,
+                        format!("Invalid regular expression: /{}/: {}", pattern, msg),
+                    ));
+                }
+                Ok(Node::new(NodeBase::RegExpLiteral(pattern, flags), tok.pos))
+            }
             _ => Err(Error::UnexpectedToken(
                 tok.pos,
                 format!("unexpected token."),
@@ -1078,10 +1883,112 @@ impl Parser {
         }
     }
 
+    /// https://tc39.github.io/ecma262/#prod-TemplateLiteral
+    fn read_template(
+        &mut self,
+        kind: TemplateKind,
+        cooked: Option<String>,
+        raw: String,
+    ) -> Result<TemplateParts, Error> {
+        let mut cooked_strings = vec![cooked];
+        let mut raw_strings = vec![raw];
+        let mut substitutions = vec![];
+        if kind == TemplateKind::NoSubstitution {
+            return Ok((cooked_strings, raw_strings, substitutions));
+        }
+
+        loop {
+            substitutions.push(self.read_expression()?);
+            let tok = self.lexer.next_skip_lineterminator()?;
+            match tok.kind {
+                Kind::Template(kind, cooked, raw)
+                    if kind == TemplateKind::Middle || kind == TemplateKind::Tail =>
+                {
+                    cooked_strings.push(cooked);
+                    raw_strings.push(raw);
+                    if kind == TemplateKind::Tail {
+                        break;
+                    }
+                }
+                _ => return Err(Error::Expect(tok.pos, "expect '}'".to_string())),
+            }
+        }
+
+        Ok((cooked_strings, raw_strings, substitutions))
+    }
+
+    /// https://tc39.github.io/ecma262/#prod-AsyncArrowFunction
+    /// ``kind`` is the kind of the token after ``async``, which is the next token.
+    /// Returns None if ``async`` does not start an async arrow function.
+    fn read_async_arrow_function(&mut self, kind: Kind) -> Option<Result<Node, Error>> {
+        match kind {
+            // async a=>{}
+            Kind::Identifier(_) => match self.lexer.peek(2) {
+                Ok(ref tok) if tok.kind == Kind::Symbol(Symbol::FatArrow) => {
+                    self.lexer.next().unwrap();
+                    Some(self.read_arrow_function(false, true))
+                }
+                _ => None,
+            },
+            // async (a,b)=>{}, or a call of a function named async.
+            Kind::Symbol(Symbol::OpeningParen) => {
+                let save_pos = self.lexer.token_pos;
+                self.lexer.next().unwrap();
+                let f = self.read_arrow_function(true, true);
+                if f.is_err() {
+                    self.lexer.token_pos = save_pos;
+                    None
+                } else {
+                    Some(f)
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// https://tc39.github.io/ecma262/#prod-YieldExpression
+    fn read_yield_expression(&mut self, pos: usize) -> Result<Node, Error> {
+        assert_eq!(
+            self.lexer.next()?.kind,
+            Kind::Identifier("yield".to_string())
+        );
+        let is_delegate = self.lexer.next_if(Kind::Symbol(Symbol::Asterisk));
+        // ``yield`` has no operand if a line terminator or the end of an expression follows.
+        let has_value = is_delegate
+            || match self.lexer.peek(0) {
+                Ok(tok) => !matches!(
+                    tok.kind,
+                    Kind::LineTerminator
+                        | Kind::Symbol(Symbol::ClosingParen)
+                        | Kind::Symbol(Symbol::ClosingBoxBracket)
+                        | Kind::Symbol(Symbol::ClosingBrace)
+                        | Kind::Symbol(Symbol::Comma)
+                        | Kind::Symbol(Symbol::Semicolon)
+                        | Kind::Symbol(Symbol::Colon)
+                ),
+                Err(_) => false,
+            };
+        let value = if has_value {
+            Some(Box::new(self.read_assignment_expression()?))
+        } else {
+            None
+        };
+        Ok(Node::new(NodeBase::Yield(value, is_delegate), pos))
+    }
+
     /// https://www.ecma-international.org/ecma-262/6.0/#sec-arrow-function-definitions
-    fn read_arrow_function(&mut self, is_parenthesized_param: bool) -> Result<Node, Error> {
+    fn read_arrow_function(
+        &mut self,
+        is_parenthesized_param: bool,
+        is_async: bool,
+    ) -> Result<Node, Error> {
         let params;
         let params_pos = self.lexer.get_current_pos();
+        let start = if is_async {
+            self.lexer.get_prev_pos()
+        } else {
+            params_pos
+        };
         if is_parenthesized_param {
             expect!(self, Kind::Symbol(Symbol::OpeningParen), "expect '('");
             params = self.read_formal_parameters()?;
@@ -1090,34 +1997,42 @@ impl Parser {
                 Kind::Identifier(s) => s,
                 _ => unreachable!(),
             };
-            params = vec![FormalParameter {
-                init: None,
-                name: param_name,
-                is_rest_param: false,
-            }];
+            params = vec![FormalParameter::new(
+                Node::new(NodeBase::Identifier(param_name), params_pos),
+                None,
+                false,
+            )];
         }
         expect_no_lineterminator!(self, Kind::Symbol(Symbol::FatArrow), "expect '=>'");
+        let save_in_async = self.in_async;
+        let save_in_generator = self.in_generator;
+        self.in_async = is_async;
+        self.in_generator = false;
         let body = if self
             .lexer
             .next_if_skip_lineterminator(Kind::Symbol(Symbol::OpeningBrace))?
         {
-            self.read_block()?
+            self.read_function_body_block()
         } else {
             let pos = self.lexer.get_current_pos();
-            Node::new(
-                NodeBase::Return(Some(Box::new(self.read_assignment_expression()?))),
-                pos,
-            )
+            self.read_assignment_expression()
+                .map(|expr| Node::new(NodeBase::Return(Some(Box::new(expr))), pos))
         };
+        self.in_async = save_in_async;
+        self.in_generator = save_in_generator;
+        let body = body?;
+        self.record_function_source(params_pos, start);
         Ok(Node::new(
-            NodeBase::ArrowFunction(params, Box::new(body)),
+            NodeBase::ArrowFunction(params, Box::new(body), is_async),
             params_pos,
         ))
     }
 
     /// https://tc39.github.io/ecma262/#prod-FunctionDeclaration
-    fn read_function_expression(&mut self) -> Result<Node, Error> {
+    fn read_function_expression(&mut self, is_async: bool) -> Result<Node, Error> {
         let pos = self.lexer.get_current_pos();
+        let start = self.function_keyword_pos(is_async);
+        let is_generator = self.read_generator_asterisk(is_async)?;
         let name = if let Kind::Identifier(name) = self.lexer.peek(0)?.kind {
             self.lexer.next()?;
             Some(name)
@@ -1131,10 +2046,11 @@ impl Parser {
 
         expect!(self, Kind::Symbol(Symbol::OpeningBrace), "expect '{'");
 
-        let body = self.read_block()?;
+        let body = self.read_function_body(is_async, is_generator)?;
+        self.record_function_source(pos, start);
 
         Ok(Node::new(
-            NodeBase::FunctionExpr(name, params, Box::new(body)),
+            NodeBase::FunctionExpr(name, params, Box::new(body), is_async, is_generator),
             pos,
         ))
     }
@@ -1212,12 +2128,14 @@ impl Parser {
 
     /// https://tc39.github.io/ecma262/#prod-PropertyDefinition
     fn read_property_definition(&mut self) -> Result<PropertyDefinition, Error> {
-        fn to_string(kind: Kind) -> String {
+        /// Any IdentifierName, including reserved words, is a property name.
+        fn to_string(kind: Kind) -> Option<String> {
             match kind {
-                Kind::Identifier(name) => name,
-                Kind::Number(n) => format!("{}", n),
-                Kind::String(s) => s,
-                _ => unimplemented!(),
+                Kind::Identifier(name) => Some(name),
+                Kind::Keyword(kw) => Some(kw.to_str().to_string()),
+                Kind::Number(n) => Some(format!("{}", n)),
+                Kind::String(s) => Some(s),
+                _ => None,
             }
         }
 
@@ -1231,36 +2149,40 @@ impl Parser {
 
         let tok = self.lexer.next_skip_lineterminator()?;
 
+        let is_method = match tok.kind {
+            Kind::Symbol(Symbol::Asterisk) | Kind::Symbol(Symbol::OpeningBoxBracket) => true,
+            // ``get``, ``set`` and ``async`` are property names when no method name follows them.
+            Kind::Identifier(ref name) if name == "get" || name == "set" || name == "async" => {
+                match self.lexer.peek_skip_lineterminator() {
+                    Ok(next) => !matches!(
+                        next.kind,
+                        Kind::Symbol(Symbol::Colon)
+                            | Kind::Symbol(Symbol::Comma)
+                            | Kind::Symbol(Symbol::ClosingBrace)
+                    ),
+                    Err(_) => false,
+                }
+            }
+            _ => self.is_method_name_end(),
+        };
+        if is_method {
+            let (kind, key, func) = self.read_method_definition(tok)?;
+            return Ok(PropertyDefinition::MethodDefinition(kind, key, func));
+        }
+
         if self
             .lexer
             .next_if_skip_lineterminator(Kind::Symbol(Symbol::Colon))?
         {
+            let name = match to_string(tok.kind) {
+                Some(name) => name,
+                None => return Err(Error::Expect(tok.pos, "Expect property name.".to_string())),
+            };
             let val = self.read_assignment_expression()?;
-            return Ok(PropertyDefinition::Property(to_string(tok.kind), val));
+            return Ok(PropertyDefinition::Property(name, val));
         }
 
         if let Kind::Identifier(name) = tok.kind {
-            if name == "get" || name == "set" {
-                let may_identifier = self.lexer.peek_skip_lineterminator();
-                if may_identifier.is_ok() && may_identifier.unwrap().is_identifier() {
-                    let f = self.read_function_expression()?;
-                    let func_name = if let NodeBase::FunctionExpr(ref name, _, _) = f.base {
-                        name.clone().unwrap()
-                    } else {
-                        panic!()
-                    };
-                    return Ok(PropertyDefinition::MethodDefinition(
-                        if name == "get" {
-                            MethodDefinitionKind::Get
-                        } else {
-                            MethodDefinitionKind::Set
-                        },
-                        func_name,
-                        f,
-                    ));
-                }
-            }
-
             return Ok(PropertyDefinition::IdentifierReference(name));
         }
 
@@ -1320,17 +2242,7 @@ impl Parser {
         let pos_catch = self.lexer.get_current_pos();
         let (catch, param) = if is_catch {
             skip_symbol_or_error!(self.lexer, Symbol::OpeningParen);
-            // TODO: should accept BindingPattern
-            let pos_param = self.lexer.get_current_pos();
-            let catch_param = match self.lexer.next()?.kind {
-                Kind::Identifier(s) => Node::new(NodeBase::Identifier(s), pos_param),
-                _ => {
-                    return Err(Error::UnexpectedToken(
-                        pos_param,
-                        "expected identifier.".to_string(),
-                    ));
-                }
-            };
+            let catch_param = self.read_pattern_target(false)?;
             skip_symbol_or_error!(self.lexer, Symbol::ClosingParen);
             skip_symbol_or_error!(self.lexer, Symbol::OpeningBrace);
             (self.read_block()?, catch_param)
@@ -1403,7 +2315,12 @@ impl Parser {
     fn read_declaration(&mut self) -> Result<Node, Error> {
         let tok = self.lexer.next_skip_lineterminator()?;
         match tok.kind {
-            Kind::Keyword(Keyword::Function) => self.read_function_declaration(),
+            Kind::Keyword(Keyword::Function) => self.read_function_declaration(false),
+            Kind::Identifier(ref name) if name == "async" => {
+                self.lexer.next()?;
+                self.read_function_declaration(true)
+            }
+            Kind::Keyword(Keyword::Class) => self.read_class_declaration(),
             Kind::Keyword(Keyword::Const) => self.read_lexical_declaration(true),
             Kind::Keyword(Keyword::Let) => self.read_lexical_declaration(false),
             _ => unreachable!(),
@@ -1422,6 +2339,14 @@ impl Parser {
         let mut list = vec![];
 
         loop {
+            if self.is_pattern_start() {
+                list.push(self.read_pattern_declaration(var_kind)?);
+                if !self.variable_declaration_continuation()? {
+                    break;
+                }
+                continue;
+            }
+
             let pos = self.lexer.get_current_pos();
             let name = match self.lexer.next_skip_lineterminator()?.kind {
                 Kind::Identifier(name) => name,
@@ -1453,8 +2378,10 @@ impl Parser {
     }
 
     /// https://tc39.github.io/ecma262/#prod-FunctionDeclaration
-    fn read_function_declaration(&mut self) -> Result<Node, Error> {
+    fn read_function_declaration(&mut self, is_async: bool) -> Result<Node, Error> {
         let pos = self.lexer.get_prev_pos();
+        let start = self.function_keyword_pos(is_async);
+        let is_generator = self.read_generator_asterisk(is_async)?;
         let name = if let Kind::Identifier(name) = self.lexer.next_skip_lineterminator()?.kind {
             name
         } else {
@@ -1470,86 +2397,636 @@ impl Parser {
 
         expect!(self, Kind::Symbol(Symbol::OpeningBrace), "expect '{'");
 
-        let body = self.read_block()?;
+        let body = self.read_function_body(is_async, is_generator)?;
+        self.record_function_source(pos, start);
 
         Ok(Node::new(
-            NodeBase::FunctionDecl(name, params, Box::new(body)),
+            NodeBase::FunctionDecl(name, params, Box::new(body), is_async, is_generator),
             pos,
         ))
     }
 
-    fn read_formal_parameters(&mut self) -> Result<FormalParameters, Error> {
-        if self
-            .lexer
-            .next_if_skip_lineterminator(Kind::Symbol(Symbol::ClosingParen))?
-        {
-            return Ok(vec![]);
-        }
+    /// Reads a function body after '{'.
+    fn read_function_body(&mut self, is_async: bool, is_generator: bool) -> Result<Node, Error> {
+        let save_in_async = self.in_async;
+        let save_in_generator = self.in_generator;
+        self.in_async = is_async;
+        self.in_generator = is_generator;
+        let body = self.read_function_body_block();
+        self.in_async = save_in_async;
+        self.in_generator = save_in_generator;
+        body
+    }
 
-        let mut params = vec![];
+    /// The position of ``function`` just read, or of ``async`` before it, where the source text
+    /// of the function starts.
+    fn function_keyword_pos(&self, is_async: bool) -> usize {
+        let token_pos = self.lexer.token_pos - if is_async { 2 } else { 1 };
+        self.lexer.buf[token_pos].pos
+    }
 
-        loop {
-            let mut rest_param = false;
+    /// Records the source text of the function or class whose node is at ``pos``, which starts
+    /// at ``start`` and ends with the previous token.
+    fn record_function_source(&mut self, pos: usize, start: usize) {
+        let end = self.lexer.get_prev_end_pos();
+        self.function_sources.insert(pos, (start, end));
+    }
 
-            params.push(
-                if self
-                    .lexer
-                    .next_if_skip_lineterminator(Kind::Symbol(Symbol::Spread))?
-                {
-                    rest_param = true;
-                    self.read_function_rest_parameter()?
-                } else {
-                    self.read_formal_parameter()?
-                },
-            );
+    /// Reads '*' after ``function`` and returns true if the function is a generator.
+    fn read_generator_asterisk(&mut self, is_async: bool) -> Result<bool, Error> {
+        if !self
+            .lexer
+            .next_if_skip_lineterminator(Kind::Symbol(Symbol::Asterisk))?
+        {
+            return Ok(false);
+        }
+        if is_async {
+            // TODO: Support async generators.
+            return Err(Error::UnsupportedFeature(self.lexer.get_prev_pos()));
+        }
+        Ok(true)
+    }
 
-            if self.lexer.next_if(Kind::Symbol(Symbol::ClosingParen)) {
-                break;
-            }
+    /// True if the numeric literal at ``pos`` is a LegacyOctalIntegerLiteral (e.g. ``017``) or a
+    /// NonOctalDecimalIntegerLiteral (e.g. ``09``), which are not allowed in strict mode code.
+    fn is_legacy_octal_like_literal(&self, pos: usize) -> bool {
+        let mut chars = self.lexer.code[pos..].chars();
+        chars.next() == Some('0') && matches!(chars.next(), Some(c) if c.is_ascii_digit())
+    }
 
-            if rest_param {
-                return Err(Error::UnexpectedToken(
-                    self.lexer.get_current_pos(),
-                    "rest parameter must be the last formal parameter".to_string(),
-                ));
+    /// True if ``async function`` follows, with no line terminator between them.
+    fn is_async_function(&mut self) -> bool {
+        if self.lexer.skip_lineterminator().is_err() {
+            return false;
+        }
+        match (self.lexer.peek(0), self.lexer.peek(1)) {
+            (Ok(tok), Ok(next)) => {
+                tok.kind == Kind::Identifier("async".to_string())
+                    && next.kind == Kind::Keyword(Keyword::Function)
             }
-
-            expect!(self, Kind::Symbol(Symbol::Comma), "expect ','");
+            _ => false,
         }
-
-        Ok(params)
     }
 
-    // TODO: Support all features: https://tc39.github.io/ecma262/#prod-FormalParameter
-    fn read_formal_parameter(&mut self) -> Result<FormalParameter, Error> {
-        let pos = self.lexer.get_current_pos();
+    /// https://tc39.github.io/ecma262/#prod-ClassDeclaration
+    /// A class declaration binds the class like a ``let`` declaration.
+    fn read_class_declaration(&mut self) -> Result<Node, Error> {
+        let pos = self.lexer.get_prev_pos();
         let name = if let Kind::Identifier(name) = self.lexer.next_skip_lineterminator()?.kind {
             name
         } else {
             return Err(Error::Expect(
-                pos,
-                "expect identifier (unsupported feature)".to_string(),
+                self.lexer.get_prev_pos(),
+                "expect class name".to_string(),
             ));
         };
-        // TODO: Implement initializer.
-        Ok(FormalParameter::new(name, None, false))
+
+        let class = self.read_class_tail(Some(name.clone()), pos)?;
+
+        Ok(Node::new(
+            NodeBase::VarDecl(name, Some(Box::new(class)), VarKind::Let),
+            pos,
+        ))
     }
 
-    fn read_function_rest_parameter(&mut self) -> Result<FormalParameter, Error> {
-        let pos = self.lexer.get_current_pos();
-        Ok(FormalParameter::new(
-            if let Kind::Identifier(name) = self.lexer.next()?.kind {
-                name
-            } else {
-                return Err(Error::Expect(
-                    pos,
-                    "rest params: expect identifier".to_string(),
+    /// https://tc39.github.io/ecma262/#prod-ClassExpression
+    fn read_class_expression(&mut self) -> Result<Node, Error> {
+        let pos = self.lexer.get_prev_pos();
+        let name = if let Kind::Identifier(name) = self.lexer.peek_skip_lineterminator()?.kind {
+            self.lexer.next_skip_lineterminator()?;
+            Some(name)
+        } else {
+            None
+        };
+
+        self.read_class_tail(name, pos)
+    }
+
+    /// https://tc39.github.io/ecma262/#prod-ClassTail
+    /// All parts of a class are strict mode code.
+    fn read_class_tail(&mut self, name: Option<String>, pos: usize) -> Result<Node, Error> {
+        let save_in_strict = self.in_strict;
+        self.in_strict = true;
+        let class = self.read_class_heritage_and_body(name, pos);
+        self.in_strict = save_in_strict;
+        if class.is_ok() {
+            self.record_function_source(pos, pos);
+        }
+        class
+    }
+
+    fn read_class_heritage_and_body(
+        &mut self,
+        name: Option<String>,
+        pos: usize,
+    ) -> Result<Node, Error> {
+        let heritage = if self
+            .lexer
+            .next_if_skip_lineterminator(Kind::Keyword(Keyword::Extends))?
+        {
+            Some(Box::new(self.read_left_hand_side_expression()?))
+        } else {
+            None
+        };
+
+        expect!(self, Kind::Symbol(Symbol::OpeningBrace), "expect '{'");
+
+        let mut constructor = None;
+        let mut elements = vec![];
+
+        loop {
+            if self
+                .lexer
+                .next_if_skip_lineterminator(Kind::Symbol(Symbol::ClosingBrace))?
+            {
+                break;
+            }
+
+            if self
+                .lexer
+                .next_if_skip_lineterminator(Kind::Symbol(Symbol::Semicolon))?
+            {
+                continue;
+            }
+
+            let element_pos = self.lexer.get_current_pos();
+            let (element, is_constructor) = self.read_class_element()?;
+
+            if !is_constructor {
+                elements.push(element);
+                continue;
+            }
+
+            if element.kind != MethodDefinitionKind::Ordinary {
+                return Err(Error::General(
+                    element_pos,
+                    "Class constructor may not be an accessor".to_string(),
                 ));
-            },
-            None,
-            true,
+            }
+
+            if constructor.is_some() {
+                return Err(Error::General(
+                    element_pos,
+                    "A class may only have one constructor".to_string(),
+                ));
+            }
+
+            constructor = Some(Box::new(element.func));
+        }
+
+        Ok(Node::new(
+            NodeBase::Class(name, heritage, constructor, elements),
+            pos,
         ))
     }
+
+    /// https://tc39.github.io/ecma262/#prod-ClassElement
+    /// Also returns true if the element is the class constructor.
+    fn read_class_element(&mut self) -> Result<(ClassElement, bool), Error> {
+        let mut tok = self.lexer.next_skip_lineterminator()?;
+
+        // ``static`` is a method name when '(' follows it.
+        let is_static =
+            if tok.kind == Kind::Identifier("static".to_string()) && !self.is_method_name_end() {
+                tok = self.lexer.next_skip_lineterminator()?;
+                true
+            } else {
+                false
+            };
+
+        let (kind, key, func) = self.read_method_definition(tok)?;
+
+        let (is_constructor, is_async, is_generator) = match func.base {
+            NodeBase::FunctionExpr(ref name, _, _, is_async, is_generator) => (
+                !is_static && name.as_ref().map(|name| name.as_str()) == Some("constructor"),
+                is_async,
+                is_generator,
+            ),
+            _ => unreachable!(),
+        };
+        if is_constructor && is_async {
+            return Err(Error::General(
+                key.pos,
+                "Class constructor may not be an async method".to_string(),
+            ));
+        }
+        if is_constructor && is_generator {
+            return Err(Error::General(
+                key.pos,
+                "Class constructor may not be a generator".to_string(),
+            ));
+        }
+
+        Ok((
+            ClassElement::new(is_static, kind, key, func),
+            is_constructor,
+        ))
+    }
+
+    /// https://tc39.github.io/ecma262/#prod-MethodDefinition
+    /// ``tok`` is the first token of the method. The key is a String node unless the name is
+    /// computed, and the function is a FunctionExpr.
+    fn read_method_definition(
+        &mut self,
+        mut tok: Token,
+    ) -> Result<(MethodDefinitionKind, Node, Node), Error> {
+        // The source text of a method does not include ``static``.
+        let start = tok.pos;
+
+        // ``async`` is a method name when '(' follows it.
+        let is_async = tok.kind == Kind::Identifier("async".to_string())
+            && !self.is_method_name_end()
+            && self.lexer.peek(0).map(|tok| tok.kind) != Ok(Kind::LineTerminator);
+        if is_async {
+            tok = self.lexer.next_skip_lineterminator()?;
+        }
+
+        let is_generator = tok.kind == Kind::Symbol(Symbol::Asterisk);
+        if is_generator {
+            if is_async {
+                // TODO: Support async generators.
+                return Err(Error::UnsupportedFeature(tok.pos));
+            }
+            tok = self.lexer.next_skip_lineterminator()?;
+        }
+
+        // ``get`` and ``set`` are method names when '(' follows them.
+        let kind = match tok.kind {
+            Kind::Identifier(ref name) if name == "get" && !self.is_method_name_end() => {
+                MethodDefinitionKind::Get
+            }
+            Kind::Identifier(ref name) if name == "set" && !self.is_method_name_end() => {
+                MethodDefinitionKind::Set
+            }
+            _ => MethodDefinitionKind::Ordinary,
+        };
+        if kind != MethodDefinitionKind::Ordinary {
+            if is_async || is_generator {
+                return Err(Error::UnexpectedToken(
+                    tok.pos,
+                    "unexpected token.".to_string(),
+                ));
+            }
+            tok = self.lexer.next_skip_lineterminator()?;
+        }
+
+        // ``name`` is None if the key is computed.
+        let name = match tok.kind {
+            Kind::Identifier(name) | Kind::String(name) => Some(name),
+            Kind::Keyword(kw) => Some(kw.to_str().to_string()),
+            Kind::Number(n) => Some(format!("{}", n)),
+            Kind::Symbol(Symbol::OpeningBoxBracket) => None,
+            _ => {
+                return Err(Error::Expect(
+                    tok.pos,
+                    "expect method definition".to_string(),
+                ))
+            }
+        };
+
+        let key = match name {
+            Some(ref name) => Node::new(NodeBase::String(name.clone()), tok.pos),
+            None => {
+                let key = self.read_assignment_expression()?;
+                expect!(self, Kind::Symbol(Symbol::ClosingBoxBracket), "expect ']'");
+                key
+            }
+        };
+
+        let pos = self.lexer.get_current_pos();
+
+        expect!(self, Kind::Symbol(Symbol::OpeningParen), "expect '('");
+
+        let params = self.read_formal_parameters()?;
+
+        expect!(self, Kind::Symbol(Symbol::OpeningBrace), "expect '{'");
+
+        let body = self.read_function_body(is_async, is_generator)?;
+        self.record_function_source(pos, start);
+
+        let func = Node::new(
+            NodeBase::FunctionExpr(name, params, Box::new(body), is_async, is_generator),
+            pos,
+        );
+
+        Ok((kind, key, func))
+    }
+
+    fn is_method_name_end(&mut self) -> bool {
+        match self.lexer.peek_skip_lineterminator() {
+            Ok(tok) => tok.kind == Kind::Symbol(Symbol::OpeningParen),
+            Err(_) => true,
+        }
+    }
+
+    fn read_formal_parameters(&mut self) -> Result<FormalParameters, Error> {
+        if self
+            .lexer
+            .next_if_skip_lineterminator(Kind::Symbol(Symbol::ClosingParen))?
+        {
+            return Ok(vec![]);
+        }
+
+        let mut params = vec![];
+
+        loop {
+            let mut rest_param = false;
+
+            params.push(
+                if self
+                    .lexer
+                    .next_if_skip_lineterminator(Kind::Symbol(Symbol::Spread))?
+                {
+                    rest_param = true;
+                    self.read_function_rest_parameter()?
+                } else {
+                    self.read_formal_parameter()?
+                },
+            );
+
+            if self
+                .lexer
+                .next_if_skip_lineterminator(Kind::Symbol(Symbol::ClosingParen))?
+            {
+                break;
+            }
+
+            if rest_param {
+                return Err(Error::UnexpectedToken(
+                    self.lexer.get_current_pos(),
+                    "rest parameter must be the last formal parameter".to_string(),
+                ));
+            }
+
+            expect!(self, Kind::Symbol(Symbol::Comma), "expect ','");
+        }
+
+        Ok(params)
+    }
+
+    /// https://tc39.github.io/ecma262/#prod-FormalParameter
+    fn read_formal_parameter(&mut self) -> Result<FormalParameter, Error> {
+        let binding = self.read_pattern_target(false)?;
+        let init = if self
+            .lexer
+            .next_if_skip_lineterminator(Kind::Symbol(Symbol::Assign))?
+        {
+            Some(self.read_assignment_expression()?)
+        } else {
+            None
+        };
+        Ok(FormalParameter::new(binding, init, false))
+    }
+
+    fn read_function_rest_parameter(&mut self) -> Result<FormalParameter, Error> {
+        let binding = self.read_pattern_target(false)?;
+        Ok(FormalParameter::new(binding, None, true))
+    }
+}
+
+impl Parser {
+    fn is_pattern_start(&mut self) -> bool {
+        match self.lexer.peek_skip_lineterminator() {
+            Ok(tok) => {
+                tok.kind == Kind::Symbol(Symbol::OpeningBoxBracket)
+                    || tok.kind == Kind::Symbol(Symbol::OpeningBrace)
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// An array or object literal followed by '=' is an assignment pattern.
+    fn is_assignment_pattern(&mut self) -> bool {
+        if !self.is_pattern_start() {
+            return false;
+        }
+        let mut i = match self.peek_after_brackets(0) {
+            Some(end) => end,
+            None => return false,
+        };
+        loop {
+            match self.lexer.peek(i) {
+                Ok(Token {
+                    kind: Kind::LineTerminator,
+                    ..
+                }) => i += 1,
+                Ok(Token {
+                    kind: Kind::Symbol(Symbol::Assign),
+                    ..
+                }) => return true,
+                _ => return false,
+            }
+        }
+    }
+
+    /// Return the index (for ``Lexer::peek``) of the token next to the brackets
+    /// opened at or after ``index``.
+    fn peek_after_brackets(&mut self, index: usize) -> Option<usize> {
+        let mut depth = 0;
+        for i in index.. {
+            match self.lexer.peek(i).ok()?.kind {
+                Kind::Symbol(Symbol::OpeningBoxBracket)
+                | Kind::Symbol(Symbol::OpeningBrace)
+                | Kind::Symbol(Symbol::OpeningParen) => depth += 1,
+                Kind::Symbol(Symbol::ClosingBoxBracket)
+                | Kind::Symbol(Symbol::ClosingBrace)
+                | Kind::Symbol(Symbol::ClosingParen) => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(i + 1);
+                    }
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    /// https://tc39.github.io/ecma262/#prod-BindingPattern
+    /// https://tc39.github.io/ecma262/#prod-AssignmentPattern
+    /// Targets in an assignment pattern may be any reference, not only identifiers.
+    fn read_pattern(&mut self, is_assignment: bool) -> Result<Node, Error> {
+        let tok = self.lexer.next_skip_lineterminator()?;
+        match tok.kind {
+            Kind::Symbol(Symbol::OpeningBoxBracket) => {
+                self.read_array_pattern(tok.pos, is_assignment)
+            }
+            Kind::Symbol(Symbol::OpeningBrace) => self.read_object_pattern(tok.pos, is_assignment),
+            _ => Err(Error::Expect(tok.pos, "expect '[' or '{'".to_string())),
+        }
+    }
+
+    fn read_array_pattern(&mut self, pos: usize, is_assignment: bool) -> Result<Node, Error> {
+        let mut elems = vec![];
+
+        loop {
+            if self
+                .lexer
+                .next_if_skip_lineterminator(Kind::Symbol(Symbol::ClosingBoxBracket))?
+            {
+                break;
+            }
+
+            let elem_pos = self.lexer.get_current_pos();
+
+            if self
+                .lexer
+                .next_if_skip_lineterminator(Kind::Symbol(Symbol::Comma))?
+            {
+                elems.push(Node::new(NodeBase::Nope, elem_pos));
+                continue;
+            }
+
+            if self
+                .lexer
+                .next_if_skip_lineterminator(Kind::Symbol(Symbol::Spread))?
+            {
+                let target = self.read_pattern_target(is_assignment)?;
+                elems.push(Node::new(NodeBase::Spread(Box::new(target)), elem_pos));
+                expect!(
+                    self,
+                    Kind::Symbol(Symbol::ClosingBoxBracket),
+                    "Rest element must be last element"
+                );
+                break;
+            }
+
+            elems.push(self.read_pattern_element(is_assignment)?);
+
+            if self
+                .lexer
+                .next_if_skip_lineterminator(Kind::Symbol(Symbol::ClosingBoxBracket))?
+            {
+                break;
+            }
+            expect!(self, Kind::Symbol(Symbol::Comma), "expect ',' or ']'");
+        }
+
+        Ok(Node::new(NodeBase::ArrayPattern(elems), pos))
+    }
+
+    fn read_object_pattern(&mut self, pos: usize, is_assignment: bool) -> Result<Node, Error> {
+        let mut properties = vec![];
+
+        loop {
+            if self
+                .lexer
+                .next_if_skip_lineterminator(Kind::Symbol(Symbol::ClosingBrace))?
+            {
+                break;
+            }
+
+            if self
+                .lexer
+                .next_if_skip_lineterminator(Kind::Symbol(Symbol::Spread))?
+            {
+                let target = self.read_pattern_target(is_assignment)?;
+                properties.push(PropertyPattern::Rest(target));
+                expect!(
+                    self,
+                    Kind::Symbol(Symbol::ClosingBrace),
+                    "Rest element must be last element"
+                );
+                break;
+            }
+
+            let tok = self.lexer.next_skip_lineterminator()?;
+            // ``shorthand`` is Some if the property may be written as ``{ name }``.
+            let (key, shorthand) = match tok.kind {
+                Kind::Identifier(name) => (
+                    Node::new(NodeBase::String(name.clone()), tok.pos),
+                    Some(name),
+                ),
+                Kind::String(name) => (Node::new(NodeBase::String(name), tok.pos), None),
+                Kind::Keyword(kw) => (
+                    Node::new(NodeBase::String(kw.to_str().to_string()), tok.pos),
+                    None,
+                ),
+                Kind::Number(n) => (Node::new(NodeBase::String(format!("{}", n)), tok.pos), None),
+                Kind::Symbol(Symbol::OpeningBoxBracket) => {
+                    let key = self.read_assignment_expression()?;
+                    expect!(self, Kind::Symbol(Symbol::ClosingBoxBracket), "expect ']'");
+                    (key, None)
+                }
+                _ => return Err(Error::Expect(tok.pos, "expect property name".to_string())),
+            };
+
+            let target = if self
+                .lexer
+                .next_if_skip_lineterminator(Kind::Symbol(Symbol::Colon))?
+            {
+                self.read_pattern_element(is_assignment)?
+            } else if let Some(name) = shorthand {
+                let target = Node::new(NodeBase::Identifier(name), tok.pos);
+                self.read_pattern_default(target)?
+            } else {
+                return Err(Error::Expect(
+                    self.lexer.get_current_pos(),
+                    "expect ':'".to_string(),
+                ));
+            };
+
+            properties.push(PropertyPattern::Property(key, target));
+
+            if self
+                .lexer
+                .next_if_skip_lineterminator(Kind::Symbol(Symbol::ClosingBrace))?
+            {
+                break;
+            }
+            expect!(self, Kind::Symbol(Symbol::Comma), "expect ',' or '}'");
+        }
+
+        Ok(Node::new(NodeBase::ObjectPattern(properties), pos))
+    }
+
+    /// A target optionally followed by a default value.
+    fn read_pattern_element(&mut self, is_assignment: bool) -> Result<Node, Error> {
+        let target = self.read_pattern_target(is_assignment)?;
+        self.read_pattern_default(target)
+    }
+
+    fn read_pattern_default(&mut self, target: Node) -> Result<Node, Error> {
+        if !self
+            .lexer
+            .next_if_skip_lineterminator(Kind::Symbol(Symbol::Assign))?
+        {
+            return Ok(target);
+        }
+        let pos = target.pos;
+        let default = self.read_assignment_expression()?;
+        Ok(Node::new(
+            NodeBase::AssignmentPattern(Box::new(target), Box::new(default)),
+            pos,
+        ))
+    }
+
+    /// An identifier or a nested pattern.
+    /// In an assignment pattern, also a member expression.
+    fn read_pattern_target(&mut self, is_assignment: bool) -> Result<Node, Error> {
+        if self.is_pattern_start() {
+            return self.read_pattern(is_assignment);
+        }
+
+        if is_assignment {
+            let target = self.read_left_hand_side_expression()?;
+            return match target.base {
+                NodeBase::Identifier(_) | NodeBase::Member(_, _) | NodeBase::Index(_, _) => {
+                    Ok(target)
+                }
+                _ => Err(Error::General(
+                    target.pos,
+                    "Invalid destructuring assignment target".to_string(),
+                )),
+            };
+        }
+
+        let tok = self.lexer.next_skip_lineterminator()?;
+        match tok.kind {
+            Kind::Identifier(name) => Ok(Node::new(NodeBase::Identifier(name), tok.pos)),
+            _ => Err(Error::Expect(tok.pos, "expect identifier".to_string())),
+        }
+    }
 }
 
 #[test]
@@ -1686,7 +3163,13 @@ fn object() {
             0
         )
     );
-    for input in ["a = {}", "a = {b}"].iter() {
+    for input in [
+        "a = {}",
+        "a = {b}",
+        "a = {default: 1, return: function() {}, if() {}}",
+    ]
+    .iter()
+    {
         let mut parser = Parser::new("test", input.to_string());
         parser.parse_all().unwrap();
     }
@@ -1788,6 +3271,8 @@ fn simple_expr_rel() {
         ("1 + 2 > 3", BinOp::Gt, 8),
         ("1 + 2 <= 3", BinOp::Le, 9),
         ("1 + 2 >= 3", BinOp::Ge, 9),
+        ("1 + 2 in 3", BinOp::In, 9),
+        ("1 + 2 instanceof 3", BinOp::InstanceOf, 17),
     ]
     .iter()
     {
@@ -2274,129 +3759,452 @@ fn return_() {
     .iter()
     {
         let mut parser = Parser::new("test", input.to_string());
-        assert_eq!(
-            parser.parse_all().unwrap(),
-            Node::new(NodeBase::StatementList(vec![node.clone()]), 0)
-        );
+        assert_eq!(
+            parser.parse_all().unwrap(),
+            Node::new(NodeBase::StatementList(vec![node.clone()]), 0)
+        );
+    }
+}
+
+#[test]
+fn if_() {
+    use crate::node::BinOp;
+
+    let mut parser = Parser::new(
+        "test",
+        "if (x <= 2) 
+            then_stmt 
+        else 
+            else_stmt"
+            .to_string(),
+    );
+    assert_eq!(
+        parser.parse_all().unwrap(),
+        Node::new(
+            NodeBase::StatementList(vec![Node::new(
+                NodeBase::If(
+                    Box::new(Node::new(
+                        NodeBase::BinaryOp(
+                            Box::new(Node::new(NodeBase::Identifier("x".to_string()), 4)),
+                            Box::new(Node::new(NodeBase::Number(2.0), 9)),
+                            BinOp::Le,
+                        ),
+                        9,
+                    )),
+                    Box::new(Node::new(NodeBase::Identifier("then_stmt".to_string()), 25)),
+                    Box::new(Node::new(NodeBase::Identifier("else_stmt".to_string()), 62)),
+                ),
+                0,
+            )]),
+            0
+        )
+    );
+
+    parser = Parser::new("test", "if (x <= 2) then_stmt ".to_string());
+    assert_eq!(
+        parser.parse_all().unwrap(),
+        Node::new(
+            NodeBase::StatementList(vec![Node::new(
+                NodeBase::If(
+                    Box::new(Node::new(
+                        NodeBase::BinaryOp(
+                            Box::new(Node::new(NodeBase::Identifier("x".to_string()), 4)),
+                            Box::new(Node::new(NodeBase::Number(2.0), 9)),
+                            BinOp::Le,
+                        ),
+                        9,
+                    )),
+                    Box::new(Node::new(NodeBase::Identifier("then_stmt".to_string()), 12)),
+                    Box::new(Node::new(NodeBase::Nope, 22)),
+                ),
+                0,
+            )]),
+            0
+        )
+    );
+
+    for input in ["if(", "if()else", "if(true){} 8j"].iter() {
+        let mut parser = Parser::new("test", input.to_string());
+        parser.parse_all().expect_err("should be error");
+    }
+}
+
+#[test]
+fn while_() {
+    let mut parser = Parser::new("test", "while (true) { }".to_string());
+    assert_eq!(
+        parser.parse_all().unwrap(),
+        Node::new(
+            NodeBase::StatementList(vec![Node::new(
+                NodeBase::While(
+                    Box::new(Node::new(NodeBase::Boolean(true), 7)),
+                    Box::new(Node::new(NodeBase::Block(vec![]), 13)),
+                ),
+                0,
+            )]),
+            0
+        )
+    );
+}
+
+#[test]
+fn for1() {
+    let mut parser = Parser::new("test", "for (;;) { }".to_string());
+    assert_eq!(
+        parser.parse_all().unwrap(),
+        Node::new(
+            NodeBase::StatementList(vec![Node::new(
+                NodeBase::Block(vec![Node::new(
+                    NodeBase::For(
+                        Box::new(Node::new(NodeBase::Nope, 4)),
+                        Box::new(Node::new(NodeBase::Boolean(true), 6)),
+                        Box::new(Node::new(NodeBase::Nope, 7)),
+                        Box::new(Node::new(NodeBase::Block(vec![]), 9)),
+                    ),
+                    0,
+                )]),
+                0,
+            )]),
+            0
+        )
+    );
+    for input in [
+        "for(){}",
+        "for(;){}",
+        "for(;;;){}",
+        "for(var){}",
+        "for(var a=1){}",
+        "for(a=1){}",
+        "for(a=1;a<8)",
+        "for(a=1;a<8;a++",
+    ]
+    .iter()
+    {
+        let mut parser = Parser::new("test", input.to_string());
+        parser.parse_all().expect_err("should be error");
+    }
+}
+
+#[test]
+fn switch() {
+    let mut parser = Parser::new("test", "switch (x) { case 1: a; default: b }".to_string());
+    assert_eq!(
+        parser.parse_all().unwrap(),
+        Node::new(
+            NodeBase::StatementList(vec![Node::new(
+                NodeBase::Switch(
+                    Box::new(Node::new(NodeBase::Identifier("x".to_string()), 8)),
+                    vec![
+                        CaseClause::new(
+                            Some(Node::new(NodeBase::Number(1.0), 18)),
+                            vec![Node::new(NodeBase::Identifier("a".to_string()), 21)],
+                        ),
+                        CaseClause::new(
+                            None,
+                            vec![Node::new(NodeBase::Identifier("b".to_string()), 33)],
+                        ),
+                    ],
+                ),
+                0,
+            )]),
+            0
+        )
+    );
+    for input in [
+        "switch(){}",
+        "switch(x)",
+        "switch(x){",
+        "switch(x){ a }",
+        "switch(x){ case 1 }",
+        "switch(x){ default: default: }",
+    ]
+    .iter()
+    {
+        let mut parser = Parser::new("test", input.to_string());
+        parser.parse_all().expect_err("should be error");
+    }
+}
+
+#[test]
+fn do_while() {
+    let mut parser = Parser::new("test", "do a; while (b)".to_string());
+    assert_eq!(
+        parser.parse_all().unwrap(),
+        Node::new(
+            NodeBase::StatementList(vec![Node::new(
+                NodeBase::DoWhile(
+                    Box::new(Node::new(NodeBase::Identifier("a".to_string()), 3)),
+                    Box::new(Node::new(NodeBase::Identifier("b".to_string()), 13)),
+                ),
+                0,
+            )]),
+            0
+        )
+    );
+    for input in ["do a", "do a; while", "do a; while (b"].iter() {
+        let mut parser = Parser::new("test", input.to_string());
+        parser.parse_all().expect_err("should be error");
+    }
+}
+
+#[test]
+fn for_in_of() {
+    let mut parser = Parser::new("test", "for (let x of a) b; for (k in o) c".to_string());
+    assert_eq!(
+        parser.parse_all().unwrap(),
+        Node::new(
+            NodeBase::StatementList(vec![
+                Node::new(
+                    NodeBase::ForOf(
+                        Box::new(Node::new(
+                            NodeBase::VarDecl("x".to_string(), None, VarKind::Let),
+                            5
+                        )),
+                        Box::new(Node::new(NodeBase::Identifier("a".to_string()), 14)),
+                        Box::new(Node::new(NodeBase::Identifier("b".to_string()), 17)),
+                    ),
+                    0,
+                ),
+                Node::new(
+                    NodeBase::ForIn(
+                        Box::new(Node::new(NodeBase::Identifier("k".to_string()), 25)),
+                        Box::new(Node::new(NodeBase::Identifier("o".to_string()), 30)),
+                        Box::new(Node::new(NodeBase::Identifier("c".to_string()), 33)),
+                    ),
+                    20,
+                ),
+            ]),
+            0
+        )
+    );
+    for input in [
+        "for (let x of) a",
+        "for (let x in o a",
+        "for (let x, y of a) b",
+        "for (1 of a) b",
+    ]
+    .iter()
+    {
+        let mut parser = Parser::new("test", input.to_string());
+        parser.parse_all().expect_err("should be error");
     }
 }
 
 #[test]
-fn if_() {
-    use crate::node::BinOp;
-
+fn class() {
     let mut parser = Parser::new(
         "test",
-        "if (x <= 2) 
-            then_stmt 
-        else 
-            else_stmt"
-            .to_string(),
+        "class A extends B { constructor() {} static get x() {} }".to_string(),
     );
     assert_eq!(
         parser.parse_all().unwrap(),
         Node::new(
             NodeBase::StatementList(vec![Node::new(
-                NodeBase::If(
-                    Box::new(Node::new(
-                        NodeBase::BinaryOp(
-                            Box::new(Node::new(NodeBase::Identifier("x".to_string()), 4)),
-                            Box::new(Node::new(NodeBase::Number(2.0), 9)),
-                            BinOp::Le,
+                NodeBase::VarDecl(
+                    "A".to_string(),
+                    Some(Box::new(Node::new(
+                        NodeBase::Class(
+                            Some("A".to_string()),
+                            Some(Box::new(Node::new(
+                                NodeBase::Identifier("B".to_string()),
+                                16
+                            ))),
+                            Some(Box::new(Node::new(
+                                NodeBase::FunctionExpr(
+                                    Some("constructor".to_string()),
+                                    vec![],
+                                    Box::new(Node::new(NodeBase::StatementList(vec![]), 34)),
+                                    false,
+                                    false,
+                                ),
+                                31
+                            ))),
+                            vec![ClassElement::new(
+                                true,
+                                MethodDefinitionKind::Get,
+                                Node::new(NodeBase::String("x".to_string()), 48),
+                                Node::new(
+                                    NodeBase::FunctionExpr(
+                                        Some("x".to_string()),
+                                        vec![],
+                                        Box::new(Node::new(NodeBase::StatementList(vec![]), 52)),
+                                        false,
+                                        false,
+                                    ),
+                                    49
+                                ),
+                            )],
                         ),
-                        9,
-                    )),
-                    Box::new(Node::new(NodeBase::Identifier("then_stmt".to_string()), 25)),
-                    Box::new(Node::new(NodeBase::Identifier("else_stmt".to_string()), 62)),
+                        0
+                    ))),
+                    VarKind::Let
                 ),
-                0,
+                0
             )]),
             0
         )
     );
+    for input in [
+        "class {}",
+        "class A { constructor() {} constructor() {} }",
+        "class A { get constructor() {} }",
+        "class A extends {}",
+        "super",
+    ]
+    .iter()
+    {
+        let mut parser = Parser::new("test", input.to_string());
+        parser.parse_all().expect_err("should be error");
+    }
+}
 
-    parser = Parser::new("test", "if (x <= 2) then_stmt ".to_string());
+#[test]
+fn template_literal() {
+    let mut parser = Parser::new("test", "`a${x}b`; t`c`".to_string());
     assert_eq!(
         parser.parse_all().unwrap(),
         Node::new(
-            NodeBase::StatementList(vec![Node::new(
-                NodeBase::If(
-                    Box::new(Node::new(
-                        NodeBase::BinaryOp(
-                            Box::new(Node::new(NodeBase::Identifier("x".to_string()), 4)),
-                            Box::new(Node::new(NodeBase::Number(2.0), 9)),
-                            BinOp::Le,
-                        ),
-                        9,
-                    )),
-                    Box::new(Node::new(NodeBase::Identifier("then_stmt".to_string()), 12)),
-                    Box::new(Node::new(NodeBase::Nope, 22)),
+            NodeBase::StatementList(vec![
+                Node::new(
+                    NodeBase::TemplateLiteral(
+                        vec!["a".to_string(), "b".to_string()],
+                        vec![Node::new(NodeBase::Identifier("x".to_string()), 4)],
+                    ),
+                    0,
                 ),
-                0,
-            )]),
+                Node::new(
+                    NodeBase::TaggedTemplate(
+                        Box::new(Node::new(NodeBase::Identifier("t".to_string()), 10)),
+                        vec![Some("c".to_string())],
+                        vec!["c".to_string()],
+                        vec![],
+                    ),
+                    10,
+                ),
+            ]),
             0
         )
     );
-
-    for input in ["if(", "if()else", "if(true){} 8j"].iter() {
+    for input in ["`a${}`", "`a${b c}`", "`a${b`", "`\\unicode`", "`\\x4`"].iter() {
         let mut parser = Parser::new("test", input.to_string());
         parser.parse_all().expect_err("should be error");
     }
 }
 
 #[test]
-fn while_() {
-    let mut parser = Parser::new("test", "while (true) { }".to_string());
+fn destructuring() {
+    let mut parser = Parser::new(
+        "test",
+        "let [a, , ...b] = x; ({ a, b: [c = 1], ...d } = y)".to_string(),
+    );
     assert_eq!(
         parser.parse_all().unwrap(),
         Node::new(
-            NodeBase::StatementList(vec![Node::new(
-                NodeBase::While(
-                    Box::new(Node::new(NodeBase::Boolean(true), 7)),
-                    Box::new(Node::new(NodeBase::Block(vec![]), 13)),
+            NodeBase::StatementList(vec![
+                Node::new(
+                    NodeBase::StatementList(vec![Node::new(
+                        NodeBase::PatternDecl(
+                            Box::new(Node::new(
+                                NodeBase::ArrayPattern(vec![
+                                    Node::new(NodeBase::Identifier("a".to_string()), 5),
+                                    Node::new(NodeBase::Nope, 8),
+                                    Node::new(
+                                        NodeBase::Spread(Box::new(Node::new(
+                                            NodeBase::Identifier("b".to_string()),
+                                            13,
+                                        ))),
+                                        10,
+                                    ),
+                                ]),
+                                4,
+                            )),
+                            Some(Box::new(Node::new(
+                                NodeBase::Identifier("x".to_string()),
+                                18,
+                            ))),
+                            VarKind::Let,
+                        ),
+                        4,
+                    )]),
+                    4,
                 ),
-                0,
-            )]),
+                Node::new(
+                    NodeBase::Assign(
+                        Box::new(Node::new(
+                            NodeBase::ObjectPattern(vec![
+                                PropertyPattern::Property(
+                                    Node::new(NodeBase::String("a".to_string()), 24),
+                                    Node::new(NodeBase::Identifier("a".to_string()), 24),
+                                ),
+                                PropertyPattern::Property(
+                                    Node::new(NodeBase::String("b".to_string()), 27),
+                                    Node::new(
+                                        NodeBase::ArrayPattern(vec![Node::new(
+                                            NodeBase::AssignmentPattern(
+                                                Box::new(Node::new(
+                                                    NodeBase::Identifier("c".to_string()),
+                                                    31,
+                                                )),
+                                                Box::new(Node::new(NodeBase::Number(1.0), 35)),
+                                            ),
+                                            31,
+                                        )]),
+                                        30,
+                                    ),
+                                ),
+                                PropertyPattern::Rest(Node::new(
+                                    NodeBase::Identifier("d".to_string()),
+                                    42,
+                                )),
+                            ]),
+                            22,
+                        )),
+                        Box::new(Node::new(NodeBase::Identifier("y".to_string()), 48)),
+                    ),
+                    22,
+                ),
+            ]),
             0
         )
     );
+    for input in [
+        "let [a, ...b, c] = x",
+        "let { ...a, b } = x",
+        "let { a }",
+        "let [a.b] = x",
+        "[1] = x",
+        "({ a: 1 } = x)",
+        "function f([a) {}",
+    ]
+    .iter()
+    {
+        let mut parser = Parser::new("test", input.to_string());
+        parser.parse_all().expect_err(input);
+    }
 }
 
 #[test]
-fn for1() {
-    let mut parser = Parser::new("test", "for (;;) { }".to_string());
+fn regexp_literal() {
+    let mut parser = Parser::new("test", "x = /a+/g".to_string());
     assert_eq!(
         parser.parse_all().unwrap(),
         Node::new(
             NodeBase::StatementList(vec![Node::new(
-                NodeBase::Block(vec![Node::new(
-                    NodeBase::For(
-                        Box::new(Node::new(NodeBase::Nope, 4)),
-                        Box::new(Node::new(NodeBase::Boolean(true), 6)),
-                        Box::new(Node::new(NodeBase::Nope, 7)),
-                        Box::new(Node::new(NodeBase::Block(vec![]), 9)),
-                    ),
-                    0,
-                )]),
+                NodeBase::Assign(
+                    Box::new(Node::new(NodeBase::Identifier("x".to_string()), 0)),
+                    Box::new(Node::new(
+                        NodeBase::RegExpLiteral("a+".to_string(), "g".to_string()),
+                        4,
+                    )),
+                ),
                 0,
             )]),
             0
         )
     );
-    for input in [
-        "for(){}",
-        "for(;){}",
-        "for(;;;){}",
-        "for(var){}",
-        "for(var a=1){}",
-        "for(a=1){}",
-        "for(a=1;a<8)",
-        "for(a=1;a<8;a++",
-    ]
-    .iter()
-    {
+    for input in ["/a**/", "/(/", "/a/gg", "/a/x"].iter() {
         let mut parser = Parser::new("test", input.to_string());
-        parser.parse_all().expect_err("should be error");
+        parser.parse_all().expect_err(input);
     }
 }
 
@@ -2415,6 +4223,8 @@ fn function_decl() {
                     "f".to_string(),
                     vec![],
                     Box::new(Node::new(NodeBase::StatementList(vec![]), 64)),
+                    false,
+                    false,
                 ),
                 0,
             ),
@@ -2429,6 +4239,8 @@ fn function_decl() {
                         NodeBase::StatementList(vec![Node::new(NodeBase::Return(None), 15)]),
                         13,
                     )),
+                    false,
+                    false,
                 ),
                 0,
             ),
@@ -2439,9 +4251,21 @@ fn function_decl() {
                 NodeBase::FunctionDecl(
                     "f".to_string(),
                     vec![
-                        FormalParameter::new("x".to_string(), None, false),
-                        FormalParameter::new("y".to_string(), None, false),
-                        FormalParameter::new("z".to_string(), None, true),
+                        FormalParameter::new(
+                            Node::new(NodeBase::Identifier("x".to_string()), 11),
+                            None,
+                            false,
+                        ),
+                        FormalParameter::new(
+                            Node::new(NodeBase::Identifier("y".to_string()), 14),
+                            None,
+                            false,
+                        ),
+                        FormalParameter::new(
+                            Node::new(NodeBase::Identifier("z".to_string()), 20),
+                            None,
+                            true,
+                        ),
                     ],
                     Box::new(Node::new(
                         NodeBase::StatementList(vec![Node::new(
@@ -2457,6 +4281,8 @@ fn function_decl() {
                         )]),
                         23,
                     )),
+                    false,
+                    false,
                 ),
                 0,
             ),
@@ -2501,12 +4327,12 @@ fn arrow_function() {
                 NodeBase::ArrowFunction(
                     vec![
                         FormalParameter {
-                            name: "a".to_string(),
+                            binding: Node::new(NodeBase::Identifier("a".to_string()), 1),
                             init: None,
                             is_rest_param: false,
                         },
                         FormalParameter {
-                            name: "b".to_string(),
+                            binding: Node::new(NodeBase::Identifier("b".to_string()), 4),
                             init: None,
                             is_rest_param: false,
                         },
@@ -2525,6 +4351,7 @@ fn arrow_function() {
                         )]),
                         10,
                     )),
+                    false,
                 ),
                 0,
             ),
@@ -2535,17 +4362,17 @@ fn arrow_function() {
                 NodeBase::ArrowFunction(
                     vec![
                         FormalParameter {
-                            name: "a".to_string(),
+                            binding: Node::new(NodeBase::Identifier("a".to_string()), 1),
                             init: None,
                             is_rest_param: false,
                         },
                         FormalParameter {
-                            name: "b".to_string(),
+                            binding: Node::new(NodeBase::Identifier("b".to_string()), 4),
                             init: None,
                             is_rest_param: false,
                         },
                         FormalParameter {
-                            name: "c".to_string(),
+                            binding: Node::new(NodeBase::Identifier("c".to_string()), 10),
                             init: None,
                             is_rest_param: true,
                         },
@@ -2557,6 +4384,7 @@ fn arrow_function() {
                         )))),
                         16,
                     )),
+                    false,
                 ),
                 0,
             ),
@@ -2587,6 +4415,119 @@ fn arrow_function() {
     }
 }
 
+#[test]
+fn async_await() {
+    let mut parser = Parser::new("test", "async function f() { await x }".to_string());
+    assert_eq!(
+        parser.parse_all().unwrap(),
+        Node::new(
+            NodeBase::StatementList(vec![Node::new(
+                NodeBase::FunctionDecl(
+                    "f".to_string(),
+                    vec![],
+                    Box::new(Node::new(
+                        NodeBase::StatementList(vec![Node::new(
+                            NodeBase::Await(Box::new(Node::new(
+                                NodeBase::Identifier("x".to_string()),
+                                27
+                            ))),
+                            21
+                        )]),
+                        19
+                    )),
+                    true,
+                    false,
+                ),
+                6,
+            )]),
+            0
+        )
+    );
+    for input in [
+        "async () => await 1",
+        "async x => { await x }",
+        "f = async function () { await g() }",
+        "class A { async m() { await 1 } }",
+        "async\nfunction f() {}",
+        "await = 1",
+    ]
+    .iter()
+    {
+        let mut parser = Parser::new("test", input.to_string());
+        parser.parse_all().unwrap();
+    }
+    for input in [
+        "async function f() { await }",
+        "class A { async constructor() {} }",
+        "class A { async get x() {} }",
+    ]
+    .iter()
+    {
+        let mut parser = Parser::new("test", input.to_string());
+        parser.parse_all().expect_err(input);
+    }
+}
+
+#[test]
+fn generator() {
+    let mut parser = Parser::new("test", "function* f() { yield* x; yield }".to_string());
+    assert_eq!(
+        parser.parse_all().unwrap(),
+        Node::new(
+            NodeBase::StatementList(vec![Node::new(
+                NodeBase::FunctionDecl(
+                    "f".to_string(),
+                    vec![],
+                    Box::new(Node::new(
+                        NodeBase::StatementList(vec![
+                            Node::new(
+                                NodeBase::Yield(
+                                    Some(Box::new(Node::new(
+                                        NodeBase::Identifier("x".to_string()),
+                                        23
+                                    ))),
+                                    true
+                                ),
+                                16
+                            ),
+                            Node::new(NodeBase::Yield(None, false), 26),
+                        ]),
+                        14
+                    )),
+                    false,
+                    true,
+                ),
+                0,
+            )]),
+            0
+        )
+    );
+    for input in [
+        "f = function* () { yield 1 }",
+        "f = function* () { let x = yield; g(yield, yield x) }",
+        "class A { *m() { yield 1 } static *[Symbol.iterator]() {} }",
+        "o = { *m() { yield 1 }, [Symbol.iterator]() {} }",
+        "function* f() { function g() { let yield = 1; } }",
+        "yield = 1",
+    ]
+    .iter()
+    {
+        let mut parser = Parser::new("test", input.to_string());
+        parser.parse_all().unwrap();
+    }
+    for input in [
+        "function* f() { () => yield 1 }",
+        "class A { *constructor() {} }",
+        "class A { *get x() {} }",
+        "async function* f() {}",
+    ]
+    .iter()
+    {
+        let mut parser = Parser::new("test", input.to_string());
+        parser.parse_all().expect_err(input);
+    }
+}
+
 #[test]
 fn asi1() {
     let mut parser = Parser::new(
@@ -2612,6 +4553,8 @@ fn asi1() {
                         ]),
                         23,
                     )),
+                    false,
+                    false,
                 ),
                 0,
             )]),
@@ -2742,3 +4685,88 @@ fn try_catch3() {
         )
     );
 }
+
+#[test]
+fn strict_mode() {
+    for input in [
+        "'use strict'; with (a) {}",
+        "'use strict'; 017",
+        "'use strict'; 08",
+        "'use strict'; delete a",
+        "function f() { 'use strict'; with (a) {} }",
+        "function f() { 'other'; \"use strict\"; 017 }",
+        "() => { 'use strict'; 017 }",
+        "class A { m() { 017 } }",
+        "class A extends (017) {}",
+    ]
+    .iter()
+    {
+        let mut parser = Parser::new("test", input.to_string());
+        parser.parse_all().expect_err("should be error");
+    }
+
+    for input in [
+        "017; delete a",
+        "function f() { 'use strict' } 017",
+        "function f() { f(); 'use strict'; 017 }",
+        "'use strict'; 0.5; 0; 0x17; 0o17",
+        "class A {} 017",
+    ]
+    .iter()
+    {
+        let mut parser = Parser::new("test", input.to_string());
+        parser.parse_all().unwrap();
+    }
+}
+
+#[test]
+fn import_export() {
+    let mut parser = Parser::new(
+        "test",
+        "import a, { b, c as d } from './m'; export { a as e }".to_string(),
+    );
+    assert_eq!(
+        parser.parse_module().unwrap(),
+        Node::new(
+            NodeBase::StatementList(vec![
+                Node::new(
+                    NodeBase::Import(
+                        vec![
+                            ImportSpecifier::new(Some("default".to_string()), "a".to_string()),
+                            ImportSpecifier::new(Some("b".to_string()), "b".to_string()),
+                            ImportSpecifier::new(Some("c".to_string()), "d".to_string()),
+                        ],
+                        "./m".to_string()
+                    ),
+                    0
+                ),
+                Node::new(
+                    NodeBase::Export(ExportDeclaration::Named(
+                        vec![("a".to_string(), "e".to_string())],
+                        None
+                    )),
+                    36
+                )
+            ]),
+            0
+        )
+    );
+
+    for input in [
+        "import * from 'm'",
+        "import { if } from 'm'",
+        "export default",
+        "export { a } 'm'",
+        "'use strict'; with (a) {}",
+    ]
+    .iter()
+    {
+        let mut parser = Parser::new("test", input.to_string());
+        parser.parse_module().expect_err("should be error");
+    }
+
+    for input in ["import a from 'm'", "export let a = 1"].iter() {
+        let mut parser = Parser::new("test", input.to_string());
+        parser.parse_all().expect_err("should be error");
+    }
+}
@@ -0,0 +1,463 @@
+use super::parser::{CharClass, ClassItem, Node};
+use super::{Captures, Flags};
+use std::rc::Rc;
+
+/// What is left to match after a node, as a linked list shared between backtrack points.
+/// https://tc39.github.io/ecma262/#sec-pattern-semantics
+enum Continuation<'n> {
+    Done,
+    /// Match the node, then the rest.
+    Node(&'n Node, Rc<Continuation<'n>>),
+    /// Close the capture group ``index`` opened at ``start``.
+    Capture {
+        index: usize,
+        start: usize,
+        next: Rc<Continuation<'n>>,
+    },
+    /// Try one more iteration of a ``Node::Repeat`` which has matched ``count`` times.
+    Iterate {
+        repeat: &'n Node,
+        count: usize,
+        next: Rc<Continuation<'n>>,
+    },
+    /// An iteration of a ``Node::Repeat`` started at ``start`` has matched.
+    Iterated {
+        repeat: &'n Node,
+        count: usize,
+        start: usize,
+        next: Rc<Continuation<'n>>,
+    },
+}
+
+/// A point to resume from when a later match fails.
+struct Backtrack<'n> {
+    pos: usize,
+    continuation: Rc<Continuation<'n>>,
+    /// The length of the capture trail to restore.
+    trail_len: usize,
+}
+
+/// Matches iteratively with an explicit stack of backtrack points, so that a long input does not
+/// exhaust the native stack. Only lookarounds, which are atomic, are matched recursively.
+pub struct Matcher<'a> {
+    input: &'a [u16],
+    flags: Flags,
+    captures: Captures,
+    /// The previous values of the captures changed so far, to undo them on backtracking.
+    trail: Vec<(usize, Option<(usize, usize)>)>,
+    /// False while matching a lookbehind.
+    forward: bool,
+}
+
+impl<'a> Matcher<'a> {
+    pub fn new(input: &'a [u16], flags: Flags, group_count: usize) -> Self {
+        Matcher {
+            input,
+            flags,
+            captures: vec![None; group_count + 1],
+            trail: vec![],
+            forward: true,
+        }
+    }
+
+    pub fn run(mut self, node: &Node, start: usize) -> Option<Captures> {
+        if start > self.input.len() {
+            return None;
+        }
+        let end = self.match_node(node, start)?;
+        self.captures[0] = Some((start, end));
+        Some(self.captures)
+    }
+
+    /// Matches ``node`` at ``pos`` and returns the position after it. The backtrack points left
+    /// are dropped, and the captures set on the way are kept in the trail.
+    fn match_node<'n>(&mut self, node: &'n Node, pos: usize) -> Option<usize> {
+        let mut stack: Vec<Backtrack<'n>> = vec![];
+        let mut pos = pos;
+        let mut continuation = Rc::new(Continuation::Node(node, Rc::new(Continuation::Done)));
+        loop {
+            let step = match *continuation {
+                Continuation::Done => return Some(pos),
+                Continuation::Node(node, ref next) => self.step(node, pos, next, &mut stack),
+                Continuation::Capture {
+                    index,
+                    start,
+                    ref next,
+                } => {
+                    let range = if self.forward {
+                        (start, pos)
+                    } else {
+                        (pos, start)
+                    };
+                    self.set_capture(index, Some(range));
+                    Some((pos, next.clone()))
+                }
+                Continuation::Iterate {
+                    repeat,
+                    count,
+                    ref next,
+                } => {
+                    let (node, captures) = match repeat {
+                        Node::Repeat { node, captures, .. } => (node, captures),
+                        _ => unreachable!(),
+                    };
+                    for index in captures.clone() {
+                        self.set_capture(index, None);
+                    }
+                    let iterated = Continuation::Iterated {
+                        repeat,
+                        count,
+                        start: pos,
+                        next: next.clone(),
+                    };
+                    Some((pos, Rc::new(Continuation::Node(node, Rc::new(iterated)))))
+                }
+                Continuation::Iterated {
+                    repeat,
+                    count,
+                    start,
+                    ref next,
+                } => {
+                    let min = match repeat {
+                        Node::Repeat { min, .. } => *min,
+                        _ => unreachable!(),
+                    };
+                    // An iteration which matches the empty string doesn't terminate.
+                    if count >= min && pos == start {
+                        None
+                    } else {
+                        self.step_repeat(repeat, count + 1, pos, next, &mut stack)
+                    }
+                }
+            };
+            match step {
+                Some((next_pos, next)) => {
+                    pos = next_pos;
+                    continuation = next;
+                }
+                None => {
+                    let backtrack = stack.pop()?;
+                    self.undo_captures(backtrack.trail_len);
+                    pos = backtrack.pos;
+                    continuation = backtrack.continuation;
+                }
+            }
+        }
+    }
+
+    /// Matches ``node`` at ``pos``, and returns the position and what to match next, or None if
+    /// it fails.
+    fn step<'n>(
+        &mut self,
+        node: &'n Node,
+        pos: usize,
+        next: &Rc<Continuation<'n>>,
+        stack: &mut Vec<Backtrack<'n>>,
+    ) -> Option<(usize, Rc<Continuation<'n>>)> {
+        let matched = |pos: usize| Some((pos, next.clone()));
+        match node {
+            Node::Empty => matched(pos),
+            Node::Char(c) => match self.read_char(pos) {
+                Some((ch, next)) if self.canonicalize(ch) == self.canonicalize(*c) => matched(next),
+                _ => None,
+            },
+            Node::Any => match self.read_char(pos) {
+                Some((ch, next)) if self.flags.dot_all || !is_line_terminator(ch) => matched(next),
+                _ => None,
+            },
+            Node::Class(class) => match self.read_char(pos) {
+                Some((ch, next)) if self.class_matches(class, ch) => matched(next),
+                _ => None,
+            },
+            Node::LineStart => {
+                let at_start = pos == 0
+                    || self.flags.multiline && is_line_terminator(self.input[pos - 1] as u32);
+                if at_start {
+                    matched(pos)
+                } else {
+                    None
+                }
+            }
+            Node::LineEnd => {
+                let at_end = pos == self.input.len()
+                    || self.flags.multiline && is_line_terminator(self.input[pos] as u32);
+                if at_end {
+                    matched(pos)
+                } else {
+                    None
+                }
+            }
+            Node::WordBoundary(negated) => {
+                let before = pos > 0 && is_word_char(self.input[pos - 1] as u32);
+                let after = pos < self.input.len() && is_word_char(self.input[pos] as u32);
+                if (before != after) != *negated {
+                    matched(pos)
+                } else {
+                    None
+                }
+            }
+            Node::Group(node, None) => Some((pos, Rc::new(Continuation::Node(node, next.clone())))),
+            Node::Group(node, Some(index)) => {
+                let capture = Continuation::Capture {
+                    index: *index,
+                    start: pos,
+                    next: next.clone(),
+                };
+                Some((pos, Rc::new(Continuation::Node(node, Rc::new(capture)))))
+            }
+            Node::LookAround {
+                node,
+                ahead,
+                negative,
+            } => {
+                // A lookaround is atomic: no backtracking into it once it has matched.
+                let trail_len = self.trail.len();
+                let saved_forward = self.forward;
+                self.forward = *ahead;
+                let found = self.match_node(node, pos).is_some();
+                self.forward = saved_forward;
+
+                // Captures are kept only from a positive lookaround which has matched.
+                if !found || *negative {
+                    self.undo_captures(trail_len);
+                }
+                if found == *negative {
+                    return None;
+                }
+                matched(pos)
+            }
+            Node::BackReference(index) => {
+                let (start, end) = match self.captures[*index] {
+                    Some(range) => range,
+                    None => return matched(pos),
+                };
+                let len = end - start;
+                let from = if self.forward {
+                    if pos + len > self.input.len() {
+                        return None;
+                    }
+                    pos
+                } else {
+                    if pos < len {
+                        return None;
+                    }
+                    pos - len
+                };
+                for i in 0..len {
+                    let (x, y) = (self.input[start + i] as u32, self.input[from + i] as u32);
+                    if self.canonicalize(x) != self.canonicalize(y) {
+                        return None;
+                    }
+                }
+                matched(if self.forward { pos + len } else { pos - len })
+            }
+            Node::Repeat { .. } => self.step_repeat(node, 0, pos, next, stack),
+            // Terms are matched from right to left in a lookbehind.
+            Node::Sequence(nodes) => {
+                let mut continuation = next.clone();
+                if self.forward {
+                    for node in nodes.iter().rev() {
+                        continuation = Rc::new(Continuation::Node(node, continuation));
+                    }
+                } else {
+                    for node in nodes {
+                        continuation = Rc::new(Continuation::Node(node, continuation));
+                    }
+                }
+                Some((pos, continuation))
+            }
+            Node::Alternative(nodes) => {
+                let (first, rest) = nodes.split_first()?;
+                for node in rest.iter().rev() {
+                    self.push_backtrack(
+                        stack,
+                        pos,
+                        Rc::new(Continuation::Node(node, next.clone())),
+                    );
+                }
+                Some((pos, Rc::new(Continuation::Node(first, next.clone()))))
+            }
+        }
+    }
+
+    /// Continues a ``Node::Repeat`` which has matched ``count`` times.
+    /// https://tc39.github.io/ecma262/#sec-runtime-semantics-repeatmatcher-abstract-operation
+    fn step_repeat<'n>(
+        &mut self,
+        repeat: &'n Node,
+        count: usize,
+        pos: usize,
+        next: &Rc<Continuation<'n>>,
+        stack: &mut Vec<Backtrack<'n>>,
+    ) -> Option<(usize, Rc<Continuation<'n>>)> {
+        let (min, max, greedy) = match repeat {
+            Node::Repeat {
+                min, max, greedy, ..
+            } => (*min, *max, *greedy),
+            _ => unreachable!(),
+        };
+        if max == Some(count) {
+            return Some((pos, next.clone()));
+        }
+
+        let iterate = Continuation::Iterate {
+            repeat,
+            count,
+            next: next.clone(),
+        };
+        if count < min {
+            return Some((pos, Rc::new(iterate)));
+        }
+        if greedy {
+            self.push_backtrack(stack, pos, next.clone());
+            Some((pos, Rc::new(iterate)))
+        } else {
+            self.push_backtrack(stack, pos, Rc::new(iterate));
+            Some((pos, next.clone()))
+        }
+    }
+
+    fn push_backtrack<'n>(
+        &self,
+        stack: &mut Vec<Backtrack<'n>>,
+        pos: usize,
+        continuation: Rc<Continuation<'n>>,
+    ) {
+        stack.push(Backtrack {
+            pos,
+            continuation,
+            trail_len: self.trail.len(),
+        });
+    }
+
+    fn set_capture(&mut self, index: usize, capture: Option<(usize, usize)>) {
+        self.trail.push((index, self.captures[index]));
+        self.captures[index] = capture;
+    }
+
+    /// Restores the captures changed after the trail was ``trail_len`` long.
+    fn undo_captures(&mut self, trail_len: usize) {
+        while self.trail.len() > trail_len {
+            let (index, capture) = self.trail.pop().unwrap();
+            self.captures[index] = capture;
+        }
+    }
+
+    /// Reads the character after ``pos``, or before ``pos`` in a lookbehind.
+    /// Returns the character and the position next to it.
+    fn read_char(&self, pos: usize) -> Option<(u32, usize)> {
+        if self.forward {
+            let c = *self.input.get(pos)? as u32;
+            if self.flags.unicode && is_lead_surrogate(c) {
+                if let Some(&trail) = self.input.get(pos + 1) {
+                    if is_trail_surrogate(trail as u32) {
+                        return Some((combine_surrogates(c, trail as u32), pos + 2));
+                    }
+                }
+            }
+            Some((c, pos + 1))
+        } else {
+            if pos == 0 {
+                return None;
+            }
+            let c = self.input[pos - 1] as u32;
+            if self.flags.unicode && is_trail_surrogate(c) && pos >= 2 {
+                let lead = self.input[pos - 2] as u32;
+                if is_lead_surrogate(lead) {
+                    return Some((combine_surrogates(lead, c), pos - 2));
+                }
+            }
+            Some((c, pos - 1))
+        }
+    }
+
+    /// https://tc39.github.io/ecma262/#sec-runtime-semantics-canonicalize-ch
+    fn canonicalize(&self, c: u32) -> u32 {
+        if !self.flags.ignore_case {
+            return c;
+        }
+        let ch = match std::char::from_u32(c) {
+            Some(ch) => ch,
+            None => return c,
+        };
+        if self.flags.unicode {
+            let mut lower = ch.to_lowercase();
+            return match (lower.next(), lower.next()) {
+                (Some(l), None) => l as u32,
+                _ => c,
+            };
+        }
+        let mut upper = ch.to_uppercase();
+        match (upper.next(), upper.next()) {
+            (Some(u), None) if (u as u32) < 0x10000 && !(c >= 128 && (u as u32) < 128) => u as u32,
+            _ => c,
+        }
+    }
+
+    fn class_matches(&self, class: &CharClass, c: u32) -> bool {
+        let item_matches = |c: u32| {
+            class.items.iter().any(|item| match *item {
+                ClassItem::Range(start, end) => start <= c && c <= end,
+                ClassItem::Digit(negated) => is_digit(c) != negated,
+                ClassItem::Word(negated) => is_word_char(c) != negated,
+                ClassItem::Space(negated) => is_space(c) != negated,
+            })
+        };
+        let mut found = item_matches(c);
+        if !found && self.flags.ignore_case {
+            if let Some(ch) = std::char::from_u32(c) {
+                found = ch.to_lowercase().chain(ch.to_uppercase()).any(|variant| {
+                    self.canonicalize(variant as u32) == self.canonicalize(c)
+                        && item_matches(variant as u32)
+                });
+            }
+        }
+        found != class.negated
+    }
+}
+
+fn is_lead_surrogate(c: u32) -> bool {
+    (0xd800..0xdc00).contains(&c)
+}
+
+fn is_trail_surrogate(c: u32) -> bool {
+    (0xdc00..0xe000).contains(&c)
+}
+
+fn combine_surrogates(lead: u32, trail: u32) -> u32 {
+    0x10000 + ((lead - 0xd800) << 10) + (trail - 0xdc00)
+}
+
+/// https://tc39.github.io/ecma262/#sec-line-terminators
+fn is_line_terminator(c: u32) -> bool {
+    matches!(c, 0x0a | 0x0d | 0x2028 | 0x2029)
+}
+
+fn is_digit(c: u32) -> bool {
+    '0' as u32 <= c && c <= '9' as u32
+}
+
+fn is_word_char(c: u32) -> bool {
+    match std::char::from_u32(c) {
+        Some(ch) => ch.is_ascii_alphanumeric() || ch == '_',
+        None => false,
+    }
+}
+
+/// https://tc39.github.io/ecma262/#sec-white-space
+fn is_space(c: u32) -> bool {
+    match c {
+        0x09
+        | 0x0b
+        | 0x0c
+        | 0x20
+        | 0xa0
+        | 0xfeff
+        | 0x1680
+        | 0x2000..=0x200a
+        | 0x202f
+        | 0x205f
+        | 0x3000 => true,
+        _ => is_line_terminator(c),
+    }
+}
@@ -0,0 +1,186 @@
+pub mod matcher;
+pub mod parser;
+
+use self::parser::Node;
+
+/// Start and end indices of each capture group. The whole match is the 0th group.
+pub type Captures = Vec<Option<(usize, usize)>>;
+
+#[derive(Clone, Debug, Default, PartialEq, Copy)]
+pub struct Flags {
+    /// g
+    pub global: bool,
+    /// i
+    pub ignore_case: bool,
+    /// m
+    pub multiline: bool,
+    /// s
+    pub dot_all: bool,
+    /// u
+    pub unicode: bool,
+    /// y
+    pub sticky: bool,
+}
+
+impl Flags {
+    pub fn parse(flags: &str) -> Result<Self, String> {
+        let mut parsed = Flags::default();
+        for c in flags.chars() {
+            let flag = match c {
+                'g' => &mut parsed.global,
+                'i' => &mut parsed.ignore_case,
+                'm' => &mut parsed.multiline,
+                's' => &mut parsed.dot_all,
+                'u' => &mut parsed.unicode,
+                'y' => &mut parsed.sticky,
+                _ => return Err(format!("Invalid flags '{}'", flags)),
+            };
+            if *flag {
+                return Err(format!("Invalid flags '{}'", flags));
+            }
+            *flag = true;
+        }
+        Ok(parsed)
+    }
+}
+
+/// A backtracking matcher for ECMAScript patterns.
+/// https://tc39.github.io/ecma262/#sec-regexp-regular-expression-objects
+/// The input is matched as UTF-16 code units, so an index is the one JavaScript sees.
+/// With the ``u`` flag, a surrogate pair is matched as a single code point.
+#[derive(Clone, Debug)]
+pub struct Regex {
+    node: Node,
+    pub flags: Flags,
+    /// The number of capture groups, excluding the whole match.
+    pub group_count: usize,
+    /// Named capture groups in the order they appear.
+    pub group_names: Vec<(String, usize)>,
+}
+
+impl Regex {
+    pub fn new(pattern: &str, flags: &str) -> Result<Self, String> {
+        let flags = Flags::parse(flags)?;
+        let (node, group_count, group_names) =
+            parser::Parser::new(pattern, flags.unicode).parse()?;
+        Ok(Regex {
+            node,
+            flags,
+            group_count,
+            group_names,
+        })
+    }
+
+    /// Matches the pattern exactly at ``start``.
+    pub fn match_at(&self, input: &[u16], start: usize) -> Option<Captures> {
+        matcher::Matcher::new(input, self.flags, self.group_count).run(&self.node, start)
+    }
+}
+
+#[test]
+fn regex() {
+    fn find(pattern: &str, flags: &str, input: &str) -> Option<Vec<Option<String>>> {
+        let regex = Regex::new(pattern, flags).unwrap();
+        let input: Vec<u16> = input.encode_utf16().collect();
+        (0..=input.len()).find_map(|start| {
+            regex.match_at(&input, start).map(|captures| {
+                captures
+                    .iter()
+                    .map(|c| c.map(|(s, e)| String::from_utf16_lossy(&input[s..e])))
+                    .collect()
+            })
+        })
+    }
+    fn found(pattern: &str, flags: &str, input: &str, expected: &[Option<&str>]) {
+        let expected: Vec<Option<String>> =
+            expected.iter().map(|c| c.map(|s| s.to_string())).collect();
+        assert_eq!(find(pattern, flags, input), Some(expected), "/{}/", pattern);
+    }
+
+    found("b+", "", "abbbc", &[Some("bbb")]);
+    found("b+?", "", "abbbc", &[Some("b")]);
+    found("a{2,3}", "", "aaaa", &[Some("aaa")]);
+    found("a{2,}?", "", "aaaa", &[Some("aa")]);
+    found(
+        "(a|ab)(c|bcd)(d*)",
+        "",
+        "abcd",
+        &[Some("abcd"), Some("a"), Some("bcd"), Some("")],
+    );
+    found(
+        "(z)((a+)?(b+)?(c))*",
+        "",
+        "zaacbbbcac",
+        &[
+            Some("zaacbbbcac"),
+            Some("z"),
+            Some("ac"),
+            Some("a"),
+            None,
+            Some("c"),
+        ],
+    );
+    found("(a*)*", "", "b", &[Some(""), None]);
+    found("(a*)b\\1+", "", "baaaac", &[Some("b"), Some("")]);
+    found("(?=(a+))a*b\\1", "", "baaabac", &[Some("aba"), Some("a")]);
+    found(
+        "(.*?)a(?!(a+)b\\2c)\\2(.*)",
+        "",
+        "baaabaac",
+        &[Some("baaabaac"), Some("ba"), None, Some("abaac")],
+    );
+    found("(?<=\\$)\\d+", "", "cost: $42", &[Some("42")]);
+    found("(?<!\\$)\\b\\d+", "", "$4 and 2", &[Some("2")]);
+    found(
+        "(?<=(\\d+)(\\d+))$",
+        "",
+        "1053",
+        &[Some(""), Some("1"), Some("053")],
+    );
+    found(
+        "(?<year>\\d{4})-(?<month>\\d{2})",
+        "",
+        "on 2019-04",
+        &[Some("2019-04"), Some("2019"), Some("04")],
+    );
+    found("(?<a>.)\\k<a>", "", "abccd", &[Some("cc"), Some("c")]);
+    found("[^a-c\\d]+", "", "ab12xyz", &[Some("xyz")]);
+    found("[\\w-]+", "", "  a-b_c ", &[Some("a-b_c")]);
+    found("\\bfoo\\b", "", "a foo b", &[Some("foo")]);
+    found("^b", "m", "a\nb", &[Some("b")]);
+    found("a.c", "s", "a\nc", &[Some("a\nc")]);
+    found("ABC", "i", "xabcx", &[Some("abc")]);
+    found("[a-z]+", "i", "1ABC2", &[Some("ABC")]);
+    found("\\u{1F600}", "u", "x\u{1F600}", &[Some("\u{1F600}")]);
+    found("^.$", "u", "\u{1F600}", &[Some("\u{1F600}")]);
+    found("\\x41\\u0042\\cJ\\0", "", "AB\n\0", &[Some("AB\n\0")]);
+    found("a{,2}", "", "a{,2}", &[Some("a{,2}")]);
+
+    assert_eq!(find("^b", "", "a\nb"), None);
+    assert_eq!(find("a.c", "", "a\nc"), None);
+    assert_eq!(find("^.$", "", "\u{1F600}"), None);
+
+    for (pattern, flags) in [
+        ("a**", ""),
+        ("(", ""),
+        ("a)", ""),
+        ("[b-a]", ""),
+        ("a{2,1}", ""),
+        ("(?<a>.)(?<a>.)", ""),
+        ("\\k<b>(?<a>.)", ""),
+        ("(?<=a)+", ""),
+        ("\\1", "u"),
+        ("{", "u"),
+        ("a", "gg"),
+        ("a", "x"),
+    ]
+    .iter()
+    {
+        assert!(
+            Regex::new(pattern, flags).is_err(),
+            "/{}/{}",
+            pattern,
+            flags
+        );
+    }
+}
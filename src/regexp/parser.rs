@@ -0,0 +1,586 @@
+use std::ops::Range;
+
+/// https://tc39.github.io/ecma262/#sec-patterns
+#[derive(Clone, Debug, PartialEq)]
+pub enum Node {
+    Empty,
+    /// A code unit, or a code point with the ``u`` flag.
+    Char(u32),
+    /// ``.``
+    Any,
+    Class(CharClass),
+    /// ``^``
+    LineStart,
+    /// ``$``
+    LineEnd,
+    /// ``\b`` or ``\B`` (negated)
+    WordBoundary(bool),
+    /// Capture group index, if capturing.
+    Group(Box<Node>, Option<usize>),
+    LookAround {
+        node: Box<Node>,
+        ahead: bool,
+        negative: bool,
+    },
+    BackReference(usize),
+    Repeat {
+        node: Box<Node>,
+        min: usize,
+        max: Option<usize>,
+        greedy: bool,
+        /// Capture groups inside ``node``, which are cleared on each iteration.
+        captures: Range<usize>,
+    },
+    Sequence(Vec<Node>),
+    Alternative(Vec<Node>),
+}
+
+/// https://tc39.github.io/ecma262/#sec-characterclass
+#[derive(Clone, Debug, PartialEq)]
+pub struct CharClass {
+    pub negated: bool,
+    pub items: Vec<ClassItem>,
+}
+
+#[derive(Clone, Debug, PartialEq, Copy)]
+pub enum ClassItem {
+    Range(u32, u32),
+    /// ``\d`` or ``\D`` (negated)
+    Digit(bool),
+    /// ``\w`` or ``\W`` (negated)
+    Word(bool),
+    /// ``\s`` or ``\S`` (negated)
+    Space(bool),
+}
+
+type ParseResult<T> = Result<T, String>;
+
+/// A parsed pattern, the number of capture groups and the named groups.
+type Pattern = (Node, usize, Vec<(String, usize)>);
+
+pub struct Parser {
+    /// Code points with the ``u`` flag, otherwise code units.
+    chars: Vec<u32>,
+    pos: usize,
+    unicode: bool,
+    /// Number of capture groups read so far.
+    group_count: usize,
+    /// Number of capture groups in the whole pattern.
+    total_group_count: usize,
+    group_names: Vec<(String, usize)>,
+}
+
+impl Parser {
+    pub fn new(pattern: &str, unicode: bool) -> Self {
+        Parser {
+            chars: if unicode {
+                pattern.chars().map(|c| c as u32).collect()
+            } else {
+                pattern.encode_utf16().map(|c| c as u32).collect()
+            },
+            pos: 0,
+            unicode,
+            group_count: 0,
+            total_group_count: 0,
+            group_names: vec![],
+        }
+    }
+
+    /// Returns the pattern, the number of capture groups and the named groups.
+    pub fn parse(mut self) -> ParseResult<Pattern> {
+        self.scan_groups()?;
+        let node = self.read_disjunction()?;
+        if self.pos < self.chars.len() {
+            // Only an unmatched ')' stops reading a disjunction.
+            return Err("Unmatched ')'".to_string());
+        }
+        Ok((node, self.group_count, self.group_names))
+    }
+
+    /// Counts capture groups and collects their names beforehand, so that a back reference can
+    /// refer to a group which appears later.
+    fn scan_groups(&mut self) -> ParseResult<()> {
+        let mut in_class = false;
+        let mut i = 0;
+        while i < self.chars.len() {
+            match self.char_at(i) {
+                '\\' => i += 1,
+                '[' => in_class = true,
+                ']' => in_class = false,
+                '(' if !in_class => {
+                    if self.char_at(i + 1) != '?' {
+                        self.total_group_count += 1;
+                    } else if self.char_at(i + 2) == '<'
+                        && self.char_at(i + 3) != '='
+                        && self.char_at(i + 3) != '!'
+                    {
+                        self.total_group_count += 1;
+                        self.pos = i + 3;
+                        let name = self.read_group_name()?;
+                        if self.group_names.iter().any(|(n, _)| *n == name) {
+                            return Err("Duplicate capture group name".to_string());
+                        }
+                        self.group_names.push((name, self.total_group_count));
+                        i = self.pos - 1;
+                    }
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+        self.pos = 0;
+        Ok(())
+    }
+
+    fn char_at(&self, i: usize) -> char {
+        self.chars
+            .get(i)
+            .and_then(|c| std::char::from_u32(*c))
+            .unwrap_or('\u{0}')
+    }
+
+    fn peek(&self) -> Option<char> {
+        if self.pos < self.chars.len() {
+            Some(self.char_at(self.pos))
+        } else {
+            None
+        }
+    }
+
+    fn next_if(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn next_char(&mut self) -> ParseResult<u32> {
+        match self.chars.get(self.pos) {
+            Some(c) => {
+                self.pos += 1;
+                Ok(*c)
+            }
+            None => Err("\\ at end of pattern".to_string()),
+        }
+    }
+}
+
+impl Parser {
+    /// https://tc39.github.io/ecma262/#prod-Disjunction
+    fn read_disjunction(&mut self) -> ParseResult<Node> {
+        let mut alternatives = vec![self.read_alternative()?];
+        while self.next_if('|') {
+            alternatives.push(self.read_alternative()?);
+        }
+        Ok(if alternatives.len() == 1 {
+            alternatives.pop().unwrap()
+        } else {
+            Node::Alternative(alternatives)
+        })
+    }
+
+    /// https://tc39.github.io/ecma262/#prod-Alternative
+    fn read_alternative(&mut self) -> ParseResult<Node> {
+        let mut terms = vec![];
+        loop {
+            match self.peek() {
+                None | Some('|') | Some(')') => break,
+                _ => terms.push(self.read_term()?),
+            }
+        }
+        Ok(match terms.len() {
+            0 => Node::Empty,
+            1 => terms.pop().unwrap(),
+            _ => Node::Sequence(terms),
+        })
+    }
+
+    /// https://tc39.github.io/ecma262/#prod-Term
+    fn read_term(&mut self) -> ParseResult<Node> {
+        let first_group = self.group_count + 1;
+        let (atom, quantifiable) = self.read_atom()?;
+
+        let (min, max) = match self.read_quantifier_prefix()? {
+            Some(range) => range,
+            None => return Ok(atom),
+        };
+        if !quantifiable {
+            return Err("Nothing to repeat".to_string());
+        }
+        let greedy = !self.next_if('?');
+
+        Ok(Node::Repeat {
+            node: Box::new(atom),
+            min,
+            max,
+            greedy,
+            captures: first_group..self.group_count + 1,
+        })
+    }
+
+    /// https://tc39.github.io/ecma262/#prod-QuantifierPrefix
+    fn read_quantifier_prefix(&mut self) -> ParseResult<Option<(usize, Option<usize>)>> {
+        let range = match self.peek() {
+            Some('*') => (0, None),
+            Some('+') => (1, None),
+            Some('?') => (0, Some(1)),
+            Some('{') => match self.read_braced_quantifier() {
+                Some((min, Some(max))) if max < min => {
+                    return Err("numbers out of order in {} quantifier".to_string())
+                }
+                Some(range) => return Ok(Some(range)),
+                None if self.unicode => return Err("Incomplete quantifier".to_string()),
+                // Annex B: '{' is a literal if it is not a quantifier.
+                None => return Ok(None),
+            },
+            _ => return Ok(None),
+        };
+        self.pos += 1;
+        Ok(Some(range))
+    }
+
+    /// ``{n}``, ``{n,}`` or ``{n,m}``. Doesn't move ``pos`` unless it is a quantifier.
+    fn read_braced_quantifier(&mut self) -> Option<(usize, Option<usize>)> {
+        let start = self.pos;
+        self.pos += 1;
+        let range = (|| {
+            let min = self.read_decimal()?;
+            if self.next_if('}') {
+                return Some((min, Some(min)));
+            }
+            if !self.next_if(',') {
+                return None;
+            }
+            if self.next_if('}') {
+                return Some((min, None));
+            }
+            let max = self.read_decimal()?;
+            if self.next_if('}') {
+                Some((min, Some(max)))
+            } else {
+                None
+            }
+        })();
+        if range.is_none() {
+            self.pos = start;
+        }
+        range
+    }
+
+    fn read_decimal(&mut self) -> Option<usize> {
+        let start = self.pos;
+        let mut n: usize = 0;
+        while let Some(d) = self.peek().and_then(|c| c.to_digit(10)) {
+            n = n.saturating_mul(10).saturating_add(d as usize);
+            self.pos += 1;
+        }
+        if start == self.pos {
+            None
+        } else {
+            Some(n)
+        }
+    }
+
+    /// Returns the atom, or an assertion, and whether it may be quantified.
+    /// https://tc39.github.io/ecma262/#prod-Atom
+    fn read_atom(&mut self) -> ParseResult<(Node, bool)> {
+        let c = self.next_char()?;
+        Ok(match std::char::from_u32(c).unwrap_or('\u{0}') {
+            '^' => (Node::LineStart, false),
+            '$' => (Node::LineEnd, false),
+            '.' => (Node::Any, true),
+            '(' => self.read_group()?,
+            '[' => (Node::Class(self.read_class()?), true),
+            '\\' => match self.peek() {
+                Some('b') => {
+                    self.pos += 1;
+                    (Node::WordBoundary(false), false)
+                }
+                Some('B') => {
+                    self.pos += 1;
+                    (Node::WordBoundary(true), false)
+                }
+                _ => (self.read_atom_escape()?, true),
+            },
+            '*' | '+' | '?' => return Err("Nothing to repeat".to_string()),
+            '{' => {
+                self.pos -= 1;
+                if self.unicode || self.read_braced_quantifier().is_some() {
+                    return Err("Nothing to repeat".to_string());
+                }
+                self.pos += 1;
+                (Node::Char(c), true)
+            }
+            ']' | '}' if self.unicode => return Err("Lone quantifier brackets".to_string()),
+            _ => (Node::Char(c), true),
+        })
+    }
+
+    /// Reads a group after ``(``.
+    fn read_group(&mut self) -> ParseResult<(Node, bool)> {
+        let (node, quantifiable) = if self.next_if('?') {
+            let c = self.next_char()?;
+            match std::char::from_u32(c).unwrap_or('\u{0}') {
+                ':' => (Node::Group(Box::new(self.read_disjunction()?), None), true),
+                '=' | '!' => (
+                    Node::LookAround {
+                        node: Box::new(self.read_disjunction()?),
+                        ahead: true,
+                        negative: c == '!' as u32,
+                    },
+                    // Annex B: A lookahead may be quantified.
+                    !self.unicode,
+                ),
+                '<' if self.next_if('=') || self.next_if('!') => (
+                    Node::LookAround {
+                        negative: self.chars[self.pos - 1] == '!' as u32,
+                        node: Box::new(self.read_disjunction()?),
+                        ahead: false,
+                    },
+                    false,
+                ),
+                '<' => {
+                    // Names were collected in scan_groups().
+                    self.read_group_name()?;
+                    self.group_count += 1;
+                    let index = self.group_count;
+                    (
+                        Node::Group(Box::new(self.read_disjunction()?), Some(index)),
+                        true,
+                    )
+                }
+                _ => return Err("Invalid group".to_string()),
+            }
+        } else {
+            self.group_count += 1;
+            let index = self.group_count;
+            (
+                Node::Group(Box::new(self.read_disjunction()?), Some(index)),
+                true,
+            )
+        };
+
+        if !self.next_if(')') {
+            return Err("Unterminated group".to_string());
+        }
+        Ok((node, quantifiable))
+    }
+
+    /// Reads a group name after ``<``, and the closing ``>``.
+    fn read_group_name(&mut self) -> ParseResult<String> {
+        let mut name = String::new();
+        loop {
+            match self.peek() {
+                Some('>') if !name.is_empty() => break,
+                Some(c) if c == '$' || c == '_' || c.is_alphabetic() => name.push(c),
+                Some(c) if c.is_alphanumeric() && !name.is_empty() => name.push(c),
+                _ => return Err("Invalid capture group name".to_string()),
+            }
+            self.pos += 1;
+        }
+        self.pos += 1;
+        Ok(name)
+    }
+
+    /// https://tc39.github.io/ecma262/#prod-AtomEscape
+    fn read_atom_escape(&mut self) -> ParseResult<Node> {
+        match self.peek() {
+            Some('1'..='9') => {
+                let start = self.pos;
+                let n = self.read_decimal().unwrap();
+                if n <= self.total_group_count {
+                    return Ok(Node::BackReference(n));
+                }
+                if self.unicode {
+                    return Err("Invalid escape".to_string());
+                }
+                // Annex B: Not a back reference, but a legacy octal escape.
+                self.pos = start;
+            }
+            Some('k') if self.unicode || !self.group_names.is_empty() => {
+                self.pos += 1;
+                if !self.next_if('<') {
+                    return Err("Invalid named reference".to_string());
+                }
+                let name = self.read_group_name()?;
+                return match self.group_names.iter().find(|(n, _)| *n == name) {
+                    Some((_, index)) => Ok(Node::BackReference(*index)),
+                    None => Err("Invalid named capture referenced".to_string()),
+                };
+            }
+            _ => {}
+        }
+
+        Ok(match self.read_class_escape(false)? {
+            ClassItem::Range(c, _) => Node::Char(c),
+            item => Node::Class(CharClass {
+                negated: false,
+                items: vec![item],
+            }),
+        })
+    }
+
+    /// Reads an escape after ``\`` except for back references.
+    /// A single character is returned as a range of itself.
+    /// https://tc39.github.io/ecma262/#prod-CharacterEscape
+    fn read_class_escape(&mut self, in_class: bool) -> ParseResult<ClassItem> {
+        let c = self.next_char()?;
+        let single = |c: u32| Ok(ClassItem::Range(c, c));
+        match std::char::from_u32(c).unwrap_or('\u{0}') {
+            'd' => Ok(ClassItem::Digit(false)),
+            'D' => Ok(ClassItem::Digit(true)),
+            'w' => Ok(ClassItem::Word(false)),
+            'W' => Ok(ClassItem::Word(true)),
+            's' => Ok(ClassItem::Space(false)),
+            'S' => Ok(ClassItem::Space(true)),
+            't' => single(0x09),
+            'n' => single(0x0a),
+            'v' => single(0x0b),
+            'f' => single(0x0c),
+            'r' => single(0x0d),
+            'b' if in_class => single(0x08),
+            '-' if in_class && self.unicode => single(c),
+            'c' => match self.peek() {
+                Some(l) if l.is_ascii_alphabetic() => {
+                    self.pos += 1;
+                    single(l as u32 % 32)
+                }
+                Some(d) if in_class && !self.unicode && (d.is_ascii_digit() || d == '_') => {
+                    self.pos += 1;
+                    single(d as u32 % 32)
+                }
+                _ if self.unicode => Err("Invalid unicode escape".to_string()),
+                // Annex B: '\' is a literal.
+                _ => {
+                    self.pos -= 1;
+                    single('\\' as u32)
+                }
+            },
+            '0' if !self.peek().is_some_and(|c| c.is_ascii_digit()) => single(0),
+            '0'..='7' if !self.unicode => {
+                // Annex B: Legacy octal escape
+                let mut n = c - '0' as u32;
+                let max_len = if c <= '3' as u32 { 2 } else { 1 };
+                for _ in 0..max_len {
+                    match self.peek().and_then(|c| c.to_digit(8)) {
+                        Some(d) => {
+                            n = n * 8 + d;
+                            self.pos += 1;
+                        }
+                        None => break,
+                    }
+                }
+                single(n)
+            }
+            'x' => match self.read_hex_digits(2) {
+                Some(n) => single(n),
+                None if self.unicode => Err("Invalid escape".to_string()),
+                None => single(c),
+            },
+            'u' => match self.read_unicode_escape() {
+                Some(n) => single(n),
+                None if self.unicode => Err("Invalid unicode escape".to_string()),
+                None => single(c),
+            },
+            '^' | '$' | '\\' | '.' | '*' | '+' | '?' | '(' | ')' | '[' | ']' | '{' | '}' | '|'
+            | '/' => single(c),
+            _ if self.unicode => Err("Invalid escape".to_string()),
+            // Annex B: Identity escape
+            _ => single(c),
+        }
+    }
+
+    /// Reads a fixed number of hex digits. Doesn't move ``pos`` on failure.
+    fn read_hex_digits(&mut self, len: usize) -> Option<u32> {
+        let mut n = 0;
+        for i in 0..len {
+            n = n * 16 + self.char_at(self.pos + i).to_digit(16)?;
+        }
+        self.pos += len;
+        Some(n)
+    }
+
+    /// Reads ``XXXX`` or ``{X...}`` after ``\u``. Doesn't move ``pos`` on failure.
+    fn read_unicode_escape(&mut self) -> Option<u32> {
+        if self.unicode && self.peek() == Some('{') {
+            let start = self.pos;
+            self.pos += 1;
+            let mut n: u32 = 0;
+            while let Some(d) = self.peek().and_then(|c| c.to_digit(16)) {
+                n = n.checked_mul(16)? + d;
+                self.pos += 1;
+            }
+            if self.pos == start + 1 || n > 0x10_ffff || !self.next_if('}') {
+                self.pos = start;
+                return None;
+            }
+            return Some(n);
+        }
+
+        let n = self.read_hex_digits(4)?;
+        // A surrogate pair is a single code point.
+        if self.unicode && (0xd800..0xdc00).contains(&n) && self.char_at(self.pos) == '\\' {
+            let start = self.pos;
+            self.pos += 1;
+            if self.next_if('u') {
+                if let Some(trail) = self.read_hex_digits(4) {
+                    if (0xdc00..0xe000).contains(&trail) {
+                        return Some(0x10000 + ((n - 0xd800) << 10) + (trail - 0xdc00));
+                    }
+                }
+            }
+            self.pos = start;
+        }
+        Some(n)
+    }
+
+    /// Reads a character class after ``[``.
+    /// https://tc39.github.io/ecma262/#prod-CharacterClass
+    fn read_class(&mut self) -> ParseResult<CharClass> {
+        let negated = self.next_if('^');
+        let mut items = vec![];
+
+        loop {
+            if self.next_if(']') {
+                break;
+            }
+            let start = self.read_class_atom()?;
+            if self.peek() != Some('-') || self.char_at(self.pos + 1) == ']' {
+                items.push(start);
+                continue;
+            }
+            self.pos += 1;
+            let end = self.read_class_atom()?;
+            match (start, end) {
+                (ClassItem::Range(start, _), ClassItem::Range(end, _)) => {
+                    if start > end {
+                        return Err("Range out of order in character class".to_string());
+                    }
+                    items.push(ClassItem::Range(start, end));
+                }
+                _ if self.unicode => return Err("Invalid character class".to_string()),
+                // Annex B: A range with a class escape is a union with '-'.
+                _ => {
+                    items.push(start);
+                    items.push(ClassItem::Range('-' as u32, '-' as u32));
+                    items.push(end);
+                }
+            }
+        }
+
+        Ok(CharClass { negated, items })
+    }
+
+    fn read_class_atom(&mut self) -> ParseResult<ClassItem> {
+        let c = self
+            .next_char()
+            .map_err(|_| "Unterminated character class".to_string())?;
+        if c == '\\' as u32 {
+            self.read_class_escape(true)
+        } else {
+            Ok(ClassItem::Range(c, c))
+        }
+    }
+}
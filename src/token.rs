@@ -7,6 +7,8 @@ pub struct Token {
     /// char position of previous token.
     /// lexer generate this after tokenizing.
     pub prev_pos: usize,
+    /// char position just after the token.
+    pub end_pos: usize,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -15,11 +17,22 @@ pub enum Kind {
     Identifier(String),
     Number(f64),
     String(String),
+    Template(TemplateKind, Option<String>, String), // Kind, Cooked (None if invalid), Raw
+    RegExp(String, String),                         // Pattern, Flags
     Symbol(Symbol),
     LineTerminator,
     EOF,
 }
 
+/// https://tc39.github.io/ecma262/#sec-template-literal-lexical-components
+#[derive(Clone, Debug, PartialEq, Copy)]
+pub enum TemplateKind {
+    NoSubstitution, // `...`
+    Head,           // `...${
+    Middle,         // }...${
+    Tail,           // }...`
+}
+
 #[derive(Clone, Debug, PartialEq, Copy)]
 pub enum Keyword {
     Abstract,
@@ -27,6 +40,7 @@ pub enum Keyword {
     Break,
     Case,
     Catch,
+    Class,
     Const,
     Continue,
     Debugger,
@@ -34,15 +48,19 @@ pub enum Keyword {
     Delete,
     Do,
     Else,
+    Export,
+    Extends,
     Finally,
     For,
     Function,
     If,
+    Import,
     In,
     Instanceof,
     Let,
     New,
     Return,
+    Super,
     Switch,
     This,
     Throw,
@@ -118,6 +136,7 @@ impl Token {
             kind: Kind::Number(f),
             pos: pos,
             prev_pos: 0,
+            end_pos: 0,
         }
     }
 
@@ -126,6 +145,7 @@ impl Token {
             kind: Kind::Identifier(ident),
             pos: pos,
             prev_pos: 0,
+            end_pos: 0,
         }
     }
 
@@ -134,6 +154,7 @@ impl Token {
             kind: Kind::Keyword(keyword),
             pos: pos,
             prev_pos: 0,
+            end_pos: 0,
         }
     }
 
@@ -142,6 +163,30 @@ impl Token {
             kind: Kind::String(s),
             pos: pos,
             prev_pos: 0,
+            end_pos: 0,
+        }
+    }
+
+    pub fn new_template(
+        kind: TemplateKind,
+        cooked: Option<String>,
+        raw: String,
+        pos: usize,
+    ) -> Token {
+        Token {
+            kind: Kind::Template(kind, cooked, raw),
+            pos,
+            prev_pos: 0,
+            end_pos: 0,
+        }
+    }
+
+    pub fn new_regexp(pattern: String, flags: String, pos: usize) -> Token {
+        Token {
+            kind: Kind::RegExp(pattern, flags),
+            pos,
+            prev_pos: 0,
+            end_pos: 0,
         }
     }
 
@@ -150,6 +195,7 @@ impl Token {
             kind: Kind::Symbol(symbol),
             pos: pos,
             prev_pos: 0,
+            end_pos: 0,
         }
     }
 
@@ -158,6 +204,7 @@ impl Token {
             kind: Kind::LineTerminator,
             pos: pos,
             prev_pos: 0,
+            end_pos: 0,
         }
     }
 }
@@ -218,6 +265,7 @@ impl Keyword {
             Keyword::Break => "break",
             Keyword::Case => "case",
             Keyword::Catch => "catch",
+            Keyword::Class => "class",
             Keyword::Continue => "continue",
             Keyword::Const => "const",
             Keyword::Debugger => "debugger",
@@ -225,15 +273,19 @@ impl Keyword {
             Keyword::Delete => "delete",
             Keyword::Do => "do",
             Keyword::Else => "else",
+            Keyword::Export => "export",
+            Keyword::Extends => "extends",
             Keyword::Finally => "finally",
             Keyword::For => "for",
             Keyword::Function => "function",
             Keyword::If => "if",
+            Keyword::Import => "import",
             Keyword::In => "in",
             Keyword::Instanceof => "instanceof",
             Keyword::Let => "let",
             Keyword::New => "new",
             Keyword::Return => "return",
+            Keyword::Super => "super",
             Keyword::Switch => "switch",
             Keyword::This => "this",
             Keyword::Throw => "throw",
@@ -254,6 +306,7 @@ pub fn convert_reserved_keyword(keyword: &str) -> Option<Keyword> {
         "break" => Some(Keyword::Break),
         "case" => Some(Keyword::Case),
         "catch" => Some(Keyword::Catch),
+        "class" => Some(Keyword::Class),
         "continue" => Some(Keyword::Continue),
         "const" => Some(Keyword::Const),
         "debugger" => Some(Keyword::Debugger),
@@ -261,15 +314,19 @@ pub fn convert_reserved_keyword(keyword: &str) -> Option<Keyword> {
         "delete" => Some(Keyword::Delete),
         "do" => Some(Keyword::Do),
         "else" => Some(Keyword::Else),
+        "export" => Some(Keyword::Export),
+        "extends" => Some(Keyword::Extends),
         "finally" => Some(Keyword::Finally),
         "for" => Some(Keyword::For),
         "function" => Some(Keyword::Function),
         "if" => Some(Keyword::If),
+        "import" => Some(Keyword::Import),
         "in" => Some(Keyword::In),
         "instanceof" => Some(Keyword::Instanceof),
         "let" => Some(Keyword::Let),
         "new" => Some(Keyword::New),
         "return" => Some(Keyword::Return),
+        "super" => Some(Keyword::Super),
         "switch" => Some(Keyword::Switch),
         "this" => Some(Keyword::This),
         "throw" => Some(Keyword::Throw),
@@ -1,12 +1,12 @@
 use crate::bytecode_gen::{ByteCode, ByteCodeGenerator, VMInst};
 use crate::node::{
-    BinOp, FormalParameter, FormalParameters, MethodDefinitionKind, Node, NodeBase,
-    PropertyDefinition, UnaryOp, VarKind,
+    BinOp, CaseClause, ClassElement, ExportDeclaration, FormalParameter, FormalParameters,
+    MethodDefinitionKind, Node, NodeBase, PropertyDefinition, PropertyPattern, UnaryOp, VarKind,
 };
-use crate::vm::constant::{ConstantTable, SpecialProperties, SpecialPropertyKind};
+use crate::vm::constant::{ClassMethodInfo, ConstantTable, SpecialProperties, SpecialPropertyKind};
 use crate::vm::factory::FunctionId;
 use crate::vm::jsvalue::function::{
-    DestinationKind, Exception, FuncInfoRef, ThisMode, UserFunctionInfo,
+    ConstructorKind, DestinationKind, Exception, FuncInfoRef, ThisMode, UserFunctionInfo,
 };
 use crate::vm::jsvalue::value;
 use crate::vm::jsvalue::value::Value;
@@ -15,6 +15,14 @@ use rustc_hash::FxHashMap;
 
 pub type CodeGenResult = Result<(), Error>;
 
+/// Name of the binding in a class scope that holds the class constructor.
+/// ``super`` is resolved through it, so it must not be a valid identifier.
+const CLASS_CONSTRUCTOR_BINDING: &str = "%ClassConstructor";
+
+/// Name of the binding in a module that holds the value of ``export default expression``.
+/// https://tc39.github.io/ecma262/#sec-exports-static-semantics-exportentries
+pub const DEFAULT_EXPORT_BINDING: &str = "*default*";
+
 #[derive(Clone, Debug)]
 pub struct Error {
     pub msg: String,
@@ -50,6 +58,22 @@ pub struct FunctionInfo {
     pub exception_table: Vec<Exception>,
     pub to_source_pos: ToSourcePos,
     pub module_func_id: FunctionId,
+    pub kind: FunctionKind,
+    /// True if ``arguments`` appears in the function or its arrow functions.
+    pub uses_arguments: bool,
+    /// True if the function is strict mode code.
+    pub is_strict: bool,
+}
+
+/// Kind of a function. Methods and class constructors can refer to ``super``.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FunctionKind {
+    Normal,
+    Arrow,
+    Method,
+    StaticMethod,
+    BaseConstructor,
+    DerivedConstructor,
 }
 
 #[derive(Debug, Clone)]
@@ -73,6 +97,20 @@ pub enum Level {
         break_jmp_instr_pos: Vec<usize>,
         continue_jmp_instr_pos: Vec<usize>,
     },
+    Switch {
+        break_jmp_instr_pos: Vec<usize>,
+    },
+    /// ``is_loop`` is true if the labelled statement is an iteration statement.
+    Label {
+        name: String,
+        is_loop: bool,
+        break_jmp_instr_pos: Vec<usize>,
+    },
+    /// The iterator of a for-in/of loop is on the stack. ``enter_iter_pos`` is the position of
+    /// the ENTER_ITER instruction of a for-of loop, whose iterator is closed when leaving it.
+    ForInOf {
+        enter_iter_pos: Option<usize>,
+    },
 }
 
 impl<'a> CodeGenerator<'a> {
@@ -92,10 +130,54 @@ impl<'a> CodeGenerator<'a> {
     }
 
     pub fn compile(&mut self, node: &Node, use_value: bool) -> Result<FuncInfoRef, Error> {
+        self.current_function().is_strict = node.has_use_strict_directive();
+        self.compile_global(node, use_value)
+    }
+
+    /// Module code is always strict mode code.
+    pub fn compile_module(&mut self, node: &Node) -> Result<FuncInfoRef, Error> {
+        self.current_function().is_strict = true;
+        let mut iseq = vec![];
+        self.visit(node, &mut iseq, false)?;
+        // The module body returns undefined to the importing code.
+        self.bytecode_generator.append_push_undefined(&mut iseq);
+        self.bytecode_generator.append_return(&mut iseq);
+        Ok(self.global_function_info(iseq))
+    }
+
+    /// Compiles the source text given to the Function constructor, which is a single function
+    /// expression. The function is created in the global scope.
+    pub fn compile_function(&mut self, node: &Node) -> Result<FuncInfoRef, Error> {
+        let func = match node.base {
+            NodeBase::StatementList(ref items) if items.len() == 1 => &items[0],
+            _ => {
+                return Err(Error::new_general_error(
+                    "Expected a single function".to_string(),
+                    node.pos,
+                ))
+            }
+        };
+        match func.base {
+            NodeBase::FunctionExpr(ref name, ref params, ref body, is_async, is_generator) => {
+                self.node_pos = func.pos;
+                let kind = FunctionKind::Normal;
+                self.visit_function(name.clone(), params, body, kind, is_async, is_generator)
+            }
+            _ => Err(Error::new_general_error(
+                "Expected a single function".to_string(),
+                func.pos,
+            )),
+        }
+    }
+
+    fn compile_global(&mut self, node: &Node, use_value: bool) -> Result<FuncInfoRef, Error> {
         let mut iseq = vec![];
         self.visit(node, &mut iseq, use_value)?;
         self.bytecode_generator.append_return(&mut iseq);
+        Ok(self.global_function_info(iseq))
+    }
 
+    fn global_function_info(&mut self, iseq: ByteCode) -> FuncInfoRef {
         let function_info = self.function_stack.pop().unwrap();
         let module_id = self.module_func_id;
         self.to_source_map
@@ -106,16 +188,24 @@ impl<'a> CodeGenerator<'a> {
             func_id: module_id,
             module_func_id: module_id,
             params: vec![],
+            length: 0,
+            source_pos: 0,
             var_names: function_info.var_names,
             lex_names: function_info.lex_names,
             func_decls: function_info.func_decls,
             constructible: false,
+            constructor_kind: ConstructorKind::Base,
+            is_class_constructor: false,
             this_mode: ThisMode::Global,
+            is_async: false,
+            is_generator: false,
+            uses_arguments: false,
+            is_strict: function_info.is_strict,
             code: iseq,
             exception_table: function_info.exception_table,
         };
 
-        Ok(self.factory.alloc_user_func_info(module_id, user_func_info))
+        self.factory.alloc_user_func_info(module_id, user_func_info)
     }
 }
 
@@ -135,26 +225,67 @@ impl<'a> CodeGenerator<'a> {
                 self.visit_if(&*cond, &*then, &*else_, iseq)?
             }
             NodeBase::While(ref cond, ref body) => self.visit_while(&*cond, &*body, iseq)?,
+            NodeBase::DoWhile(ref body, ref cond) => self.visit_do_while(body, cond, iseq)?,
             NodeBase::For(ref init, ref cond, ref step, ref body) => {
                 self.visit_for(&*init, &*cond, &*step, &*body, iseq)?
             }
+            NodeBase::ForIn(ref var, ref obj, ref body) => {
+                self.visit_for_in_of(var, obj, body, false, iseq)?
+            }
+            NodeBase::ForOf(ref var, ref iterable, ref body) => {
+                self.visit_for_in_of(var, iterable, body, true, iseq)?
+            }
+            NodeBase::Switch(ref discriminant, ref clauses) => {
+                self.visit_switch(discriminant, clauses, iseq)?
+            }
+            NodeBase::Label(ref name, ref body) => self.visit_label(name, body, iseq)?,
             NodeBase::Break(ref name) => self.visit_break(name, iseq)?,
             NodeBase::Continue(ref name) => self.visit_continue(name, iseq)?,
             NodeBase::Try(ref try_clause, ref catch, ref param, ref finally) => {
                 self.visit_try(&*try_clause, &*catch, &*param, &*finally, iseq)?
             }
-            NodeBase::FunctionDecl(ref name, ref params, ref body) => {
-                self.visit_function_decl(name, params, &*body)?
+            NodeBase::FunctionDecl(ref name, ref params, ref body, is_async, is_generator) => {
+                self.visit_function_decl(name, params, body, is_async, is_generator)?
+            }
+            NodeBase::FunctionExpr(ref name, ref params, ref body, is_async, is_generator) => {
+                if use_value {
+                    self.visit_function_expr(
+                        name,
+                        params,
+                        body,
+                        FunctionKind::Normal,
+                        is_async,
+                        is_generator,
+                        iseq,
+                    )?
+                }
             }
-            NodeBase::FunctionExpr(ref name, ref params, ref body) => {
-                self.visit_function_expr(name, params, &*body, true, iseq, use_value)?
+            NodeBase::ArrowFunction(ref params, ref body, is_async) => {
+                if use_value {
+                    self.visit_function_expr(
+                        &None,
+                        params,
+                        body,
+                        FunctionKind::Arrow,
+                        is_async,
+                        false,
+                        iseq,
+                    )?
+                }
             }
-            NodeBase::ArrowFunction(ref params, ref body) => {
-                self.visit_function_expr(&None, params, &*body, false, iseq, use_value)?
+            NodeBase::Class(ref name, ref heritage, ref constructor, ref elements) => {
+                self.visit_class(name, name, heritage, constructor, elements, iseq, use_value)?
             }
             NodeBase::VarDecl(ref name, ref init, ref kind) => {
                 self.visit_var_decl(node, name, init, kind, iseq)?
             }
+            NodeBase::PatternDecl(ref pattern, ref init, ref kind) => {
+                self.visit_pattern_decl(node, pattern, init, kind, iseq)?
+            }
+            // Patterns only appear as assignment targets.
+            NodeBase::ArrayPattern(_)
+            | NodeBase::ObjectPattern(_)
+            | NodeBase::AssignmentPattern(_, _) => unreachable!(),
             NodeBase::Member(ref parent, ref property) => {
                 self.visit_member(&*parent, property, iseq, use_value)?
             }
@@ -174,10 +305,20 @@ impl<'a> CodeGenerator<'a> {
                 self.visit_call(&*callee, args, iseq, use_value)?
             }
             NodeBase::Throw(ref val) => self.visit_throw(val, iseq)?,
+            NodeBase::Await(ref val) => self.visit_await(val, iseq, use_value)?,
+            NodeBase::Yield(ref val, is_delegate) => {
+                self.visit_yield(val, is_delegate, iseq, use_value)?
+            }
             NodeBase::Return(ref val) => self.visit_return(val, iseq)?,
             NodeBase::New(ref expr) => self.visit_new(&*expr, iseq, use_value)?,
             NodeBase::Object(ref properties) => self.visit_object_literal(properties, iseq)?,
             NodeBase::Array(ref elems) => self.visit_array_literal(elems, iseq)?,
+            NodeBase::TemplateLiteral(ref strings, ref substitutions) => {
+                self.visit_template_literal(strings, substitutions, iseq, use_value)?
+            }
+            NodeBase::TaggedTemplate(ref tag, ref cooked, ref raw, ref substitutions) => {
+                self.visit_tagged_template(tag, cooked, raw, substitutions, iseq, use_value)?
+            }
             NodeBase::Identifier(ref name) => {
                 self.save_source_pos(iseq);
                 self.bytecode_generator.append_get_value(name, iseq);
@@ -203,12 +344,23 @@ impl<'a> CodeGenerator<'a> {
                     self.bytecode_generator.append_push_this(iseq);
                 }
             }
+            NodeBase::Super => self.visit_super(node, iseq, use_value)?,
+            NodeBase::Arguments => self.visit_arguments(iseq, use_value),
             NodeBase::String(ref s) => {
                 if use_value {
                     self.bytecode_generator
                         .append_push_const(self.factory.string(s.clone()), iseq)
                 }
             }
+            NodeBase::RegExpLiteral(ref pattern, ref flags) => {
+                if use_value {
+                    self.bytecode_generator
+                        .append_push_const(self.factory.string(pattern.clone()), iseq);
+                    self.bytecode_generator
+                        .append_push_const(self.factory.string(flags.clone()), iseq);
+                    self.bytecode_generator.append_create_regexp(iseq);
+                }
+            }
             NodeBase::Number(n) => {
                 if use_value {
                     self.bytecode_generator.append_push_number(n, iseq)
@@ -228,12 +380,48 @@ impl<'a> CodeGenerator<'a> {
             NodeBase::TernaryOp(ref condition, ref then_clause, ref else_clause) => {
                 self.visit_ternary_op(&*condition, &*then_clause, &*else_clause, iseq, use_value)?
             }
-            ref e => unimplemented!("{:?}", e),
+            // Imported bindings are created when the module is linked.
+            NodeBase::Import(_, _) => {}
+            NodeBase::Export(ref declaration) => self.visit_export(declaration, iseq)?,
+            NodeBase::ImportCall(ref specifier) => {
+                self.visit(specifier, iseq, true)?;
+                self.save_source_pos(iseq);
+                self.bytecode_generator.append_import_call(iseq);
+                if !use_value {
+                    self.bytecode_generator.append_pop(iseq);
+                }
+            }
         }
 
         Ok(())
     }
 
+    /// export declaration
+    /// Exported names are bound to the declared variables when the module is linked.
+    fn visit_export(
+        &mut self,
+        declaration: &ExportDeclaration,
+        iseq: &mut ByteCode,
+    ) -> CodeGenResult {
+        match declaration {
+            ExportDeclaration::Declaration(ref node) => self.visit(node, iseq, false),
+            ExportDeclaration::Default(ref node) => match node.base {
+                NodeBase::FunctionDecl(_, _, _, _, _) | NodeBase::VarDecl(_, _, _) => {
+                    self.visit(node, iseq, false)
+                }
+                // An anonymous function or class is named "default".
+                _ => {
+                    let name = DEFAULT_EXPORT_BINDING.to_string();
+                    self.visit_named_evaluation(node, "default", iseq)?;
+                    self.save_source_pos(iseq);
+                    self.bytecode_generator.append_set_value(&name, iseq);
+                    self.declare(node, name, &VarKind::Let)
+                }
+            },
+            ExportDeclaration::Named(_, _) | ExportDeclaration::All(_, _) => Ok(()),
+        }
+    }
+
     fn visit_statement_list(
         &mut self,
         node_list: &Vec<Node>,
@@ -362,6 +550,41 @@ impl<'a> CodeGenerator<'a> {
         Ok(())
     }
 
+    pub fn visit_do_while(
+        &mut self,
+        body: &Node,
+        cond: &Node,
+        iseq: &mut ByteCode,
+    ) -> CodeGenResult {
+        self.current_function().level.push(Level::Loop {
+            break_jmp_instr_pos: vec![],
+            continue_jmp_instr_pos: vec![],
+        });
+
+        let start = iseq.len() as isize;
+
+        self.visit(body, iseq, false)?;
+
+        let continue_pos = iseq.len() as isize;
+
+        self.visit(cond, iseq, true)?;
+
+        self.bytecode_generator.append_jmp_if_false(5, iseq);
+        let loop_pos = iseq.len() as isize;
+        self.bytecode_generator
+            .append_jmp((start - loop_pos) as i32 - 5, iseq);
+
+        let end = iseq.len() as isize;
+
+        self.current_function()
+            .level
+            .pop()
+            .unwrap()
+            .replace_break_and_continue(&mut self.bytecode_generator, iseq, end, continue_pos);
+
+        Ok(())
+    }
+
     pub fn visit_for(
         &mut self,
         init: &Node,
@@ -409,27 +632,312 @@ impl<'a> CodeGenerator<'a> {
         Ok(())
     }
 
-    pub fn visit_break(&mut self, _name: &Option<String>, iseq: &mut ByteCode) -> CodeGenResult {
-        self.unwind_loop(iseq);
+    /// for (var in obj) body
+    /// for (var of iterable) body
+    pub fn visit_for_in_of(
+        &mut self,
+        var: &Node,
+        expr: &Node,
+        body: &Node,
+        is_for_of: bool,
+        iseq: &mut ByteCode,
+    ) -> CodeGenResult {
+        self.visit(expr, iseq, true)?;
+
+        // The iterator stays on the stack top while the loop runs.
+        self.node_pos = expr.pos;
+        self.save_source_pos(iseq);
+        let enter_iter_pos = if is_for_of {
+            self.bytecode_generator.append_get_iterator(iseq);
+            let enter_iter_pos = iseq.len();
+            self.bytecode_generator.append_enter_iter(iseq);
+            Some(enter_iter_pos)
+        } else {
+            self.bytecode_generator.append_enumerate(iseq);
+            None
+        };
+
+        self.current_function()
+            .level
+            .push(Level::ForInOf { enter_iter_pos });
+        self.current_function().level.push(Level::Loop {
+            break_jmp_instr_pos: vec![],
+            continue_jmp_instr_pos: vec![],
+        });
+
+        let start = iseq.len() as isize;
+        self.save_source_pos(iseq);
+        self.bytecode_generator.append_iter_next(0, iseq);
+
+        // let/const declarations get a fresh binding for each iteration.
+        let lex_env_id = match var.base {
+            NodeBase::VarDecl(_, _, VarKind::Let)
+            | NodeBase::VarDecl(_, _, VarKind::Const)
+            | NodeBase::PatternDecl(_, _, VarKind::Let)
+            | NodeBase::PatternDecl(_, _, VarKind::Const) => {
+                let id = self
+                    .bytecode_generator
+                    .constant_table
+                    .add_lex_env_info(vec![]);
+                self.bytecode_generator.append_push_env(id as u32, iseq);
+                self.current_function().level.push(Level::new_block_level());
+                Some(id)
+            }
+            _ => None,
+        };
+
+        match var.base {
+            NodeBase::VarDecl(ref name, _, _) => {
+                self.visit(var, iseq, false)?;
+                let dst = Node::new(NodeBase::Identifier(name.clone()), var.pos);
+                self.assign_stack_top_to(&dst, iseq)?;
+            }
+            NodeBase::PatternDecl(ref pattern, _, _) => {
+                self.visit(var, iseq, false)?;
+                self.assign_stack_top_to(pattern, iseq)?;
+            }
+            _ => self.assign_stack_top_to(var, iseq)?,
+        }
+
+        self.visit(body, iseq, false)?;
+
+        if let Some(id) = lex_env_id {
+            match self.current_function().level.pop().unwrap() {
+                Level::Block { names } => {
+                    *self
+                        .bytecode_generator
+                        .constant_table
+                        .get_mut(id)
+                        .as_lex_env_info_mut() = names;
+                }
+                _ => unreachable!(),
+            };
+            self.bytecode_generator.append_pop_env(iseq);
+        }
+
+        let loop_pos = iseq.len() as isize;
+        self.bytecode_generator
+            .append_jmp((start - loop_pos) as i32 - 5, iseq);
+
+        // An exception thrown in the loop closes the iterator and is rethrown.
+        if let Some(enter_iter_pos) = enter_iter_pos {
+            let iter_close_throw_pos = iseq.len();
+            self.bytecode_generator
+                .append_iter_close_throw(enter_iter_pos, iseq);
+            self.current_function().exception_table.push(Exception {
+                start: start as usize,
+                end: iter_close_throw_pos,
+                dst_kind: DestinationKind::Catch,
+            });
+        }
+
+        let end = iseq.len() as isize;
+        self.bytecode_generator.replace_int32(
+            (end - start) as i32 - 5,
+            &mut iseq[start as usize + 1..start as usize + 5],
+        );
+
+        self.current_function()
+            .level
+            .pop()
+            .unwrap()
+            .replace_break_and_continue(&mut self.bytecode_generator, iseq, end, start);
+
+        assert_eq!(
+            self.current_function().level.pop().unwrap(),
+            Level::ForInOf { enter_iter_pos }
+        );
+        match enter_iter_pos {
+            // Close the iterator unless it is done.
+            Some(enter_iter_pos) => self
+                .bytecode_generator
+                .append_iter_close(enter_iter_pos, iseq),
+            // Pop the iterator.
+            None => self.bytecode_generator.append_pop(iseq),
+        }
+
+        Ok(())
+    }
+
+    pub fn visit_switch(
+        &mut self,
+        discriminant: &Node,
+        clauses: &Vec<CaseClause>,
+        iseq: &mut ByteCode,
+    ) -> CodeGenResult {
+        self.current_function().level.push(Level::Switch {
+            break_jmp_instr_pos: vec![],
+        });
+
+        self.visit(discriminant, iseq, true)?;
+
+        let id = self
+            .bytecode_generator
+            .constant_table
+            .add_lex_env_info(vec![]);
+        self.bytecode_generator.append_push_env(id as u32, iseq);
+        self.current_function().level.push(Level::new_block_level());
+
+        // Compare the discriminant with each case in order. On a match, the discriminant is
+        // popped and control jumps to the body of the clause.
+        let mut body_jmp_instr_pos = vec![];
+        for clause in clauses {
+            if let Some(ref test) = clause.test {
+                self.bytecode_generator.append_double(iseq);
+                self.visit(test, iseq, true)?;
+                self.bytecode_generator.append_seq(iseq);
+                self.bytecode_generator.append_jmp_if_false(6, iseq);
+                self.bytecode_generator.append_pop(iseq);
+                body_jmp_instr_pos.push(Some(iseq.len()));
+                self.bytecode_generator.append_jmp(0, iseq);
+            } else {
+                body_jmp_instr_pos.push(None);
+            }
+        }
+
+        // No case matched: jump to the default clause, or out of the switch.
+        self.bytecode_generator.append_pop(iseq);
+        let default_jmp_instr_pos = iseq.len();
+        self.bytecode_generator.append_jmp(0, iseq);
+        let mut default_dst = None;
+
+        // Bodies are laid out in source order so that control falls through.
+        for (clause, jmp_instr_pos) in clauses.iter().zip(body_jmp_instr_pos) {
+            let dst = iseq.len();
+            match jmp_instr_pos {
+                Some(instr_pos) => self.bytecode_generator.replace_int32(
+                    (dst - instr_pos) as i32 - 5,
+                    &mut iseq[instr_pos + 1..instr_pos + 5],
+                ),
+                None => default_dst = Some(dst),
+            }
+            self.visit_statement_list(&clause.body, iseq, false)?;
+        }
+
+        match self.current_function().level.pop().unwrap() {
+            Level::Block { names } => {
+                *self
+                    .bytecode_generator
+                    .constant_table
+                    .get_mut(id)
+                    .as_lex_env_info_mut() = names;
+            }
+            _ => unreachable!(),
+        };
+
+        let pop_env_pos = iseq.len();
+        self.bytecode_generator.append_pop_env(iseq);
+
+        let end = iseq.len();
+        let default_dst = default_dst.unwrap_or(pop_env_pos);
+        self.bytecode_generator.replace_int32(
+            (default_dst - default_jmp_instr_pos) as i32 - 5,
+            &mut iseq[default_jmp_instr_pos + 1..default_jmp_instr_pos + 5],
+        );
+
+        self.current_function().level.pop().unwrap().replace_break(
+            &mut self.bytecode_generator,
+            iseq,
+            end as isize,
+        );
+
+        Ok(())
+    }
+
+    pub fn visit_label(
+        &mut self,
+        name: &String,
+        body: &Node,
+        iseq: &mut ByteCode,
+    ) -> CodeGenResult {
+        if self.current_function().find_label(name).is_some() {
+            return Err(Error::new_general_error(
+                format!("Label '{}' has already been declared", name),
+                self.node_pos,
+            ));
+        }
+
+        self.current_function().level.push(Level::Label {
+            name: name.clone(),
+            is_loop: body.is_iteration_statement(),
+            break_jmp_instr_pos: vec![],
+        });
+
+        self.visit(body, iseq, false)?;
+
+        let end = iseq.len() as isize;
+        self.current_function().level.pop().unwrap().replace_break(
+            &mut self.bytecode_generator,
+            iseq,
+            end,
+        );
+
+        Ok(())
+    }
+
+    pub fn visit_break(&mut self, name: &Option<String>, iseq: &mut ByteCode) -> CodeGenResult {
+        let target = match name {
+            Some(name) => match self.current_function().find_label(name) {
+                Some(target) => target,
+                None => return Err(self.undefined_label_error(name)),
+            },
+            None => match self.current_function().find_last_breakable() {
+                Some(target) => target,
+                None => {
+                    return Err(Error::new_general_error(
+                        "Illegal break statement".to_string(),
+                        self.node_pos,
+                    ))
+                }
+            },
+        };
+
+        self.unwind_to(target, iseq);
 
         let break_instr_pos = iseq.len();
         self.bytecode_generator.append_jmp(0, iseq);
 
-        self.current_function()
-            .get_last_loop()
-            .as_loop_mut()
-            .0
+        self.current_function().level[target]
+            .as_break_jmp_instr_pos_mut()
             .push(break_instr_pos);
 
         Ok(())
     }
 
-    pub fn visit_continue(&mut self, _name: &Option<String>, iseq: &mut ByteCode) -> CodeGenResult {
+    pub fn visit_continue(&mut self, name: &Option<String>, iseq: &mut ByteCode) -> CodeGenResult {
+        let target = match name {
+            Some(name) => match self.current_function().find_label(name) {
+                Some(label) => match self.current_function().find_labelled_loop(label) {
+                    Some(target) => target,
+                    None => {
+                        return Err(Error::new_general_error(
+                            format!(
+                                "Illegal continue statement: '{}' does not denote an iteration statement",
+                                name
+                            ),
+                            self.node_pos,
+                        ))
+                    }
+                },
+                None => return Err(self.undefined_label_error(name)),
+            },
+            None => match self.current_function().find_last_loop() {
+                Some(target) => target,
+                None => {
+                    return Err(Error::new_general_error(
+                        "Illegal continue statement".to_string(),
+                        self.node_pos,
+                    ))
+                }
+            },
+        };
+
+        self.unwind_to(target, iseq);
+
         let continue_instr_pos = iseq.len();
         self.bytecode_generator.append_jmp(0, iseq);
 
-        self.current_function()
-            .get_last_loop()
+        self.current_function().level[target]
             .as_loop_mut()
             .1
             .push(continue_instr_pos);
@@ -484,10 +992,6 @@ impl<'a> CodeGenerator<'a> {
         // Catch block
         let (catch_, catch_to_finally, leave_catch) = if has_catch {
             let catch_start = iseq.len() as usize;
-            let param_name = match param.base {
-                NodeBase::Identifier(ref name) => name.clone(),
-                _ => unimplemented!(),
-            };
 
             self.current_function()
                 .level
@@ -498,10 +1002,9 @@ impl<'a> CodeGenerator<'a> {
                 .add_lex_env_info(vec![]);
             self.bytecode_generator.append_push_env(env_id as u32, iseq);
             self.current_function().level.push(Level::Block {
-                names: vec![param_name.clone()],
+                names: param.bound_names(),
             });
-            self.save_source_pos(iseq);
-            self.bytecode_generator.append_set_value(&param_name, iseq);
+            self.assign_stack_top_to(param, iseq)?;
 
             self.visit(catch, iseq, false)?;
 
@@ -536,9 +1039,12 @@ impl<'a> CodeGenerator<'a> {
         // Finally block
         let finally_start = iseq.len() as usize;
 
-        let has_return_try =
-            try_.set_jmp_to_finally(finally_start, &mut self.bytecode_generator, iseq)
-                || catch_.set_jmp_to_finally(finally_start, &mut self.bytecode_generator, iseq);
+        // Both must be evaluated to set all the jumps.
+        let try_has_return =
+            try_.set_jmp_to_finally(finally_start, &mut self.bytecode_generator, iseq);
+        let catch_has_return =
+            catch_.set_jmp_to_finally(finally_start, &mut self.bytecode_generator, iseq);
+        let has_return_try = try_has_return || catch_has_return;
 
         self.current_function().level.push(Level::Finally);
         self.visit(finally, iseq, false)?;
@@ -591,27 +1097,35 @@ impl<'a> CodeGenerator<'a> {
         name: &String,
         params: &FormalParameters,
         body: &Node,
+        is_async: bool,
+        is_generator: bool,
     ) -> CodeGenResult {
-        let func_info = self.visit_function(Some(name.clone()), params, body, true)?;
+        let func_info = self.visit_function(
+            Some(name.clone()),
+            params,
+            body,
+            FunctionKind::Normal,
+            is_async,
+            is_generator,
+        )?;
         self.current_function().var_names.push(name.clone());
         self.current_function().func_decls.push(func_info);
         Ok(())
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn visit_function_expr(
         &mut self,
         name: &Option<String>,
         params: &FormalParameters,
         body: &Node,
-        arrow_function: bool,
+        kind: FunctionKind,
+        is_async: bool,
+        is_generator: bool,
         iseq: &mut ByteCode,
-        use_value: bool,
     ) -> CodeGenResult {
-        if !use_value {
-            return Ok(());
-        }
-
-        let func_info = self.visit_function(name.clone(), params, body, arrow_function)?;
+        let func_info =
+            self.visit_function(name.clone(), params, body, kind, is_async, is_generator)?;
         let val = self.factory.function(func_info, None);
         self.bytecode_generator.append_push_const(val, iseq);
         self.bytecode_generator.append_set_outer_env(iseq);
@@ -619,39 +1133,81 @@ impl<'a> CodeGenerator<'a> {
         Ok(())
     }
 
+    /// https://tc39.github.io/ecma262/#sec-runtime-semantics-namedevaluation
+    /// Evaluates ``node``, naming it ``name`` if it is an anonymous function or class.
+    fn visit_named_evaluation(
+        &mut self,
+        node: &Node,
+        name: &str,
+        iseq: &mut ByteCode,
+    ) -> CodeGenResult {
+        let name = Some(name.to_string());
+        self.node_pos = node.pos;
+        match node.base {
+            NodeBase::FunctionExpr(None, ref params, ref body, is_async, is_generator) => self
+                .visit_function_expr(
+                    &name,
+                    params,
+                    body,
+                    FunctionKind::Normal,
+                    is_async,
+                    is_generator,
+                    iseq,
+                ),
+            NodeBase::ArrowFunction(ref params, ref body, is_async) => self.visit_function_expr(
+                &name,
+                params,
+                body,
+                FunctionKind::Arrow,
+                is_async,
+                false,
+                iseq,
+            ),
+            NodeBase::Class(None, ref heritage, ref constructor, ref elements) => {
+                self.visit_class(&None, &name, heritage, constructor, elements, iseq, true)
+            }
+            _ => self.visit(node, iseq, true),
+        }
+    }
+
     fn visit_function(
         &mut self,
         name: Option<String>,
         params: &FormalParameters,
         body: &Node,
-        arrow_function: bool,
+        kind: FunctionKind,
+        is_async: bool,
+        is_generator: bool,
     ) -> Result<FuncInfoRef, Error> {
-        self.function_stack
-            .push(FunctionInfo::new(name, self.module_func_id));
+        let source_pos = self.node_pos;
+        let mut function_info = FunctionInfo::new(name, self.module_func_id);
+        function_info.kind = kind;
+        // Functions in strict mode code and all parts of a class are strict.
+        function_info.is_strict = self.current_function().is_strict
+            || !matches!(kind, FunctionKind::Normal | FunctionKind::Arrow)
+            || body.has_use_strict_directive();
+        self.function_stack.push(function_info);
 
         let mut func_iseq = vec![];
 
+        // https://tc39.github.io/ecma262/#sec-static-semantics-expectedargumentcount
+        let length = params
+            .iter()
+            .take_while(|param| param.init.is_none() && !param.is_rest_param)
+            .count();
+
+        let params = params
+            .iter()
+            .enumerate()
+            .map(|(i, param)| self.visit_formal_parameter(i, param, &mut func_iseq))
+            .collect::<Result<Vec<value::FunctionParameter>, Error>>()?;
+
         self.visit(body, &mut func_iseq, false)?;
 
         self.bytecode_generator
             .append_push_undefined(&mut func_iseq);
         self.bytecode_generator.append_return(&mut func_iseq);
 
-        let params = params
-            .clone()
-            .iter()
-            .map(
-                |FormalParameter {
-                     name,
-                     is_rest_param,
-                     ..
-                 }| value::FunctionParameter {
-                    name: name.clone(),
-                    rest_param: *is_rest_param,
-                },
-            )
-            .collect();
-
         let function_info = self.function_stack.pop().unwrap();
 
         let func_id = self.factory.new_func_id();
@@ -664,15 +1220,41 @@ impl<'a> CodeGenerator<'a> {
             func_id,
             module_func_id: self.module_func_id,
             params,
+            length,
+            source_pos,
             var_names: function_info.var_names,
             lex_names: function_info.lex_names,
             func_decls: function_info.func_decls,
-            constructible: arrow_function,
-            this_mode: if arrow_function {
-                ThisMode::Global
+            constructible: !is_async
+                && !is_generator
+                && match kind {
+                    FunctionKind::Normal
+                    | FunctionKind::BaseConstructor
+                    | FunctionKind::DerivedConstructor => true,
+                    FunctionKind::Arrow | FunctionKind::Method | FunctionKind::StaticMethod => {
+                        false
+                    }
+                },
+            constructor_kind: if kind == FunctionKind::DerivedConstructor {
+                ConstructorKind::Derived
             } else {
+                ConstructorKind::Base
+            },
+            is_class_constructor: matches!(
+                kind,
+                FunctionKind::BaseConstructor | FunctionKind::DerivedConstructor
+            ),
+            this_mode: if kind == FunctionKind::Arrow {
                 ThisMode::Lexical
+            } else if function_info.is_strict {
+                ThisMode::Strict
+            } else {
+                ThisMode::Global
             },
+            is_async,
+            is_generator,
+            uses_arguments: function_info.uses_arguments,
+            is_strict: function_info.is_strict,
             code: func_iseq,
             exception_table: function_info.exception_table,
         };
@@ -682,6 +1264,232 @@ impl<'a> CodeGenerator<'a> {
         Ok(func_ref)
     }
 
+    /// Returns the parameter bound on call. A default value and destructuring are done at the
+    /// beginning of the function, where a pattern is destructured from a hidden parameter.
+    fn visit_formal_parameter(
+        &mut self,
+        idx: usize,
+        param: &FormalParameter,
+        iseq: &mut ByteCode,
+    ) -> Result<value::FunctionParameter, Error> {
+        let name = match param.binding.base {
+            NodeBase::Identifier(ref name) if param.init.is_none() => {
+                return Ok(value::FunctionParameter {
+                    name: name.clone(),
+                    rest_param: param.is_rest_param,
+                });
+            }
+            NodeBase::Identifier(ref name) => name.clone(),
+            _ => {
+                let names = param.binding.bound_names();
+                self.current_function().var_names.extend(names);
+                format!("%param{}", idx)
+            }
+        };
+
+        self.bytecode_generator.append_get_value(&name, iseq);
+        if let Some(ref init) = param.init {
+            self.append_default_value(&param.binding, init, iseq)?;
+        }
+        self.assign_stack_top_to(&param.binding, iseq)?;
+
+        Ok(value::FunctionParameter {
+            name,
+            rest_param: param.is_rest_param,
+        })
+    }
+
+    /// https://tc39.github.io/ecma262/#sec-runtime-semantics-classdefinitionevaluation
+    /// The class is evaluated in its own scope, which binds the class name and the class
+    /// constructor that ``super`` refers to. ``func_name`` is the name of the constructor, which
+    /// an anonymous class gets from the binding it is assigned to.
+    #[allow(clippy::too_many_arguments)]
+    fn visit_class(
+        &mut self,
+        name: &Option<String>,
+        func_name: &Option<String>,
+        heritage: &Option<Box<Node>>,
+        constructor: &Option<Box<Node>>,
+        elements: &[ClassElement],
+        iseq: &mut ByteCode,
+        use_value: bool,
+    ) -> CodeGenResult {
+        let pos = self.node_pos;
+        let mut names = vec![CLASS_CONSTRUCTOR_BINDING.to_string()];
+        if let Some(name) = name {
+            names.push(name.clone());
+        }
+        let id = self
+            .bytecode_generator
+            .constant_table
+            .add_lex_env_info(names);
+        self.bytecode_generator.append_push_env(id as u32, iseq);
+
+        match heritage {
+            Some(heritage) => self.visit(heritage, iseq, true)?,
+            None => self
+                .bytecode_generator
+                .append_push_const(Value::empty(), iseq),
+        }
+
+        self.bytecode_generator.append_push_seperator(iseq);
+        let mut methods = vec![];
+        for element in elements.iter().rev() {
+            let (name, params, body, is_async, is_generator) = match element.func.base {
+                NodeBase::FunctionExpr(ref name, ref params, ref body, is_async, is_generator) => {
+                    (name, params, body, is_async, is_generator)
+                }
+                _ => unreachable!(),
+            };
+            let kind = if element.is_static {
+                FunctionKind::StaticMethod
+            } else {
+                FunctionKind::Method
+            };
+            self.node_pos = element.func.pos;
+            self.visit_function_expr(name, params, body, kind, is_async, is_generator, iseq)?;
+            self.visit(&element.key, iseq, true)?;
+            methods.push(ClassMethodInfo {
+                kind: element.kind,
+                is_static: element.is_static,
+            });
+        }
+        // Methods are popped in the source order.
+        methods.reverse();
+
+        let kind = if heritage.is_some() {
+            FunctionKind::DerivedConstructor
+        } else {
+            FunctionKind::BaseConstructor
+        };
+        // The source text of the constructor is that of the class.
+        self.node_pos = pos;
+        match constructor {
+            Some(constructor) => match constructor.base {
+                NodeBase::FunctionExpr(_, ref params, ref body, _, _) => {
+                    self.visit_function_expr(func_name, params, body, kind, false, false, iseq)?
+                }
+                _ => unreachable!(),
+            },
+            None => {
+                let (params, body) = default_constructor(kind, pos);
+                self.visit_function_expr(func_name, &params, &body, kind, false, false, iseq)?
+            }
+        }
+
+        let id = self
+            .bytecode_generator
+            .constant_table
+            .add_class_info(methods);
+        self.save_source_pos(iseq);
+        self.bytecode_generator.append_create_class(id, iseq);
+
+        self.bytecode_generator.append_double(iseq);
+        self.bytecode_generator
+            .append_set_value(&CLASS_CONSTRUCTOR_BINDING.to_string(), iseq);
+        if let Some(name) = name {
+            self.bytecode_generator.append_double(iseq);
+            self.bytecode_generator.append_set_value(name, iseq);
+        }
+
+        self.bytecode_generator.append_pop_env(iseq);
+
+        if !use_value {
+            self.bytecode_generator.append_pop(iseq);
+        }
+
+        return Ok(());
+
+        /// https://tc39.github.io/ecma262/#sec-runtime-semantics-classdefinitionevaluation (step 10)
+        /// ``constructor() {}`` or ``constructor(...args) { super(...args) }``
+        fn default_constructor(kind: FunctionKind, pos: usize) -> (FormalParameters, Node) {
+            if kind == FunctionKind::BaseConstructor {
+                return (vec![], Node::new(NodeBase::StatementList(vec![]), pos));
+            }
+
+            let args = Node::new(NodeBase::Identifier("args".to_string()), pos);
+            let super_call = Node::new(
+                NodeBase::Call(
+                    Box::new(Node::new(NodeBase::Super, pos)),
+                    vec![Node::new(NodeBase::Spread(Box::new(args)), pos)],
+                ),
+                pos,
+            );
+            let args = Node::new(NodeBase::Identifier("args".to_string()), pos);
+            (
+                vec![FormalParameter::new(args, None, true)],
+                Node::new(NodeBase::StatementList(vec![super_call]), pos),
+            )
+        }
+    }
+
+    /// ``super.name`` and ``super[expr]`` refer to a property of the \[\[Prototype\]\] of the home
+    /// object, which is the class prototype, or the class constructor in static methods.
+    fn visit_super(&mut self, node: &Node, iseq: &mut ByteCode, use_value: bool) -> CodeGenResult {
+        let is_static = self.is_super_in_static_method(node.pos)?;
+
+        self.save_source_pos(iseq);
+        self.bytecode_generator
+            .append_get_value(&CLASS_CONSTRUCTOR_BINDING.to_string(), iseq);
+        if !is_static {
+            let prototype = self.factory.string("prototype");
+            self.bytecode_generator.append_push_const(prototype, iseq);
+            self.bytecode_generator.append_get_member(iseq);
+        }
+        let proto = self.factory.string("__proto__");
+        self.bytecode_generator.append_push_const(proto, iseq);
+        self.bytecode_generator.append_get_member(iseq);
+
+        if !use_value {
+            self.bytecode_generator.append_pop(iseq);
+        }
+
+        Ok(())
+    }
+
+    /// https://tc39.github.io/ecma262/#sec-super-keyword-runtime-semantics-evaluation
+    /// Arguments are passed as an array, so that they may contain spread elements.
+    fn visit_super_call(
+        &mut self,
+        callee: &Node,
+        args: &Vec<Node>,
+        iseq: &mut ByteCode,
+        use_value: bool,
+    ) -> CodeGenResult {
+        // Arrow functions call super() of the function they are in.
+        let kind = self
+            .function_stack
+            .iter()
+            .rev()
+            .map(|info| info.kind)
+            .find(|kind| *kind != FunctionKind::Arrow);
+        if kind != Some(FunctionKind::DerivedConstructor) {
+            return Err(Error::new_general_error(
+                "'super' keyword unexpected here".to_string(),
+                callee.pos,
+            ));
+        }
+
+        self.visit_array_literal(args, iseq)?;
+
+        self.save_source_pos(iseq);
+        self.bytecode_generator
+            .append_get_value(&CLASS_CONSTRUCTOR_BINDING.to_string(), iseq);
+        let proto = self.factory.string("__proto__");
+        self.bytecode_generator.append_push_const(proto, iseq);
+        self.bytecode_generator.append_get_member(iseq);
+
+        self.save_source_pos(iseq);
+        self.bytecode_generator.append_super_call(iseq);
+        self.bytecode_generator.append_bind_this(iseq);
+
+        if !use_value {
+            self.bytecode_generator.append_pop(iseq);
+        }
+
+        Ok(())
+    }
+
     pub fn visit_var_decl(
         &mut self,
         node: &Node,
@@ -690,6 +1498,41 @@ impl<'a> CodeGenerator<'a> {
         kind: &VarKind,
         iseq: &mut ByteCode,
     ) -> CodeGenResult {
+        // let mut is_initialized = false;
+
+        if let Some(init) = init {
+            self.visit_named_evaluation(init, name, iseq)?;
+            self.save_source_pos(iseq);
+            self.bytecode_generator.append_set_value(name, iseq);
+            // is_initialized = true;
+        }
+
+        self.declare(node, name.clone(), kind)
+    }
+
+    /// var/let/const [a, b] = init
+    /// var/let/const { a, b } = init
+    pub fn visit_pattern_decl(
+        &mut self,
+        node: &Node,
+        pattern: &Node,
+        init: &Option<Box<Node>>,
+        kind: &VarKind,
+        iseq: &mut ByteCode,
+    ) -> CodeGenResult {
+        for name in pattern.bound_names() {
+            self.declare(node, name, kind)?;
+        }
+
+        if let Some(init) = init {
+            self.visit(init, iseq, true)?;
+            self.assign_stack_top_to(pattern, iseq)?;
+        }
+
+        Ok(())
+    }
+
+    fn declare(&mut self, node: &Node, name: String, kind: &VarKind) -> CodeGenResult {
         fn let_decl(codegen: &mut CodeGenerator, node: &Node, name: String) -> CodeGenResult {
             fn check_duplicate(
                 names: &mut Vec<String>,
@@ -712,22 +1555,13 @@ impl<'a> CodeGenerator<'a> {
             check_duplicate(&mut cur_func.lex_names, name, node)
         }
 
-        // let mut is_initialized = false;
-
-        if let &Some(ref init) = init {
-            self.visit(&*init, iseq, true)?;
-            self.save_source_pos(iseq);
-            self.bytecode_generator.append_set_value(name, iseq);
-            // is_initialized = true;
-        }
-
         match kind {
             VarKind::Var => {
-                self.current_function().var_names.push(name.clone());
+                self.current_function().var_names.push(name);
             }
-            VarKind::Let => let_decl(self, node, name.clone())?,
+            VarKind::Let => let_decl(self, node, name)?,
             // TODO: Const needs double-assignment check
-            VarKind::Const => let_decl(self, node, name.clone())?,
+            VarKind::Const => let_decl(self, node, name)?,
         }
 
         Ok(())
@@ -744,7 +1578,7 @@ impl<'a> CodeGenerator<'a> {
         let property = self.factory.string(member.clone());
         self.bytecode_generator.append_push_const(property, iseq);
         self.save_source_pos(iseq);
-        self.bytecode_generator.append_get_member(iseq);
+        self.append_get_member_of(parent, iseq);
 
         if !use_value {
             self.bytecode_generator.append_pop(iseq);
@@ -763,7 +1597,7 @@ impl<'a> CodeGenerator<'a> {
         self.visit(parent, iseq, true)?;
         self.visit(index, iseq, true)?;
         self.save_source_pos(iseq);
-        self.bytecode_generator.append_get_member(iseq);
+        self.append_get_member_of(parent, iseq);
 
         if !use_value {
             self.bytecode_generator.append_pop(iseq);
@@ -779,9 +1613,17 @@ impl<'a> CodeGenerator<'a> {
         iseq: &mut ByteCode,
         use_value: bool,
     ) -> CodeGenResult {
+        if op == &UnaryOp::Delete {
+            return self.visit_delete(expr, iseq, use_value);
+        }
+
         self.visit(expr, iseq, true)?;
 
         match op {
+            &UnaryOp::Void => {
+                self.bytecode_generator.append_pop(iseq);
+                self.bytecode_generator.append_push_undefined(iseq);
+            }
             &UnaryOp::Typeof => self.bytecode_generator.append_typeof(iseq),
             &UnaryOp::Plus => self.bytecode_generator.append_posi(iseq),
             &UnaryOp::Minus => self.bytecode_generator.append_neg(iseq),
@@ -815,7 +1657,48 @@ impl<'a> CodeGenerator<'a> {
                 self.bytecode_generator.append_sub(iseq);
                 self.assign_stack_top_to(expr, iseq)?;
             }
-            _ => unimplemented!(),
+            &UnaryOp::Delete => unreachable!(),
+        }
+
+        if !use_value {
+            self.bytecode_generator.append_pop(iseq);
+        }
+
+        Ok(())
+    }
+
+    /// https://tc39.github.io/ecma262/#sec-delete-operator-runtime-semantics-evaluation
+    fn visit_delete(&mut self, expr: &Node, iseq: &mut ByteCode, use_value: bool) -> CodeGenResult {
+        match expr.base {
+            NodeBase::Member(ref parent, _) | NodeBase::Index(ref parent, _)
+                if parent.base == NodeBase::Super =>
+            {
+                return Err(Error::new_general_error(
+                    "Reference error: Unsupported reference to 'super'".to_string(),
+                    expr.pos,
+                ));
+            }
+            NodeBase::Member(ref parent, ref property) => {
+                self.visit(parent, iseq, true)?;
+                let property = self.factory.string(property.clone());
+                self.bytecode_generator.append_push_const(property, iseq);
+                self.save_source_pos(iseq);
+                self.bytecode_generator.append_delete(iseq);
+            }
+            NodeBase::Index(ref parent, ref index) => {
+                self.visit(parent, iseq, true)?;
+                self.visit(index, iseq, true)?;
+                self.save_source_pos(iseq);
+                self.bytecode_generator.append_delete(iseq);
+            }
+            // Bindings created by declarations can not be deleted.
+            NodeBase::Identifier(ref name) => {
+                self.bytecode_generator.append_delete_value(name, iseq)
+            }
+            _ => {
+                self.visit(expr, iseq, false)?;
+                self.bytecode_generator.append_push_bool(true, iseq);
+            }
         }
 
         if !use_value {
@@ -890,6 +1773,8 @@ impl<'a> CodeGenerator<'a> {
             &BinOp::Gt => self.bytecode_generator.append_gt(iseq),
             &BinOp::Le => self.bytecode_generator.append_le(iseq),
             &BinOp::Ge => self.bytecode_generator.append_ge(iseq),
+            &BinOp::In => self.bytecode_generator.append_in(iseq),
+            &BinOp::InstanceOf => self.bytecode_generator.append_instance_of(iseq),
             &BinOp::Shl => self.bytecode_generator.append_shl(iseq),
             &BinOp::Shr => self.bytecode_generator.append_shr(iseq),
             &BinOp::ZFShr => self.bytecode_generator.append_zfshr(iseq),
@@ -945,7 +1830,10 @@ impl<'a> CodeGenerator<'a> {
         iseq: &mut ByteCode,
         use_value: bool,
     ) -> CodeGenResult {
-        self.visit(src, iseq, true)?;
+        match dst.base {
+            NodeBase::Identifier(ref name) => self.visit_named_evaluation(src, name, iseq)?,
+            _ => self.visit(src, iseq, true)?,
+        }
 
         if use_value {
             self.bytecode_generator.append_double(iseq);
@@ -963,30 +1851,60 @@ impl<'a> CodeGenerator<'a> {
         iseq: &mut ByteCode,
         use_value: bool,
     ) -> CodeGenResult {
+        if callee.base == NodeBase::Super {
+            return self.visit_super_call(callee, args, iseq, use_value);
+        }
+
         for arg in args.iter().rev() {
             self.visit(arg, iseq, true)?
         }
 
+        self.call_with_pushed_args(callee, args.len() as u32, iseq)?;
+
+        if !use_value {
+            self.bytecode_generator.append_pop(iseq);
+        }
+
+        Ok(())
+    }
+
+    /// Evaluate ``callee`` and call it with ``argc`` arguments already on the stack.
+    fn call_with_pushed_args(
+        &mut self,
+        callee: &Node,
+        argc: u32,
+        iseq: &mut ByteCode,
+    ) -> CodeGenResult {
         match callee.base {
+            // super.method() is called with the current this.
+            NodeBase::Member(ref parent, _) | NodeBase::Index(ref parent, _)
+                if parent.base == NodeBase::Super =>
+            {
+                self.bytecode_generator.append_push_this(iseq);
+                self.visit(callee, iseq, true)?;
+                self.save_source_pos(iseq);
+                self.bytecode_generator.append_call_with_this(argc, iseq);
+            }
             NodeBase::Member(ref parent, ref property_name) => {
                 self.bytecode_generator
                     .append_push_const(self.factory.string(property_name.clone()), iseq);
                 self.visit(&*parent, iseq, true)?;
                 self.save_source_pos(iseq);
-                self.bytecode_generator
-                    .append_call_method(args.len() as u32, iseq);
+                self.bytecode_generator.append_call_method(argc, iseq);
+            }
+            NodeBase::Index(ref parent, ref idx) => {
+                self.visit(idx, iseq, true)?;
+                self.visit(parent, iseq, true)?;
+                self.save_source_pos(iseq);
+                self.bytecode_generator.append_call_method(argc, iseq);
             }
             _ => {
                 self.visit(callee, iseq, true)?;
                 self.save_source_pos(iseq);
-                self.bytecode_generator.append_call(args.len() as u32, iseq);
+                self.bytecode_generator.append_call(argc, iseq);
             }
         }
 
-        if !use_value {
-            self.bytecode_generator.append_pop(iseq);
-        }
-
         Ok(())
     }
 
@@ -1004,6 +1922,106 @@ impl<'a> CodeGenerator<'a> {
         Ok(())
     }
 
+    /// https://tc39.github.io/ecma262/#sec-await
+    /// The function is resumed with the settled value and true if the promise was rejected,
+    /// in which case the value is thrown at the ``await``.
+    fn visit_await(&mut self, val: &Node, iseq: &mut ByteCode, use_value: bool) -> CodeGenResult {
+        self.visit(val, iseq, true)?;
+
+        self.save_source_pos(iseq);
+        self.bytecode_generator.append_await(iseq);
+
+        let jmp_if_false_pos = iseq.len() as isize;
+        self.bytecode_generator.append_jmp_if_false(0, iseq);
+
+        if self.current_function().in_try_or_catch() {
+            self.unwind_try_or_catch(iseq);
+        } else if self.current_function().in_finally() {
+            self.unwind_finally(iseq);
+        }
+        self.save_source_pos(iseq);
+        self.bytecode_generator.append_throw(iseq);
+
+        let end = iseq.len() as isize;
+        self.bytecode_generator.replace_int32(
+            (end - jmp_if_false_pos) as i32 - 5,
+            &mut iseq[jmp_if_false_pos as usize + 1..jmp_if_false_pos as usize + 5],
+        );
+
+        if !use_value {
+            self.bytecode_generator.append_pop(iseq);
+        }
+
+        Ok(())
+    }
+
+    /// https://tc39.github.io/ecma262/#sec-generator-function-definitions-runtime-semantics-evaluation
+    /// The generator is resumed with [value, is_throw, is_return]. The value is thrown at the
+    /// ``yield`` if is_throw is true, or returned from the generator if is_return is true.
+    /// ``yield*`` resumes the inner iterator instead, and returns only when it is done.
+    fn visit_yield(
+        &mut self,
+        val: &Option<Box<Node>>,
+        is_delegate: bool,
+        iseq: &mut ByteCode,
+        use_value: bool,
+    ) -> CodeGenResult {
+        if let Some(val) = val {
+            self.visit(val, iseq, true)?
+        } else {
+            self.bytecode_generator.append_push_undefined(iseq);
+        }
+
+        if is_delegate {
+            self.save_source_pos(iseq);
+            self.bytecode_generator.append_get_iterator(iseq);
+            self.bytecode_generator.append_push_undefined(iseq);
+            self.bytecode_generator.append_push_bool(false, iseq);
+            self.bytecode_generator.append_push_bool(false, iseq);
+            self.save_source_pos(iseq);
+            self.bytecode_generator.append_yield_delegate(iseq);
+        } else {
+            self.save_source_pos(iseq);
+            self.bytecode_generator.append_yield(iseq);
+        }
+
+        let jmp_if_false_pos = iseq.len() as isize;
+        self.bytecode_generator.append_jmp_if_false(0, iseq);
+        if !is_delegate {
+            // Pop is_throw.
+            self.bytecode_generator.append_pop(iseq);
+        }
+        self.append_return_value(iseq);
+        let end = iseq.len() as isize;
+        self.bytecode_generator.replace_int32(
+            (end - jmp_if_false_pos) as i32 - 5,
+            &mut iseq[jmp_if_false_pos as usize + 1..jmp_if_false_pos as usize + 5],
+        );
+
+        if !is_delegate {
+            let jmp_if_false_pos = iseq.len() as isize;
+            self.bytecode_generator.append_jmp_if_false(0, iseq);
+            if self.current_function().in_try_or_catch() {
+                self.unwind_try_or_catch(iseq);
+            } else if self.current_function().in_finally() {
+                self.unwind_finally(iseq);
+            }
+            self.save_source_pos(iseq);
+            self.bytecode_generator.append_throw(iseq);
+            let end = iseq.len() as isize;
+            self.bytecode_generator.replace_int32(
+                (end - jmp_if_false_pos) as i32 - 5,
+                &mut iseq[jmp_if_false_pos as usize + 1..jmp_if_false_pos as usize + 5],
+            );
+        }
+
+        if !use_value {
+            self.bytecode_generator.append_pop(iseq);
+        }
+
+        Ok(())
+    }
+
     fn visit_return(&mut self, val: &Option<Box<Node>>, iseq: &mut ByteCode) -> CodeGenResult {
         if let Some(val) = val {
             self.visit(val, iseq, true)?
@@ -1011,6 +2029,31 @@ impl<'a> CodeGenerator<'a> {
             self.bytecode_generator.append_push_undefined(iseq);
         }
 
+        self.append_return_value(iseq);
+
+        Ok(())
+    }
+
+    /// Returns the value on the stack, running the enclosing finally blocks first.
+    /// The iterators of the for-of loops inside the innermost try statement are closed here,
+    /// and the rest when the function returns.
+    fn append_return_value(&mut self, iseq: &mut ByteCode) {
+        let enter_iter_positions: Vec<usize> = self
+            .current_function()
+            .level
+            .iter()
+            .rev()
+            .take_while(|level| !matches!(level, Level::TryOrCatch { .. }))
+            .filter_map(|level| match level {
+                Level::ForInOf { enter_iter_pos } => *enter_iter_pos,
+                _ => None,
+            })
+            .collect();
+        for enter_iter_pos in enter_iter_positions {
+            self.bytecode_generator
+                .append_iter_close(enter_iter_pos, iseq);
+        }
+
         if self.current_function().in_try_or_catch() {
             self.current_function()
                 .get_last_try_or_catch()
@@ -1020,11 +2063,29 @@ impl<'a> CodeGenerator<'a> {
         } else {
             self.bytecode_generator.append_return(iseq);
         }
+    }
 
-        Ok(())
+    /// ``arguments`` of an arrow function refers to that of the enclosing function.
+    fn visit_arguments(&mut self, iseq: &mut ByteCode, use_value: bool) {
+        if let Some(function) = self
+            .function_stack
+            .iter_mut()
+            .rev()
+            .find(|function| function.kind != FunctionKind::Arrow)
+        {
+            function.uses_arguments = true;
+        }
+
+        self.save_source_pos(iseq);
+        self.bytecode_generator
+            .append_get_value(&"arguments".to_string(), iseq);
+        if !use_value {
+            self.bytecode_generator.append_pop(iseq);
+        }
     }
 
     fn visit_new(&mut self, expr: &Node, iseq: &mut ByteCode, use_value: bool) -> CodeGenResult {
+        let node_pos = self.node_pos;
         let (callee, args) = match expr.base {
             NodeBase::Call(ref callee, ref args) => (&*callee, args),
             _ => unimplemented!(),
@@ -1047,6 +2108,8 @@ impl<'a> CodeGenerator<'a> {
             }
         }
 
+        self.node_pos = node_pos;
+        self.save_source_pos(iseq);
         self.bytecode_generator.append_construct(args.len(), iseq);
 
         if !use_value {
@@ -1078,13 +2141,14 @@ impl<'a> CodeGenerator<'a> {
                 }
                 // { name: val }
                 Property(name, val) => {
-                    self.visit(&val, iseq, true)?;
+                    self.visit_named_evaluation(val, name, iseq)?;
                     self.bytecode_generator
                         .append_push_const(self.factory.string(name.clone()), iseq);
                 }
-                // { get name(){ node }}
-                // { set name(){ node }}
-                MethodDefinition(kind, name, node) => {
+                // { name(){ node } }
+                // { get name(){ node } }
+                // { set name(){ node } }
+                MethodDefinition(kind, key, node) => {
                     match kind {
                         Ordinary => {}
                         Set => {
@@ -1094,14 +2158,13 @@ impl<'a> CodeGenerator<'a> {
                             special_properties.insert(len - i - 1, Getter);
                         }
                     };
-                    self.visit(&node, iseq, true)?;
-                    self.bytecode_generator
-                        .append_push_const(self.factory.string(name.clone()), iseq);
+                    self.visit(node, iseq, true)?;
+                    self.visit(key, iseq, true)?;
                 }
                 // { ...node }
                 SpreadObject(node) => {
                     special_properties.insert(len - i - 1, Spread);
-                    self.visit(&node, iseq, true)?;
+                    self.visit(node, iseq, true)?;
                     self.bytecode_generator.append_push_null(iseq);
                 }
             }
@@ -1117,6 +2180,93 @@ impl<'a> CodeGenerator<'a> {
         Ok(())
     }
 
+    fn visit_template_literal(
+        &mut self,
+        strings: &[String],
+        substitutions: &[Node],
+        iseq: &mut ByteCode,
+        use_value: bool,
+    ) -> CodeGenResult {
+        self.bytecode_generator
+            .append_push_const(self.factory.string(strings[0].clone()), iseq);
+        for (substitution, string) in substitutions.iter().zip(strings[1..].iter()) {
+            self.visit(substitution, iseq, true)?;
+            self.bytecode_generator.append_to_string(iseq);
+            self.bytecode_generator.append_add(iseq);
+            if !string.is_empty() {
+                self.bytecode_generator
+                    .append_push_const(self.factory.string(string.clone()), iseq);
+                self.bytecode_generator.append_add(iseq);
+            }
+        }
+
+        if !use_value {
+            self.bytecode_generator.append_pop(iseq);
+        }
+
+        Ok(())
+    }
+
+    fn visit_tagged_template(
+        &mut self,
+        tag: &Node,
+        cooked: &[Option<String>],
+        raw: &[String],
+        substitutions: &[Node],
+        iseq: &mut ByteCode,
+        use_value: bool,
+    ) -> CodeGenResult {
+        for substitution in substitutions.iter().rev() {
+            self.visit(substitution, iseq, true)?
+        }
+
+        let template_object = self.create_template_object(cooked, raw);
+        self.bytecode_generator
+            .append_push_const(template_object, iseq);
+
+        self.call_with_pushed_args(tag, substitutions.len() as u32 + 1, iseq)?;
+
+        if !use_value {
+            self.bytecode_generator.append_pop(iseq);
+        }
+
+        Ok(())
+    }
+
+    /// https://tc39.github.io/ecma262/#sec-gettemplateobject
+    /// The template object is created at compile time and held in the constant table,
+    /// so every evaluation of the same tagged template gets the same object.
+    fn create_template_object(&mut self, cooked: &[Option<String>], raw: &[String]) -> Value {
+        let raw_elems = raw
+            .iter()
+            .map(|s| value::Property::new_data_simple(self.factory.string(s.clone())))
+            .collect();
+        let raw_object = self.factory.array(raw_elems);
+        raw_object.get_object_info().set_integrity_level(true);
+
+        // The cooked string of an invalid escape sequence is undefined.
+        let cooked_elems = cooked
+            .iter()
+            .map(|s| match s {
+                Some(s) => value::Property::new_data_simple(self.factory.string(s.clone())),
+                None => value::Property::new_data_simple(Value::undefined()),
+            })
+            .collect();
+        let template_object = self.factory.array(cooked_elems);
+        template_object.get_object_info().property.insert(
+            "raw".to_string(),
+            value::Property::new_data(value::DataProperty {
+                val: raw_object,
+                writable: false,
+                enumerable: false,
+                configurable: false,
+            }),
+        );
+        template_object.get_object_info().set_integrity_level(true);
+
+        template_object
+    }
+
     fn visit_array_literal(&mut self, elems: &Vec<Node>, iseq: &mut ByteCode) -> CodeGenResult {
         self.bytecode_generator.append_push_seperator(iseq);
         for elem in elems.iter().rev() {
@@ -1136,6 +2286,22 @@ impl<'a> CodeGenerator<'a> {
                 self.save_source_pos(iseq);
                 self.bytecode_generator.append_set_value(name, iseq);
             }
+            // TODO: super.name = val should call a setter found through super.
+            NodeBase::Member(ref parent, ref property) if parent.base == NodeBase::Super => {
+                self.is_super_in_static_method(parent.pos)?;
+                self.bytecode_generator.append_push_this(iseq);
+                let property = self.factory.string(property.clone());
+                self.bytecode_generator.append_push_const(property, iseq);
+                self.save_source_pos(iseq);
+                self.bytecode_generator.append_set_member(iseq);
+            }
+            NodeBase::Index(ref parent, ref index) if parent.base == NodeBase::Super => {
+                self.is_super_in_static_method(parent.pos)?;
+                self.bytecode_generator.append_push_this(iseq);
+                self.visit(index, iseq, true)?;
+                self.save_source_pos(iseq);
+                self.bytecode_generator.append_set_member(iseq);
+            }
             NodeBase::Member(ref parent, ref property) => {
                 self.visit(&*parent, iseq, true)?;
                 let property = self.factory.string(property.clone());
@@ -1149,6 +2315,14 @@ impl<'a> CodeGenerator<'a> {
                 self.save_source_pos(iseq);
                 self.bytecode_generator.append_set_member(iseq);
             }
+            NodeBase::ArrayPattern(ref elems) => self.assign_to_array_pattern(dst, elems, iseq)?,
+            NodeBase::ObjectPattern(ref properties) => {
+                self.assign_to_object_pattern(dst, properties, iseq)?
+            }
+            NodeBase::AssignmentPattern(ref target, ref default) => {
+                self.append_default_value(target, default, iseq)?;
+                self.assign_stack_top_to(target, iseq)?;
+            }
             _ => {
                 return Err(Error::new_general_error(
                     "Reference error: Invalid left-hand side in assignment.".to_string(),
@@ -1160,10 +2334,187 @@ impl<'a> CodeGenerator<'a> {
         Ok(())
     }
 
+    /// https://tc39.github.io/ecma262/#sec-runtime-semantics-iteratordestructuringassignmentevaluation
+    /// The iterator stays on the stack top while the elements are assigned.
+    fn assign_to_array_pattern(
+        &mut self,
+        pattern: &Node,
+        elems: &[Node],
+        iseq: &mut ByteCode,
+    ) -> CodeGenResult {
+        self.node_pos = pattern.pos;
+        self.save_source_pos(iseq);
+        self.bytecode_generator.append_get_iterator(iseq);
+        let enter_iter_pos = iseq.len();
+        self.bytecode_generator.append_enter_iter(iseq);
+        let start = iseq.len();
+
+        for elem in elems {
+            self.node_pos = elem.pos;
+            self.save_source_pos(iseq);
+            match elem.base {
+                NodeBase::Nope => {
+                    self.bytecode_generator.append_iter_value(iseq);
+                    self.bytecode_generator.append_pop(iseq);
+                }
+                NodeBase::Spread(ref target) => {
+                    self.bytecode_generator.append_iter_rest(iseq);
+                    self.assign_stack_top_to(target, iseq)?;
+                }
+                _ => {
+                    self.bytecode_generator.append_iter_value(iseq);
+                    self.assign_stack_top_to(elem, iseq)?;
+                }
+            }
+        }
+
+        let jmp_pos = iseq.len();
+        self.bytecode_generator.append_jmp(0, iseq);
+
+        // An exception thrown while assigning the elements closes the iterator and is rethrown.
+        let iter_close_throw_pos = iseq.len();
+        self.bytecode_generator
+            .append_iter_close_throw(enter_iter_pos, iseq);
+        self.current_function().exception_table.push(Exception {
+            start,
+            end: iter_close_throw_pos,
+            dst_kind: DestinationKind::Catch,
+        });
+
+        let end = iseq.len();
+        self.bytecode_generator.replace_int32(
+            (end - jmp_pos) as i32 - 5,
+            &mut iseq[jmp_pos + 1..jmp_pos + 5],
+        );
+
+        // Close the iterator unless it is done.
+        self.bytecode_generator
+            .append_iter_close(enter_iter_pos, iseq);
+
+        Ok(())
+    }
+
+    /// https://tc39.github.io/ecma262/#sec-runtime-semantics-propertydestructuringassignmentevaluation
+    /// The object stays on the stack top while the properties are assigned.
+    fn assign_to_object_pattern(
+        &mut self,
+        pattern: &Node,
+        properties: &[PropertyPattern],
+        iseq: &mut ByteCode,
+    ) -> CodeGenResult {
+        let has_rest = properties
+            .iter()
+            .any(|property| matches!(property, PropertyPattern::Rest(_)));
+        // Keys the rest element excludes.
+        let mut excluded_keys = vec![];
+
+        for property in properties {
+            match property {
+                PropertyPattern::Property(key, target) => {
+                    self.bytecode_generator.append_double(iseq);
+                    self.visit(key, iseq, true)?;
+                    if has_rest {
+                        match key.base {
+                            NodeBase::String(_) => excluded_keys.push(key.clone()),
+                            // A computed key is evaluated once, and kept in a hidden variable.
+                            _ => {
+                                let name = format!("%key{}", key.pos);
+                                self.bytecode_generator.append_double(iseq);
+                                self.bytecode_generator.append_set_value(&name, iseq);
+                                self.current_function().var_names.push(name.clone());
+                                excluded_keys.push(Node::new(NodeBase::Identifier(name), key.pos));
+                            }
+                        }
+                    }
+                    self.node_pos = pattern.pos;
+                    self.save_source_pos(iseq);
+                    self.bytecode_generator.append_get_member(iseq);
+                    self.assign_stack_top_to(target, iseq)?;
+                }
+                PropertyPattern::Rest(target) => {
+                    self.bytecode_generator.append_double(iseq);
+                    for key in &excluded_keys {
+                        self.visit(key, iseq, true)?;
+                    }
+                    self.node_pos = pattern.pos;
+                    self.save_source_pos(iseq);
+                    self.bytecode_generator
+                        .append_copy_data_properties(excluded_keys.len(), iseq);
+                    self.assign_stack_top_to(target, iseq)?;
+                }
+            }
+        }
+
+        // Pop the object.
+        self.bytecode_generator.append_pop(iseq);
+
+        Ok(())
+    }
+
+    /// Replaces the stack top with ``default`` if it is undefined.
+    /// Replaces undefined on the stack top with ``default``, which is named after ``target``
+    /// if it is an anonymous function.
+    fn append_default_value(
+        &mut self,
+        target: &Node,
+        default: &Node,
+        iseq: &mut ByteCode,
+    ) -> CodeGenResult {
+        self.bytecode_generator.append_double(iseq);
+        self.bytecode_generator.append_push_undefined(iseq);
+        self.bytecode_generator.append_seq(iseq);
+        let jmp_if_false_pos = iseq.len() as isize;
+        self.bytecode_generator.append_jmp_if_false(0, iseq);
+
+        self.bytecode_generator.append_pop(iseq);
+        match target.base {
+            NodeBase::Identifier(ref name) => self.visit_named_evaluation(default, name, iseq)?,
+            _ => self.visit(default, iseq, true)?,
+        }
+
+        let end = iseq.len() as isize;
+        self.bytecode_generator.replace_int32(
+            (end - jmp_if_false_pos) as i32 - 5,
+            &mut iseq[jmp_if_false_pos as usize + 1..jmp_if_false_pos as usize + 5],
+        );
+
+        Ok(())
+    }
+
     fn current_function(&mut self) -> &mut FunctionInfo {
         self.function_stack.last_mut().unwrap()
     }
 
+    /// A property found through ``super`` is got with the current this as the receiver.
+    fn append_get_member_of(&mut self, parent: &Node, iseq: &mut ByteCode) {
+        if parent.base == NodeBase::Super {
+            self.bytecode_generator.append_get_super_member(iseq);
+        } else {
+            self.bytecode_generator.append_get_member(iseq);
+        }
+    }
+
+    /// Returns true if ``super`` refers to the home object of a static method.
+    /// Arrow functions use ``super`` of the enclosing function.
+    fn is_super_in_static_method(&self, pos: usize) -> Result<bool, Error> {
+        let kind = self
+            .function_stack
+            .iter()
+            .rev()
+            .map(|info| info.kind)
+            .find(|kind| *kind != FunctionKind::Arrow);
+        match kind {
+            Some(FunctionKind::StaticMethod) => Ok(true),
+            Some(FunctionKind::Method)
+            | Some(FunctionKind::BaseConstructor)
+            | Some(FunctionKind::DerivedConstructor) => Ok(false),
+            _ => Err(Error::new_general_error(
+                "'super' keyword unexpected here".to_string(),
+                pos,
+            )),
+        }
+    }
+
     /// Save the position in bytecode corresponds to the current node.
     fn save_source_pos(&mut self, iseq: &mut ByteCode) {
         let node_pos = self.node_pos;
@@ -1192,18 +2543,38 @@ impl<'a> CodeGenerator<'a> {
         }
     }
 
-    fn unwind_loop(&mut self, iseq: &mut ByteCode) {
-        let mut count = 0;
-        for level in self.current_function().level.iter().rev() {
-            match level {
-                &Level::Loop { .. } => break,
-                &Level::Block { .. } => count += 1,
+    /// Leave all the levels above ``level_idx`` before jumping out of them.
+    /// Blocks pop their environments, for-in loops pop their iterators, for-of loops close them,
+    /// try/catch clauses run the finally clause as a subroutine, and finally clauses drop the
+    /// completion to resume with.
+    fn unwind_to(&mut self, level_idx: usize, iseq: &mut ByteCode) {
+        let level_len = self.current_function().level.len();
+        for i in (level_idx + 1..level_len).rev() {
+            match self.current_function().level[i] {
+                Level::Block { .. } => self.bytecode_generator.append_pop_env(iseq),
+                Level::ForInOf {
+                    enter_iter_pos: None,
+                } => self.bytecode_generator.append_pop(iseq),
+                Level::ForInOf {
+                    enter_iter_pos: Some(enter_iter_pos),
+                } => self
+                    .bytecode_generator
+                    .append_iter_close(enter_iter_pos, iseq),
+                Level::TryOrCatch { .. } => {
+                    let jmp_sub_instr_pos = iseq.len();
+                    self.current_function().level[i]
+                        .as_try_or_catch_mut()
+                        .push(jmp_sub_instr_pos);
+                    self.bytecode_generator.append_jmp_sub(0, iseq);
+                }
+                Level::Finally => self.bytecode_generator.append_drop_sub(iseq),
                 _ => {}
             }
         }
-        for _ in 0..count {
-            self.bytecode_generator.append_pop_env(iseq);
-        }
+    }
+
+    fn undefined_label_error(&self, name: &str) -> Error {
+        Error::new_general_error(format!("Undefined label '{}'", name), self.node_pos)
     }
 }
 
@@ -1241,6 +2612,9 @@ impl FunctionInfo {
             exception_table: vec![],
             to_source_pos: ToSourcePos::new(module_func_id),
             module_func_id,
+            kind: FunctionKind::Normal,
+            uses_arguments: false,
+            is_strict: false,
         }
     }
 
@@ -1281,15 +2655,37 @@ impl FunctionInfo {
         })
     }
 
-    pub fn get_last_loop(&mut self) -> &mut Level {
+    /// Returns the index of the innermost loop level.
+    pub fn find_last_loop(&self) -> Option<usize> {
         self.level
-            .iter_mut()
-            .rev()
-            .find(|level| match level {
-                &Level::Loop { .. } => true,
-                _ => false,
-            })
-            .unwrap()
+            .iter()
+            .rposition(|level| matches!(level, Level::Loop { .. }))
+    }
+
+    /// Returns the index of the innermost level an unlabelled ``break`` jumps out of.
+    pub fn find_last_breakable(&self) -> Option<usize> {
+        self.level
+            .iter()
+            .rposition(|level| matches!(level, Level::Loop { .. } | Level::Switch { .. }))
+    }
+
+    /// Returns the index of the label level named ``name``.
+    pub fn find_label(&self, name: &str) -> Option<usize> {
+        self.level.iter().rposition(|level| match level {
+            Level::Label { name: name_, .. } => name_ == name,
+            _ => false,
+        })
+    }
+
+    /// Returns the index of the loop level labelled by the label level at ``label_idx``.
+    pub fn find_labelled_loop(&self, label_idx: usize) -> Option<usize> {
+        if !matches!(self.level[label_idx], Level::Label { is_loop: true, .. }) {
+            return None;
+        }
+        self.level[label_idx..]
+            .iter()
+            .position(|level| matches!(level, Level::Loop { .. }))
+            .map(|i| label_idx + i)
     }
 
     pub fn get_last_block(&mut self) -> Option<&mut Level> {
@@ -1351,6 +2747,47 @@ impl Level {
         }
     }
 
+    pub fn as_break_jmp_instr_pos_mut(&mut self) -> &mut Vec<usize> {
+        match self {
+            Level::Loop {
+                ref mut break_jmp_instr_pos,
+                ..
+            }
+            | Level::Switch {
+                ref mut break_jmp_instr_pos,
+            }
+            | Level::Label {
+                ref mut break_jmp_instr_pos,
+                ..
+            } => break_jmp_instr_pos,
+            _ => panic!(),
+        }
+    }
+
+    pub fn replace_break(
+        self,
+        bytecode_generator: &mut ByteCodeGenerator,
+        iseq: &mut ByteCode,
+        break_dst: isize,
+    ) {
+        let break_jmp_instr_pos = match self {
+            Level::Switch {
+                break_jmp_instr_pos,
+            }
+            | Level::Label {
+                break_jmp_instr_pos,
+                ..
+            } => break_jmp_instr_pos,
+            _ => panic!(),
+        };
+        for instr_pos in break_jmp_instr_pos {
+            bytecode_generator.replace_int32(
+                (break_dst - instr_pos as isize) as i32 - 5,
+                &mut iseq[instr_pos + 1..instr_pos + 5],
+            );
+        }
+    }
+
     pub fn replace_break_and_continue(
         self,
         bytecode_generator: &mut ByteCodeGenerator,
@@ -1402,7 +2839,7 @@ impl Level {
         for instr_pos in finally_jmp_instr_pos {
             has_return_from_try_or_catch |= iseq[instr_pos] == VMInst::RETURN_TRY;
             assert!(match iseq[instr_pos] {
-                VMInst::RETURN_TRY | VMInst::JMP => true,
+                VMInst::RETURN_TRY | VMInst::JMP | VMInst::JMP_SUB => true,
                 _ => false,
             });
             bytecode_generator.replace_int32(
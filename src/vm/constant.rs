@@ -1,3 +1,4 @@
+use crate::node::MethodDefinitionKind;
 use crate::vm::jsvalue::value::Value;
 use rustc_hash::FxHashMap;
 
@@ -7,6 +8,7 @@ pub enum Constant {
     Value(Value),
     LexicalEnvironmentInfo { names: Vec<String> },
     ObjectLiteralInfo(SpecialProperties),
+    ClassInfo(Vec<ClassMethodInfo>),
 }
 
 pub type SpecialProperties = FxHashMap<usize, SpecialPropertyKind>;
@@ -18,6 +20,13 @@ pub enum SpecialPropertyKind {
     Spread,
 }
 
+/// Kind of each class method, in the order they are popped from the stack.
+#[derive(Clone, Debug, PartialEq, Copy)]
+pub struct ClassMethodInfo {
+    pub kind: MethodDefinitionKind,
+    pub is_static: bool,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct ConstantTable {
     pub table: Vec<Constant>,
@@ -72,6 +81,12 @@ impl ConstantTable {
         id
     }
 
+    pub fn add_class_info(&mut self, methods: Vec<ClassMethodInfo>) -> usize {
+        let id = self.table.len();
+        self.table.push(Constant::ClassInfo(methods));
+        id
+    }
+
     pub fn add_lex_env_info(&mut self, names: Vec<String>) -> usize {
         let id = self.table.len();
         self.table.push(Constant::LexicalEnvironmentInfo { names });
@@ -122,4 +137,11 @@ impl Constant {
             _ => panic!(),
         }
     }
+
+    pub fn as_class_info(&self) -> &Vec<ClassMethodInfo> {
+        match self {
+            Constant::ClassInfo(methods) => methods,
+            _ => panic!(),
+        }
+    }
 }
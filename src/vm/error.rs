@@ -1,6 +1,6 @@
 use crate::vm::exec_context::ExecContext;
 use crate::vm::jsvalue::value::Value;
-use crate::vm::vm::{Factory, FunctionId};
+use crate::vm::vm::{FunctionId, VM};
 use ansi_term::Colour;
 
 #[derive(Clone, PartialEq, Debug)]
@@ -19,7 +19,9 @@ pub struct RuntimeError {
 pub enum ErrorKind {
     Unknown,
     Type(String),
+    Range(String),
     Reference(String),
+    Syntax(String),
     General(String),
     Exception(Value),
     Unimplemented,
@@ -48,10 +50,22 @@ impl RuntimeError {
         RuntimeError::default(ErrorKind::Type(msg.into()))
     }
 
+    pub fn range(msg: impl Into<String>) -> RuntimeError {
+        RuntimeError::default(ErrorKind::Range(msg.into()))
+    }
+
     pub fn reference(msg: impl Into<String>) -> RuntimeError {
         RuntimeError::default(ErrorKind::Reference(msg.into()))
     }
 
+    pub fn syntax(msg: impl Into<String>) -> RuntimeError {
+        RuntimeError::default(ErrorKind::Syntax(msg.into()))
+    }
+
+    pub fn general(msg: impl Into<String>) -> RuntimeError {
+        RuntimeError::default(ErrorKind::General(msg.into()))
+    }
+
     pub fn error_add_info(mut self, context: &ExecContext) -> RuntimeError {
         self.func_id = context.func_ref.func_id;
         self.module_func_id = context.func_ref.module_func_id;
@@ -60,15 +74,23 @@ impl RuntimeError {
     }
 
     /// convert RuntimeError -> Value
-    pub fn to_value(self, factory: &mut Factory) -> Value {
-        match self.kind {
-            ErrorKind::Exception(v) => v,
-            ErrorKind::Type(s) => factory.error(format!("Type error: {}", s)),
-            ErrorKind::General(s) => factory.error(format!("Error: {}", s)),
-            ErrorKind::Reference(s) => factory.error(format!("Reference error: {}", s)),
-            ErrorKind::Unimplemented => factory.error("Unimplemented"),
-            ErrorKind::Unknown => factory.error("Unknown"),
-        }
+    /// Each kind of error becomes an instance of the corresponding Error constructor, which has
+    /// the stack trace of the running contexts.
+    pub fn to_value(self, vm: &mut VM) -> Value {
+        let prototypes = &vm.factory.object_prototypes;
+        let (prototype, message) = match self.kind {
+            ErrorKind::Exception(v) => return v,
+            ErrorKind::Type(s) => (prototypes.type_error, s),
+            ErrorKind::Range(s) => (prototypes.range_error, s),
+            ErrorKind::Reference(s) => (prototypes.reference_error, s),
+            ErrorKind::Syntax(s) => (prototypes.syntax_error, s),
+            ErrorKind::General(s) => (prototypes.error, s),
+            ErrorKind::Unimplemented => (prototypes.error, "Unimplemented".to_string()),
+            ErrorKind::Unknown => (prototypes.error, "Unknown".to_string()),
+        };
+        let error = vm.factory.error_object(prototype, Some(message));
+        vm.capture_stack_trace(error);
+        error
     }
 }
 
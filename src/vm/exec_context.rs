@@ -5,6 +5,7 @@ use crate::vm::jsvalue::function::{FuncInfoRef, UserFunctionInfo};
 use crate::vm::error::ErrorKind;
 use crate::vm::error::RuntimeError;
 //use crate::vm::jsvalue::function::Exception;
+use crate::vm::jsvalue::object::{DataProperty, Property};
 use crate::vm::jsvalue::value::{BoxedValue, Value};
 use crate::vm::vm::{CallMode, Factory, VMResult};
 use rustc_hash::FxHashMap;
@@ -35,6 +36,35 @@ pub struct ExecContext {
     pub variable_environment: LexicalEnvironmentRef,
     pub lexical_environment: LexicalEnvironmentRef,
     pub saved_lexical_environment: Vec<LexicalEnvironmentRef>,
+    /// Where to continue after each finally block being executed. Kept in the context so that
+    /// a function suspended in a finally block can be resumed.
+    pub subroutine_stack: Vec<SubroutineKind>,
+    /// The iterators of the running for-of loops and array destructurings, which are closed
+    /// when they are left before the iterator is done.
+    pub open_iterators: Vec<OpenIterator>,
+}
+
+/// An iterator on the stack to be closed.
+/// https://tc39.github.io/ecma262/#sec-iteratorclose
+#[derive(Debug, Clone)]
+pub struct OpenIterator {
+    /// The position of the ENTER_ITER instruction, which identifies the iterator.
+    pub enter_iter_pos: usize,
+    /// The index of the iterator in the stack.
+    pub stack_pos: usize,
+    /// \[\[Done\]\]: the iterator is exhausted or has thrown, and must not be closed.
+    pub done: bool,
+}
+
+/// How to leave a finally block.
+#[derive(Debug, Clone)]
+pub enum SubroutineKind {
+    /// Jump back to the position.
+    Ordinary(usize),
+    /// Rethrow the exception on the stack.
+    Throw,
+    /// Return the value on the stack.
+    Return,
 }
 
 #[derive(Debug, Clone)]
@@ -48,13 +78,18 @@ pub enum EnvironmentRecord {
     Declarative(FxHashMap<String, Value>),
     Object(Value),
     Global(Value),
+    /// https://tc39.github.io/ecma262/#sec-module-environment-records
     Module {
         this: Value,
         record: FxHashMap<String, Value>,
-        // TODO: https://www.ecma-international.org/ecma-262/6.0/#sec-module-environment-records
+        /// Imported bindings, which refer to the bindings of other module environments.
+        imports: FxHashMap<String, (LexicalEnvironmentRef, String)>,
     },
     Function {
+        /// Value::empty() in an arrow function, which has no this binding.
         this: Value,
+        /// Undefined unless calling JS function as a constructor.
+        new_target: Value,
         record: FxHashMap<String, Value>,
         // TODO: https://www.ecma-international.org/ecma-262/6.0/#sec-function-environment-records
     },
@@ -79,6 +114,8 @@ impl ExecContext {
             variable_environment: var_env,
             lexical_environment: lex_env,
             saved_lexical_environment: vec![],
+            subroutine_stack: vec![],
+            open_iterators: vec![],
         }
     }
     pub fn empty() -> Self {
@@ -93,6 +130,8 @@ impl ExecContext {
             variable_environment: LexicalEnvironmentRef::new_null(),
             lexical_environment: LexicalEnvironmentRef::new_null(),
             saved_lexical_environment: vec![],
+            subroutine_stack: vec![],
+            open_iterators: vec![],
         }
     }
 
@@ -148,10 +187,18 @@ impl ExecContext {
         RuntimeError::new(ErrorKind::Type(msg.into()), self)
     }
 
+    pub fn error_range(&self, msg: impl Into<String>) -> RuntimeError {
+        RuntimeError::new(ErrorKind::Range(msg.into()), self)
+    }
+
     pub fn error_reference(&self, msg: impl Into<String>) -> RuntimeError {
         RuntimeError::new(ErrorKind::Reference(msg.into()), self)
     }
 
+    pub fn error_syntax(&self, msg: impl Into<String>) -> RuntimeError {
+        RuntimeError::new(ErrorKind::Syntax(msg.into()), self)
+    }
+
     pub fn error_exception(&self, val: Value) -> RuntimeError {
         RuntimeError::new(ErrorKind::Exception(val), self)
     }
@@ -177,13 +224,22 @@ impl LexicalEnvironment {
     }
 
     pub fn new_global_initialized(factory: &mut Factory) -> Self {
-        use crate::builtin::{deep_seq, parse_float, require};
+        use crate::builtin::{
+            deep_seq, is_finite, is_nan, parse_float, parse_int, require, require_resolve,
+        };
         use crate::builtins;
 
-        let log = factory.builtin_function("log", builtins::console::console_log);
-        let parse_float = factory.builtin_function("parseFloat", parse_float);
-        let require = factory.builtin_function("require", require);
-        let deep_seq = factory.builtin_function("__assert_deep_seq", deep_seq);
+        let log = factory.builtin_function("log", builtins::console::console_log, 0);
+        let parse_float = factory.builtin_function("parseFloat", parse_float, 1);
+        let parse_int = factory.builtin_function("parseInt", parse_int, 2);
+        let is_nan = factory.builtin_function("isNaN", is_nan, 1);
+        let is_finite = factory.builtin_function("isFinite", is_finite, 1);
+        let require = factory.builtin_function("require", require, 1);
+        let require_resolve = factory.builtin_function("resolve", require_resolve, 1);
+        let require_cache = make_normal_object!(factory);
+        require.set_property("resolve", require_resolve);
+        require.set_property("cache", require_cache);
+        let deep_seq = factory.builtin_function("__assert_deep_seq", deep_seq, 2);
         let console = make_normal_object!(factory,
             log => true, false, true: log
         );
@@ -192,7 +248,24 @@ impl LexicalEnvironment {
         let array_constructor = builtins::array::array(factory);
         let symbol_constructor = builtins::symbol::symbol(factory);
         let error_constructor = builtins::error::error(factory);
+        let [eval_error_constructor, range_error_constructor, reference_error_constructor, syntax_error_constructor, type_error_constructor, uri_error_constructor] =
+            builtins::error::native_errors(factory, error_constructor);
+        let aggregate_error_constructor =
+            builtins::error::aggregate_error(factory, error_constructor);
         let math_object = builtins::math::math(factory);
+        let json_object = builtins::json::json(factory);
+        let regexp_constructor = builtins::regexp::regexp(factory);
+        let promise_constructor = builtins::promise::promise(factory);
+        let date_constructor = builtins::date::date(factory);
+        let number_constructor = builtins::number::number(factory);
+        let string_constructor = builtins::string::string(factory);
+        let map_constructor = builtins::map::map(factory);
+        let set_constructor = builtins::set::set(factory);
+        let weak_map_constructor = builtins::map::weak_map(factory);
+        let weak_set_constructor = builtins::set::weak_set(factory);
+        // Number.parseFloat and Number.parseInt are the same functions as the global ones.
+        number_constructor.set_property("parseFloat", parse_float);
+        number_constructor.set_property("parseInt", parse_int);
         LexicalEnvironment {
             record: EnvironmentRecord::Global(make_normal_object!(
                 factory,
@@ -202,13 +275,33 @@ impl LexicalEnvironment {
                 require    => true, false, true: require,
                 __assert_deep_seq    => true, false, true: deep_seq,
                 parseFloat => true, false, true: parse_float,
+                parseInt   => true, false, true: parse_int,
+                isNaN      => true, false, true: is_nan,
+                isFinite   => true, false, true: is_finite,
                 console    => true, false, true: console,
                 Object     => true, false, true: object_constructor,
                 Function   => true, false, true: function_constructor,
                 Array      => true, false, true: array_constructor,
                 Symbol     => true, false, true: symbol_constructor,
                 Error      => true, false, true: error_constructor,
-                Math       => true, false, true: math_object
+                EvalError  => true, false, true: eval_error_constructor,
+                RangeError => true, false, true: range_error_constructor,
+                ReferenceError => true, false, true: reference_error_constructor,
+                SyntaxError => true, false, true: syntax_error_constructor,
+                TypeError  => true, false, true: type_error_constructor,
+                URIError   => true, false, true: uri_error_constructor,
+                AggregateError => true, false, true: aggregate_error_constructor,
+                Math       => true, false, true: math_object,
+                JSON       => true, false, true: json_object,
+                RegExp     => true, false, true: regexp_constructor,
+                Promise    => true, false, true: promise_constructor,
+                Date       => true, false, true: date_constructor,
+                Number     => true, false, true: number_constructor,
+                String     => true, false, true: string_constructor,
+                Map        => true, false, true: map_constructor,
+                Set        => true, false, true: set_constructor,
+                WeakMap    => true, false, true: weak_map_constructor,
+                WeakSet    => true, false, true: weak_set_constructor
             )),
             outer: None,
         }
@@ -217,6 +310,10 @@ impl LexicalEnvironment {
     pub fn get_value(&self, name: impl Into<String>) -> Result<Value, RuntimeError> {
         let name = name.into();
         match self.record {
+            EnvironmentRecord::Module { ref imports, .. } if imports.contains_key(&name) => {
+                let (env, ref binding_name) = imports[&name];
+                return env.get_value(binding_name.as_str());
+            }
             EnvironmentRecord::Function { ref record, .. }
             | EnvironmentRecord::Module { ref record, .. }
             | EnvironmentRecord::Declarative(ref record) => match record.get(&name) {
@@ -253,8 +350,47 @@ impl LexicalEnvironment {
         }
     }
 
+    /// Only a property of an object environment can be deleted. Deleting an unresolvable name
+    /// succeeds.
+    /// https://tc39.github.io/ecma262/#sec-delete-operator-runtime-semantics-evaluation
+    pub fn delete_binding(&mut self, name: &str) -> bool {
+        match self.record {
+            EnvironmentRecord::Module { ref imports, .. } if imports.contains_key(name) => {
+                return false;
+            }
+            EnvironmentRecord::Function { ref record, .. }
+            | EnvironmentRecord::Module { ref record, .. }
+            | EnvironmentRecord::Declarative(ref record) => {
+                if record.contains_key(name) {
+                    return false;
+                }
+            }
+            EnvironmentRecord::Global(obj) | EnvironmentRecord::Object(obj) => {
+                if obj.has_own_property(name) {
+                    return obj.get_object_info().delete_property(name);
+                }
+            }
+        };
+
+        match self.outer {
+            Some(mut outer) => outer.delete_binding(name),
+            None => true,
+        }
+    }
+
     pub fn set_value(&mut self, name: String, val: Value) -> VMResult {
+        self.set_mutable_binding(name, val, false)
+    }
+
+    /// In strict mode code, assignment to an undeclared identifier or a read-only global
+    /// property throws instead of creating or ignoring the property.
+    /// https://tc39.github.io/ecma262/#sec-putvalue
+    pub fn set_mutable_binding(&mut self, name: String, val: Value, is_strict: bool) -> VMResult {
         match self.record {
+            // Imported bindings are immutable.
+            EnvironmentRecord::Module { ref imports, .. } if imports.contains_key(&name) => {
+                return Err(RuntimeError::typeerr("Assignment to constant variable."));
+            }
             EnvironmentRecord::Function { ref mut record, .. }
             | EnvironmentRecord::Module { ref mut record, .. }
             | EnvironmentRecord::Declarative(ref mut record) => match record.get_mut(&name) {
@@ -264,6 +400,23 @@ impl LexicalEnvironment {
                 }
                 None => {}
             },
+            EnvironmentRecord::Global(obj) | EnvironmentRecord::Object(obj) if is_strict => {
+                match obj.get_object_properties().unwrap().get(&name) {
+                    Some(Property::Data(DataProperty {
+                        writable: false, ..
+                    })) => {
+                        return Err(RuntimeError::typeerr(format!(
+                            "Cannot assign to read only property '{}' of object",
+                            name
+                        )));
+                    }
+                    Some(_) => {
+                        obj.set_property(name, val);
+                        return Ok(());
+                    }
+                    None => {}
+                }
+            }
             EnvironmentRecord::Global(obj) | EnvironmentRecord::Object(obj) => {
                 obj.set_property(name, val);
                 return Ok(());
@@ -271,7 +424,12 @@ impl LexicalEnvironment {
         };
 
         if let Some(mut outer) = self.outer {
-            outer.set_value(name, val)
+            outer.set_mutable_binding(name, val, is_strict)
+        } else if is_strict {
+            Err(RuntimeError::reference(format!(
+                "'{}' is not defined",
+                name
+            )))
         } else {
             Err(RuntimeError::reference(format!(
                 "Assignment to undeclared identifier '{}'",
@@ -301,9 +459,27 @@ impl LexicalEnvironment {
         }
     }
 
+    /// https://tc39.github.io/ecma262/#sec-bindthisvalue
+    /// An arrow function binds this of the function it is in, where it calls super().
+    pub fn bind_this_value(&mut self, val: Value) -> VMResult {
+        match self.record {
+            EnvironmentRecord::Function { ref mut this, .. } if *this != Value::empty() => {
+                if *this != Value::uninitialized() {
+                    return Err(RuntimeError::reference(
+                        "Super constructor may only be called once",
+                    ));
+                }
+                *this = val;
+                Ok(())
+            }
+            _ => self.outer.unwrap().bind_this_value(val),
+        }
+    }
+
     pub fn get_this_binding(&self) -> Value {
         match self.record {
-            EnvironmentRecord::Function { this, .. } => this,
+            EnvironmentRecord::Function { this, .. } if this != Value::empty() => this,
+            EnvironmentRecord::Module { this, .. } => this,
             EnvironmentRecord::Global(obj) => obj,
             _ => {
                 if let Some(outer) = self.outer {
@@ -314,6 +490,31 @@ impl LexicalEnvironment {
             }
         }
     }
+
+    /// https://tc39.github.io/ecma262/#sec-getnewtarget
+    pub fn get_new_target(&self) -> Value {
+        match self.record {
+            EnvironmentRecord::Function {
+                this, new_target, ..
+            } if this != Value::empty() => new_target,
+            _ => {
+                if let Some(outer) = self.outer {
+                    outer.get_new_target()
+                } else {
+                    Value::undefined()
+                }
+            }
+        }
+    }
+
+    pub fn set_new_target(&mut self, val: Value) {
+        if let EnvironmentRecord::Function {
+            ref mut new_target, ..
+        } = self.record
+        {
+            *new_target = val;
+        }
+    }
 }
 
 impl LexicalEnvironmentRef {
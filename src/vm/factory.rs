@@ -1,12 +1,21 @@
 use crate::builtin::BuiltinFuncTy;
 use crate::gc;
 use crate::vm::{
+    jsvalue::collection::MapCursor,
     jsvalue::prototype::ObjectPrototypes,
+    jsvalue::symbol::WellKnownSymbols,
     jsvalue::value::{
-        ArrayObjectInfo, ErrorObjectInfo, FuncInfoRef, FunctionObjectInfo, FunctionObjectKind,
-        ObjectInfo, ObjectKind, Property, SymbolInfo, UserFunctionInfo, Value,
+        ArrayIteratorInfo, ArrayObjectInfo, AsyncFunctionInfo, DataProperty, ErrorObjectInfo,
+        ForInIteratorInfo, FuncInfoRef, FunctionObjectInfo, FunctionObjectKind, GeneratorInfo,
+        GeneratorState, IterationKind, MapIteratorInfo, ModuleNamespaceInfo, ObjectInfo,
+        ObjectKind, PromiseInfo, Property, PropertyMap, RegExpInfo, RegExpStringIteratorInfo,
+        ResolvedBinding, SetIteratorInfo, StringInfo, StringIteratorInfo, SymbolInfo,
+        UserFunctionInfo, Value,
+    },
+    vm::{
+        EnvironmentRecord, ExecContext, FunctionParameter, LexicalEnvironment,
+        LexicalEnvironmentRef,
     },
-    vm::{LexicalEnvironmentRef, LexicalEnvironment, EnvironmentRecord, FunctionParameter},
 };
 use rustc_hash::FxHashMap;
 
@@ -36,8 +45,12 @@ impl std::fmt::Debug for FunctionId {
 pub struct Factory {
     pub memory_allocator: gc::MemoryAllocator,
     pub object_prototypes: ObjectPrototypes,
+    pub symbols: WellKnownSymbols,
     pub func_refs: Vec<Option<FuncInfoRef>>,
     pub next_func_id: usize,
+    /// Symbols by their id, which is how symbol-keyed properties refer to them. The symbols are
+    /// never collected so that Object.getOwnPropertySymbols() can return them.
+    pub symbol_registry: FxHashMap<usize, Value>,
 }
 
 impl Factory {
@@ -45,9 +58,12 @@ impl Factory {
         let mut factory = Factory {
             memory_allocator,
             object_prototypes,
+            symbols: WellKnownSymbols::dummy(),
             func_refs: vec![None; 30],
             next_func_id: 1,
+            symbol_registry: FxHashMap::default(),
         };
+        factory.symbols = WellKnownSymbols::new(&mut factory);
         let func_ref =
             factory.alloc_user_func_info(FunctionId::default(), UserFunctionInfo::default());
         factory.func_refs[0] = Some(func_ref);
@@ -122,16 +138,22 @@ impl Factory {
 impl Factory {
     /// Generate Value for a string.
     pub fn string(&mut self, body: impl Into<String>) -> Value {
-        Value::String(self.alloc(std::ffi::CString::new(body.into()).unwrap()))
+        Value::String(self.alloc(StringInfo::new(body.into())))
+    }
+
+    /// A string of UTF-16 code units, which may contain lone surrogates.
+    pub fn utf16_string(&mut self, units: Vec<u16>) -> Value {
+        Value::String(self.alloc(StringInfo::from_utf16(units)))
     }
 
     /// Generate Value for an object.
-    pub fn object(&mut self, property: FxHashMap<String, Property>) -> Value {
+    pub fn object(&mut self, property: PropertyMap) -> Value {
         Value::Object(self.alloc(ObjectInfo {
             kind: ObjectKind::Ordinary,
             prototype: self.object_prototypes.object,
             property,
             sym_property: FxHashMap::default(),
+            extensible: true,
         }))
     }
 
@@ -142,12 +164,23 @@ impl Factory {
         outer_env: impl Into<Option<LexicalEnvironmentRef>>,
     ) -> Value {
         let name_prop = self.string(info.func_name.clone().unwrap_or("".to_string()));
-        let prototype = self.object(FxHashMap::default());
+        // The prototype of generator objects has no ``constructor``.
+        let prototype = if info.is_generator {
+            Value::Object(self.alloc(ObjectInfo {
+                kind: ObjectKind::Ordinary,
+                prototype: self.object_prototypes.generator,
+                property: PropertyMap::default(),
+                sym_property: FxHashMap::default(),
+                extensible: true,
+            }))
+        } else {
+            self.object(PropertyMap::default())
+        };
 
         let f = Value::Object(self.alloc(ObjectInfo {
             prototype: self.object_prototypes.function,
             property: make_property_map!(
-                length    => false, false, true : Value::Number(info.params.len() as f64), /* TODO: rest param */
+                length    => false, false, true : Value::Number(info.length as f64),
                 name      => false, false, true : name_prop,
                 prototype => true , false, false: prototype
             ),
@@ -156,21 +189,23 @@ impl Factory {
                 kind: FunctionObjectKind::User{info, outer_env: outer_env.into()},
             }),
             sym_property: FxHashMap::default(),
+            extensible: true,
         }));
 
-        f.get_property("prototype")
-            .get_object_info()
-            .property
-            .insert("constructor".to_string(), Property::new_data_simple(f));
+        if !info.is_generator {
+            f.get_property("prototype").set_constructor(f);
+        }
 
         f
     }
 
-    /// Generate Value for a built-in (native) function.
+    /// Generate Value for a built-in (native) function. ``length`` is the number of the
+    /// parameters which are not optional.
     pub fn builtin_function(
         &mut self,
         name: impl Into<String>,
         func: crate::builtin::BuiltinFuncTy,
+        length: usize,
     ) -> Value {
         let name: String = name.into();
         let name_prop = self.string(name.clone());
@@ -181,43 +216,350 @@ impl Factory {
             }),
             prototype: self.object_prototypes.function,
             property: make_property_map!(
-                length => false, false, true : Value::Number(0.0),
+                length => false, false, true : Value::Number(length as f64),
                 name   => false, false, true : name_prop
             ),
             sym_property: FxHashMap::default(),
+            extensible: true,
+        }))
+    }
+
+    /// https://tc39.github.io/ecma262/#sec-boundfunctioncreate
+    pub fn bound_function(&mut self, target: Value, this: Value, args: Vec<Value>) -> Value {
+        let name = format!("bound {}", target.get_property("name").to_string());
+        let name_prop = self.string(name.clone());
+        // The arguments bound in advance are not counted.
+        let length = match target.get_property("length") {
+            Value::Number(len) => (len.trunc() - args.len() as f64).max(0.0),
+            _ => 0.0,
+        };
+        Value::Object(self.alloc(ObjectInfo {
+            kind: ObjectKind::Function(FunctionObjectInfo {
+                name: Some(name),
+                kind: FunctionObjectKind::Bound { target, this, args },
+            }),
+            prototype: self.object_prototypes.function,
+            property: make_property_map!(
+                length => false, false, true : Value::Number(length),
+                name   => false, false, true : name_prop
+            ),
+            sym_property: FxHashMap::default(),
+            extensible: true,
         }))
     }
 
     pub fn array(&mut self, elems: Vec<Property>) -> Value {
         Value::Object(self.alloc(ObjectInfo {
-            kind: ObjectKind::Array(ArrayObjectInfo { elems }),
+            kind: ObjectKind::Array(ArrayObjectInfo::new(elems)),
             prototype: self.object_prototypes.array,
             property: make_property_map!(),
             sym_property: FxHashMap::default(),
+            extensible: true,
         }))
     }
 
-    pub fn symbol(&mut self, description: Option<String>) -> Value {
+    pub fn array_iterator(&mut self, iterated: Value, kind: IterationKind) -> Value {
         Value::Object(self.alloc(ObjectInfo {
-            kind: ObjectKind::Symbol(SymbolInfo {
-                id: crate::id::get_unique_id(),
-                description,
+            kind: ObjectKind::ArrayIterator(ArrayIteratorInfo {
+                iterated,
+                next_index: 0,
+                kind,
             }),
-            prototype: self.object_prototypes.symbol,
+            prototype: self.object_prototypes.array_iterator,
             property: make_property_map!(),
             sym_property: FxHashMap::default(),
+            extensible: true,
         }))
     }
 
-    pub fn error(&mut self, message: impl Into<String>) -> Value {
-        let message = self.string(message.into());
+    /// The iterator a for-in loop enumerates ``keys`` of ``object`` with.
+    pub fn for_in_iterator(&mut self, object: Value, keys: Vec<String>) -> Value {
         Value::Object(self.alloc(ObjectInfo {
-            kind: ObjectKind::Error(ErrorObjectInfo::new()),
-            prototype: self.object_prototypes.error,
+            kind: ObjectKind::ForInIterator(ForInIteratorInfo {
+                object,
+                keys,
+                next_index: 0,
+            }),
+            prototype: self.object_prototypes.object,
+            property: make_property_map!(),
+            sym_property: FxHashMap::default(),
+            extensible: true,
+        }))
+    }
+
+    /// ``iterated`` must be a string value.
+    pub fn string_iterator(&mut self, iterated: Value) -> Value {
+        Value::Object(self.alloc(ObjectInfo {
+            kind: ObjectKind::StringIterator(StringIteratorInfo {
+                iterated,
+                next_index: 0,
+            }),
+            prototype: self.object_prototypes.string_iterator,
+            property: make_property_map!(),
+            sym_property: FxHashMap::default(),
+            extensible: true,
+        }))
+    }
+
+    pub fn regexp(&mut self, info: RegExpInfo) -> Value {
+        Value::Object(self.alloc(ObjectInfo {
+            kind: ObjectKind::RegExp(info),
+            prototype: self.object_prototypes.regexp,
             property: make_property_map!(
-                message => true, false, true: message
+                lastIndex => true, false, false : Value::Number(0.0)
             ),
             sym_property: FxHashMap::default(),
+            extensible: true,
+        }))
+    }
+
+    /// ``iterated`` must be a string value.
+    pub fn regexp_string_iterator(
+        &mut self,
+        iterating_regexp: Value,
+        iterated: Value,
+        global: bool,
+        unicode: bool,
+    ) -> Value {
+        Value::Object(self.alloc(ObjectInfo {
+            kind: ObjectKind::RegExpStringIterator(RegExpStringIteratorInfo {
+                iterating_regexp,
+                iterated,
+                global,
+                unicode,
+                done: false,
+            }),
+            prototype: self.object_prototypes.regexp_string_iterator,
+            property: make_property_map!(),
+            sym_property: FxHashMap::default(),
+            extensible: true,
+        }))
+    }
+
+    /// A pending promise. ``prototype`` differs from Promise.prototype for a subclass.
+    pub fn promise(&mut self, prototype: Value) -> Value {
+        Value::Object(self.alloc(ObjectInfo {
+            kind: ObjectKind::Promise(PromiseInfo::default()),
+            prototype,
+            property: make_property_map!(),
+            sym_property: FxHashMap::default(),
+            extensible: true,
+        }))
+    }
+
+    /// A Date object whose [[DateValue]] is ``time``.
+    pub fn date(&mut self, prototype: Value, time: f64) -> Value {
+        Value::Object(self.alloc(ObjectInfo {
+            kind: ObjectKind::Date(time),
+            prototype,
+            property: make_property_map!(),
+            sym_property: FxHashMap::default(),
+            extensible: true,
+        }))
+    }
+
+    /// A Number object whose [[NumberData]] is ``n``.
+    pub fn number_object(&mut self, prototype: Value, n: f64) -> Value {
+        Value::Object(self.alloc(ObjectInfo {
+            kind: ObjectKind::Number(n),
+            prototype,
+            property: make_property_map!(),
+            sym_property: FxHashMap::default(),
+            extensible: true,
+        }))
+    }
+
+    /// An empty Map, Set, WeakMap or WeakSet object. ``kind`` must be one of them.
+    pub fn keyed_collection(&mut self, prototype: Value, kind: ObjectKind) -> Value {
+        Value::Object(self.alloc(ObjectInfo {
+            kind,
+            prototype,
+            property: make_property_map!(),
+            sym_property: FxHashMap::default(),
+            extensible: true,
+        }))
+    }
+
+    /// ``iterated`` must be a Map object, and ``cursor`` a cursor of its data.
+    pub fn map_iterator(
+        &mut self,
+        iterated: Value,
+        cursor: MapCursor,
+        kind: IterationKind,
+    ) -> Value {
+        Value::Object(self.alloc(ObjectInfo {
+            kind: ObjectKind::MapIterator(MapIteratorInfo {
+                iterated,
+                cursor,
+                kind,
+            }),
+            prototype: self.object_prototypes.map_iterator,
+            property: make_property_map!(),
+            sym_property: FxHashMap::default(),
+            extensible: true,
+        }))
+    }
+
+    /// ``iterated`` must be a Set object, and ``cursor`` a cursor of its data.
+    pub fn set_iterator(
+        &mut self,
+        iterated: Value,
+        cursor: MapCursor,
+        kind: IterationKind,
+    ) -> Value {
+        Value::Object(self.alloc(ObjectInfo {
+            kind: ObjectKind::SetIterator(SetIteratorInfo {
+                iterated,
+                cursor,
+                kind,
+            }),
+            prototype: self.object_prototypes.set_iterator,
+            property: make_property_map!(),
+            sym_property: FxHashMap::default(),
+            extensible: true,
+        }))
+    }
+
+    /// A String object whose [[StringData]] is ``s``, with its ``length`` and index properties.
+    pub fn string_object(&mut self, prototype: Value, s: Value) -> Value {
+        let units = s.to_utf16();
+        let mut property = make_property_map!();
+        for (i, unit) in units.iter().enumerate() {
+            let unit = self.utf16_string(vec![*unit]);
+            property.insert(
+                i.to_string(),
+                Property::Data(DataProperty {
+                    val: unit,
+                    writable: false,
+                    enumerable: true,
+                    configurable: false,
+                }),
+            );
+        }
+        property.insert(
+            "length".to_string(),
+            Property::Data(DataProperty {
+                val: Value::Number(units.len() as f64),
+                writable: false,
+                enumerable: false,
+                configurable: false,
+            }),
+        );
+        Value::Object(self.alloc(ObjectInfo {
+            kind: ObjectKind::String(s),
+            prototype,
+            property,
+            sym_property: FxHashMap::default(),
+            extensible: true,
+        }))
+    }
+
+    /// A generator object suspended at the start of ``context``.
+    pub fn generator(&mut self, prototype: Value, context: ExecContext) -> Value {
+        Value::Object(self.alloc(ObjectInfo {
+            kind: ObjectKind::Generator(Box::new(GeneratorInfo {
+                state: GeneratorState::SuspendedStart,
+                context: Some(context),
+            })),
+            prototype,
+            property: make_property_map!(),
+            sym_property: FxHashMap::default(),
+            extensible: true,
+        }))
+    }
+
+    /// A module namespace object, whose properties are the exports of a module.
+    /// ``exports`` must be sorted by name.
+    pub fn module_namespace(&mut self, exports: Vec<(String, ResolvedBinding)>) -> Value {
+        Value::Object(self.alloc(ObjectInfo {
+            kind: ObjectKind::ModuleNamespace(Box::new(ModuleNamespaceInfo { exports })),
+            prototype: Value::null(),
+            property: make_property_map!(),
+            sym_property: FxHashMap::default(),
+            extensible: true,
+        }))
+    }
+
+    /// An unmapped arguments object, iterable by %ArrayProto_values%.
+    /// https://tc39.github.io/ecma262/#sec-createunmappedargumentsobject
+    pub fn arguments(&mut self, args: &[Value]) -> Value {
+        let mut property = make_property_map!(
+            length => true, false, true : Value::Number(args.len() as f64)
+        );
+        for (i, arg) in args.iter().enumerate() {
+            property.insert(i.to_string(), Property::new_data_simple(*arg));
+        }
+
+        let mut sym_property = FxHashMap::default();
+        sym_property.insert(
+            self.symbols.iterator.get_symbol_info().id,
+            Property::Data(DataProperty {
+                val: self.object_prototypes.array.get_property("values"),
+                writable: true,
+                enumerable: false,
+                configurable: true,
+            }),
+        );
+
+        Value::Object(self.alloc(ObjectInfo {
+            kind: ObjectKind::Ordinary,
+            prototype: self.object_prototypes.object,
+            property,
+            sym_property,
+            extensible: true,
+        }))
+    }
+
+    /// The state of an async function call. The object is never exposed to scripts.
+    pub fn async_function(&mut self, context: ExecContext, promise: Value) -> Value {
+        Value::Object(self.alloc(ObjectInfo {
+            kind: ObjectKind::AsyncFunction(Box::new(AsyncFunctionInfo {
+                context: Some(context),
+                promise,
+            })),
+            prototype: Value::null(),
+            property: make_property_map!(),
+            sym_property: FxHashMap::default(),
+            extensible: true,
+        }))
+    }
+
+    pub fn symbol(&mut self, description: Option<String>) -> Value {
+        let id = crate::id::get_unique_id();
+        let symbol = Value::Object(self.alloc(ObjectInfo {
+            kind: ObjectKind::Symbol(SymbolInfo { id, description }),
+            prototype: self.object_prototypes.symbol,
+            property: make_property_map!(),
+            sym_property: FxHashMap::default(),
+            extensible: true,
+        }));
+        self.memory_allocator.lock_value(symbol);
+        self.symbol_registry.insert(id, symbol);
+        symbol
+    }
+
+    pub fn error(&mut self, message: impl Into<String>) -> Value {
+        let prototype = self.object_prototypes.error;
+        self.error_object(prototype, Some(message.into()))
+    }
+
+    /// An Error object that inherits from ``prototype``, which is %Error.prototype%, that of a
+    /// NativeError, or that of a subclass. The message inherited from it is used without
+    /// ``message``.
+    pub fn error_object(&mut self, prototype: Value, message: Option<String>) -> Value {
+        let property = match message {
+            Some(message) => {
+                let message = self.string(message);
+                make_property_map!(message => true, false, true: message)
+            }
+            None => make_property_map!(),
+        };
+        Value::Object(self.alloc(ObjectInfo {
+            kind: ObjectKind::Error(ErrorObjectInfo::new()),
+            prototype,
+            property,
+            sym_property: FxHashMap::default(),
+            extensible: true,
         }))
     }
 
@@ -225,11 +567,20 @@ impl Factory {
         &mut self,
         constructor_name: impl Into<String>,
         constructor_func: BuiltinFuncTy,
+        length: usize,
         prototype: Value,
     ) -> Value {
-        let ary = self.builtin_function(constructor_name, constructor_func);
-        ary.set_property("prototype", prototype);
-        ary.get_property("prototype").set_constructor(ary);
+        let ary = self.builtin_function(constructor_name, constructor_func, length);
+        ary.get_object_info().property.insert(
+            "prototype".to_string(),
+            Property::Data(DataProperty {
+                val: prototype,
+                writable: false,
+                enumerable: false,
+                configurable: false,
+            }),
+        );
+        prototype.set_constructor(ary);
         ary
     }
 }
@@ -299,6 +650,9 @@ impl Factory {
                     for name in &user_func.var_names {
                         record.insert(name.clone(), Value::undefined());
                     }
+                    if user_func.uses_arguments {
+                        record.insert("arguments".to_string(), self.arguments(args));
+                    }
                     for (i, FunctionParameter { name, rest_param }) in
                         user_func.params.iter().enumerate()
                     {
@@ -321,6 +675,7 @@ impl Factory {
                     record
                 },
                 this,
+                new_target: Value::undefined(),
             },
             outer: outer_env,
         };
@@ -328,4 +683,39 @@ impl Factory {
         LexicalEnvironmentRef(self.alloc(env))
     }
 
+    /// Creates the environment of a module, where the top-level declarations of the module
+    /// are bound. Imported bindings are added when the module is linked.
+    /// https://tc39.github.io/ecma262/#sec-source-text-module-record-initialize-environment
+    pub fn create_module_environment(
+        &mut self,
+        info: FuncInfoRef,
+        outer_env_ref: LexicalEnvironmentRef,
+    ) -> LexicalEnvironmentRef {
+        let mut record = FxHashMap::default();
+        for name in &info.var_names {
+            record.insert(name.clone(), Value::undefined());
+        }
+        for name in &info.lex_names {
+            record.insert(name.clone(), Value::uninitialized());
+        }
+
+        let env = LexicalEnvironment {
+            record: EnvironmentRecord::Module {
+                this: Value::undefined(),
+                record,
+                imports: FxHashMap::default(),
+            },
+            outer: Some(outer_env_ref),
+        };
+        let mut env_ref = LexicalEnvironmentRef(self.alloc(env));
+
+        for func_info in &info.func_decls {
+            let name = func_info.func_name.clone().unwrap();
+            let func = self.function(*func_info, env_ref);
+            env_ref.set_own_value(name, func).unwrap();
+        }
+
+        env_ref
+    }
+
 }
\ No newline at end of file
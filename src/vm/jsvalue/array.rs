@@ -6,9 +6,18 @@ use super::value::*;
 #[derive(Clone, Debug)]
 pub struct ArrayObjectInfo {
     pub elems: Vec<Property>,
+    /// False once the array is frozen.
+    pub length_writable: bool,
 }
 
 impl ArrayObjectInfo {
+    pub fn new(elems: Vec<Property>) -> Self {
+        ArrayObjectInfo {
+            elems,
+            length_writable: true,
+        }
+    }
+
     pub fn get_element(&self, idx: usize) -> Property {
         if idx >= self.elems.len() {
             return Property::new_data_simple(Value::undefined());
@@ -39,8 +48,14 @@ impl ArrayObjectInfo {
         }
 
         match self.elems[idx] {
-            Property::Data(DataProperty { ref mut val, .. }) => {
-                *val = val_;
+            Property::Data(DataProperty {
+                ref mut val,
+                writable,
+                ..
+            }) => {
+                if writable {
+                    *val = val_;
+                }
                 None
             }
             Property::Accessor(AccessorProperty { set, .. }) => {
@@ -77,20 +92,21 @@ impl ArrayObjectInfo {
 
 impl ArrayObjectInfo {
     /// https://tc39.github.io/ecma262/#sec-array.prototype.join
-    pub fn join(&self, separator: Option<String>) -> String {
-        let separator = separator.unwrap_or(",".to_string());
-        let separator_str = separator.as_str();
-        let mut res = "".to_string();
-        for (i, elem) in self.elems.iter().enumerate() {
-            if let Some(data) = elem.get_data() {
-                res += &(data.val.to_string()
-                    + if self.elems.len() - 1 != i {
-                        separator_str
-                    } else {
-                        ""
-                    });
-            }
-        }
-        res
+    /// Holes, undefined and null are joined as the empty string. The result is in UTF-16 code
+    /// units.
+    pub fn join(&self, separator: Option<&[u16]>) -> Vec<u16> {
+        let separator = separator.unwrap_or(&[',' as u16]);
+        self.elems
+            .iter()
+            .map(|elem| match elem.get_data() {
+                Some(data)
+                    if !data.val.is_empty() && !data.val.is_undefined() && !data.val.is_null() =>
+                {
+                    data.val.to_utf16()
+                }
+                _ => vec![],
+            })
+            .collect::<Vec<Vec<u16>>>()
+            .join(separator)
     }
 }
@@ -0,0 +1,190 @@
+use super::value::*;
+use rustc_hash::FxHashMap;
+use std::cell::Cell;
+use std::hash::{Hash, Hasher};
+use std::mem;
+use std::rc::{Rc, Weak};
+
+/// A value hashed and compared by SameValueZero, so that NaN is a single key, +0 and -0 are the
+/// same key, and objects are told apart by identity.
+/// https://tc39.github.io/ecma262/#sec-samevaluezero
+#[derive(Clone, Copy, Debug)]
+pub struct SameValueZeroKey(pub Value);
+
+impl PartialEq for SameValueZeroKey {
+    fn eq(&self, other: &SameValueZeroKey) -> bool {
+        self.0.same_value_zero(other.0)
+    }
+}
+
+impl Eq for SameValueZeroKey {}
+
+impl Hash for SameValueZeroKey {
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        mem::discriminant(&self.0).hash(state);
+        match self.0 {
+            // -0 and +0 match the same pattern.
+            Value::Number(0.0) => state.write_u64(0.0f64.to_bits()),
+            Value::Number(n) if n.is_nan() => state.write_u64(f64::NAN.to_bits()),
+            Value::Number(n) => state.write_u64(n.to_bits()),
+            Value::String(_) => self.0.into_utf16().hash(state),
+            Value::Bool(b) => b.hash(state),
+            Value::Object(obj) => (obj as usize).hash(state),
+            Value::Other(i) => i.hash(state),
+        }
+    }
+}
+
+/// The index of the next entry an iterator visits. The collection moves it when it removes
+/// deleted entries.
+pub type MapCursor = Rc<Cell<usize>>;
+
+/// The entries of a Map, Set, WeakMap or WeakSet object. A Set uses only the keys.
+/// https://tc39.github.io/ecma262/#sec-keyed-collections
+#[derive(Clone, Debug, Default)]
+pub struct MapData {
+    /// The entries in insertion order. A deleted entry is left as None until more than half of
+    /// the entries are deleted, and then they are removed together.
+    entries: Vec<Option<(Value, Value)>>,
+    /// The index of each key in ``entries``.
+    indices: FxHashMap<SameValueZeroKey, usize>,
+    /// The cursors of the iterators, which are dropped with them.
+    cursors: Vec<Weak<Cell<usize>>>,
+}
+
+/// Deleted entries are not removed while there are fewer of them than this.
+const MIN_DELETED_TO_COMPACT: usize = 8;
+
+impl MapData {
+    pub fn get(&self, key: Value) -> Option<Value> {
+        let index = self.indices.get(&SameValueZeroKey(key))?;
+        self.entries[*index].map(|(_, val)| val)
+    }
+
+    pub fn has(&self, key: Value) -> bool {
+        self.indices.contains_key(&SameValueZeroKey(key))
+    }
+
+    pub fn set(&mut self, key: Value, val: Value) {
+        // -0 is stored as +0.
+        let key = if key == Value::Number(0.0) {
+            Value::Number(0.0)
+        } else {
+            key
+        };
+        match self.indices.get(&SameValueZeroKey(key)) {
+            Some(index) => self.entries[*index] = Some((key, val)),
+            None => {
+                self.indices
+                    .insert(SameValueZeroKey(key), self.entries.len());
+                self.entries.push(Some((key, val)));
+            }
+        }
+    }
+
+    /// Returns false if there was no entry for ``key``.
+    pub fn delete(&mut self, key: Value) -> bool {
+        match self.indices.remove(&SameValueZeroKey(key)) {
+            Some(index) => {
+                self.entries[index] = None;
+                self.compact_if_sparse();
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.indices.clear();
+        self.move_cursors(|_| 0);
+    }
+
+    /// The number of live entries.
+    pub fn size(&self) -> usize {
+        self.indices.len()
+    }
+
+    /// Returns a cursor at the first entry.
+    pub fn cursor(&mut self) -> MapCursor {
+        // Drop the cursors of the collected iterators before the vector grows.
+        if self.cursors.len() == self.cursors.capacity() {
+            self.cursors.retain(|cursor| cursor.strong_count() > 0);
+        }
+        let cursor = Rc::new(Cell::new(0));
+        self.cursors.push(Rc::downgrade(&cursor));
+        cursor
+    }
+
+    /// Returns the first live entry at or after ``cursor`` and moves the cursor past it, or
+    /// None at the end. Entries added while iterating are visited as well.
+    pub fn next_entry(&self, cursor: &MapCursor) -> Option<(Value, Value)> {
+        while cursor.get() < self.entries.len() {
+            let entry = self.entries[cursor.get()];
+            cursor.set(cursor.get() + 1);
+            if entry.is_some() {
+                return entry;
+            }
+        }
+        None
+    }
+
+    /// The live entries in insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = &(Value, Value)> {
+        self.entries.iter().flatten()
+    }
+
+    /// Deletes the entries whose key does not satisfy ``f``.
+    pub fn retain(&mut self, mut f: impl FnMut(Value) -> bool) {
+        for entry in &mut self.entries {
+            if let Some((key, _)) = *entry {
+                if !f(key) {
+                    self.indices.remove(&SameValueZeroKey(key));
+                    *entry = None;
+                }
+            }
+        }
+        self.compact_if_sparse();
+    }
+
+    /// Removes the deleted entries if more than half of the entries are deleted. The cursors
+    /// are moved to keep pointing at the same live entries.
+    fn compact_if_sparse(&mut self) {
+        let deleted = self.entries.len() - self.indices.len();
+        if deleted < MIN_DELETED_TO_COMPACT || deleted * 2 <= self.entries.len() {
+            return;
+        }
+
+        // The new index of each old index, that is, the number of live entries before it.
+        let mut new_indices = Vec::with_capacity(self.entries.len() + 1);
+        let mut live = 0;
+        for entry in &self.entries {
+            new_indices.push(live);
+            if entry.is_some() {
+                live += 1;
+            }
+        }
+        new_indices.push(live);
+        self.move_cursors(|index| new_indices[index]);
+
+        self.entries.retain(Option::is_some);
+        for (index, (key, _)) in self.entries.iter().flatten().enumerate() {
+            *self.indices.get_mut(&SameValueZeroKey(*key)).unwrap() = index;
+        }
+    }
+
+    /// Moves the cursors by ``f`` and drops those of the collected iterators.
+    fn move_cursors(&mut self, f: impl Fn(usize) -> usize) {
+        let len = self.entries.len();
+        self.cursors.retain(|cursor| match cursor.upgrade() {
+            Some(cursor) => {
+                cursor.set(f(cursor.get().min(len)));
+                true
+            }
+            None => false,
+        });
+    }
+}
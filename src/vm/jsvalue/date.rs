@@ -0,0 +1,593 @@
+//! Time values and the conversions between them and dates.
+//! A time value is the number of milliseconds since 1970-01-01T00:00:00Z, or NaN.
+//! https://tc39.github.io/ecma262/#sec-time-values-and-time-range
+
+use chrono::{DateTime, Local, LocalResult, Offset, TimeDelta, TimeZone};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const MS_PER_SECOND: f64 = 1000.0;
+pub const MS_PER_MINUTE: f64 = 60_000.0;
+pub const MS_PER_HOUR: f64 = 3_600_000.0;
+pub const MS_PER_DAY: f64 = 86_400_000.0;
+
+const WEEK_DAY_NAMES: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+const MONTH_NAMES: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// The time value of the current time.
+pub fn current_time() -> f64 {
+    match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(duration) => duration.as_millis() as f64,
+        Err(err) => -(err.duration().as_millis() as f64),
+    }
+}
+
+/// https://tc39.github.io/ecma262/#sec-day-number-and-time-within-day
+pub fn day(t: f64) -> f64 {
+    (t / MS_PER_DAY).floor()
+}
+
+pub fn time_within_day(t: f64) -> f64 {
+    t.rem_euclid(MS_PER_DAY) + 0.0
+}
+
+/// https://tc39.github.io/ecma262/#sec-year-number
+fn days_in_year(y: f64) -> f64 {
+    if y % 4.0 != 0.0 || (y % 100.0 == 0.0 && y % 400.0 != 0.0) {
+        365.0
+    } else {
+        366.0
+    }
+}
+
+fn day_from_year(y: f64) -> f64 {
+    365.0 * (y - 1970.0) + ((y - 1969.0) / 4.0).floor() - ((y - 1901.0) / 100.0).floor()
+        + ((y - 1601.0) / 400.0).floor()
+}
+
+fn time_from_year(y: f64) -> f64 {
+    MS_PER_DAY * day_from_year(y)
+}
+
+pub fn year_from_time(t: f64) -> f64 {
+    let mut y = (t / (MS_PER_DAY * 365.2425)).floor() + 1970.0;
+    while time_from_year(y) > t {
+        y -= 1.0;
+    }
+    while time_from_year(y + 1.0) <= t {
+        y += 1.0;
+    }
+    y
+}
+
+/// The number of days in the months of the year before ``month``.
+fn days_before_month(month: usize, in_leap_year: bool) -> f64 {
+    const DAYS: [f64; 12] = [
+        0.0, 31.0, 59.0, 90.0, 120.0, 151.0, 181.0, 212.0, 243.0, 273.0, 304.0, 334.0,
+    ];
+    if in_leap_year && month >= 2 {
+        DAYS[month] + 1.0
+    } else {
+        DAYS[month]
+    }
+}
+
+/// https://tc39.github.io/ecma262/#sec-month-number
+pub fn month_from_time(t: f64) -> f64 {
+    let year = year_from_time(t);
+    let day_within_year = day(t) - day_from_year(year);
+    let in_leap_year = days_in_year(year) == 366.0;
+    (1..12)
+        .take_while(|month| days_before_month(*month, in_leap_year) <= day_within_year)
+        .count() as f64
+}
+
+/// https://tc39.github.io/ecma262/#sec-date-number
+pub fn date_from_time(t: f64) -> f64 {
+    let year = year_from_time(t);
+    let day_within_year = day(t) - day_from_year(year);
+    let in_leap_year = days_in_year(year) == 366.0;
+    day_within_year - days_before_month(month_from_time(t) as usize, in_leap_year) + 1.0
+}
+
+/// https://tc39.github.io/ecma262/#sec-week-day
+pub fn week_day(t: f64) -> f64 {
+    (day(t) + 4.0).rem_euclid(7.0) + 0.0
+}
+
+/// https://tc39.github.io/ecma262/#sec-hours-minutes-second-and-milliseconds
+/// ``rem_euclid`` keeps the sign of -0, so 0 is added to make the results +0.
+pub fn hour_from_time(t: f64) -> f64 {
+    (t / MS_PER_HOUR).floor().rem_euclid(24.0) + 0.0
+}
+
+pub fn min_from_time(t: f64) -> f64 {
+    (t / MS_PER_MINUTE).floor().rem_euclid(60.0) + 0.0
+}
+
+pub fn sec_from_time(t: f64) -> f64 {
+    (t / MS_PER_SECOND).floor().rem_euclid(60.0) + 0.0
+}
+
+pub fn ms_from_time(t: f64) -> f64 {
+    t.rem_euclid(MS_PER_SECOND) + 0.0
+}
+
+/// https://tc39.github.io/ecma262/#sec-maketime
+pub fn make_time(hour: f64, min: f64, sec: f64, ms: f64) -> f64 {
+    if !hour.is_finite() || !min.is_finite() || !sec.is_finite() || !ms.is_finite() {
+        return f64::NAN;
+    }
+    hour.trunc() * MS_PER_HOUR
+        + min.trunc() * MS_PER_MINUTE
+        + sec.trunc() * MS_PER_SECOND
+        + ms.trunc()
+}
+
+/// https://tc39.github.io/ecma262/#sec-makeday
+pub fn make_day(year: f64, month: f64, date: f64) -> f64 {
+    if !year.is_finite() || !month.is_finite() || !date.is_finite() {
+        return f64::NAN;
+    }
+    let ym = year.trunc() + (month.trunc() / 12.0).floor();
+    // Far beyond the range of time values.
+    if ym.abs() > 400_000.0 {
+        return f64::NAN;
+    }
+    let mn = month.trunc().rem_euclid(12.0) as usize;
+    let in_leap_year = days_in_year(ym) == 366.0;
+    day_from_year(ym) + days_before_month(mn, in_leap_year) + date.trunc() - 1.0
+}
+
+/// https://tc39.github.io/ecma262/#sec-makedate
+pub fn make_date(day: f64, time: f64) -> f64 {
+    if !day.is_finite() || !time.is_finite() {
+        return f64::NAN;
+    }
+    day * MS_PER_DAY + time
+}
+
+/// https://tc39.github.io/ecma262/#sec-timeclip
+pub fn time_clip(time: f64) -> f64 {
+    if !time.is_finite() || time.abs() > 8.64e15 {
+        return f64::NAN;
+    }
+    // Converts -0 to +0.
+    time.trunc() + 0.0
+}
+
+/// The offset of the local time zone from UTC in milliseconds at the time value.
+/// The local time zone is given by the TZ environment variable.
+/// https://tc39.github.io/ecma262/#sec-local-time-zone-adjustment
+pub fn local_tza(t: f64) -> f64 {
+    match DateTime::from_timestamp_millis(t as i64) {
+        Some(utc) => {
+            let offset = Local.offset_from_utc_datetime(&utc.naive_utc());
+            offset.fix().local_minus_utc() as f64 * MS_PER_SECOND
+        }
+        None => 0.0,
+    }
+}
+
+/// https://tc39.github.io/ecma262/#sec-localtime
+pub fn local_time(t: f64) -> f64 {
+    t + local_tza(t)
+}
+
+/// Converts a local time value to a time value. A local time skipped or repeated by a
+/// transition of the time zone is interpreted with the offset before the transition.
+/// https://tc39.github.io/ecma262/#sec-utc-t
+pub fn utc(t: f64) -> f64 {
+    if !t.is_finite() {
+        return f64::NAN;
+    }
+    let local = match DateTime::from_timestamp_millis(t as i64) {
+        Some(local) => local.naive_utc(),
+        None => return t,
+    };
+    let offset = match Local.offset_from_local_datetime(&local) {
+        LocalResult::Single(offset) => offset.local_minus_utc(),
+        // The earlier instant, whose offset is the larger.
+        LocalResult::Ambiguous(offset1, offset2) => {
+            offset1.local_minus_utc().max(offset2.local_minus_utc())
+        }
+        LocalResult::None => Local
+            .offset_from_utc_datetime(&(local - TimeDelta::days(1)))
+            .local_minus_utc(),
+    };
+    t - offset as f64 * MS_PER_SECOND
+}
+
+fn format_year(year: f64) -> String {
+    if year < 0.0 {
+        format!("-{:04}", -year)
+    } else {
+        format!("{:04}", year)
+    }
+}
+
+/// e.g. "Tue Jan 01 2019". ``t`` is a local time value.
+/// https://tc39.github.io/ecma262/#sec-datestring
+fn date_string(t: f64) -> String {
+    format!(
+        "{} {} {:02} {}",
+        WEEK_DAY_NAMES[week_day(t) as usize],
+        MONTH_NAMES[month_from_time(t) as usize],
+        date_from_time(t),
+        format_year(year_from_time(t))
+    )
+}
+
+/// e.g. "09:00:00 GMT". ``t`` is a local time value.
+/// https://tc39.github.io/ecma262/#sec-timestring
+fn time_string(t: f64) -> String {
+    format!(
+        "{:02}:{:02}:{:02} GMT",
+        hour_from_time(t),
+        min_from_time(t),
+        sec_from_time(t)
+    )
+}
+
+/// e.g. "+0900". The name of the time zone is omitted.
+/// https://tc39.github.io/ecma262/#sec-timezoneestring
+fn time_zone_string(tv: f64) -> String {
+    let offset = local_tza(tv);
+    let sign = if offset >= 0.0 { '+' } else { '-' };
+    let offset = offset.abs();
+    format!(
+        "{}{:02}{:02}",
+        sign,
+        hour_from_time(offset),
+        min_from_time(offset)
+    )
+}
+
+/// e.g. "Tue Jan 01 2019 09:00:00 GMT+0900"
+/// https://tc39.github.io/ecma262/#sec-todatestring
+pub fn to_date_string(tv: f64) -> String {
+    if tv.is_nan() {
+        return "Invalid Date".to_string();
+    }
+    let t = local_time(tv);
+    format!(
+        "{} {}{}",
+        date_string(t),
+        time_string(t),
+        time_zone_string(tv)
+    )
+}
+
+/// e.g. "Tue Jan 01 2019"
+pub fn to_local_date_string(tv: f64) -> String {
+    if tv.is_nan() {
+        return "Invalid Date".to_string();
+    }
+    date_string(local_time(tv))
+}
+
+/// e.g. "09:00:00 GMT+0900"
+pub fn to_local_time_string(tv: f64) -> String {
+    if tv.is_nan() {
+        return "Invalid Date".to_string();
+    }
+    format!("{}{}", time_string(local_time(tv)), time_zone_string(tv))
+}
+
+/// e.g. "Tue, 01 Jan 2019 00:00:00 GMT"
+/// https://tc39.github.io/ecma262/#sec-date.prototype.toutcstring
+pub fn to_utc_string(tv: f64) -> String {
+    if tv.is_nan() {
+        return "Invalid Date".to_string();
+    }
+    format!(
+        "{}, {:02} {} {} {}",
+        WEEK_DAY_NAMES[week_day(tv) as usize],
+        date_from_time(tv),
+        MONTH_NAMES[month_from_time(tv) as usize],
+        format_year(year_from_time(tv)),
+        time_string(tv)
+    )
+}
+
+/// e.g. "2019-01-01T00:00:00.000Z". Returns None for an invalid time value.
+/// https://tc39.github.io/ecma262/#sec-date-time-string-format
+pub fn to_iso_string(tv: f64) -> Option<String> {
+    if tv.is_nan() {
+        return None;
+    }
+    let year = year_from_time(tv);
+    let year = if (0.0..=9999.0).contains(&year) {
+        format!("{:04}", year)
+    } else if year < 0.0 {
+        format!("-{:06}", -year)
+    } else {
+        format!("+{:06}", year)
+    };
+    Some(format!(
+        "{}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month_from_time(tv) + 1.0,
+        date_from_time(tv),
+        hour_from_time(tv),
+        min_from_time(tv),
+        sec_from_time(tv),
+        ms_from_time(tv)
+    ))
+}
+
+/// The date part in the en-US locale, e.g. "1/1/2019".
+fn locale_date_string(t: f64) -> String {
+    format!(
+        "{}/{}/{}",
+        month_from_time(t) + 1.0,
+        date_from_time(t),
+        year_from_time(t)
+    )
+}
+
+/// The time part in the en-US locale, e.g. "9:00:00 AM".
+fn locale_time_string(t: f64) -> String {
+    let hour = hour_from_time(t);
+    let (hour12, am_pm) = match hour as u8 {
+        0 => (12.0, "AM"),
+        1..=11 => (hour, "AM"),
+        12 => (12.0, "PM"),
+        _ => (hour - 12.0, "PM"),
+    };
+    format!(
+        "{}:{:02}:{:02} {}",
+        hour12,
+        min_from_time(t),
+        sec_from_time(t),
+        am_pm
+    )
+}
+
+/// e.g. "1/1/2019, 9:00:00 AM"
+pub fn to_locale_string(tv: f64) -> String {
+    if tv.is_nan() {
+        return "Invalid Date".to_string();
+    }
+    let t = local_time(tv);
+    format!("{}, {}", locale_date_string(t), locale_time_string(t))
+}
+
+pub fn to_locale_date_string(tv: f64) -> String {
+    if tv.is_nan() {
+        return "Invalid Date".to_string();
+    }
+    locale_date_string(local_time(tv))
+}
+
+pub fn to_locale_time_string(tv: f64) -> String {
+    if tv.is_nan() {
+        return "Invalid Date".to_string();
+    }
+    locale_time_string(local_time(tv))
+}
+
+/// Parses the date time string format, and the formats of toString(), toUTCString() and
+/// toLocaleString(). Returns NaN for any other string.
+/// https://tc39.github.io/ecma262/#sec-date.parse
+pub fn parse(s: &str) -> f64 {
+    let s = s.trim();
+    match parse_iso_format(s).or_else(|| parse_other_format(s)) {
+        Some(t) => time_clip(t),
+        None => f64::NAN,
+    }
+}
+
+/// Reads exactly ``len`` digits.
+fn read_digits(s: &[u8], pos: &mut usize, len: usize) -> Option<f64> {
+    let digits = s.get(*pos..*pos + len)?;
+    if !digits.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    *pos += len;
+    Some(
+        digits
+            .iter()
+            .fold(0.0, |n, d| n * 10.0 + f64::from(d - b'0')),
+    )
+}
+
+fn read_char(s: &[u8], pos: &mut usize, c: u8) -> bool {
+    if s.get(*pos) == Some(&c) {
+        *pos += 1;
+        true
+    } else {
+        false
+    }
+}
+
+/// YYYY[-MM[-DD]][THH:mm[:ss[.sss]][Z|±HH:mm]], where the year may be ±YYYYYY.
+/// Date-only forms are UTC, and date-time forms without an offset are local time.
+/// https://tc39.github.io/ecma262/#sec-date-time-string-format
+fn parse_iso_format(s: &str) -> Option<f64> {
+    let s = s.as_bytes();
+    let mut pos = 0;
+
+    let year = match s.first() {
+        Some(b'+') | Some(b'-') => {
+            pos += 1;
+            let year = read_digits(s, &mut pos, 6)?;
+            if s[0] == b'-' {
+                // -000000 is not allowed.
+                if year == 0.0 {
+                    return None;
+                }
+                -year
+            } else {
+                year
+            }
+        }
+        _ => read_digits(s, &mut pos, 4)?,
+    };
+    let mut month = 1.0;
+    let mut date = 1.0;
+    if read_char(s, &mut pos, b'-') {
+        month = read_digits(s, &mut pos, 2)?;
+        if read_char(s, &mut pos, b'-') {
+            date = read_digits(s, &mut pos, 2)?;
+        }
+    }
+    if !(1.0..=12.0).contains(&month) || !(1.0..=31.0).contains(&date) {
+        return None;
+    }
+
+    let (mut hour, mut min, mut sec, mut ms) = (0.0, 0.0, 0.0, 0.0);
+    let mut offset = Some(0.0);
+    if read_char(s, &mut pos, b'T') {
+        hour = read_digits(s, &mut pos, 2)?;
+        if !read_char(s, &mut pos, b':') {
+            return None;
+        }
+        min = read_digits(s, &mut pos, 2)?;
+        if read_char(s, &mut pos, b':') {
+            sec = read_digits(s, &mut pos, 2)?;
+            if read_char(s, &mut pos, b'.') {
+                ms = read_digits(s, &mut pos, 3)?;
+            }
+        }
+        if hour > 24.0 || min > 59.0 || sec > 59.0 || (hour == 24.0 && min + sec + ms > 0.0) {
+            return None;
+        }
+        offset = match s.get(pos) {
+            Some(b'Z') => {
+                pos += 1;
+                Some(0.0)
+            }
+            Some(sign @ b'+') | Some(sign @ b'-') => {
+                let sign = if *sign == b'+' { 1.0 } else { -1.0 };
+                pos += 1;
+                let hours = read_digits(s, &mut pos, 2)?;
+                if !read_char(s, &mut pos, b':') {
+                    return None;
+                }
+                let minutes = read_digits(s, &mut pos, 2)?;
+                Some(sign * (hours * MS_PER_HOUR + minutes * MS_PER_MINUTE))
+            }
+            _ => None,
+        };
+    }
+    if pos != s.len() {
+        return None;
+    }
+
+    let t = make_date(
+        make_day(year, month - 1.0, date),
+        make_time(hour, min, sec, ms),
+    );
+    match offset {
+        Some(offset) => Some(t - offset),
+        None => Some(utc(t)),
+    }
+}
+
+/// e.g. "Tue Jan 01 2019 09:00:00 GMT+0900 (JST)", "Tue, 01 Jan 2019 00:00:00 GMT" and
+/// "1/1/2019, 9:00:00 AM". Without "GMT" or "UTC", the time is local time.
+fn parse_other_format(s: &str) -> Option<f64> {
+    // Strip a comment like the name of the time zone.
+    let s = match s.find('(') {
+        Some(i) => &s[..i],
+        None => s,
+    };
+
+    let (mut year, mut month, mut date) = (None, None, None);
+    let (mut hour, mut min, mut sec) = (0.0, 0.0, 0.0);
+    let mut offset = None;
+    let mut is_pm = None;
+
+    for token in s.split(|c: char| c.is_whitespace() || c == ',') {
+        if token.is_empty() {
+            continue;
+        }
+        let upper = token.to_ascii_uppercase();
+        if upper.starts_with("GMT") || upper.starts_with("UTC") || upper == "Z" {
+            offset = Some(parse_offset(token.get(3..).unwrap_or(""))?);
+        } else if upper == "AM" || upper == "PM" {
+            is_pm = Some(upper == "PM");
+        } else if token.starts_with('+') || token.starts_with('-') {
+            offset = Some(parse_offset(token)?);
+        } else if token.contains(':') {
+            let mut parts = token.split(':').map(|n| n.parse::<f64>().ok());
+            hour = parts.next()??;
+            min = parts.next()??;
+            sec = parts.next().unwrap_or(Some(0.0))?;
+            if parts.next().is_some() {
+                return None;
+            }
+        } else if token.contains('/') {
+            let parts = token
+                .split('/')
+                .map(|n| n.parse::<f64>().ok())
+                .collect::<Option<Vec<f64>>>()?;
+            if parts.len() != 3 {
+                return None;
+            }
+            // Either Y/M/D or M/D/Y.
+            let (y, m, d) = if parts[0] > 31.0 {
+                (parts[0], parts[1], parts[2])
+            } else {
+                (parts[2], parts[0], parts[1])
+            };
+            year = Some(y);
+            month = Some(m - 1.0);
+            date = Some(d);
+        } else if let Ok(n) = token.parse::<f64>() {
+            if date.is_none() && year.is_none() && n <= 31.0 {
+                date = Some(n);
+            } else if year.is_none() {
+                year = Some(n);
+            } else {
+                return None;
+            }
+        } else if let Some(i) = MONTH_NAMES
+            .iter()
+            .position(|name| upper.starts_with(&name.to_ascii_uppercase()))
+        {
+            month = Some(i as f64);
+        } else if !WEEK_DAY_NAMES
+            .iter()
+            .any(|name| upper.starts_with(&name.to_ascii_uppercase()))
+        {
+            return None;
+        }
+    }
+
+    match is_pm {
+        Some(true) if hour < 12.0 => hour += 12.0,
+        Some(false) if hour == 12.0 => hour = 0.0,
+        _ => {}
+    }
+    let t = make_date(
+        make_day(year?, month?, date?),
+        make_time(hour, min, sec, 0.0),
+    );
+    match offset {
+        Some(offset) => Some(t - offset),
+        None => Some(utc(t)),
+    }
+}
+
+/// Parses "+0900", "-09:00" or "", and returns the offset in milliseconds.
+fn parse_offset(s: &str) -> Option<f64> {
+    if s.is_empty() {
+        return Some(0.0);
+    }
+    let sign = match s.as_bytes()[0] {
+        b'+' => 1.0,
+        b'-' => -1.0,
+        _ => return None,
+    };
+    let digits: String = s[1..].chars().filter(|c| *c != ':').collect();
+    if digits.len() != 4 {
+        return None;
+    }
+    let mut pos = 0;
+    let hours = read_digits(digits.as_bytes(), &mut pos, 2)?;
+    let minutes = read_digits(digits.as_bytes(), &mut pos, 2)?;
+    Some(sign * (hours * MS_PER_HOUR + minutes * MS_PER_MINUTE))
+}
@@ -1,4 +1,5 @@
 //use super::value::*;
+use super::value::Value;
 use crate::builtin::BuiltinFuncTy;
 use crate::bytecode_gen::ByteCode;
 use crate::vm::exec_context::LexicalEnvironmentRef;
@@ -18,6 +19,15 @@ pub enum FunctionObjectKind {
         info: FuncInfoRef,
     },
     Builtin(BuiltinFuncTy),
+    /// https://tc39.github.io/ecma262/#sec-bound-function-exotic-objects
+    Bound {
+        /// Internal slot \[\[BoundTargetFunction\]\]
+        target: Value,
+        /// Internal slot \[\[BoundThis\]\]
+        this: Value,
+        /// Internal slot \[\[BoundArguments\]\]
+        args: Vec<Value>,
+    },
 }
 
 #[derive(Clone, Debug)]
@@ -33,6 +43,12 @@ pub struct UserFunctionInfo {
     /// Internal slot \[\[FormalParameters\]\]
     pub params: Vec<FunctionParameter>,
 
+    /// ExpectedArgumentCount of the parameters, which is the ``length`` of the function
+    pub length: usize,
+
+    /// Position of the function node, by which the source text is found in ScriptInfo
+    pub source_pos: usize,
+
     /// Varaible declared names
     pub var_names: Vec<String>,
 
@@ -51,8 +67,26 @@ pub struct UserFunctionInfo {
     /// Represent if constructible or not
     pub constructible: bool,
 
+    /// Internal slot \[\[ConstructorKind\]\]
+    pub constructor_kind: ConstructorKind,
+
+    /// True if \[\[FunctionKind\]\] is "classConstructor"
+    pub is_class_constructor: bool,
+
     /// Internal slot \[\[ThisMode\]\]
     pub this_mode: ThisMode,
+
+    /// True if \[\[FunctionKind\]\] is "async"
+    pub is_async: bool,
+
+    /// True if \[\[FunctionKind\]\] is "generator"
+    pub is_generator: bool,
+
+    /// True if the body refers to ``arguments``, which is then bound on call
+    pub uses_arguments: bool,
+
+    /// True if the function is strict mode code
+    pub is_strict: bool,
 }
 
 #[derive(Clone, Debug, Copy)]
@@ -96,6 +130,13 @@ pub enum ThisMode {
     Strict,
 }
 
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub enum ConstructorKind {
+    Base,
+    /// The constructor of a class with ``extends``. ``this`` is bound by ``super()``.
+    Derived,
+}
+
 #[derive(Clone, Debug)]
 pub struct FunctionParameter {
     pub name: String,
@@ -125,11 +166,19 @@ impl UserFunctionInfo {
             func_id: factory.new_func_id(),
             module_func_id,
             params: vec![],
+            length: 0,
+            source_pos: 0,
             var_names: vec![],
             lex_names: vec![],
             func_decls: vec![],
             constructible: false,
+            constructor_kind: ConstructorKind::Base,
+            is_class_constructor: false,
             this_mode: ThisMode::Global,
+            is_async: false,
+            is_generator: false,
+            uses_arguments: false,
+            is_strict: false,
             code: vec![0x0c, 0x28], // [PUSH_UNDEFINED][RETURN]
             exception_table: vec![],
         }
@@ -141,11 +190,19 @@ impl UserFunctionInfo {
             func_id: FunctionId::default(),
             module_func_id: FunctionId::default(),
             params: vec![],
+            length: 0,
+            source_pos: 0,
             var_names: vec![],
             lex_names: vec![],
             func_decls: vec![],
             constructible: false,
+            constructor_kind: ConstructorKind::Base,
+            is_class_constructor: false,
             this_mode: ThisMode::Global,
+            is_async: false,
+            is_generator: false,
+            uses_arguments: false,
+            is_strict: false,
             code: vec![0x0c, 0x28], // [PUSH_UNDEFINED][RETURN]
             exception_table: vec![],
         }
@@ -171,6 +228,7 @@ impl ::std::fmt::Debug for FunctionObjectKind {
             match self {
                 FunctionObjectKind::User { info, .. } => format!("{:?}", info),
                 FunctionObjectKind::Builtin(_) => "[BuiltinFunction]".to_string(),
+                FunctionObjectKind::Bound { .. } => "[BoundFunction]".to_string(),
             }
         )
     }
@@ -0,0 +1,19 @@
+use crate::vm::exec_context::ExecContext;
+
+/// Internal slots of a generator object.
+/// https://tc39.github.io/ecma262/#sec-properties-of-generator-instances
+#[derive(Clone, Debug)]
+pub struct GeneratorInfo {
+    /// \[\[GeneratorState\]\]
+    pub state: GeneratorState,
+    /// \[\[GeneratorContext\]\]. None while executing or after completed.
+    pub context: Option<ExecContext>,
+}
+
+#[derive(Clone, Debug, PartialEq, Copy)]
+pub enum GeneratorState {
+    SuspendedStart,
+    SuspendedYield,
+    Executing,
+    Completed,
+}
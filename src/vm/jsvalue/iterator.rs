@@ -0,0 +1,83 @@
+use super::collection::MapCursor;
+use super::value::*;
+
+/// Internal slots of an Array Iterator object.
+/// https://tc39.github.io/ecma262/#sec-properties-of-array-iterator-instances
+#[derive(Clone, Debug)]
+pub struct ArrayIteratorInfo {
+    /// \[\[IteratedObject\]\]. Undefined after the iterator has completed.
+    pub iterated: Value,
+    /// \[\[ArrayIteratorNextIndex\]\]
+    pub next_index: usize,
+    /// \[\[ArrayIterationKind\]\]
+    pub kind: IterationKind,
+}
+
+#[derive(Clone, Debug, PartialEq, Copy)]
+pub enum IterationKind {
+    Key,
+    Value,
+    KeyAndValue,
+}
+
+/// Internal slots of a String Iterator object.
+/// https://tc39.github.io/ecma262/#sec-properties-of-string-iterator-instances
+#[derive(Clone, Debug)]
+pub struct StringIteratorInfo {
+    /// \[\[IteratedString\]\]. Undefined after the iterator has completed.
+    pub iterated: Value,
+    /// \[\[StringIteratorNextIndex\]\] (byte offset in the string)
+    pub next_index: usize,
+}
+
+/// Internal slots of a RegExp String Iterator object.
+/// https://tc39.github.io/ecma262/#sec-properties-of-regexp-string-iterator-instances
+#[derive(Clone, Debug)]
+pub struct RegExpStringIteratorInfo {
+    /// \[\[IteratingRegExp\]\]
+    pub iterating_regexp: Value,
+    /// \[\[IteratedString\]\]
+    pub iterated: Value,
+    /// \[\[Global\]\]
+    pub global: bool,
+    /// \[\[Unicode\]\]
+    pub unicode: bool,
+    /// \[\[Done\]\]
+    pub done: bool,
+}
+
+/// Internal slots of the iterator a for-in loop enumerates the keys of an object with.
+/// https://tc39.github.io/ecma262/#sec-enumerate-object-properties
+#[derive(Clone, Debug)]
+pub struct ForInIteratorInfo {
+    /// The object or the string whose keys are enumerated.
+    pub object: Value,
+    /// The enumerable string keys of the object and its prototypes, collected when the loop
+    /// starts.
+    pub keys: Vec<String>,
+    pub next_index: usize,
+}
+
+/// Internal slots of a Map Iterator object.
+/// https://tc39.github.io/ecma262/#sec-properties-of-map-iterator-instances
+#[derive(Clone, Debug)]
+pub struct MapIteratorInfo {
+    /// \[\[IteratedObject\]\]. Undefined after the iterator has completed.
+    pub iterated: Value,
+    /// \[\[MapNextIndex\]\]
+    pub cursor: MapCursor,
+    /// \[\[MapIterationKind\]\]
+    pub kind: IterationKind,
+}
+
+/// Internal slots of a Set Iterator object.
+/// https://tc39.github.io/ecma262/#sec-properties-of-set-iterator-instances
+#[derive(Clone, Debug)]
+pub struct SetIteratorInfo {
+    /// \[\[IteratedSet\]\]. Undefined after the iterator has completed.
+    pub iterated: Value,
+    /// \[\[SetNextIndex\]\]
+    pub cursor: MapCursor,
+    /// \[\[SetIterationKind\]\]
+    pub kind: IterationKind,
+}
@@ -1,8 +1,17 @@
 #[macro_use]
 pub mod value;
 pub mod array;
+pub mod collection;
+pub mod date;
 pub mod error;
 pub mod function;
+pub mod generator;
+pub mod iterator;
+pub mod module;
+pub mod number;
 pub mod object;
+pub mod promise;
 pub mod prototype;
+pub mod regexp;
+pub mod string;
 pub mod symbol;
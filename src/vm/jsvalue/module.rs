@@ -0,0 +1,44 @@
+use super::value::*;
+use crate::vm::error::RuntimeError;
+use crate::vm::exec_context::LexicalEnvironmentRef;
+
+/// Internal slots of a module namespace object.
+/// https://tc39.github.io/ecma262/#sec-module-namespace-exotic-objects
+#[derive(Clone, Debug)]
+pub struct ModuleNamespaceInfo {
+    /// \[\[Exports\]\] sorted by name, with the bindings they resolve to.
+    pub exports: Vec<(String, ResolvedBinding)>,
+}
+
+/// The binding an exported name resolves to.
+/// https://tc39.github.io/ecma262/#resolvedbinding-record
+#[derive(Clone, Debug)]
+pub enum ResolvedBinding {
+    /// A binding in the environment of a module.
+    Binding(LexicalEnvironmentRef, String),
+    /// The namespace object of a module, exported by ``export * as name from``.
+    Namespace(Value),
+}
+
+impl ModuleNamespaceInfo {
+    pub fn get_export(&self, name: &str) -> Option<&ResolvedBinding> {
+        self.exports
+            .binary_search_by(|(export_name, _)| export_name.as_str().cmp(name))
+            .ok()
+            .map(|i| &self.exports[i].1)
+    }
+
+    pub fn has_export(&self, name: &str) -> bool {
+        self.get_export(name).is_some()
+    }
+}
+
+impl ResolvedBinding {
+    /// Reads the current value of the binding. Throws if it is not initialized yet.
+    pub fn get_value(&self) -> Result<Value, RuntimeError> {
+        match self {
+            ResolvedBinding::Binding(env, name) => env.get_value(name.as_str()),
+            ResolvedBinding::Namespace(namespace) => Ok(*namespace),
+        }
+    }
+}
@@ -0,0 +1,410 @@
+//! Conversions between number values and strings.
+
+const DIGITS: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+/// The shortest decimal digits that round-trip to positive and finite ``x``, and the exponent
+/// of the first digit: x = 0.d1d2d3... * 10^n.
+fn shortest_digits(x: f64) -> (Vec<u8>, i32) {
+    // `{:e}` prints the shortest round-tripping digits, e.g. "1.2345e-7".
+    let repr = format!("{:e}", x);
+    let (mantissa, exponent) = repr.split_at(repr.find('e').unwrap());
+    let digits = mantissa.bytes().filter(|c| *c != b'.').collect();
+    (digits, exponent[1..].parse::<i32>().unwrap() + 1)
+}
+
+/// The exact decimal digits of positive and finite ``x`` without trailing zeros, and the
+/// exponent of the first digit: x = 0.d1d2d3... * 10^n.
+fn exact_digits(x: f64) -> (Vec<u8>, i32) {
+    // Any double has at most 767 significant decimal digits, so this precision is exact.
+    let repr = format!("{:.800e}", x);
+    let (mantissa, exponent) = repr.split_at(repr.find('e').unwrap());
+    let mut digits: Vec<u8> = mantissa.bytes().filter(|c| *c != b'.').collect();
+    while digits.len() > 1 && digits.last() == Some(&b'0') {
+        digits.pop();
+    }
+    (digits, exponent[1..].parse::<i32>().unwrap() + 1)
+}
+
+/// Rounds ``digits`` to ``len`` digits, rounding half up. Returns the digits and whether the
+/// rounding carried into a new leading digit, in which case the result has ``len + 1`` digits.
+fn round_digits(digits: &[u8], len: usize) -> (Vec<u8>, bool) {
+    let mut rounded: Vec<u8> = (0..len)
+        .map(|i| digits.get(i).copied().unwrap_or(b'0'))
+        .collect();
+    if digits.get(len).is_some_and(|d| *d >= b'5') {
+        for d in rounded.iter_mut().rev() {
+            if *d == b'9' {
+                *d = b'0';
+            } else {
+                *d += 1;
+                return (rounded, false);
+            }
+        }
+        rounded.insert(0, b'1');
+        return (rounded, true);
+    }
+    (rounded, false)
+}
+
+/// ``len`` significant digits of positive and finite ``x``, rounded half up, and the exponent
+/// of the first digit: x ~ d1.d2d3... * 10^e.
+fn significant_digits(x: f64, len: usize) -> (String, i32) {
+    let (digits, n) = exact_digits(x);
+    let (mut rounded, carried) = round_digits(&digits, len);
+    if carried {
+        rounded.pop();
+    }
+    let e = if carried { n } else { n - 1 };
+    (String::from_utf8(rounded).unwrap(), e)
+}
+
+/// Number::toString(x)
+/// https://tc39.github.io/ecma262/#sec-numeric-types-number-tostring
+pub fn to_string(x: f64) -> String {
+    if x.is_nan() {
+        return "NaN".to_string();
+    }
+    if x == 0.0 {
+        return "0".to_string();
+    }
+    if x < 0.0 {
+        return format!("-{}", to_string(-x));
+    }
+    if x.is_infinite() {
+        return "Infinity".to_string();
+    }
+
+    let (digits, n) = shortest_digits(x);
+    let digits = String::from_utf8(digits).unwrap();
+    let k = digits.len() as i32;
+    if k <= n && n <= 21 {
+        format!("{}{}", digits, "0".repeat((n - k) as usize))
+    } else if 0 < n && n <= 21 {
+        format!("{}.{}", &digits[..n as usize], &digits[n as usize..])
+    } else if -6 < n && n <= 0 {
+        format!("0.{}{}", "0".repeat(-n as usize), digits)
+    } else {
+        let sign = if n - 1 < 0 { '-' } else { '+' };
+        if k == 1 {
+            format!("{}e{}{}", digits, sign, (n - 1).abs())
+        } else {
+            format!(
+                "{}.{}e{}{}",
+                &digits[..1],
+                &digits[1..],
+                sign,
+                (n - 1).abs()
+            )
+        }
+    }
+}
+
+/// Converts ``x`` to a string in ``radix``. The fraction part has as many digits as are
+/// needed to tell ``x`` from the adjacent doubles.
+pub fn to_radix_string(x: f64, radix: u32) -> String {
+    if radix == 10 || !x.is_finite() || x == 0.0 {
+        return to_string(x);
+    }
+    if x < 0.0 {
+        return format!("-{}", to_radix_string(-x, radix));
+    }
+
+    let radix_f = f64::from(radix);
+    let mut integer = x.floor();
+    let mut fraction = x - integer;
+    // Half the distance to the next double, but at least the smallest double.
+    let mut delta = (0.5 * (f64::from_bits(x.to_bits() + 1) - x)).max(f64::from_bits(1));
+
+    let mut fraction_digits = vec![];
+    if fraction >= delta {
+        loop {
+            fraction *= radix_f;
+            delta *= radix_f;
+            let digit = fraction as usize;
+            fraction_digits.push(digit);
+            fraction -= digit as f64;
+            let round_up = fraction > 0.5 || (fraction == 0.5 && digit & 1 == 1);
+            if round_up && fraction + delta > 1.0 {
+                // Round up, and propagate the carry.
+                loop {
+                    match fraction_digits.pop() {
+                        Some(digit) if digit + 1 < radix as usize => {
+                            fraction_digits.push(digit + 1);
+                            break;
+                        }
+                        Some(_) => {}
+                        None => {
+                            integer += 1.0;
+                            break;
+                        }
+                    }
+                }
+                break;
+            }
+            if fraction < delta {
+                break;
+            }
+        }
+    }
+
+    let mut integer_digits = vec![];
+    // Digits below the precision of a double are zeros.
+    while integer / radix_f >= 9_007_199_254_740_992.0 {
+        integer /= radix_f;
+        integer_digits.push(0);
+    }
+    loop {
+        let remainder = integer % radix_f;
+        integer_digits.push(remainder as usize);
+        integer = (integer - remainder) / radix_f;
+        if integer <= 0.0 {
+            break;
+        }
+    }
+
+    let mut string: String = integer_digits
+        .iter()
+        .rev()
+        .map(|d| DIGITS[*d] as char)
+        .collect();
+    if !fraction_digits.is_empty() {
+        string.push('.');
+        string.extend(fraction_digits.iter().map(|d| DIGITS[*d] as char));
+    }
+    string
+}
+
+/// Number.prototype.toFixed for finite ``x`` less than 10^21 in magnitude.
+/// https://tc39.github.io/ecma262/#sec-number.prototype.tofixed
+pub fn to_fixed(x: f64, fraction_digits: usize) -> String {
+    let sign = if x < 0.0 { "-" } else { "" };
+    let x = x.abs();
+    let integer_digits = if x == 0.0 {
+        vec![b'0']
+    } else {
+        // The digits of round(x * 10^f).
+        let (digits, n) = exact_digits(x);
+        let len = n + fraction_digits as i32;
+        if len < 0 {
+            vec![b'0']
+        } else if len == 0 {
+            vec![if digits[0] >= b'5' { b'1' } else { b'0' }]
+        } else {
+            round_digits(&digits, len as usize).0
+        }
+    };
+
+    let mut digits = String::from_utf8(integer_digits).unwrap();
+    if fraction_digits == 0 {
+        return format!("{}{}", sign, digits);
+    }
+    if digits.len() <= fraction_digits {
+        digits = format!(
+            "{}{}",
+            "0".repeat(fraction_digits + 1 - digits.len()),
+            digits
+        );
+    }
+    let point = digits.len() - fraction_digits;
+    format!("{}{}.{}", sign, &digits[..point], &digits[point..])
+}
+
+/// Number.prototype.toExponential for finite ``x``. Without ``fraction_digits``, uses as many
+/// digits as are needed to represent ``x`` uniquely.
+/// https://tc39.github.io/ecma262/#sec-number.prototype.toexponential
+pub fn to_exponential(x: f64, fraction_digits: Option<usize>) -> String {
+    let sign = if x < 0.0 { "-" } else { "" };
+    let x = x.abs();
+    let (digits, e) = match (x == 0.0, fraction_digits) {
+        (true, fraction_digits) => ("0".repeat(fraction_digits.unwrap_or(0) + 1), 0),
+        (false, Some(fraction_digits)) => significant_digits(x, fraction_digits + 1),
+        (false, None) => {
+            let (digits, n) = shortest_digits(x);
+            (String::from_utf8(digits).unwrap(), n - 1)
+        }
+    };
+    format!(
+        "{}{}e{}{}",
+        sign,
+        with_point(&digits, 1),
+        if e < 0 { '-' } else { '+' },
+        e.abs()
+    )
+}
+
+/// Number.prototype.toPrecision for finite ``x``.
+/// https://tc39.github.io/ecma262/#sec-number.prototype.toprecision
+pub fn to_precision(x: f64, precision: usize) -> String {
+    let sign = if x < 0.0 { "-" } else { "" };
+    let x = x.abs();
+    let (digits, e) = if x == 0.0 {
+        ("0".repeat(precision), 0)
+    } else {
+        significant_digits(x, precision)
+    };
+
+    if e < -6 || e >= precision as i32 {
+        return format!(
+            "{}{}e{}{}",
+            sign,
+            with_point(&digits, 1),
+            if e < 0 { '-' } else { '+' },
+            e.abs()
+        );
+    }
+    if e >= 0 {
+        format!("{}{}", sign, with_point(&digits, e as usize + 1))
+    } else {
+        format!("{}0.{}{}", sign, "0".repeat((-e - 1) as usize), digits)
+    }
+}
+
+/// Inserts a decimal point after ``integer_len`` digits unless it is the end.
+fn with_point(digits: &str, integer_len: usize) -> String {
+    if digits.len() == integer_len {
+        digits.to_string()
+    } else {
+        format!("{}.{}", &digits[..integer_len], &digits[integer_len..])
+    }
+}
+
+/// WhiteSpace and LineTerminator.
+/// https://tc39.github.io/ecma262/#prod-StrWhiteSpaceChar
+fn is_str_whitespace(c: char) -> bool {
+    c.is_whitespace() || c == '\u{feff}'
+}
+
+/// The length of the longest prefix of ``s`` that is a StrUnsignedDecimalLiteral.
+/// https://tc39.github.io/ecma262/#prod-StrUnsignedDecimalLiteral
+fn decimal_literal_len(s: &str) -> usize {
+    if s.starts_with("Infinity") {
+        return "Infinity".len();
+    }
+    let s = s.as_bytes();
+    let digits_len = |pos: usize| s[pos..].iter().take_while(|c| c.is_ascii_digit()).count();
+
+    let mut pos = digits_len(0);
+    let mut has_digits = pos > 0;
+    if s.get(pos) == Some(&b'.') {
+        let fraction_len = digits_len(pos + 1);
+        if has_digits || fraction_len > 0 {
+            pos += 1 + fraction_len;
+            has_digits = true;
+        }
+    }
+    if !has_digits {
+        return 0;
+    }
+    if let Some(b'e') | Some(b'E') = s.get(pos) {
+        let sign_len = match s.get(pos + 1) {
+            Some(b'+') | Some(b'-') => 1,
+            _ => 0,
+        };
+        let exponent_len = digits_len(pos + 1 + sign_len);
+        if exponent_len > 0 {
+            pos += 1 + sign_len + exponent_len;
+        }
+    }
+    pos
+}
+
+/// Parses a StrUnsignedDecimalLiteral.
+fn parse_decimal_literal(s: &str) -> f64 {
+    if s == "Infinity" {
+        f64::INFINITY
+    } else {
+        s.parse::<f64>().unwrap_or(f64::NAN)
+    }
+}
+
+/// Splits an optional sign off ``s``, and returns the sign as a factor.
+fn split_sign(s: &str) -> (f64, &str) {
+    if let Some(s) = s.strip_prefix('-') {
+        (-1.0, s)
+    } else if let Some(s) = s.strip_prefix('+') {
+        (1.0, s)
+    } else {
+        (1.0, s)
+    }
+}
+
+/// Parses ``digits`` in ``radix``. Returns None unless all the characters are digits.
+fn parse_digits(digits: &str, radix: u32) -> Option<f64> {
+    if digits.is_empty() {
+        return None;
+    }
+    if radix == 10 {
+        // Rounds correctly even beyond 2^53.
+        return digits.parse::<f64>().ok();
+    }
+    digits.chars().try_fold(0.0, |n, c| {
+        c.to_digit(radix)
+            .map(|d| n * f64::from(radix) + f64::from(d))
+    })
+}
+
+/// ToNumber applied to the String type.
+/// https://tc39.github.io/ecma262/#sec-tonumber-applied-to-the-string-type
+pub fn string_to_number(s: &str) -> f64 {
+    let s = s.trim_matches(is_str_whitespace);
+    if s.is_empty() {
+        return 0.0;
+    }
+    let radix = match s.get(..2) {
+        Some("0x") | Some("0X") => 16,
+        Some("0o") | Some("0O") => 8,
+        Some("0b") | Some("0B") => 2,
+        _ => 10,
+    };
+    if radix != 10 {
+        return parse_digits(&s[2..], radix).unwrap_or(f64::NAN);
+    }
+
+    let (sign, unsigned) = split_sign(s);
+    if decimal_literal_len(unsigned) != unsigned.len() {
+        return f64::NAN;
+    }
+    sign * parse_decimal_literal(unsigned)
+}
+
+/// parseFloat(string)
+/// https://tc39.github.io/ecma262/#sec-parsefloat-string
+pub fn parse_float(s: &str) -> f64 {
+    let s = s.trim_start_matches(is_str_whitespace);
+    let (sign, unsigned) = split_sign(s);
+    let len = decimal_literal_len(unsigned);
+    if len == 0 {
+        return f64::NAN;
+    }
+    sign * parse_decimal_literal(&unsigned[..len])
+}
+
+/// parseInt(string, radix), where ``radix`` is already converted by ToInt32.
+/// https://tc39.github.io/ecma262/#sec-parseint-string-radix
+pub fn parse_int(s: &str, radix: i32) -> f64 {
+    let s = s.trim_start_matches(is_str_whitespace);
+    let (sign, mut s) = split_sign(s);
+    let mut strip_prefix = true;
+    let mut radix = radix;
+    if radix != 0 {
+        if !(2..=36).contains(&radix) {
+            return f64::NAN;
+        }
+        if radix != 16 {
+            strip_prefix = false;
+        }
+    } else {
+        radix = 10;
+    }
+    if strip_prefix && (s.starts_with("0x") || s.starts_with("0X")) {
+        s = &s[2..];
+        radix = 16;
+    }
+
+    let radix = radix as u32;
+    let len = s.find(|c: char| !c.is_digit(radix)).unwrap_or(s.len());
+    match parse_digits(&s[..len], radix) {
+        Some(n) => sign * n,
+        None => f64::NAN,
+    }
+}
@@ -2,7 +2,13 @@ use super::super::super::gc::MemoryAllocator;
 use super::super::error;
 use super::value::*;
 use crate::vm::vm::Factory;
+use indexmap::IndexMap;
 pub use rustc_hash::FxHashMap;
+use rustc_hash::FxHasher;
+use std::hash::BuildHasherDefault;
+
+/// String-keyed properties, kept in the order they were created.
+pub type PropertyMap = IndexMap<String, Property, BuildHasherDefault<FxHasher>>;
 
 #[derive(Clone, Debug)]
 pub struct ObjectInfo {
@@ -11,9 +17,11 @@ pub struct ObjectInfo {
     /// Internal slot \[\[Prototype\]\]
     pub prototype: Value,
     /// Properties
-    pub property: FxHashMap<String, Property>,
+    pub property: PropertyMap,
     /// Symbol properties
     pub sym_property: FxHashMap<usize, Property>,
+    /// Internal slot \[\[Extensible\]\]
+    pub extensible: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -38,9 +46,46 @@ pub enum ObjectKind {
     Array(ArrayObjectInfo),
     Symbol(SymbolInfo),
     Error(ErrorObjectInfo),
+    ArrayIterator(ArrayIteratorInfo),
+    StringIterator(StringIteratorInfo),
+    RegExp(RegExpInfo),
+    RegExpStringIterator(RegExpStringIteratorInfo),
+    Promise(PromiseInfo),
+    AsyncFunction(Box<AsyncFunctionInfo>),
+    Generator(Box<GeneratorInfo>),
+    ModuleNamespace(Box<ModuleNamespaceInfo>),
+    /// [[DateValue]]: the time value, or NaN for an invalid date.
+    Date(f64),
+    /// [[NumberData]] of a Number object.
+    Number(f64),
+    /// [[StringData]] of a String object.
+    String(Value),
+    /// [[MapData]] of a Map object.
+    Map(Box<MapData>),
+    /// [[SetData]] of a Set object.
+    Set(Box<MapData>),
+    /// [[WeakMapData]] of a WeakMap object. Entries are dropped when their key is collected.
+    WeakMap(Box<MapData>),
+    /// [[WeakSetData]] of a WeakSet object. Entries are dropped when they are collected.
+    WeakSet(Box<MapData>),
+    MapIterator(MapIteratorInfo),
+    SetIterator(SetIteratorInfo),
+    ForInIterator(ForInIteratorInfo),
     Ordinary,
 }
 
+/// A property descriptor whose absent fields are None.
+/// https://tc39.github.io/ecma262/#sec-property-descriptor-specification-type
+#[derive(Clone, Debug, Default)]
+pub struct PropertyDescriptor {
+    pub value: Option<Value>,
+    pub writable: Option<bool>,
+    pub get: Option<Value>,
+    pub set: Option<Value>,
+    pub enumerable: Option<bool>,
+    pub configurable: Option<bool>,
+}
+
 #[derive(Clone, PartialEq, Debug, Copy)]
 pub enum Property {
     Data(DataProperty),
@@ -68,6 +113,287 @@ impl ObjectInfo {
         self.property.contains_key(key)
     }
 
+    /// Returns own string-keyed property names paired with their \[\[Enumerable\]\] attribute,
+    /// in the order of https://tc39.github.io/ecma262/#sec-ordinaryownpropertykeys.
+    pub fn own_string_keys(&self) -> Vec<(String, bool)> {
+        let mut keys = vec![];
+
+        if let ObjectKind::ModuleNamespace(ref info) = self.kind {
+            return info
+                .exports
+                .iter()
+                .map(|(name, _)| (name.clone(), true))
+                .collect();
+        }
+
+        if let ObjectKind::Array(ref info) = self.kind {
+            for (i, elem) in info.elems.iter().enumerate() {
+                match elem {
+                    Property::Data(DataProperty { val, .. }) if val.is_empty() => {}
+                    _ => keys.push((i.to_string(), elem.is_enumerable())),
+                }
+            }
+        }
+
+        let mut index_keys = vec![];
+        let mut other_keys = vec![];
+        for (key, prop) in &self.property {
+            match key.parse::<u32>() {
+                Ok(idx) if idx != u32::MAX && idx.to_string() == *key => {
+                    index_keys.push((idx, prop.is_enumerable()))
+                }
+                _ => other_keys.push((key.clone(), prop.is_enumerable())),
+            }
+        }
+        index_keys.sort_by_key(|(idx, _)| *idx);
+
+        keys.extend(
+            index_keys
+                .into_iter()
+                .map(|(idx, enumerable)| (idx.to_string(), enumerable)),
+        );
+        keys.extend(other_keys);
+        keys
+    }
+
+    /// https://tc39.github.io/ecma262/#sec-ordinarygetownproperty
+    pub fn get_own_property(
+        &self,
+        allocator: &mut MemoryAllocator,
+        key: Value,
+    ) -> Option<Property> {
+        if key.is_symbol() {
+            return self.sym_property.get(&key.get_symbol_info().id).copied();
+        }
+
+        if let ObjectKind::Array(ref info) = self.kind {
+            let idx = key
+                .is_array_index()
+                .or_else(|| key.is_canonical_numeric_index_string(allocator));
+            if let Some(idx) = idx {
+                return match info.elems.get(idx) {
+                    Some(Property::Data(DataProperty { val, .. })) if val.is_empty() => None,
+                    prop => prop.copied(),
+                };
+            }
+            if key.is_string() && key.into_str() == "length" {
+                return Some(Property::Data(DataProperty {
+                    val: Value::Number(info.elems.len() as f64),
+                    writable: info.length_writable,
+                    enumerable: false,
+                    configurable: false,
+                }));
+            }
+        }
+
+        self.property.get(key.to_string().as_str()).copied()
+    }
+
+    /// Returns false if the property can not be defined as ``desc``.
+    /// https://tc39.github.io/ecma262/#sec-validateandapplypropertydescriptor
+    pub fn define_own_property(
+        &mut self,
+        allocator: &mut MemoryAllocator,
+        key: Value,
+        desc: PropertyDescriptor,
+    ) -> bool {
+        let mut array_index = None;
+        if let ObjectKind::Array(ref mut info) = self.kind {
+            array_index = key
+                .is_array_index()
+                .or_else(|| key.is_canonical_numeric_index_string(allocator));
+            // The length of an array is neither enumerable nor configurable.
+            if array_index.is_none() && key.is_string() && key.into_str() == "length" {
+                if desc.get.is_some()
+                    || desc.set.is_some()
+                    || desc.enumerable == Some(true)
+                    || desc.configurable == Some(true)
+                    || (!info.length_writable && desc.writable == Some(true))
+                {
+                    return false;
+                }
+                if let Some(len) = desc.value {
+                    match len.is_array_index() {
+                        Some(len) if len == info.elems.len() => {}
+                        Some(len) if info.length_writable => info.set_length(len),
+                        _ => return false,
+                    }
+                }
+                if desc.writable == Some(false) {
+                    info.length_writable = false;
+                }
+                return true;
+            }
+        }
+
+        let prop = match self.get_own_property(allocator, key) {
+            Some(current) => match apply_property_descriptor(current, &desc) {
+                Some(prop) => prop,
+                None => return false,
+            },
+            None if !self.extensible => return false,
+            None if desc.get.is_some() || desc.set.is_some() => {
+                Property::Accessor(AccessorProperty {
+                    get: desc.get.unwrap_or_else(Value::undefined),
+                    set: desc.set.unwrap_or_else(Value::undefined),
+                    enumerable: desc.enumerable.unwrap_or(false),
+                    configurable: desc.configurable.unwrap_or(false),
+                })
+            }
+            None => Property::Data(DataProperty {
+                val: desc.value.unwrap_or_else(Value::undefined),
+                writable: desc.writable.unwrap_or(false),
+                enumerable: desc.enumerable.unwrap_or(false),
+                configurable: desc.configurable.unwrap_or(false),
+            }),
+        };
+
+        match (array_index, &mut self.kind) {
+            (Some(idx), ObjectKind::Array(ref mut info)) => {
+                if idx >= info.elems.len() {
+                    info.set_length(idx + 1);
+                }
+                info.elems[idx] = prop;
+            }
+            _ if key.is_symbol() => {
+                self.sym_property.insert(key.get_symbol_info().id, prop);
+            }
+            _ => {
+                self.property.insert(key.to_string(), prop);
+            }
+        }
+        true
+    }
+
+    /// Makes the object non-extensible and its own properties non-configurable. If ``frozen``,
+    /// data properties also become non-writable.
+    /// https://tc39.github.io/ecma262/#sec-setintegritylevel
+    pub fn set_integrity_level(&mut self, frozen: bool) {
+        fn restrict(prop: &mut Property, frozen: bool) {
+            match prop {
+                Property::Data(DataProperty {
+                    writable,
+                    configurable,
+                    ..
+                }) => {
+                    *configurable = false;
+                    if frozen {
+                        *writable = false;
+                    }
+                }
+                Property::Accessor(AccessorProperty { configurable, .. }) => *configurable = false,
+            }
+        }
+
+        self.extensible = false;
+        if let ObjectKind::Array(ref mut info) = self.kind {
+            for elem in &mut info.elems {
+                restrict(elem, frozen);
+            }
+            if frozen {
+                info.length_writable = false;
+            }
+        }
+        for (_, prop) in self.property.iter_mut() {
+            restrict(prop, frozen);
+        }
+        for prop in self.sym_property.values_mut() {
+            restrict(prop, frozen);
+        }
+    }
+
+    /// https://tc39.github.io/ecma262/#sec-testintegritylevel
+    pub fn test_integrity_level(&self, frozen: bool) -> bool {
+        let is_restricted = |prop: &Property| match prop {
+            // A hole is not a property.
+            Property::Data(DataProperty { val, .. }) if val.is_empty() => true,
+            Property::Data(DataProperty {
+                writable,
+                configurable,
+                ..
+            }) => !*configurable && !(frozen && *writable),
+            Property::Accessor(AccessorProperty { configurable, .. }) => !*configurable,
+        };
+
+        if self.extensible {
+            return false;
+        }
+        if let ObjectKind::Array(ref info) = self.kind {
+            if !info.elems.iter().all(is_restricted) || (frozen && info.length_writable) {
+                return false;
+            }
+        }
+        self.property.values().all(is_restricted) && self.sym_property.values().all(is_restricted)
+    }
+
+    fn has_own_property_by_value(&self, key: Value) -> bool {
+        if key.is_symbol() {
+            self.sym_property.contains_key(&key.get_symbol_info().id)
+        } else {
+            self.property.contains_key(key.to_string().as_str())
+        }
+    }
+
+    /// Returns true if ``key`` is an own or inherited property.
+    /// https://tc39.github.io/ecma262/#sec-ordinaryhasproperty
+    pub fn has_property_by_value(&self, allocator: &mut MemoryAllocator, key: Value) -> bool {
+        if let ObjectKind::Array(ref info) = self.kind {
+            let idx = key
+                .is_array_index()
+                .or_else(|| key.is_canonical_numeric_index_string(allocator));
+            if let Some(idx) = idx {
+                match info.elems.get(idx) {
+                    Some(Property::Data(DataProperty { val, .. })) if val.is_empty() => {}
+                    Some(_) => return true,
+                    None => {}
+                }
+            } else if key.is_string() && key.into_str() == "length" {
+                return true;
+            }
+        }
+
+        if let ObjectKind::ModuleNamespace(ref info) = self.kind {
+            if !key.is_symbol() {
+                return info.has_export(key.to_string().as_str());
+            }
+        }
+
+        if self.has_own_property_by_value(key) {
+            return true;
+        }
+
+        if self.prototype.is_object() {
+            return self
+                .prototype
+                .get_object_info()
+                .has_property_by_value(allocator, key);
+        }
+
+        false
+    }
+
+    /// Looks up the prototype chain for an accessor property.
+    /// Returns None if a data property is found first.
+    fn get_inherited_accessor(&self, key: Value) -> Option<AccessorProperty> {
+        let mut proto = self.prototype;
+        while proto.is_object() {
+            let info = proto.get_object_info();
+            if info.has_own_property_by_value(key) {
+                let prop = if key.is_symbol() {
+                    info.sym_property[&key.get_symbol_info().id]
+                } else {
+                    info.property[key.to_string().as_str()]
+                };
+                return match prop {
+                    Property::Accessor(accessor) => Some(accessor),
+                    Property::Data(_) => None,
+                };
+            }
+            proto = info.prototype;
+        }
+        None
+    }
+
     #[inline]
     pub fn get_prototype(&self) -> Value {
         self.prototype
@@ -78,8 +404,11 @@ impl ObjectInfo {
         factory: &mut Factory,
         key: Value,
     ) -> Result<Property, error::RuntimeError> {
-        // Annoying
-        if key.is_string() && key.into_str() == "__proto__" {
+        // Annoying. An own "__proto__" property (e.g. created by JSON.parse) shadows the accessor.
+        if key.is_string()
+            && key.into_str() == "__proto__"
+            && !self.property.contains_key("__proto__")
+        {
             return Ok(Property::new_data_simple(self.get_prototype()));
         }
 
@@ -87,6 +416,9 @@ impl ObjectInfo {
             let id = key.get_symbol_info().id;
             return match self.sym_property.get(&id) {
                 Some(prop) => Ok(*prop),
+                None if self.prototype.is_null() => {
+                    Ok(Property::new_data_simple(Value::undefined()))
+                }
                 None => self.prototype.get_property_by_value(factory, key),
             };
         }
@@ -109,6 +441,13 @@ impl ObjectInfo {
                     )));
                 }
             }
+            // https://tc39.github.io/ecma262/#sec-module-namespace-exotic-objects-get-p-receiver
+            ObjectKind::ModuleNamespace(ref info) => {
+                return match info.get_export(key.to_string().as_str()) {
+                    Some(binding) => Ok(Property::new_data_simple(binding.get_value()?)),
+                    None => Ok(Property::new_data_simple(Value::undefined())),
+                };
+            }
             _ => {}
         }
 
@@ -142,29 +481,146 @@ impl ObjectInfo {
         }
     }
 
+    /// Removes the own property ``key``. Returns false if it is not configurable.
+    pub fn delete_property(&mut self, key: &str) -> bool {
+        if let ObjectKind::ModuleNamespace(ref info) = self.kind {
+            return !info.has_export(key);
+        }
+
+        if let ObjectKind::Array(ref mut info) = self.kind {
+            match key.parse::<usize>() {
+                Ok(idx) if idx.to_string() == key => {
+                    if idx < info.elems.len() {
+                        if !info.elems[idx].is_configurable() {
+                            return false;
+                        }
+                        info.elems[idx] = Property::new_data_simple(Value::empty());
+                    }
+                    return true;
+                }
+                _ if key == "length" => return false,
+                _ => {}
+            }
+        }
+
+        match self.property.get(key) {
+            Some(prop) if !prop.is_configurable() => false,
+            Some(_) => {
+                self.property.shift_remove(key);
+                true
+            }
+            None => true,
+        }
+    }
+
+    /// Removes the own property ``key``, which may be a symbol.
+    /// https://tc39.github.io/ecma262/#sec-ordinarydelete
+    pub fn delete_property_by_value(&mut self, key: Value) -> bool {
+        if !key.is_symbol() {
+            return self.delete_property(key.to_string().as_str());
+        }
+
+        let id = key.get_symbol_info().id;
+        match self.sym_property.get(&id) {
+            Some(prop) if !prop.is_configurable() => false,
+            Some(_) => {
+                self.sym_property.remove(&id);
+                true
+            }
+            None => true,
+        }
+    }
+
+    /// Returns false if an assignment to ``key`` is ignored, that is, the property is a
+    /// non-writable data property or an accessor property without a setter.
+    pub fn is_assignable(&self, allocator: &mut MemoryAllocator, key: Value) -> bool {
+        fn is_assignable_property(prop: &Property) -> bool {
+            match prop {
+                Property::Data(DataProperty { writable, .. }) => *writable,
+                Property::Accessor(AccessorProperty { set, .. }) => !set.is_undefined(),
+            }
+        }
+
+        if key.is_string()
+            && key.into_str() == "__proto__"
+            && !self.property.contains_key("__proto__")
+        {
+            return true;
+        }
+
+        // Properties of a module namespace object are never assignable.
+        if let ObjectKind::ModuleNamespace(_) = self.kind {
+            return false;
+        }
+
+        if let ObjectKind::Array(ref info) = self.kind {
+            let idx = key
+                .is_array_index()
+                .or_else(|| key.is_canonical_numeric_index_string(allocator));
+            if let Some(idx) = idx {
+                return match info.elems.get(idx) {
+                    Some(prop) => is_assignable_property(prop),
+                    None => self.extensible,
+                };
+            }
+            if key.is_string() && key.into_str() == "length" {
+                return info.length_writable;
+            }
+        }
+
+        let own_property = if key.is_symbol() {
+            self.sym_property.get(&key.get_symbol_info().id)
+        } else {
+            self.property.get(key.to_string().as_str())
+        };
+
+        match own_property {
+            Some(prop) => is_assignable_property(prop),
+            None => match self.get_inherited_accessor(key) {
+                Some(AccessorProperty { set, .. }) => !set.is_undefined(),
+                None => self.extensible,
+            },
+        }
+    }
+
     pub fn set_property_by_value(
         &mut self,
         allocator: &mut MemoryAllocator,
         key: Value,
         val_: Value,
     ) -> Result<Option<Value>, error::RuntimeError> {
+        // https://tc39.github.io/ecma262/#sec-module-namespace-exotic-objects-set-p-v-receiver
+        if let ObjectKind::ModuleNamespace(_) = self.kind {
+            return Ok(None);
+        }
+
         // Annoying
-        if key.is_string() && key.into_str() == "__proto__" {
+        if key.is_string()
+            && key.into_str() == "__proto__"
+            && !self.property.contains_key("__proto__")
+        {
             self.prototype = val_;
             return Ok(None);
         }
 
+        let extensible = self.extensible;
         match self.kind {
             ObjectKind::Array(ref mut info) => {
-                if let Some(idx) = key.is_array_index() {
-                    return Ok(info.set_element(idx, val_));
-                }
-
-                if let Some(idx) = key.is_canonical_numeric_index_string(allocator) {
+                let idx = key
+                    .is_array_index()
+                    .or_else(|| key.is_canonical_numeric_index_string(allocator));
+                if let Some(idx) = idx {
+                    // A non-extensible array can not grow.
+                    if idx >= info.elems.len() && !extensible {
+                        return Ok(None);
+                    }
                     return Ok(info.set_element(idx, val_));
                 }
 
                 if key.is_string() && key.into_str() == "length" {
+                    if !info.length_writable {
+                        return Ok(None);
+                    }
                     if let Some(new_length) = val_.is_array_index() {
                         info.set_length(new_length);
                         return Ok(None);
@@ -174,6 +630,16 @@ impl ObjectInfo {
             _ => {}
         }
 
+        // A setter may be inherited from the prototype chain.
+        if !self.has_own_property_by_value(key) {
+            if let Some(AccessorProperty { set, .. }) = self.get_inherited_accessor(key) {
+                return Ok(if set.is_undefined() { None } else { Some(set) });
+            }
+            if !self.extensible {
+                return Ok(None);
+            }
+        }
+
         let property = if key.is_symbol() {
             let id = key.get_symbol_info().id;
             self.sym_property
@@ -207,6 +673,79 @@ impl ObjectInfo {
     }
 }
 
+/// Applies ``desc`` to the existing property ``current``, or returns None if it is not allowed.
+fn apply_property_descriptor(current: Property, desc: &PropertyDescriptor) -> Option<Property> {
+    let current_configurable = current.is_configurable();
+    let current_enumerable = current.is_enumerable();
+    if !current_configurable
+        && (desc.configurable == Some(true)
+            || desc.enumerable.is_some_and(|e| e != current_enumerable))
+    {
+        return None;
+    }
+    let enumerable = desc.enumerable.unwrap_or(current_enumerable);
+    let configurable = desc.configurable.unwrap_or(current_configurable);
+    let is_accessor_descriptor = desc.get.is_some() || desc.set.is_some();
+    let is_data_descriptor = desc.value.is_some() || desc.writable.is_some();
+
+    let prop = match current {
+        // Converts a data property into an accessor property.
+        Property::Data(_) if is_accessor_descriptor => {
+            if !current_configurable {
+                return None;
+            }
+            Property::Accessor(AccessorProperty {
+                get: desc.get.unwrap_or_else(Value::undefined),
+                set: desc.set.unwrap_or_else(Value::undefined),
+                enumerable,
+                configurable,
+            })
+        }
+        // Converts an accessor property into a data property.
+        Property::Accessor(_) if is_data_descriptor => {
+            if !current_configurable {
+                return None;
+            }
+            Property::Data(DataProperty {
+                val: desc.value.unwrap_or_else(Value::undefined),
+                writable: desc.writable.unwrap_or(false),
+                enumerable,
+                configurable,
+            })
+        }
+        Property::Data(data) => {
+            if !current_configurable
+                && !data.writable
+                && (desc.writable == Some(true)
+                    || desc.value.is_some_and(|val| !val.same_value(data.val)))
+            {
+                return None;
+            }
+            Property::Data(DataProperty {
+                val: desc.value.unwrap_or(data.val),
+                writable: desc.writable.unwrap_or(data.writable),
+                enumerable,
+                configurable,
+            })
+        }
+        Property::Accessor(accessor) => {
+            if !current_configurable
+                && (desc.get.is_some_and(|get| !get.same_value(accessor.get))
+                    || desc.set.is_some_and(|set| !set.same_value(accessor.set)))
+            {
+                return None;
+            }
+            Property::Accessor(AccessorProperty {
+                get: desc.get.unwrap_or(accessor.get),
+                set: desc.set.unwrap_or(accessor.set),
+                enumerable,
+                configurable,
+            })
+        }
+    };
+    Some(prop)
+}
+
 impl Property {
     pub fn new_data(data: DataProperty) -> Self {
         Property::Data(data)
@@ -221,6 +760,20 @@ impl Property {
         })
     }
 
+    pub fn is_enumerable(&self) -> bool {
+        match self {
+            Property::Data(DataProperty { enumerable, .. })
+            | Property::Accessor(AccessorProperty { enumerable, .. }) => *enumerable,
+        }
+    }
+
+    pub fn is_configurable(&self) -> bool {
+        match self {
+            Property::Data(DataProperty { configurable, .. })
+            | Property::Accessor(AccessorProperty { configurable, .. }) => *configurable,
+        }
+    }
+
     pub fn as_data(self) -> DataProperty {
         match self {
             Property::Data(data) => data,
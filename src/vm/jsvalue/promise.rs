@@ -0,0 +1,92 @@
+use super::value::*;
+use crate::vm::error::RuntimeError;
+use crate::vm::exec_context::ExecContext;
+use std::collections::VecDeque;
+
+/// Internal slots of a Promise object.
+/// https://tc39.github.io/ecma262/#sec-properties-of-promise-instances
+#[derive(Clone, Debug)]
+pub struct PromiseInfo {
+    /// \[\[PromiseState\]\]
+    pub state: PromiseState,
+    /// \[\[PromiseResult\]\]. Undefined while pending.
+    pub result: Value,
+    /// \[\[PromiseFulfillReactions\]\]
+    pub fulfill_reactions: Vec<PromiseReaction>,
+    /// \[\[PromiseRejectReactions\]\]
+    pub reject_reactions: Vec<PromiseReaction>,
+    /// \[\[PromiseIsHandled\]\]
+    pub is_handled: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Copy)]
+pub enum PromiseState {
+    Pending,
+    Fulfilled,
+    Rejected,
+}
+
+/// https://tc39.github.io/ecma262/#sec-promisereaction-records
+#[derive(Clone, Debug)]
+pub struct PromiseReaction {
+    /// \[\[Capability\]\]. The promise to settle with the result of the handler.
+    /// None if the reaction is only for the handler, e.g. resuming an ``await``.
+    pub capability: Option<Value>,
+    /// \[\[Type\]\]
+    pub kind: ReactionKind,
+    /// \[\[Handler\]\]. Undefined if the result passes through.
+    pub handler: Value,
+}
+
+#[derive(Clone, Debug, PartialEq, Copy)]
+pub enum ReactionKind {
+    Fulfill,
+    Reject,
+}
+
+/// A job in the job queue of the VM.
+/// https://tc39.github.io/ecma262/#sec-jobs-and-job-queues
+#[derive(Clone, Debug)]
+pub enum Job {
+    /// https://tc39.github.io/ecma262/#sec-promisereactionjob
+    PromiseReaction {
+        reaction: PromiseReaction,
+        argument: Value,
+    },
+    /// https://tc39.github.io/ecma262/#sec-promiseresolvethenablejob
+    PromiseResolveThenable {
+        promise: Value,
+        thenable: Value,
+        then: Value,
+    },
+}
+
+/// Jobs waiting to run after the script, and rejected promises without handlers.
+#[derive(Clone, Debug, Default)]
+pub struct JobQueue {
+    pub jobs: VecDeque<Job>,
+    /// Reported after the jobs run unless a handler is added.
+    /// https://tc39.github.io/ecma262/#sec-host-promise-rejection-tracker
+    pub rejected_promises: Vec<(Value, RuntimeError)>,
+}
+
+/// The state of an async function call, kept by the functions that resume it after ``await``.
+#[derive(Clone, Debug)]
+pub struct AsyncFunctionInfo {
+    /// The execution context suspended at ``await``. None while running.
+    pub context: Option<ExecContext>,
+    /// The promise returned by the call.
+    pub promise: Value,
+}
+
+impl Default for PromiseInfo {
+    fn default() -> Self {
+        PromiseInfo {
+            state: PromiseState::Pending,
+            result: Value::undefined(),
+            fulfill_reactions: vec![],
+            reject_reactions: vec![],
+            is_handled: false,
+        }
+    }
+}
@@ -1,8 +1,12 @@
 #![macro_use]
 use super::value::*;
 use super::value::Value;
+use crate::builtin::BuiltinFuncTy;
 use crate::builtins;
-use crate::builtins::{array, function};
+use crate::builtins::{
+    array, date, error, function, generator, iterator, map, number, object, promise, regexp, set,
+    string,
+};
 use crate::vm::vm::Factory;
 use rustc_hash::FxHashMap;
 
@@ -14,6 +18,145 @@ pub struct ObjectPrototypes {
     pub array: Value,
     pub symbol: Value,
     pub error: Value,
+    pub eval_error: Value,
+    pub range_error: Value,
+    pub reference_error: Value,
+    pub syntax_error: Value,
+    pub type_error: Value,
+    pub uri_error: Value,
+    pub aggregate_error: Value,
+    pub iterator: Value,
+    pub array_iterator: Value,
+    pub string_iterator: Value,
+    pub regexp: Value,
+    pub regexp_string_iterator: Value,
+    pub promise: Value,
+    pub generator: Value,
+    pub date: Value,
+    pub number: Value,
+    pub map: Value,
+    pub map_iterator: Value,
+    pub set: Value,
+    pub set_iterator: Value,
+    pub weak_map: Value,
+    pub weak_set: Value,
+}
+
+/// Symbol-keyed built-in methods are writable and configurable, but not enumerable.
+fn set_symbol_method(obj: Value, symbol: Value, method: Value) {
+    obj.get_object_info().sym_property.insert(
+        symbol.get_symbol_info().id,
+        Property::Data(DataProperty {
+            val: method,
+            writable: true,
+            enumerable: false,
+            configurable: true,
+        }),
+    );
+}
+
+/// Built-in methods are writable and configurable, but not enumerable.
+fn set_methods(
+    factory: &mut Factory,
+    function_prototype: Value,
+    obj: Value,
+    methods: &[(&str, BuiltinFuncTy, usize)],
+) {
+    for (name, func, length) in methods {
+        let method = Value::builtin_function_with_proto(
+            &mut factory.memory_allocator,
+            function_prototype,
+            *name,
+            *func,
+            *length,
+        );
+        obj.get_object_info().property.insert(
+            name.to_string(),
+            Property::Data(DataProperty {
+                val: method,
+                writable: true,
+                enumerable: false,
+                configurable: true,
+            }),
+        );
+    }
+}
+
+/// An accessor property with only a getter, which is configurable but not enumerable.
+fn set_getter(
+    factory: &mut Factory,
+    function_prototype: Value,
+    obj: Value,
+    name: &str,
+    func: BuiltinFuncTy,
+) {
+    let get = Value::builtin_function_with_proto(
+        &mut factory.memory_allocator,
+        function_prototype,
+        format!("get {}", name),
+        func,
+        0,
+    );
+    obj.get_object_info().property.insert(
+        name.to_string(),
+        Property::Accessor(AccessorProperty {
+            get,
+            set: Value::undefined(),
+            enumerable: false,
+            configurable: true,
+        }),
+    );
+}
+
+/// An accessor property with a getter and a setter, which is configurable but not enumerable.
+fn set_accessor(
+    factory: &mut Factory,
+    function_prototype: Value,
+    obj: Value,
+    name: &str,
+    get: BuiltinFuncTy,
+    set: BuiltinFuncTy,
+) {
+    let get = Value::builtin_function_with_proto(
+        &mut factory.memory_allocator,
+        function_prototype,
+        format!("get {}", name),
+        get,
+        0,
+    );
+    let set = Value::builtin_function_with_proto(
+        &mut factory.memory_allocator,
+        function_prototype,
+        format!("set {}", name),
+        set,
+        1,
+    );
+    obj.get_object_info().property.insert(
+        name.to_string(),
+        Property::Accessor(AccessorProperty {
+            get,
+            set,
+            enumerable: false,
+            configurable: true,
+        }),
+    );
+}
+
+/// The prototype of a NativeError or AggregateError, which inherits from %Error.prototype%.
+/// https://tc39.github.io/ecma262/#sec-properties-of-the-nativeerror-prototype-objects
+fn native_error_prototype(factory: &mut Factory, error_prototype: Value, name: &str) -> Value {
+    let name = factory.string(name);
+    let message = factory.string("");
+    Value::Object(factory.alloc(ObjectInfo {
+        kind: ObjectKind::Ordinary,
+        prototype: error_prototype,
+        property: make_property_map!(
+            message => true, false, true: message,
+            name    => true, false, true: name
+        ),
+        sym_property: FxHashMap::default(),
+        extensible: true,
+    }))
 }
 
 impl ObjectPrototypes {
@@ -23,6 +166,7 @@ impl ObjectPrototypes {
             prototype: Value::null(),
             property: make_property_map!(),
             sym_property: FxHashMap::default(),
+            extensible: true,
         }));
         let default_func_ref = factory.get_default_func_ref();
         // https://www.ecma-international.org/ecma-262/9.0/index.html#sec-properties-of-the-function-prototype-object
@@ -35,102 +179,867 @@ impl ObjectPrototypes {
                 prototype: object_prototype,
                 property: make_property_map!(),
                 sym_property: FxHashMap::default(),
+                extensible: true,
             }));
 
+            let function_prototype_apply = Value::builtin_function_with_proto(
+                &mut factory.memory_allocator,
+                function_prototype,
+                "apply",
+                function::function_prototype_apply,
+                2,
+            );
+
+            let function_prototype_bind = Value::builtin_function_with_proto(
+                &mut factory.memory_allocator,
+                function_prototype,
+                "bind",
+                function::function_prototype_bind,
+                1,
+            );
+
             let function_prototype_call = Value::builtin_function_with_proto(
                 &mut factory.memory_allocator,
                 function_prototype,
                 "call",
                 function::function_prototype_call,
+                1,
+            );
+
+            let function_prototype_to_string = Value::builtin_function_with_proto(
+                &mut factory.memory_allocator,
+                function_prototype,
+                "toString",
+                function::function_prototype_to_string,
+                0,
+            );
+
+            let function_prototype_has_instance = Value::builtin_function_with_proto(
+                &mut factory.memory_allocator,
+                function_prototype,
+                "[Symbol.hasInstance]",
+                function::function_prototype_has_instance,
+                1,
             );
 
             let mut info = function_prototype.get_object_info();
             info.prototype = object_prototype;
-            info.property = make_property_map!(call: function_prototype_call);
+            let name = factory.string("");
+            info.property = make_property_map!(
+                length   => false, false, true: Value::Number(0.0),
+                name     => false, false, true: name,
+                apply    => true,  false, true: function_prototype_apply,
+                bind     => true,  false, true: function_prototype_bind,
+                call     => true,  false, true: function_prototype_call,
+                toString => true,  false, true: function_prototype_to_string
+            );
+            // Neither writable nor configurable, so that instanceof can not be broken globally.
+            info.sym_property.insert(
+                factory.symbols.has_instance.get_symbol_info().id,
+                Property::Data(DataProperty {
+                    val: function_prototype_has_instance,
+                    writable: false,
+                    enumerable: false,
+                    configurable: false,
+                }),
+            );
 
             function_prototype
         };
 
+        // https://tc39.github.io/ecma262/#sec-properties-of-the-object-prototype-object
+        // Defined here since the methods need Function.prototype.
+        set_methods(
+            factory,
+            function_prototype,
+            object_prototype,
+            &[
+                ("hasOwnProperty", object::object_prototype_has_own_property, 1),
+                ("isPrototypeOf", object::object_prototype_is_prototype_of, 1),
+                ("propertyIsEnumerable", object::object_prototype_property_is_enumerable, 1),
+                ("toString", object::object_prototype_to_string, 0),
+                ("valueOf", object::object_prototype_value_of, 0),
+            ],
+        );
+
+        // https://tc39.github.io/ecma262/#sec-properties-of-the-string-prototype-object
         let string_prototype = {
-            let index_of = Value::builtin_function_with_proto(
+            // String.prototype is itself a String object whose value is the empty String.
+            let empty = factory.string("");
+            let string_prototype = Value::Object(factory.alloc(ObjectInfo {
+                kind: ObjectKind::String(empty),
+                prototype: object_prototype,
+                property: make_property_map!(
+                    length => false, false, false : Value::Number(0.0)
+                ),
+                sym_property: FxHashMap::default(),
+                extensible: true,
+            }));
+
+            let methods: [(&str, BuiltinFuncTy, usize); 31] = [
+                ("charAt", string::string_prototype_char_at, 1),
+                ("charCodeAt", string::string_prototype_char_code_at, 1),
+                ("codePointAt", string::string_prototype_code_point_at, 1),
+                ("concat", string::string_prototype_concat, 1),
+                ("endsWith", string::string_prototype_ends_with, 1),
+                ("includes", string::string_prototype_includes, 1),
+                ("indexOf", string::string_prototype_index_of, 1),
+                ("lastIndexOf", string::string_prototype_last_index_of, 1),
+                ("localeCompare", string::string_prototype_locale_compare, 1),
+                ("match", string::string_prototype_match, 1),
+                ("matchAll", string::string_prototype_match_all, 1),
+                ("normalize", string::string_prototype_normalize, 0),
+                ("padEnd", string::string_prototype_pad_end, 1),
+                ("padStart", string::string_prototype_pad_start, 1),
+                ("repeat", string::string_prototype_repeat, 1),
+                ("replace", string::string_prototype_replace, 2),
+                ("replaceAll", string::string_prototype_replace_all, 2),
+                ("search", string::string_prototype_search, 1),
+                ("slice", string::string_prototype_slice, 2),
+                ("split", string::string_prototype_split, 2),
+                ("startsWith", string::string_prototype_starts_with, 1),
+                ("substring", string::string_prototype_substring, 2),
+                ("toLocaleLowerCase", string::string_prototype_to_lower_case, 0),
+                ("toLocaleUpperCase", string::string_prototype_to_upper_case, 0),
+                ("toLowerCase", string::string_prototype_to_lower_case, 0),
+                ("toString", string::string_prototype_to_string, 0),
+                ("toUpperCase", string::string_prototype_to_upper_case, 0),
+                ("trim", string::string_prototype_trim, 0),
+                ("trimEnd", string::string_prototype_trim_end, 0),
+                ("trimStart", string::string_prototype_trim_start, 0),
+                ("valueOf", string::string_prototype_value_of, 0),
+            ];
+            for (name, func, length) in methods.iter() {
+                let method = Value::builtin_function_with_proto(
+                    &mut factory.memory_allocator,
+                    function_prototype,
+                    *name,
+                    *func,
+                    *length,
+                );
+                string_prototype.get_object_info().property.insert(
+                    name.to_string(),
+                    Property::Data(DataProperty {
+                        val: method,
+                        writable: true,
+                        enumerable: false,
+                        configurable: true,
+                    }),
+                );
+            }
+            // Annex B: trimLeft and trimRight are the same function objects as trimStart and trimEnd.
+            for (alias, name) in [("trimLeft", "trimStart"), ("trimRight", "trimEnd")].iter() {
+                let method = string_prototype.get_property(name);
+                string_prototype.get_object_info().property.insert(
+                    alias.to_string(),
+                    Property::Data(DataProperty {
+                        val: method,
+                        writable: true,
+                        enumerable: false,
+                        configurable: true,
+                    }),
+                );
+            }
+
+            let iterator = Value::builtin_function_with_proto(
+                &mut factory.memory_allocator,
+                function_prototype,
+                "[Symbol.iterator]",
+                builtins::string::string_prototype_iterator,
+                0,
+            );
+            set_symbol_method(string_prototype, factory.symbols.iterator, iterator);
+
+            string_prototype
+        };
+
+        let array_prototype = {
+            let array_prototype = Value::Object(factory.alloc(ObjectInfo {
+                kind: ObjectKind::Array(ArrayObjectInfo::new(vec![])),
+                prototype: object_prototype,
+                property: make_property_map!(
+                    length => false, false, true : Value::Number(0.0)
+                ),
+                sym_property: FxHashMap::default(),
+                extensible: true,
+            }));
+
+            let methods: [(&str, BuiltinFuncTy, usize); 30] = [
+                ("concat", array::array_prototype_concat, 1),
+                ("copyWithin", array::array_prototype_copy_within, 2),
+                ("entries", array::array_prototype_entries, 0),
+                ("every", array::array_prototype_every, 1),
+                ("fill", array::array_prototype_fill, 1),
+                ("filter", array::array_prototype_filter, 1),
+                ("find", array::array_prototype_find, 1),
+                ("findIndex", array::array_prototype_find_index, 1),
+                ("flat", array::array_prototype_flat, 0),
+                ("flatMap", array::array_prototype_flat_map, 1),
+                ("forEach", array::array_prototype_for_each, 1),
+                ("includes", array::array_prototype_includes, 1),
+                ("indexOf", array::array_prototype_index_of, 1),
+                ("join", array::array_prototype_join, 1),
+                ("keys", array::array_prototype_keys, 0),
+                ("lastIndexOf", array::array_prototype_last_index_of, 1),
+                ("map", array::array_prototype_map, 1),
+                ("pop", array::array_prototype_pop, 0),
+                ("push", array::array_prototype_push, 1),
+                ("reduce", array::array_prototype_reduce, 1),
+                ("reduceRight", array::array_prototype_reduce_right, 1),
+                ("reverse", array::array_prototype_reverse, 0),
+                ("shift", array::array_prototype_shift, 0),
+                ("slice", array::array_prototype_slice, 2),
+                ("some", array::array_prototype_some, 1),
+                ("sort", array::array_prototype_sort, 1),
+                ("splice", array::array_prototype_splice, 2),
+                ("toString", array::array_prototype_to_string, 0),
+                ("unshift", array::array_prototype_unshift, 1),
+                ("values", array::array_prototype_values, 0),
+            ];
+            for (name, func, length) in methods.iter() {
+                let method = Value::builtin_function_with_proto(
+                    &mut factory.memory_allocator,
+                    function_prototype,
+                    *name,
+                    *func,
+                    *length,
+                );
+                array_prototype.get_object_info().property.insert(
+                    name.to_string(),
+                    Property::Data(DataProperty {
+                        val: method,
+                        writable: true,
+                        enumerable: false,
+                        configurable: true,
+                    }),
+                );
+            }
+
+            // Array.prototype[@@iterator] is the same function object as Array.prototype.values.
+            let values = array_prototype.get_property("values");
+            set_symbol_method(array_prototype, factory.symbols.iterator, values);
+
+            array_prototype
+        };
+
+        let symbol_prototype = {
+            Value::Object(factory.alloc(ObjectInfo {
+                kind: ObjectKind::Ordinary,
+                prototype: object_prototype,
+                // TODO: https://tc39.github.io/ecma262/#sec-properties-of-the-symbol-prototype-object
+                property: make_property_map!(),
+                sym_property: FxHashMap::default(),
+                extensible: true,
+            }))
+        };
+
+        factory.symbols.set_prototype(symbol_prototype);
+
+        // https://tc39.github.io/ecma262/#sec-properties-of-the-error-prototype-object
+        let error_prototype = {
+            let name = factory.string("Error");
+            let message = factory.string("");
+            let error_prototype = Value::Object(factory.alloc(ObjectInfo {
+                kind: ObjectKind::Ordinary,
+                prototype: object_prototype,
+                property: make_property_map!(
+                    message => true, false, true: message,
+                    name    => true, false, true: name
+                ),
+                sym_property: FxHashMap::default(),
+                extensible: true,
+            }));
+            set_methods(
+                factory,
+                function_prototype,
+                error_prototype,
+                &[("toString", error::error_prototype_to_string, 0)],
+            );
+            set_accessor(
+                factory,
+                function_prototype,
+                error_prototype,
+                "stack",
+                error::error_prototype_stack,
+                error::error_prototype_set_stack,
+            );
+            error_prototype
+        };
+
+        let eval_error_prototype = native_error_prototype(factory, error_prototype, "EvalError");
+        let range_error_prototype = native_error_prototype(factory, error_prototype, "RangeError");
+        let reference_error_prototype =
+            native_error_prototype(factory, error_prototype, "ReferenceError");
+        let syntax_error_prototype =
+            native_error_prototype(factory, error_prototype, "SyntaxError");
+        let type_error_prototype = native_error_prototype(factory, error_prototype, "TypeError");
+        let uri_error_prototype = native_error_prototype(factory, error_prototype, "URIError");
+        let aggregate_error_prototype =
+            native_error_prototype(factory, error_prototype, "AggregateError");
+
+        // https://tc39.github.io/ecma262/#sec-%iteratorprototype%-object
+        let iterator_prototype = {
+            let iterator_prototype = Value::Object(factory.alloc(ObjectInfo {
+                kind: ObjectKind::Ordinary,
+                prototype: object_prototype,
+                property: make_property_map!(),
+                sym_property: FxHashMap::default(),
+                extensible: true,
+            }));
+
+            let iterator = Value::builtin_function_with_proto(
                 &mut factory.memory_allocator,
                 function_prototype,
-                "indexOf",
-                builtins::string::string_prototype_index_of,
+                "[Symbol.iterator]",
+                iterator::iterator_prototype_iterator,
+                0,
             );
+            set_symbol_method(iterator_prototype, factory.symbols.iterator, iterator);
+
+            iterator_prototype
+        };
 
-            let split = Value::builtin_function_with_proto(
+        // https://tc39.github.io/ecma262/#sec-%arrayiteratorprototype%-object
+        let array_iterator_prototype = {
+            let next = Value::builtin_function_with_proto(
                 &mut factory.memory_allocator,
                 function_prototype,
-                "split",
-                builtins::string::string_prototype_split,
+                "next",
+                array::array_iterator_prototype_next,
+                0,
             );
 
             Value::Object(factory.alloc(ObjectInfo {
+                kind: ObjectKind::Ordinary,
+                prototype: iterator_prototype,
+                property: make_property_map!(
+                    next => true, false, true : next
+                ),
+                sym_property: FxHashMap::default(),
+                extensible: true,
+            }))
+        };
+
+        // https://tc39.github.io/ecma262/#sec-%stringiteratorprototype%-object
+        let string_iterator_prototype = {
+            let next = Value::builtin_function_with_proto(
+                &mut factory.memory_allocator,
+                function_prototype,
+                "next",
+                builtins::string::string_iterator_prototype_next,
+                0,
+            );
+
+            Value::Object(factory.alloc(ObjectInfo {
+                kind: ObjectKind::Ordinary,
+                prototype: iterator_prototype,
+                property: make_property_map!(
+                    next => true, false, true : next
+                ),
+                sym_property: FxHashMap::default(),
+                extensible: true,
+            }))
+        };
+
+        // https://tc39.github.io/ecma262/#sec-properties-of-the-regexp-prototype-object
+        let regexp_prototype = {
+            let exec = Value::builtin_function_with_proto(
+                &mut factory.memory_allocator,
+                function_prototype,
+                "exec",
+                regexp::regexp_prototype_exec,
+                1,
+            );
+
+            let test = Value::builtin_function_with_proto(
+                &mut factory.memory_allocator,
+                function_prototype,
+                "test",
+                regexp::regexp_prototype_test,
+                1,
+            );
+
+            let to_string = Value::builtin_function_with_proto(
+                &mut factory.memory_allocator,
+                function_prototype,
+                "toString",
+                regexp::regexp_prototype_to_string,
+                0,
+            );
+
+            let regexp_prototype = Value::Object(factory.alloc(ObjectInfo {
                 kind: ObjectKind::Ordinary,
                 prototype: object_prototype,
-                property: make_property_map!(indexOf: index_of, split: split),
+                property: make_property_map!(
+                    exec     => true, false, true : exec,
+                    test     => true, false, true : test,
+                    toString => true, false, true : to_string
+                ),
                 sym_property: FxHashMap::default(),
+                extensible: true,
+            }));
+
+            let getters: [(&str, BuiltinFuncTy); 8] = [
+                ("source", regexp::regexp_prototype_source),
+                ("flags", regexp::regexp_prototype_flags),
+                ("global", regexp::regexp_prototype_global),
+                ("ignoreCase", regexp::regexp_prototype_ignore_case),
+                ("multiline", regexp::regexp_prototype_multiline),
+                ("dotAll", regexp::regexp_prototype_dot_all),
+                ("unicode", regexp::regexp_prototype_unicode),
+                ("sticky", regexp::regexp_prototype_sticky),
+            ];
+            for (name, func) in getters.iter() {
+                let get = Value::builtin_function_with_proto(
+                    &mut factory.memory_allocator,
+                    function_prototype,
+                    format!("get {}", name),
+                    *func,
+                    0,
+                );
+                regexp_prototype.get_object_info().property.insert(
+                    name.to_string(),
+                    Property::Accessor(AccessorProperty {
+                        get,
+                        set: Value::undefined(),
+                        enumerable: false,
+                        configurable: true,
+                    }),
+                );
+            }
+
+            let symbol_methods: [(Value, &str, BuiltinFuncTy, usize); 5] = [
+                (factory.symbols.match_, "[Symbol.match]", regexp::regexp_prototype_match, 1),
+                (
+                    factory.symbols.match_all,
+                    "[Symbol.matchAll]",
+                    regexp::regexp_prototype_match_all,
+                    1,
+                ),
+                (factory.symbols.replace, "[Symbol.replace]", regexp::regexp_prototype_replace, 2),
+                (factory.symbols.search, "[Symbol.search]", regexp::regexp_prototype_search, 1),
+                (factory.symbols.split, "[Symbol.split]", regexp::regexp_prototype_split, 2),
+            ];
+            for (symbol, name, func, length) in symbol_methods.iter() {
+                let method = Value::builtin_function_with_proto(
+                    &mut factory.memory_allocator,
+                    function_prototype,
+                    *name,
+                    *func,
+                    *length,
+                );
+                set_symbol_method(regexp_prototype, *symbol, method);
+            }
+
+            regexp_prototype
+        };
+
+        // https://tc39.github.io/ecma262/#sec-%regexpstringiteratorprototype%-object
+        let regexp_string_iterator_prototype = {
+            let next = Value::builtin_function_with_proto(
+                &mut factory.memory_allocator,
+                function_prototype,
+                "next",
+                regexp::regexp_string_iterator_prototype_next,
+                0,
+            );
+
+            Value::Object(factory.alloc(ObjectInfo {
+                kind: ObjectKind::Ordinary,
+                prototype: iterator_prototype,
+                property: make_property_map!(
+                    next => true, false, true : next
+                ),
+                sym_property: FxHashMap::default(),
+                extensible: true,
             }))
         };
 
-        let array_prototype = {
-            let push = Value::builtin_function_with_proto(
+        // https://tc39.github.io/ecma262/#sec-properties-of-the-promise-prototype-object
+        let promise_prototype = {
+            let then = Value::builtin_function_with_proto(
                 &mut factory.memory_allocator,
                 function_prototype,
-                "push",
-                array::array_prototype_push,
+                "then",
+                promise::promise_prototype_then,
+                2,
             );
 
-            let map = Value::builtin_function_with_proto(
+            let catch = Value::builtin_function_with_proto(
                 &mut factory.memory_allocator,
                 function_prototype,
-                "map",
-                array::array_prototype_map,
+                "catch",
+                promise::promise_prototype_catch,
+                1,
             );
 
-            let join = Value::builtin_function_with_proto(
+            let finally = Value::builtin_function_with_proto(
                 &mut factory.memory_allocator,
                 function_prototype,
-                "join",
-                array::array_prototype_join,
+                "finally",
+                promise::promise_prototype_finally,
+                1,
             );
 
             Value::Object(factory.alloc(ObjectInfo {
-                kind: ObjectKind::Array(ArrayObjectInfo { elems: vec![] }),
+                kind: ObjectKind::Ordinary,
                 prototype: object_prototype,
                 property: make_property_map!(
-                    length => false, false, true : Value::Number(0.0),
-                    join   => true,  false, true : join,
-                    push   => true,  false, true : push,
-                    map    => true,  false, true : map
+                    then    => true, false, true : then,
+                    catch   => true, false, true : catch,
+                    finally => true, false, true : finally
                 ),
                 sym_property: FxHashMap::default(),
+                extensible: true,
             }))
         };
 
-        let symbol_prototype = {
+        // https://tc39.github.io/ecma262/#sec-properties-of-generator-prototype
+        let generator_prototype = {
+            let next = Value::builtin_function_with_proto(
+                &mut factory.memory_allocator,
+                function_prototype,
+                "next",
+                generator::generator_prototype_next,
+                1,
+            );
+
+            let return_ = Value::builtin_function_with_proto(
+                &mut factory.memory_allocator,
+                function_prototype,
+                "return",
+                generator::generator_prototype_return,
+                1,
+            );
+
+            let throw = Value::builtin_function_with_proto(
+                &mut factory.memory_allocator,
+                function_prototype,
+                "throw",
+                generator::generator_prototype_throw,
+                1,
+            );
+
             Value::Object(factory.alloc(ObjectInfo {
+                kind: ObjectKind::Ordinary,
+                prototype: iterator_prototype,
+                property: make_property_map!(
+                    next   => true, false, true : next,
+                    return => true, false, true : return_,
+                    throw  => true, false, true : throw
+                ),
+                sym_property: FxHashMap::default(),
+                extensible: true,
+            }))
+        };
+
+        // https://tc39.github.io/ecma262/#sec-properties-of-the-date-prototype-object
+        let date_prototype = {
+            let date_prototype = Value::Object(factory.alloc(ObjectInfo {
                 kind: ObjectKind::Ordinary,
                 prototype: object_prototype,
-                // TODO: https://tc39.github.io/ecma262/#sec-properties-of-the-symbol-prototype-object
                 property: make_property_map!(),
                 sym_property: FxHashMap::default(),
-            }))
+                extensible: true,
+            }));
+
+            let methods: [(&str, BuiltinFuncTy, usize); 43] = [
+                ("getDate", date::date_prototype_get_date, 0),
+                ("getDay", date::date_prototype_get_day, 0),
+                ("getFullYear", date::date_prototype_get_full_year, 0),
+                ("getHours", date::date_prototype_get_hours, 0),
+                ("getMilliseconds", date::date_prototype_get_milliseconds, 0),
+                ("getMinutes", date::date_prototype_get_minutes, 0),
+                ("getMonth", date::date_prototype_get_month, 0),
+                ("getSeconds", date::date_prototype_get_seconds, 0),
+                ("getTime", date::date_prototype_get_time, 0),
+                ("getTimezoneOffset", date::date_prototype_get_timezone_offset, 0),
+                ("getUTCDate", date::date_prototype_get_utc_date, 0),
+                ("getUTCDay", date::date_prototype_get_utc_day, 0),
+                ("getUTCFullYear", date::date_prototype_get_utc_full_year, 0),
+                ("getUTCHours", date::date_prototype_get_utc_hours, 0),
+                ("getUTCMilliseconds", date::date_prototype_get_utc_milliseconds, 0),
+                ("getUTCMinutes", date::date_prototype_get_utc_minutes, 0),
+                ("getUTCMonth", date::date_prototype_get_utc_month, 0),
+                ("getUTCSeconds", date::date_prototype_get_utc_seconds, 0),
+                ("setDate", date::date_prototype_set_date, 1),
+                ("setFullYear", date::date_prototype_set_full_year, 3),
+                ("setHours", date::date_prototype_set_hours, 4),
+                ("setMilliseconds", date::date_prototype_set_milliseconds, 1),
+                ("setMinutes", date::date_prototype_set_minutes, 3),
+                ("setMonth", date::date_prototype_set_month, 2),
+                ("setSeconds", date::date_prototype_set_seconds, 2),
+                ("setTime", date::date_prototype_set_time, 1),
+                ("setUTCDate", date::date_prototype_set_utc_date, 1),
+                ("setUTCFullYear", date::date_prototype_set_utc_full_year, 3),
+                ("setUTCHours", date::date_prototype_set_utc_hours, 4),
+                ("setUTCMilliseconds", date::date_prototype_set_utc_milliseconds, 1),
+                ("setUTCMinutes", date::date_prototype_set_utc_minutes, 3),
+                ("setUTCMonth", date::date_prototype_set_utc_month, 2),
+                ("setUTCSeconds", date::date_prototype_set_utc_seconds, 2),
+                ("toDateString", date::date_prototype_to_date_string, 0),
+                ("toISOString", date::date_prototype_to_iso_string, 0),
+                ("toJSON", date::date_prototype_to_json, 1),
+                ("toLocaleDateString", date::date_prototype_to_locale_date_string, 0),
+                ("toLocaleString", date::date_prototype_to_locale_string, 0),
+                ("toLocaleTimeString", date::date_prototype_to_locale_time_string, 0),
+                ("toString", date::date_prototype_to_string, 0),
+                ("toTimeString", date::date_prototype_to_time_string, 0),
+                ("toUTCString", date::date_prototype_to_utc_string, 0),
+                ("valueOf", date::date_prototype_value_of, 0),
+            ];
+            for (name, func, length) in methods.iter() {
+                let method = Value::builtin_function_with_proto(
+                    &mut factory.memory_allocator,
+                    function_prototype,
+                    *name,
+                    *func,
+                    *length,
+                );
+                date_prototype.get_object_info().property.insert(
+                    name.to_string(),
+                    Property::Data(DataProperty {
+                        val: method,
+                        writable: true,
+                        enumerable: false,
+                        configurable: true,
+                    }),
+                );
+            }
+
+            let to_primitive = Value::builtin_function_with_proto(
+                &mut factory.memory_allocator,
+                function_prototype,
+                "[Symbol.toPrimitive]",
+                date::date_prototype_to_primitive,
+                1,
+            );
+            // Not writable, so that assignment can not override the hint handling.
+            date_prototype.get_object_info().sym_property.insert(
+                factory.symbols.to_primitive.get_symbol_info().id,
+                Property::Data(DataProperty {
+                    val: to_primitive,
+                    writable: false,
+                    enumerable: false,
+                    configurable: true,
+                }),
+            );
+
+            date_prototype
         };
 
-        let error_prototype = {
+        // https://tc39.github.io/ecma262/#sec-properties-of-the-number-prototype-object
+        let number_prototype = {
+            let to_exponential = Value::builtin_function_with_proto(
+                &mut factory.memory_allocator,
+                function_prototype,
+                "toExponential",
+                number::number_prototype_to_exponential,
+                1,
+            );
+
+            let to_fixed = Value::builtin_function_with_proto(
+                &mut factory.memory_allocator,
+                function_prototype,
+                "toFixed",
+                number::number_prototype_to_fixed,
+                1,
+            );
+
+            let to_locale_string = Value::builtin_function_with_proto(
+                &mut factory.memory_allocator,
+                function_prototype,
+                "toLocaleString",
+                number::number_prototype_to_locale_string,
+                0,
+            );
+
+            let to_precision = Value::builtin_function_with_proto(
+                &mut factory.memory_allocator,
+                function_prototype,
+                "toPrecision",
+                number::number_prototype_to_precision,
+                1,
+            );
+
+            let to_string = Value::builtin_function_with_proto(
+                &mut factory.memory_allocator,
+                function_prototype,
+                "toString",
+                number::number_prototype_to_string,
+                1,
+            );
+
+            let value_of = Value::builtin_function_with_proto(
+                &mut factory.memory_allocator,
+                function_prototype,
+                "valueOf",
+                number::number_prototype_value_of,
+                0,
+            );
+
+            // Number.prototype is itself a Number object whose value is +0.
             Value::Object(factory.alloc(ObjectInfo {
-                kind: ObjectKind::Error(ErrorObjectInfo {
-                    stack_trace: "".to_string(),
-                }),
+                kind: ObjectKind::Number(0.0),
                 prototype: object_prototype,
-                // TODO: https://tc39.github.io/ecma262/#sec-properties-of-the-error-prototype-object
-                property: make_property_map!(),
+                property: make_property_map!(
+                    toExponential  => true, false, true : to_exponential,
+                    toFixed        => true, false, true : to_fixed,
+                    toLocaleString => true, false, true : to_locale_string,
+                    toPrecision    => true, false, true : to_precision,
+                    toString       => true, false, true : to_string,
+                    valueOf        => true, false, true : value_of
+                ),
                 sym_property: FxHashMap::default(),
+                extensible: true,
             }))
         };
 
+        // https://tc39.github.io/ecma262/#sec-properties-of-the-map-prototype-object
+        let map_prototype = {
+            let map_prototype = Value::Object(factory.alloc(ObjectInfo {
+                kind: ObjectKind::Ordinary,
+                prototype: object_prototype,
+                property: make_property_map!(),
+                sym_property: FxHashMap::default(),
+                extensible: true,
+            }));
+            set_methods(
+                factory,
+                function_prototype,
+                map_prototype,
+                &[
+                    ("clear", map::map_prototype_clear, 0),
+                    ("delete", map::map_prototype_delete, 1),
+                    ("entries", map::map_prototype_entries, 0),
+                    ("forEach", map::map_prototype_for_each, 1),
+                    ("get", map::map_prototype_get, 1),
+                    ("has", map::map_prototype_has, 1),
+                    ("keys", map::map_prototype_keys, 0),
+                    ("set", map::map_prototype_set, 2),
+                    ("values", map::map_prototype_values, 0),
+                ],
+            );
+            set_getter(factory, function_prototype, map_prototype, "size", map::map_prototype_size);
+            // Map.prototype[@@iterator] is the same function object as Map.prototype.entries.
+            let entries = map_prototype.get_property("entries");
+            set_symbol_method(map_prototype, factory.symbols.iterator, entries);
+            map_prototype
+        };
+
+        // https://tc39.github.io/ecma262/#sec-%mapiteratorprototype%-object
+        let map_iterator_prototype = {
+            let map_iterator_prototype = Value::Object(factory.alloc(ObjectInfo {
+                kind: ObjectKind::Ordinary,
+                prototype: iterator_prototype,
+                property: make_property_map!(),
+                sym_property: FxHashMap::default(),
+                extensible: true,
+            }));
+            set_methods(
+                factory,
+                function_prototype,
+                map_iterator_prototype,
+                &[("next", map::map_iterator_prototype_next, 0)],
+            );
+            map_iterator_prototype
+        };
+
+        // https://tc39.github.io/ecma262/#sec-properties-of-the-set-prototype-object
+        let set_prototype = {
+            let set_prototype = Value::Object(factory.alloc(ObjectInfo {
+                kind: ObjectKind::Ordinary,
+                prototype: object_prototype,
+                property: make_property_map!(),
+                sym_property: FxHashMap::default(),
+                extensible: true,
+            }));
+            set_methods(
+                factory,
+                function_prototype,
+                set_prototype,
+                &[
+                    ("add", set::set_prototype_add, 1),
+                    ("clear", set::set_prototype_clear, 0),
+                    ("delete", set::set_prototype_delete, 1),
+                    ("entries", set::set_prototype_entries, 0),
+                    ("forEach", set::set_prototype_for_each, 1),
+                    ("has", set::set_prototype_has, 1),
+                    ("values", set::set_prototype_values, 0),
+                ],
+            );
+            set_getter(factory, function_prototype, set_prototype, "size", set::set_prototype_size);
+            // Set.prototype.keys and Set.prototype[@@iterator] are the same function object as
+            // Set.prototype.values.
+            let values = set_prototype.get_property("values");
+            set_prototype.set_property("keys", values);
+            set_symbol_method(set_prototype, factory.symbols.iterator, values);
+            set_prototype
+        };
+
+        // https://tc39.github.io/ecma262/#sec-%setiteratorprototype%-object
+        let set_iterator_prototype = {
+            let set_iterator_prototype = Value::Object(factory.alloc(ObjectInfo {
+                kind: ObjectKind::Ordinary,
+                prototype: iterator_prototype,
+                property: make_property_map!(),
+                sym_property: FxHashMap::default(),
+                extensible: true,
+            }));
+            set_methods(
+                factory,
+                function_prototype,
+                set_iterator_prototype,
+                &[("next", set::set_iterator_prototype_next, 0)],
+            );
+            set_iterator_prototype
+        };
+
+        // https://tc39.github.io/ecma262/#sec-properties-of-the-weakmap-prototype-object
+        let weak_map_prototype = {
+            let weak_map_prototype = Value::Object(factory.alloc(ObjectInfo {
+                kind: ObjectKind::Ordinary,
+                prototype: object_prototype,
+                property: make_property_map!(),
+                sym_property: FxHashMap::default(),
+                extensible: true,
+            }));
+            set_methods(
+                factory,
+                function_prototype,
+                weak_map_prototype,
+                &[
+                    ("delete", map::weak_map_prototype_delete, 1),
+                    ("get", map::weak_map_prototype_get, 1),
+                    ("has", map::weak_map_prototype_has, 1),
+                    ("set", map::weak_map_prototype_set, 2),
+                ],
+            );
+            weak_map_prototype
+        };
+
+        // https://tc39.github.io/ecma262/#sec-properties-of-the-weakset-prototype-object
+        let weak_set_prototype = {
+            let weak_set_prototype = Value::Object(factory.alloc(ObjectInfo {
+                kind: ObjectKind::Ordinary,
+                prototype: object_prototype,
+                property: make_property_map!(),
+                sym_property: FxHashMap::default(),
+                extensible: true,
+            }));
+            set_methods(
+                factory,
+                function_prototype,
+                weak_set_prototype,
+                &[
+                    ("add", set::weak_set_prototype_add, 1),
+                    ("delete", set::weak_set_prototype_delete, 1),
+                    ("has", set::weak_set_prototype_has, 1),
+                ],
+            );
+            weak_set_prototype
+        };
+
         ObjectPrototypes {
             object: object_prototype,
             function: function_prototype,
@@ -138,6 +1047,28 @@ impl ObjectPrototypes {
             array: array_prototype,
             symbol: symbol_prototype,
             error: error_prototype,
+            eval_error: eval_error_prototype,
+            range_error: range_error_prototype,
+            reference_error: reference_error_prototype,
+            syntax_error: syntax_error_prototype,
+            type_error: type_error_prototype,
+            uri_error: uri_error_prototype,
+            aggregate_error: aggregate_error_prototype,
+            iterator: iterator_prototype,
+            array_iterator: array_iterator_prototype,
+            string_iterator: string_iterator_prototype,
+            regexp: regexp_prototype,
+            regexp_string_iterator: regexp_string_iterator_prototype,
+            promise: promise_prototype,
+            generator: generator_prototype,
+            date: date_prototype,
+            number: number_prototype,
+            map: map_prototype,
+            map_iterator: map_iterator_prototype,
+            set: set_prototype,
+            set_iterator: set_iterator_prototype,
+            weak_map: weak_map_prototype,
+            weak_set: weak_set_prototype,
         }
     }
 
@@ -149,6 +1080,28 @@ impl ObjectPrototypes {
     array: Value::undefined(),
     symbol: Value::undefined(),
     error: Value::undefined(),
+    eval_error: Value::undefined(),
+    range_error: Value::undefined(),
+    reference_error: Value::undefined(),
+    syntax_error: Value::undefined(),
+    type_error: Value::undefined(),
+    uri_error: Value::undefined(),
+    aggregate_error: Value::undefined(),
+    iterator: Value::undefined(),
+    array_iterator: Value::undefined(),
+    string_iterator: Value::undefined(),
+    regexp: Value::undefined(),
+    regexp_string_iterator: Value::undefined(),
+    promise: Value::undefined(),
+    generator: Value::undefined(),
+    date: Value::undefined(),
+    number: Value::undefined(),
+    map: Value::undefined(),
+    map_iterator: Value::undefined(),
+    set: Value::undefined(),
+    set_iterator: Value::undefined(),
+    weak_map: Value::undefined(),
+    weak_set: Value::undefined(),
     }
     }
 }
@@ -0,0 +1,59 @@
+use crate::regexp::Regex;
+use std::fmt;
+use std::rc::Rc;
+
+/// Internal slots of a RegExp object.
+/// https://tc39.github.io/ecma262/#sec-properties-of-regexp-instances
+#[derive(Clone, Debug)]
+pub struct RegExpInfo {
+    /// \[\[OriginalSource\]\]
+    pub source: String,
+    /// \[\[OriginalFlags\]\]
+    pub flags: String,
+    /// \[\[RegExpMatcher\]\]
+    pub regex: Rc<Regex>,
+}
+
+impl RegExpInfo {
+    /// https://tc39.github.io/ecma262/#sec-escaperegexppattern
+    pub fn escaped_source(&self) -> String {
+        if self.source.is_empty() {
+            return "(?:)".to_string();
+        }
+
+        let mut escaped = "".to_string();
+        let mut in_class = false;
+        let mut chars = self.source.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '/' if !in_class => escaped.push_str("\\/"),
+                '\n' => escaped.push_str("\\n"),
+                '\r' => escaped.push_str("\\r"),
+                '\u{2028}' => escaped.push_str("\\u2028"),
+                '\u{2029}' => escaped.push_str("\\u2029"),
+                '\\' => {
+                    escaped.push(c);
+                    if let Some(c) = chars.next() {
+                        escaped.push(c)
+                    }
+                }
+                _ => {
+                    if c == '[' {
+                        in_class = true
+                    } else if c == ']' {
+                        in_class = false
+                    }
+                    escaped.push(c)
+                }
+            }
+        }
+        escaped
+    }
+}
+
+/// https://tc39.github.io/ecma262/#sec-regexp.prototype.tostring
+impl fmt::Display for RegExpInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "/{}/{}", self.escaped_source(), self.flags)
+    }
+}
@@ -0,0 +1,36 @@
+use std::cell::OnceCell;
+
+/// The body of a string value, which is a sequence of UTF-16 code units and may contain lone
+/// surrogates. The UTF-8 form, in which lone surrogates are replaced with U+FFFD, is computed the
+/// first time it is needed and kept.
+#[derive(Clone, Debug)]
+pub struct StringInfo {
+    units: Vec<u16>,
+    string: OnceCell<String>,
+}
+
+impl StringInfo {
+    pub fn new(string: String) -> Self {
+        StringInfo {
+            units: string.encode_utf16().collect(),
+            string: OnceCell::from(string),
+        }
+    }
+
+    pub fn from_utf16(units: Vec<u16>) -> Self {
+        StringInfo {
+            units,
+            string: OnceCell::new(),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.string
+            .get_or_init(|| String::from_utf16_lossy(&self.units))
+    }
+
+    /// The UTF-16 code units of the string.
+    pub fn utf16(&self) -> &[u16] {
+        &self.units
+    }
+}
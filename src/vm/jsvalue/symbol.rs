@@ -45,3 +45,78 @@ impl GlobalSymbolRegistry {
         Value::undefined()
     }
 }
+
+/// Well-known symbols shared by the whole realm.
+/// https://tc39.github.io/ecma262/#sec-well-known-symbols
+#[derive(Debug, Clone)]
+pub struct WellKnownSymbols {
+    /// @@asyncIterator
+    pub async_iterator: Value,
+    /// @@hasInstance
+    pub has_instance: Value,
+    /// @@iterator
+    pub iterator: Value,
+    /// @@match
+    pub match_: Value,
+    /// @@matchAll
+    pub match_all: Value,
+    /// @@replace
+    pub replace: Value,
+    /// @@search
+    pub search: Value,
+    /// @@split
+    pub split: Value,
+    /// @@toPrimitive
+    pub to_primitive: Value,
+}
+
+impl WellKnownSymbols {
+    pub fn new(factory: &mut Factory) -> Self {
+        WellKnownSymbols {
+            async_iterator: factory.symbol(Some("Symbol.asyncIterator".to_string())),
+            has_instance: factory.symbol(Some("Symbol.hasInstance".to_string())),
+            iterator: factory.symbol(Some("Symbol.iterator".to_string())),
+            match_: factory.symbol(Some("Symbol.match".to_string())),
+            match_all: factory.symbol(Some("Symbol.matchAll".to_string())),
+            replace: factory.symbol(Some("Symbol.replace".to_string())),
+            search: factory.symbol(Some("Symbol.search".to_string())),
+            split: factory.symbol(Some("Symbol.split".to_string())),
+            to_primitive: factory.symbol(Some("Symbol.toPrimitive".to_string())),
+        }
+    }
+
+    pub fn dummy() -> Self {
+        WellKnownSymbols {
+            async_iterator: Value::undefined(),
+            has_instance: Value::undefined(),
+            iterator: Value::undefined(),
+            match_: Value::undefined(),
+            match_all: Value::undefined(),
+            replace: Value::undefined(),
+            search: Value::undefined(),
+            split: Value::undefined(),
+            to_primitive: Value::undefined(),
+        }
+    }
+
+    pub fn all(&self) -> Vec<Value> {
+        vec![
+            self.async_iterator,
+            self.has_instance,
+            self.iterator,
+            self.match_,
+            self.match_all,
+            self.replace,
+            self.search,
+            self.split,
+            self.to_primitive,
+        ]
+    }
+
+    /// Well-known symbols are created before %SymbolPrototype% exists.
+    pub fn set_prototype(&self, symbol_prototype: Value) {
+        for symbol in self.all() {
+            symbol.get_object_info().prototype = symbol_prototype;
+        }
+    }
+}
@@ -1,16 +1,24 @@
 use super::super::error;
 pub use super::array::ArrayObjectInfo;
+pub use super::collection::MapData;
+use super::date;
 pub use super::error::*;
 pub use super::function::*;
+pub use super::generator::*;
+pub use super::iterator::*;
+pub use super::module::*;
+use super::number;
 pub use super::object::*;
+pub use super::promise::*;
 pub use super::prototype::*;
+pub use super::regexp::*;
+pub use super::string::*;
 pub use super::symbol::*;
 use crate::builtin::BuiltinFuncTy;
 use crate::gc;
 use crate::vm::exec_context::LexicalEnvironmentRef;
 use crate::vm::vm::Factory;
 pub use rustc_hash::FxHashMap;
-use std::ffi::CString;
 
 pub const UNINITIALIZED: i32 = 0;
 pub const EMPTY: i32 = 1;
@@ -30,7 +38,7 @@ make_nanbox! {
     pub unsafe enum BoxedValue, Value {
         Number(f64),
         Bool(u8), // 0 | 1 = false | true
-        String(*mut CString), // TODO: Using CString is good for JIT. However, we need better one instead.
+        String(*mut StringInfo),
         Object(*mut ObjectInfo),
         // Symbol(*mut SymbolInfo),
         Other(i32) // UNINITIALIZED | EMPTY | NULL | UNDEFINED
@@ -46,7 +54,7 @@ macro_rules! make_property_map_sub {
          $configurable:ident
     ),*) => { {
         #[allow(unused_mut)]
-        let mut record = rustc_hash::FxHashMap::default();
+        let mut record = crate::vm::jsvalue::object::PropertyMap::default();
         $( record.insert(
             (stringify!($property_name)).to_string(),
             crate::vm::jsvalue::object::Property::Data(crate::vm::jsvalue::object::DataProperty {
@@ -78,8 +86,9 @@ macro_rules! make_normal_object {
             crate::vm::jsvalue::object::ObjectInfo {
                 kind: crate::vm::jsvalue::object::ObjectKind::Ordinary,
                 prototype: $factory.object_prototypes.object,
-                property: rustc_hash::FxHashMap::default(),
-                sym_property: rustc_hash::FxHashMap::default()
+                property: crate::vm::jsvalue::object::PropertyMap::default(),
+                sym_property: rustc_hash::FxHashMap::default(),
+                extensible: true
             }
         ))
     } };
@@ -88,8 +97,9 @@ macro_rules! make_normal_object {
             ObjectInfo {
                 kind: ObjectKind::Ordinary,
                 prototype: $object_prototypes.object,
-                property: FxHashMap::default(),
-                sym_property: FxHashMap::default()
+                property: PropertyMap::default(),
+                sym_property: FxHashMap::default(),
+                extensible: true
             }
         ))
     } };
@@ -99,7 +109,8 @@ macro_rules! make_normal_object {
                 kind: ObjectKind::Ordinary,
                 prototype: $object_prototypes.object,
                 property: make_property_map_sub!($($property_name, $val, $x, $y, $z),* ),
-                sym_property: FxHashMap::default()
+                sym_property: FxHashMap::default(),
+                extensible: true
             }
             ))
     } };
@@ -109,7 +120,8 @@ macro_rules! make_normal_object {
                 kind: crate::vm::jsvalue::object::ObjectKind::Ordinary,
                 prototype: $factory.object_prototypes.object,
                 property: make_property_map_sub!($($property_name, $val, $x, $y, $z),* ),
-                sym_property: rustc_hash::FxHashMap::default()
+                sym_property: rustc_hash::FxHashMap::default(),
+                extensible: true
             }
             ))
     } };
@@ -122,7 +134,7 @@ impl std::fmt::Display for Value {
             Value::Bool(0) => write!(f, "false"),
             Value::Bool(1) => write!(f, "true"),
             Value::Bool(u) => write!(f, "Bool({})", u),
-            Value::String(cstr) => write!(f, "{:?}", cstrp_to_str(*cstr)),
+            Value::String(_) => write!(f, "{:?}", self.into_str()),
             Value::Other(UNINITIALIZED) => write!(f, "UNINITIALIZED"),
             Value::Other(EMPTY) => write!(f, "UNINITIALIZED"),
             Value::Other(NULL) => write!(f, "NULL"),
@@ -137,6 +149,24 @@ impl std::fmt::Display for Value {
                     ObjectKind::Array(_) => write!(f, "Array"),
                     ObjectKind::Symbol(_) => write!(f, "Symbol"),
                     ObjectKind::Error(_) => write!(f, "Error"),
+                    ObjectKind::ArrayIterator(_) => write!(f, "ArrayIterator"),
+                    ObjectKind::ForInIterator(_) => write!(f, "ForInIterator"),
+                    ObjectKind::StringIterator(_) => write!(f, "StringIterator"),
+                    ObjectKind::RegExp(_) => write!(f, "RegExp"),
+                    ObjectKind::RegExpStringIterator(_) => write!(f, "RegExpStringIterator"),
+                    ObjectKind::Promise(_) => write!(f, "Promise"),
+                    ObjectKind::AsyncFunction(_) => write!(f, "AsyncFunction"),
+                    ObjectKind::Generator(_) => write!(f, "Generator"),
+                    ObjectKind::ModuleNamespace(_) => write!(f, "ModuleNamespace"),
+                    ObjectKind::Date(_) => write!(f, "Date"),
+                    ObjectKind::Number(_) => write!(f, "Number"),
+                    ObjectKind::String(_) => write!(f, "String"),
+                    ObjectKind::Map(_) => write!(f, "Map"),
+                    ObjectKind::Set(_) => write!(f, "Set"),
+                    ObjectKind::WeakMap(_) => write!(f, "WeakMap"),
+                    ObjectKind::WeakSet(_) => write!(f, "WeakSet"),
+                    ObjectKind::MapIterator(_) => write!(f, "MapIterator"),
+                    ObjectKind::SetIterator(_) => write!(f, "SetIterator"),
                 }
             }
         }
@@ -169,20 +199,33 @@ impl Value {
         Value::Other(SEPERATOR)
     }
 
+    /// A number value. NaN produced by arithmetic may have any bit pattern, which the NaN-boxing
+    /// would read as another kind of value, so it is canonicalized.
+    #[inline]
+    pub fn number(n: f64) -> Self {
+        Value::Number(if n.is_nan() { f64::NAN } else { n })
+    }
+
     #[inline]
     pub fn bool(x: bool) -> Self {
         Value::Bool(if x { 1 } else { 0 })
     }
 
     fn string(memory_allocator: &mut gc::MemoryAllocator, body: String) -> Self {
-        Value::String(memory_allocator.alloc(CString::new(body).unwrap()))
+        Value::String(memory_allocator.alloc(StringInfo::new(body)))
+    }
+
+    fn utf16_string(memory_allocator: &mut gc::MemoryAllocator, units: Vec<u16>) -> Self {
+        Value::String(memory_allocator.alloc(StringInfo::from_utf16(units)))
     }
 
+    /// ``length`` is the number of the parameters which are not optional.
     pub fn builtin_function_with_proto(
         memory_allocator: &mut gc::MemoryAllocator,
         proto: Value,
         name: impl Into<String>,
         func: BuiltinFuncTy,
+        length: usize,
     ) -> Self {
         let name: String = name.into();
         let name_prop = Value::string(memory_allocator, name.clone());
@@ -193,10 +236,11 @@ impl Value {
             }),
             prototype: proto,
             property: make_property_map!(
-                length => false, false, true : Value::Number(0.0),
+                length => false, false, true : Value::Number(length as f64),
                 name   => false, false, true : name_prop
             ),
             sym_property: FxHashMap::default(),
+            extensible: true,
         }))
     }
 }
@@ -337,7 +381,7 @@ impl Value {
         }
     }
 
-    pub fn get_object_properties(&self) -> Option<&FxHashMap<String, Property>> {
+    pub fn get_object_properties(&self) -> Option<&PropertyMap> {
         match self {
             Value::Object(obj_info) => Some(&unsafe { &**obj_info }.property),
             _ => None,
@@ -358,16 +402,21 @@ impl Value {
     ) -> Result<Property, error::RuntimeError> {
         fn string_get_property(
             factory: &mut Factory,
-            s: &str,
+            s: &[u16],
             key: Value,
         ) -> Result<Property, error::RuntimeError> {
             match key {
-                Value::Number(idx) if is_integer(idx) => Ok(Property::new_data_simple(
-                    factory.string(s.chars().nth(idx as usize).unwrap().to_string()),
-                )),
-                Value::String(x) if cstrp_to_str(x) == "length" => Ok(Property::new_data_simple(
-                    Value::Number(s.chars().fold(0, |x, c| x + c.len_utf16()) as f64),
-                )),
+                // Indices are in UTF-16 code units.
+                Value::Number(idx) if is_integer(idx) && idx >= 0.0 => {
+                    let unit = s.get(idx as usize);
+                    Ok(Property::new_data_simple(match unit {
+                        Some(unit) => factory.utf16_string(vec![*unit]),
+                        None => Value::undefined(),
+                    }))
+                }
+                Value::String(_) if key.into_str() == "length" => {
+                    Ok(Property::new_data_simple(Value::Number(s.len() as f64)))
+                }
                 key => factory
                     .object_prototypes
                     .string
@@ -377,17 +426,23 @@ impl Value {
         }
 
         match self {
-            Value::String(s) => {
-                return string_get_property(factory, cstrp_to_str(*s), key);
+            Value::String(_) => {
+                return string_get_property(factory, self.into_utf16(), key);
             }
             Value::Other(_) => {
                 return Err(error::RuntimeError::typeerr(format!(
-                    "TypeError: Cannot read property '{}' of {}",
+                    "Cannot read property '{}' of {}",
                     key.to_string(),
                     self.to_string()
                 )));
             }
-            // TODO: Number
+            Value::Number(_) => {
+                return factory
+                    .object_prototypes
+                    .number
+                    .get_object_info()
+                    .get_property_by_value(factory, key);
+            }
             _ => {}
         }
 
@@ -415,7 +470,7 @@ impl Value {
                 ObjectRef(*obj_info).set_property_by_value(allocator, key, val)
             }
             Value::Other(_) => Err(error::RuntimeError::typeerr(format!(
-                "TypeError: Cannot set property '{}' of {}",
+                "Cannot set property '{}' of {}",
                 key.to_string(),
                 self.to_string()
             ))),
@@ -507,7 +562,15 @@ impl Value {
 
     pub fn into_str(self) -> &'static str {
         match self {
-            Value::String(s) => cstrp_to_str(s),
+            Value::String(s) => unsafe { strp_to_str(s) },
+            _ => panic!(),
+        }
+    }
+
+    /// The UTF-16 code units of a string value.
+    pub fn into_utf16(self) -> &'static [u16] {
+        match self {
+            Value::String(s) => unsafe { &*s }.utf16(),
             _ => panic!(),
         }
     }
@@ -542,18 +605,7 @@ impl Value {
             Value::Bool(0) => 0.0,
             Value::Bool(1) => 1.0,
             Value::Number(n) => *n,
-            Value::String(s) => {
-                let s = cstrp_to_str(*s);
-                if s == "Infinity" || s == "-Infinity" {
-                    ::std::f64::INFINITY
-                } else if s.len() == 0 {
-                    0.0
-                } else if s.chars().all(|c| c.is_whitespace()) {
-                    0.0
-                } else {
-                    s.parse::<f64>().unwrap_or(::std::f64::NAN)
-                }
-            }
+            Value::String(_) => number::string_to_number(self.into_str()),
             Value::Object(_) => self
                 .to_primitive(allocator, Some(PreferredType::Number))
                 .to_number(allocator),
@@ -562,28 +614,63 @@ impl Value {
         }
     }
 
+    /// The UTF-16 code units of ToString(self), which keep lone surrogates in a string.
+    pub fn to_utf16(&self) -> Vec<u16> {
+        match self {
+            Value::String(_) => self.into_utf16().to_vec(),
+            Value::Object(info) => match ObjectRef(*info).kind {
+                ObjectKind::Array(ref info) => info.join(None),
+                ObjectKind::String(s) => s.to_utf16(),
+                _ => self.to_string().encode_utf16().collect(),
+            },
+            _ => self.to_string().encode_utf16().collect(),
+        }
+    }
+
     // TODO: https://www.ecma-international.org/ecma-262/6.0/#sec-tostring
     pub fn to_string(&self) -> String {
         match self {
             Value::Bool(0) => "false".to_string(),
             Value::Bool(1) => "true".to_string(),
-            Value::String(s) => cstrp_to_str(*s).to_string(),
+            Value::String(_) => self.into_str().to_string(),
             Value::Other(UNDEFINED) => "undefined".to_string(),
             Value::Other(NULL) => "null".to_string(),
-            Value::Number(n) => {
-                if n.is_nan() {
-                    "NaN".to_string()
-                } else if n.is_infinite() {
-                    "Infinity".to_string()
-                } else {
-                    format!("{}", n)
-                }
-            }
+            Value::Number(n) => number::to_string(*n),
             Value::Object(info) => {
                 let info = ObjectRef(*info);
                 match info.kind {
                     ObjectKind::Ordinary => "[object Object]".to_string(),
-                    ObjectKind::Array(ref info) => info.join(None),
+                    ObjectKind::Array(ref info) => String::from_utf16_lossy(&info.join(None)),
+                    ObjectKind::RegExp(ref info) => info.to_string(),
+                    ObjectKind::Promise(ref info) => match info.state {
+                        PromiseState::Pending => "Promise { <pending> }".to_string(),
+                        PromiseState::Fulfilled => {
+                            format!("Promise {{ {} }}", info.result.debug_string(true))
+                        }
+                        PromiseState::Rejected => {
+                            format!(
+                                "Promise {{ <rejected> {} }}",
+                                info.result.debug_string(true)
+                            )
+                        }
+                    },
+                    ObjectKind::AsyncFunction(_) => "[AsyncFunction]".to_string(),
+                    ObjectKind::Date(time) => date::to_date_string(time),
+                    ObjectKind::Number(n) => number::to_string(n),
+                    ObjectKind::String(s) => s.to_string(),
+                    ObjectKind::Map(_) => "[object Map]".to_string(),
+                    ObjectKind::Set(_) => "[object Set]".to_string(),
+                    ObjectKind::WeakMap(_) => "[object WeakMap]".to_string(),
+                    ObjectKind::WeakSet(_) => "[object WeakSet]".to_string(),
+                    ObjectKind::Error(_) => {
+                        let name = info.get_property("name").to_string();
+                        let message = info.get_property("message").to_string();
+                        if message.is_empty() {
+                            name
+                        } else {
+                            format!("{}: {}", name, message)
+                        }
+                    }
                     _ => "[unimplemented]".to_string(), // TODO
                 }
             }
@@ -602,18 +689,15 @@ impl Value {
                     true
                 }
             }
-            Value::String(s) => cstrp_to_str(*s).len() != 0,
+            Value::String(_) => !self.into_utf16().is_empty(),
+            Value::Other(UNDEFINED) | Value::Other(NULL) => false,
             _ => true,
         }
     }
 
     /// https://tc39.github.io/ecma262/#sec-toint32
     pub fn to_int32(&self, allocator: &mut gc::MemoryAllocator) -> i32 {
-        let number = self.to_number(allocator);
-        match number {
-            number if number.is_nan() || number == 0.0 || number.is_infinite() => 0,
-            number => (number.trunc()) as i32,
-        }
+        self.to_uint32(allocator) as i32
     }
 
     /// https://tc39.github.io/ecma262/#sec-touint32
@@ -621,7 +705,8 @@ impl Value {
         let number = self.to_number(allocator);
         match number {
             number if number.is_nan() || number == 0.0 || number.is_infinite() => 0,
-            number => (number.trunc()) as u32,
+            // Modulo 2^32.
+            number => number.trunc().rem_euclid(4_294_967_296.0) as u32,
         }
     }
 
@@ -635,13 +720,11 @@ impl Value {
             return *self;
         }
 
-        let mut hint = preferred_type.unwrap_or(PreferredType::Default);
-
         // TODO: Call @@toPrimitive if present
-
-        if hint == PreferredType::Default {
-            hint = PreferredType::Number
-        }
+        let hint = match preferred_type {
+            Some(PreferredType::String) => PreferredType::String,
+            _ => PreferredType::Number,
+        };
 
         self.ordinary_to_primitive(allocator, hint)
     }
@@ -676,6 +759,23 @@ impl Value {
                     ObjectKind::Function(_) => None,
                     ObjectKind::Array(_) => None,
                     ObjectKind::Error(_) => None,
+                    ObjectKind::ArrayIterator(_)
+                    | ObjectKind::StringIterator(_)
+                    | ObjectKind::RegExpStringIterator(_)
+                    | ObjectKind::MapIterator(_)
+                    | ObjectKind::SetIterator(_)
+                    | ObjectKind::ForInIterator(_) => None,
+                    ObjectKind::Map(_)
+                    | ObjectKind::Set(_)
+                    | ObjectKind::WeakMap(_)
+                    | ObjectKind::WeakSet(_) => None,
+                    ObjectKind::RegExp(_) => None,
+                    ObjectKind::Promise(_) | ObjectKind::AsyncFunction(_) => None,
+                    ObjectKind::Generator(_) => None,
+                    ObjectKind::ModuleNamespace(_) => None,
+                    ObjectKind::Date(time) => Some(Value::Number(time)),
+                    ObjectKind::Number(n) => Some(Value::Number(n)),
+                    ObjectKind::String(s) => Some(s),
                     ObjectKind::Symbol(_) => Some(self), // TODO
                 }
             }
@@ -691,43 +791,33 @@ impl Value {
         let lprim = self.to_primitive(allocator, None);
         let rprim = val.to_primitive(allocator, None);
         match (lprim, rprim) {
-            (Value::Number(x), Value::Number(y)) => Value::Number(x + y),
-            (Value::String(x), Value::String(y)) => {
-                let x = cstrp_to_str(x);
-                let y = cstrp_to_str(y);
-                let cat = format!("{}{}", x, y);
-                Value::string(allocator, cat)
-            }
-            (Value::String(x), _) => {
-                let x = cstrp_to_str(x);
-                Value::string(allocator, format!("{}{}", x, rprim.to_string()))
+            (Value::Number(x), Value::Number(y)) => Value::number(x + y),
+            (Value::String(_), _) | (_, Value::String(_)) => {
+                let cat = [lprim.to_utf16(), rprim.to_utf16()].concat();
+                Value::utf16_string(allocator, cat)
             }
-            (_, Value::String(y)) => {
-                let y = cstrp_to_str(y);
-                Value::string(allocator, format!("{}{}", lprim.to_string(), y))
-            }
-            (x, y) => Value::Number(x.to_number(allocator) + y.to_number(allocator)),
+            (x, y) => Value::number(x.to_number(allocator) + y.to_number(allocator)),
         }
     }
 
     // https://www.ecma-international.org/ecma-262/6.0/#sec-subtraction-operator-minus-runtime-semantics-evaluation
     pub fn sub(self, allocator: &mut gc::MemoryAllocator, val: Value) -> Self {
         match (self, val) {
-            (Value::Number(x), Value::Number(y)) => Value::Number(x - y),
-            (x, y) => Value::Number(x.to_number(allocator) - y.to_number(allocator)),
+            (Value::Number(x), Value::Number(y)) => Value::number(x - y),
+            (x, y) => Value::number(x.to_number(allocator) - y.to_number(allocator)),
         }
     }
 
     pub fn mul(self, val: Value) -> Self {
         match (self, val) {
-            (Value::Number(x), Value::Number(y)) => Value::Number(x * y),
+            (Value::Number(x), Value::Number(y)) => Value::number(x * y),
             _ => Value::undefined(),
         }
     }
 
     pub fn div(self, val: Value) -> Self {
         match (self, val) {
-            (Value::Number(x), Value::Number(y)) => Value::Number(x / y),
+            (Value::Number(x), Value::Number(y)) => Value::number(x / y),
             _ => Value::undefined(),
         }
     }
@@ -740,7 +830,9 @@ impl Value {
     }
 
     pub fn exp(self, allocator: &mut gc::MemoryAllocator, val: Value) -> Self {
-        Value::Number(self.to_number(allocator).powf(val.to_number(allocator)))
+        let base = self.to_number(allocator);
+        let exponent = val.to_number(allocator);
+        Value::number(crate::builtins::math::pow(base, exponent))
     }
 
     pub fn and(self, allocator: &mut gc::MemoryAllocator, val: Value) -> Self {
@@ -822,13 +914,33 @@ impl Value {
 
         match self {
             Value::Number(_) => self.into_number() == val.into_number(),
-            Value::String(_) => self.into_str() == val.into_str(),
+            Value::String(_) => self.into_utf16() == val.into_utf16(),
             Value::Bool(_) => self.into_bool() == val.into_bool(),
             Value::Object(_) => get_obj_ptr(self) == get_obj_ptr(val),
             _ => false,
         }
     }
 
+    /// Same as strict equality, except that NaN equals NaN and +0 differs from -0.
+    /// https://tc39.github.io/ecma262/#sec-samevalue
+    pub fn same_value(self, val: Value) -> bool {
+        match (self, val) {
+            (Value::Number(x), Value::Number(y)) if x == 0.0 && y == 0.0 => {
+                x.is_sign_negative() == y.is_sign_negative()
+            }
+            _ => self.same_value_zero(val),
+        }
+    }
+
+    /// Same as strict equality, except that NaN equals NaN.
+    /// https://tc39.github.io/ecma262/#sec-samevaluezero
+    pub fn same_value_zero(self, val: Value) -> bool {
+        match (self, val) {
+            (Value::Number(x), Value::Number(y)) if x.is_nan() && y.is_nan() => true,
+            _ => self.strict_eq_bool(val),
+        }
+    }
+
     pub fn ne(self, allocator: &mut gc::MemoryAllocator, val: Value) -> Self {
         Value::bool(!self.eq(allocator, val).into_bool())
     }
@@ -839,11 +951,12 @@ impl Value {
 
     /// https://tc39.github.io/ecma262/#sec-abstract-relational-comparison
     pub fn cmp(self, allocator: &mut gc::MemoryAllocator, val: Value) -> Self {
-        let px = self.to_primitive(allocator, None);
-        let py = val.to_primitive(allocator, None);
+        let px = self.to_primitive(allocator, Some(PreferredType::Number));
+        let py = val.to_primitive(allocator, Some(PreferredType::Number));
 
-        if let (Value::String(x), Value::String(y)) = (px, py) {
-            return Value::bool(cstrp_to_str(x) < cstrp_to_str(y));
+        // Strings are compared in UTF-16 code units.
+        if let (Value::String(_), Value::String(_)) = (px, py) {
+            return Value::bool(px.into_utf16() < py.into_utf16());
         }
 
         let nx = px.to_number(allocator);
@@ -916,6 +1029,21 @@ impl Value {
                     ObjectKind::Array(_) => "object",
                     ObjectKind::Symbol(_) => "symbol",
                     ObjectKind::Error(_) => "error",
+                    ObjectKind::ArrayIterator(_)
+                    | ObjectKind::StringIterator(_)
+                    | ObjectKind::RegExpStringIterator(_)
+                    | ObjectKind::MapIterator(_)
+                    | ObjectKind::SetIterator(_)
+                    | ObjectKind::ForInIterator(_) => "object",
+                    ObjectKind::Map(_)
+                    | ObjectKind::Set(_)
+                    | ObjectKind::WeakMap(_)
+                    | ObjectKind::WeakSet(_) => "object",
+                    ObjectKind::RegExp(_) => "object",
+                    ObjectKind::Promise(_) | ObjectKind::AsyncFunction(_) => "object",
+                    ObjectKind::Generator(_) => "object",
+                    ObjectKind::ModuleNamespace(_) => "object",
+                    ObjectKind::Date(_) | ObjectKind::Number(_) | ObjectKind::String(_) => "object",
                     ObjectKind::Ordinary => "object",
                 }
             }
@@ -970,17 +1098,11 @@ impl Value {
             Value::Bool(1) => "true".to_string(),
             Value::Bool(0) => "false".to_string(),
             Value::Bool(_) => unreachable!(),
-            Value::Number(n) => {
-                if n.is_nan() {
-                    "NaN".to_string()
-                } else if n.is_infinite() {
-                    "Infinity".to_string()
-                } else {
-                    format!("{}", n)
-                }
-            }
-            Value::String(s) => {
-                let s = cstrp_to_str(*s);
+            // Unlike ToString(), tells -0 from +0.
+            Value::Number(n) if *n == 0.0 && n.is_sign_negative() => "-0".to_string(),
+            Value::Number(n) => number::to_string(*n),
+            Value::String(_) => {
+                let s = self.into_str();
                 if nest {
                     format!("'{}'", s)
                 } else {
@@ -1004,8 +1126,79 @@ impl Value {
                         "Symbol({})",
                         info.description.as_ref().unwrap_or(&"".to_string())
                     ),
-                    ObjectKind::Error(ref _info) => {
-                        format!("Error({})", obj_info.get_property("message").to_string())
+                    ObjectKind::Error(ref _info) => format!(
+                        "{}({})",
+                        obj_info.get_property("name").to_string(),
+                        obj_info.get_property("message").to_string()
+                    ),
+                    ObjectKind::ArrayIterator(_) => "[Array Iterator]".to_string(),
+                    ObjectKind::StringIterator(_) => "[String Iterator]".to_string(),
+                    ObjectKind::RegExpStringIterator(_) => "[RegExp String Iterator]".to_string(),
+                    ObjectKind::MapIterator(_) => "[Map Iterator]".to_string(),
+                    ObjectKind::SetIterator(_) => "[Set Iterator]".to_string(),
+                    ObjectKind::ForInIterator(_) => "[For-In Iterator]".to_string(),
+                    ObjectKind::RegExp(ref info) => info.to_string(),
+                    ObjectKind::Promise(ref info) => match info.state {
+                        PromiseState::Pending => "Promise { <pending> }".to_string(),
+                        PromiseState::Fulfilled => {
+                            format!("Promise {{ {} }}", info.result.debug_string(true))
+                        }
+                        PromiseState::Rejected => {
+                            format!(
+                                "Promise {{ <rejected> {} }}",
+                                info.result.debug_string(true)
+                            )
+                        }
+                    },
+                    ObjectKind::AsyncFunction(_) => "[AsyncFunction]".to_string(),
+                    ObjectKind::Generator(_) => "Object [Generator] {}".to_string(),
+                    ObjectKind::Date(time) => {
+                        date::to_iso_string(time).unwrap_or_else(|| "Invalid Date".to_string())
+                    }
+                    ObjectKind::Number(n) => {
+                        format!("[Number: {}]", Value::Number(n).debug_string(nest))
+                    }
+                    ObjectKind::String(s) => format!("[String: {}]", s.debug_string(true)),
+                    ObjectKind::Map(ref data) => {
+                        let entries = data
+                            .iter()
+                            .map(|(key, val)| {
+                                format!("{} => {}", key.debug_string(true), val.debug_string(true))
+                            })
+                            .collect::<Vec<String>>();
+                        if entries.is_empty() {
+                            "Map {}".to_string()
+                        } else {
+                            format!("Map {{ {} }}", entries.join(", "))
+                        }
+                    }
+                    ObjectKind::Set(ref data) => {
+                        let entries = data
+                            .iter()
+                            .map(|(key, _)| key.debug_string(true))
+                            .collect::<Vec<String>>();
+                        if entries.is_empty() {
+                            "Set {}".to_string()
+                        } else {
+                            format!("Set {{ {} }}", entries.join(", "))
+                        }
+                    }
+                    ObjectKind::WeakMap(_) => "WeakMap { <items unknown> }".to_string(),
+                    ObjectKind::WeakSet(_) => "WeakSet { <items unknown> }".to_string(),
+                    ObjectKind::ModuleNamespace(ref info) => {
+                        let exports = info
+                            .exports
+                            .iter()
+                            .map(|(name, binding)| match binding.get_value() {
+                                Ok(val) => format!("'{}': {}", name, val.debug_string(true)),
+                                Err(_) => format!("'{}': <uninitialized>", name),
+                            })
+                            .collect::<Vec<String>>();
+                        if exports.is_empty() {
+                            "[Module] {}".to_string()
+                        } else {
+                            format!("[Module] {{ {} }}", exports.join(", "))
+                        }
                     }
                     ObjectKind::Function(ref func_info) => {
                         if let Some(ref name) = func_info.name {
@@ -1081,7 +1274,10 @@ pub fn is_integer(n: f64) -> bool {
     n - n.floor() == 0.0
 }
 
+/// # Safety
+///
+/// ``p`` must point to a live string allocated by the memory allocator.
 #[inline]
-pub fn cstrp_to_str(p: *mut CString) -> &'static str {
-    unsafe { &*p }.to_str().unwrap()
+pub unsafe fn strp_to_str(p: *mut StringInfo) -> &'static str {
+    unsafe { &*p }.as_str()
 }
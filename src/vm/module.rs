@@ -0,0 +1,671 @@
+use crate::builtins;
+use crate::node::{ExportDeclaration, Node, NodeBase};
+use crate::parser::Parser;
+use crate::vm::codegen::DEFAULT_EXPORT_BINDING;
+use crate::vm::error::RuntimeError;
+use crate::vm::exec_context::{EnvironmentRecord, ExecContext, LexicalEnvironmentRef};
+use crate::vm::jsvalue::value::*;
+use crate::vm::vm::{CallMode, VMResult, VMValueResult, VM};
+use rustc_hash::FxHashMap;
+use std::path::Path;
+
+/// An index of a module in the ModuleMap.
+pub type ModuleId = usize;
+
+/// Source text module record.
+/// https://tc39.github.io/ecma262/#sec-source-text-module-records
+#[derive(Clone, Debug)]
+pub struct ModuleRecord {
+    /// File name with absolute path, which identifies the module.
+    pub file_name: String,
+    pub status: ModuleStatus,
+    /// The code of the module body.
+    pub func_ref: FuncInfoRef,
+    /// \[\[Environment\]\]. None until the module is linked.
+    pub env: Option<LexicalEnvironmentRef>,
+    /// \[\[Namespace\]\]. Created when it is first requested.
+    pub namespace: Option<Value>,
+    /// \[\[EvaluationError\]\]. The value thrown while evaluating the module.
+    pub evaluation_error: Option<Value>,
+    /// \[\[RequestedModules\]\] as absolute paths, in source order.
+    pub requested_modules: Vec<String>,
+    /// \[\[ImportEntries\]\]
+    pub import_entries: Vec<ImportEntry>,
+    /// \[\[LocalExportEntries\]\]
+    pub local_export_entries: Vec<LocalExportEntry>,
+    /// \[\[IndirectExportEntries\]\]
+    pub indirect_export_entries: Vec<IndirectExportEntry>,
+    /// \[\[StarExportEntries\]\]. The modules re-exported by ``export * from``.
+    pub star_export_entries: Vec<String>,
+}
+
+/// https://tc39.github.io/ecma262/#sec-cyclic-module-records
+#[derive(Clone, Debug, PartialEq, Copy)]
+pub enum ModuleStatus {
+    Unlinked,
+    Linking,
+    Linked,
+    Evaluating,
+    Evaluated,
+}
+
+/// https://tc39.github.io/ecma262/#importentry-record
+#[derive(Clone, Debug)]
+pub struct ImportEntry {
+    /// The absolute path of the imported module.
+    pub module_request: String,
+    /// None for ``import * as local``.
+    pub import_name: Option<String>,
+    pub local_name: String,
+}
+
+/// An export of a binding declared in the module.
+/// https://tc39.github.io/ecma262/#exportentry-record
+#[derive(Clone, Debug)]
+pub struct LocalExportEntry {
+    pub export_name: String,
+    pub local_name: String,
+}
+
+/// An export of a binding of another module.
+/// https://tc39.github.io/ecma262/#exportentry-record
+#[derive(Clone, Debug)]
+pub struct IndirectExportEntry {
+    pub export_name: String,
+    /// The absolute path of the module exporting the binding.
+    pub module_request: String,
+    /// None for ``export * as export_name from``.
+    pub import_name: Option<String>,
+}
+
+/// The result of ResolveExport.
+/// https://tc39.github.io/ecma262/#sec-resolveexport
+#[derive(Clone, Debug, PartialEq)]
+enum Resolution {
+    /// The module and the name of the binding. The name is None for the namespace object.
+    Resolved(ModuleId, Option<String>),
+    NotFound,
+    /// Found in more than one module re-exported by ``export * from``.
+    Ambiguous,
+}
+
+/// All the modules loaded by the VM, each of which is loaded and evaluated only once.
+#[derive(Clone, Debug)]
+pub struct ModuleMap {
+    pub modules: Vec<ModuleRecord>,
+    ids: FxHashMap<String, ModuleId>,
+    /// ``require.cache``. The ``module`` objects of CommonJS modules keyed by absolute path.
+    pub require_cache: Value,
+}
+
+impl ModuleMap {
+    pub fn new(require_cache: Value) -> Self {
+        ModuleMap {
+            modules: vec![],
+            ids: FxHashMap::default(),
+            require_cache,
+        }
+    }
+
+    pub fn get_id(&self, file_name: &str) -> Option<ModuleId> {
+        self.ids.get(file_name).cloned()
+    }
+
+    fn insert(&mut self, module: ModuleRecord) -> ModuleId {
+        let id = self.modules.len();
+        self.ids.insert(module.file_name.clone(), id);
+        self.modules.push(module);
+        id
+    }
+
+    /// Removes the modules loaded after the first ``len`` modules.
+    fn truncate(&mut self, len: usize) {
+        for module in self.modules.drain(len..) {
+            self.ids.remove(&module.file_name);
+        }
+    }
+}
+
+/// Resolves ``specifier`` relative to the directory of the importing file. ``.js`` is
+/// appended if the specifier has no extension.
+pub fn resolve_module_specifier(referrer: &str, specifier: &str) -> Result<String, String> {
+    let dir = Path::new(referrer)
+        .parent()
+        .unwrap_or_else(|| Path::new(""));
+    let mut path = dir.join(specifier);
+    if path.extension().is_none() {
+        path.set_extension("js");
+    }
+    match path.canonicalize() {
+        Ok(path) => Ok(path.to_string_lossy().to_string()),
+        Err(_) => Err(format!(
+            "Cannot find module '{}' imported from {}",
+            specifier, referrer
+        )),
+    }
+}
+
+impl ModuleRecord {
+    /// Collects the import and export entries of the module.
+    /// https://tc39.github.io/ecma262/#sec-parsemodule
+    pub fn new(
+        file_name: String,
+        func_ref: FuncInfoRef,
+        node: &Node,
+    ) -> Result<ModuleRecord, String> {
+        let mut module = ModuleRecord {
+            file_name,
+            status: ModuleStatus::Unlinked,
+            func_ref,
+            env: None,
+            namespace: None,
+            evaluation_error: None,
+            requested_modules: vec![],
+            import_entries: vec![],
+            local_export_entries: vec![],
+            indirect_export_entries: vec![],
+            star_export_entries: vec![],
+        };
+
+        let items = match node.base {
+            NodeBase::StatementList(ref items) => items,
+            _ => unreachable!(),
+        };
+
+        let mut export_entries = vec![];
+        for item in items {
+            match item.base {
+                NodeBase::Import(ref specifiers, ref specifier) => {
+                    let module_request = module.request_module(specifier)?;
+                    for specifier in specifiers {
+                        module.import_entries.push(ImportEntry {
+                            module_request: module_request.clone(),
+                            import_name: specifier.imported.clone(),
+                            local_name: specifier.local.clone(),
+                        });
+                    }
+                }
+                NodeBase::Export(ExportDeclaration::Declaration(ref declaration)) => {
+                    for name in declared_names(declaration) {
+                        export_entries.push(LocalExportEntry {
+                            export_name: name.clone(),
+                            local_name: name,
+                        });
+                    }
+                }
+                NodeBase::Export(ExportDeclaration::Default(ref declaration)) => {
+                    let local_name = match declaration.base {
+                        NodeBase::FunctionDecl(ref name, _, _, _, _)
+                        | NodeBase::VarDecl(ref name, _, _) => name.clone(),
+                        _ => DEFAULT_EXPORT_BINDING.to_string(),
+                    };
+                    export_entries.push(LocalExportEntry {
+                        export_name: "default".to_string(),
+                        local_name,
+                    });
+                }
+                NodeBase::Export(ExportDeclaration::Named(ref names, None)) => {
+                    for (local_name, export_name) in names {
+                        export_entries.push(LocalExportEntry {
+                            export_name: export_name.clone(),
+                            local_name: local_name.clone(),
+                        });
+                    }
+                }
+                NodeBase::Export(ExportDeclaration::Named(ref names, Some(ref specifier))) => {
+                    let module_request = module.request_module(specifier)?;
+                    for (import_name, export_name) in names {
+                        module.indirect_export_entries.push(IndirectExportEntry {
+                            export_name: export_name.clone(),
+                            module_request: module_request.clone(),
+                            import_name: Some(import_name.clone()),
+                        });
+                    }
+                }
+                NodeBase::Export(ExportDeclaration::All(Some(ref export_name), ref specifier)) => {
+                    let module_request = module.request_module(specifier)?;
+                    module.indirect_export_entries.push(IndirectExportEntry {
+                        export_name: export_name.clone(),
+                        module_request,
+                        import_name: None,
+                    });
+                }
+                NodeBase::Export(ExportDeclaration::All(None, ref specifier)) => {
+                    let module_request = module.request_module(specifier)?;
+                    module.star_export_entries.push(module_request);
+                }
+                _ => {}
+            }
+        }
+
+        // Re-exports of imported bindings refer to the imported module directly.
+        for entry in export_entries {
+            let import = module
+                .import_entries
+                .iter()
+                .find(|import| import.local_name == entry.local_name);
+            match import {
+                Some(ImportEntry {
+                    module_request,
+                    import_name: Some(import_name),
+                    ..
+                }) => module.indirect_export_entries.push(IndirectExportEntry {
+                    export_name: entry.export_name,
+                    module_request: module_request.clone(),
+                    import_name: Some(import_name.clone()),
+                }),
+                _ => module.local_export_entries.push(entry),
+            }
+        }
+
+        Ok(module)
+    }
+
+    /// Resolves ``specifier`` and adds it to the requested modules.
+    fn request_module(&mut self, specifier: &str) -> Result<String, String> {
+        let module_request = resolve_module_specifier(&self.file_name, specifier)?;
+        if !self.requested_modules.contains(&module_request) {
+            self.requested_modules.push(module_request.clone());
+        }
+        Ok(module_request)
+    }
+}
+
+/// Names bound by a declaration.
+fn declared_names(declaration: &Node) -> Vec<String> {
+    match declaration.base {
+        NodeBase::StatementList(ref list) => list.iter().flat_map(declared_names).collect(),
+        NodeBase::FunctionDecl(ref name, _, _, _, _) | NodeBase::VarDecl(ref name, _, _) => {
+            vec![name.clone()]
+        }
+        NodeBase::PatternDecl(ref pattern, _, _) => pattern.bound_names(),
+        _ => vec![],
+    }
+}
+
+impl VM {
+    /// Runs the file as a module. Modules imported by it are loaded and run first.
+    pub fn run_module(&mut self, file_name: &str) -> VMResult {
+        let file_name = match Path::new(file_name).canonicalize() {
+            Ok(path) => path.to_string_lossy().to_string(),
+            Err(_) => {
+                return Err(RuntimeError::general(format!(
+                    "Cannot find module '{}'",
+                    file_name
+                )))
+            }
+        };
+        let id = self.load_module_graph(&file_name)?;
+        self.link_module(id)?;
+        self.evaluate_module(id, true)?;
+        self.run_jobs()
+    }
+
+    /// import(specifier)
+    /// Returns a promise for the namespace object of the module.
+    /// https://tc39.github.io/ecma262/#sec-import-calls
+    pub fn import_dynamically(&mut self, specifier: Value) -> Value {
+        let promise = self.factory.promise(self.factory.object_prototypes.promise);
+        match self.import_module(specifier) {
+            Ok(namespace) => builtins::promise::resolve_promise(self, promise, namespace),
+            Err(err) => {
+                let reason = err.to_value(self);
+                builtins::promise::reject_promise(self, promise, reason)
+            }
+        }
+        promise
+    }
+
+    /// Loads, links and evaluates the module ``specifier`` imported by the running script or
+    /// module, and returns its namespace object.
+    fn import_module(&mut self, specifier: Value) -> VMValueResult {
+        let specifier = specifier.to_string();
+        let referrer = self.current_file_name();
+        let file_name =
+            resolve_module_specifier(&referrer, &specifier).map_err(RuntimeError::general)?;
+
+        let id = self.load_module_graph(&file_name)?;
+        self.link_module(id)?;
+        self.evaluate_module(id, false)?;
+        Ok(self.get_module_namespace(id))
+    }
+
+    /// Returns the file name of the running script or module, or an empty string if unknown.
+    pub fn current_file_name(&self) -> String {
+        let module_func_id = self.current_context.func_ref.module_func_id;
+        self.script_info
+            .iter()
+            .find(|(id, _)| *id == module_func_id)
+            .map(|(_, info)| info.file_name.clone())
+            .unwrap_or_default()
+    }
+
+    /// Loads the module and all the modules it depends on. Nothing is loaded on error.
+    fn load_module_graph(&mut self, file_name: &str) -> Result<ModuleId, RuntimeError> {
+        let len = self.module_map.modules.len();
+        let result = self.load_module(file_name);
+        if result.is_err() {
+            self.module_map.truncate(len);
+        }
+        result
+    }
+
+    /// Parses and compiles the module, unless it is already loaded.
+    /// https://tc39.github.io/ecma262/#sec-hostresolveimportedmodule
+    fn load_module(&mut self, file_name: &str) -> Result<ModuleId, RuntimeError> {
+        if let Some(id) = self.module_map.get_id(file_name) {
+            return Ok(id);
+        }
+
+        let code = std::fs::read_to_string(file_name).map_err(|ioerr| {
+            RuntimeError::general(format!("Cannot load module '{}': {}", file_name, ioerr))
+        })?;
+        let mut parser = Parser::new(file_name, code);
+        let node = parser.parse_module().map_err(|parse_err| {
+            parser.handle_error(&parse_err);
+            RuntimeError::syntax(format!("Error in parsing module \"{}\"", file_name))
+        })?;
+
+        use crate::vm::codegen::Error;
+        let func_ref = self.compile_module(&node).map_err(|codegen_err| {
+            let Error { msg, token_pos, .. } = codegen_err;
+            parser.show_error_at(token_pos, msg);
+            RuntimeError::syntax(format!("Error in parsing module \"{}\"", file_name))
+        })?;
+        self.script_info
+            .push((func_ref.module_func_id, parser.into_script_info()));
+
+        let module = ModuleRecord::new(file_name.to_string(), func_ref, &node)
+            .map_err(RuntimeError::general)?;
+        let requested_modules = module.requested_modules.clone();
+        let id = self.module_map.insert(module);
+
+        for module_request in requested_modules {
+            self.load_module(&module_request)?;
+        }
+
+        Ok(id)
+    }
+
+    /// Creates the environments of the module and the modules it depends on, and binds
+    /// their imports. Imports may refer to modules in a cycle, so all the environments are
+    /// created first.
+    /// https://tc39.github.io/ecma262/#sec-moduledeclarationlinking
+    fn link_module(&mut self, id: ModuleId) -> VMResult {
+        let mut linking = vec![];
+        self.create_module_environments(id, &mut linking);
+
+        for &id in &linking {
+            if let Err(err) = self.initialize_imports(id) {
+                for &id in &linking {
+                    let module = &mut self.module_map.modules[id];
+                    module.status = ModuleStatus::Unlinked;
+                    module.env = None;
+                    module.namespace = None;
+                }
+                return Err(err);
+            }
+        }
+
+        for id in linking {
+            self.module_map.modules[id].status = ModuleStatus::Linked;
+        }
+        Ok(())
+    }
+
+    fn create_module_environments(&mut self, id: ModuleId, linking: &mut Vec<ModuleId>) {
+        if self.module_map.modules[id].status != ModuleStatus::Unlinked {
+            return;
+        }
+
+        let func_ref = self.module_map.modules[id].func_ref;
+        let env = self
+            .factory
+            .create_module_environment(func_ref, self.global_environment);
+        let module = &mut self.module_map.modules[id];
+        module.env = Some(env);
+        module.status = ModuleStatus::Linking;
+        linking.push(id);
+
+        for module_request in module.requested_modules.clone() {
+            let requested = self.module_map.get_id(&module_request).unwrap();
+            self.create_module_environments(requested, linking);
+        }
+    }
+
+    /// https://tc39.github.io/ecma262/#sec-source-text-module-record-initialize-environment
+    fn initialize_imports(&mut self, id: ModuleId) -> VMResult {
+        let module = self.module_map.modules[id].clone();
+
+        for entry in &module.indirect_export_entries {
+            if let Resolution::Resolved(_, _) =
+                self.resolve_export(id, &entry.export_name, &mut vec![])
+            {
+                continue;
+            }
+            return Err(RuntimeError::general(format!(
+                "The requested module '{}' does not provide an export named '{}'",
+                entry.module_request,
+                entry.import_name.as_ref().unwrap()
+            )));
+        }
+
+        let mut env = module.env.unwrap();
+        for entry in &module.import_entries {
+            let imported = self.module_map.get_id(&entry.module_request).unwrap();
+            let import_name = match entry.import_name {
+                Some(ref import_name) => import_name,
+                None => {
+                    let namespace = self.get_module_namespace(imported);
+                    env.set_own_value(entry.local_name.clone(), namespace)?;
+                    continue;
+                }
+            };
+
+            match self.resolve_export(imported, import_name, &mut vec![]) {
+                Resolution::Resolved(target, Some(binding_name)) => {
+                    let target_env = self.module_map.modules[target].env.unwrap();
+                    if let EnvironmentRecord::Module {
+                        ref mut imports, ..
+                    } = env.record
+                    {
+                        imports.insert(entry.local_name.clone(), (target_env, binding_name));
+                    }
+                }
+                Resolution::Resolved(target, None) => {
+                    let namespace = self.get_module_namespace(target);
+                    env.set_own_value(entry.local_name.clone(), namespace)?;
+                }
+                Resolution::NotFound => {
+                    return Err(RuntimeError::general(format!(
+                        "The requested module '{}' does not provide an export named '{}'",
+                        entry.module_request, import_name
+                    )));
+                }
+                Resolution::Ambiguous => {
+                    return Err(RuntimeError::general(format!(
+                        "The requested module '{}' contains conflicting star exports for name '{}'",
+                        entry.module_request, import_name
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Finds the binding ``export_name`` of the module refers to, following re-exports.
+    /// ``resolve_set`` holds the exports being resolved to detect circular re-exports.
+    /// https://tc39.github.io/ecma262/#sec-resolveexport
+    fn resolve_export(
+        &self,
+        id: ModuleId,
+        export_name: &str,
+        resolve_set: &mut Vec<(ModuleId, String)>,
+    ) -> Resolution {
+        if resolve_set
+            .iter()
+            .any(|(module, name)| *module == id && name == export_name)
+        {
+            return Resolution::NotFound;
+        }
+        resolve_set.push((id, export_name.to_string()));
+
+        let module = &self.module_map.modules[id];
+
+        for entry in &module.local_export_entries {
+            if entry.export_name == export_name {
+                return Resolution::Resolved(id, Some(entry.local_name.clone()));
+            }
+        }
+
+        for entry in &module.indirect_export_entries {
+            if entry.export_name == export_name {
+                let imported = self.module_map.get_id(&entry.module_request).unwrap();
+                return match entry.import_name {
+                    Some(ref import_name) => {
+                        self.resolve_export(imported, import_name, resolve_set)
+                    }
+                    None => Resolution::Resolved(imported, None),
+                };
+            }
+        }
+
+        // ``export * from`` does not re-export the default export.
+        if export_name == "default" {
+            return Resolution::NotFound;
+        }
+
+        let mut star_resolution = Resolution::NotFound;
+        for module_request in &module.star_export_entries {
+            let imported = self.module_map.get_id(module_request).unwrap();
+            match self.resolve_export(imported, export_name, resolve_set) {
+                Resolution::Ambiguous => return Resolution::Ambiguous,
+                Resolution::NotFound => {}
+                resolution => {
+                    if star_resolution == Resolution::NotFound {
+                        star_resolution = resolution;
+                    } else if star_resolution != resolution {
+                        return Resolution::Ambiguous;
+                    }
+                }
+            }
+        }
+        star_resolution
+    }
+
+    /// https://tc39.github.io/ecma262/#sec-getexportednames
+    fn get_exported_names(&self, id: ModuleId, export_star_set: &mut Vec<ModuleId>) -> Vec<String> {
+        if export_star_set.contains(&id) {
+            return vec![];
+        }
+        export_star_set.push(id);
+
+        let module = &self.module_map.modules[id];
+        let mut names = vec![];
+        for entry in &module.local_export_entries {
+            names.push(entry.export_name.clone());
+        }
+        for entry in &module.indirect_export_entries {
+            names.push(entry.export_name.clone());
+        }
+        for module_request in &module.star_export_entries {
+            let imported = self.module_map.get_id(module_request).unwrap();
+            for name in self.get_exported_names(imported, export_star_set) {
+                if name != "default" && !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+        }
+        names
+    }
+
+    /// Returns the namespace object of the module. Ambiguous exports are left out.
+    /// https://tc39.github.io/ecma262/#sec-getmodulenamespace
+    pub fn get_module_namespace(&mut self, id: ModuleId) -> Value {
+        if let Some(namespace) = self.module_map.modules[id].namespace {
+            return namespace;
+        }
+
+        let mut exports = vec![];
+        for name in self.get_exported_names(id, &mut vec![]) {
+            let binding = match self.resolve_export(id, &name, &mut vec![]) {
+                Resolution::Resolved(target, Some(binding_name)) => ResolvedBinding::Binding(
+                    self.module_map.modules[target].env.unwrap(),
+                    binding_name,
+                ),
+                Resolution::Resolved(target, None) => {
+                    ResolvedBinding::Namespace(self.get_module_namespace(target))
+                }
+                Resolution::NotFound | Resolution::Ambiguous => continue,
+            };
+            exports.push((name, binding));
+        }
+        exports.sort_by(|(name1, _), (name2, _)| name1.cmp(name2));
+
+        let namespace = self.factory.module_namespace(exports);
+        self.module_map.modules[id].namespace = Some(namespace);
+        namespace
+    }
+
+    /// Evaluates the modules the module depends on, and then the module itself. A module in
+    /// a cycle that is being evaluated is skipped, and its bindings may be uninitialized.
+    /// https://tc39.github.io/ecma262/#sec-moduleevaluation
+    fn evaluate_module(&mut self, id: ModuleId, is_top_level: bool) -> VMResult {
+        match self.module_map.modules[id].status {
+            ModuleStatus::Evaluating => return Ok(()),
+            ModuleStatus::Evaluated => {
+                return match self.module_map.modules[id].evaluation_error {
+                    Some(err) => Err(self.current_context.error_exception(err)),
+                    None => Ok(()),
+                };
+            }
+            _ => {}
+        }
+        self.module_map.modules[id].status = ModuleStatus::Evaluating;
+
+        let mut result = Ok(());
+        for module_request in self.module_map.modules[id].requested_modules.clone() {
+            let requested = self.module_map.get_id(&module_request).unwrap();
+            result = self.evaluate_module(requested, is_top_level);
+            if result.is_err() {
+                break;
+            }
+        }
+        if result.is_ok() {
+            result = self.run_module_body(id, is_top_level);
+        }
+
+        self.module_map.modules[id].status = ModuleStatus::Evaluated;
+        if let Err(ref err) = result {
+            let val = err.clone().to_value(self);
+            self.module_map.modules[id].evaluation_error = Some(val);
+        }
+        result
+    }
+
+    fn run_module_body(&mut self, id: ModuleId, is_top_level: bool) -> VMResult {
+        let module = &self.module_map.modules[id];
+        let env = module.env.unwrap();
+        let func_ref = module.func_ref;
+
+        if is_top_level {
+            // Nothing but the module is running, so it can run like a script.
+            let context = ExecContext::new(
+                env,
+                env,
+                func_ref,
+                Value::undefined(),
+                CallMode::OrdinaryCall,
+            );
+            self.current_context = context;
+            return self.run().map(|_| ());
+        }
+
+        let context =
+            ExecContext::new(env, env, func_ref, Value::undefined(), CallMode::FromNative);
+        let caller = std::mem::replace(&mut self.current_context, context);
+        self.saved_context.push(caller);
+        self.run_from_native().map(|_| ())
+    }
+}
@@ -1,18 +1,24 @@
+use crate::builtin::object_kind;
+use crate::builtins;
 use crate::builtins::console::debug_print;
+use crate::builtins::iterator::create_iter_result_object;
 use crate::bytecode_gen::{inst_to_inst_name, show_inst, VMInst};
 use crate::gc;
-use crate::node::Node;
+use crate::node::{MethodDefinitionKind, Node};
 use crate::parser::ScriptInfo;
 pub use crate::vm::exec_context::{
-    EnvironmentRecord, ExecContext, LexicalEnvironment, LexicalEnvironmentRef,
+    EnvironmentRecord, ExecContext, LexicalEnvironment, LexicalEnvironmentRef, OpenIterator,
+    SubroutineKind,
 };
 pub use crate::vm::factory::{Factory, FunctionId};
 pub use crate::vm::jsvalue::function::{DestinationKind, FunctionParameter, ThisMode};
 use crate::vm::{
     codegen, codegen::CodeGenerator, constant, error::*, jsvalue::prototype::ObjectPrototypes,
-    jsvalue::symbol::GlobalSymbolRegistry, jsvalue::value::*,
+    jsvalue::symbol::GlobalSymbolRegistry, jsvalue::value::*, module::ModuleMap,
 };
-use rustc_hash::FxHashMap;
+use rand::rngs::SmallRng;
+use rand::{FromEntropy, SeedableRng};
+use rustc_hash::{FxHashMap, FxHashSet};
 use std::time::{Duration, Instant};
 
 pub type VMResult = Result<(), RuntimeError>;
@@ -26,6 +32,9 @@ pub struct VM {
     pub global_symbol_registry: GlobalSymbolRegistry,
     pub current_context: ExecContext,
     pub saved_context: Vec<ExecContext>,
+    /// The context of an async function suspended by the last ``await``.
+    pub suspended_context: Option<ExecContext>,
+    pub job_queue: JobQueue,
     pub is_called_from_native: bool,
     ///func_id, ToSourcePos
     pub to_source_map: FxHashMap<FunctionId, codegen::ToSourcePos>,
@@ -33,6 +42,9 @@ pub struct VM {
     pub is_trace: bool,
     ///(func_id, script_info)
     pub script_info: Vec<(FunctionId, ScriptInfo)>,
+    pub module_map: ModuleMap,
+    /// The generator of Math.random().
+    pub random: SmallRng,
     pub profile: Profiler,
 }
 
@@ -47,6 +59,16 @@ pub struct Profiler {
     start_flag: bool,
 }
 
+/// The result of resuming the inner iterator of ``yield*``.
+enum YieldDelegateStep {
+    /// The inner iterator result to yield as is.
+    Yield(Value),
+    /// The value of the ``yield*`` expression.
+    Complete(Value),
+    /// The value to return from the generator.
+    Return(Value),
+}
+
 #[derive(Debug, Clone, PartialEq, Copy)]
 pub enum CallMode {
     OrdinaryCall,
@@ -61,6 +83,10 @@ impl VM {
         factory.object_prototypes = ObjectPrototypes::new(&mut factory);
         let global_env = LexicalEnvironment::new_global_initialized(&mut factory);
         let global_environment = LexicalEnvironmentRef(factory.alloc(global_env));
+        let require_cache = global_environment
+            .get_value("require")
+            .unwrap()
+            .get_property("cache");
         VM {
             global_environment,
             factory,
@@ -68,11 +94,15 @@ impl VM {
             global_symbol_registry: GlobalSymbolRegistry::new(),
             current_context: ExecContext::empty(),
             saved_context: vec![],
+            suspended_context: None,
+            job_queue: JobQueue::default(),
             is_called_from_native: false,
             to_source_map: FxHashMap::default(),
             is_profile: false,
             is_trace: false,
             script_info: vec![],
+            module_map: ModuleMap::new(require_cache),
+            random: SmallRng::from_entropy(),
             profile: Profiler {
                 instant: Instant::now(),
                 prev_time: Duration::from_secs(0),
@@ -96,15 +126,24 @@ impl VM {
         self
     }
 
+    /// Seeds Math.random() so that it returns the same sequence on every run.
+    pub fn random_seed(mut self, seed: u64) -> Self {
+        self.random = SmallRng::seed_from_u64(seed);
+        self
+    }
+
     pub fn gc_mark(&mut self) {
         let time_before_gc = self.profile.instant.elapsed();
         let gc_mode = self.factory.memory_allocator.state;
         self.factory.memory_allocator.mark(
             self.global_environment,
             &self.factory.object_prototypes,
+            &self.factory.symbols,
             &self.constant_table,
             &self.current_context,
             &self.saved_context,
+            &self.job_queue,
+            &self.module_map,
         );
         let i = match gc_mode {
             gc::GCState::Initial => 0,
@@ -128,6 +167,28 @@ impl VM {
         res
     }
 
+    pub fn compile_function(&mut self, node: &Node) -> Result<FuncInfoRef, codegen::Error> {
+        let func_id = self.factory.new_func_id();
+        let mut code_generator =
+            CodeGenerator::new(&mut self.constant_table, &mut self.factory, func_id);
+        let res = code_generator.compile_function(node);
+        for (func_id, list) in code_generator.to_source_map {
+            self.to_source_map.insert(func_id, list);
+        }
+        res
+    }
+
+    pub fn compile_module(&mut self, node: &Node) -> Result<FuncInfoRef, codegen::Error> {
+        let func_id = self.factory.new_func_id();
+        let mut code_generator =
+            CodeGenerator::new(&mut self.constant_table, &mut self.factory, func_id);
+        let res = code_generator.compile_module(node);
+        for (func_id, list) in code_generator.to_source_map {
+            self.to_source_map.insert(func_id, list);
+        }
+        res
+    }
+
     pub fn create_global_context(&mut self, global_info: FuncInfoRef) -> ExecContext {
         let global_env_ref = self.global_environment;
 
@@ -159,10 +220,26 @@ impl VM {
     pub fn run_global(&mut self, func_info: FuncInfoRef) -> VMResult {
         self.current_context = self.create_global_context(func_info);
         self.run()?;
+        self.run_jobs()?;
 
         Ok(())
     }
 
+    /// Runs the jobs in the job queue until it becomes empty.
+    /// Returns Err if a rejected promise is left without handlers.
+    pub fn run_jobs(&mut self) -> VMResult {
+        while let Some(job) = self.job_queue.jobs.pop_front() {
+            builtins::promise::run_job(self, job)?;
+        }
+
+        if self.job_queue.rejected_promises.is_empty() {
+            return Ok(());
+        }
+        let (_, error) = self.job_queue.rejected_promises.remove(0);
+        self.job_queue.rejected_promises.clear();
+        Err(error)
+    }
+
     pub fn call_function(&mut self, callee: Value, args: &[Value], this: Value) -> VMValueResult {
         if !callee.is_function_object() {
             return Err(self.current_context.error_type("Not a function"));
@@ -172,9 +249,23 @@ impl VM {
 
         match info.kind {
             FunctionObjectKind::Builtin(func) => func(self, args, this),
+            FunctionObjectKind::User { info, outer_env } if info.is_async => {
+                self.call_async_function(info, outer_env, args, this)
+            }
+            FunctionObjectKind::User { info, outer_env } if info.is_generator => {
+                self.call_generator_function(callee, info, outer_env, args, this)
+            }
             FunctionObjectKind::User { info, outer_env } => {
                 self.call_user_function(info, outer_env, args, this, false)
             }
+            FunctionObjectKind::Bound {
+                target,
+                this,
+                args: ref bound_args,
+            } => {
+                let args = [&bound_args[..], args].concat();
+                self.call_function(target, &args, this)
+            }
         }
     }
 
@@ -194,14 +285,141 @@ impl VM {
             CallMode::FromNative,
             constructor_call,
         )?;
+        self.run_from_native()
+    }
+
+    /// Run the current context prepared with CallMode::FromNative until it returns.
+    /// On error, the context of the native caller is restored.
+    pub fn run_from_native(&mut self) -> VMValueResult {
+        let depth = self.saved_context.len();
         // if called from builtin func, do not GC.
         let save = self.is_called_from_native;
         self.is_called_from_native = true;
         let res = self.run();
         self.is_called_from_native = save;
+        if res.is_err() && self.saved_context.len() >= depth {
+            self.saved_context.truncate(depth);
+            self.current_context = self.saved_context.pop().unwrap();
+        }
         res
     }
 
+    /// Call an async function. The body runs until the first ``await``, and the promise for the
+    /// result is returned.
+    /// https://tc39.github.io/ecma262/#sec-async-functions-abstract-operations-async-function-start
+    fn call_async_function(
+        &mut self,
+        user_func: FuncInfoRef,
+        outer_env: Option<LexicalEnvironmentRef>,
+        args: &[Value],
+        this: Value,
+    ) -> VMValueResult {
+        let promise = self.factory.promise(self.factory.object_prototypes.promise);
+        self.prepare_context_for_function_invokation(
+            user_func,
+            outer_env,
+            args,
+            this,
+            CallMode::FromNative,
+            false,
+        )?;
+        self.run_async_function(None, promise);
+        Ok(promise)
+    }
+
+    /// Resume an async function suspended at ``await`` with the result of the awaited promise.
+    pub fn resume_async_function(&mut self, state: Value, value: Value, is_rejected: bool) {
+        let mut obj_info = state.get_object_info();
+        let (context, promise) = match obj_info.kind {
+            ObjectKind::AsyncFunction(ref mut info) => (info.context.take().unwrap(), info.promise),
+            _ => unreachable!(),
+        };
+        let caller = std::mem::replace(&mut self.current_context, context);
+        self.saved_context.push(caller);
+        self.current_context.stack.push(value.into());
+        self.current_context
+            .stack
+            .push(Value::bool(is_rejected).into());
+        self.run_async_function(Some(state), promise);
+    }
+
+    /// Run the current context of an async function until it awaits or completes.
+    /// On completion, ``promise`` is settled with the result.
+    fn run_async_function(&mut self, state: Option<Value>, promise: Value) {
+        match self.run_from_native() {
+            Ok(val) => match self.suspended_context.take() {
+                Some(context) => {
+                    let state = match state {
+                        Some(state) => {
+                            if let ObjectKind::AsyncFunction(ref mut info) =
+                                state.get_object_info().kind
+                            {
+                                info.context = Some(context);
+                            }
+                            state
+                        }
+                        None => self.factory.async_function(context, promise),
+                    };
+                    builtins::promise::await_value(self, state, val)
+                }
+                None => builtins::promise::resolve_promise(self, promise, val),
+            },
+            Err(err) => {
+                let reason = err.to_value(self);
+                builtins::promise::reject_promise(self, promise, reason)
+            }
+        }
+    }
+
+    /// Call a generator function. The body does not run until ``next()`` is called on the
+    /// returned generator object.
+    /// https://tc39.github.io/ecma262/#sec-generator-function-definitions-runtime-semantics-evaluatebody
+    fn call_generator_function(
+        &mut self,
+        callee: Value,
+        user_func: FuncInfoRef,
+        outer_env: Option<LexicalEnvironmentRef>,
+        args: &[Value],
+        this: Value,
+    ) -> VMValueResult {
+        let prototype = match callee.get_property("prototype") {
+            prototype if prototype.is_object() => prototype,
+            _ => self.factory.object_prototypes.generator,
+        };
+        self.prepare_context_for_function_invokation(
+            user_func,
+            outer_env,
+            args,
+            this,
+            CallMode::FromNative,
+            false,
+        )?;
+        let caller = self.saved_context.pop().unwrap();
+        let context = std::mem::replace(&mut self.current_context, caller);
+        Ok(self.factory.generator(prototype, context))
+    }
+
+    /// Run the context of a generator until it yields or completes. Returns the iterator result
+    /// and the context suspended at ``yield``, or the return value and None on completion.
+    pub fn run_generator_context(
+        &mut self,
+        context: ExecContext,
+    ) -> Result<(Value, Option<ExecContext>), RuntimeError> {
+        let caller = std::mem::replace(&mut self.current_context, context);
+        self.saved_context.push(caller);
+        let val = self.run_from_native()?;
+        Ok((val, self.suspended_context.take()))
+    }
+
+    /// Suspend the current context, which runs from native code, and return ``val`` to the
+    /// native caller. The context is kept in ``suspended_context`` until the caller takes it.
+    fn suspend_current_context(&mut self, val: Value) {
+        let caller = self.saved_context.pop().unwrap();
+        let context = std::mem::replace(&mut self.current_context, caller);
+        self.suspended_context = Some(context);
+        self.current_context.stack.push(val.into());
+    }
+
     fn get_property_to_stack_top(&mut self, parent: Value, key: Value) -> VMResult {
         let val = parent.get_property_by_value(&mut self.factory, key)?;
         match val {
@@ -231,8 +449,7 @@ impl VM {
                 if get.is_undefined() {
                     return Ok(Value::undefined());
                 }
-                self.call_function(get, &[], parent)?;
-                Ok(self.current_context.stack.pop().unwrap().into(): Value)
+                self.call_function(get, &[], parent)
             }
         }
     }
@@ -242,40 +459,89 @@ impl VM {
             parent.set_property_by_value(&mut self.factory.memory_allocator, key, val)?;
         if let Some(setter) = maybe_setter {
             self.call_function(setter, &[val], parent)?;
-            self.current_context.stack.pop().unwrap(); // Pop undefined (setter's return value)
         }
         Ok(())
     }
 }
 
 impl VM {
+    /// Records the stack trace of the running contexts in ``error``, an Error object.
+    pub fn capture_stack_trace(&self, error: Value) {
+        let stack_trace = self.stack_trace();
+        if let ObjectKind::Error(ref mut info) = error.get_object_info().kind {
+            info.stack_trace = stack_trace;
+        }
+    }
+
+    /// A line of ``    at fnName (file:line:col)`` for each running context, the innermost first.
+    /// A constructor is ``new fnName``, and an anonymous function or the top level has only
+    /// ``file:line:col``.
+    fn stack_trace(&self) -> String {
+        let contexts =
+            std::iter::once(&self.current_context).chain(self.saved_context.iter().rev());
+        let mut stack_trace = String::new();
+        for context in contexts {
+            if context.func_ref.as_ptr().is_null() {
+                continue;
+            }
+            let func = context.func_ref;
+            let script_info = match self
+                .script_info
+                .iter()
+                .find(|(id, _)| *id == func.module_func_id)
+            {
+                Some((_, script_info)) => script_info,
+                None => continue,
+            };
+            let pos = self
+                .to_source_map
+                .get(&func.func_id)
+                .and_then(|source_map| source_map.get_node_pos(context.current_inst_pc));
+            let location = match pos {
+                Some(pos) => {
+                    let (line, column) = script_info.line_and_column(pos);
+                    format!("{}:{}:{}", script_info.file_name, line, column)
+                }
+                None => script_info.file_name.clone(),
+            };
+            let new = if context.constructor_call { "new " } else { "" };
+            stack_trace += &match func.func_name {
+                Some(ref name) => format!("\n    at {}{} ({})", new, name, location),
+                None => format!("\n    at {}", location),
+            };
+        }
+        stack_trace
+    }
+
     pub fn show_error_message(&self, error: RuntimeError) {
         match &error.kind {
             ErrorKind::Unknown => runtime_error("UnknownError"),
             ErrorKind::Unimplemented => runtime_error("Unimplemented feature"),
             ErrorKind::Reference(msg) => runtime_error(format!("ReferenceError: {}", msg)),
             ErrorKind::Type(msg) => runtime_error(format!("TypeError: {}", msg)),
+            ErrorKind::Range(msg) => runtime_error(format!("RangeError: {}", msg)),
+            ErrorKind::Syntax(msg) => runtime_error(format!("SyntaxError: {}", msg)),
             ErrorKind::General(msg) => runtime_error(format!("Error: {}", msg)),
             ErrorKind::Exception(ref val) => {
                 runtime_error("Uncaught Exception");
                 let pos_in_script = self
                     .to_source_map
                     .get(&error.func_id)
-                    .unwrap()
-                    .get_node_pos(error.inst_pc);
+                    .and_then(|source_map| source_map.get_node_pos(error.inst_pc));
                 let module_func_id = error.module_func_id;
-                let info = &self
+                let info = self
                     .script_info
                     .iter()
-                    .find(|info| info.0 == module_func_id)
-                    .unwrap()
-                    .1;
-                if let Some(pos) = pos_in_script {
+                    .find(|info| info.0 == module_func_id);
+                if let (Some(pos), Some((_, info))) = (pos_in_script, info) {
                     let (msg, _, line) = get_code_around_err_point(info, pos);
                     println!("line: {}", line);
                     println!("{}", msg);
                 }
                 debug_print(val, false);
+                if let Some(ObjectKind::Error(info)) = object_kind(*val) {
+                    print!("{}", info.stack_trace);
+                }
                 println!();
             }
         }
@@ -326,14 +592,7 @@ macro_rules! read_int32 {
 
 impl VM {
     pub fn run(&mut self) -> VMValueResult {
-        #[derive(Debug, Clone)]
-        enum SubroutineKind {
-            Ordinary(usize),
-            Throw,
-            Return,
-        }
-
-        fn handle_exception(vm: &mut VM, subroutine_stack: &mut Vec<SubroutineKind>) -> VMResult {
+        fn handle_exception(vm: &mut VM) -> VMResult {
             let mut trycatch_found = false;
             let save_error_info = vm.current_context.error_unknown();
             loop {
@@ -346,7 +605,9 @@ impl VM {
                     match exception.dst_kind {
                         DestinationKind::Catch => vm.current_context.pc = exception.end,
                         DestinationKind::Finally => {
-                            subroutine_stack.push(SubroutineKind::Throw);
+                            vm.current_context
+                                .subroutine_stack
+                                .push(SubroutineKind::Throw);
                             vm.current_context.pc = exception.end
                         }
                     }
@@ -359,7 +620,10 @@ impl VM {
                     break;
                 }
 
-                if vm.saved_context.len() == 0 {
+                // Do not unwind the native caller. It receives the error instead.
+                if vm.current_context.call_mode == CallMode::FromNative
+                    || vm.saved_context.is_empty()
+                {
                     break;
                 }
                 vm.unwind_context();
@@ -367,6 +631,9 @@ impl VM {
 
             if !trycatch_found {
                 let val: Value = vm.current_context.stack.pop().unwrap().into();
+                if vm.current_context.call_mode == CallMode::FromNative {
+                    vm.current_context = vm.saved_context.pop().unwrap();
+                }
                 let mut err = save_error_info;
                 err.kind = ErrorKind::Exception(val);
                 return Err(err);
@@ -375,7 +642,6 @@ impl VM {
             }
         }
 
-        let mut subroutine_stack: Vec<SubroutineKind> = vec![];
         self.profile.trace_string = "".to_string();
 
         loop {
@@ -386,12 +652,18 @@ impl VM {
 
             macro_rules! type_error {
                 ($msg:expr) => {{
-                    let val = self
-                        .current_context
-                        .error_type($msg)
-                        .to_value(&mut self.factory);
+                    let val = self.current_context.error_type($msg).to_value(self);
+                    self.current_context.stack.push(val.into());
+                    handle_exception(self)?;
+                    continue;
+                }};
+            }
+
+            macro_rules! reference_error {
+                ($msg:expr) => {{
+                    let val = self.current_context.error_reference($msg).to_value(self);
                     self.current_context.stack.push(val.into());
-                    handle_exception(self, &mut subroutine_stack)?;
+                    handle_exception(self)?;
                     continue;
                 }};
             }
@@ -402,9 +674,9 @@ impl VM {
                         Ok(ok) => ok,
                         Err(err) => {
                             let err = err.error_add_info(&self.current_context);
-                            let val = err.to_value(&mut self.factory);
+                            let val = err.to_value(self);
                             self.current_context.stack.push(val.into());
-                            handle_exception(self, &mut subroutine_stack)?;
+                            handle_exception(self)?;
                             continue;
                         }
                     }
@@ -429,8 +701,8 @@ impl VM {
                             .push(unsafe { std::mem::transmute(res) });
                     } else {
                         */
-                    let rhs_val: Value = rhs.into();
-                    let lhs_val: Value = lhs.into();
+                    let lhs_val = etry!(self.to_primitive(lhs.into(), PreferredType::Default));
+                    let rhs_val = etry!(self.to_primitive(rhs.into(), PreferredType::Default));
                     self.current_context.stack.push(
                         lhs_val
                             .add(&mut self.factory.memory_allocator, rhs_val)
@@ -442,6 +714,8 @@ impl VM {
                     self.current_context.pc += 1;
                     let rhs: Value = self.current_context.stack.pop().unwrap().into();
                     let lhs: Value = self.current_context.stack.pop().unwrap().into();
+                    let lhs = etry!(self.to_primitive(lhs, PreferredType::Number));
+                    let rhs = etry!(self.to_primitive(rhs, PreferredType::Number));
                     self.current_context
                         .stack
                         .push(lhs.sub(&mut self.factory.memory_allocator, rhs).into());
@@ -450,24 +724,32 @@ impl VM {
                     self.current_context.pc += 1;
                     let rhs: Value = self.current_context.stack.pop().unwrap().into();
                     let lhs: Value = self.current_context.stack.pop().unwrap().into();
+                    let lhs = etry!(self.to_primitive(lhs, PreferredType::Number));
+                    let rhs = etry!(self.to_primitive(rhs, PreferredType::Number));
                     self.current_context.stack.push(lhs.mul(rhs).into());
                 }
                 VMInst::DIV => {
                     self.current_context.pc += 1;
                     let rhs: Value = self.current_context.stack.pop().unwrap().into();
                     let lhs: Value = self.current_context.stack.pop().unwrap().into();
+                    let lhs = etry!(self.to_primitive(lhs, PreferredType::Number));
+                    let rhs = etry!(self.to_primitive(rhs, PreferredType::Number));
                     self.current_context.stack.push(lhs.div(rhs).into());
                 }
                 VMInst::REM => {
                     self.current_context.pc += 1;
                     let rhs: Value = self.current_context.stack.pop().unwrap().into();
                     let lhs: Value = self.current_context.stack.pop().unwrap().into();
+                    let lhs = etry!(self.to_primitive(lhs, PreferredType::Number));
+                    let rhs = etry!(self.to_primitive(rhs, PreferredType::Number));
                     self.current_context.stack.push(lhs.rem(rhs).into());
                 }
                 VMInst::EXP => {
                     self.current_context.pc += 1;
                     let rhs: Value = self.current_context.stack.pop().unwrap().into();
                     let lhs: Value = self.current_context.stack.pop().unwrap().into();
+                    let lhs = etry!(self.to_primitive(lhs, PreferredType::Number));
+                    let rhs = etry!(self.to_primitive(rhs, PreferredType::Number));
                     self.current_context
                         .stack
                         .push(lhs.exp(&mut self.factory.memory_allocator, rhs).into());
@@ -476,6 +758,7 @@ impl VM {
                     self.current_context.pc += 1;
                     let rhs: Value = self.current_context.stack.pop().unwrap().into();
                     let lhs: Value = self.current_context.stack.pop().unwrap().into();
+                    let (lhs, rhs) = etry!(self.equality_operands(lhs, rhs));
                     self.current_context
                         .stack
                         .push(lhs.eq(&mut self.factory.memory_allocator, rhs).into());
@@ -490,6 +773,7 @@ impl VM {
                     self.current_context.pc += 1;
                     let rhs: Value = self.current_context.stack.pop().unwrap().into();
                     let lhs: Value = self.current_context.stack.pop().unwrap().into();
+                    let (lhs, rhs) = etry!(self.equality_operands(lhs, rhs));
                     self.current_context
                         .stack
                         .push(lhs.ne(&mut self.factory.memory_allocator, rhs).into());
@@ -504,6 +788,8 @@ impl VM {
                     self.current_context.pc += 1;
                     let rhs: Value = self.current_context.stack.pop().unwrap().into();
                     let lhs: Value = self.current_context.stack.pop().unwrap().into();
+                    let lhs = etry!(self.to_primitive(lhs, PreferredType::Number));
+                    let rhs = etry!(self.to_primitive(rhs, PreferredType::Number));
                     self.current_context
                         .stack
                         .push(lhs.lt(&mut self.factory.memory_allocator, rhs).into());
@@ -512,6 +798,8 @@ impl VM {
                     self.current_context.pc += 1;
                     let rhs: Value = self.current_context.stack.pop().unwrap().into();
                     let lhs: Value = self.current_context.stack.pop().unwrap().into();
+                    let lhs = etry!(self.to_primitive(lhs, PreferredType::Number));
+                    let rhs = etry!(self.to_primitive(rhs, PreferredType::Number));
                     self.current_context
                         .stack
                         .push(lhs.le(&mut self.factory.memory_allocator, rhs).into());
@@ -520,6 +808,8 @@ impl VM {
                     self.current_context.pc += 1;
                     let rhs: Value = self.current_context.stack.pop().unwrap().into();
                     let lhs: Value = self.current_context.stack.pop().unwrap().into();
+                    let lhs = etry!(self.to_primitive(lhs, PreferredType::Number));
+                    let rhs = etry!(self.to_primitive(rhs, PreferredType::Number));
                     self.current_context
                         .stack
                         .push(rhs.lt(&mut self.factory.memory_allocator, lhs).into());
@@ -528,14 +818,74 @@ impl VM {
                     self.current_context.pc += 1;
                     let rhs: Value = self.current_context.stack.pop().unwrap().into();
                     let lhs: Value = self.current_context.stack.pop().unwrap().into();
+                    let lhs = etry!(self.to_primitive(lhs, PreferredType::Number));
+                    let rhs = etry!(self.to_primitive(rhs, PreferredType::Number));
                     self.current_context
                         .stack
                         .push(rhs.le(&mut self.factory.memory_allocator, lhs).into());
                 }
+                VMInst::IN => {
+                    self.current_context.pc += 1;
+                    let rhs: Value = self.current_context.stack.pop().unwrap().into();
+                    let lhs: Value = self.current_context.stack.pop().unwrap().into();
+                    if !rhs.is_object() {
+                        type_error!(format!(
+                            "Cannot use 'in' operator to search for '{}' in {}",
+                            lhs.debug_string(false),
+                            rhs.debug_string(true)
+                        ))
+                    }
+                    let has_property = rhs
+                        .get_object_info()
+                        .has_property_by_value(&mut self.factory.memory_allocator, lhs);
+                    self.current_context
+                        .stack
+                        .push(Value::bool(has_property).into());
+                }
+                VMInst::DELETE => {
+                    self.current_context.pc += 1;
+                    let key: Value = self.current_context.stack.pop().unwrap().into();
+                    let parent: Value = self.current_context.stack.pop().unwrap().into();
+                    let deleted = match parent {
+                        Value::Object(_) => parent.get_object_info().delete_property_by_value(key),
+                        Value::String(_) => {
+                            // Indices and length of a string are not configurable.
+                            let len = parent.into_utf16().len();
+                            match key.to_string().as_str() {
+                                "length" => false,
+                                idx => idx.parse::<usize>().map_or(true, |idx| {
+                                    idx.to_string() != key.to_string() || idx >= len
+                                }),
+                            }
+                        }
+                        _ if parent.is_undefined() || parent.is_null() => type_error!(format!(
+                            "Cannot convert {} to object",
+                            parent.debug_string(false)
+                        )),
+                        _ => true,
+                    };
+                    if !deleted && self.current_context.func_ref.is_strict {
+                        type_error!(format!(
+                            "Cannot delete property '{}' of {}",
+                            key.debug_string(false),
+                            parent.debug_string(true)
+                        ))
+                    }
+                    self.current_context.stack.push(Value::bool(deleted).into());
+                }
+                VMInst::INSTANCE_OF => {
+                    self.current_context.pc += 1;
+                    let rhs: Value = self.current_context.stack.pop().unwrap().into();
+                    let lhs: Value = self.current_context.stack.pop().unwrap().into();
+                    let result = etry!(self.instance_of(lhs, rhs));
+                    self.current_context.stack.push(Value::bool(result).into());
+                }
                 VMInst::AND => {
                     self.current_context.pc += 1;
                     let rhs: Value = self.current_context.stack.pop().unwrap().into();
                     let lhs: Value = self.current_context.stack.pop().unwrap().into();
+                    let lhs = etry!(self.to_primitive(lhs, PreferredType::Number));
+                    let rhs = etry!(self.to_primitive(rhs, PreferredType::Number));
                     self.current_context
                         .stack
                         .push(rhs.and(&mut self.factory.memory_allocator, lhs).into());
@@ -544,6 +894,8 @@ impl VM {
                     self.current_context.pc += 1;
                     let rhs: Value = self.current_context.stack.pop().unwrap().into();
                     let lhs: Value = self.current_context.stack.pop().unwrap().into();
+                    let lhs = etry!(self.to_primitive(lhs, PreferredType::Number));
+                    let rhs = etry!(self.to_primitive(rhs, PreferredType::Number));
                     self.current_context
                         .stack
                         .push(rhs.or(&mut self.factory.memory_allocator, lhs).into());
@@ -552,6 +904,8 @@ impl VM {
                     self.current_context.pc += 1;
                     let rhs: Value = self.current_context.stack.pop().unwrap().into();
                     let lhs: Value = self.current_context.stack.pop().unwrap().into();
+                    let lhs = etry!(self.to_primitive(lhs, PreferredType::Number));
+                    let rhs = etry!(self.to_primitive(rhs, PreferredType::Number));
                     self.current_context
                         .stack
                         .push(rhs.xor(&mut self.factory.memory_allocator, lhs).into());
@@ -559,6 +913,7 @@ impl VM {
                 VMInst::NOT => {
                     self.current_context.pc += 1;
                     let rhs: Value = self.current_context.stack.pop().unwrap().into();
+                    let rhs = etry!(self.to_primitive(rhs, PreferredType::Number));
                     self.current_context
                         .stack
                         .push(rhs.not(&mut self.factory.memory_allocator).into());
@@ -567,6 +922,8 @@ impl VM {
                     self.current_context.pc += 1;
                     let rhs: Value = self.current_context.stack.pop().unwrap().into();
                     let lhs: Value = self.current_context.stack.pop().unwrap().into();
+                    let lhs = etry!(self.to_primitive(lhs, PreferredType::Number));
+                    let rhs = etry!(self.to_primitive(rhs, PreferredType::Number));
                     self.current_context
                         .stack
                         .push(lhs.shift_l(&mut self.factory.memory_allocator, rhs).into());
@@ -575,6 +932,8 @@ impl VM {
                     self.current_context.pc += 1;
                     let rhs: Value = self.current_context.stack.pop().unwrap().into();
                     let lhs: Value = self.current_context.stack.pop().unwrap().into();
+                    let lhs = etry!(self.to_primitive(lhs, PreferredType::Number));
+                    let rhs = etry!(self.to_primitive(rhs, PreferredType::Number));
                     self.current_context
                         .stack
                         .push(lhs.shift_r(&mut self.factory.memory_allocator, rhs).into());
@@ -583,6 +942,8 @@ impl VM {
                     self.current_context.pc += 1;
                     let rhs: Value = self.current_context.stack.pop().unwrap().into();
                     let lhs: Value = self.current_context.stack.pop().unwrap().into();
+                    let lhs = etry!(self.to_primitive(lhs, PreferredType::Number));
+                    let rhs = etry!(self.to_primitive(rhs, PreferredType::Number));
                     self.current_context.stack.push(
                         lhs.z_shift_r(&mut self.factory.memory_allocator, rhs)
                             .into(),
@@ -591,11 +952,13 @@ impl VM {
                 VMInst::NEG => {
                     self.current_context.pc += 1;
                     let val: Value = self.current_context.stack.pop().unwrap().into();
+                    let val = etry!(self.to_primitive(val, PreferredType::Number));
                     self.current_context.stack.push(val.minus().into());
                 }
                 VMInst::POSI => {
                     self.current_context.pc += 1;
                     let val: Value = self.current_context.stack.pop().unwrap().into();
+                    let val = etry!(self.to_primitive(val, PreferredType::Number));
                     self.current_context
                         .stack
                         .push(val.positive(&mut self.factory.memory_allocator).into());
@@ -638,9 +1001,13 @@ impl VM {
                 }
                 VMInst::PUSH_THIS => {
                     self.current_context.pc += 1;
-                    self.current_context
-                        .stack
-                        .push(self.current_context.this.into());
+                    let this = self.this_value();
+                    if this == Value::uninitialized() {
+                        reference_error!(
+                            "Must call super constructor in derived class before accessing 'this'"
+                        )
+                    }
+                    self.current_context.stack.push(this.into());
                 }
                 VMInst::PUSH_FALSE => {
                     self.current_context.pc += 1;
@@ -652,9 +1019,11 @@ impl VM {
                 }
                 VMInst::SPREAD_ARRAY => {
                     self.current_context.pc += 1;
-                    let val: Value = self.current_context.stack.pop().unwrap().into();
+                    let mut val: Value = self.current_context.stack.pop().unwrap().into();
+                    // Other iterables are collected into an array by the iterator protocol.
                     if !val.is_array_object() {
-                        type_error!("Not an array.")
+                        let iter = etry!(self.get_iterator(val));
+                        val = etry!(self.iterator_rest(iter));
                     }
                     let ary = val.as_array_mut();
                     let len = ary.get_length();
@@ -669,11 +1038,38 @@ impl VM {
                     let parent: Value = self.current_context.stack.pop().unwrap().into();
                     etry!(self.get_property_to_stack_top(parent, property))
                 }
+                VMInst::GET_SUPER_MEMBER => {
+                    self.current_context.pc += 1;
+                    let property: Value = self.current_context.stack.pop().unwrap().into();
+                    let home_proto: Value = self.current_context.stack.pop().unwrap().into();
+                    let this = self.this_value();
+                    if this == Value::uninitialized() {
+                        reference_error!(
+                            "Must call super constructor in derived class before accessing 'this'"
+                        )
+                    }
+                    let val = etry!(home_proto.get_property_by_value(&mut self.factory, property));
+                    match val {
+                        Property::Data(DataProperty { val, .. }) => {
+                            self.current_context.stack.push(val.into())
+                        }
+                        Property::Accessor(AccessorProperty { get, .. }) => {
+                            if get.is_undefined() {
+                                self.current_context.stack.push(Value::undefined().into());
+                            } else {
+                                etry!(self.enter_function(get, &[], this, false))
+                            }
+                        }
+                    }
+                }
                 VMInst::SET_MEMBER => {
                     self.current_context.pc += 1;
                     let property: Value = self.current_context.stack.pop().unwrap().into();
                     let parent: Value = self.current_context.stack.pop().unwrap().into();
                     let val: Value = self.current_context.stack.pop().unwrap().into();
+                    if self.current_context.func_ref.is_strict {
+                        etry!(self.check_strict_assignment(parent, property))
+                    }
                     etry!(self.set_property_by_value(parent, property, val))
                 }
                 VMInst::SET_VALUE => {
@@ -681,10 +1077,19 @@ impl VM {
                     read_int32!(self, name_id, usize);
                     let val = self.current_context.stack.pop().unwrap();
                     let name = self.constant_table.get(name_id).as_string().clone();
-                    etry!(self
-                        .current_context
-                        .lex_env_mut()
-                        .set_value(name, val.into()));
+                    let is_strict = self.current_context.func_ref.is_strict;
+                    etry!(self.current_context.lex_env_mut().set_mutable_binding(
+                        name,
+                        val.into(),
+                        is_strict
+                    ));
+                }
+                VMInst::DELETE_VALUE => {
+                    self.current_context.pc += 1;
+                    read_int32!(self, name_id, usize);
+                    let name = self.constant_table.get(name_id).as_string();
+                    let deleted = self.current_context.lex_env_mut().delete_binding(name);
+                    self.current_context.stack.push(Value::bool(deleted).into());
                 }
                 VMInst::GET_VALUE => {
                     self.current_context.pc += 1;
@@ -701,7 +1106,30 @@ impl VM {
                     for _ in 0..argc {
                         args.push(self.current_context.stack.pop().unwrap().into());
                     }
-                    self.enter_constructor(callee, &args)?;
+                    etry!(self.enter_constructor(callee, &args, callee))
+                }
+                VMInst::SUPER_CALL => {
+                    self.current_context.pc += 1;
+                    let callee: Value = self.current_context.stack.pop().unwrap().into();
+                    let args: Value = self.current_context.stack.pop().unwrap().into();
+                    if !callee.is_function_object() {
+                        type_error!("Super constructor is not a constructor")
+                    }
+                    let ary = args.as_array_mut();
+                    let args: Vec<Value> = (0..ary.get_length())
+                        .map(|i| ary.get_element(i).as_data().val)
+                        .collect();
+                    let new_target = self.current_context.variable_environment.get_new_target();
+                    etry!(self.enter_constructor(callee, &args, new_target))
+                }
+                VMInst::BIND_THIS => {
+                    self.current_context.pc += 1;
+                    let this: Value = (*self.current_context.stack.last().unwrap()).into();
+                    etry!(self
+                        .current_context
+                        .variable_environment
+                        .bind_this_value(this));
+                    self.current_context.this = this;
                 }
                 VMInst::CALL => {
                     self.current_context.pc += 1;
@@ -711,7 +1139,18 @@ impl VM {
                     for _ in 0..argc {
                         args.push(self.current_context.stack.pop().unwrap().into());
                     }
-                    etry!(self.enter_function(callee, &args, self.current_context.this, false))
+                    etry!(self.enter_function(callee, &args, Value::undefined(), false))
+                }
+                VMInst::CALL_WITH_THIS => {
+                    self.current_context.pc += 1;
+                    read_int32!(self, argc, usize);
+                    let callee: Value = self.current_context.stack.pop().unwrap().into();
+                    let this: Value = self.current_context.stack.pop().unwrap().into();
+                    let mut args: Vec<Value> = vec![];
+                    for _ in 0..argc {
+                        args.push(self.current_context.stack.pop().unwrap().into());
+                    }
+                    etry!(self.enter_function(callee, &args, this, false))
                 }
                 VMInst::CALL_METHOD => {
                     self.current_context.pc += 1;
@@ -747,6 +1186,11 @@ impl VM {
                     self.create_array()?;
                     //self.gc_mark();
                 }
+                VMInst::CREATE_CLASS => {
+                    self.current_context.pc += 1;
+                    read_int32!(self, id, usize);
+                    etry!(self.create_class(id));
+                }
                 VMInst::DOUBLE => {
                     self.current_context.pc += 1;
                     let val = *self.current_context.stack.last().unwrap();
@@ -770,6 +1214,84 @@ impl VM {
                     self.current_context.pc += 1;
                     self.current_context.stack.pop();
                 }
+                VMInst::GET_ITERATOR => {
+                    self.current_context.pc += 1;
+                    let iterable: Value = self.current_context.stack.pop().unwrap().into();
+                    let iter = etry!(self.get_iterator(iterable));
+                    self.current_context.stack.push(iter.into());
+                }
+                VMInst::ENUMERATE => {
+                    self.current_context.pc += 1;
+                    let obj: Value = self.current_context.stack.pop().unwrap().into();
+                    let iter = self.enumerate_object_properties(obj);
+                    self.current_context.stack.push(iter.into());
+                }
+                VMInst::ITER_NEXT => {
+                    self.current_context.pc += 1;
+                    read_int32!(self, dst, i32);
+                    let iter: Value = (*self.current_context.stack.last().unwrap()).into();
+                    match etry!(self.open_iterator_step(iter)) {
+                        Some(val) => self.current_context.stack.push(val.into()),
+                        None => {
+                            self.current_context.pc =
+                                (self.current_context.pc as isize + dst as isize) as usize
+                        }
+                    }
+                }
+                VMInst::ITER_VALUE => {
+                    self.current_context.pc += 1;
+                    let iter: Value = (*self.current_context.stack.last().unwrap()).into();
+                    let val = etry!(self.open_iterator_step(iter)).unwrap_or(Value::undefined());
+                    self.current_context.stack.push(val.into());
+                }
+                VMInst::ITER_REST => {
+                    self.current_context.pc += 1;
+                    let iter: Value = (*self.current_context.stack.last().unwrap()).into();
+                    let rest = etry!(self.iterator_rest(iter));
+                    self.current_context.stack.push(rest.into());
+                }
+                VMInst::ENTER_ITER => {
+                    let enter_iter_pos = self.current_context.pc;
+                    self.current_context.pc += 1;
+                    let stack_pos = self.current_context.stack.len() - 1;
+                    self.current_context.open_iterators.push(OpenIterator {
+                        enter_iter_pos,
+                        stack_pos,
+                        done: false,
+                    });
+                }
+                VMInst::ITER_CLOSE => {
+                    let pos = self.current_context.pc;
+                    self.current_context.pc += 1;
+                    read_int32!(self, offset, usize);
+                    etry!(self.close_iterators(Some(pos - offset)));
+                }
+                VMInst::ITER_CLOSE_THROW => {
+                    let pos = self.current_context.pc;
+                    self.current_context.pc += 1;
+                    read_int32!(self, offset, usize);
+                    let exception = self.current_context.stack.pop().unwrap();
+                    self.close_iterators_on_throw(pos - offset);
+                    self.current_context.stack.push(exception);
+                    handle_exception(self)?;
+                }
+                VMInst::COPY_DATA_PROPERTIES => {
+                    self.current_context.pc += 1;
+                    read_int32!(self, len, usize);
+                    let rest = etry!(self.copy_data_properties(len));
+                    self.current_context.stack.push(rest.into());
+                }
+                VMInst::CREATE_REGEXP => {
+                    self.current_context.pc += 1;
+                    let flags: Value = self.current_context.stack.pop().unwrap().into();
+                    let pattern: Value = self.current_context.stack.pop().unwrap().into();
+                    let regexp = etry!(builtins::regexp::regexp_create(
+                        self,
+                        pattern.to_string(),
+                        flags.to_string()
+                    ));
+                    self.current_context.stack.push(regexp.into());
+                }
                 VMInst::JMP_IF_FALSE => {
                     self.current_context.pc += 1;
                     read_int32!(self, dst, i32);
@@ -789,7 +1311,10 @@ impl VM {
                 VMInst::JMP_SUB => {
                     self.current_context.pc += 1;
                     read_int32!(self, dst, i32);
-                    subroutine_stack.push(SubroutineKind::Ordinary(self.current_context.pc));
+                    let pos = self.current_context.pc;
+                    self.current_context
+                        .subroutine_stack
+                        .push(SubroutineKind::Ordinary(pos));
                     self.current_context.pc =
                         (self.current_context.pc as isize + dst as isize) as usize;
                 }
@@ -798,24 +1323,50 @@ impl VM {
                     read_int32!(self, dst, i32);
                     self.current_context.pc =
                         (self.current_context.pc as isize + dst as isize) as usize;
-                    subroutine_stack.push(SubroutineKind::Return);
+                    self.current_context
+                        .subroutine_stack
+                        .push(SubroutineKind::Return);
                 }
                 VMInst::RETURN_SUB => {
                     self.current_context.pc += 1;
-                    match subroutine_stack.pop().unwrap() {
+                    match self.current_context.subroutine_stack.pop().unwrap() {
                         SubroutineKind::Ordinary(pos) => self.current_context.pc = pos,
-                        SubroutineKind::Throw => handle_exception(self, &mut subroutine_stack)?,
+                        SubroutineKind::Throw => handle_exception(self)?,
                         SubroutineKind::Return => {
+                            etry!(self.close_iterators(None));
+                            let call_mode = self.current_context.call_mode;
                             self.unwind_context();
+                            if call_mode == CallMode::FromNative {
+                                break;
+                            }
+                        }
+                    }
+                }
+                VMInst::DROP_SUB => {
+                    self.current_context.pc += 1;
+                    match self.current_context.subroutine_stack.pop().unwrap() {
+                        SubroutineKind::Ordinary(_) => {}
+                        // The exception or the return value on the stack.
+                        SubroutineKind::Throw | SubroutineKind::Return => {
+                            self.current_context.stack.pop().unwrap();
                         }
                     }
                 }
                 VMInst::THROW => {
                     self.current_context.pc += 1;
-                    handle_exception(self, &mut subroutine_stack)?;
+                    handle_exception(self)?;
                 }
                 VMInst::RETURN => {
                     self.current_context.pc += 1;
+                    etry!(self.close_iterators(None));
+                    if self.current_context.constructor_call
+                        && self.this_value() == Value::uninitialized()
+                    {
+                        let ret_val: Value = (*self.current_context.stack.last().unwrap()).into();
+                        if !ret_val.is_object() {
+                            reference_error!("Must call super constructor in derived class before returning from derived constructor")
+                        }
+                    }
                     let call_mode = self.current_context.call_mode;
                     if self.saved_context.len() == 0 {
                         break;
@@ -846,6 +1397,71 @@ impl VM {
                     let type_str_val = self.factory.string(type_str.to_string());
                     self.current_context.stack.push(type_str_val.into());
                 }
+                VMInst::TO_STRING => {
+                    self.current_context.pc += 1;
+                    let val: Value = self.current_context.stack.pop().unwrap().into();
+                    let val = etry!(self.to_primitive(val, PreferredType::String));
+                    if val.is_symbol() {
+                        type_error!("Cannot convert a Symbol value to a string")
+                    }
+                    let string = if val.is_string() {
+                        val
+                    } else {
+                        self.factory.utf16_string(val.to_utf16())
+                    };
+                    self.current_context.stack.push(string.into());
+                }
+                VMInst::AWAIT => {
+                    self.current_context.pc += 1;
+                    let val: Value = self.current_context.stack.pop().unwrap().into();
+                    // An async function always runs from native code. Return to it with the
+                    // awaited value, and resume with the result as [value, is_rejected].
+                    self.suspend_current_context(val);
+                    break;
+                }
+                VMInst::YIELD => {
+                    self.current_context.pc += 1;
+                    let val: Value = self.current_context.stack.pop().unwrap().into();
+                    // A generator always runs from native code. Return to it with an iterator
+                    // result, and resume with [value, is_throw, is_return].
+                    let result = create_iter_result_object(&mut self.factory, val, false);
+                    self.suspend_current_context(result);
+                    break;
+                }
+                VMInst::YIELD_DELEGATE => {
+                    self.current_context.pc += 1;
+                    let is_return: Value = self.current_context.stack.pop().unwrap().into();
+                    let is_throw: Value = self.current_context.stack.pop().unwrap().into();
+                    let received: Value = self.current_context.stack.pop().unwrap().into();
+                    let iter: Value = (*self.current_context.stack.last().unwrap()).into();
+                    let step = etry!(self.yield_delegate(
+                        iter,
+                        received,
+                        is_throw.to_boolean(),
+                        is_return.to_boolean()
+                    ));
+                    let (val, is_return) = match step {
+                        YieldDelegateStep::Yield(result) => {
+                            // Resumed at this instruction again with the received value.
+                            self.current_context.pc -= 1;
+                            self.suspend_current_context(result);
+                            break;
+                        }
+                        YieldDelegateStep::Complete(val) => (val, false),
+                        YieldDelegateStep::Return(val) => (val, true),
+                    };
+                    self.current_context.stack.pop(); // iterator
+                    self.current_context.stack.push(val.into());
+                    self.current_context
+                        .stack
+                        .push(Value::bool(is_return).into());
+                }
+                VMInst::IMPORT_CALL => {
+                    self.current_context.pc += 1;
+                    let specifier: Value = self.current_context.stack.pop().unwrap().into();
+                    let promise = self.import_dynamically(specifier);
+                    self.current_context.stack.push(promise.into());
+                }
                 VMInst::END => break,
                 _ => {
                     print!("Not yet implemented VMInst: ");
@@ -908,7 +1524,7 @@ impl VM {
                 ),
                 match self.current_context.stack.last() {
                     None => format!("<empty>"),
-                    Some(val) => format!("{:10}", (*val).into(): Value),
+                    Some(val) => format!("{:10}", Value::from(*val)),
                 }
             );
         }
@@ -980,6 +1596,16 @@ impl VM {
         );
     }
 
+    /// This value of the current context. A derived constructor may have bound it by calling
+    /// super() in an arrow function.
+    fn this_value(&mut self) -> Value {
+        if self.current_context.this == Value::uninitialized() {
+            self.current_context.this =
+                self.current_context.variable_environment.get_this_binding();
+        }
+        self.current_context.this
+    }
+
     /// Return from JS function.
     /// 1. Pop a Value from the stack of the current execution context.
     /// 2. Pop an ExecContext from the context stack.
@@ -988,16 +1614,13 @@ impl VM {
     pub fn unwind_context(&mut self) {
         let prev_context = self.saved_context.pop().unwrap();
         let return_value = if self.current_context.call_mode == CallMode::ModuleCall {
-            self.current_context
-                .lex_env()
-                .get_value("module")
-                .unwrap()
-                .get_object_info()
-                .get_property("exports")
+            let module = self.current_context.lex_env().get_value("module").unwrap();
+            module.set_property("loaded", Value::bool(true));
+            module.get_property("exports")
         } else {
             let ret_val: Value = self.current_context.stack.pop().unwrap().into();
             if self.current_context.constructor_call && !ret_val.is_object() {
-                self.current_context.this
+                self.this_value()
             } else {
                 ret_val
             }
@@ -1020,72 +1643,659 @@ impl VM {
         Ok(())
     }
 
+    /// Throws if an assignment to ``parent[key]`` in strict mode code would be ignored.
+    /// https://tc39.github.io/ecma262/#sec-putvalue
+    fn check_strict_assignment(&mut self, parent: Value, key: Value) -> VMResult {
+        let assignable = match parent {
+            Value::Object(_) => parent
+                .get_object_info()
+                .is_assignable(&mut self.factory.memory_allocator, key),
+            Value::String(_) | Value::Number(_) | Value::Bool(_) => false,
+            _ => true,
+        };
+        if assignable {
+            return Ok(());
+        }
+        Err(self.current_context.error_type(if parent.is_object() {
+            format!(
+                "Cannot assign to read only property '{}' of {}",
+                key.debug_string(false),
+                parent.debug_string(true)
+            )
+        } else {
+            format!(
+                "Cannot create property '{}' on {}",
+                key.debug_string(false),
+                parent.debug_string(true)
+            )
+        }))
+    }
+
+    /// https://tc39.github.io/ecma262/#sec-toprimitive
+    pub fn to_primitive(&mut self, val: Value, hint: PreferredType) -> VMValueResult {
+        if !val.is_object() {
+            return Ok(val);
+        }
+
+        let exotic_to_prim = self.get_property_by_value(val, self.factory.symbols.to_primitive)?;
+        if !exotic_to_prim.is_undefined() && !exotic_to_prim.is_null() {
+            let hint = self.factory.string(match hint {
+                PreferredType::Default => "default",
+                PreferredType::Number => "number",
+                PreferredType::String => "string",
+            });
+            let result = self.call_function(exotic_to_prim, &[hint], val)?;
+            if result.is_object() {
+                return Err(self
+                    .current_context
+                    .error_type("Cannot convert object to primitive value"));
+            }
+            return Ok(result);
+        }
+
+        let hint = match hint {
+            PreferredType::Default => PreferredType::Number,
+            hint => hint,
+        };
+        self.ordinary_to_primitive(val, hint)
+    }
+
+    /// https://tc39.github.io/ecma262/#sec-ordinarytoprimitive
+    pub fn ordinary_to_primitive(&mut self, val: Value, hint: PreferredType) -> VMValueResult {
+        let method_names = match hint {
+            PreferredType::String => ["toString", "valueOf"],
+            _ => ["valueOf", "toString"],
+        };
+
+        for name in &method_names {
+            let name = self.factory.string(*name);
+            let method = self.get_property_by_value(val, name)?;
+            if method.is_function_object() {
+                let result = self.call_function(method, &[], val)?;
+                if !result.is_object() {
+                    return Ok(result);
+                }
+            }
+        }
+
+        Err(self
+            .current_context
+            .error_type("Cannot convert object to primitive value"))
+    }
+
+    /// The operands of ``==`` and ``!=``, where an object compared with a primitive other than
+    /// null and undefined is converted with ToPrimitive.
+    /// https://tc39.github.io/ecma262/#sec-abstract-equality-comparison
+    fn equality_operands(
+        &mut self,
+        lhs: Value,
+        rhs: Value,
+    ) -> Result<(Value, Value), RuntimeError> {
+        let is_nullish = |val: Value| val.is_undefined() || val.is_null();
+        match (lhs.is_object(), rhs.is_object()) {
+            (true, false) if !is_nullish(rhs) => {
+                Ok((self.to_primitive(lhs, PreferredType::Default)?, rhs))
+            }
+            (false, true) if !is_nullish(lhs) => {
+                Ok((lhs, self.to_primitive(rhs, PreferredType::Default)?))
+            }
+            _ => Ok((lhs, rhs)),
+        }
+    }
+
+    /// https://tc39.github.io/ecma262/#sec-instanceofoperator
+    pub fn instance_of(&mut self, val: Value, target: Value) -> Result<bool, RuntimeError> {
+        if !target.is_object() {
+            return Err(self.current_context.error_type(format!(
+                "Right-hand side of 'instanceof' is not an object: {}",
+                target.debug_string(true)
+            )));
+        }
+
+        let inst_of_handler =
+            self.get_property_by_value(target, self.factory.symbols.has_instance)?;
+        if !inst_of_handler.is_undefined() && !inst_of_handler.is_null() {
+            let result = self.call_function(inst_of_handler, &[val], target)?;
+            return Ok(result.to_boolean());
+        }
+
+        if !target.is_function_object() {
+            return Err(self
+                .current_context
+                .error_type("Right-hand side of 'instanceof' is not callable"));
+        }
+
+        self.ordinary_has_instance(target, val)
+    }
+
+    /// https://tc39.github.io/ecma262/#sec-ordinaryhasinstance
+    pub fn ordinary_has_instance(&mut self, c: Value, o: Value) -> Result<bool, RuntimeError> {
+        if !c.is_function_object() {
+            return Ok(false);
+        }
+
+        if let FunctionObjectKind::Bound { target, .. } = c.as_function().kind {
+            return self.instance_of(o, target);
+        }
+
+        if !o.is_object() {
+            return Ok(false);
+        }
+
+        let prototype = self.factory.string("prototype");
+        let prototype = self.get_property_by_value(c, prototype)?;
+        if !prototype.is_object() {
+            return Err(self.current_context.error_type(format!(
+                "Function has non-object prototype '{}' in instanceof check",
+                prototype.debug_string(true)
+            )));
+        }
+
+        let mut proto = o.get_prototype();
+        while proto.is_object() {
+            if proto == prototype {
+                return Ok(true);
+            }
+            proto = proto.get_prototype();
+        }
+
+        Ok(false)
+    }
+
+    /// https://tc39.github.io/ecma262/#sec-getiterator
+    pub fn get_iterator(&mut self, iterable: Value) -> VMValueResult {
+        let method = self.get_property_by_value(iterable, self.factory.symbols.iterator)?;
+        if !method.is_function_object() {
+            return Err(self
+                .current_context
+                .error_type(format!("{} is not iterable", iterable.debug_string(true))));
+        }
+
+        let iter = self.call_function(method, &[], iterable)?;
+        if !iter.is_object() {
+            return Err(self
+                .current_context
+                .error_type("Result of the Symbol.iterator method is not an object"));
+        }
+
+        Ok(iter)
+    }
+
+    /// Calls ``iter.next()`` and returns the value of the result, or None when it is done.
+    /// https://tc39.github.io/ecma262/#sec-iteratorstep
+    pub fn iterator_step(&mut self, iter: Value) -> Result<Option<Value>, RuntimeError> {
+        if let ObjectKind::ForInIterator(ref mut info) = iter.get_object_info().kind {
+            while info.next_index < info.keys.len() {
+                let key = self.factory.string(info.keys[info.next_index].clone());
+                info.next_index += 1;
+                // Keys deleted before being visited are skipped.
+                if !info.object.is_object()
+                    || info
+                        .object
+                        .get_object_info()
+                        .has_property_by_value(&mut self.factory.memory_allocator, key)
+                {
+                    return Ok(Some(key));
+                }
+            }
+            return Ok(None);
+        }
+
+        let next = self.factory.string("next");
+        let next = self.get_property_by_value(iter, next)?;
+        let result = self.call_function(next, &[], iter)?;
+        if !result.is_object() {
+            return Err(self.current_context.error_type(format!(
+                "Iterator result {} is not an object",
+                result.debug_string(true)
+            )));
+        }
+
+        let done = self.factory.string("done");
+        if self.get_property_by_value(result, done)?.to_boolean() {
+            return Ok(None);
+        }
+
+        let value = self.factory.string("value");
+        Ok(Some(self.get_property_by_value(result, value)?))
+    }
+
+    /// Passes the value the generator is resumed with to the inner iterator of ``yield*``.
+    /// https://tc39.github.io/ecma262/#sec-generator-function-definitions-runtime-semantics-evaluation
+    fn yield_delegate(
+        &mut self,
+        iter: Value,
+        received: Value,
+        is_throw: bool,
+        is_return: bool,
+    ) -> Result<YieldDelegateStep, RuntimeError> {
+        let method_name = if is_throw {
+            "throw"
+        } else if is_return {
+            "return"
+        } else {
+            "next"
+        };
+        let key = self.factory.string(method_name);
+        let method = self.get_property_by_value(iter, key)?;
+
+        if is_return && (method.is_undefined() || method.is_null()) {
+            return Ok(YieldDelegateStep::Return(received));
+        }
+        if is_throw && (method.is_undefined() || method.is_null()) {
+            let key = self.factory.string("return");
+            let return_ = self.get_property_by_value(iter, key)?;
+            if !return_.is_undefined() && !return_.is_null() {
+                self.call_function(return_, &[], iter)?;
+            }
+            return Err(self
+                .current_context
+                .error_type("The iterator does not provide a 'throw' method"));
+        }
+
+        let result = self.call_function(method, &[received], iter)?;
+        if !result.is_object() {
+            return Err(self.current_context.error_type(format!(
+                "Iterator result {} is not an object",
+                result.debug_string(true)
+            )));
+        }
+
+        let done = self.factory.string("done");
+        if !self.get_property_by_value(result, done)?.to_boolean() {
+            return Ok(YieldDelegateStep::Yield(result));
+        }
+        let value = self.factory.string("value");
+        let value = self.get_property_by_value(result, value)?;
+        if is_return {
+            Ok(YieldDelegateStep::Return(value))
+        } else {
+            Ok(YieldDelegateStep::Complete(value))
+        }
+    }
+
+    /// Collects the remaining values of ``iter`` into a new array.
+    fn iterator_rest(&mut self, iter: Value) -> VMValueResult {
+        let mut elems = vec![];
+        while let Some(val) = self.open_iterator_step(iter)? {
+            elems.push(Property::new_data_simple(val));
+        }
+        Ok(self.factory.array(elems))
+    }
+
+    /// Steps ``iter`` on the stack top. If it is an open iterator, records that it is done
+    /// once it is exhausted or throws, and does not step it any more.
+    fn open_iterator_step(&mut self, iter: Value) -> Result<Option<Value>, RuntimeError> {
+        let top = self.current_context.stack.len() - 1;
+        let is_open = match self.current_context.open_iterators.last() {
+            Some(open) if open.stack_pos == top => {
+                if open.done {
+                    return Ok(None);
+                }
+                true
+            }
+            _ => false,
+        };
+
+        let result = self.iterator_step(iter);
+        if is_open && !matches!(result, Ok(Some(_))) {
+            self.current_context.open_iterators.last_mut().unwrap().done = true;
+        }
+        result
+    }
+
+    /// Closes the open iterators down to the one entered at ``enter_iter_pos``, or all of them
+    /// if None, innermost first. Each iterator is removed from the stack.
+    /// Each record is removed before its iterator is closed, so that an error thrown by
+    /// ``return()`` does not close it again.
+    fn close_iterators(&mut self, enter_iter_pos: Option<usize>) -> VMResult {
+        let len = match enter_iter_pos {
+            Some(enter_iter_pos) => match self
+                .current_context
+                .open_iterators
+                .iter()
+                .rposition(|open| open.enter_iter_pos == enter_iter_pos)
+            {
+                Some(len) => len,
+                // Already closed.
+                None => return Ok(()),
+            },
+            None => 0,
+        };
+
+        while self.current_context.open_iterators.len() > len {
+            let open = self.current_context.open_iterators.pop().unwrap();
+            let iter: Value = self.current_context.stack.remove(open.stack_pos).into();
+            if !open.done {
+                self.iterator_close(iter)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Closes the open iterators down to the one entered at ``enter_iter_pos`` because of an
+    /// exception, ignoring the errors thrown by ``return()``. The stack is truncated to the
+    /// iterator.
+    fn close_iterators_on_throw(&mut self, enter_iter_pos: usize) {
+        let len = match self
+            .current_context
+            .open_iterators
+            .iter()
+            .rposition(|open| open.enter_iter_pos == enter_iter_pos)
+        {
+            Some(len) => len,
+            None => return,
+        };
+
+        while self.current_context.open_iterators.len() > len {
+            let open = self.current_context.open_iterators.pop().unwrap();
+            let iter: Value = self.current_context.stack[open.stack_pos].into();
+            self.current_context.stack.truncate(open.stack_pos);
+            if !open.done {
+                let _ = self.iterator_close(iter);
+            }
+        }
+    }
+
+    /// https://tc39.github.io/ecma262/#sec-iteratorclose
+    fn iterator_close(&mut self, iter: Value) -> VMResult {
+        let key = self.factory.string("return");
+        let return_ = self.get_property_by_value(iter, key)?;
+        if return_.is_undefined() || return_.is_null() {
+            return Ok(());
+        }
+
+        let result = self.call_function(return_, &[], iter)?;
+        if !result.is_object() {
+            return Err(self.current_context.error_type(format!(
+                "Iterator result {} is not an object",
+                result.debug_string(true)
+            )));
+        }
+        Ok(())
+    }
+
+    /// Pops ``excluded_len`` keys and the source, and returns a new object with the own
+    /// enumerable properties of the source except the keys.
+    /// https://tc39.github.io/ecma262/#sec-copydataproperties
+    fn copy_data_properties(&mut self, excluded_len: usize) -> VMValueResult {
+        let mut excluded_keys = vec![];
+        let mut excluded_symbols = vec![];
+        for _ in 0..excluded_len {
+            let key: Value = self.current_context.stack.pop().unwrap().into();
+            if key.is_symbol() {
+                excluded_symbols.push(key.get_symbol_info().id);
+            } else {
+                excluded_keys.push(key.to_string());
+            }
+        }
+        let source: Value = self.current_context.stack.pop().unwrap().into();
+        if source.is_undefined() || source.is_null() {
+            return Err(self.current_context.error_type(format!(
+                "Cannot destructure '{}' as it is {}.",
+                source.to_string(),
+                source.to_string()
+            )));
+        }
+
+        let target = self.factory.object(PropertyMap::default());
+        if !source.is_object() {
+            return Ok(target);
+        }
+
+        let source_info = source.get_object_info();
+        for (key, enumerable) in source_info.own_string_keys() {
+            if !enumerable || excluded_keys.contains(&key) {
+                continue;
+            }
+            let key_val = self.factory.string(key.clone());
+            let val = self.get_property_by_value(source, key_val)?;
+            target.get_object_info().set_property(key, val);
+        }
+
+        let symbol_properties = source_info
+            .sym_property
+            .iter()
+            .filter(|(id, prop)| prop.is_enumerable() && !excluded_symbols.contains(id))
+            .map(|(id, prop)| (*id, *prop))
+            .collect::<Vec<(usize, Property)>>();
+        for (id, prop) in symbol_properties {
+            let val = match prop {
+                Property::Data(DataProperty { val, .. }) => val,
+                Property::Accessor(AccessorProperty { get, .. }) => {
+                    if get.is_undefined() {
+                        Value::undefined()
+                    } else {
+                        self.call_function(get, &[], source)?
+                    }
+                }
+            };
+            target
+                .get_object_info()
+                .sym_property
+                .insert(id, Property::new_data_simple(val));
+        }
+
+        Ok(target)
+    }
+
+    /// Returns an iterator over the enumerable string keys of ``obj`` and its prototype chain.
+    /// The keys are collected here, and those deleted before being visited are skipped.
+    /// https://tc39.github.io/ecma262/#sec-enumerate-object-properties
+    fn enumerate_object_properties(&mut self, obj: Value) -> Value {
+        let mut keys = vec![];
+
+        match obj {
+            Value::String(_) => {
+                let len = obj.into_utf16().len();
+                keys.extend((0..len).map(|i| i.to_string()));
+            }
+            Value::Object(_) => {
+                let mut visited = FxHashSet::default();
+                let mut obj = obj;
+                while obj.is_object() {
+                    let obj_info = obj.get_object_info();
+                    for (key, enumerable) in obj_info.own_string_keys() {
+                        // Non-enumerable properties still shadow those of the prototypes.
+                        if visited.insert(key.clone()) && enumerable {
+                            keys.push(key);
+                        }
+                    }
+                    obj = obj_info.get_prototype();
+                }
+            }
+            _ => {}
+        }
+
+        self.factory.for_in_iterator(obj, keys)
+    }
+
     fn create_object(&mut self, id: usize) -> VMResult {
         let special_properties = self.constant_table.get(id).as_object_literal_info();
-        let mut properties = FxHashMap::default();
+        let mut properties = PropertyMap::default();
+        let mut sym_properties = FxHashMap::default();
 
         let mut i = 0;
         loop {
-            let prop: Value = self.current_context.stack.pop().unwrap().into();
-            if prop.is_seperator() {
+            let key: Value = self.current_context.stack.pop().unwrap().into();
+            if key.is_seperator() {
                 break;
             }
-            let name = prop.to_string();
             let val: Value = self.current_context.stack.pop().unwrap().into();
             use constant::SpecialPropertyKind::*;
-            if let Some(kind) = special_properties.get(&i) {
-                if *kind == Spread {
-                    if val.is_object() {
-                        let map = val.get_object_properties().unwrap();
-                        for (name, prop) in map {
-                            properties.insert(name.clone(), prop.clone());
-                        }
-                        if val.is_array_object() {
-                            let ary = val.as_array_mut();
-                            let len = ary.get_length();
-                            for i in 0..len {
-                                properties.insert(i.to_string(), ary.get_element(i));
-                            }
+            let kind = special_properties.get(&i).copied();
+            i += 1;
+
+            if kind == Some(Spread) {
+                if val.is_object() {
+                    let map = val.get_object_properties().unwrap();
+                    for (name, prop) in map {
+                        properties.insert(name.clone(), *prop);
+                    }
+                    if val.is_array_object() {
+                        let ary = val.as_array_mut();
+                        let len = ary.get_length();
+                        for i in 0..len {
+                            properties.insert(i.to_string(), ary.get_element(i));
                         }
                     }
-                } else {
-                    let AccessorProperty { get, set, .. } = properties
-                        .entry(name)
-                        .or_insert(Property::Accessor(AccessorProperty {
+                }
+                continue;
+            }
+
+            let old = if key.is_symbol() {
+                sym_properties.get(&key.get_symbol_info().id).copied()
+            } else {
+                properties.get(key.to_string().as_str()).copied()
+            };
+            let prop = match kind {
+                Some(kind) => {
+                    let mut accessor = match old {
+                        Some(Property::Accessor(accessor)) => accessor,
+                        _ => AccessorProperty {
                             get: Value::undefined(),
                             set: Value::undefined(),
                             // TODO
                             enumerable: true,
                             configurable: true,
-                        }))
-                        .as_accessor_mut();
-                    match kind {
-                        Getter => *get = val,
-                        Setter => *set = val,
-                        Spread => {}
+                        },
+                    };
+                    if kind == Getter {
+                        accessor.get = val;
+                    } else {
+                        accessor.set = val;
                     }
-                }
+                    Property::Accessor(accessor)
+                }
+                None => Property::Data(DataProperty {
+                    val,
+                    // TODO
+                    writable: true,
+                    enumerable: true,
+                    configurable: true,
+                }),
+            };
+            if key.is_symbol() {
+                sym_properties.insert(key.get_symbol_info().id, prop);
             } else {
-                properties.insert(
-                    name,
-                    Property::Data(DataProperty {
-                        val,
-                        // TODO
-                        writable: true,
-                        enumerable: true,
-                        configurable: true,
-                    }),
-                );
+                properties.insert(key.to_string(), prop);
             }
-            i += 1;
         }
 
         let obj = self.factory.object(properties);
+        obj.get_object_info().sym_property = sym_properties;
         self.current_context.stack.push(obj.into());
 
         Ok(())
     }
 
+    /// https://tc39.github.io/ecma262/#sec-runtime-semantics-classdefinitionevaluation
+    /// The stack holds the heritage (empty if none), a seperator, pairs of a method and its key,
+    /// and the class constructor.
+    fn create_class(&mut self, id: usize) -> VMResult {
+        let methods = self.constant_table.get(id).as_class_info().clone();
+        let constructor: Value = self.current_context.stack.pop().unwrap().into();
+        let mut keys_and_methods = vec![];
+        loop {
+            let key: Value = self.current_context.stack.pop().unwrap().into();
+            if key.is_seperator() {
+                break;
+            }
+            let method: Value = self.current_context.stack.pop().unwrap().into();
+            keys_and_methods.push((key, method));
+        }
+        let heritage: Value = self.current_context.stack.pop().unwrap().into();
+
+        let (proto_parent, constructor_parent) = if heritage.is_empty() {
+            (
+                self.factory.object_prototypes.object,
+                self.factory.object_prototypes.function,
+            )
+        } else if heritage.is_null() {
+            (Value::null(), self.factory.object_prototypes.function)
+        } else if heritage.is_function_object() {
+            let key = self.factory.string("prototype");
+            let proto_parent = self.get_property_by_value(heritage, key)?;
+            if !proto_parent.is_object() && !proto_parent.is_null() {
+                return Err(self
+                    .current_context
+                    .error_type("Class extends value does not have valid prototype property"));
+            }
+            (proto_parent, heritage)
+        } else {
+            return Err(self
+                .current_context
+                .error_type("Class extends value is not a constructor or null"));
+        };
+
+        let prototype = Value::Object(self.factory.alloc(ObjectInfo {
+            kind: ObjectKind::Ordinary,
+            prototype: proto_parent,
+            property: PropertyMap::default(),
+            sym_property: FxHashMap::default(),
+            extensible: true,
+        }));
+        prototype.set_constructor(constructor);
+
+        let mut constructor_info = constructor.get_object_info();
+        constructor_info.prototype = constructor_parent;
+        constructor_info.property.insert(
+            "prototype".to_string(),
+            Property::Data(DataProperty {
+                val: prototype,
+                writable: false,
+                enumerable: false,
+                configurable: false,
+            }),
+        );
+
+        // Class methods are not enumerable.
+        for ((key, method), constant::ClassMethodInfo { kind, is_static }) in
+            keys_and_methods.into_iter().zip(methods)
+        {
+            let mut target = if is_static { constructor } else { prototype }.get_object_info();
+            let old = if key.is_symbol() {
+                target.sym_property.get(&key.get_symbol_info().id).copied()
+            } else {
+                target.property.get(key.to_string().as_str()).copied()
+            };
+            let prop = match kind {
+                MethodDefinitionKind::Ordinary => Property::Data(DataProperty {
+                    val: method,
+                    writable: true,
+                    enumerable: false,
+                    configurable: true,
+                }),
+                MethodDefinitionKind::Get | MethodDefinitionKind::Set => {
+                    let mut accessor = match old {
+                        Some(Property::Accessor(accessor)) => accessor,
+                        _ => AccessorProperty {
+                            get: Value::undefined(),
+                            set: Value::undefined(),
+                            enumerable: false,
+                            configurable: true,
+                        },
+                    };
+                    if kind == MethodDefinitionKind::Get {
+                        accessor.get = method;
+                    } else {
+                        accessor.set = method;
+                    }
+                    Property::Accessor(accessor)
+                }
+            };
+            if key.is_symbol() {
+                target.sym_property.insert(key.get_symbol_info().id, prop);
+            } else {
+                target.property.insert(key.to_string(), prop);
+            }
+        }
+
+        self.current_context.stack.push(constructor.into());
+
+        Ok(())
+    }
+
     fn create_array(&mut self) -> VMResult {
         let mut elems = vec![];
         loop {
@@ -1107,15 +2317,63 @@ impl VM {
         Ok(())
     }
 
-    fn enter_constructor(&mut self, callee: Value, args: &[Value]) -> VMResult {
-        let this = Value::Object(self.factory.alloc(ObjectInfo {
-            kind: ObjectKind::Ordinary,
-            prototype: callee.get_property("prototype"),
-            property: FxHashMap::default(),
-            sym_property: FxHashMap::default(),
-        }));
+    /// https://tc39.github.io/ecma262/#sec-construct
+    /// The prototype of the new object is taken from ``new_target``, which differs from ``callee``
+    /// when a derived class calls ``super()``.
+    fn enter_constructor(&mut self, callee: Value, args: &[Value], new_target: Value) -> VMResult {
+        let (is_builtin, is_derived) = if callee.is_function_object() {
+            match callee.as_function().kind {
+                // https://tc39.github.io/ecma262/#sec-bound-function-exotic-objects-construct-argumentslist-newtarget
+                FunctionObjectKind::Bound {
+                    target,
+                    args: ref bound_args,
+                    ..
+                } => {
+                    let args = [&bound_args[..], args].concat();
+                    let new_target = if new_target == callee {
+                        target
+                    } else {
+                        new_target
+                    };
+                    return self.enter_constructor(target, &args, new_target);
+                }
+                FunctionObjectKind::Builtin(_) => (true, false),
+                FunctionObjectKind::User { info, .. } => {
+                    (false, info.constructor_kind == ConstructorKind::Derived)
+                }
+            }
+        } else {
+            (false, false)
+        };
+
+        // A derived constructor binds this by calling super().
+        let this = if is_derived {
+            Value::uninitialized()
+        } else {
+            Value::Object(self.factory.alloc(ObjectInfo {
+                kind: ObjectKind::Ordinary,
+                prototype: new_target.get_property("prototype"),
+                property: PropertyMap::default(),
+                sym_property: FxHashMap::default(),
+                extensible: true,
+            }))
+        };
 
-        self.enter_function(callee, args, this, true)
+        self.enter_function(callee, args, this, true)?;
+
+        if !is_builtin {
+            self.current_context
+                .variable_environment
+                .set_new_target(new_target);
+        } else if new_target != callee {
+            // A built-in constructor creates its own object. Make it an instance of the subclass.
+            let val: Value = (*self.current_context.stack.last().unwrap()).into();
+            if val.is_object() {
+                val.get_object_info().prototype = new_target.get_property("prototype");
+            }
+        }
+
+        Ok(())
     }
 
     fn enter_function(
@@ -1136,6 +2394,37 @@ impl VM {
                 self.current_context.stack.push(val.into());
                 Ok(())
             }
+            FunctionObjectKind::Bound {
+                target,
+                this,
+                args: ref bound_args,
+            } => {
+                let args = [&bound_args[..], args].concat();
+                self.enter_function(target, &args, this, constructor_call)
+            }
+            FunctionObjectKind::User {
+                ref info,
+                outer_env,
+            } if info.is_async => {
+                if constructor_call {
+                    return Err(self.current_context.error_type("Not a constructor"));
+                }
+                let promise = self.call_async_function(*info, outer_env, args, this)?;
+                self.current_context.stack.push(promise.into());
+                Ok(())
+            }
+            FunctionObjectKind::User {
+                ref info,
+                outer_env,
+            } if info.is_generator => {
+                if constructor_call {
+                    return Err(self.current_context.error_type("Not a constructor"));
+                }
+                let generator =
+                    self.call_generator_function(callee, *info, outer_env, args, this)?;
+                self.current_context.stack.push(generator.into());
+                Ok(())
+            }
             FunctionObjectKind::User {
                 ref info,
                 outer_env,
@@ -1177,16 +2466,27 @@ impl VM {
         let context = std::mem::replace(&mut self.current_context, ExecContext::empty());
         self.saved_context.push(context);
 
-        let this = if user_func.this_mode == ThisMode::Lexical {
+        // https://tc39.github.io/ecma262/#sec-ordinarycallbindthis
+        let this = match user_func.this_mode {
             // Arrow function
-            outer_env.unwrap().get_this_binding()
+            ThisMode::Lexical => outer_env.unwrap().get_this_binding(),
+            ThisMode::Global
+                if mode != CallMode::ModuleCall && (this.is_undefined() || this.is_null()) =>
+            {
+                self.global_environment.get_global_object()
+            }
+            ThisMode::Global | ThisMode::Strict => this,
+        };
+
+        // An arrow function has no this binding of its own.
+        let this_binding = if user_func.this_mode == ThisMode::Lexical {
+            Value::empty()
         } else {
             this
         };
-
-        let var_env_ref = self
-            .factory
-            .create_function_environment(user_func, outer_env, args, this);
+        let var_env_ref =
+            self.factory
+                .create_function_environment(user_func, outer_env, args, this_binding);
 
         let mut lex_env_ref = self
             .factory
@@ -1216,6 +2516,12 @@ impl VM {
             return Err(self.current_context.error_type("Not a constructor"));
         }
 
+        if user_func.is_class_constructor && !constructor_call {
+            return Err(self
+                .current_context
+                .error_type("Class constructor cannot be invoked without 'new'"));
+        }
+
         self.prepare_context_for_function_invokation(
             user_func,
             outer_env,
@@ -22,3 +22,159 @@ assert(c.join(), '1,2,3')
 assert(c.join(undefined), '1,2,3')
 assert(c.join('_'), '1_2_3')
 assert(c.join(2), '12223')
+assert([1, , undefined].join('-'), '1--')
+assert([null, 'a', undefined].join(), ',a,')
+assert([1, 2].toString(), '1,2')
+assert([1, [2, 3], null].toString(), '1,2,3,')
+assert('' + [1, 2], '1,2')
+assert(Array.prototype.toString.call({ join: function() { return 'joined' } }), 'joined')
+assert(Array.prototype.toString.call({}), '[object Object]')
+
+// Array statics
+assert(Array.isArray([]), true)
+assert(Array.isArray({ length: 0 }), false)
+assert(Array.of(7), [7])
+assert(Array.of(1, 2, 3), [1, 2, 3])
+assert(Array.from('abc'), ['a', 'b', 'c'])
+assert(Array.from({ length: 3 }, function(v, i) { return i * 2 }), [0, 2, 4])
+assert(Array.from([1, 2, 3].keys()), [0, 1, 2])
+
+// push / pop / shift / unshift
+let d = [1, 2, 3]
+assert(d.push(4, 5), 5)
+assert(d.pop(), 5)
+assert(d.shift(), 1)
+assert(d.unshift(0, 1), 5)
+assert(d, [0, 1, 2, 3, 4])
+assert([].pop(), undefined)
+assert([].shift(), undefined)
+
+// slice / splice / concat
+assert([1, 2, 3, 4, 5].slice(1, 3), [2, 3])
+assert([1, 2, 3, 4, 5].slice(-2), [4, 5])
+assert([1, 2, 3].slice(2, 1), [])
+let e = [1, 2, 3, 4, 5]
+assert(e.splice(1, 2), [2, 3])
+assert(e, [1, 4, 5])
+assert(e.splice(1, 0, 'a', 'b'), [])
+assert(e, [1, 'a', 'b', 4, 5])
+assert(e.splice(-2), [4, 5])
+assert(e, [1, 'a', 'b'])
+assert([1].concat([2, 3], 4, [[5]]), [1, 2, 3, 4, [5]])
+
+// reverse / sort
+assert([1, 2, 3].reverse(), [3, 2, 1])
+assert([10, 9, 1, 2].sort(), [1, 10, 2, 9])
+assert([10, 9, 1, 2].sort(function(x, y) { return x - y }), [1, 2, 9, 10])
+assert([3, undefined, 1].sort(), [1, 3, undefined])
+let people = [{ name: 'a', age: 30 }, { name: 'b', age: 20 },
+  { name: 'c', age: 30 }, { name: 'd', age: 20 }]
+let sorted = people.sort(function(x, y) { return x.age - y.age })
+assert(sorted.map(function(p) { return p.name }), ['b', 'd', 'a', 'c'])
+
+// Searching
+assert([1, 2, 3, 2].indexOf(2), 1)
+assert([1, 2, 3, 2].indexOf(2, 2), 3)
+assert([1, 2, 3, 2].lastIndexOf(2), 3)
+assert([1, 2, 3, 2].lastIndexOf(2, -2), 1)
+assert([1, 2, 3].indexOf(4), -1)
+assert([NaN].indexOf(NaN), -1)
+assert([NaN].includes(NaN), true)
+assert([1, 2, 3].includes(2, 2), false)
+assert([5, 12, 8].find(function(x) { return x > 6 }), 12)
+assert([5, 12, 8].findIndex(function(x) { return x > 6 }), 1)
+assert([5].find(function(x) { return x > 6 }), undefined)
+assert([5].findIndex(function(x) { return x > 6 }), -1)
+
+// Iteration
+assert([1, 2, 3, 4].filter(function(x) { return x % 2 == 0 }), [2, 4])
+assert([1, 2, 3].map(function(x, i) { return x * i }), [0, 2, 6])
+assert([1, 2, 3].reduce(function(acc, x) { return acc + x }), 6)
+assert([1, 2, 3].reduce(function(acc, x) { return acc + x }, 10), 16)
+assert(['a', 'b', 'c'].reduceRight(function(acc, x) { return acc + x }), 'cba')
+assert([1, 2, 3].some(function(x) { return x > 2 }), true)
+assert([1, 2, 3].every(function(x) { return x > 2 }), false)
+assert([].every(function(x) { return false }), true)
+// Callbacks returning values other than booleans
+assert([1, 2].filter(function() {}), [])
+assert([1, 2, 3].filter(function(x) { return x - 2 }), [1, 3])
+assert(['', 'a'].filter(function(x) { return x }), ['a'])
+assert([1].some(function() { return null }), false)
+assert([1].some(function() { return 'yes' }), true)
+assert([1, 2].every(function() { return 1 }), true)
+assert([1, 2].every(function() {}), false)
+assert([0, 1].find(function(x) { return x }), 1)
+assert([0, 1].findIndex(function(x) { return x }), 1)
+assert([1].find(function() { return '' }), undefined)
+let sum = 0
+;[1, 2, 3].forEach(function(x) { sum += x })
+assert(sum, 6)
+let counter = { count: 0 }
+;[1, 2].forEach(function() { this.count++ }, counter)
+assert(counter.count, 2)
+
+// Holes are skipped
+let holes = [1, , 3]
+let visited = []
+holes.forEach(function(x, i) { visited.push(i) })
+assert(visited, [0, 2])
+assert(holes.indexOf(undefined), -1)
+assert(holes.includes(undefined), true)
+assert(holes.filter(function() { return true }), [1, 3])
+
+// fill / copyWithin / flat / flatMap
+assert([1, 2, 3].fill(0), [0, 0, 0])
+assert([1, 2, 3, 4].fill(9, 1, -1), [1, 9, 9, 4])
+assert([1, 2, 3, 4, 5].copyWithin(0, 3), [4, 5, 3, 4, 5])
+assert([1, 2, 3, 4, 5].copyWithin(1, 3, 4), [1, 4, 3, 4, 5])
+assert([1, [2, [3, [4]]]].flat(), [1, 2, [3, [4]]])
+assert([1, [2, [3, [4]]]].flat(Infinity), [1, 2, 3, 4])
+assert([1, 2].flatMap(function(x) { return [x, x * 2] }), [1, 2, 2, 4])
+
+// keys / values / entries
+let keys = []
+for (let k of ['a', 'b'].keys()) keys.push(k)
+assert(keys, [0, 1])
+let entries = []
+for (let e of ['a', 'b'].entries()) entries.push(e)
+assert(entries, [[0, 'a'], [1, 'b']])
+assert(Array.prototype[Symbol.iterator] === Array.prototype.values, true)
+
+// Generic methods on array-like objects
+function rest() {
+  return Array.prototype.slice.call(arguments, 1)
+}
+assert(rest(1, 2, 3), [2, 3])
+let arrayLike = { length: 2, 0: 'a', 1: 'b' }
+assert([].map.call(arrayLike, function(x) { return x + x }), ['aa', 'bb'])
+assert(Array.prototype.join.call(arrayLike, '-'), 'a-b')
+assert(Array.prototype.indexOf.call(arrayLike, 'b'), 1)
+assert(Array.prototype.join.call('abc'), 'a,b,c')
+let pushed = { length: 1, 0: 'x' }
+assert(Array.prototype.push.call(pushed, 'y', 'z'), 3)
+assert(pushed, { length: 3, 0: 'x', 1: 'y', 2: 'z' })
+assert(Array.prototype.pop.call(pushed), 'z')
+assert(pushed, { length: 2, 0: 'x', 1: 'y' })
+assert(Array.prototype.shift.call(pushed), 'x')
+assert(pushed, { length: 1, 0: 'y' })
+assert(Array.prototype.unshift.call(pushed, 'v', 'w'), 3)
+assert(pushed, { length: 3, 0: 'v', 1: 'w', 2: 'y' })
+assert(Array.prototype.reverse.call(pushed), { length: 3, 0: 'y', 1: 'w', 2: 'v' })
+assert(Array.prototype.splice.call(pushed, 1, 1, 'a', 'b'), ['w'])
+assert(pushed, { length: 4, 0: 'y', 1: 'a', 2: 'b', 3: 'v' })
+assert(Array.prototype.sort.call(pushed), { length: 4, 0: 'a', 1: 'b', 2: 'v', 3: 'y' })
+assert(Array.prototype.reduce.call(pushed, function(acc, x) { return acc + x }), 'abvy')
+let pushedToEmpty = {}
+Array.prototype.push.call(pushedToEmpty, 1)
+assert(pushedToEmpty, { 0: 1, length: 1 })
+function sumArguments() {
+  return Array.prototype.reduce.call(arguments, function(acc, x) { return acc + x }, 0)
+}
+assert(sumArguments(1, 2, 3), 6)
+let threw = false
+try {
+  Array.prototype.map.call(undefined, function(x) { return x })
+} catch (e) {
+  threw = e instanceof TypeError
+}
+assert(threw, true)
@@ -0,0 +1,111 @@
+let assert = require('assert').deepStrictEqual
+
+let log = []
+
+async function add(a, b) {
+  log.push('add')
+  let x = await a
+  let y = await Promise.resolve(b)
+  return x + y
+}
+
+let sum = add(1, 2)
+log.push('called')
+assert(log, ['add', 'called'])
+assert(typeof sum.then, 'function')
+
+async function fail() {
+  await null
+  throw new Error('failed')
+}
+
+async function catcher() {
+  try {
+    await fail()
+    return 'unreachable'
+  } catch (e) {
+    return 'caught ' + e.message
+  } finally {
+    log.push('finally')
+  }
+}
+
+async function rejected() {
+  try {
+    await Promise.reject('rejected')
+  } catch (e) {
+    return e
+  }
+}
+
+let arrow = async x => (await x) * 2
+let arrow2 = async (x, y) => {
+  return (await x) + (await y)
+}
+let expr = async function() {
+  return 'expr'
+}
+
+class Counter {
+  constructor() {
+    this.count = 0
+  }
+  async increment() {
+    await undefined
+    this.count++
+    return this.count
+  }
+}
+
+async function loop() {
+  let total = 0
+  for (let i = 0; i < 5; i++) {
+    total += await i
+  }
+  return total
+}
+
+async function nested() {
+  async function inner(x) {
+    return x + 1
+  }
+  return (await inner(1)) + (await inner(2))
+}
+
+async function main() {
+  assert(await sum, 3)
+  assert(await catcher(), 'caught failed')
+  assert(await rejected(), 'rejected')
+  assert(await arrow(Promise.resolve(21)), 42)
+  assert(await arrow2(1, Promise.resolve(2)), 3)
+  assert(await expr(), 'expr')
+  let counter = new Counter()
+  await counter.increment()
+  assert(await counter.increment(), 2)
+  assert(await loop(), 10)
+  assert(await nested(), 5)
+  assert(await 'plain', 'plain')
+  let all = await Promise.all([add(1, 1), arrow(2)])
+  assert(all, [2, 4])
+  return 'done'
+}
+
+let order = []
+async function first() {
+  order.push(1)
+  await null
+  order.push(3)
+}
+first()
+order.push(2)
+
+main()
+  .then(function(result) {
+    assert(result, 'done')
+    assert(order, [1, 2, 3])
+    log.push(result)
+  })
+  .catch(function(e) {
+    log.push('main failed')
+    throw e
+  })
@@ -0,0 +1,110 @@
+let assert = require('assert').deepStrictEqual
+
+class Point {
+  constructor(x, y) {
+    this.x = x
+    this.y = y
+  }
+  get norm2() { return this.x * this.x + this.y * this.y }
+  set both(v) {
+    this.x = v
+    this.y = v
+  }
+  toString() { return '(' + this.x + ', ' + this.y + ')' }
+  static origin() { return new this(0, 0) }
+}
+
+let p = new Point(3, 4)
+assert(p.norm2, 25)
+assert(p.toString(), '(3, 4)')
+p.both = 2
+assert([p.x, p.y], [2, 2])
+assert(Point.origin().toString(), '(0, 0)')
+assert(p.constructor === Point, true)
+
+// Methods and accessors are not enumerable.
+let keys = []
+for (let k in p) keys.push(k)
+assert(keys, ['x', 'y'])
+
+class Point3D extends Point {
+  constructor(x, y, z) {
+    super(x, y)
+    this.z = z
+  }
+  get norm2() { return super.norm2 + this.z * this.z }
+  toString() { return super.toString() + '@' + this.z }
+}
+
+let q = new Point3D(1, 2, 3)
+assert(q.norm2, 14)
+assert(q.toString(), '(1, 2)@3')
+assert(q.__proto__ === Point3D.prototype, true)
+assert(Point3D.prototype.__proto__ === Point.prototype, true)
+assert(Point3D.__proto__ === Point, true)
+// Static methods are inherited, and `this` is the derived class.
+assert(Point3D.origin().toString(), '(0, 0)@undefined')
+
+// The default derived constructor passes all the arguments to super().
+class Named extends Point3D {
+  static origin() { return 'named ' + super.origin().toString() }
+}
+assert(new Named(1, 1, 1).norm2, 3)
+assert(Named.origin(), 'named (0, 0)@undefined')
+
+// Class expressions, computed keys and arrow functions using super.
+let Counter = class Count {
+  constructor() { this.n = 0 }
+  [Symbol.iterator]() { return [this.n, this.n + 1][Symbol.iterator]() }
+  self() { return Count }
+}
+let counter = new Counter()
+let values = []
+for (let v of counter) values.push(v)
+assert(values, [0, 1])
+assert(counter.self() === Counter, true)
+
+class Greeter extends Counter {
+  greet() {
+    let f = () => super.self()
+    return f() === Counter
+  }
+}
+assert(new Greeter().greet(), true)
+
+// Subclassing a built-in constructor.
+class Stack extends Array {
+  top() { return this[this.length - 1] }
+}
+let s = new Stack()
+s.push(1)
+s.push(2)
+assert(s.top(), 2)
+
+let errors = []
+try { Point(1, 2) } catch (e) { errors.push(e.message) }
+class NoSuper extends Point { constructor() { this.x = 1 } }
+try { new NoSuper() } catch (e) { errors.push(e.message) }
+class Twice extends Point { constructor() { super(); super() } }
+try { new Twice() } catch (e) { errors.push(e.message) }
+try { class Bad extends 1 {} } catch (e) { errors.push(e.message) }
+assert(errors.length, 4)
+
+// super() in an arrow function binds this of the derived constructor.
+class ArrowSuper extends Point {
+  constructor() {
+    let early = () => this
+    let thisError = false
+    try { early() } catch (e) { thisError = e instanceof ReferenceError }
+    const init = () => { const inner = () => super(1, 2); inner() }
+    init()
+    let twiceError = false
+    try { init() } catch (e) { twiceError = e instanceof ReferenceError }
+    this.checks = [thisError, early() === this, twiceError]
+  }
+}
+let arrowSuper = new ArrowSuper()
+assert(arrowSuper.checks, [true, true, true])
+assert(arrowSuper.x, 1)
+class ArrowSuperChild extends ArrowSuper {}
+assert(Object.getPrototypeOf(new ArrowSuperChild()) === ArrowSuperChild.prototype, true)
@@ -0,0 +1,183 @@
+let assert = require('assert').deepStrictEqual
+
+// Map
+let m = new Map()
+assert(m.size, 0)
+assert(m.set('a', 1) === m, true)
+m.set(NaN, 'nan')
+m.set(-0, 'zero')
+let key = {}
+m.set(key, 'obj')
+assert(m.size, 4)
+assert(m.get('a'), 1)
+assert(m.get(NaN), 'nan')
+assert(m.get(0), 'zero')
+assert(m.get(+0), 'zero')
+assert(Object.is(Array.from(m.keys())[2], 0), true)
+assert(m.get(key), 'obj')
+assert(m.get({}), undefined)
+assert(m.has('a'), true)
+assert(m.has('b'), false)
+m.set('a', 2)
+assert(m.get('a'), 2)
+assert(m.size, 4)
+assert(Array.from(m.values()), [2, 'nan', 'zero', 'obj'])
+assert(m.delete('a'), true)
+assert(m.delete('a'), false)
+assert(m.size, 3)
+m.set('a', 3)
+assert(Array.from(m.keys())[3], 'a')
+m.clear()
+assert(m.size, 0)
+assert(m.get(NaN), undefined)
+
+let m2 = new Map([['x', 1], ['y', 2]])
+assert(Array.from(m2), [['x', 1], ['y', 2]])
+assert(Array.from(m2.entries()), [['x', 1], ['y', 2]])
+assert(m2[Symbol.iterator] === m2.entries, true)
+let pairs = []
+for (let [k, v] of m2) pairs.push(k + v)
+assert(pairs, ['x1', 'y2'])
+
+// forEach visits entries added during the iteration, but not deleted ones.
+let m3 = new Map([[1, 'a'], [2, 'b'], [3, 'c']])
+let visited = []
+m3.forEach(function(v, k, map) {
+  visited.push(k)
+  if (k === 1) {
+    map.delete(2)
+    map.set(4, 'd')
+  }
+})
+assert(visited, [1, 3, 4])
+
+// Iterators are stable under mutation.
+let m4 = new Map([[1, 1], [2, 2], [3, 3]])
+let it = m4.keys()
+assert(it.next().value, 1)
+m4.delete(2)
+m4.set(5, 5)
+assert(it.next().value, 3)
+assert(it.next().value, 5)
+assert(it.next().done, true)
+m4.set(6, 6)
+assert(it.next().done, true)
+let it2 = m4.values()
+m4.clear()
+m4.set(7, 7)
+assert(it2.next().value, 7)
+
+let mapError = false
+try {
+  Map()
+} catch (e) {
+  mapError = true
+}
+assert(mapError, true)
+
+// Set
+let s = new Set([1, 2, 2, NaN, NaN, 0, -0])
+assert(s.size, 4)
+assert(Array.from(s), [1, 2, NaN, 0])
+assert(s.add(3) === s, true)
+assert(s.has(3), true)
+assert(s.has(NaN), true)
+assert(s.has(-0), true)
+assert(s.delete(1), true)
+assert(s.has(1), false)
+assert(Array.from(s.entries()), [[2, 2], [NaN, NaN], [0, 0], [3, 3]])
+assert(s.keys === s.values, true)
+assert(s[Symbol.iterator] === s.values, true)
+let sum = 0
+s.forEach(function(v, k, set) {
+  if (v === 2) set.add(10)
+  if (v === v) sum += v
+})
+assert(sum, 15)
+assert(Array.from(new Set('abca')), ['a', 'b', 'c'])
+s.clear()
+assert(s.size, 0)
+let sv = []
+for (let v of new Set(['p', 'q'])) sv.push(v)
+assert(sv, ['p', 'q'])
+
+// WeakMap
+let k1 = {}
+let k2 = function() {}
+let wm = new WeakMap([[k1, 'one']])
+assert(wm.get(k1), 'one')
+assert(wm.set(k2, 'two') === wm, true)
+assert(wm.has(k2), true)
+assert(wm.get({}), undefined)
+assert(wm.get(1), undefined)
+assert(wm.delete(k1), true)
+assert(wm.has(k1), false)
+let wmError = false
+try {
+  wm.set('str', 1)
+} catch (e) {
+  wmError = true
+}
+assert(wmError, true)
+
+// WeakSet
+let ws = new WeakSet([k1])
+assert(ws.has(k1), true)
+assert(ws.add(k2) === ws, true)
+assert(ws.has(k2), true)
+assert(ws.has(1), false)
+assert(ws.delete(k2), true)
+assert(ws.has(k2), false)
+let wsError = false
+try {
+  ws.add(1)
+} catch (e) {
+  wsError = true
+}
+assert(wsError, true)
+
+// Entries whose key is otherwise unreachable are dropped without breaking the live ones.
+let live = []
+let wm2 = new WeakMap()
+for (let i = 0; i < 5000; i++) {
+  let obj = { i: i }
+  wm2.set(obj, { value: i })
+  if (i % 1000 === 0) live.push(obj)
+}
+for (let i = 0; i < live.length; i++) {
+  assert(wm2.get(live[i]).value, i * 1000)
+}
+
+assert(Object.prototype.toString.call(new Map()), '[object Map]')
+assert(typeof new Set(), 'object')
+
+// Deleting most entries while iterating keeps the iterators at the same entries.
+let big = new Map()
+for (let i = 0; i < 100; i++) big.set(i, i)
+let bigIter = big.keys()
+for (let i = 0; i < 50; i++) bigIter.next()
+for (let i = 0; i < 90; i++) big.delete(i)
+big.set(100, 100)
+let rest = []
+for (const k of bigIter) rest.push(k)
+assert(rest, [90, 91, 92, 93, 94, 95, 96, 97, 98, 99, 100])
+assert(big.size, 11)
+assert(big.get(95), 95)
+
+let bigSet = new Set()
+for (let i = 0; i < 20; i++) bigSet.add(i)
+let forEachVisited = []
+bigSet.forEach(v => {
+  forEachVisited.push(v)
+  bigSet.delete(v + 1)
+  bigSet.delete(v + 2)
+})
+assert(forEachVisited, [0, 3, 6, 9, 12, 15, 18])
+
+// An iterator continues from the start after clear().
+let cleared = new Map([[1, 1], [2, 2]])
+let clearedIter = cleared.values()
+clearedIter.next()
+cleared.clear()
+cleared.set(3, 3)
+assert([...clearedIter], [3])
@@ -0,0 +1,103 @@
+let assert = require('assert').deepStrictEqual
+
+// Run with TZ=JST-9.
+let epoch = new Date(0)
+assert(epoch.getTime(), 0)
+assert(epoch.valueOf(), 0)
+assert(epoch.getFullYear(), 1970)
+assert(epoch.getMonth(), 0)
+assert(epoch.getDate(), 1)
+assert(epoch.getDay(), 4)
+assert(epoch.getHours(), 9)
+assert(epoch.getUTCHours(), 0)
+assert(epoch.getTimezoneOffset(), -540)
+assert(epoch.toString(), 'Thu Jan 01 1970 09:00:00 GMT+0900')
+assert(epoch.toDateString(), 'Thu Jan 01 1970')
+assert(epoch.toTimeString(), '09:00:00 GMT+0900')
+assert(epoch.toUTCString(), 'Thu, 01 Jan 1970 00:00:00 GMT')
+assert(epoch.toISOString(), '1970-01-01T00:00:00.000Z')
+assert(epoch.toJSON(), '1970-01-01T00:00:00.000Z')
+assert(epoch.toLocaleString(), '1/1/1970, 9:00:00 AM')
+assert(epoch.toLocaleDateString(), '1/1/1970')
+assert(epoch.toLocaleTimeString(), '9:00:00 AM')
+assert(JSON.stringify({ d: epoch }), '{"d":"1970-01-01T00:00:00.000Z"}')
+
+// Components are local time.
+let d = new Date(2019, 0, 31, 13, 5, 9, 7)
+assert(d.getTime(), 1548907509007)
+assert(d.toISOString(), '2019-01-31T04:05:09.007Z')
+assert(d.getMilliseconds(), 7)
+assert(new Date(2019, 0).getTime(), 1546268400000)
+assert(new Date(99, 0).getFullYear(), 1999)
+
+// Setters overflow into the larger components.
+assert(d.setMonth(1), 1551585909007)
+assert(d.toISOString(), '2019-03-03T04:05:09.007Z')
+d.setUTCFullYear(2020, 1, 29)
+assert(d.toISOString(), '2020-02-29T04:05:09.007Z')
+d.setHours(25)
+assert(d.getDate(), 1)
+assert(d.getHours(), 1)
+d.setTime(86400000)
+assert(d.getUTCDate(), 2)
+
+assert(Date.UTC(2019, 0, 1), 1546300800000)
+assert(Date.UTC(99, 11), 944006400000)
+assert(Date.UTC(), NaN)
+assert(typeof Date.now(), 'number')
+assert(typeof Date(), 'string')
+assert(typeof new Date(), 'object')
+
+// Date-only forms are UTC, date-time forms without an offset are local time.
+assert(Date.parse('2019-01-01'), 1546300800000)
+assert(Date.parse('2019-01-01T00:00'), 1546268400000)
+assert(Date.parse('2019-01-01T00:00:00.123+01:00'), 1546297200123)
+assert(Date.parse('+275760-09-13T00:00:00.000Z'), 8.64e15)
+assert(Date.parse('Thu Jan 01 1970 09:00:00 GMT+0900 (JST)'), 0)
+assert(Date.parse('Thu, 01 Jan 1970 00:00:00 GMT'), 0)
+assert(Date.parse('1/1/1970, 9:00:00 AM'), 0)
+assert(Date.parse('foo'), NaN)
+assert(new Date('2019-03-04T05:06:07Z').getTime(), 1551675967000)
+assert(new Date(epoch).getTime(), 0)
+assert(new Date(8.64e15 + 1).getTime(), NaN)
+assert(new Date(-1).toISOString(), '1969-12-31T23:59:59.999Z')
+assert(new Date(-62198755200001).toISOString(), '-000002-12-31T23:59:59.999Z')
+assert(new Date(1e15).toISOString(), '+033658-09-27T01:46:40.000Z')
+// Components of negative time values are +0, not -0.
+assert(new Date(-86400000).toISOString(), '1969-12-31T00:00:00.000Z')
+assert(new Date(-62233315200000).toISOString(), '-000003-11-27T00:00:00.000Z')
+assert(new Date(-118800000).toString(), 'Wed Dec 31 1969 00:00:00 GMT+0900')
+assert(1 / new Date(-86400000).getUTCHours(), Infinity)
+assert(1 / new Date(-86400000).getUTCMilliseconds(), Infinity)
+assert(1 / new Date(-118800000).getMinutes(), Infinity)
+
+let invalid = new Date(NaN)
+assert(invalid.getTime(), NaN)
+assert(invalid.getFullYear(), NaN)
+assert(invalid.toString(), 'Invalid Date')
+assert(invalid.toJSON(), null)
+assert(invalid.setDate(1), NaN)
+invalid.setFullYear(2000)
+assert(invalid.toISOString(), '1999-12-31T15:00:00.000Z')
+
+// The default hint is string.
+assert(epoch + 1, 'Thu Jan 01 1970 09:00:00 GMT+09001')
+assert(epoch - 1, -1)
+assert(epoch < new Date(1), true)
+assert(epoch[Symbol.toPrimitive]('number'), 0)
+assert(epoch[Symbol.toPrimitive]('default'), 'Thu Jan 01 1970 09:00:00 GMT+0900')
+let custom = new Date(0)
+custom.toString = () => 'custom'
+custom.valueOf = () => 42
+assert(custom + 1, 'custom1')
+assert(custom * 1, 42)
+
+class MyDate extends Date {
+  year() {
+    return this.getUTCFullYear()
+  }
+}
+let my = new MyDate(0)
+assert(my.year(), 1970)
+assert(my instanceof Date, true)
+assert(new Date(my), new Date(0))
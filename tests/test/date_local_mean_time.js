@@ -0,0 +1,8 @@
+let assert = require('assert').deepStrictEqual
+
+// New York used the local mean time (UTC-4:56:02) until 1883.
+let date = new Date(-3e12)
+// The offset is not rounded to minutes.
+assert(date.getTimezoneOffset(), (4 * 3600 + 56 * 60 + 2) / 60)
+assert([date.getHours(), date.getMinutes(), date.getSeconds()], [13, 43, 58])
+assert(new Date(0).getTimezoneOffset(), 300)
@@ -0,0 +1,102 @@
+let assert = require('assert').deepStrictEqual
+
+// void
+assert(void 0, undefined)
+assert(void 'str', undefined)
+let count = 0
+void count++
+assert(count, 1)
+
+// delete obj.x and obj[k]
+let obj = { a: 1, b: 2, c: 3 }
+assert(delete obj.a, true)
+assert(obj, { b: 2, c: 3 })
+assert('a' in obj, false)
+let key = 'b'
+assert(delete obj[key], true)
+assert(obj, { c: 3 })
+assert(delete obj.missing, true)
+assert(obj.c, 3)
+
+// Inherited properties are not touched.
+class Base {
+  method() {
+    return 'base'
+  }
+}
+let inst = new Base()
+assert(delete inst.method, true)
+assert(inst.method(), 'base')
+
+// Symbol keys
+let sym = Symbol('s')
+let withSym = {}
+withSym[sym] = 1
+assert(delete withSym[sym], true)
+assert(sym in withSym, false)
+
+// Arrays keep holes.
+let arr = [1, 2, 3]
+assert(delete arr[1], true)
+assert(arr.length, 3)
+assert(1 in arr, false)
+assert(arr[1], undefined)
+assert(delete arr['0'], true)
+assert(0 in arr, false)
+assert(delete arr[10], true)
+assert(arr.length, 3)
+assert(delete arr.length, false)
+assert(arr.length, 3)
+
+// Non-configurable properties
+assert(delete Function.prototype[Symbol.hasInstance], false)
+
+// Primitives
+assert(delete 'abc'.length, false)
+assert(delete 'abc'[0], false)
+assert(delete 'abc'[5], true)
+assert(delete (1).x, true)
+assert(delete 1, true)
+
+let caught = false
+try {
+  let n = null
+  delete n.x
+} catch (e) {
+  caught = true
+}
+assert(caught, true)
+
+// Other operands are evaluated and the result is true.
+count = 0
+assert(delete count++, true)
+assert(count, 1)
+
+let x = 1
+assert(delete x, false)
+assert(x, 1)
+
+// Unresolvable names are deleted, and so are properties of the global object.
+assert(delete undeclaredName, true)
+implicitGlobal = 1
+assert(delete implicitGlobal, true)
+let deletedGlobal = false
+try {
+  implicitGlobal
+} catch (e) {
+  deletedGlobal = e instanceof ReferenceError
+}
+assert(deletedGlobal, true)
+function inner() {
+  let local = 1
+  return [delete local, delete undeclaredInFunction]
+}
+assert(inner(), [false, true])
+
+function f() {
+  return arguments
+}
+let args = f(1, 2)
+assert(delete args[0], true)
+assert(args[0], undefined)
+assert(args[1], 2)
@@ -0,0 +1,162 @@
+let assert = require('assert').deepStrictEqual
+
+// Declarations
+{
+  let [a, b, c] = [1, 2, 3]
+  assert([a, b, c], [1, 2, 3])
+
+  const { x, y: z } = { x: 4, y: 5 }
+  assert([x, z], [4, 5])
+
+  var [, second, , fourth] = [1, 2, 3, 4]
+  assert([second, fourth], [2, 4])
+
+  let [p, q] = 'ab'
+  assert([p, q], ['a', 'b'])
+}
+
+// Defaults
+{
+  let [a = 1, b = 2] = [undefined, null]
+  assert([a, b], [1, null])
+
+  let { c = 3, d: e = 4 } = { d: 5 }
+  assert([c, e], [3, 5])
+
+  let called = 0
+  let f = function() {
+    called++
+    return 6
+  }
+  let [g = f(), h = f()] = [7]
+  assert([g, h, called], [7, 6, 1])
+}
+
+// Nested patterns
+{
+  let {
+    a: [b, { c }],
+    d: { e = 1 }
+  } = { a: [2, { c: 3 }], d: {} }
+  assert([b, c, e], [2, 3, 1])
+}
+
+// Computed keys
+{
+  let key = 'foo'
+  let { [key]: a, ['b' + 'ar']: b } = { foo: 1, bar: 2 }
+  assert([a, b], [1, 2])
+}
+
+// Rest elements
+{
+  let [a, ...b] = [1, 2, 3]
+  assert([a, b], [1, [2, 3]])
+
+  let [...c] = []
+  assert(c, [])
+
+  let key = 'y'
+  let { x, [key]: y, ...rest } = { x: 1, y: 2, z: 3, w: 4 }
+  assert([x, y, rest], [1, 2, { z: 3, w: 4 }])
+}
+
+// Assignments
+{
+  let a = 1,
+    b = 2
+  ;[a, b] = [b, a]
+  assert([a, b], [2, 1])
+
+  let obj = {}
+  let arr = []
+  ;({ x: obj.x, y: arr[0] } = { x: 3, y: 4 })
+  assert([obj.x, arr[0]], [3, 4])
+
+  let c, d
+  let result = ([c, d = 5] = [6])
+  assert([c, d, result], [6, 5, [6]])
+}
+
+// Parameters
+{
+  function f({ a, b = 2 }, [c, ...d], e = 5) {
+    return [a, b, c, d, e]
+  }
+  assert(f({ a: 1 }, [3, 4]), [1, 2, 3, [4], 5])
+
+  function g(a, b = a + 1) {
+    return [a, b]
+  }
+  assert(g(1), [1, 2])
+  assert(g(1, 3), [1, 3])
+
+  let h = ({ a }) => a
+  assert(h({ a: 7 }), 7)
+
+  let i = (a = 1) => a
+  assert(i(), 1)
+}
+
+// Catch parameters
+{
+  try {
+    throw { message: 'err', code: 1 }
+  } catch ({ message, code }) {
+    assert([message, code], ['err', 1])
+  }
+}
+
+// For-of
+{
+  let pairs = []
+  for (let [k, v] of [['a', 1], ['b', 2]]) pairs.push(k + v)
+  assert(pairs, ['a1', 'b2'])
+
+  let sum = 0
+  let x
+  for ({ x } of [{ x: 1 }, { x: 2 }]) sum += x
+  assert(sum, 3)
+}
+
+// Closing the iterator
+{
+  let closed = 0
+  let steps = 0
+  const iterable = {}
+  iterable[Symbol.iterator] = () => ({
+    next: function() {
+      steps++
+      return steps <= 2 ? { value: steps, done: false } : { done: true }
+    },
+    'return': function() {
+      closed++
+      return {}
+    },
+  })
+
+  let [a] = iterable
+  assert([a, closed], [1, 1])
+
+  steps = 0
+  let [b, c, d, e] = iterable
+  assert([b, c, d, e, closed, steps], [1, 2, undefined, undefined, 1, 3])
+
+  steps = 0
+  let [...rest] = iterable
+  assert([rest, closed], [[1, 2], 1])
+
+  steps = 0
+  try {
+    const target = {}
+    Object.defineProperty(target, 'x', {
+      set: function(v) {
+        throw 'err'
+      },
+    })
+    ;[target.x] = iterable
+  } catch (err) {
+    assert(err, 'err')
+  }
+  assert(closed, 2)
+}
@@ -0,0 +1,23 @@
+let assert = require('assert').deepStrictEqual
+
+let i = 0
+do {
+  i++
+} while (i < 5)
+assert(i, 5)
+
+// The body runs at least once.
+let count = 0
+do count++; while (false)
+assert(count, 1)
+
+// continue jumps to the condition.
+let log = []
+let j = 0
+do {
+  j++
+  if (j % 2 === 0) continue
+  if (j > 7) break
+  log.push(j)
+} while (j < 10)
+assert(log, [1, 3, 5, 7])
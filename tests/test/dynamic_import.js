@@ -0,0 +1,13 @@
+let assert = require('assert').deepStrictEqual
+
+let loaded = false
+import('./module/counter.mjs').then(function(ns) {
+  loaded = true
+  assert(ns.count, 0)
+  ns.increment()
+  assert(ns.count, 1)
+  return import('./module/counter.mjs')
+}).then(function(ns) {
+  assert(ns.count, 1)
+})
+assert(loaded, false)
@@ -0,0 +1,114 @@
+let assert = require('assert').deepStrictEqual
+
+let e = new Error('msg')
+assert(e instanceof Error, true)
+assert(e.message, 'msg')
+assert(e.name, 'Error')
+assert(e.toString(), 'Error: msg')
+assert('' + e, 'Error: msg')
+assert(new Error().toString(), 'Error')
+assert(Error('called').message, 'called')
+assert(Object.keys(e), [])
+assert(Error.prototype.message, '')
+
+let natives = [
+  [EvalError, 'EvalError'],
+  [RangeError, 'RangeError'],
+  [ReferenceError, 'ReferenceError'],
+  [SyntaxError, 'SyntaxError'],
+  [TypeError, 'TypeError'],
+  [URIError, 'URIError']]
+for (let [NativeError, name] of natives) {
+  let err = new NativeError('oops')
+  assert(err instanceof NativeError, true)
+  assert(err instanceof Error, true)
+  assert(err.name, name)
+  assert(err.toString(), name + ': oops')
+  assert(NativeError('x') instanceof NativeError, true)
+  assert(Object.getPrototypeOf(NativeError) === Error, true)
+  assert(Object.getPrototypeOf(NativeError.prototype) === Error.prototype, true)
+  assert(NativeError.prototype.constructor === NativeError, true)
+}
+assert(new TypeError('t') instanceof RangeError, false)
+
+let custom = { name: 'Custom', message: 'text' }
+assert(Error.prototype.toString.call(custom), 'Custom: text')
+assert(Error.prototype.toString.call({ name: '', message: 'only' }), 'only')
+assert(Error.prototype.toString.call({}), 'Error')
+
+let cause = new Error('inner')
+let outer = new Error('outer', { cause: cause })
+assert(outer.cause === cause, true)
+assert(Object.keys(outer), [])
+assert('cause' in new Error('no cause', {}), false)
+assert(new RangeError('r', { cause: 1 }).cause, 1)
+
+let agg = new AggregateError([1, new TypeError('t')], 'many')
+assert(agg instanceof AggregateError, true)
+assert(agg instanceof Error, true)
+assert(agg.name, 'AggregateError')
+assert(agg.message, 'many')
+assert(agg.errors.length, 2)
+assert(agg.errors[0], 1)
+assert(agg.toString(), 'AggregateError: many')
+
+class MyError extends Error {
+  constructor(message) {
+    super(message)
+    this.name = 'MyError'
+  }
+}
+let mine = new MyError('mine')
+assert(mine instanceof MyError, true)
+assert(mine instanceof Error, true)
+assert(mine.message, 'mine')
+assert(mine.toString(), 'MyError: mine')
+
+function thrown(f) {
+  try {
+    f()
+  } catch (err) {
+    return err
+  }
+}
+assert(thrown(() => null.x) instanceof TypeError, true)
+assert(thrown(() => undefinedVariable) instanceof ReferenceError, true)
+assert(thrown(() => new Array(-1)) instanceof RangeError, true)
+assert(thrown(() => new Array(1.5)) instanceof RangeError, true)
+assert(thrown(() => 'a'.repeat(-1)) instanceof RangeError, true)
+assert(thrown(() => (1).toFixed(101)) instanceof RangeError, true)
+assert(thrown(() => { throw new SyntaxError('s') }).name, 'SyntaxError')
+assert(new Array(3).length, 3)
+
+function makeError() {
+  return new Error('here')
+}
+function callMakeError() {
+  return makeError()
+}
+let stack = callMakeError().stack.split('\n')
+assert(stack[0], 'Error: here')
+assert(stack[1].startsWith('    at makeError ('), true)
+assert(stack[1].endsWith('error.js:84:10)'), true)
+assert(stack[2].startsWith('    at callMakeError ('), true)
+assert(stack[2].endsWith('error.js:87:10)'), true)
+assert(mine.stack.split('\n')[0], 'MyError: mine')
+assert(Error.prototype.stack, undefined)
+
+function readNull() {
+  return null.x
+}
+let internal = thrown(readNull)
+assert(internal.stack.split('\n')[0], internal.toString())
+assert(internal.stack.split('\n')[1].startsWith('    at readNull ('), true)
+
+let replaced = new Error('replaced')
+replaced.stack = 'custom'
+assert(replaced.stack, 'custom')
+assert(Object.keys(replaced), [])
+
+// Early errors of source text given at run time are SyntaxErrors.
+assert(thrown(() => new Function('}')) instanceof SyntaxError, true)
+assert(thrown(() => JSON.parse('x')) instanceof SyntaxError, true)
+assert(thrown(() => new RegExp('(')) instanceof SyntaxError, true)
+assert(thrown(() => new RegExp('(')).name, 'SyntaxError')
@@ -0,0 +1,227 @@
+let assert = require('assert').deepStrictEqual
+
+// for-in visits integer keys first, then the others in insertion order.
+let keys = []
+for (var k in { b: 1, a: 2, 2: 'x', 1: 'y' }) keys.push(k)
+assert(keys, ['1', '2', 'b', 'a'])
+
+// Holes are skipped.
+keys = []
+for (let k in [5, , 7]) keys.push(k)
+assert(keys, ['0', '2'])
+
+// Inherited enumerable properties are visited once, after the own ones.
+let proto = { shadowed: 1, inherited: 2 }
+let child = { own: 3, shadowed: 4 }
+child.__proto__ = proto
+keys = []
+for (const k in child) keys.push(k)
+assert(keys, ['own', 'shadowed', 'inherited'])
+
+// Builtin methods are not enumerable.
+keys = []
+for (const k in []) keys.push(k)
+for (const k in function() {}) keys.push(k)
+assert(keys, [])
+
+// Keys deleted before being visited are skipped.
+let deleting = { a: 1, b: 2, c: 3 }
+keys = []
+for (const k in deleting) {
+  keys.push(k)
+  delete deleting.b
+}
+assert(keys, ['a', 'c'])
+let ary = [1, 2, 3]
+keys = []
+for (const k in ary) {
+  keys.push(k)
+  ary.length = 1
+}
+assert(keys, ['0'])
+keys = []
+for (const k in new String('ab')) keys.push(k)
+for (const k in 'cd') keys.push(k)
+assert(keys, ['0', '1', '0', '1'])
+
+for (const k in null) throw 'unreachable'
+for (const k in undefined) throw 'unreachable'
+
+// for-of over arrays and strings.
+let sum = 0
+for (const x of [1, 2, 3, 4]) {
+  if (x === 2) continue
+  if (x === 4) break
+  sum += x
+}
+assert(sum, 4)
+
+let chars = []
+for (let c of 'aé😀') chars.push(c)
+assert(chars, ['a', 'é', '😀'])
+
+// let/const get a fresh binding on each iteration.
+let fns = []
+for (let x of [1, 2, 3]) fns.push(() => x)
+assert(fns.map(f => f()), [1, 2, 3])
+
+// Any assignment target can be used.
+let last
+for (last of [1, 2]) {}
+assert(last, 2)
+let obj = {}
+for (obj.p of ['a', 'b']) {}
+assert(obj.p, 'b')
+
+// User-defined iterables.
+let iterable = {}
+iterable[Symbol.iterator] = function() {
+  let n = 0
+  return {
+    next: function() {
+      n++
+      return { value: n, done: n > 3 }
+    },
+  }
+}
+let got = []
+for (const v of iterable) got.push(v)
+assert(got, [1, 2, 3])
+
+// Iterators are themselves iterable.
+let iter = [10, 20][Symbol.iterator]()
+assert(iter[Symbol.iterator]() === iter, true)
+assert(iter.next(), { value: 10, done: false })
+got = []
+for (const v of iter) got.push(v)
+assert(got, [20])
+assert(iter.next(), { value: undefined, done: true })
+
+// Nested loops and return from inside a loop.
+let pairs = []
+for (const x of [1, 2]) for (const y of 'ab') pairs.push(x + y)
+assert(pairs, ['1a', '1b', '2a', '2b'])
+
+function find(ary, x) {
+  for (const v of ary) if (v === x) return true
+  return false
+}
+assert(find([1, 2, 3], 2), true)
+assert(find([1, 2, 3], 4), false)
+
+let caught = false
+try {
+  for (const x of 1) {}
+} catch (e) {
+  caught = true
+}
+assert(caught, true)
+
+// Leaving a loop early closes the iterator.
+let log = []
+function* gen() {
+  try {
+    yield 1
+    yield 2
+  } finally {
+    log.push('finally')
+  }
+}
+for (const x of gen()) break
+assert(log, ['finally'])
+
+log = []
+function firstOf(iterable) {
+  for (const x of iterable) return x
+}
+assert(firstOf(gen()), 1)
+assert(log, ['finally'])
+
+log = []
+try {
+  for (const x of gen()) throw 'err'
+} catch (e) {
+  log.push(e)
+}
+assert(log, ['finally', 'err'])
+
+log = []
+outer: for (const x of [1, 2]) {
+  for (const y of gen()) continue outer
+}
+assert(log, ['finally', 'finally'])
+
+log = []
+function closedBeforeFinally() {
+  try {
+    for (const x of gen()) return x
+  } finally {
+    log.push('outer finally')
+  }
+}
+assert(closedBeforeFinally(), 1)
+assert(log, ['finally', 'outer finally'])
+
+// An iterator which is done or throws is not closed.
+function makeIterable(values, throwAt) {
+  let i = 0
+  const iterable = { closed: 0 }
+  iterable[Symbol.iterator] = () => ({
+    next: function() {
+      if (i === throwAt) throw 'next'
+      return i < values.length ? { value: values[i++], done: false } : { done: true }
+    },
+    return: function() {
+      iterable.closed++
+      return {}
+    },
+  })
+  return iterable
+}
+let closable = makeIterable([1, 2])
+for (const x of closable) {}
+assert(closable.closed, 0)
+closable = makeIterable([1, 2], 1)
+try {
+  for (const x of closable) {}
+} catch (e) {
+  assert(e, 'next')
+}
+assert(closable.closed, 0)
+closable = makeIterable([1, 2])
+for (const x of closable) break
+assert(closable.closed, 1)
+
+// Errors thrown by return() are thrown unless the loop is left by an exception.
+let throwingReturn = {}
+throwingReturn[Symbol.iterator] = () => ({
+  next: function() {
+    return { value: 1, done: false }
+  },
+  return: function() {
+    throw 'return'
+  },
+})
+try {
+  for (const x of throwingReturn) break
+} catch (e) {
+  log = e
+}
+assert(log, 'return')
+try {
+  for (const x of throwingReturn) throw 'body'
+} catch (e) {
+  log = e
+}
+assert(log, 'body')
+
+// An exception caught in the loop keeps the iterator open.
+log = []
+for (const x of [1, 2]) {
+  try {
+    for (const y of gen()) throw 'err'
+  } catch (e) {
+    log.push(x)
+  }
+}
+assert(log, ['finally', 1, 'finally', 2])
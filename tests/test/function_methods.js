@@ -12,3 +12,125 @@ function Food(name, price) {
 
 assert(new Food('cheese', 5).name, 'cheese')
 // expected output: "cheese"
+
+// apply
+function add(a, b) {
+  return a + b
+}
+assert(add.apply(null, [1, 2]), 3)
+assert(add.apply(null, { length: 2, 0: 'x', 1: 'y' }), 'xy')
+assert(Math.max.apply(null, [1, 5, 3]), 5)
+assert(add.apply(null), NaN)
+function getThis() {
+  return this
+}
+let obj = { a: 1 }
+assert(getThis.apply(obj, []) === obj, true)
+let applyError = false
+try {
+  add.apply(null, 1)
+} catch (e) {
+  applyError = true
+}
+assert(applyError, true)
+
+// bind
+let add10 = add.bind(null, 10)
+assert(add10(5), 15)
+assert(add10.name, 'bound add')
+assert(add10.length, 1)
+assert(add.bind(null, 1, 2, 3).length, 0)
+assert(getThis.bind(obj)() === obj, true)
+let BoundFood = Food.bind(null, 'bread')
+let bread = new BoundFood(2)
+assert(bread.name, 'bread')
+assert(bread.price, 2)
+assert(bread instanceof Food, true)
+assert(bread instanceof BoundFood, true)
+
+// toString
+assert(add.toString(), 'function add(a, b) {\n  return a + b\n}')
+assert((x => x * 2).toString(), 'x => x * 2')
+assert((async (a) => { return a }).toString(), 'async (a) => { return a }')
+class Shape { area() { return 0 } }
+assert(Shape.toString(), 'class Shape { area() { return 0 } }')
+assert(Shape.prototype.area.toString(), 'area() { return 0 }')
+assert(Math.max.toString(), 'function max() { [native code] }')
+let toStringError = false
+try {
+  Function.prototype.toString.call({})
+} catch (e) {
+  toStringError = true
+}
+assert(toStringError, true)
+
+// name and length
+function withDefaults(a, b = 1, ...rest) {}
+assert(withDefaults.length, 1)
+assert(((...args) => 0).length, 0)
+assert(withDefaults.name, 'withDefaults')
+assert(Function.prototype.length, 0)
+assert(Function.prototype.name, '')
+assert(Math.max.length, 2)
+assert(Math.max.name, 'max')
+assert([].push.length, 1)
+assert(Object.defineProperty.length, 3)
+assert(Promise.prototype.then.length, 2)
+assert(Function.prototype.call.length, 1)
+assert(parseInt.length, 2)
+assert(Date.length, 7)
+assert(Map.length, 0)
+assert(Error.length, 1)
+assert(AggregateError.length, 2)
+
+// inferred names
+let arrow = () => 1
+assert(arrow.name, 'arrow')
+let anonymousClass = class {}
+assert(anonymousClass.name, 'anonymousClass')
+assert(new anonymousClass() instanceof anonymousClass, true)
+let assigned
+assigned = function() {}
+assert(assigned.name, 'assigned')
+let methods = { m: () => 0, n: function() {} }
+assert(methods.m.name, 'm')
+assert(methods.n.name, 'n')
+function withDefault(callback = () => 0) {
+  return callback.name
+}
+assert(withDefault(), 'callback')
+let { fromPattern = function() {} } = {}
+assert(fromPattern.name, 'fromPattern')
+let notInferred = {}
+notInferred.f = function() {}
+assert(notInferred.f.name, '')
+let keepsName = function original() {}
+assert(keepsName.name, 'original')
+
+// Function constructor
+let sum = new Function('a', 'b', 'return a+b')
+assert(sum(1, 2), 3)
+assert(sum.name, 'anonymous')
+assert(sum.length, 2)
+assert(sum.toString(), 'function anonymous(a,b\n) {\nreturn a+b\n}')
+assert(Function('return 7')(), 7)
+assert(new Function('a, b', 'c', 'return a + b + c')(1, 2, 3), 6)
+assert(new Function()(), undefined)
+function makeFunction() {
+  let local = 1
+  return new Function('return local')
+}
+let localError = false
+try {
+  makeFunction()()
+} catch (e) {
+  localError = true
+}
+assert(localError, true)
+let sourceError = false
+try {
+  new Function('}); (function() {')
+} catch (e) {
+  sourceError = true
+}
+assert(sourceError, true)
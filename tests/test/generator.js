@@ -0,0 +1,243 @@
+let assert = require('assert').deepStrictEqual
+
+function* gen() {
+  let x = yield 1
+  try {
+    yield x
+  } finally {
+    log.push('finally')
+  }
+  return 3
+}
+
+let log = []
+let it = gen()
+assert(it.next('ignored'), { value: 1, done: false })
+assert(it.next('a'), { value: 'a', done: false })
+assert(it.next(), { value: 3, done: true })
+assert(it.next(), { value: undefined, done: true })
+assert(log, ['finally'])
+assert(it[Symbol.iterator]() === it, true)
+
+// return() and throw() run finally blocks.
+log = []
+it = gen()
+it.next()
+it.next()
+assert(it.return(9), { value: 9, done: true })
+assert(log, ['finally'])
+assert(it.next(), { value: undefined, done: true })
+
+it = gen()
+assert(it.return(1), { value: 1, done: true })
+assert(it.next(), { value: undefined, done: true })
+
+it = gen()
+it.next()
+try {
+  it.throw(new Error('boom'))
+  assert(true, false)
+} catch (e) {
+  assert(e.message, 'boom')
+}
+assert(it.next(), { value: undefined, done: true })
+
+function* catcher() {
+  while (true) {
+    try {
+      yield 'waiting'
+    } catch (e) {
+      yield 'caught ' + e
+    }
+  }
+}
+it = catcher()
+it.next()
+assert(it.throw('E'), { value: 'caught E', done: false })
+assert(it.next(), { value: 'waiting', done: false })
+
+// A finally block may yield while returning.
+function* yieldInFinally() {
+  try {
+    yield 1
+  } finally {
+    yield 2
+    log.push('after yield')
+  }
+}
+log = []
+it = yieldInFinally()
+it.next()
+assert(it.return(5), { value: 2, done: false })
+assert(it.next(), { value: 5, done: true })
+assert(log, ['after yield'])
+
+// yield*
+function* inner() {
+  let received = yield 'a'
+  yield received
+  return 'result'
+}
+function* outer() {
+  let result = yield* inner()
+  yield result
+  yield* [1, 2]
+  yield* 'xy'
+}
+let values = []
+for (let v of outer()) values.push(v)
+assert(values, ['a', undefined, 'result', 1, 2, 'x', 'y'])
+
+it = outer()
+it.next()
+assert(it.next('sent'), { value: 'sent', done: false })
+
+function* innerCatcher() {
+  try {
+    yield 1
+  } catch (e) {
+    yield 'inner caught ' + e
+  }
+}
+function* outerCatcher() {
+  yield* innerCatcher()
+  yield 'end'
+}
+it = outerCatcher()
+it.next()
+assert(it.throw('X'), { value: 'inner caught X', done: false })
+assert(it.next(), { value: 'end', done: false })
+
+function* innerFinally() {
+  try {
+    yield 1
+    yield 2
+  } finally {
+    log.push('inner')
+  }
+}
+function* outerFinally() {
+  try {
+    yield* innerFinally()
+  } finally {
+    log.push('outer')
+  }
+}
+log = []
+it = outerFinally()
+it.next()
+assert(it.return(42), { value: 42, done: true })
+assert(log, ['inner', 'outer'])
+
+function* tree(n) {
+  if (n > 0) {
+    yield* tree(n - 1)
+    yield n
+    yield* tree(n - 1)
+  }
+}
+assert([...tree(3)], [1, 2, 1, 3, 1, 2, 1])
+
+// Generator expressions and methods
+const anonymous = function*() {
+  yield
+}
+assert(anonymous().next(), { value: undefined, done: false })
+
+class Range {
+  constructor(n) {
+    this.n = n
+  }
+  *[Symbol.iterator]() {
+    for (let i = 0; i < this.n; i++) yield i
+  }
+  static *of(a, b) {
+    yield a
+    yield b
+  }
+}
+assert([...new Range(3)], [0, 1, 2])
+assert([...Range.of('a', 'b')], ['a', 'b'])
+
+function* fib() {
+  let a = 0, b = 1
+  while (true) {
+    yield a;
+    [a, b] = [b, a + b]
+  }
+}
+values = []
+for (let x of fib()) {
+  if (x > 50) break
+  values.push(x)
+}
+assert(values, [0, 1, 1, 2, 3, 5, 8, 13, 21, 34])
+
+let running
+function* reentrant() {
+  running.next()
+}
+running = reentrant()
+let caught = false
+try {
+  running.next()
+} catch (e) {
+  caught = true
+}
+assert(caught, true)
+
+caught = false
+try {
+  new anonymous()
+} catch (e) {
+  caught = true
+}
+assert(caught, true)
+
+// Well-known symbols
+assert(typeof Symbol.iterator, 'symbol')
+assert(typeof Symbol.asyncIterator, 'symbol')
+assert(typeof Symbol.hasInstance, 'symbol')
+assert(typeof Symbol.toPrimitive, 'symbol')
+
+// arguments objects are iterable.
+function args() {
+  return [...arguments]
+}
+assert(args(1, 2, 3), [1, 2, 3])
+
+function argsLength() {
+  return arguments.length
+}
+assert(argsLength(4, 5), 2)
+
+function arrowArgs() {
+  let f = () => arguments[0]
+  return f('arrow')
+}
+assert(arrowArgs('outer'), 'outer')
+
+function* delegateArgs() {
+  yield* arguments
+}
+assert([...delegateArgs('a', 'b')], ['a', 'b'])
+
+values = []
+function forOfArgs() {
+  for (let x of arguments) values.push(x)
+}
+forOfArgs(1, 2)
+assert(values, [1, 2])
+
+// Generator and computed methods in object literals
+let iterable = {
+  *gen(n) {
+    yield n
+    yield n + 1
+  },
+  [Symbol.iterator]() {
+    return this.gen(1)
+  }
+}
+assert([...iterable.gen(5)], [5, 6])
+assert([...iterable], [1, 2])
@@ -0,0 +1,108 @@
+let assert = require('assert').deepStrictEqual
+
+// instanceof
+class Animal {}
+class Dog extends Animal {}
+let dog = new Dog()
+assert(dog instanceof Dog, true)
+assert(dog instanceof Animal, true)
+assert(dog instanceof Object, true)
+assert(new Animal() instanceof Dog, false)
+assert([] instanceof Array, true)
+assert([] instanceof Object, true)
+assert({} instanceof Array, false)
+assert(1 instanceof Object, false)
+assert('str' instanceof Object, false)
+assert(function() {} instanceof Function, true)
+assert(new Error('e') instanceof Error, true)
+assert(Promise.resolve(1) instanceof Promise, true)
+assert(/a/ instanceof RegExp, true)
+
+function Point(x) {
+  this.x = x
+}
+let p = new Point(1)
+assert(p instanceof Point, true)
+Point.prototype = {}
+assert(p instanceof Point, false)
+
+function* gen() {}
+assert(gen() instanceof gen, true)
+
+// Symbol.hasInstance
+class Even {
+  static [Symbol.hasInstance](n) {
+    return n % 2 === 0
+  }
+}
+assert(2 instanceof Even, true)
+assert(3 instanceof Even, false)
+
+let callable = {}
+callable[Symbol.hasInstance] = function(v) {
+  return v === 'yes'
+}
+assert('yes' instanceof callable, true)
+assert(Function.prototype[Symbol.hasInstance].call(Dog, dog), true)
+
+let caught = false
+try {
+  dog instanceof {}
+} catch (e) {
+  caught = true
+}
+assert(caught, true)
+
+caught = false
+try {
+  dog instanceof 1
+} catch (e) {
+  caught = true
+}
+assert(caught, true)
+
+// in
+let obj = { a: 1, b: undefined }
+assert('a' in obj, true)
+assert('b' in obj, true)
+assert('c' in obj, false)
+assert('constructor' in obj, true)
+assert('x' in dog, false)
+assert('constructor' in dog, true)
+
+let arr = [1, , 3]
+assert(0 in arr, true)
+assert(1 in arr, false)
+assert('2' in arr, true)
+assert(3 in arr, false)
+assert('length' in arr, true)
+assert('push' in arr, true)
+
+let sym = Symbol('s')
+let withSym = {}
+withSym[sym] = 1
+assert(sym in withSym, true)
+assert(Symbol.iterator in withSym, false)
+assert(Symbol.iterator in arr, true)
+
+let getterObj = {
+  get g() {
+    throw new Error('not called')
+  }
+}
+assert('g' in getterObj, true)
+assert('next' in gen(), true)
+
+caught = false
+try {
+  'a' in 'abc'
+} catch (e) {
+  caught = true
+}
+assert(caught, true)
+
+let keys = []
+for (let k in obj) keys.push(k)
+assert(keys, ['a', 'b'])
+assert(!('a' in obj), false)
+assert('a' in obj && 'b' in obj, true)
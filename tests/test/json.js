@@ -0,0 +1,61 @@
+let assert = require('assert').deepStrictEqual
+
+assert(JSON.parse('{"a": [1, 2.5, -3e2], "b": {"c": null}, "d": true}'), {
+  a: [1, 2.5, -300],
+  b: { c: null },
+  d: true
+})
+assert(JSON.parse(' "a\\n\\u0041\\"" '), 'a\nA"')
+assert(JSON.parse('[]'), [])
+assert(JSON.parse('{"a": 1, "a": 2}'), { a: 2 })
+
+let revived = JSON.parse('{"a": 1, "b": [2, 3], "c": 4}', function(key, val) {
+  if (key === 'c') return undefined
+  return typeof val === 'number' ? val * 10 : val
+})
+assert(revived, { a: 10, b: [20, 30] })
+
+let errors = 0
+let inputs = ['{', '[1,]', '{"a" 1}', '01', '"a', 'nul', '1 2', "'a'"]
+for (let input of inputs) {
+  try {
+    JSON.parse(input)
+  } catch (e) {
+    errors++
+  }
+}
+assert(errors, inputs.length)
+
+assert(JSON.stringify({ a: [1, 'x', null, true], b: { c: {} } }), '{"a":[1,"x",null,true],"b":{"c":{}}}')
+assert(JSON.stringify('a"\n'), '"a\\"\\n"')
+assert(JSON.stringify(undefined), undefined)
+assert(JSON.stringify(function() {}), undefined)
+assert(JSON.stringify([undefined, function() {}, NaN, Infinity]), '[null,null,null,null]')
+assert(JSON.stringify({ a: undefined, b: function() {}, c: 1 }), '{"c":1}')
+assert(JSON.stringify({ toJSON: function(key) { return 'k' + key } }), '"k"')
+assert(JSON.stringify({ a: { toJSON: function(key) { return key } } }), '{"a":"a"}')
+
+assert(JSON.stringify({ a: 1, b: 2, c: 3 }, ['c', 'a']), '{"c":3,"a":1}')
+assert(
+  JSON.stringify({ a: 1, b: 'x' }, function(key, val) {
+    return typeof val === 'number' ? val + 1 : val
+  }),
+  '{"a":2,"b":"x"}'
+)
+
+assert(JSON.stringify({ a: [1, 2], b: {} }, null, 2), '{\n  "a": [\n    1,\n    2\n  ],\n  "b": {}\n}')
+assert(JSON.stringify([1], null, '--'), '[\n--1\n]')
+
+let obj = { a: 1 }
+assert(JSON.parse(JSON.stringify(obj)), obj)
+
+assert(JSON.parse('"\\u0000"'), '\u0000')
+assert(JSON.parse('"a\\u0000b"').length, 3)
+
+let proto = JSON.parse('{"__proto__":1}')
+assert(Object.keys(proto), ['__proto__'])
+assert(proto.__proto__, 1)
+assert(Object.getPrototypeOf(proto) === Object.prototype, true)
+
+assert(JSON.stringify([new Number(3)]), '[3]')
+assert(JSON.stringify({ s: new String('s') }), '{"s":"s"}')
@@ -0,0 +1,106 @@
+let assert = require('assert').deepStrictEqual
+
+// Labeled break and continue of nested loops.
+let log = []
+outer: for (let i = 0; i < 3; i++) {
+  for (let j = 0; j < 3; j++) {
+    if (j === 1) continue outer
+    if (i === 2) break outer
+    log.push(i + '' + j)
+  }
+}
+assert(log, ['00', '10'])
+
+// Labeled blocks.
+log = []
+block: {
+  log.push('a')
+  if (log.length) break block
+  log.push('b')
+}
+assert(log, ['a'])
+
+// Several labels on the same loop, and for-of loops nested in each other.
+log = []
+a: b: for (const x of [1, 2, 3]) {
+  for (const y of [4, 5]) {
+    if (y === 5) continue a
+    if (x === 3) break b
+    log.push(x * y)
+  }
+}
+assert(log, [4, 8])
+
+// Break out of a switch nested in a loop.
+log = []
+loop: for (const x of [1, 2, 3]) {
+  switch (x) {
+    case 2:
+      break loop
+    default:
+      log.push(x)
+  }
+}
+assert(log, [1])
+
+// Jumps out of try and catch run the finally clauses.
+function f() {
+  let log = []
+  loop: while (true) {
+    try {
+      try {
+        log.push('try')
+        break loop
+      } finally {
+        log.push('inner finally')
+      }
+    } finally {
+      log.push('outer finally')
+    }
+  }
+  for (const x of [1, 2]) {
+    try {
+      if (x === 1) throw x
+      log.push(x)
+    } catch (e) {
+      continue
+    } finally {
+      log.push('finally ' + x)
+    }
+  }
+  return log
+}
+assert(f(), ['try', 'inner finally', 'outer finally', 'finally 1', 2, 'finally 2'])
+
+// Break and continue in finally blocks discard the pending completion.
+log = []
+for (;;) {
+  try {
+  } finally {
+    break
+  }
+}
+for (const x of [1, 2, 3]) {
+  try {
+    throw x
+  } finally {
+    log.push(x)
+    continue
+  }
+}
+assert(log, [1, 2, 3])
+function breakInFinally() {
+  outer: for (let i = 0; i < 2; i++) {
+    for (const y of [1]) {
+      try {
+        return 'returned'
+      } finally {
+        log.push(i)
+        break outer
+      }
+    }
+  }
+  return 'broke'
+}
+assert(breakInFinally(), 'broke')
+assert(log, [1, 2, 3, 0])
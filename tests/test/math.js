@@ -0,0 +1,91 @@
+let assert = require('assert').deepStrictEqual
+
+assert(Math.PI, 3.141592653589793)
+assert(Math.E, 2.718281828459045)
+assert(Math.SQRT2, 1.4142135623730951)
+assert(Math.LN2, 0.6931471805599453)
+Math.PI = 3
+assert(Math.PI, 3.141592653589793)
+
+assert(Math.floor(-1.5), -2)
+assert(Math.ceil(-0.5), -0)
+assert(Math.trunc(-1.5), -1)
+assert(Math.trunc('12.7'), 12)
+assert(Math.round(2.5), 3)
+assert(Math.round(-2.5), -2)
+assert(Math.round(-0.2), -0)
+assert(Math.round(0.49999999999999994), 0)
+assert(Math.round(NaN), NaN)
+assert(Math.sign(-3), -1)
+assert(Math.sign(-0), -0)
+assert(Math.sign('x'), NaN)
+
+assert(Math.abs(-2), 2)
+assert(Math.abs(null), 0)
+assert(Math.abs(), NaN)
+assert(Math.max(), -Infinity)
+assert(Math.min(), Infinity)
+assert(Math.max(1, 3, 2), 3)
+assert(Math.min(1, '0', 2), 0)
+assert(Math.max(1, NaN, 3), NaN)
+assert(Math.max(-0, 0), 0)
+assert(Math.min(0, -0), -0)
+assert(Math.hypot(), 0)
+assert(Math.hypot(3, 4), 5)
+assert(Math.hypot(NaN, Infinity), Infinity)
+assert(Math.hypot(3e200, 4e200) > 4.9e200, true)
+
+assert(Math.sqrt(16), 4)
+assert(Math.sqrt(-1), NaN)
+assert(Math.cbrt(-27), -3)
+assert(Math.pow(2, 10), 1024)
+assert(Math.pow(1, Infinity), NaN)
+assert(Math.pow(NaN, 0), 1)
+assert(2 ** 0.5, Math.SQRT2)
+assert((-1) ** Infinity, NaN)
+assert(Math.exp(0), 1)
+assert(Math.expm1(-0), -0)
+assert(Math.log(Math.E), 1)
+assert(Math.log(0), -Infinity)
+assert(Math.log1p(-1), -Infinity)
+assert(Math.log2(8), 3)
+assert(Math.log10(1000), 3)
+
+assert(Math.sin(0), 0)
+assert(Math.cos(0), 1)
+assert(Math.tan(-0), -0)
+assert(Math.asin(2), NaN)
+assert(Math.acos(1), 0)
+assert(Math.atan(Infinity), Math.PI / 2)
+assert(Math.atan2(1, 1), Math.PI / 4)
+assert(Math.atan2(-0, 1), -0)
+assert(Math.sinh(0), 0)
+assert(Math.cosh(0), 1)
+assert(Math.tanh(Infinity), 1)
+assert(Math.asinh(-0), -0)
+assert(Math.acosh(1), 0)
+assert(Math.atanh(1), Infinity)
+
+assert(Math.clz32(1), 31)
+assert(Math.clz32(0), 32)
+assert(Math.clz32(-1), 0)
+assert(Math.clz32(4294967296), 32)
+assert(Math.imul(3, 4), 12)
+assert(Math.imul(0xffffffff, 5), -5)
+assert(Math.imul(0x7fffffff, 2), -2)
+assert(Math.fround(5.5), 5.5)
+assert(Math.fround(5.05), 5.050000190734863)
+assert(Math.fround(2 ** 128), Infinity)
+
+assert(-1 >>> 0, 4294967295)
+assert(4294967296 | 0, 0)
+assert(2147483648 | 0, -2147483648)
+
+let r = Math.random()
+assert(r >= 0 && r < 1, true)
+
+// NaN from arithmetic
+let zero = 0
+assert(zero / zero, NaN)
+assert(Infinity - Infinity, NaN)
+assert(Infinity * zero, NaN)
@@ -0,0 +1,4 @@
+export default function greet(name) {
+  return 'hello, ' + name
+}
+export let name = 'lib'
@@ -0,0 +1,58 @@
+let assert = require('assert').deepStrictEqual
+
+import { count, increment, PI as pi } from './counter.mjs'
+import greet, * as lib from './lib.mjs'
+import answer from './default_expr.mjs'
+import anonymous from './anonymous.mjs'
+import { even } from './even.mjs'
+import { odd } from './odd.mjs'
+import * as all from './reexport.mjs'
+
+assert(count, 0)
+increment()
+assert(count, 1)
+assert(pi, 3.14)
+
+assert(greet('a'), 'hello, a')
+assert(lib.default === greet, true)
+assert(lib.name, 'lib')
+assert(answer, 42)
+assert(anonymous(), 1)
+assert(anonymous.name, 'default')
+
+assert(even(10), true)
+assert(odd(10), false)
+
+assert(all.count, 1)
+assert(all.counter.count, 1)
+assert(all.greeting('b'), 'hello, b')
+assert(all.pi, 3.14)
+assert(all.PI, 3.14)
+assert('default' in all, false)
+
+assert(this, undefined)
+
+let error = 0
+try {
+  count = 2
+} catch (e) {
+  error++
+}
+try {
+  all.count = 2
+} catch (e) {
+  error++
+}
+assert(error, 2)
+assert(count, 1)
+
+import('./lib.mjs').then(function(ns) {
+  assert(ns === lib, true)
+})
+import('./missing.mjs')
+  .catch(function(e) {
+    error++
+  })
+  .then(function() {
+    assert(error, 3)
+  })
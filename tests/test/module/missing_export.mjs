@@ -0,0 +1 @@
+import { missing } from './counter.mjs'
@@ -0,0 +1,5 @@
+import { PI } from './counter.mjs'
+export * from './counter.mjs'
+export * as counter from './counter.mjs'
+export { default as greeting, name } from './lib.mjs'
+export { PI as pi }
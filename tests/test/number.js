@@ -0,0 +1,98 @@
+let assert = require('assert').deepStrictEqual
+
+// Number()
+assert(Number('42'), 42)
+assert(Number(' 0x10 '), 16)
+assert(Number('0b101'), 5)
+assert(Number('0o17'), 15)
+assert(Number('1e3'), 1000)
+assert(Number(''), 0)
+assert(Number('-Infinity'), -Infinity)
+assert(Number('inf'), NaN)
+assert(Number('12px'), NaN)
+assert(Number(true), 1)
+assert(Number(null), 0)
+assert(Number(undefined), NaN)
+assert(Number(), 0)
+
+// Number objects
+let n = new Number(3)
+assert(typeof n, 'object')
+assert(n.valueOf(), 3)
+assert(n + 1, 4)
+assert(n.toString(), '3')
+
+// Constants
+assert(Number.MAX_SAFE_INTEGER, 9007199254740991)
+assert(Number.MIN_SAFE_INTEGER, -9007199254740991)
+assert(Number.EPSILON, 2.220446049250313e-16)
+assert(Number.MAX_VALUE, 1.7976931348623157e+308)
+assert(Number.MIN_VALUE, 5e-324)
+assert(Number.POSITIVE_INFINITY, Infinity)
+assert(Number.NEGATIVE_INFINITY, -Infinity)
+assert(Number.isNaN(Number.NaN), true)
+
+// Static predicates
+assert(Number.isInteger(5), true)
+assert(Number.isInteger(5.5), false)
+assert(Number.isInteger('5'), false)
+assert(Number.isSafeInteger(9007199254740992), false)
+assert(Number.isSafeInteger(9007199254740991), true)
+assert(Number.isFinite(Infinity), false)
+assert(Number.isFinite('1'), false)
+assert(Number.isNaN('abc'), false)
+assert(isNaN('abc'), true)
+assert(isFinite('12'), true)
+assert(Number.parseInt === parseInt, true)
+assert(Number.parseFloat === parseFloat, true)
+
+// parseInt / parseFloat
+assert(parseInt('  42px'), 42)
+assert(parseInt('-0x1F'), -31)
+assert(parseInt('ff', 16), 255)
+assert(parseInt('101', 2), 5)
+assert(parseInt('z', 37), NaN)
+assert(parseInt('xyz'), NaN)
+assert(parseFloat('3.14abc'), 3.14)
+assert(parseFloat('.5'), 0.5)
+assert(parseFloat('-Infinityx'), -Infinity)
+assert(parseFloat('1e-3'), 0.001)
+
+// Shortest round-trip formatting
+assert('' + (0.1 + 0.2), '0.30000000000000004')
+assert('' + 1e21, '1e+21')
+assert('' + 1e20, '100000000000000000000')
+assert('' + 1e-7, '1e-7')
+assert('' + 0.000001, '0.000001')
+assert('' + 123456789012345680000, '123456789012345680000')
+assert('' + -0, '0')
+assert('' + 1.5e300, '1.5e+300')
+
+// toString(radix)
+assert((255).toString(16), 'ff')
+assert((255).toString(2), '11111111')
+assert((-255).toString(36), '-73')
+assert((0.5).toString(2), '0.1')
+assert((3.75).toString(8), '3.6')
+
+// toFixed
+assert((1.005).toFixed(2), '1.00')
+assert((1.45).toFixed(1), '1.4')
+assert((0.5).toFixed(0), '1')
+assert((123.456).toFixed(), '123')
+assert((-1.5).toFixed(0), '-2')
+assert((1e21).toFixed(2), '1e+21')
+
+// toPrecision
+assert((123.456).toPrecision(4), '123.5')
+assert((0.000123).toPrecision(2), '0.00012')
+assert((123456).toPrecision(2), '1.2e+5')
+assert((1.5).toPrecision(), '1.5')
+
+// toExponential
+assert((123456).toExponential(2), '1.23e+5')
+assert((0.00015).toExponential(1), '1.5e-4')
+assert((5).toExponential(), '5e+0')
+assert((1.25).toExponential(), '1.25e+0')
+
+assert((1234.5).toLocaleString === undefined, false)
@@ -0,0 +1,172 @@
+let assert = require('assert').deepStrictEqual
+
+// constructor
+assert(typeof Object(), 'object')
+assert(typeof new Object(null), 'object')
+assert(typeof Object(1), 'object')
+assert(Object('ab').length, 2)
+let o = { a: 1 }
+assert(Object(o) === o, true)
+
+// keys / values / entries
+o = { a: 1, b: 'x', c: true }
+assert(Object.keys(o), ['a', 'b', 'c'])
+assert(Object.values(o), [1, 'x', true])
+assert(Object.entries(o), [['a', 1], ['b', 'x'], ['c', true]])
+assert(Object.keys([5, 6]), ['0', '1'])
+assert(Object.keys('ab'), ['0', '1'])
+
+// assign
+let target = { a: 1 }
+let assigned = Object.assign(target, { b: 2 }, null, { a: 3 })
+assert(assigned === target, true)
+assert(target, { a: 3, b: 2 })
+let sym = Symbol('s')
+let hidden = Symbol('hidden')
+let symSource = {}
+symSource[sym] = 'symbol'
+Object.defineProperty(symSource, hidden, { value: 'hidden', enumerable: false })
+let symTarget = Object.assign({}, symSource)
+assert(symTarget[sym], 'symbol')
+assert(symTarget[hidden], undefined)
+assert(Object.getOwnPropertySymbols(symTarget).length, 1)
+
+// create
+let proto = { greet: function() { return 'hi ' + this.name } }
+let created = Object.create(proto)
+created.name = 'bob'
+assert(created.greet(), 'hi bob')
+assert(Object.getPrototypeOf(created) === proto, true)
+let bare = Object.create(null)
+assert(Object.getPrototypeOf(bare), null)
+let withProps = Object.create(proto, { x: { value: 1, enumerable: true } })
+assert(withProps.x, 1)
+assert(Object.keys(withProps), ['x'])
+
+// defineProperty / getOwnPropertyDescriptor
+let d = {}
+Object.defineProperty(d, 'x', { value: 42 })
+assert(d.x, 42)
+assert(Object.keys(d), [])
+assert(Object.getOwnPropertyDescriptor(d, 'x'), {
+  value: 42, writable: false, enumerable: false, configurable: false })
+d.x = 1
+assert(d.x, 42)
+Object.defineProperty(d, 'y', { get: function() { return this.x + 1 }, enumerable: true, configurable: true })
+assert(d.y, 43)
+let yDesc = Object.getOwnPropertyDescriptor(d, 'y')
+assert(typeof yDesc.get, 'function')
+assert(yDesc.set, undefined)
+assert(yDesc.enumerable, true)
+assert(Object.getOwnPropertyDescriptor(d, 'z'), undefined)
+let protoDesc = Object.getOwnPropertyDescriptor(Array, 'prototype')
+assert(protoDesc.value === Array.prototype, true)
+assert([protoDesc.writable, protoDesc.enumerable, protoDesc.configurable], [false, false, false])
+assert(Object.getOwnPropertyDescriptor([1], 'length'), {
+  value: 1, writable: true, enumerable: false, configurable: false })
+Object.defineProperty(d, 'x', { value: 42 })
+
+Object.defineProperties(d, { p: { value: 'p', enumerable: true }, q: { value: 'q' } })
+assert(Object.keys(d), ['y', 'p'])
+let descs = Object.getOwnPropertyDescriptors({ a: 1 })
+assert(descs.a, { value: 1, writable: true, enumerable: true, configurable: true })
+
+// getOwnPropertyNames / getOwnPropertySymbols
+assert(Object.getOwnPropertyNames(d), ['x', 'y', 'p', 'q'])
+assert(Object.getOwnPropertyNames(['a']), ['0', 'length'])
+let s1 = Symbol('s1')
+let s2 = Symbol('s2')
+let withSyms = { a: 1 }
+withSyms[s1] = 1
+withSyms[s2] = 2
+let syms = Object.getOwnPropertySymbols(withSyms)
+assert(syms.length, 2)
+assert(syms[0] === s1, true)
+assert(syms[1] === s2, true)
+
+// freeze / seal / preventExtensions
+let f = { a: 1 }
+assert(Object.isFrozen(f), false)
+assert(Object.freeze(f) === f, true)
+f.a = 2
+f.b = 3
+assert(f, { a: 1 })
+assert(Object.isFrozen(f), true)
+assert(Object.isSealed(f), true)
+assert(Object.isExtensible(f), false)
+
+let sealed = Object.seal({ a: 1 })
+sealed.a = 2
+sealed.b = 3
+assert(sealed, { a: 2 })
+assert(Object.isSealed(sealed), true)
+assert(Object.isFrozen(sealed), false)
+
+let ne = Object.preventExtensions({ a: 1 })
+ne.b = 1
+assert(Object.keys(ne), ['a'])
+assert(Object.isExtensible(ne), false)
+assert(Object.isSealed(ne), false)
+assert(Object.isFrozen(Object.preventExtensions({})), true)
+assert(Object.isFrozen(1), true)
+
+let frozenAry = Object.freeze([1, 2])
+frozenAry[0] = 5
+frozenAry[2] = 5
+assert(frozenAry, [1, 2])
+let pushError = false
+try {
+  frozenAry.push(3)
+} catch (e) {
+  pushError = true
+}
+assert(pushError, true)
+frozenAry.length = 0
+assert(frozenAry.length, 2)
+assert(Object.getOwnPropertyDescriptor(frozenAry, 'length').writable, false)
+assert(Object.isFrozen(Object.seal([1])), false)
+
+// setPrototypeOf
+let child = {}
+assert(Object.setPrototypeOf(child, proto) === child, true)
+child.name = 'amy'
+assert(child.greet(), 'hi amy')
+let cyclic = false
+try {
+  Object.setPrototypeOf(proto, child)
+} catch (e) {
+  cyclic = true
+}
+assert(cyclic, true)
+
+// fromEntries
+assert(Object.fromEntries([['a', 1], ['b', 2]]), { a: 1, b: 2 })
+assert(Object.fromEntries(Object.entries({ x: 'y' })), { x: 'y' })
+
+// is
+assert(Object.is(NaN, NaN), true)
+assert(Object.is(0, -0), false)
+assert(Object.is('a', 'a'), true)
+assert(Object.is({}, {}), false)
+
+// Object.prototype
+let h = { a: 1 }
+assert(h.hasOwnProperty('a'), true)
+assert(h.hasOwnProperty('hasOwnProperty'), false)
+assert([1].hasOwnProperty(0), true)
+assert([1].hasOwnProperty('length'), true)
+assert(Object.prototype.isPrototypeOf(h), true)
+assert(proto.isPrototypeOf(created), true)
+assert(created.isPrototypeOf(proto), false)
+assert(h.propertyIsEnumerable('a'), true)
+assert([].propertyIsEnumerable('length'), false)
+assert(h.toString(), '[object Object]')
+assert(Object.prototype.toString.call([]), '[object Array]')
+assert(Object.prototype.toString.call(null), '[object Null]')
+assert(Object.prototype.toString.call(undefined), '[object Undefined]')
+assert(Object.prototype.toString.call(1), '[object Number]')
+assert(Object.prototype.toString.call('s'), '[object String]')
+assert(Object.prototype.toString.call(true), '[object Boolean]')
+assert(Object.prototype.toString.call(function() {}), '[object Function]')
+assert(Object.prototype.toString.call(new Date(0)), '[object Date]')
+assert(h.valueOf() === h, true)
@@ -0,0 +1,124 @@
+let assert = require('assert').deepStrictEqual
+
+let log = []
+
+let p = new Promise(function(resolve, reject) {
+  log.push('executor')
+  resolve(1)
+  resolve(2)
+  reject(3)
+})
+log.push('after new')
+
+p.then(function(x) {
+  log.push('then ' + x)
+  return x + 1
+})
+  .then(function(x) {
+    log.push('chained ' + x)
+    throw new Error('oops')
+  })
+  .then(function() {
+    log.push('skipped')
+  })
+  .catch(function(e) {
+    log.push('caught ' + e.message)
+    return 'recovered'
+  })
+  .finally(function() {
+    log.push('finally')
+    return 'ignored'
+  })
+  .then(function(x) {
+    log.push('after finally ' + x)
+  })
+
+Promise.resolve('a').then(function(x) {
+  log.push('resolve ' + x)
+})
+Promise.reject('b').then(undefined, function(x) {
+  log.push('reject ' + x)
+})
+
+let thenable = {
+  then: function(resolve) {
+    resolve('thenable')
+  }
+}
+Promise.resolve(thenable).then(function(x) {
+  log.push(x)
+})
+
+let resolved = Promise.resolve(7)
+assert(Promise.resolve(resolved) === resolved, true)
+assert(typeof Promise.prototype.then, 'function')
+
+let cycle = new Promise(function(resolve) {
+  Promise.resolve().then(function() {
+    resolve(cycle)
+  })
+})
+cycle.catch(function(e) {
+  log.push('cycle ' + typeof e.message)
+})
+
+Promise.all([1, Promise.resolve(2), new Promise(function(resolve) { resolve(3) })]).then(
+  function(values) {
+    assert(values, [1, 2, 3])
+  }
+)
+Promise.all([]).then(function(values) {
+  assert(values, [])
+})
+Promise.all([Promise.resolve(1), Promise.reject('no')]).catch(function(reason) {
+  assert(reason, 'no')
+})
+Promise.allSettled([Promise.resolve(1), Promise.reject(2)]).then(function(results) {
+  assert(results, [{ status: 'fulfilled', value: 1 }, { status: 'rejected', reason: 2 }])
+})
+Promise.race([new Promise(function() {}), Promise.resolve('fast')]).then(function(x) {
+  assert(x, 'fast')
+})
+Promise.any([Promise.reject(1), Promise.resolve(2)]).then(function(x) {
+  assert(x, 2)
+})
+Promise.any([Promise.reject(1), Promise.reject(2)]).catch(function(e) {
+  assert(e instanceof AggregateError, true)
+  assert(e.message, 'All promises were rejected')
+  assert(e.errors, [1, 2])
+  assert(Object.keys(e), [])
+})
+Promise.any([]).catch(function(e) {
+  assert(e instanceof AggregateError, true)
+  assert(e.errors, [])
+})
+
+new Promise(function() {
+  throw 'thrown in executor'
+}).catch(function(e) {
+  log.push(e)
+})
+
+assert(log, ['executor', 'after new'])
+
+let done = false
+let check = Promise.resolve()
+for (let i = 0; i < 10; i++) {
+  check = check.then(function() {})
+}
+check.then(function() {
+  assert(log, [
+    'executor',
+    'after new',
+    'then 1',
+    'resolve a',
+    'reject b',
+    'thrown in executor',
+    'chained 2',
+    'thenable',
+    'cycle string',
+    'caught oops',
+    'finally',
+    'after finally recovered'])
+  done = true
+})
@@ -0,0 +1,82 @@
+let assert = require('assert').deepStrictEqual
+
+let re = /(\d+)-(\d+)/g
+assert(re.source, '(\\d+)-(\\d+)')
+assert(re.flags, 'g')
+assert(re.global, true)
+assert(re.sticky, false)
+assert(re.toString(), '/(\\d+)-(\\d+)/g')
+assert(new RegExp('a/b').toString(), '/a\\/b/')
+assert(new RegExp('').toString(), '/(?:)/')
+assert(new RegExp(re, 'i').flags, 'i')
+
+let m = re.exec('1-2 34-56')
+assert([...m], ['1-2', '1', '2'])
+assert(m.index, 0)
+assert(m.input, '1-2 34-56')
+assert(m.groups, undefined)
+assert(re.lastIndex, 3)
+m = re.exec('1-2 34-56')
+assert([...m], ['34-56', '34', '56'])
+assert(m.index, 4)
+assert(re.exec('1-2 34-56'), null)
+assert(re.lastIndex, 0)
+
+let sticky = /a/y
+assert(sticky.test('aab'), true)
+assert(sticky.test('aab'), true)
+assert(sticky.test('aab'), false)
+assert(sticky.lastIndex, 0)
+
+assert(/^abc$/i.test('ABC'), true)
+assert(/^b/m.test('a\nb'), true)
+assert(/a.c/.test('a\nc'), false)
+assert(/a.c/s.test('a\nc'), true)
+assert(/^.$/u.test('😀'), true)
+assert(/^.$/.test('😀'), false)
+
+let date = /(?<year>\d{4})-(?<month>\d{2})/.exec('on 2019-04')
+assert(date.groups.year, '2019')
+assert(date.groups.month, '04')
+assert(/(?<=\$)\d+/.exec('cost: $42')[0], '42')
+assert(/(?<!\$)\b\d+/.exec('$4 and 2')[0], '2')
+assert(/(a)\1/.test('aa'), true)
+
+assert('a1b22c333'.match(/\d+/g), ['1', '22', '333'])
+assert('abc'.match(/x/), null)
+assert('abc'.match('b').index, 1)
+let all = []
+for (let m of 'a1b22'.matchAll(/[a-z](\d+)/g)) all.push(m[1])
+assert(all, ['1', '22'])
+
+assert('aaa'.replace(/a/g, 'b'), 'bbb')
+assert('aaa'.replace(/a/, 'b'), 'baa')
+assert('aaa'.replace('a', 'b'), 'baa')
+assert('john smith'.replace(/(\w+)\s(\w+)/, '$2, $1'), 'smith, john')
+assert('2019-04'.replace(/(?<y>\d+)-(?<m>\d+)/, '$<m>/$<y>'), '04/2019')
+assert('abc'.replace(/b/, "[$`|$&|$'|$$]"), 'a[a|b|c|$]c')
+assert('a1b2'.replace(/\d/g, function(d, i) { return d + d + ':' + i }), 'a11:1b22:3')
+
+assert('hello world'.search(/o/), 4)
+assert('hello'.search(/x/), -1)
+
+assert('a1b2c3'.split(/\d/), ['a', 'b', 'c', ''])
+assert('a1b2c3'.split(/(\d)/, 3), ['a', '1', 'b'])
+assert('abc'.split(''), ['a', 'b', 'c'])
+assert('a,b,c'.split(',', 2), ['a', 'b'])
+assert(''.split(/,/), [''])
+
+let custom = {}
+custom[Symbol.replace] = function(s, r) { return s + r }
+assert('x'.replace(custom, 'y'), 'xy')
+
+// Long subjects don't exhaust the stack
+let long = 'ab'.repeat(10000)
+assert(/(?:a|b)*/.exec(long)[0].length, 20000)
+assert(/^(?:ab)+$/.test(long), true)
+assert(/(a)+/.exec('a'.repeat(50000))[1], 'a')
+assert(/(a|b)*?c/.exec(long + 'c')[1], 'b')
+assert(long.replace(/a/g, '').length, 10000)
+assert(long.split(/b/).length, 10001)
+let execResult = /b/.exec(long)
+assert(execResult.input === long, true)
@@ -0,0 +1,48 @@
+let assert = require('assert').deepStrictEqual
+
+let counter = require('./require/counter')
+counter.countup(5)
+assert(require('./require/counter.js') === counter, true)
+assert(require('./require/counter').count(), 5)
+
+let file = require.resolve('./require/counter')
+assert(require.cache[file].exports === counter, true)
+assert(require.cache[file].loaded, true)
+assert(require.cache[file].id, file)
+delete require.cache[file]
+assert(require('./require/counter') === counter, false)
+assert(require('./require/counter').count(), 0)
+
+assert(require('./require/data.json'), { name: 'data', values: [1, 2] })
+assert(require('./require/dir'), { name: 'dir', single: 'single' })
+assert(require('./require/packages'), { pkg: { name: 'pkg' }, single: 'single' })
+
+let a = require('./require/cycle_a')
+assert(a.b_saw_a_done, false)
+assert(a.done, true)
+
+let paths = require('./require/paths')
+assert(paths.filename, require.resolve('./require/paths.js'))
+assert(paths.dirname + '/paths.js', paths.filename)
+assert(paths.this_is_exports, true)
+
+let error = 0
+try {
+  require('./require/missing')
+} catch (e) {
+  error++
+}
+try {
+  require.resolve('missing')
+} catch (e) {
+  error++
+}
+assert(error, 2)
+
+let syntaxError = null
+try {
+  require('./require/syntax_error')
+} catch (e) {
+  syntaxError = e
+}
+assert(syntaxError instanceof SyntaxError, true)
@@ -0,0 +1,9 @@
+let count = 0
+module.exports = {
+  countup: x => {
+    count += x
+  },
+  count: () => {
+    return count
+  }
+}
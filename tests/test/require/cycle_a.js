@@ -0,0 +1,4 @@
+exports.done = false
+let b = require('./cycle_b')
+exports.b_saw_a_done = b.a_done
+exports.done = true
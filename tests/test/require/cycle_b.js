@@ -0,0 +1,2 @@
+let a = require('./cycle_a')
+exports.a_done = a.done
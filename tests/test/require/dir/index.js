@@ -0,0 +1,2 @@
+exports.name = 'dir'
+exports.single = require('single')
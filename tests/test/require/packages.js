@@ -0,0 +1,2 @@
+exports.pkg = require('pkg')
+exports.single = require('single')
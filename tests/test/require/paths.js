@@ -0,0 +1,3 @@
+exports.filename = __filename
+exports.dirname = __dirname
+exports.this_is_exports = this === module.exports
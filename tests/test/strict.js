@@ -0,0 +1,166 @@
+let assert = require('assert').deepStrictEqual
+
+function throws(f) {
+  try {
+    f()
+  } catch (e) {
+    return true
+  }
+  return false
+}
+
+// Assignment to undeclared identifiers
+function sloppyAssign() {
+  sloppyGlobal = 1
+}
+sloppyAssign()
+assert(sloppyGlobal, 1)
+
+function strictAssign() {
+  'use strict'
+  strictGlobal = 1
+}
+assert(throws(strictAssign), true)
+assert(throws(() => strictGlobal), true)
+
+function strictAssignDeclared() {
+  'use strict'
+  sloppyGlobal = 2
+  let local
+  local = 3
+  return local
+}
+assert(strictAssignDeclared(), 3)
+assert(sloppyGlobal, 2)
+
+assert(throws(function() {
+  'use strict'
+  undefined = 1
+}), true)
+assert(throws(function() {
+  undefined = 1
+}), false)
+
+// Nested functions and arrow functions inherit strictness.
+function outer() {
+  'use strict'
+  return function() {
+    nestedGlobal = 1
+  }
+}
+assert(throws(outer()), true)
+
+function outerArrow() {
+  'use strict'
+  return () => {
+    arrowGlobal = 1
+  }
+}
+assert(throws(outerArrow()), true)
+
+// Other string literals may precede the directive.
+function laterDirective() {
+  'another directive'
+  'use strict'
+  laterGlobal = 1
+}
+assert(throws(laterDirective), true)
+
+// A string after another statement is not a directive.
+function notDirective() {
+  let x = 0
+  'use strict'
+  notDirectiveGlobal = 1
+}
+notDirective()
+assert(notDirectiveGlobal, 1)
+
+// Assignment to non-writable properties
+let arr = [1, 2]
+function strictArrayLength() {
+  'use strict'
+  arr.length = 1
+  return arr
+}
+assert(strictArrayLength(), [1])
+
+let withGetter = {
+  get only() {
+    return 1
+  }
+}
+assert(throws(function() {
+  withGetter.only = 2
+}), false)
+assert(throws(function() {
+  'use strict'
+  withGetter.only = 2
+}), true)
+assert(withGetter.only, 1)
+
+assert(throws(function() {
+  'use strict'
+  Function.prototype[Symbol.hasInstance] = null
+}), true)
+assert(throws(function() {
+  'use strict'
+  'str'.prop = 1
+}), true)
+assert(throws(function() {
+  'str'.prop = 1
+}), false)
+
+// Deleting non-configurable properties
+assert(throws(function() {
+  'use strict'
+  delete arr.length
+}), true)
+assert(throws(function() {
+  'use strict'
+  return delete arr[0]
+}), false)
+assert(arr.length, 1)
+assert(0 in arr, false)
+
+// this is undefined for plain calls in strict mode code.
+function strictThis() {
+  'use strict'
+  return this
+}
+assert(strictThis(), undefined)
+
+function sloppyThis() {
+  return this
+}
+assert(typeof sloppyThis(), 'object')
+assert(sloppyThis() === undefined, false)
+
+let obj = { strictThis: strictThis, sloppyThis: sloppyThis }
+assert(obj.strictThis() === obj, true)
+assert(obj.sloppyThis() === obj, true)
+
+function methodCallsPlain() {
+  return strictThis()
+}
+obj.methodCalls = methodCallsPlain
+assert(obj.methodCalls(), undefined)
+
+// Class code is always strict.
+class Klass {
+  assignUndeclared() {
+    classGlobal = 1
+  }
+  static plainThis() {
+    return strictThis()
+  }
+  callMethod() {
+    let m = this.getThis
+    return m()
+  }
+  getThis() {
+    return this
+  }
+}
+assert(throws(() => new Klass().assignUndeclared()), true)
+assert(Klass.plainThis(), undefined)
+assert(new Klass().callMethod(), undefined)
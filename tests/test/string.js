@@ -0,0 +1,151 @@
+let assert = require('assert').deepStrictEqual
+
+// Escape sequences
+assert("\u00e9t\u00e9", 'été')
+assert("\x41g", 'Ag')
+assert('\u{1F600}!', '😀!')
+assert('\uD83D\uDE00'.length, 2)
+
+// Indices are in UTF-16 code units
+let s = 'aあ😀b'
+assert(s.length, 5)
+assert(s[1], 'あ')
+assert(s[4], 'b')
+assert(s[5], undefined)
+assert(s.charAt(1), 'あ')
+assert(s.charAt(9), '')
+assert(s.charCodeAt(1), 0x3042)
+assert(s.charCodeAt(2), 0xd83d)
+assert(s.charCodeAt(-1), NaN)
+assert(s.codePointAt(2), 0x1f600)
+assert(s.codePointAt(3), 0xde00)
+assert(s.codePointAt(5), undefined)
+assert(s.indexOf('b'), 4)
+assert(s.indexOf('b', 2), 4)
+assert(s.indexOf('a', 1), -1)
+assert(s.indexOf('', 10), 5)
+assert('あい'.indexOf('い', 1), 1)
+assert(s.lastIndexOf('b'), 4)
+assert('canal'.lastIndexOf('a'), 3)
+assert('canal'.lastIndexOf('a', 2), 1)
+assert('canal'.lastIndexOf('a', 0), -1)
+assert('canal'.lastIndexOf(''), 5)
+
+// slice / substring
+assert(s.slice(1, 4), 'あ😀')
+assert('hello'.slice(-3), 'llo')
+assert('hello'.slice(1, -1), 'ell')
+assert('hello'.slice(3, 1), '')
+assert('hello'.substring(3, 1), 'el')
+assert('hello'.substring(-2, 2), 'he')
+assert('hello'.substring(2), 'llo')
+
+// Case
+assert('Hello, World'.toUpperCase(), 'HELLO, WORLD')
+assert('ÀÉÎ'.toLowerCase(), 'àéî')
+assert('straße'.toUpperCase(), 'STRASSE')
+
+// trim
+assert('  \t\n hi ﻿'.trim(), 'hi')
+assert('  hi  '.trimStart(), 'hi  ')
+assert('  hi  '.trimEnd(), '  hi')
+assert(String.prototype.trimLeft === String.prototype.trimStart, true)
+assert(String.prototype.trimRight === String.prototype.trimEnd, true)
+
+// pad / repeat
+assert('5'.padStart(3, '0'), '005')
+assert('abc'.padStart(10, '123'), '1231231abc')
+assert('abc'.padEnd(6), 'abc   ')
+assert('abc'.padEnd(2), 'abc')
+assert('abc'.padEnd(6, ''), 'abc')
+assert('ab'.repeat(3), 'ababab')
+assert('ab'.repeat(0), '')
+
+// startsWith / endsWith / includes
+assert('Hello'.startsWith('He'), true)
+assert('Hello'.startsWith('l', 2), true)
+assert('Hello'.endsWith('lo'), true)
+assert('Hello'.endsWith('l', 4), true)
+assert('Hello'.endsWith('Hello!'), false)
+assert('Hello'.includes('ell'), true)
+assert('Hello'.includes('ell', 2), false)
+
+// concat
+assert('a'.concat('b', 1, true), 'ab1true')
+
+// replace / replaceAll
+assert('a-b-c'.replace('-', '+'), 'a+b-c')
+assert('a-b-c'.replaceAll('-', '+'), 'a+b+c')
+assert('aaa'.replaceAll('a', '$&$&'), 'aaaaaa')
+assert('abc'.replaceAll('', '.'), '.a.b.c.')
+assert('a-b'.replaceAll('-', function(m, p) { return '[' + p + ']' }), 'a[1]b')
+assert('1a2a'.replaceAll(/a/g, 'b'), '1b2b')
+
+// localeCompare
+assert('a'.localeCompare('b'), -1)
+assert('b'.localeCompare('a'), 1)
+assert('a'.localeCompare('a'), 0)
+assert('a'.localeCompare('B'), -1)
+assert('a'.localeCompare('A'), -1)
+assert('é'.localeCompare('f'), -1)
+assert('e'.localeCompare('é'), -1)
+
+// normalize
+let decomposed = 'é'
+assert(decomposed.length, 2)
+assert(decomposed.normalize().length, 1)
+assert(decomposed.normalize() === 'é', true)
+assert('é'.normalize('NFD') === decomposed, true)
+assert('ﬁ'.normalize('NFKC'), 'fi')
+
+// String and its statics
+assert(String(123), '123')
+assert(String(null), 'null')
+assert(String(), '')
+assert(String(Symbol('x')), 'Symbol(x)')
+assert(String.fromCharCode(72, 105), 'Hi')
+assert(String.fromCharCode(0x10041), 'A')
+assert(String.fromCodePoint(0x1f600).length, 2)
+assert(String.fromCodePoint(0x1f600) === '😀', true)
+// Code unit 0
+assert(String.fromCharCode(0).length, 1)
+assert(String.fromCharCode(0).charCodeAt(0), 0)
+let withNul = String.fromCharCode(97, 0, 98)
+assert(withNul.length, 3)
+assert(withNul === 'a\0b', true)
+assert(withNul.split('\0'), ['a', 'b'])
+assert(String.fromCodePoint(0, 65), '\0A')
+assert(String.raw({ raw: ['a', 'b', 'c'] }, 1, 2), 'a1b2c')
+assert(String.raw({ raw: 'xyz' }, 0, 1), 'x0y1z')
+
+// String objects
+let o = new String('ab')
+assert(typeof o, 'object')
+assert(o.length, 2)
+assert(o[1], 'b')
+assert(o.valueOf(), 'ab')
+assert(o.toString(), 'ab')
+assert(o + 'c', 'abc')
+assert(o.toUpperCase(), 'AB')
+assert('x'.valueOf(), 'x')
+
+// Lone surrogates are kept
+let lead = 'a😀'.charAt(1)
+assert(lead.charCodeAt(0), 0xd83d)
+assert(lead.length, 1)
+assert('a😀'.split('').join(''), 'a😀')
+assert('a😀'.split('').length, 3)
+assert(String.fromCharCode(0xd83d) + String.fromCharCode(0xde00), '😀')
+assert(lead + '😀'.charAt(1) === '😀', true)
+assert(lead === '😀'.charAt(1), false)
+assert(lead === '😀'.slice(0, 1), true)
+assert(lead < '😀'.charAt(1), true)
+assert(['x', lead].join('').charCodeAt(1), 0xd83d)
+assert(''.concat(lead, '😀'.charAt(1)), '😀')
+assert(lead.repeat(2).charCodeAt(1), 0xd83d)
+assert((' ' + lead + ' ').trim().charCodeAt(0), 0xd83d)
+assert(('a' + lead).toUpperCase().charCodeAt(1), 0xd83d)
+assert(String(lead).charCodeAt(0), 0xd83d)
+assert([...('b' + lead)].length, 2)
+assert([...'😀b'], ['😀', 'b'])
+assert(JSON.parse('"\\ud83d"').charCodeAt(0), 0xd83d)
@@ -0,0 +1,87 @@
+let assert = require('assert').deepStrictEqual
+
+function classify(x) {
+  switch (x) {
+    case 1:
+      return 'one'
+    case 2:
+    case 3:
+      return 'two or three'
+    case '1':
+      return 'string one'
+    default:
+      return 'other'
+  }
+}
+assert(classify(1), 'one')
+assert(classify(2), 'two or three')
+assert(classify(3), 'two or three')
+assert(classify('1'), 'string one')
+assert(classify(4), 'other')
+
+// fallthrough and break
+let log = []
+for (let i = 0; i < 4; i++) {
+  switch (i) {
+    case 0:
+      log.push('a')
+    case 1:
+      log.push('b')
+      break
+    default:
+      log.push('c')
+    case 2:
+      log.push('d')
+  }
+}
+assert(log, ['a', 'b', 'b', 'd', 'c', 'd'])
+
+// no matching case and no default
+let hit = false
+switch (10) {
+  case 1:
+    hit = true
+}
+assert(hit, false)
+
+// the discriminant is evaluated once, cases are evaluated lazily
+let count = 0
+let evaluated = []
+switch (count++) {
+  case (evaluated.push(1), 1):
+    break
+  case (evaluated.push(0), 0):
+    break
+  case (evaluated.push(2), 2):
+    break
+}
+assert(count, 1)
+assert(evaluated, [1, 0])
+
+// continue inside a switch targets the enclosing loop
+let sum = 0
+for (let i = 0; i < 5; i++) {
+  switch (i % 2) {
+    case 0:
+      continue
+  }
+  sum += i
+}
+assert(sum, 4)
+
+// a simple state machine with lexical declarations in the case block
+let state = 'start', steps = []
+while (state !== 'end') {
+  switch (state) {
+    case 'start':
+      let next = 'middle'
+      steps.push(state)
+      state = next
+      break
+    case 'middle':
+      steps.push(state)
+      state = 'end'
+      break
+  }
+}
+assert(steps, ['start', 'middle'])
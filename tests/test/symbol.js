@@ -19,3 +19,41 @@ let xx = Symbol.for("xx")
 let xx2 = Symbol.for("xx")
 assert(xx === xx2)
 assert(Symbol.keyFor(xx) == "xx")
+
+// Symbol.toPrimitive
+let prim = {}
+prim[Symbol.toPrimitive] = () => 7
+assert(prim + 1 === 8)
+assert(prim * 2 === 14)
+assert(prim == 7)
+assert(prim < 8)
+assert(`${prim}` === "7")
+
+let hints = []
+let hinted = {
+  [Symbol.toPrimitive](hint) {
+    hints.push(hint)
+    return 1
+  }
+}
+hinted + 1;
++hinted;
+`${hinted}`;
+hinted == 1;
+assert(hints.join() === "default,number,string,default")
+
+let valueAndString = {
+  valueOf() { return 3 },
+  toString() { return "str" }
+}
+assert(valueAndString + 1 === 4)
+assert(valueAndString * 2 === 6)
+assert(`${valueAndString}` === "str")
+
+let notPrimitive = false
+try {
+  ({ [Symbol.toPrimitive]() { return {} } }) + 1
+} catch (e) {
+  notPrimitive = e instanceof TypeError
+}
+assert(notPrimitive)
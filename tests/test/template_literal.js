@@ -0,0 +1,75 @@
+let assert = require('assert').deepStrictEqual
+
+let name = 'world', n = 3
+assert(`hello`, 'hello')
+assert(``, '')
+assert(`hello ${name}!`, 'hello world!')
+assert(`${n} + ${n} = ${n + n}`, '3 + 3 = 6')
+assert(`${n}${n}`, '33')
+assert(`a${{ x: 1 }.x}b`, 'a1b')
+assert(`[${[1, 2, 3]}]`, '[1,2,3]')
+assert(`\t\x41\`\${}$`, '\tA`${}$')
+assert(`line1
+line2`, 'line1\nline2')
+assert(`con\
+tinued`, 'continued')
+assert(`\u{41}\u0042\0`, 'AB\0')
+assert(`\uD83D\uDE00`, '\uD83D\uDE00')
+
+// Nested templates
+let items = ['a', 'b']
+assert(`<${items.length > 0 ? `${items.length} items` : 'empty'}>`, '<2 items>')
+assert(`${`${`${n}`}`}`, '3')
+
+// Braces inside substitutions
+let f = x => { return { v: x } }
+assert(`${f(5).v}${(() => { return '}' })()}`, '5}')
+
+// Tagged templates
+function tag(strings, ...values) {
+  return { strings: strings, raw: strings.raw, values: values }
+}
+let r = tag`a${1}b\n${2}`
+assert(r.strings.length, 3)
+assert(r.strings[0], 'a')
+assert(r.strings[1], 'b\n')
+assert(r.strings[2], '')
+assert(r.raw[1], 'b\\n')
+assert(r.values, [1, 2])
+assert(tag`x`.strings[0], 'x')
+
+// Invalid escape sequences are cooked to undefined in tagged templates
+let invalid = tag`\unicode${0}a\1${1}\u{110000}b\xyz`
+assert(invalid.strings[0], undefined)
+assert(invalid.strings[1], undefined)
+assert(invalid.strings[2], undefined)
+assert(invalid.raw, ['\\unicode', 'a\\1', '\\u{110000}b\\xyz'])
+assert(String.raw`C:\xyz\u{41}`, 'C:\\xyz\\u{41}')
+
+// The strings array is cached per call site and frozen
+function site() { return tag`site${0}`.strings }
+assert(site() === site(), true)
+assert(tag`site${0}`.strings === site(), false)
+let s = site()
+s[0] = 'changed'
+assert(s[0], 'site')
+let keys = []
+for (let k in s) keys.push(k)
+assert(keys, ['0', '1'])
+assert(Object.isFrozen(s), true)
+assert(Object.isFrozen(s.raw), true)
+assert(Object.isExtensible(s), false)
+assert(Object.getOwnPropertyDescriptor(s, 'length').writable, false)
+s.length = 0
+s.raw.length = 0
+s.extra = 1
+assert(s.length, 2)
+assert(s.raw.length, 2)
+assert(s.extra, undefined)
+
+// Tag with a receiver
+let obj = {
+  prefix: '> ',
+  log: function(strings, value) { return this.prefix + strings[0] + value }
+}
+assert(obj.log`value: ${42}`, '> value: 42')
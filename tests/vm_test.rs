@@ -21,8 +21,33 @@ pub fn test_file(file_name: impl Into<String>, answer: impl Into<String>) {
 /// ### Panic
 /// Panic if the given code returned Err.
 pub fn assert_file(file_name: &str) {
-    println!("{}", format!("tests/test/{}.js", file_name));
-    execute_script(load_file(file_name));
+    let path = format!("tests/test/{}.js", file_name);
+    println!("{}", path);
+    execute_script_in_file(&path, load_file(file_name));
+}
+
+/// Load the file ("test/{file_name}.js"), and execute the script in a child process whose local
+/// time zone is ``tz``. The TZ environment variable is not set in this process, where tests run
+/// in parallel.
+/// ### Panic
+/// Panic if the script failed in the child process.
+pub fn assert_file_in_time_zone(file_name: &str, tz: &str) {
+    let status = std::process::Command::new(std::env::current_exe().unwrap())
+        .args(&["--exact", "time_zone_child", "--ignored", "--nocapture"])
+        .env("TZ", tz)
+        .env("RAPIDUS_TEST_FILE", file_name)
+        .status()
+        .unwrap();
+    assert!(status.success());
+}
+
+/// Load the module file ("test/module/{file_name}.mjs"), and execute the module.
+/// ### Panic
+/// Panic if the given code returned Err.
+pub fn assert_module(file_name: &str) {
+    let path = format!("tests/test/module/{}.mjs", file_name);
+    println!("{}", path);
+    vm::vm::VM::new().run_module(&path).unwrap();
 }
 
 fn load_file(file_name: impl Into<String>) -> String {
@@ -63,15 +88,39 @@ pub fn runtime_error(text: &str) -> String {
     };
 }
 
+/// Compile the given code, and normally terminates only when a compile error is returned.
+/// ### Panic
+/// Panic if the code returned a parse error, or was compiled without error.
+pub fn codegen_error(text: &str) -> String {
+    let mut vm = vm::vm::VM::new();
+
+    let mut parser = parser::Parser::new("test", text);
+    let node = parser.parse_all().unwrap();
+
+    match vm.compile(&node, true) {
+        Ok(_) => panic!(),
+        Err(err) => return format!("{:?}", err),
+    }
+}
+
 /// Execute the given code.
 /// ### Panic
 /// Panic if the given code returned Err.
 pub fn execute_script(text: String) -> String {
+    execute_script_in_file("test", text)
+}
+
+/// Execute the given code as the script of the file.
+/// ### Panic
+/// Panic if the given code returned Err.
+fn execute_script_in_file(file_name: &str, text: String) -> String {
     let mut vm = vm::vm::VM::new();
 
-    let mut parser = parser::Parser::new("test", text);
+    let mut parser = parser::Parser::new(file_name, text);
     let node = parser.parse_all().unwrap();
     let func_info = vm.compile(&node, true).unwrap();
+    vm.script_info
+        .push((func_info.module_func_id, parser.into_script_info()));
     vm.run_global(func_info).unwrap();
     let val: Value = vm
         .current_context
@@ -171,6 +220,46 @@ fn r#if() {
     assert_file("if")
 }
 
+#[test]
+fn switch() {
+    assert_file("switch")
+}
+
+#[test]
+fn do_while() {
+    assert_file("do_while")
+}
+
+#[test]
+fn for_in_of() {
+    assert_file("for_in_of")
+}
+
+#[test]
+fn label() {
+    test_file("label", "[0,0,0,1,0,2,1,0,2,0,3,0,3,1,4,1,4,2,0]")
+}
+
+#[test]
+fn labeled_jump() {
+    assert_file("labeled_jump")
+}
+
+#[test]
+fn class() {
+    assert_file("class")
+}
+
+#[test]
+fn template_literal() {
+    assert_file("template_literal")
+}
+
+#[test]
+fn destructuring() {
+    assert_file("destructuring")
+}
+
 #[test]
 fn arrow_function() {
     test_code("let f = (x) => { return x * x }; f(5)", "25");
@@ -232,6 +321,136 @@ fn string_methods() {
     assert_file("string_methods")
 }
 
+#[test]
+fn regexp() {
+    assert_file("regexp")
+}
+
+#[test]
+fn json() {
+    assert_file("json")
+}
+
+#[test]
+fn promise() {
+    assert_file("promise")
+}
+
+#[test]
+fn async_await() {
+    assert_file("async")
+}
+
+#[test]
+fn generator() {
+    assert_file("generator")
+}
+
+#[test]
+fn instanceof_in() {
+    assert_file("instanceof_in")
+}
+
+#[test]
+fn delete_void() {
+    assert_file("delete_void")
+}
+
+#[test]
+fn strict() {
+    assert_file("strict")
+}
+
+#[test]
+fn require_test() {
+    assert_file("require")
+}
+
+#[test]
+fn module_test() {
+    assert_module("main")
+}
+
+#[test]
+fn module_missing_export() {
+    let mut vm = vm::vm::VM::new();
+    assert!(vm
+        .run_module("tests/test/module/missing_export.mjs")
+        .is_err());
+}
+
+#[test]
+fn dynamic_import() {
+    assert_file("dynamic_import")
+}
+
+/// Run by assert_file_in_time_zone().
+#[test]
+#[ignore]
+fn time_zone_child() {
+    if let Ok(file_name) = std::env::var("RAPIDUS_TEST_FILE") {
+        assert_file(&file_name)
+    }
+}
+
+#[test]
+fn date_test() {
+    // The time zone of the expected values in the test is UTC+9.
+    assert_file_in_time_zone("date", "JST-9")
+}
+
+#[test]
+fn date_local_mean_time() {
+    assert_file_in_time_zone("date_local_mean_time", "America/New_York")
+}
+
+#[test]
+fn math_test() {
+    assert_file("math")
+}
+
+#[test]
+fn math_random_seed() {
+    let random_numbers = |seed: u64| {
+        let mut vm = vm::vm::VM::new().random_seed(seed);
+        let mut parser =
+            parser::Parser::new("test", "var numbers = [Math.random(), Math.random()]");
+        let node = parser.parse_all().unwrap();
+        let func_info = vm.compile(&node, true).unwrap();
+        vm.run_global(func_info).unwrap();
+        let env = vm.current_context.variable_environment;
+        let numbers = env.get_value("numbers").unwrap();
+        numbers.debug_string(true)
+    };
+    assert_eq!(random_numbers(1), random_numbers(1));
+    assert_ne!(random_numbers(1), random_numbers(2));
+}
+
+#[test]
+fn number_test() {
+    assert_file("number")
+}
+
+#[test]
+fn string_test() {
+    assert_file("string")
+}
+
+#[test]
+fn object_test() {
+    assert_file("object")
+}
+
+#[test]
+fn collection_test() {
+    assert_file("collection")
+}
+
+#[test]
+fn error_test() {
+    assert_file("error")
+}
+
 #[test]
 fn runtime_error1() {
     runtime_error("let a = {}; a.b.c");
@@ -257,6 +476,121 @@ fn runtime_error5() {
     runtime_error("let a = {}; a(5)");
 }
 
+#[test]
+fn runtime_error6() {
+    runtime_error("class A {}; A()");
+}
+
+#[test]
+fn runtime_error7() {
+    runtime_error("class A extends Object { constructor() { this.a = 1 } }; new A()");
+}
+
+#[test]
+fn runtime_error8() {
+    runtime_error("let { a } = null");
+}
+
+#[test]
+fn runtime_error9() {
+    runtime_error("let [a] = 1");
+}
+
+#[test]
+fn runtime_error10() {
+    runtime_error("new RegExp('(')");
+}
+
+#[test]
+fn runtime_error11() {
+    runtime_error("'a'.matchAll(/a/)");
+}
+
+#[test]
+fn runtime_error12() {
+    runtime_error("let a = {}; a.a = a; JSON.stringify(a)");
+}
+
+#[test]
+fn runtime_error13() {
+    runtime_error("Date.prototype.getTime.call({})");
+}
+
+#[test]
+fn runtime_error14() {
+    runtime_error("(1).toFixed(101)");
+}
+
+#[test]
+fn runtime_error15() {
+    runtime_error("'a'.repeat(-1)");
+}
+
+#[test]
+fn runtime_error16() {
+    runtime_error("'abc'.startsWith(/a/)");
+}
+
+#[test]
+fn runtime_error17() {
+    runtime_error("[].reduce(function(acc, x) { return acc + x })");
+}
+
+#[test]
+fn runtime_error18() {
+    runtime_error("let a = Object.freeze({ b: 1 }); Object.defineProperty(a, 'b', { value: 2 })");
+}
+
+#[test]
+fn runtime_error19() {
+    runtime_error("new WeakMap().set(1, 2)");
+}
+
+#[test]
+fn runtime_error20() {
+    runtime_error("Function.prototype.bind.call(1)");
+}
+
+#[test]
+fn runtime_error21() {
+    runtime_error("new Array(-1)");
+}
+
+#[test]
+fn codegen_error1() {
+    codegen_error("while (true) { break a }");
+}
+
+#[test]
+fn codegen_error2() {
+    codegen_error("a: { while (true) { continue a } }");
+}
+
+#[test]
+fn codegen_error3() {
+    codegen_error("a: while (true) { a: while (true) {} }");
+}
+
+#[test]
+fn codegen_error4() {
+    codegen_error("break;");
+}
+
+#[test]
+fn codegen_error5() {
+    codegen_error("function f() { super.x }");
+}
+
+#[test]
+fn codegen_error6() {
+    codegen_error("class A { constructor() { super() } }");
+}
+
+#[test]
+fn codegen_error7() {
+    codegen_error("class A extends Object { m() { let f = () => super(); } }");
+}
+
 use test::Bencher;
 #[bench]
 fn bench_fibo(b: &mut Bencher) {